@@ -0,0 +1,95 @@
+//! 嵌入用的公开接口
+//!
+//! [`Launcher`] 封装安装、启动和列表等核心操作，进度和日志通过 [`ProgressSink`] 交给调用方，
+//! 不依赖 Tauri 窗口，可在其他 Rust 程序或命令行工具中使用。桌面端的命令仍由控制器层提供。
+//!
+//! ```ignore
+//! struct Print;
+//! impl ProgressSink for Print {
+//!     fn send(&self, event: &str, payload: serde_json::Value) -> Result<(), LauncherError> {
+//!         println!("{}: {}", event, payload);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let launcher = Launcher::new(Print);
+//! launcher.install_version("1.20.4").await?;
+//! launcher.launch("1.20.4", None).await?;
+//! ```
+
+use crate::errors::LauncherError;
+use crate::models::modpack::ModpackInstallOptions;
+use crate::models::{GameConfig, InstanceInfo, LaunchOverrides, VersionManifest};
+use crate::services::event_sink::{EventSink, ProgressSink};
+use crate::services::loaders::LoaderType;
+use crate::services::modpack_installer::ModpackInstaller;
+use crate::services::{config, download, instance};
+
+/// 启动器核心操作
+#[derive(Clone)]
+pub struct Launcher {
+    sink: EventSink,
+}
+
+impl Launcher {
+    /// 使用指定的事件接收方
+    pub fn new(sink: impl ProgressSink) -> Self {
+        Self {
+            sink: EventSink::new(sink),
+        }
+    }
+
+    /// 不接收任何进度和日志事件
+    pub fn silent() -> Self {
+        Self {
+            sink: EventSink::silent(),
+        }
+    }
+
+    /// 当前配置（游戏目录、Java 路径等）
+    pub fn config(&self) -> Result<GameConfig, LauncherError> {
+        config::load_config()
+    }
+
+    /// 可安装的 Minecraft 版本列表
+    pub async fn list_versions(&self) -> Result<VersionManifest, LauncherError> {
+        download::get_versions().await
+    }
+
+    /// 已安装的实例
+    pub async fn list_instances(&self) -> Result<Vec<InstanceInfo>, LauncherError> {
+        instance::get_instances().await
+    }
+
+    /// 下载并安装原版版本（使用配置中的下载镜像）
+    pub async fn install_version(&self, version_id: &str) -> Result<(), LauncherError> {
+        let mirror = config::load_config()?.download_mirror;
+        download::process_and_download_version(version_id.to_string(), mirror, &self.sink).await
+    }
+
+    /// 基于已安装的版本创建实例，可同时安装模组加载器
+    pub async fn create_instance(
+        &self,
+        name: &str,
+        base_version: &str,
+        loader: Option<LoaderType>,
+    ) -> Result<(), LauncherError> {
+        instance::create_instance(name.to_string(), base_version.to_string(), loader, &self.sink).await
+    }
+
+    /// 安装 Modrinth 整合包
+    pub async fn install_modpack(&self, options: ModpackInstallOptions) -> Result<(), LauncherError> {
+        ModpackInstaller::new()
+            .install_modrinth_modpack(options, &self.sink)
+            .await
+    }
+
+    /// 启动实例；游戏进程启动后返回，退出时发送 `minecraft-exited` 事件
+    pub async fn launch(
+        &self,
+        instance_name: &str,
+        overrides: Option<LaunchOverrides>,
+    ) -> Result<(), LauncherError> {
+        instance::launch_instance(instance_name.to_string(), overrides, self.sink.clone()).await
+    }
+}
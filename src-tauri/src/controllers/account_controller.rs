@@ -0,0 +1,70 @@
+use crate::errors::LauncherError;
+use crate::services::accounts::{self, AccountInfo};
+use crate::services::auth::UserType;
+use crate::services::kiosk;
+use crate::services::skin_server::SkinModel;
+use crate::services::skins::{self, SkinInfo};
+
+/// 列出保存的账户
+#[tauri::command]
+pub async fn get_accounts() -> Result<Vec<AccountInfo>, LauncherError> {
+    accounts::get_accounts()
+}
+
+/// 添加账户（未指定类型时为离线账户，正版账户需提供 UUID 和访问令牌）
+#[tauri::command]
+pub async fn add_account(
+    username: String,
+    account_type: Option<UserType>,
+    uuid: Option<String>,
+    access_token: Option<String>,
+    xuid: Option<String>,
+) -> Result<AccountInfo, LauncherError> {
+    kiosk::require_admin()?;
+    accounts::add_account(username, account_type.unwrap_or_default(), uuid, access_token, xuid)
+}
+
+/// 删除账户
+#[tauri::command]
+pub async fn remove_account(account_id: String) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    accounts::remove_account(&account_id)
+}
+
+/// 设置默认账户
+#[tauri::command]
+pub async fn set_active_account(account_id: String) -> Result<AccountInfo, LauncherError> {
+    kiosk::require_admin()?;
+    accounts::set_active_account(&account_id)
+}
+
+/// 获取账户（未指定时为默认账户）的皮肤，材质缓存到本地供预览
+#[tauri::command]
+pub async fn get_skin(account_id: Option<String>) -> Result<SkinInfo, LauncherError> {
+    skins::get_skin(account_id).await
+}
+
+/// 上传皮肤（正版账户上传到 Mojang，离线账户保存到本地皮肤服务）
+#[tauri::command]
+pub async fn upload_skin(
+    account_id: Option<String>,
+    file_path: String,
+    model: Option<SkinModel>,
+) -> Result<SkinInfo, LauncherError> {
+    kiosk::require_admin()?;
+    skins::upload_skin(account_id, file_path, model.unwrap_or_default()).await
+}
+
+/// 切换皮肤模型（经典 / 纤细）
+#[tauri::command]
+pub async fn set_skin_variant(account_id: Option<String>, model: SkinModel) -> Result<SkinInfo, LauncherError> {
+    kiosk::require_admin()?;
+    skins::set_skin_variant(account_id, model).await
+}
+
+/// 选择正版账户显示的披风，不指定时隐藏披风
+#[tauri::command]
+pub async fn set_active_cape(account_id: Option<String>, cape_id: Option<String>) -> Result<SkinInfo, LauncherError> {
+    kiosk::require_admin()?;
+    skins::set_active_cape(account_id, cape_id).await
+}
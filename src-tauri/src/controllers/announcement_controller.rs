@@ -0,0 +1,8 @@
+use crate::errors::LauncherError;
+use crate::services::announcements::{self, Announcement};
+
+/// 获取启动器公告
+#[tauri::command]
+pub async fn get_announcements() -> Result<Vec<Announcement>, LauncherError> {
+    announcements::get_announcements().await
+}
@@ -1,4 +1,6 @@
 use crate::errors::LauncherError;
+use crate::services::skin_server::{self, OfflineSkin, SkinModel, SkinServerStatus};
+use crate::services::kiosk;
 
 // 控制器层作为 #[tauri::command] 入口，调用config服务中的认证相关方法
 
@@ -9,6 +11,10 @@ pub async fn get_saved_username() -> Result<Option<String>, LauncherError> {
 
 #[tauri::command]
 pub async fn set_saved_username(username: String) -> Result<(), LauncherError> {
+    // 锁定模式下启动前保存未改变的用户名仍然允许
+    if crate::services::config::get_saved_username().await?.as_deref() != Some(username.as_str()) {
+        kiosk::require_admin()?;
+    }
     crate::services::config::set_saved_username(username).await
 }
 
@@ -19,5 +25,59 @@ pub async fn get_saved_uuid() -> Result<Option<String>, LauncherError> {
 
 #[tauri::command]
 pub async fn set_saved_uuid(uuid: String) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
     crate::services::config::set_saved_uuid(uuid).await
 }
+
+/// 校验离线用户名，返回错误信息（有效时返回 None）
+#[tauri::command]
+pub fn validate_username(username: String) -> Option<String> {
+    crate::services::auth::validate_username(&username)
+        .err()
+        .map(|e| e.to_string())
+}
+
+/// 将用户名的离线 UUID 重置为标准算法生成的值
+#[tauri::command]
+pub async fn regenerate_offline_uuid(username: String) -> Result<String, LauncherError> {
+    kiosk::require_admin()?;
+    crate::services::auth::regenerate_offline_uuid(&username)
+}
+
+/// 获取离线皮肤服务状态
+#[tauri::command]
+pub async fn get_skin_server_status() -> Result<SkinServerStatus, LauncherError> {
+    skin_server::get_status()
+}
+
+/// 启用或禁用离线皮肤服务
+#[tauri::command]
+pub async fn set_skin_server_enabled(enabled: bool) -> Result<SkinServerStatus, LauncherError> {
+    kiosk::require_admin()?;
+    skin_server::set_skin_server_enabled(enabled).await
+}
+
+/// 设置本机皮肤服务是否对局域网开放
+#[tauri::command]
+pub async fn set_skin_server_lan(enabled: bool) -> Result<SkinServerStatus, LauncherError> {
+    kiosk::require_admin()?;
+    skin_server::set_skin_server_lan(enabled).await
+}
+
+/// 为离线用户名设置皮肤
+#[tauri::command]
+pub async fn set_offline_skin(
+    username: String,
+    file_path: String,
+    model: Option<SkinModel>,
+) -> Result<OfflineSkin, LauncherError> {
+    kiosk::require_admin()?;
+    skin_server::set_offline_skin(&username, &file_path, model.unwrap_or_default())
+}
+
+/// 移除离线用户名的皮肤
+#[tauri::command]
+pub async fn remove_offline_skin(username: String) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    skin_server::remove_offline_skin(&username)
+}
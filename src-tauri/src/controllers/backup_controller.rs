@@ -0,0 +1,37 @@
+use crate::errors::LauncherError;
+use crate::models::BackupTarget;
+use crate::services::backup::{self, BackupInfo};
+use crate::services::kiosk;
+
+/// 备份实例存档到配置的备份位置
+#[tauri::command]
+pub async fn backup_world(
+    instance_name: String,
+    world_name: String,
+    password: Option<String>,
+) -> Result<BackupInfo, LauncherError> {
+    backup::backup_world(instance_name, world_name, password).await
+}
+
+/// 列出备份位置中的备份
+#[tauri::command]
+pub async fn list_backups() -> Result<Vec<BackupInfo>, LauncherError> {
+    backup::list_backups().await
+}
+
+/// 从备份位置恢复存档（本地或远程）
+#[tauri::command]
+pub async fn restore_world_backup(
+    instance_name: String,
+    backup_name: String,
+    password: Option<String>,
+) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    backup::restore_world_backup(instance_name, backup_name, password).await
+}
+
+/// 测试备份位置是否可读写
+#[tauri::command]
+pub async fn test_backup_target(target: BackupTarget) -> Result<(), LauncherError> {
+    backup::test_backup_target(target).await
+}
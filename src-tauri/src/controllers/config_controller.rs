@@ -1,6 +1,10 @@
+use crate::controllers::window_sink;
 use crate::errors::LauncherError;
 use crate::models::*;
-use crate::services::config;
+use crate::services::{config, download};
+use crate::services::config_history::{self, ConfigChangeEntry};
+use crate::services::kiosk;
+use std::collections::BTreeMap;
 
 #[tauri::command(rename = "get_config")]
 pub async fn get_config() -> Result<GameConfig, LauncherError> {
@@ -14,6 +18,7 @@ pub async fn load_config_key(key: String) -> Result<Option<String>, LauncherErro
 
 #[tauri::command]
 pub async fn save_config_key(key: String, value: String) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
     config::save_config_key(key, value).await
 }
 
@@ -28,6 +33,20 @@ pub async fn select_game_dir(_window: tauri::Window) -> Result<String, LauncherE
     config::get_game_dir()
 }
 
+/// 导入网络管理员提供的 CA 证书（PEM 或 DER），返回保存后的路径
+#[tauri::command]
+pub async fn import_ca_certificate(path: String) -> Result<String, LauncherError> {
+    kiosk::require_admin()?;
+    config::import_ca_certificate(path).await
+}
+
+/// 移除已导入的 CA 证书
+#[tauri::command]
+pub async fn clear_ca_certificate() -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    config::clear_ca_certificate().await
+}
+
 #[tauri::command]
 pub async fn get_game_dir_info() -> Result<GameDirInfo, LauncherError> {
     config::get_game_dir_info().await
@@ -35,11 +54,36 @@ pub async fn get_game_dir_info() -> Result<GameDirInfo, LauncherError> {
 
 #[tauri::command]
 pub async fn set_game_dir(path: String, window: tauri::Window) -> Result<(), LauncherError> {
-    config::set_game_dir(path, &window).await
+    kiosk::require_admin()?;
+    config::set_game_dir(path, &window_sink::sink(&window)).await
+}
+
+#[tauri::command]
+pub async fn list_game_dirs() -> Result<Vec<GameDirInfo>, LauncherError> {
+    config::list_game_dirs().await
+}
+
+#[tauri::command]
+pub async fn add_game_dir(path: String, window: tauri::Window) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    config::add_game_dir(path, &window_sink::sink(&window)).await
+}
+
+#[tauri::command]
+pub async fn remove_game_dir(path: String, window: tauri::Window) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    config::remove_game_dir(path, &window_sink::sink(&window)).await
+}
+
+#[tauri::command]
+pub async fn set_default_game_dir(path: String, window: tauri::Window) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    config::set_default_game_dir(path, &window_sink::sink(&window)).await
 }
 
 #[tauri::command]
 pub async fn set_version_isolation(enabled: bool) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
     config::set_version_isolation(enabled).await
 }
 
@@ -50,12 +94,32 @@ pub fn get_download_threads() -> Result<u8, LauncherError> {
 
 #[tauri::command]
 pub async fn set_download_threads(threads: u8) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
     config::set_download_threads(threads).await
 }
 
+/// 校验版本文件，未指定级别时只检查文件存在和大小
+#[tauri::command]
+pub async fn validate_version_files(
+    version_id: String,
+    level: Option<VerificationLevel>,
+) -> Result<Vec<String>, LauncherError> {
+    crate::services::file_verification::validate_version_files(version_id, level.unwrap_or_default()).await
+}
+
+/// 估算各校验级别的耗时
 #[tauri::command]
-pub async fn validate_version_files(version_id: String) -> Result<Vec<String>, LauncherError> {
-    crate::services::file_verification::validate_version_files(version_id).await
+pub async fn estimate_version_verification(
+    version_id: String,
+) -> Result<Vec<crate::services::file_verification::VerificationEstimate>, LauncherError> {
+    crate::services::file_verification::estimate_verification(version_id).await
+}
+
+/// 设置 Modrinth 文件镜像（为空时使用官方 CDN）
+#[tauri::command]
+pub async fn set_modrinth_mirror(mirror: Option<String>) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    config::set_modrinth_mirror(mirror).await
 }
 
 #[tauri::command]
@@ -68,6 +132,18 @@ pub async fn get_memory_stats() -> Result<crate::services::memory::MemoryStats,
     config::get_memory_stats().await
 }
 
+/// 开始实时内存采样（定期发送 `memory-stats` 事件），返回实际采样间隔（毫秒）
+#[tauri::command]
+pub fn start_memory_sampling(interval_ms: Option<u64>, window: tauri::Window) -> u64 {
+    crate::services::memory::start_memory_sampling(interval_ms, window_sink::sink(&window))
+}
+
+/// 停止实时内存采样
+#[tauri::command]
+pub fn stop_memory_sampling() {
+    crate::services::memory::stop_memory_sampling()
+}
+
 #[tauri::command]
 pub async fn recommend_memory(
     version: String,
@@ -94,11 +170,13 @@ pub async fn get_auto_memory_config(
 
 #[tauri::command]
 pub async fn set_auto_memory_enabled(enabled: bool) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
     config::set_auto_memory_enabled(enabled).await
 }
 
 #[tauri::command]
 pub async fn auto_set_memory() -> Result<Option<u32>, LauncherError> {
+    kiosk::require_admin()?;
     config::auto_set_memory().await
 }
 
@@ -128,6 +206,7 @@ pub async fn get_window_settings() -> Result<WindowSettings, LauncherError> {
 
 #[tauri::command]
 pub async fn set_window_settings(width: Option<u32>, height: Option<u32>, fullscreen: bool) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
     let mut config = config::load_config()?;
     config.window_width = width;
     config.window_height = height;
@@ -136,6 +215,45 @@ pub async fn set_window_settings(width: Option<u32>, height: Option<u32>, fullsc
     Ok(())
 }
 
+/// 设置全局生命周期钩子（实例设置中的钩子优先）
+#[tauri::command]
+pub async fn set_global_hooks(hooks: LifecycleHooks) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    config::set_global_hooks(hooks).await
+}
+
+/// 覆盖默认 JVM 参数规则，`enabled` 为空时恢复自动判断
+#[tauri::command]
+pub async fn set_jvm_flag_override(id: String, enabled: Option<bool>) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    config::set_jvm_flag_override(id, enabled).await
+}
+
+#[tauri::command]
+pub fn get_extra_jvm_args() -> Result<Vec<String>, LauncherError> {
+    Ok(config::load_config()?.extra_jvm_args)
+}
+
+/// 设置全局自定义 JVM 参数（实例设置中的参数追加在其后）
+#[tauri::command]
+pub async fn set_extra_jvm_args(args: Vec<String>) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    config::set_extra_jvm_args(args).await
+}
+
+/// 各下载主机生效的并发限制（内置默认值叠加配置覆盖）
+#[tauri::command]
+pub fn get_host_limits() -> Result<BTreeMap<String, HostLimit>, LauncherError> {
+    Ok(download::effective_host_limits(&config::load_config()?))
+}
+
+/// 覆盖下载主机的并发限制，`limit` 为空时恢复默认值
+#[tauri::command]
+pub async fn set_host_limit(host: String, limit: Option<HostLimit>) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    config::set_host_limit(host, limit).await
+}
+
 #[tauri::command]
 pub fn get_last_selected_version() -> Option<String> {
     config::get_last_selected_version()
@@ -145,3 +263,16 @@ pub fn get_last_selected_version() -> Option<String> {
 pub async fn set_last_selected_version(version: String) -> Result<(), LauncherError> {
     config::set_last_selected_version(&version)
 }
+
+/// 获取配置变更历史
+#[tauri::command]
+pub async fn get_config_history() -> Result<Vec<ConfigChangeEntry>, LauncherError> {
+    Ok(config_history::get_config_history())
+}
+
+/// 撤销指定的配置变更，返回撤销后的配置
+#[tauri::command]
+pub async fn revert_config_change(id: u64) -> Result<GameConfig, LauncherError> {
+    kiosk::require_admin()?;
+    config_history::revert_config_change(id)
+}
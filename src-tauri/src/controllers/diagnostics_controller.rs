@@ -0,0 +1,21 @@
+use crate::errors::LauncherError;
+use crate::services::diagnostics::{self, NetworkProbe};
+use crate::services::metrics::{self, OperationMetrics};
+
+/// 获取性能指标
+#[tauri::command]
+pub async fn get_performance_metrics() -> Result<Vec<OperationMetrics>, LauncherError> {
+    Ok(metrics::snapshot())
+}
+
+/// 导出诊断包，返回 zip 路径
+#[tauri::command]
+pub async fn export_diagnostics_bundle() -> Result<String, LauncherError> {
+    tokio::task::spawn_blocking(diagnostics::export_diagnostics_bundle).await?
+}
+
+/// 网络诊断：区分离线、DNS 失败和 TLS 拦截
+#[tauri::command]
+pub async fn probe_network() -> Result<NetworkProbe, LauncherError> {
+    diagnostics::probe_network().await
+}
@@ -1,8 +1,13 @@
+use crate::controllers::window_sink;
 use crate::errors::LauncherError;
 use crate::models::VersionManifest;
-use crate::services::download;
-use crate::services::download::batch::set_cancel_flag;
-use tauri::{Emitter, Window};
+use crate::services::asset_gc::{self, AssetGcReport};
+use crate::services::download::{self, DownloadEstimate};
+use crate::services::kiosk;
+use crate::services::tasks::{self, TaskKind};
+use crate::services::telemetry;
+use crate::services::version_watcher::{self, NewVersionInfo};
+use tauri::Window;
 
 /// 获取 Minecraft 版本列表
 #[tauri::command]
@@ -10,6 +15,12 @@ pub async fn get_versions() -> Result<VersionManifest, LauncherError> {
     download::get_versions().await
 }
 
+/// 立即检查是否有新版本发布（返回自上次检查以来新增的版本）
+#[tauri::command]
+pub async fn check_new_versions() -> Result<Vec<NewVersionInfo>, LauncherError> {
+    version_watcher::check_new_versions().await
+}
+
 /// 下载 Minecraft 版本
 #[tauri::command]
 pub async fn download_version(
@@ -17,17 +28,30 @@ pub async fn download_version(
     mirror: Option<String>,
     window: Window,
 ) -> Result<(), LauncherError> {
-    download::process_and_download_version(version_id, mirror, &window).await
+    telemetry::timed_install("version", download::process_and_download_version(version_id, mirror, &window_sink::sink(&window))).await
 }
 
-/// 取消下载
+/// 预估下载指定版本需要的数据量（不实际下载）
 #[tauri::command]
-pub async fn cancel_download(window: Window) -> Result<(), LauncherError> {
-    // 设置全局取消标志
-    set_cancel_flag();
-    // 同时发送事件以触发监听器
-    window.emit("cancel-download", ()).map_err(|e| {
-        LauncherError::Custom(format!("发送取消事件失败: {}", e))
-    })?;
+pub async fn estimate_version_download(
+    version_id: String,
+    mirror: Option<String>,
+) -> Result<DownloadEstimate, LauncherError> {
+    download::estimate_version_download(version_id, mirror).await
+}
+
+/// 取消所有正在进行的下载
+#[tauri::command]
+pub async fn cancel_download() -> Result<(), LauncherError> {
+    tasks::cancel_kind(TaskKind::Download);
     Ok(())
-}
\ No newline at end of file
+}
+/// 回收所有版本都不再引用的资源文件，`dry_run` 为 true 时只统计可释放的空间
+#[tauri::command]
+pub async fn collect_asset_garbage(dry_run: Option<bool>) -> Result<AssetGcReport, LauncherError> {
+    let dry_run = dry_run.unwrap_or(false);
+    if !dry_run {
+        kiosk::require_admin()?;
+    }
+    tokio::task::spawn_blocking(move || asset_gc::collect_asset_garbage(dry_run)).await?
+}
@@ -0,0 +1,56 @@
+use crate::errors::LauncherError;
+use crate::services::fs_access::{self, DirEntryInfo, FsRoot};
+use crate::services::kiosk;
+use crate::services::resourcepack_dedupe::{self, ConsolidateResult, ResourcePackScan};
+
+/// 读取实例目录内的文本文件
+#[tauri::command]
+pub async fn read_instance_file(
+    instance_name: String,
+    relative_path: String,
+) -> Result<String, LauncherError> {
+    fs_access::read_instance_file(&instance_name, &relative_path)
+}
+
+/// 列出游戏目录或启动器目录内的目录内容，指定实例时列出该实例的运行目录
+#[tauri::command]
+pub async fn list_game_directory(
+    relative_path: Option<String>,
+    root: Option<FsRoot>,
+    instance_name: Option<String>,
+) -> Result<Vec<DirEntryInfo>, LauncherError> {
+    fs_access::list_dir(
+        root.unwrap_or_default(),
+        instance_name.as_deref(),
+        relative_path.as_deref().unwrap_or(""),
+    )
+}
+
+/// 在文件管理器中打开游戏目录或启动器目录内的路径（文件则打开所在目录），指定实例时相对于该实例的运行目录
+#[tauri::command]
+pub async fn open_game_path(
+    relative_path: Option<String>,
+    root: Option<FsRoot>,
+    instance_name: Option<String>,
+) -> Result<(), LauncherError> {
+    fs_access::open_path(
+        root.unwrap_or_default(),
+        instance_name.as_deref(),
+        relative_path.as_deref().unwrap_or(""),
+    )
+}
+
+/// 扫描各实例和公共目录中重复的资源包
+#[tauri::command]
+pub async fn scan_duplicate_resourcepacks() -> Result<ResourcePackScan, LauncherError> {
+    resourcepack_dedupe::scan_duplicate_resourcepacks().await
+}
+
+/// 把重复的资源包合并到公共目录并替换为链接，`sha1s` 为空时处理全部重复组
+#[tauri::command]
+pub async fn consolidate_resourcepacks(
+    sha1s: Option<Vec<String>>,
+) -> Result<ConsolidateResult, LauncherError> {
+    kiosk::require_admin()?;
+    resourcepack_dedupe::consolidate_resourcepacks(sha1s).await
+}
@@ -0,0 +1,19 @@
+use crate::errors::LauncherError;
+use crate::services::health::{self, StartupReport};
+use crate::services::instance_health::{self, InstanceHealth};
+
+#[tauri::command]
+pub async fn get_startup_report() -> Result<StartupReport, LauncherError> {
+    health::get_startup_report()
+}
+
+#[tauri::command]
+pub async fn run_health_check() -> Result<StartupReport, LauncherError> {
+    Ok(tokio::task::spawn_blocking(health::run_startup_checks).await?)
+}
+
+/// 实例健康检查（综合文件、加载器、模组前置和 Java 兼容性），`refresh` 为 true 时忽略缓存
+#[tauri::command]
+pub async fn get_instance_health(instance: String, refresh: Option<bool>) -> Result<InstanceHealth, LauncherError> {
+    instance_health::get_instance_health(&instance, refresh.unwrap_or(false)).await
+}
@@ -1,8 +1,22 @@
+use crate::controllers::window_sink;
 use crate::errors::LauncherError;
-use crate::models::InstanceInfo;
-use crate::services::instance;
+use crate::models::{InstanceInfo, InstanceSettings, LaunchOverrides};
+use crate::services::base_version::{self, BaseVersionCheck};
+use crate::services::instance::{self, RegeneratedVersionJson, ServerInstanceReport};
+use crate::services::instance_archive::{self, ArchivedInstance};
+use crate::services::instance_share::{self, ShareInfo};
+use crate::services::mod_import::{self, ModImportReport};
+use crate::services::mod_install::{self, ModInstallResult};
+use crate::services::mod_list::{self, ExportedModList, ModListFormat};
+use crate::services::paths::ContentKind;
+use crate::services::server_ping::{self, ServerStatus};
+use crate::services::shortcut::{self, ShortcutLocation};
+use crate::services::tasks::{self, TaskKind};
+use crate::services::telemetry;
+use tauri::Emitter;
 use crate::services::loaders::LoaderType;
 use crate::utils::file_utils::{validate_instance_name, InstanceNameValidation};
+use crate::services::kiosk;
 
 /// 验证实例名称是否有效
 #[tauri::command]
@@ -23,7 +37,56 @@ pub async fn create_instance(
     loader: Option<LoaderType>,
     window: tauri::Window
 ) -> Result<(), LauncherError> {
-    instance::create_instance(new_instance_name, base_version_id, loader, &window).await
+    let task = tasks::begin(TaskKind::InstanceCreate, &new_instance_name);
+    let _ = window.emit("task-started", task.info());
+    let sink = window_sink::sink(&window);
+    let install = instance::create_instance(new_instance_name, base_version_id, loader, &sink);
+    telemetry::timed_install("instance", task.run(install)).await
+}
+
+/// 读取存档的 level.dat 并创建匹配版本的实例，返回实例名称
+#[tauri::command]
+pub async fn create_instance_for_world(
+    world_path: String,
+    instance_name: Option<String>,
+    window: tauri::Window,
+) -> Result<String, LauncherError> {
+    let task = tasks::begin(TaskKind::InstanceCreate, &world_path);
+    let _ = window.emit("task-started", task.info());
+    task.run(instance::create_instance_for_world(world_path, instance_name, &window_sink::sink(&window)))
+        .await
+}
+
+/// 查询服务器状态（版本、玩家数、模组列表）
+#[tauri::command]
+pub async fn ping_server(address: String) -> Result<ServerStatus, LauncherError> {
+    server_ping::ping_server(&address).await
+}
+
+/// 创建与服务器版本和加载器匹配的实例，并安装服务器报告的模组
+#[tauri::command]
+pub async fn create_instance_for_server(
+    address: String,
+    instance_name: Option<String>,
+    window: tauri::Window,
+) -> Result<ServerInstanceReport, LauncherError> {
+    let task = tasks::begin(TaskKind::InstanceCreate, &address);
+    let _ = window.emit("task-started", task.info());
+    task.run(instance::create_instance_for_server(address, instance_name, &window_sink::sink(&window)))
+        .await
+}
+
+/// 从版本 JSON 地址安装自定义版本
+#[tauri::command]
+pub async fn install_version_from_url(
+    json_url: String,
+    name: String,
+    window: tauri::Window,
+) -> Result<(), LauncherError> {
+    let task = tasks::begin(TaskKind::InstanceCreate, &name);
+    let _ = window.emit("task-started", task.info());
+    task.run(instance::install_version_from_url(json_url, name, &window_sink::sink(&window)))
+        .await
 }
 
 #[tauri::command]
@@ -31,14 +94,42 @@ pub async fn get_instances() -> Result<Vec<InstanceInfo>, LauncherError> {
     instance::get_instances().await
 }
 
+/// 删除实例；游戏运行中时返回 InstanceRunning 错误，`force` 为 true 时结束游戏后删除
 #[tauri::command]
-pub async fn delete_instance(instance_name: String) -> Result<(), LauncherError> {
-    instance::delete_instance(instance_name).await
+pub async fn delete_instance(instance_name: String, force: Option<bool>) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    instance::delete_instance(instance_name, force.unwrap_or(false)).await
 }
 
+/// 重命名实例；游戏运行中时返回 InstanceRunning 错误，`force` 为 true 时结束游戏后重命名
 #[tauri::command]
-pub async fn rename_instance(old_name: String, new_name: String) -> Result<(), LauncherError> {
-    instance::rename_instance(old_name, new_name).await
+pub async fn rename_instance(
+    old_name: String,
+    new_name: String,
+    force: Option<bool>,
+) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    instance::rename_instance(old_name, new_name, force.unwrap_or(false)).await
+}
+
+/// 归档实例：压缩后删除版本目录，列表中仍显示为已归档；`force` 为 true 时结束游戏后归档
+#[tauri::command]
+pub async fn archive_instance(instance_name: String, force: Option<bool>) -> Result<ArchivedInstance, LauncherError> {
+    kiosk::require_admin()?;
+    instance_archive::archive_instance(instance_name, force.unwrap_or(false)).await
+}
+
+/// 从归档恢复实例
+#[tauri::command]
+pub async fn unarchive_instance(instance_name: String) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    instance_archive::unarchive_instance(instance_name).await
+}
+
+/// 所有游戏目录中的已归档实例
+#[tauri::command]
+pub async fn list_archived_instances() -> Result<Vec<ArchivedInstance>, LauncherError> {
+    instance_archive::get_archived_instances()
 }
 
 #[tauri::command]
@@ -46,7 +137,148 @@ pub async fn open_instance_folder(instance_name: String) -> Result<(), LauncherE
     instance::open_instance_folder(instance_name).await
 }
 
+/// 打开实例的 mods/config/saves/logs/crash-reports/screenshots 等子目录
+#[tauri::command]
+pub async fn open_instance_content_folder(instance_name: String, kind: ContentKind) -> Result<(), LauncherError> {
+    instance::open_instance_content_folder(instance_name, kind).await
+}
+
+/// 在实例的游戏运行目录中打开终端
+#[tauri::command]
+pub async fn open_instance_terminal(instance_name: String) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    instance::open_instance_terminal(instance_name).await
+}
+
+/// 启动实例，可选的覆盖项只对本次启动生效（附加 JVM 或游戏参数时需要管理员解锁）
+#[tauri::command]
+pub async fn launch_instance(
+    instance_name: String,
+    overrides: Option<LaunchOverrides>,
+    window: tauri::Window,
+) -> Result<(), LauncherError> {
+    if overrides
+        .as_ref()
+        .is_some_and(|o| !o.extra_jvm_args.is_empty() || !o.extra_game_args.is_empty())
+    {
+        kiosk::require_admin()?;
+    }
+    instance::launch_instance(instance_name, overrides, window_sink::sink(&window)).await
+}
+
+/// 检查实例继承的基础版本能否从官方清单安装
+#[tauri::command]
+pub async fn check_base_version(instance_name: String) -> Result<BaseVersionCheck, LauncherError> {
+    base_version::check_base_version(&instance_name).await
+}
+
+/// 将实例改为继承指定的官方版本
+#[tauri::command]
+pub async fn rebase_instance(instance_name: String, base_version: String) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    base_version::rebase_instance(&instance_name, &base_version).await
+}
+
+/// 在局域网中分享实例，返回分享码和下载地址（用于二维码）
+#[tauri::command]
+pub async fn start_instance_share(instance_name: String) -> Result<ShareInfo, LauncherError> {
+    kiosk::require_admin()?;
+    instance_share::start_sharing(instance_name).await
+}
+
+/// 停止局域网分享
 #[tauri::command]
-pub async fn launch_instance(instance_name: String, window: tauri::Window) -> Result<(), LauncherError> {
-    instance::launch_instance(instance_name, window).await
-}
\ No newline at end of file
+pub fn stop_instance_share() {
+    instance_share::stop_sharing()
+}
+
+/// 通过分享码接收实例，返回导入后的实例名
+#[tauri::command]
+pub async fn receive_instance(
+    code: String,
+    instance_name: Option<String>,
+    window: tauri::Window,
+) -> Result<String, LauncherError> {
+    kiosk::require_admin()?;
+    instance_share::receive_instance(code, instance_name, &window_sink::sink(&window)).await
+}
+
+/// 为实例创建桌面/开始菜单快捷方式，返回快捷方式路径
+#[tauri::command]
+pub async fn create_instance_shortcut(
+    instance_name: String,
+    location: Option<ShortcutLocation>,
+) -> Result<String, LauncherError> {
+    shortcut::create_instance_shortcut(&instance_name, location.unwrap_or_default())
+}
+
+/// 获取实例独立设置
+#[tauri::command]
+pub fn get_instance_settings(instance_name: String) -> Result<InstanceSettings, LauncherError> {
+    instance::get_instance_settings(&instance_name)
+}
+
+/// 保存实例独立设置
+#[tauri::command]
+pub fn set_instance_settings(
+    instance_name: String,
+    settings: InstanceSettings,
+) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    instance::set_instance_settings(&instance_name, &settings)
+}
+
+/// 将启动时自愈补齐的库写入实例版本 JSON，返回新增数量
+#[tauri::command]
+pub fn rebuild_instance_classpath(instance_name: String) -> Result<usize, LauncherError> {
+    kiosk::require_admin()?;
+    instance::rebuild_instance_classpath(&instance_name)
+}
+
+/// 根据记录的游戏版本和加载器重新生成损坏的实例版本 JSON，保留用户添加的参数
+#[tauri::command]
+pub async fn regenerate_version_json(
+    instance_name: String,
+    window: tauri::Window,
+) -> Result<RegeneratedVersionJson, LauncherError> {
+    kiosk::require_admin()?;
+    let task = tasks::begin(TaskKind::InstanceCreate, &instance_name);
+    let _ = window.emit("task-started", task.info());
+    task.run(instance::regenerate_version_json(instance_name, &window_sink::sink(&window))).await
+}
+
+/// 获取游戏实际读取的实例内容目录（mods/resourcepacks/saves 等，遵循版本隔离设置）
+#[tauri::command]
+pub fn get_instance_content_dir(instance_name: String, kind: ContentKind) -> Result<String, LauncherError> {
+    Ok(instance::instance_content_dir(&instance_name, kind)?
+        .to_string_lossy()
+        .to_string())
+}
+
+/// 从已有 .minecraft（或其 mods 目录）导入模组，可选按哈希匹配 Modrinth 项目
+#[tauri::command]
+pub async fn import_mods_from_folder(
+    instance_name: String,
+    path: String,
+    match_modrinth: Option<bool>,
+) -> Result<ModImportReport, LauncherError> {
+    kiosk::require_admin()?;
+    mod_import::import_mods_from_folder(instance_name, path, match_modrinth.unwrap_or(true)).await
+}
+
+/// 从 Modrinth 安装模组（未指定版本时选择兼容的最新版本），结果中包含推荐安装的可选依赖
+#[tauri::command]
+pub async fn install_modrinth_mod(
+    instance_name: String,
+    project_id: String,
+    version_id: Option<String>,
+) -> Result<ModInstallResult, LauncherError> {
+    kiosk::require_admin()?;
+    mod_install::install_modrinth_mod(instance_name, project_id, version_id).await
+}
+
+/// 导出实例的模组列表（Markdown 表格或 CSV，含可识别的 Modrinth 链接）
+#[tauri::command]
+pub async fn export_mod_list(instance_name: String, format: ModListFormat) -> Result<ExportedModList, LauncherError> {
+    mod_list::export_mod_list(&instance_name, format).await
+}
@@ -1,4 +1,6 @@
 use crate::errors::LauncherError;
+use crate::services::java_runtime::{self, ManagedRuntime, RuntimeVerifyReport};
+use crate::services::kiosk;
 
 #[tauri::command]
 pub async fn find_java_installations_command() -> Result<Vec<String>, LauncherError> {
@@ -13,6 +15,7 @@ pub async fn refresh_java_installations() -> Result<Vec<String>, LauncherError>
 
 #[tauri::command]
 pub async fn set_java_path_command(path: String) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
     crate::services::java::set_java_path_command(path).await
 }
 
@@ -25,3 +28,29 @@ pub async fn validate_java_path(path: String) -> Result<bool, LauncherError> {
 pub async fn get_java_version(path: String) -> Result<String, LauncherError> {
     crate::services::java::get_java_version(path).await
 }
+
+/// 安装 Mojang 托管 Java 运行时（如 java-runtime-gamma）
+#[tauri::command]
+pub async fn install_runtime(component: String) -> Result<ManagedRuntime, LauncherError> {
+    kiosk::require_admin()?;
+    java_runtime::install_runtime(component).await
+}
+
+/// 列出已安装的托管 Java 运行时
+#[tauri::command]
+pub async fn list_runtimes() -> Result<Vec<ManagedRuntime>, LauncherError> {
+    java_runtime::list_runtimes()
+}
+
+/// 按安装时保存的清单校验托管运行时
+#[tauri::command]
+pub async fn verify_runtime(id: String) -> Result<RuntimeVerifyReport, LauncherError> {
+    java_runtime::verify_runtime(id).await
+}
+
+/// 修复托管运行时（只重新下载缺失或损坏的文件）
+#[tauri::command]
+pub async fn repair_runtime(id: String) -> Result<RuntimeVerifyReport, LauncherError> {
+    kiosk::require_admin()?;
+    java_runtime::repair_runtime(id).await
+}
@@ -0,0 +1,26 @@
+use crate::errors::LauncherError;
+use crate::services::kiosk::{self, KioskStatus};
+
+/// 获取管理员锁定状态
+#[tauri::command]
+pub fn get_kiosk_status() -> KioskStatus {
+    kiosk::kiosk_status()
+}
+
+/// 用管理员 PIN 解锁本次运行中的管理操作
+#[tauri::command]
+pub fn unlock_admin(pin: String) -> Result<KioskStatus, LauncherError> {
+    kiosk::unlock_admin(&pin)
+}
+
+/// 立即恢复锁定
+#[tauri::command]
+pub fn lock_admin() -> KioskStatus {
+    kiosk::lock_admin()
+}
+
+/// 设置管理员 PIN（为空时关闭锁定模式）
+#[tauri::command]
+pub fn set_admin_pin(pin: Option<String>) -> Result<KioskStatus, LauncherError> {
+    kiosk::set_admin_pin(pin.as_deref())
+}
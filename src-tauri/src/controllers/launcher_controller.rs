@@ -1,10 +1,57 @@
 use crate::errors::LauncherError;
-use crate::models::LaunchOptions;
+use crate::services::kiosk;
+use crate::services::launcher::{
+    DefaultJvmFlagInfo, ExportedLaunchScript, NativesDirInfo, NativesPruneResult, ScriptFormat, SessionInfo,
+    TroubleshootingToggleInfo, ValidationIssue,
+};
 
+/// 校验版本 JSON，返回发现的问题列表
 #[tauri::command]
-pub async fn launch_minecraft(
-    options: LaunchOptions,
-    window: tauri::Window,
-) -> Result<(), LauncherError> {
-    crate::services::launcher::launch_minecraft(options, window).await
+pub async fn validate_version_json(version: String) -> Result<Vec<ValidationIssue>, LauncherError> {
+    crate::services::launcher::validate_version_json(&version)
+}
+
+/// 导出实例的启动脚本（bat/sh）或单行启动命令
+#[tauri::command]
+pub async fn export_launch_script(
+    instance_name: String,
+    format: ScriptFormat,
+) -> Result<ExportedLaunchScript, LauncherError> {
+    crate::services::launcher::export_launch_script(&instance_name, format).await
+}
+
+/// 实例可用的故障排查启动选项（安全模式）
+#[tauri::command]
+pub async fn get_troubleshooting_options(
+    instance_name: String,
+) -> Result<Vec<TroubleshootingToggleInfo>, LauncherError> {
+    crate::services::launcher::troubleshooting_toggles(&instance_name)
+}
+
+/// 实例的启动会话（每次启动的命令、日志和崩溃报告集中在一个目录）
+#[tauri::command]
+pub async fn get_sessions(instance_name: String) -> Result<Vec<SessionInfo>, LauncherError> {
+    tokio::task::spawn_blocking(move || crate::services::launcher::list_sessions(&instance_name)).await?
+}
+
+/// 实例的默认 JVM 参数规则（按版本范围和配置覆盖判断是否生效）
+#[tauri::command]
+pub async fn get_default_jvm_flags(instance_name: String) -> Result<Vec<DefaultJvmFlagInfo>, LauncherError> {
+    tokio::task::spawn_blocking(move || crate::services::launcher::default_jvm_flags(&instance_name)).await?
+}
+
+/// 列出可清理的 natives 目录（版本已删除或超过 `unused_days` 天未启动）和过期临时目录
+#[tauri::command]
+pub async fn list_orphaned_natives(unused_days: Option<u32>) -> Result<Vec<NativesDirInfo>, LauncherError> {
+    tokio::task::spawn_blocking(move || crate::services::launcher::list_orphaned_natives(unused_days)).await?
+}
+
+/// 清理 natives 和临时目录，`paths` 为空时清理全部可清理目录
+#[tauri::command]
+pub async fn prune_natives(
+    unused_days: Option<u32>,
+    paths: Option<Vec<String>>,
+) -> Result<NativesPruneResult, LauncherError> {
+    kiosk::require_admin()?;
+    tokio::task::spawn_blocking(move || crate::services::launcher::prune_natives(unused_days, paths)).await?
 }
@@ -3,7 +3,7 @@
 use crate::errors::LauncherError;
 use crate::services::loaders::{
     fabric,
-    forge::{self, ForgeVersion},
+    forge::{self, ForgeInstallReport, ForgeVersion},
     neoforge,
     quilt,
 };
@@ -67,6 +67,12 @@ pub async fn get_neoforge_versions(minecraft_version: String) -> Result<Vec<Load
         .collect())
 }
 
+/// 校验实例的 Forge processor 产出文件（补丁后的客户端 jar、SRG 映射等）
+#[tauri::command]
+pub async fn verify_forge_install(instance_name: String) -> Result<ForgeInstallReport, LauncherError> {
+    forge::verify_forge_install(instance_name).await
+}
+
 /// 检查指定 MC 版本支持哪些加载器
 #[tauri::command]
 pub async fn get_available_loaders(minecraft_version: String) -> Result<AvailableLoaders, LauncherError> {
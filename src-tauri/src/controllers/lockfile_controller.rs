@@ -0,0 +1,19 @@
+use crate::errors::LauncherError;
+use crate::services::kiosk;
+use crate::services::lockfile::{self, InstanceLockfile, LockSyncReport};
+
+/// 重新生成实例的锁定文件
+#[tauri::command]
+pub async fn generate_instance_lockfile(instance_name: String) -> Result<InstanceLockfile, LauncherError> {
+    tokio::task::spawn_blocking(move || lockfile::generate_instance_lockfile(&instance_name)).await?
+}
+
+/// 将实例还原到锁定状态（可指定好友共享的锁定文件路径）
+#[tauri::command]
+pub async fn sync_instance_to_lockfile(
+    instance_name: String,
+    lockfile_path: Option<String>,
+) -> Result<LockSyncReport, LauncherError> {
+    kiosk::require_admin()?;
+    lockfile::sync_instance_to_lockfile(&instance_name, lockfile_path.as_deref()).await
+}
@@ -1,8 +1,21 @@
+pub mod account_controller;
+pub mod announcement_controller;
 pub mod auth_controller;
+pub mod backup_controller;
 pub mod config_controller;
+pub mod diagnostics_controller;
 pub mod download_controller;
+pub mod fs_controller;
+pub mod health_controller;
 pub mod java_controller;
+pub mod kiosk_controller;
 pub mod launcher_controller;
 pub mod instance_controller;
 pub mod loader_controller;
+pub mod lockfile_controller;
 pub mod modpack_controller;
+pub mod scheduler_controller;
+pub mod server_sync_controller;
+pub mod task_controller;
+pub mod telemetry_controller;
+pub mod window_sink;
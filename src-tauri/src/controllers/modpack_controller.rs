@@ -1,6 +1,10 @@
+use crate::controllers::window_sink;
 use crate::errors::LauncherError;
 use crate::models::modpack::*;
-use crate::services::modpack_installer;
+use crate::services::{kiosk, modpack_health, modpack_installer, modpack_update};
+use crate::services::tasks::{self, TaskKind};
+use crate::services::telemetry;
+use tauri::Emitter;
 
 #[tauri::command]
 pub async fn search_modrinth_modpacks(
@@ -50,12 +54,44 @@ pub async fn install_modrinth_modpack(
     window: tauri::Window,
 ) -> Result<(), LauncherError> {
     let installer = modpack_installer::ModpackInstaller::new();
-    installer.install_modrinth_modpack(options, &window).await
+    let task = tasks::begin(TaskKind::ModpackInstall, &options.instance_name);
+    let _ = window.emit("task-started", task.info());
+    telemetry::timed_install("modpack", task.run(installer.install_modrinth_modpack(options, &window_sink::sink(&window)))).await
+}
+
+/// 从本地 .mrpack 或 CurseForge 整合包 zip 安装（按包内的 modrinth.index.json / manifest.json 识别格式）
+#[tauri::command]
+pub async fn import_modpack_from_file(
+    file_path: String,
+    instance_name: String,
+    window: tauri::Window,
+) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    let installer = modpack_installer::ModpackInstaller::new();
+    let task = tasks::begin(TaskKind::ModpackInstall, &instance_name);
+    let _ = window.emit("task-started", task.info());
+    let sink = window_sink::sink(&window);
+    let install = installer.install_modpack_from_file(&file_path, &instance_name, &sink);
+    telemetry::timed_install("modpack", task.run(install)).await
 }
 
 /// 取消整合包安装
 #[tauri::command]
 pub async fn cancel_modpack_install() -> Result<(), LauncherError> {
-    modpack_installer::set_modpack_cancel_flag();
+    tasks::cancel_kind(TaskKind::ModpackInstall);
     Ok(())
-}
\ No newline at end of file
+}
+/// 获取整合包实例的安装校验报告
+#[tauri::command]
+pub async fn get_modpack_health(instance_name: String) -> Result<Option<ModpackHealthReport>, LauncherError> {
+    modpack_health::get_health_report(&instance_name)
+}
+
+/// 预览整合包更新将新增、移除和变化的文件
+#[tauri::command]
+pub async fn preview_modpack_update(
+    instance_name: String,
+    new_version: String,
+) -> Result<ModpackUpdatePreview, LauncherError> {
+    modpack_update::preview_modpack_update(&instance_name, &new_version).await
+}
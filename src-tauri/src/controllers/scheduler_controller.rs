@@ -0,0 +1,48 @@
+use crate::controllers::window_sink;
+use crate::errors::LauncherError;
+use crate::services::kiosk;
+use crate::services::scheduler::{self, ScheduledTask, ScheduledTaskKind, TaskRunRecord};
+use tauri::AppHandle;
+
+/// 列出定时任务
+#[tauri::command]
+pub async fn list_scheduled_tasks() -> Result<Vec<ScheduledTask>, LauncherError> {
+    scheduler::list_scheduled_tasks()
+}
+
+/// 添加定时任务，`interval_hours` 为执行间隔（小时）
+#[tauri::command]
+pub async fn add_scheduled_task(
+    name: String,
+    kind: ScheduledTaskKind,
+    interval_hours: u32,
+) -> Result<ScheduledTask, LauncherError> {
+    kiosk::require_admin()?;
+    scheduler::add_scheduled_task(name, kind, interval_hours)
+}
+
+/// 删除定时任务
+#[tauri::command]
+pub async fn remove_scheduled_task(task_id: String) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    scheduler::remove_scheduled_task(&task_id)
+}
+
+/// 启用或停用定时任务
+#[tauri::command]
+pub async fn set_scheduled_task_enabled(task_id: String, enabled: bool) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    scheduler::set_scheduled_task_enabled(&task_id, enabled)
+}
+
+/// 立即执行定时任务
+#[tauri::command]
+pub async fn run_task_now(task_id: String, app: AppHandle) -> Result<TaskRunRecord, LauncherError> {
+    scheduler::run_task_now(task_id, &window_sink::app_sink(&app)).await
+}
+
+/// 获取定时任务执行历史（可按任务筛选）
+#[tauri::command]
+pub async fn get_task_history(task_id: Option<String>) -> Result<Vec<TaskRunRecord>, LauncherError> {
+    scheduler::get_task_history(task_id.as_deref())
+}
@@ -0,0 +1,25 @@
+use crate::errors::LauncherError;
+use crate::services::kiosk;
+use crate::services::server_sync::{self, ServerSyncReport};
+
+/// 按服务器发布的清单同步实例（未指定地址时使用上次的地址），`dry_run` 为 true 时只预览差异
+#[tauri::command]
+pub async fn sync_instance_from_server(
+    instance_name: String,
+    manifest_url: Option<String>,
+    protected_paths: Option<Vec<String>>,
+    dry_run: Option<bool>,
+) -> Result<ServerSyncReport, LauncherError> {
+    let dry_run = dry_run.unwrap_or(false);
+    if !dry_run {
+        kiosk::require_admin()?;
+    }
+    server_sync::sync_instance_from_server(&instance_name, manifest_url, protected_paths.unwrap_or_default(), dry_run)
+        .await
+}
+
+/// 实例上次同步的清单地址
+#[tauri::command]
+pub fn get_server_sync_url(instance_name: String) -> Result<Option<String>, LauncherError> {
+    server_sync::last_sync_url(&instance_name)
+}
@@ -0,0 +1,14 @@
+use crate::errors::LauncherError;
+use crate::services::tasks::{self, TaskInfo};
+
+/// 取消指定任务
+#[tauri::command]
+pub async fn cancel_task(task_id: String) -> Result<(), LauncherError> {
+    tasks::cancel_task(&task_id)
+}
+
+/// 列出正在进行的任务
+#[tauri::command]
+pub async fn list_tasks() -> Result<Vec<TaskInfo>, LauncherError> {
+    Ok(tasks::list_tasks())
+}
@@ -0,0 +1,16 @@
+use crate::errors::LauncherError;
+use crate::services::telemetry::{self, TelemetryStatus};
+use crate::services::kiosk;
+
+/// 获取匿名统计状态及待发送的内容
+#[tauri::command]
+pub async fn get_telemetry_status() -> Result<TelemetryStatus, LauncherError> {
+    telemetry::get_telemetry_status()
+}
+
+/// 开启或关闭匿名统计
+#[tauri::command]
+pub async fn set_telemetry_enabled(enabled: bool) -> Result<(), LauncherError> {
+    kiosk::require_admin()?;
+    telemetry::set_telemetry_enabled(enabled)
+}
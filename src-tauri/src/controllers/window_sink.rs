@@ -0,0 +1,78 @@
+//! 将 Tauri 窗口包装为事件接收方
+//!
+//! 事件直接发往前端；系统级进度同步到任务栏（Windows）/ 程序坞（macOS）进度条和窗口标题。
+//! 后台任务（配置监视、定时任务等）没有对应窗口，使用 [`app_sink`] 向所有窗口广播。
+
+use crate::errors::LauncherError;
+use crate::services::event_sink::{EventSink, ProgressSink};
+use serde_json::Value;
+use std::sync::Mutex;
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::{AppHandle, Emitter, Window};
+
+/// 开始显示进度前的窗口标题
+static BASE_TITLE: Mutex<Option<String>> = Mutex::new(None);
+
+struct WindowSink(Window);
+
+fn set_progress(window: &Window, status: ProgressBarStatus, progress: Option<u64>) {
+    let _ = window.set_progress_bar(ProgressBarState {
+        status: Some(status),
+        progress,
+    });
+}
+
+impl ProgressSink for WindowSink {
+    fn send(&self, event: &str, payload: Value) -> Result<(), LauncherError> {
+        Ok(self.0.emit(event, payload)?)
+    }
+
+    fn show_os_progress(&self, percent: u8) {
+        let window = &self.0;
+        set_progress(window, ProgressBarStatus::Normal, Some(percent as u64));
+        #[cfg(target_os = "macos")]
+        let _ = window.set_badge_label(Some(format!("{}%", percent)));
+
+        let Ok(mut base) = BASE_TITLE.lock() else {
+            return;
+        };
+        if base.is_none() {
+            *base = window.title().ok();
+        }
+        if let Some(title) = base.as_deref() {
+            let _ = window.set_title(&format!("{} - {}%", title, percent));
+        }
+    }
+
+    fn clear_os_progress(&self, failed: bool) {
+        let window = &self.0;
+        if let Some(title) = BASE_TITLE.lock().ok().and_then(|mut t| t.take()) {
+            let _ = window.set_title(&title);
+        }
+        #[cfg(target_os = "macos")]
+        let _ = window.set_badge_label(None);
+        if failed {
+            set_progress(window, ProgressBarStatus::Error, Some(100));
+        } else {
+            set_progress(window, ProgressBarStatus::None, None);
+        }
+    }
+}
+
+/// 窗口对应的事件接收方
+pub fn sink(window: &Window) -> EventSink {
+    EventSink::new(WindowSink(window.clone()))
+}
+
+struct AppSink(AppHandle);
+
+impl ProgressSink for AppSink {
+    fn send(&self, event: &str, payload: Value) -> Result<(), LauncherError> {
+        Ok(self.0.emit(event, payload)?)
+    }
+}
+
+/// 应用级事件接收方，事件发往所有窗口，不显示系统级进度
+pub fn app_sink(app: &AppHandle) -> EventSink {
+    EventSink::new(AppSink(app.clone()))
+}
@@ -7,29 +7,94 @@ pub enum LauncherError {
     #[error("IO 错误: {0}")]
     Io(#[from] io::Error),
     #[error("网络请求错误: {0}")]
-    Http(#[from] reqwest::Error),
+    Http(reqwest::Error),
+    #[error("TLS 证书校验失败: {0}。当前网络可能在拦截 HTTPS 流量（如学校或企业代理），请在设置中导入网络管理员提供的 CA 证书，或运行网络诊断")]
+    Tls(String),
     #[error("JSON 解析错误: {0}")]
     Json(#[from] serde_json::Error),
     #[error("压缩包错误: {0}")]
     Zip(#[from] zip::result::ZipError),
     #[error("Tauri 错误: {0}")]
     Tauri(#[from] tauri::Error),
+    #[error("用户名无效: {0}")]
+    InvalidUsername(#[from] UsernameError),
+    #[error("实例 '{instance}' 正在{operation}，请稍后再试")]
+    InstanceBusy { instance: String, operation: String },
+    #[error("实例 '{instance}' 的游戏正在运行 (PID: {pid})，请先关闭游戏")]
+    InstanceRunning { instance: String, pid: u32 },
+    #[error("{}", not_installable_message(.version, .required_by.as_deref(), .suggestions))]
+    VersionNotInstallable {
+        version: String,
+        /// 继承该版本的实例
+        required_by: Option<String>,
+        /// 最接近的官方版本
+        suggestions: Vec<String>,
+    },
     #[error("{0}")]
     Custom(String),
 }
 
+/// 离线用户名校验错误
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum UsernameError {
+    #[error("用户名不能为空")]
+    Empty,
+    #[error("长度不能少于 {0} 个字符")]
+    TooShort(usize),
+    #[error("长度不能超过 {0} 个字符")]
+    TooLong(usize),
+    #[error("包含不允许的字符 '{0}'，只能使用字母、数字和下划线")]
+    InvalidChar(char),
+    #[error("包含不允许的字符 '{0}'，不能使用空白或控制字符")]
+    UnsafeChar(char),
+}
+
+fn not_installable_message(version: &str, required_by: Option<&str>, suggestions: &[String]) -> String {
+    let mut message = match required_by {
+        Some(instance) => format!("实例 '{}' 继承的版本 '{}' 不在官方版本清单中", instance, version),
+        None => format!("版本 '{}' 不在官方版本清单中", version),
+    };
+    message.push_str("（可能是已下架的快照或自定义版本 ID），无法下载");
+    if !suggestions.is_empty() {
+        message.push_str(&format!("。最接近的官方版本: {}", suggestions.join(", ")));
+    }
+    message
+}
+
 impl serde::Serialize for LauncherError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::ser::Serializer,
     {
         use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("LauncherError", 1)?;
+        let mut state = serializer.serialize_struct("LauncherError", 4)?;
         state.serialize_field("message", &self.to_string())?;
+        // 前端据此提供"改为继承官方版本"的修复入口
+        if let LauncherError::VersionNotInstallable { version, suggestions, .. } = self {
+            state.serialize_field("kind", "versionNotInstallable")?;
+            state.serialize_field("version", version)?;
+            state.serialize_field("suggestions", suggestions)?;
+        }
+        // 前端据此询问是否强制结束游戏后继续
+        if let LauncherError::InstanceRunning { instance, pid } = self {
+            state.serialize_field("kind", "instanceRunning")?;
+            state.serialize_field("instance", instance)?;
+            state.serialize_field("pid", pid)?;
+        }
         state.end()
     }
 }
 
+impl From<reqwest::Error> for LauncherError {
+    fn from(err: reqwest::Error) -> Self {
+        if crate::services::http_client::is_tls_error(&err) {
+            LauncherError::Tls(err.to_string())
+        } else {
+            LauncherError::Http(err)
+        }
+    }
+}
+
 impl From<JoinError> for LauncherError {
     fn from(err: JoinError) -> Self {
         LauncherError::Custom(format!("任务执行错误: {}", err))
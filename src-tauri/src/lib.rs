@@ -1,37 +1,81 @@
+//! Ar1s Launcher 核心库
+//!
+//! 嵌入到其他程序时使用 [`Launcher`] 及其相关类型；其余模块是桌面端的内部实现，
+//! 不保证接口稳定。
+
+mod api;
+#[doc(hidden)]
 pub mod controllers;
 mod errors;
-mod models;
+#[doc(hidden)]
+pub mod models;
+#[doc(hidden)]
 pub mod services;
+#[doc(hidden)]
 pub mod utils;
-pub use errors::LauncherError;
-pub use models::*;
-pub use services::config::{load_config, save_config};
-pub use services::launcher::launch_minecraft;
+
+pub use api::Launcher;
+pub use errors::{LauncherError, UsernameError};
+pub use models::modpack::ModpackInstallOptions;
+pub use models::{GameConfig, InstanceInfo, LaunchOverrides, VersionManifest};
+pub use services::event_sink::{EventSink, ProgressSink};
+pub use services::loaders::LoaderType;
+use tauri::{Listener, Manager};
 use utils::logger::setup_logger;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // 初始化日志记录器
-    if let Err(e) = setup_logger() {
+    let logger_started = std::time::Instant::now();
+    let logger_result = setup_logger();
+    services::metrics::record("startup:logger", logger_started.elapsed(), logger_result.is_ok());
+    if let Err(e) = logger_result {
         eprintln!("Error setting up logger: {}", e);
     }
 
     log::info!("[DEBUG] 程序启动");
 
+    let cli = utils::cli::parse_args();
+    if let Some(shell) = cli.completions.as_deref() {
+        match utils::cli::completion_script(shell, "ar1s_launcher") {
+            Some(script) => print!("{}", script),
+            None => eprintln!("不支持的 shell: {}", shell),
+        }
+        return;
+    }
+    if cli.print_game_dir {
+        if let Ok(game_dir) = services::config::get_game_dir() {
+            println!("{}", game_dir);
+        }
+        return;
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_http::init())
         .invoke_handler(tauri::generate_handler![
             controllers::download_controller::get_versions,
+            controllers::download_controller::check_new_versions,
             controllers::download_controller::download_version,
+            controllers::download_controller::estimate_version_download,
             controllers::download_controller::cancel_download,
-            controllers::launcher_controller::launch_minecraft,
+            controllers::download_controller::collect_asset_garbage,
+            controllers::launcher_controller::validate_version_json,
+            controllers::launcher_controller::export_launch_script,
+            controllers::launcher_controller::get_troubleshooting_options,
+            controllers::launcher_controller::get_default_jvm_flags,
+            controllers::launcher_controller::get_sessions,
+            controllers::launcher_controller::list_orphaned_natives,
+            controllers::launcher_controller::prune_natives,
             controllers::config_controller::get_config,
             controllers::config_controller::get_game_dir,
             controllers::config_controller::get_game_dir_info,
             controllers::config_controller::set_game_dir,
+            controllers::config_controller::list_game_dirs,
+            controllers::config_controller::add_game_dir,
+            controllers::config_controller::remove_game_dir,
+            controllers::config_controller::set_default_game_dir,
             controllers::config_controller::select_game_dir,
             controllers::config_controller::set_version_isolation,
             controllers::java_controller::find_java_installations_command,
@@ -41,15 +85,38 @@ pub fn run() {
             controllers::config_controller::save_config_key,
             controllers::java_controller::validate_java_path,
             controllers::java_controller::get_java_version,
+            controllers::java_controller::install_runtime,
+            controllers::java_controller::list_runtimes,
+            controllers::java_controller::verify_runtime,
+            controllers::java_controller::repair_runtime,
             controllers::config_controller::get_download_threads,
             controllers::config_controller::set_download_threads,
+            controllers::config_controller::set_modrinth_mirror,
             controllers::config_controller::validate_version_files,
+            controllers::config_controller::estimate_version_verification,
+            controllers::account_controller::get_accounts,
+            controllers::account_controller::add_account,
+            controllers::account_controller::remove_account,
+            controllers::account_controller::set_active_account,
+            controllers::account_controller::get_skin,
+            controllers::account_controller::upload_skin,
+            controllers::account_controller::set_skin_variant,
+            controllers::account_controller::set_active_cape,
             controllers::auth_controller::get_saved_username,
             controllers::auth_controller::set_saved_username,
             controllers::auth_controller::get_saved_uuid,
             controllers::auth_controller::set_saved_uuid,
+            controllers::auth_controller::validate_username,
+            controllers::auth_controller::regenerate_offline_uuid,
+            controllers::auth_controller::get_skin_server_status,
+            controllers::auth_controller::set_skin_server_enabled,
+            controllers::auth_controller::set_skin_server_lan,
+            controllers::auth_controller::set_offline_skin,
+            controllers::auth_controller::remove_offline_skin,
             controllers::config_controller::get_total_memory,
             controllers::config_controller::get_memory_stats,
+            controllers::config_controller::start_memory_sampling,
+            controllers::config_controller::stop_memory_sampling,
             controllers::config_controller::recommend_memory,
             controllers::config_controller::validate_memory_setting,
             controllers::config_controller::check_memory_warning,
@@ -59,47 +126,135 @@ pub fn run() {
             controllers::config_controller::analyze_memory_efficiency,
             controllers::config_controller::get_window_settings,
             controllers::config_controller::set_window_settings,
+            controllers::config_controller::set_global_hooks,
+            controllers::config_controller::set_jvm_flag_override,
+            controllers::config_controller::get_extra_jvm_args,
+            controllers::config_controller::set_extra_jvm_args,
+            controllers::config_controller::get_host_limits,
+            controllers::config_controller::set_host_limit,
             controllers::config_controller::get_last_selected_version,
             controllers::config_controller::set_last_selected_version,
+            controllers::config_controller::import_ca_certificate,
+            controllers::config_controller::clear_ca_certificate,
+            controllers::config_controller::get_config_history,
+            controllers::config_controller::revert_config_change,
             controllers::instance_controller::validate_instance_name_cmd,
             controllers::instance_controller::check_instance_name_available,
             controllers::instance_controller::create_instance,
+            controllers::instance_controller::create_instance_for_world,
+            controllers::instance_controller::ping_server,
+            controllers::instance_controller::create_instance_for_server,
+            controllers::instance_controller::install_version_from_url,
             controllers::instance_controller::get_instances,
             controllers::instance_controller::delete_instance,
             controllers::instance_controller::rename_instance,
+            controllers::instance_controller::archive_instance,
+            controllers::instance_controller::unarchive_instance,
+            controllers::instance_controller::list_archived_instances,
             controllers::instance_controller::open_instance_folder,
+            controllers::instance_controller::open_instance_content_folder,
+            controllers::instance_controller::open_instance_terminal,
             controllers::instance_controller::launch_instance,
+            controllers::instance_controller::check_base_version,
+            controllers::instance_controller::rebase_instance,
+            controllers::instance_controller::start_instance_share,
+            controllers::instance_controller::stop_instance_share,
+            controllers::instance_controller::receive_instance,
+            controllers::instance_controller::create_instance_shortcut,
+            controllers::instance_controller::get_instance_settings,
+            controllers::instance_controller::set_instance_settings,
+            controllers::instance_controller::rebuild_instance_classpath,
+            controllers::instance_controller::regenerate_version_json,
+            controllers::instance_controller::get_instance_content_dir,
+            controllers::instance_controller::import_mods_from_folder,
+            controllers::instance_controller::install_modrinth_mod,
+            controllers::instance_controller::export_mod_list,
             controllers::loader_controller::get_forge_versions,
             controllers::loader_controller::get_fabric_versions,
             controllers::loader_controller::get_quilt_versions,
             controllers::loader_controller::get_neoforge_versions,
             controllers::loader_controller::get_available_loaders,
+            controllers::loader_controller::verify_forge_install,
             controllers::modpack_controller::search_modrinth_modpacks,
             controllers::modpack_controller::get_modrinth_modpack_versions,
             controllers::modpack_controller::install_modrinth_modpack,
-            controllers::modpack_controller::cancel_modpack_install
+            controllers::modpack_controller::import_modpack_from_file,
+            controllers::modpack_controller::cancel_modpack_install,
+            controllers::modpack_controller::get_modpack_health,
+            controllers::modpack_controller::preview_modpack_update,
+            controllers::health_controller::get_startup_report,
+            controllers::health_controller::run_health_check,
+            controllers::health_controller::get_instance_health,
+            controllers::fs_controller::read_instance_file,
+            controllers::fs_controller::list_game_directory,
+            controllers::fs_controller::open_game_path,
+            controllers::fs_controller::scan_duplicate_resourcepacks,
+            controllers::fs_controller::consolidate_resourcepacks,
+            controllers::diagnostics_controller::get_performance_metrics,
+            controllers::diagnostics_controller::export_diagnostics_bundle,
+            controllers::diagnostics_controller::probe_network,
+            controllers::task_controller::cancel_task,
+            controllers::task_controller::list_tasks,
+            controllers::announcement_controller::get_announcements,
+            controllers::telemetry_controller::get_telemetry_status,
+            controllers::telemetry_controller::set_telemetry_enabled,
+            controllers::kiosk_controller::get_kiosk_status,
+            controllers::kiosk_controller::unlock_admin,
+            controllers::kiosk_controller::lock_admin,
+            controllers::kiosk_controller::set_admin_pin,
+            controllers::lockfile_controller::generate_instance_lockfile,
+            controllers::lockfile_controller::sync_instance_to_lockfile,
+            controllers::server_sync_controller::sync_instance_from_server,
+            controllers::server_sync_controller::get_server_sync_url,
+            controllers::backup_controller::backup_world,
+            controllers::backup_controller::list_backups,
+            controllers::backup_controller::restore_world_backup,
+            controllers::backup_controller::test_backup_target,
+            controllers::scheduler_controller::list_scheduled_tasks,
+            controllers::scheduler_controller::add_scheduled_task,
+            controllers::scheduler_controller::remove_scheduled_task,
+            controllers::scheduler_controller::set_scheduled_task_enabled,
+            controllers::scheduler_controller::run_task_now,
+            controllers::scheduler_controller::get_task_history
         ])
-        .setup(|_| {
+        .setup(move |app| {
             log::info!("[DEBUG] Tauri应用初始化完成");
-            
-            // 预加载配置文件
-            if let Err(e) = services::config::preload_config() {
-                log::error!("配置预加载失败: {}", e);
+
+            services::startup::initialize(&controllers::window_sink::app_sink(app.handle()));
+
+            // 无界面模式：隐藏主窗口并直接启动指定实例
+            if let Some(instance_name) = cli.launch_instance.clone() {
+                start_headless_launch(app, instance_name);
             }
-            
-            // 后台预热 Java 检测缓存（异步执行，不阻塞启动）
-            std::thread::spawn(|| {
-                log::info!("后台预热 Java 检测缓存...");
-                let rt = tokio::runtime::Runtime::new().unwrap();
-                rt.block_on(async {
-                    if let Err(e) = services::java::find_java_installations_command().await {
-                        log::warn!("Java 缓存预热失败: {}", e);
-                    }
-                });
-            });
-            
+
             Ok(())
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+/// 无界面启动实例，游戏退出后关闭启动器；启动失败时显示主窗口以便查看错误
+fn start_headless_launch(app: &mut tauri::App, instance_name: String) {
+    let Some(webview_window) = app.get_webview_window("main") else {
+        log::error!("无界面启动失败：找不到主窗口");
+        return;
+    };
+    let _ = webview_window.hide();
+    let window = webview_window.as_ref().window();
+    log::info!("无界面模式启动实例: {}", instance_name);
+
+    let app_handle = app.handle().clone();
+    for event in ["minecraft-exited", "minecraft-error"] {
+        let handle = app_handle.clone();
+        window.listen(event, move |_| handle.exit(0));
+    }
+
+    let sink = controllers::window_sink::sink(&window);
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = services::instance::launch_instance(instance_name, None, sink.clone()).await {
+            log::error!("无界面启动失败: {}", e);
+            let _ = webview_window.show();
+            services::log_events::emit(&sink, "log-error", format!("启动失败: {}", e));
+        }
+    });
+}
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 // 默认下载线程数
@@ -12,6 +12,21 @@ pub fn default_max_memory() -> u32 {
     4096
 }
 
+// 默认皮肤服务端口
+pub fn default_skin_server_port() -> u16 {
+    25585
+}
+
+// 默认分块下载阈值 (MB)
+pub fn default_chunked_download_threshold_mb() -> u64 {
+    32
+}
+
+// 默认分块下载连接数
+pub fn default_chunked_download_parts() -> u8 {
+    4
+}
+
 // 默认为true的辅助函数
 pub fn default_true() -> bool {
     true
@@ -22,10 +37,49 @@ pub fn default_false() -> bool {
     false
 }
 
+/// 离线 UUID 策略
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UuidStrategy {
+    /// 始终使用标准 OfflinePlayer MD5 算法
+    Standard,
+    /// 保留用户名之前使用过的 UUID
+    #[default]
+    Preserve,
+}
+
+/// 备份存储位置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BackupTarget {
+    /// 本地目录（为空时使用 game_dir/backups）
+    Local { path: Option<String> },
+    /// WebDAV 目录
+    WebDav {
+        url: String,
+        username: Option<String>,
+        password: Option<String>,
+    },
+    /// S3 兼容存储（路径风格访问）
+    S3 {
+        endpoint: String,
+        bucket: String,
+        region: String,
+        access_key: String,
+        secret_key: String,
+        #[serde(default)]
+        prefix: String,
+    },
+}
+
 // 游戏配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameConfig {
+    /// 默认游戏目录，新安装的实例放在这里
     pub game_dir: String,
+    /// 额外登记的游戏目录，实例列表会汇总所有目录
+    #[serde(default)]
+    pub game_dirs: Vec<String>,
     #[serde(default = "default_true")]
     pub version_isolation: bool,
     pub java_path: Option<String>,
@@ -57,6 +111,127 @@ pub struct GameConfig {
     pub instance_last_played: HashMap<String, i64>,
     /// 上次选择的游戏版本
     pub last_selected_version: Option<String>,
+    /// Linux: 可用时通过 gamemoderun 启动游戏
+    #[serde(default = "default_true")]
+    pub use_gamemode: bool,
+    /// Linux: Wayland 会话下为游戏设置 SDL/GLFW 环境提示
+    #[serde(default = "default_false")]
+    pub wayland_hints: bool,
+    /// 按账户隔离存档：启用后存档/截图位于实例的 profiles/<账户名> 目录
+    #[serde(default = "default_false")]
+    pub isolate_per_account: bool,
+    /// 后台定时预取版本清单和加载器元数据
+    #[serde(default = "default_true")]
+    pub background_prefetch: bool,
+    /// 启动时的联网活动（元数据预取、新版本检查）；关闭后首次联网推迟到下一个周期，离线时也能立即打开
+    #[serde(default = "default_true")]
+    pub startup_network: bool,
+    /// 离线 UUID 策略
+    #[serde(default)]
+    pub uuid_strategy: UuidStrategy,
+    /// 用户名 -> 已使用的离线 UUID
+    #[serde(default)]
+    pub offline_uuids: HashMap<String, String>,
+    /// 启用离线皮肤服务（authlib-injector + 本地 Yggdrasil）
+    #[serde(default = "default_false")]
+    pub offline_skin_server: bool,
+    /// 本地皮肤服务端口
+    #[serde(default = "default_skin_server_port")]
+    pub skin_server_port: u16,
+    /// 本机皮肤服务同时对局域网开放（默认只监听 127.0.0.1）
+    #[serde(default = "default_false")]
+    pub skin_server_lan: bool,
+    /// 使用局域网内其他主机的皮肤服务（为空时使用本机服务）
+    pub skin_server_url: Option<String>,
+    /// 超过该大小 (MB) 的单个文件使用多连接分块下载
+    #[serde(default = "default_chunked_download_threshold_mb")]
+    pub chunked_download_threshold_mb: u64,
+    /// 分块下载的连接数（1 表示禁用分块下载）
+    #[serde(default = "default_chunked_download_parts")]
+    pub chunked_download_parts: u8,
+    /// 公告源地址（为空时使用默认地址）
+    #[serde(default)]
+    pub announcements_url: Option<String>,
+    /// 是否启用匿名使用统计（默认关闭）
+    #[serde(default)]
+    pub telemetry_enabled: bool,
+    /// 匿名统计上报地址（为空时不上报）
+    #[serde(default)]
+    pub telemetry_endpoint: Option<String>,
+    /// 存档备份位置（为空时备份到本地 game_dir/backups）
+    #[serde(default)]
+    pub backup_target: Option<BackupTarget>,
+    /// 自定义资源文件镜像（基础地址，对象路径为 `<基础地址>/<hash 前两位>/<hash>`），在官方源和 BMCLAPI 之后依次尝试
+    #[serde(default)]
+    pub asset_mirrors: Vec<String>,
+    /// 定时比对版本清单，有新版本（如新快照）发布时通知
+    #[serde(default)]
+    pub version_watcher: bool,
+    /// 额外信任的 CA 证书路径（PEM 或 DER），用于有 TLS 中间人检查的网络
+    #[serde(default)]
+    pub custom_ca_cert: Option<String>,
+    /// Modrinth 文件镜像：含 `{path}` 的模板（如 `https://mirror.example/modrinth{path}`），
+    /// 或替换 `https://cdn.modrinth.com` 的基础地址。镜像下载的文件仍需通过哈希校验
+    #[serde(default)]
+    pub modrinth_mirror: Option<String>,
+    /// 全局生命周期钩子，实例没有设置对应钩子时使用
+    #[serde(default)]
+    pub hooks: LifecycleHooks,
+    /// 实例的生命周期钩子（实例名 -> 钩子）。保存在启动器配置而不是实例目录，
+    /// 整合包或分享包带来的文件不能设置钩子
+    #[serde(default)]
+    pub instance_hooks: HashMap<String, LifecycleHooks>,
+    /// 默认 JVM 参数规则的覆盖（规则 ID -> 是否启用），未列出的规则按版本范围自动判断
+    #[serde(default)]
+    pub jvm_flag_overrides: BTreeMap<String, bool>,
+    /// 自定义 JVM 参数（如 `-XX:+UseZGC`），追加在默认参数之后、classpath 之前
+    #[serde(default)]
+    pub extra_jvm_args: Vec<String>,
+    /// 下载主机的并发限制覆盖（主机名 -> 限制），未列出的已知主机使用内置默认值
+    #[serde(default)]
+    pub host_limits: BTreeMap<String, HostLimit>,
+}
+
+/// 单个下载主机的并发和请求间隔限制
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostLimit {
+    /// 最大并发连接数
+    pub max_connections: u32,
+    /// 相邻请求开始的最小间隔（毫秒）
+    #[serde(default)]
+    pub delay_ms: u64,
+}
+
+/// 生命周期钩子执行的脚本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookCommand {
+    /// 脚本或可执行文件路径
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// 超时时间（秒），未设置时为 60 秒
+    pub timeout_secs: Option<u64>,
+}
+
+/// 各生命周期节点的钩子
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LifecycleHooks {
+    /// 启动游戏前执行，失败时取消启动
+    pub pre_launch: Option<HookCommand>,
+    /// 游戏退出后执行
+    pub post_exit: Option<HookCommand>,
+    /// 整合包安装完成后执行
+    pub post_modpack_install: Option<HookCommand>,
+}
+
+impl LifecycleHooks {
+    /// 是否没有设置任何钩子
+    pub fn is_empty(&self) -> bool {
+        self.pre_launch.is_none() && self.post_exit.is_none() && self.post_modpack_install.is_none()
+    }
 }
 
 // 游戏目录信息
@@ -99,12 +274,132 @@ pub struct LaunchOptions {
     pub version: String,
     pub username: String,
     pub memory: Option<u32>,
+    /// Java 路径（未指定时使用全局配置）
+    #[serde(default)]
+    pub java_path: Option<String>,
     /// 窗口宽度
     pub window_width: Option<u32>,
     /// 窗口高度
     pub window_height: Option<u32>,
     /// 是否全屏
     pub fullscreen: Option<bool>,
+    /// 自定义窗口标题/启动器品牌
+    #[serde(default)]
+    pub window_title: Option<String>,
+    /// 追加的 JVM 参数
+    #[serde(default)]
+    pub extra_jvm_args: Vec<String>,
+    /// 追加的游戏参数
+    #[serde(default)]
+    pub extra_game_args: Vec<String>,
+    /// 启动后直接加入的服务器（`host[:port]`）
+    #[serde(default)]
+    pub server: Option<String>,
+    /// 临时启动：不把账户写回配置
+    #[serde(default)]
+    pub transient: bool,
+    /// 本次启动启用的故障排查选项
+    #[serde(default)]
+    pub troubleshooting: Vec<TroubleshootingToggle>,
+}
+
+// 故障排查（安全模式）启动选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TroubleshootingToggle {
+    /// 临时移走非核心模组，游戏退出后恢复
+    DisableMods,
+    /// Fabric / Quilt：固定模组加载顺序
+    DisableModShuffle,
+    /// 输出 Mixin 详细日志，注入失败时导出目标类
+    MixinDebug,
+    /// Forge / NeoForge：关闭早期加载窗口（常见于显卡驱动兼容问题）
+    DisableEarlyWindow,
+}
+
+// 单次启动的选项覆盖（"以...启动"菜单），不会保存到配置或实例设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchOverrides {
+    /// 最大内存（MB）
+    pub memory: Option<u32>,
+    /// 使用的离线账户名
+    pub username: Option<String>,
+    pub window_width: Option<u32>,
+    pub window_height: Option<u32>,
+    pub fullscreen: Option<bool>,
+    #[serde(default)]
+    pub extra_jvm_args: Vec<String>,
+    #[serde(default)]
+    pub extra_game_args: Vec<String>,
+    /// 启动后直接加入的服务器（`host[:port]`）
+    pub server: Option<String>,
+    #[serde(default)]
+    pub troubleshooting: Vec<TroubleshootingToggle>,
+}
+
+/// 进度事件的阶段
+///
+/// 与文字说明一起发送，前端据此显示统一的图标和步骤，读屏软件也能播报当前阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressStage {
+    /// 检查目录和现有文件
+    Preparing,
+    /// 获取版本清单或整合包信息
+    FetchingManifest,
+    /// 下载整合包文件
+    DownloadingModpack,
+    /// 下载游戏文件（客户端、库）
+    DownloadingFiles,
+    /// 下载资源文件
+    DownloadingAssets,
+    /// 下载模组文件
+    DownloadingMods,
+    /// 解压整合包
+    Extracting,
+    /// 复制文件
+    CopyingFiles,
+    /// 安装加载器
+    InstallingLoader,
+    /// 写入实例配置
+    Configuring,
+    /// 校验文件
+    VerifyingFiles,
+    /// 解压 natives
+    ExtractingNatives,
+    /// 构建 classpath
+    BuildingClasspath,
+    /// 构建启动参数
+    PreparingArguments,
+    /// 启动游戏进程
+    Launching,
+    /// 已完成
+    Completed,
+}
+
+impl ProgressStage {
+    /// 阶段的默认中文名称（前端未提供翻译时使用）
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Preparing => "准备中",
+            Self::FetchingManifest => "获取版本信息",
+            Self::DownloadingModpack => "下载整合包",
+            Self::DownloadingFiles => "下载游戏文件",
+            Self::DownloadingAssets => "下载资源文件",
+            Self::DownloadingMods => "下载模组",
+            Self::Extracting => "解压文件",
+            Self::CopyingFiles => "复制文件",
+            Self::InstallingLoader => "安装加载器",
+            Self::Configuring => "配置实例",
+            Self::VerifyingFiles => "校验文件",
+            Self::ExtractingNatives => "解压本地库",
+            Self::BuildingClasspath => "构建 Classpath",
+            Self::PreparingArguments => "构建启动参数",
+            Self::Launching => "启动游戏",
+            Self::Completed => "已完成",
+        }
+    }
 }
 
 // 下载状态
@@ -120,6 +415,7 @@ pub enum DownloadStatus {
 // 下载进度
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadProgress {
+    pub stage: ProgressStage,
     pub progress: u64,
     pub total: u64,
     pub speed: f64,
@@ -149,6 +445,87 @@ pub struct InstanceConfig {
     pub created_at: String,
 }
 
+// 实例独立设置（保存在实例目录的 ar1s_instance.json，未设置的项使用全局配置）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceSettings {
+    /// 窗口宽度
+    pub window_width: Option<u32>,
+    /// 窗口高度
+    pub window_height: Option<u32>,
+    /// 是否全屏
+    pub fullscreen: Option<bool>,
+    /// 自定义窗口标题（通过 -Dminecraft.launcher.brand 传递）
+    pub window_title: Option<String>,
+    /// Java 路径
+    #[serde(default)]
+    pub java_path: Option<String>,
+    /// 最大内存（MB）
+    #[serde(default)]
+    pub max_memory: Option<u32>,
+    /// 追加的 JVM 参数（在全局参数之后）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub jvm_args: Vec<String>,
+    /// 追加的游戏参数
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub game_args: Vec<String>,
+    /// 启动时自愈补齐的库（相对 libraries 目录），由 rebuild_instance_classpath 写入版本 JSON 后清空
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub healed_libraries: Vec<String>,
+    /// 因显卡驱动崩溃等偶发问题退出时自动重新启动一次
+    #[serde(default)]
+    pub auto_restart_on_crash: bool,
+    /// 启动时使用去掉 META-INF 签名的客户端 JAR（兼容修改过原版 class 的旧版客户端）
+    #[serde(default)]
+    pub strip_jar_signatures: bool,
+    /// 实例的生命周期钩子（覆盖全局钩子）。实际保存在启动器配置的 `instance_hooks`，
+    /// 实例目录文件中的该字段会被忽略
+    #[serde(default, skip_serializing_if = "LifecycleHooks::is_empty")]
+    pub hooks: LifecycleHooks,
+    /// 游戏进程使用的代理（通过 JVM 参数传入，不影响启动器自身的下载）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<GameProxy>,
+}
+
+/// 游戏进程代理类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GameProxyKind {
+    Http,
+    Socks5,
+}
+
+/// 游戏进程代理设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameProxy {
+    pub kind: GameProxyKind,
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// 不走代理的主机，支持 `*.example.com` 形式的通配符
+    #[serde(default)]
+    pub non_proxy_hosts: Vec<String>,
+}
+
+/// 版本文件校验级别
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VerificationLevel {
+    /// 只检查文件存在和大小
+    #[default]
+    Quick,
+    /// 校验库文件和主 JAR 的 SHA1，资源文件抽样校验
+    Standard,
+    /// 校验全部文件的 SHA1
+    Full,
+}
+
+impl VerificationLevel {
+    pub const ALL: [VerificationLevel; 3] = [Self::Quick, Self::Standard, Self::Full];
+}
+
 // 实例信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -161,14 +538,24 @@ pub struct InstanceInfo {
     pub loader_type: Option<String>,
     pub game_version: Option<String>,
     pub last_played: Option<i64>,
-}
-
-// Forge版本
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct ForgeVersion {
-    pub version: String,
-    pub mcversion: String,
-    pub build: i32,
+    /// 原版 Minecraft 版本
+    pub mc_version: Option<String>,
+    /// 加载器版本
+    pub loader_version: Option<String>,
+    /// 实例所在的游戏目录
+    pub game_dir: String,
+    /// 整合包安装校验结果，非整合包实例为空
+    pub health: Option<modpack::ModpackHealthStatus>,
+    /// 基础版本类型（release / snapshot / old_beta / old_alpha / custom）
+    pub version_type: Option<String>,
+    /// 继承链中缺失的父版本，启动前需要下载或改为继承官方版本
+    pub missing_base: Option<String>,
+    /// 各校验级别上次通过的时间（Unix 秒）
+    #[serde(default)]
+    pub verified_levels: BTreeMap<VerificationLevel, u64>,
+    /// 是否已归档（版本目录已压缩移出，需要解档后才能启动）
+    #[serde(default)]
+    pub archived: bool,
 }
 
 // 整合包相关模型
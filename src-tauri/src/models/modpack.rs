@@ -49,6 +49,16 @@ pub struct ModrinthHashes {
     pub sha512: String,
 }
 
+// 按哈希匹配到的 Modrinth 文件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModrinthFileMatch {
+    pub project_id: String,
+    pub version_id: String,
+    pub version_number: String,
+    pub url: String,
+}
+
 // Modrinth依赖关系
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ModrinthDependency {
@@ -82,4 +92,77 @@ pub struct ModpackInstallOptions {
     pub version_id: String,
     pub instance_name: String,
     pub install_path: String,
-}
\ No newline at end of file
+}
+// 整合包安装校验结果等级，用于实例列表的健康标记
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModpackHealthStatus {
+    /// 全部检查通过
+    Green,
+    /// 存在可能影响游戏的问题
+    Yellow,
+    /// 存在会导致无法启动的问题
+    Red,
+}
+
+// 整合包校验发现的问题
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackHealthIssue {
+    /// 检查项：version / file / dependency
+    pub category: String,
+    pub message: String,
+    /// 是否会导致无法启动
+    pub fatal: bool,
+}
+
+// 整合包安装后校验报告
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackHealthReport {
+    pub instance_name: String,
+    pub status: ModpackHealthStatus,
+    pub checked_at: String,
+    pub files_checked: usize,
+    pub mods_checked: usize,
+    pub issues: Vec<ModpackHealthIssue>,
+}
+
+// 整合包安装的单个文件（相对实例目录）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackFileRecord {
+    pub path: String,
+    pub sha1: String,
+    pub size: u64,
+}
+
+// 整合包更新中一个文件的变化
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackFileDiff {
+    pub path: String,
+    /// 当前实例中的大小（新增文件为空）
+    pub old_size: Option<u64>,
+    /// 新版本中的大小（移除文件为空）
+    pub new_size: Option<u64>,
+    /// 文件被用户修改过（与上次安装时的内容不同），更新会覆盖或删除这些修改
+    pub user_modified: bool,
+}
+
+// 整合包更新预览
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackUpdatePreview {
+    pub instance_name: String,
+    pub current_version: Option<String>,
+    pub new_version: String,
+    pub added: Vec<ModpackFileDiff>,
+    pub removed: Vec<ModpackFileDiff>,
+    pub changed: Vec<ModpackFileDiff>,
+    pub unchanged: usize,
+    /// 需要下载的字节数（整合包文件和新增、变化的文件）
+    pub download_bytes: u64,
+    /// 没有上次安装的文件记录（旧版启动器安装）时无法判断移除的文件和用户修改
+    pub has_install_record: bool,
+}
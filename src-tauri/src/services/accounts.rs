@@ -0,0 +1,313 @@
+//! 账户管理
+//!
+//! 保存多个离线和正版账户，保存在配置文件同目录的 `ar1s_accounts.json`（访问令牌不写入
+//! 主配置，避免进入配置历史）。切换默认账户时同步配置中的用户名和 UUID，
+//! 旧版只保存单个用户名的配置在首次读取时迁移为一个离线账户。
+
+use crate::errors::LauncherError;
+use crate::services::auth::{self, AuthSession, UserType};
+use crate::services::config;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 保存的账户
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Account {
+    id: String,
+    username: String,
+    uuid: String,
+    account_type: UserType,
+    #[serde(default)]
+    access_token: Option<String>,
+    #[serde(default)]
+    xuid: Option<String>,
+    #[serde(default)]
+    client_id: Option<String>,
+    added_at: String,
+}
+
+/// 前端可见的账户信息（不含访问令牌）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInfo {
+    pub id: String,
+    pub username: String,
+    pub uuid: String,
+    pub account_type: UserType,
+    /// 是否为默认账户
+    pub active: bool,
+    pub added_at: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountStore {
+    accounts: Vec<Account>,
+    active_account: Option<String>,
+}
+
+impl AccountStore {
+    fn info(&self, account: &Account) -> AccountInfo {
+        AccountInfo {
+            id: account.id.clone(),
+            username: account.username.clone(),
+            uuid: account.uuid.clone(),
+            account_type: account.account_type,
+            active: self.active_account.as_deref() == Some(account.id.as_str()),
+            added_at: account.added_at.clone(),
+        }
+    }
+
+    fn active(&self) -> Option<&Account> {
+        let id = self.active_account.as_deref()?;
+        self.accounts.iter().find(|a| a.id == id)
+    }
+}
+
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+fn store_path() -> Result<PathBuf, LauncherError> {
+    Ok(config::get_config_path()?.with_file_name("ar1s_accounts.json"))
+}
+
+fn new_offline_account(username: &str, uuid: String) -> Account {
+    Account {
+        id: format!("account-{}", chrono::Utc::now().timestamp_millis()),
+        username: username.to_string(),
+        uuid,
+        account_type: UserType::Legacy,
+        access_token: None,
+        xuid: None,
+        client_id: None,
+        added_at: chrono::Local::now().to_rfc3339(),
+    }
+}
+
+/// 旧配置只保存了单个用户名和 UUID，迁移为默认离线账户
+fn migrate_legacy() -> AccountStore {
+    let mut store = AccountStore::default();
+    let Ok(cfg) = config::load_config() else {
+        return store;
+    };
+    // 旧版本允许的用户名可能不符合现在的规则，照常迁移
+    if let Some(username) = cfg.username.filter(|name| !name.trim().is_empty()) {
+        let uuid = cfg.uuid.unwrap_or_else(|| auth::standard_offline_uuid(&username));
+        let account = new_offline_account(&username, uuid);
+        store.active_account = Some(account.id.clone());
+        store.accounts.push(account);
+        log::info!("已将配置中的用户名 {} 迁移为离线账户", username);
+    }
+    store
+}
+
+/// 读取账户存储，第二项表示是否刚从旧配置迁移（需要保存）
+///
+/// 文件无法解析时备份为 `.corrupt` 并返回错误，不会用空存储覆盖已有账户
+fn load_store(path: &Path) -> Result<(AccountStore, bool), LauncherError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok((migrate_legacy(), true)),
+        Err(e) => return Err(e.into()),
+    };
+    match serde_json::from_str(&content) {
+        Ok(store) => Ok((store, false)),
+        Err(e) => {
+            let backup = path.with_extension("json.corrupt");
+            fs::rename(path, &backup)?;
+            log::error!("账户文件无法解析，已备份为 {}: {}", backup.display(), e);
+            Err(LauncherError::Custom(format!(
+                "账户文件已损坏，已备份为 {}: {}",
+                backup.display(),
+                e
+            )))
+        }
+    }
+}
+
+/// 在存储锁内读取账户（只在迁移旧配置时写入）
+fn read_store<T>(f: impl FnOnce(&AccountStore) -> T) -> Result<T, LauncherError> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = store_path()?;
+    let (store, migrated) = load_store(&path)?;
+    if migrated {
+        fs::write(&path, serde_json::to_string_pretty(&store)?)?;
+    }
+    Ok(f(&store))
+}
+
+/// 在存储锁内读取、修改并保存
+fn with_store<T>(f: impl FnOnce(&mut AccountStore) -> Result<T, LauncherError>) -> Result<T, LauncherError> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = store_path()?;
+    let (mut store, _) = load_store(&path)?;
+    let result = f(&mut store)?;
+    fs::write(&path, serde_json::to_string_pretty(&store)?)?;
+    Ok(result)
+}
+
+/// 默认账户写回配置中的用户名和 UUID，兼容只读取单个用户名的旧逻辑
+fn sync_config(account: Option<&Account>) -> Result<(), LauncherError> {
+    let mut cfg = config::load_config()?;
+    cfg.username = account.map(|a| a.username.clone());
+    cfg.uuid = account.map(|a| a.uuid.clone());
+    config::save_config(&cfg)
+}
+
+/// 列出所有账户
+pub fn get_accounts() -> Result<Vec<AccountInfo>, LauncherError> {
+    read_store(|store| store.accounts.iter().map(|a| store.info(a)).collect())
+}
+
+/// 添加账户；同一账户已存在时更新其信息
+///
+/// 离线账户只需用户名，UUID 按配置的策略生成；正版账户需要提供 UUID 和访问令牌。
+/// 没有默认账户时新账户成为默认账户。
+pub fn add_account(
+    username: String,
+    account_type: UserType,
+    uuid: Option<String>,
+    access_token: Option<String>,
+    xuid: Option<String>,
+) -> Result<AccountInfo, LauncherError> {
+    let mut account = match account_type {
+        UserType::Legacy => {
+            auth::validate_username(&username)?;
+            let uuid = auth::resolve_offline_uuid(&username)?;
+            new_offline_account(&username, uuid)
+        }
+        UserType::Msa | UserType::Mojang => {
+            let (Some(uuid), Some(token)) = (uuid, access_token) else {
+                return Err(LauncherError::Custom("正版账户需要提供 UUID 和访问令牌".to_string()));
+            };
+            if username.trim().is_empty() {
+                return Err(LauncherError::Custom("用户名不能为空".to_string()));
+            }
+            Account {
+                account_type,
+                access_token: Some(token),
+                xuid,
+                ..new_offline_account(&username, uuid)
+            }
+        }
+    };
+
+    let (info, became_active) = with_store(|store| {
+        let existing = store.accounts.iter_mut().find(|a| match account_type {
+            UserType::Legacy => a.account_type == UserType::Legacy && a.username == account.username,
+            _ => a.account_type != UserType::Legacy && a.uuid == account.uuid,
+        });
+        let account = match existing {
+            Some(existing) => {
+                account.id = existing.id.clone();
+                account.added_at = existing.added_at.clone();
+                *existing = account.clone();
+                account
+            }
+            None => {
+                store.accounts.push(account.clone());
+                account
+            }
+        };
+        let became_active = store.active().is_none();
+        if became_active {
+            store.active_account = Some(account.id.clone());
+        }
+        Ok((store.info(&account), became_active.then_some(account)))
+    })?;
+
+    if let Some(account) = became_active {
+        sync_config(Some(&account))?;
+    }
+    log::info!("已添加账户 {} ({})", info.username, info.account_type.as_str());
+    Ok(info)
+}
+
+/// 删除账户；删除默认账户时由剩余的第一个账户接替
+pub fn remove_account(account_id: &str) -> Result<(), LauncherError> {
+    let new_active = with_store(|store| {
+        let before = store.accounts.len();
+        store.accounts.retain(|a| a.id != account_id);
+        if store.accounts.len() == before {
+            return Err(LauncherError::Custom(format!("账户 {} 不存在", account_id)));
+        }
+        if store.active_account.as_deref() != Some(account_id) {
+            return Ok(None);
+        }
+        let next = store.accounts.first().cloned();
+        store.active_account = next.as_ref().map(|a| a.id.clone());
+        Ok(Some(next))
+    })?;
+
+    if let Some(next) = new_active {
+        sync_config(next.as_ref())?;
+    }
+    Ok(())
+}
+
+/// 设置默认账户
+pub fn set_active_account(account_id: &str) -> Result<AccountInfo, LauncherError> {
+    let (info, account) = with_store(|store| {
+        let account = store
+            .accounts
+            .iter()
+            .find(|a| a.id == account_id)
+            .cloned()
+            .ok_or_else(|| LauncherError::Custom(format!("账户 {} 不存在", account_id)))?;
+        store.active_account = Some(account.id.clone());
+        Ok((store.info(&account), account))
+    })?;
+    sync_config(Some(&account))?;
+    Ok(info)
+}
+
+/// 指定账户（未指定时为默认账户）的 ID 和认证信息
+pub fn account_session(account_id: Option<&str>) -> Result<(String, AuthSession), LauncherError> {
+    let account = read_store(|store| match account_id {
+        Some(id) => store.accounts.iter().find(|a| a.id == id).cloned(),
+        None => store.active().cloned(),
+    })?
+    .ok_or_else(|| match account_id {
+        Some(id) => LauncherError::Custom(format!("账户 {} 不存在", id)),
+        None => LauncherError::Custom("尚未添加账户".to_string()),
+    })?;
+
+    let session = match (account.account_type, account.access_token) {
+        (UserType::Legacy, _) | (_, None) => AuthSession::offline(&account.username, &account.uuid),
+        (user_type, Some(access_token)) => AuthSession {
+            username: account.username,
+            uuid: account.uuid,
+            access_token,
+            user_type,
+            xuid: account.xuid,
+            client_id: account.client_id,
+        },
+    };
+    Ok((account.id, session))
+}
+
+/// 指定用户名的启动会话：有同名正版账户时使用其令牌，否则按离线账户处理
+pub fn launch_session(username: &str) -> Result<AuthSession, LauncherError> {
+    let online = read_store(|store| {
+        // 默认账户优先，避免多个同名账户时选错
+        let candidates = store.active().into_iter().chain(store.accounts.iter());
+        candidates
+            .filter(|a| a.account_type != UserType::Legacy && a.username == username)
+            .find_map(|a| Some((a.clone(), a.access_token.clone()?)))
+    })?;
+
+    match online {
+        Some((account, access_token)) => Ok(AuthSession {
+            username: account.username,
+            uuid: account.uuid,
+            access_token,
+            user_type: account.account_type,
+            xuid: account.xuid,
+            client_id: account.client_id,
+        }),
+        None => auth::offline_session(username),
+    }
+}
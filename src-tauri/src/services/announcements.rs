@@ -0,0 +1,124 @@
+//! 启动器公告
+//!
+//! 从可配置的地址获取公告 JSON（启动器更新、镜像故障等），
+//! 成功后写入本地缓存，离线或获取失败时使用缓存。
+
+use crate::errors::LauncherError;
+use crate::services::{config, http_client, meta_cache};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// 默认公告源
+const DEFAULT_ANNOUNCEMENTS_URL: &str =
+    "https://raw.githubusercontent.com/JDBeWL/ar1s_launcher/main/announcements.json";
+
+const CACHE_KEY: &str = "announcements";
+
+/// 公告级别
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnouncementLevel {
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+/// 公告类别
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnouncementCategory {
+    #[default]
+    General,
+    /// 启动器更新
+    Update,
+    /// 镜像源故障
+    MirrorOutage,
+}
+
+/// 单条公告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Announcement {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub level: AnnouncementLevel,
+    #[serde(default)]
+    pub category: AnnouncementCategory,
+    #[serde(default)]
+    pub url: Option<String>,
+    /// RFC 3339 时间
+    #[serde(default)]
+    pub published_at: Option<String>,
+    /// 过期后不再显示（RFC 3339）
+    #[serde(default)]
+    pub expires_at: Option<String>,
+}
+
+/// 公告源文件格式
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AnnouncementFeed {
+    #[serde(default)]
+    announcements: Vec<Announcement>,
+}
+
+/// 本地缓存路径
+fn cache_path() -> Result<PathBuf, LauncherError> {
+    Ok(config::get_config_path()?.with_file_name("ar1s_announcements.json"))
+}
+
+fn load_cached_feed() -> Option<AnnouncementFeed> {
+    let content = fs::read_to_string(cache_path().ok()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+async fn fetch_feed() -> Result<AnnouncementFeed, LauncherError> {
+    let url = config::load_config()?
+        .announcements_url
+        .unwrap_or_else(|| DEFAULT_ANNOUNCEMENTS_URL.to_string());
+    let response = http_client::get_client().get(&url).send().await?;
+    if !response.status().is_success() {
+        return Err(LauncherError::Custom(format!(
+            "获取公告失败: HTTP {}",
+            response.status()
+        )));
+    }
+    let feed: AnnouncementFeed = response.json().await?;
+    if let Ok(path) = cache_path() {
+        if let Err(e) = fs::write(&path, serde_json::to_string_pretty(&feed)?) {
+            log::warn!("写入公告缓存失败: {}", e);
+        }
+    }
+    Ok(feed)
+}
+
+/// 是否已过期（无法解析的时间视为未过期）
+fn is_expired(announcement: &Announcement, now: chrono::DateTime<chrono::Utc>) -> bool {
+    announcement
+        .expires_at
+        .as_deref()
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .is_some_and(|t| t < now)
+}
+
+/// 获取当前有效的公告（网络失败时使用本地缓存）
+pub async fn get_announcements() -> Result<Vec<Announcement>, LauncherError> {
+    let feed = match meta_cache::get_or_fetch(CACHE_KEY, meta_cache::DEFAULT_TTL, fetch_feed).await {
+        Ok(feed) => feed,
+        Err(e) => {
+            log::warn!("获取公告失败，使用本地缓存: {}", e);
+            load_cached_feed().unwrap_or_default()
+        }
+    };
+
+    let now = chrono::Utc::now();
+    Ok(feed
+        .announcements
+        .into_iter()
+        .filter(|a| !is_expired(a, now))
+        .collect())
+}
@@ -0,0 +1,165 @@
+//! 资源文件回收
+//!
+//! 资源对象（`assets/objects/<前两位>/<哈希>`）按哈希在版本间共享，删除版本时不会一起删除，
+//! 长期使用后往往占据游戏目录的大部分空间。回收时读取每个游戏目录中已安装版本（含继承链）
+//! 使用的资源索引，删除不被任何索引引用的对象和不再使用的索引文件。
+
+use crate::errors::LauncherError;
+use crate::services::{config, paths, tasks, version_info};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// 回收结果；`dry_run` 为 true 时只统计，不删除
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetGcReport {
+    pub dry_run: bool,
+    /// 仍在使用的资源索引
+    pub referenced_indexes: Vec<String>,
+    /// 被引用的资源对象数
+    pub referenced_objects: usize,
+    /// 未被引用（已删除或将删除）的资源对象数
+    pub unreferenced_objects: usize,
+    /// 不再使用（已删除或将删除）的资源索引
+    pub unreferenced_indexes: Vec<String>,
+    /// 释放（或可释放）的字节数
+    pub freed_bytes: u64,
+    /// 无法确定引用关系等原因，未删除任何文件时的说明
+    pub warnings: Vec<String>,
+}
+
+/// 游戏目录中已安装版本使用的资源索引；存在无法解析的版本 JSON 时返回 Err(版本名)
+fn referenced_indexes(game_dir: &Path) -> Result<BTreeSet<String>, String> {
+    let versions_dir = game_dir.join("versions");
+    let mut indexes = BTreeSet::new();
+    let Ok(entries) = fs::read_dir(&versions_dir) else {
+        return Ok(indexes);
+    };
+    for entry in entries.flatten().filter(|e| e.path().is_dir()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !entry.path().join(format!("{}.json", name)).exists() {
+            continue;
+        }
+        let chain = version_info::load_chain(&versions_dir, &name);
+        if chain.is_empty() {
+            return Err(name);
+        }
+        // 与启动时一致：继承链中最近声明的 assetIndex，旧版本 JSON 只有 assets 字段
+        let index = chain
+            .iter()
+            .find_map(|v| v["assetIndex"]["id"].as_str())
+            .or_else(|| chain.iter().find_map(|v| v["assets"].as_str()));
+        if let Some(index) = index {
+            indexes.insert(index.to_string());
+        }
+    }
+    Ok(indexes)
+}
+
+/// 资源索引引用的全部对象哈希；索引文件缺失时返回空集合（对象会在启动前重新下载）
+fn index_objects(assets_dir: &Path, index: &str, objects: &mut HashSet<String>) -> Result<(), LauncherError> {
+    let path = assets_dir.join("indexes").join(format!("{}.json", index));
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(());
+    };
+    let json: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| LauncherError::Custom(format!("资源索引 {} 无法解析: {}", path.display(), e)))?;
+    if let Some(entries) = json["objects"].as_object() {
+        objects.extend(entries.values().filter_map(|o| o["hash"].as_str()).map(str::to_lowercase));
+    }
+    Ok(())
+}
+
+fn collect_game_dir(game_dir: &Path, dry_run: bool, report: &mut AssetGcReport) {
+    let assets_dir = game_dir.join("assets");
+    if !assets_dir.exists() {
+        return;
+    }
+    let indexes = match referenced_indexes(game_dir) {
+        Ok(indexes) => indexes,
+        Err(version) => {
+            report.warnings.push(format!(
+                "{} 中的版本 {} 的 JSON 无法解析，跳过该游戏目录",
+                game_dir.display(),
+                version
+            ));
+            return;
+        }
+    };
+    let mut referenced = HashSet::new();
+    for index in &indexes {
+        if let Err(e) = index_objects(&assets_dir, index, &mut referenced) {
+            report.warnings.push(format!("{}，跳过该游戏目录", e));
+            return;
+        }
+    }
+    report.referenced_objects += referenced.len();
+    report.referenced_indexes.extend(indexes.iter().cloned());
+
+    for prefix in fs::read_dir(assets_dir.join("objects")).into_iter().flatten().flatten() {
+        for object in fs::read_dir(prefix.path()).into_iter().flatten().flatten() {
+            let hash = object.file_name().to_string_lossy().to_lowercase();
+            if referenced.contains(&hash) || !object.path().is_file() {
+                continue;
+            }
+            let size = object.metadata().map(|m| m.len()).unwrap_or(0);
+            if dry_run || fs::remove_file(object.path()).is_ok() {
+                report.unreferenced_objects += 1;
+                report.freed_bytes += size;
+            }
+        }
+        if !dry_run {
+            // 只会删除已清空的前缀目录
+            let _ = fs::remove_dir(prefix.path());
+        }
+    }
+
+    for entry in fs::read_dir(assets_dir.join("indexes")).into_iter().flatten().flatten() {
+        let path = entry.path();
+        let Some(index) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_suffix(".json"))
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        if indexes.contains(&index) {
+            continue;
+        }
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        if dry_run || fs::remove_file(&path).is_ok() {
+            report.freed_bytes += size;
+            report.unreferenced_indexes.push(index);
+        }
+    }
+}
+
+/// 回收所有游戏目录中未被引用的资源文件
+///
+/// 正在下载或安装时拒绝删除（新下载的对象可能还没有对应的版本 JSON），只允许预览。
+pub fn collect_asset_garbage(dry_run: bool) -> Result<AssetGcReport, LauncherError> {
+    if !dry_run && !tasks::list_tasks().is_empty() {
+        return Err(LauncherError::Custom("有正在进行的下载或安装任务，请完成后再清理资源文件".to_string()));
+    }
+    let config = config::load_config()?;
+    let mut report = AssetGcReport {
+        dry_run,
+        ..Default::default()
+    };
+    for game_dir in paths::game_dirs(&config) {
+        collect_game_dir(&game_dir, dry_run, &mut report);
+    }
+    report.referenced_indexes.sort();
+    report.referenced_indexes.dedup();
+    log::info!(
+        "资源回收{}：{} 个未引用对象，{} 个未使用索引，{:.1} MB",
+        if dry_run { "预览" } else { "完成" },
+        report.unreferenced_objects,
+        report.unreferenced_indexes.len(),
+        report.freed_bytes as f64 / 1024.0 / 1024.0
+    );
+    Ok(report)
+}
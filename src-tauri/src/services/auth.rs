@@ -0,0 +1,198 @@
+//! 离线账户：用户名校验与 UUID 策略
+
+use crate::errors::{LauncherError, UsernameError};
+use crate::models::UuidStrategy;
+use crate::services::config::{load_config, save_config};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 用户名最短长度
+const MIN_USERNAME_LEN: usize = 3;
+/// 用户名最长长度
+const MAX_USERNAME_LEN: usize = 16;
+
+/// 账户类型，对应启动参数 `${user_type}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserType {
+    /// 微软账户
+    Msa,
+    /// 旧版 Mojang 账户
+    Mojang,
+    /// 离线账户
+    #[default]
+    Legacy,
+}
+
+impl UserType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Msa => "msa",
+            Self::Mojang => "mojang",
+            Self::Legacy => "legacy",
+        }
+    }
+}
+
+/// 启动游戏所需的认证信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthSession {
+    pub username: String,
+    pub uuid: String,
+    pub access_token: String,
+    pub user_type: UserType,
+    /// 微软账户的 Xbox 用户 ID
+    pub xuid: Option<String>,
+    /// 微软账户登录时使用的客户端 ID
+    pub client_id: Option<String>,
+}
+
+impl AuthSession {
+    /// 离线账户会话
+    pub fn offline(username: &str, uuid: &str) -> Self {
+        Self {
+            username: username.to_string(),
+            uuid: uuid.to_string(),
+            access_token: "0".to_string(),
+            user_type: UserType::Legacy,
+            xuid: None,
+            client_id: None,
+        }
+    }
+
+    /// 旧版 `${auth_session}` 参数值
+    pub fn legacy_session(&self) -> String {
+        match self.user_type {
+            UserType::Legacy => self.access_token.clone(),
+            _ => format!("token:{}:{}", self.access_token, self.uuid),
+        }
+    }
+}
+
+/// 为指定用户名构建离线启动会话（校验用户名并按策略获取 UUID）
+///
+/// 已保存的用户名只做基本检查，不符合严格规则时仅记录警告
+pub fn offline_session(username: &str) -> Result<AuthSession, LauncherError> {
+    validate_existing_username(username)?;
+    if let Err(e) = validate_username(username) {
+        log::warn!("用户名 {} 不符合正版用户名规则（{}），部分服务器可能拒绝登录", username, e);
+    }
+    let uuid = resolve_offline_uuid(username)?;
+    Ok(AuthSession::offline(username, &uuid))
+}
+
+/// 按 Mojang 规则校验新建的离线用户名：3-16 位，仅允许字母、数字和下划线
+pub fn validate_username(username: &str) -> Result<(), UsernameError> {
+    if username.is_empty() {
+        return Err(UsernameError::Empty);
+    }
+    let len = username.chars().count();
+    if len < MIN_USERNAME_LEN {
+        return Err(UsernameError::TooShort(MIN_USERNAME_LEN));
+    }
+    if len > MAX_USERNAME_LEN {
+        return Err(UsernameError::TooLong(MAX_USERNAME_LEN));
+    }
+    if let Some(c) = username
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '_'))
+    {
+        return Err(UsernameError::InvalidChar(c));
+    }
+    Ok(())
+}
+
+/// 已保存用户名的基本校验：不能为空，不能含空白或控制字符（会破坏启动参数）
+///
+/// 旧版本允许中文等非 ASCII 用户名，这些名称继续可用；严格规则只用于新建用户名
+pub fn validate_existing_username(username: &str) -> Result<(), UsernameError> {
+    if username.trim().is_empty() {
+        return Err(UsernameError::Empty);
+    }
+    if let Some(c) = username.chars().find(|c| c.is_whitespace() || c.is_control()) {
+        return Err(UsernameError::UnsafeChar(c));
+    }
+    Ok(())
+}
+
+/// 修改保存的用户名时的校验：与当前保存的名称相同时只做基本检查，新名称必须符合严格规则
+pub fn validate_username_change(saved: Option<&str>, username: &str) -> Result<(), UsernameError> {
+    if saved == Some(username) {
+        validate_existing_username(username)
+    } else {
+        validate_username(username)
+    }
+}
+
+/// 标准离线 UUID：UUID v3 (MD5) 基于 "OfflinePlayer:{username}"
+pub fn standard_offline_uuid(username: &str) -> String {
+    Uuid::new_v3(
+        &Uuid::NAMESPACE_DNS,
+        format!("OfflinePlayer:{}", username).as_bytes(),
+    )
+    .to_string()
+}
+
+/// 按配置的策略获取离线 UUID
+///
+/// - `Standard`：始终使用标准算法
+/// - `Preserve`：优先使用该用户名之前使用过的 UUID，首次使用时生成标准 UUID 并记录
+pub fn resolve_offline_uuid(username: &str) -> Result<String, LauncherError> {
+    let mut config = load_config()?;
+
+    let uuid = match config.uuid_strategy {
+        UuidStrategy::Standard => standard_offline_uuid(username),
+        UuidStrategy::Preserve => {
+            // 兼容旧配置：只保存了单个用户名和 UUID
+            let legacy = match (&config.username, &config.uuid) {
+                (Some(name), Some(uuid)) if name == username => Some(uuid.clone()),
+                _ => None,
+            };
+            config
+                .offline_uuids
+                .get(username)
+                .cloned()
+                .or(legacy)
+                .unwrap_or_else(|| standard_offline_uuid(username))
+        }
+    };
+
+    if config.offline_uuids.get(username) != Some(&uuid) {
+        config.offline_uuids.insert(username.to_string(), uuid.clone());
+        save_config(&config)?;
+    }
+    Ok(uuid)
+}
+
+/// 重新生成指定用户名的离线 UUID（恢复为标准算法结果）
+pub fn regenerate_offline_uuid(username: &str) -> Result<String, LauncherError> {
+    validate_existing_username(username)?;
+
+    let mut config = load_config()?;
+    let uuid = standard_offline_uuid(username);
+    config.offline_uuids.insert(username.to_string(), uuid.clone());
+    if config.username.as_deref() == Some(username) {
+        config.uuid = Some(uuid.clone());
+    }
+    save_config(&config)?;
+    Ok(uuid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unchanged_saved_name_uses_lenient_rule() {
+        assert!(validate_username_change(Some("玩家一"), "玩家一").is_ok());
+        assert!(validate_username_change(Some("a b"), "a b").is_err());
+    }
+
+    #[test]
+    fn new_name_uses_strict_rule() {
+        assert!(validate_username_change(Some("玩家一"), "Steve_01").is_ok());
+        assert!(validate_username_change(Some("Steve"), "玩家一").is_err());
+        assert!(validate_username_change(None, "ab").is_err());
+    }
+}
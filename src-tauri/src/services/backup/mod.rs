@@ -0,0 +1,225 @@
+//! 存档备份
+//!
+//! 将实例存档打包为 zip（可选 AES-256 加密），上传到配置的备份位置
+//! （本地目录、WebDAV 或 S3 兼容存储），并支持从备份位置恢复。
+
+mod s3;
+mod target;
+
+use crate::errors::LauncherError;
+use crate::models::BackupTarget;
+use crate::services::config;
+use crate::services::paths::{self, ContentKind};
+use crate::utils::long_path;
+use serde::Serialize;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use zip::write::{FileOptions, SimpleFileOptions};
+
+/// 探测文件名，用于测试备份位置的读写权限
+const PROBE_FILE: &str = ".ar1s_backup_probe";
+
+/// 备份文件信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub name: String,
+    pub size: u64,
+}
+
+/// 当前配置的备份位置，未配置时使用游戏目录下的 backups
+fn configured_target() -> Result<BackupTarget, LauncherError> {
+    Ok(config::load_config()?
+        .backup_target
+        .unwrap_or(BackupTarget::Local { path: None }))
+}
+
+/// 临时文件目录
+fn temp_dir() -> Result<PathBuf, LauncherError> {
+    let config = config::load_config()?;
+    let dir = Path::new(&config.game_dir).join("backups").join(".tmp");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn saves_dir(instance_name: &str) -> Result<PathBuf, LauncherError> {
+    let config = config::load_config()?;
+    Ok(paths::content_dir(&config, instance_name, ContentKind::Saves))
+}
+
+/// 递归写入目录到 zip
+fn zip_dir(
+    zip: &mut zip::ZipWriter<fs::File>,
+    dir: &Path,
+    prefix: &str,
+    options: FileOptions<'_, ()>,
+) -> Result<(), LauncherError> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = format!("{}/{}", prefix, entry.file_name().to_string_lossy());
+        if path.is_dir() {
+            zip.add_directory(name.as_str(), options)?;
+            zip_dir(zip, &path, &name, options)?;
+        } else {
+            // 游戏运行时 session.lock 被独占，跳过即可
+            if entry.file_name() == "session.lock" {
+                continue;
+            }
+            zip.start_file(name.as_str(), options)?;
+            io::copy(&mut fs::File::open(&path)?, zip)?;
+        }
+    }
+    Ok(())
+}
+
+/// 备份指定实例的存档，`password` 非空时使用 AES-256 加密
+pub async fn backup_world(
+    instance_name: String,
+    world_name: String,
+    password: Option<String>,
+) -> Result<BackupInfo, LauncherError> {
+    let world_dir = saves_dir(&instance_name)?.join(&world_name);
+    if !world_dir.is_dir() {
+        return Err(LauncherError::Custom(format!("存档 {} 不存在", world_name)));
+    }
+
+    let name = format!(
+        "{}_{}_{}.zip",
+        instance_name,
+        world_name,
+        chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+    );
+    let temp_path = temp_dir()?.join(&name);
+    let password = password.filter(|p| !p.is_empty());
+
+    let zip_path = temp_path.clone();
+    tokio::task::spawn_blocking(move || -> Result<(), LauncherError> {
+        let mut zip = zip::ZipWriter::new(fs::File::create(&zip_path)?);
+        let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        let options = match password.as_deref() {
+            Some(pw) => options.with_aes_encryption(zip::AesMode::Aes256, pw),
+            None => options,
+        };
+        zip.add_directory(world_name.as_str(), options)?;
+        zip_dir(&mut zip, &long_path::extended(&world_dir), &world_name, options)?;
+        zip.finish()?.flush()?;
+        Ok(())
+    })
+    .await??;
+
+    let size = fs::metadata(&temp_path)?.len();
+    let result = configured_target()?.upload(&name, &temp_path).await;
+    let _ = fs::remove_file(&temp_path);
+    result?;
+
+    log::info!("存档备份完成: {} ({} 字节)", name, size);
+    Ok(BackupInfo { name, size })
+}
+
+/// 列出备份位置中的备份
+pub async fn list_backups() -> Result<Vec<BackupInfo>, LauncherError> {
+    configured_target()?.list().await
+}
+
+/// 从备份位置恢复存档；解压成功后才替换，同名存档会先重命名保留
+pub async fn restore_world_backup(
+    instance_name: String,
+    backup_name: String,
+    password: Option<String>,
+) -> Result<(), LauncherError> {
+    if backup_name.contains(['/', '\\']) || backup_name.contains("..") {
+        return Err(LauncherError::Custom("无效的备份名称".to_string()));
+    }
+    let temp_path = temp_dir()?.join(&backup_name);
+    configured_target()?.download(&backup_name, &temp_path).await?;
+
+    let saves = saves_dir(&instance_name)?;
+    let password = password.filter(|p| !p.is_empty());
+    let zip_path = temp_path.clone();
+    let result = tokio::task::spawn_blocking(move || extract_backup(&zip_path, &saves, password.as_deref())).await;
+    let _ = fs::remove_file(&temp_path);
+    result??;
+
+    log::info!("已从 {} 恢复存档到实例 {}", backup_name, instance_name);
+    Ok(())
+}
+
+/// 先解压到存档目录下的临时目录，全部成功（密码正确、压缩包完整）后才替换存档
+fn extract_backup(zip_path: &Path, saves: &Path, password: Option<&str>) -> Result<(), LauncherError> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(zip_path)?)?;
+
+    // 备份根目录即存档名
+    let world_name = (0..archive.len())
+        .filter_map(|i| archive.name_for_index(i))
+        .find_map(|n| n.split('/').next().filter(|s| !s.is_empty()).map(String::from))
+        .ok_or_else(|| LauncherError::Custom("备份文件为空".to_string()))?;
+
+    let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+    let staging = saves.join(format!(".{}_restoring_{}", world_name, timestamp));
+    let result = extract_entries(&mut archive, &staging, &world_name, password);
+    let extracted = staging.join(&world_name);
+    if let Err(e) = result.and_then(|_| {
+        if extracted.is_dir() {
+            Ok(())
+        } else {
+            Err(LauncherError::Custom("备份中没有存档目录".to_string()))
+        }
+    }) {
+        let _ = fs::remove_dir_all(long_path::extended(&staging));
+        return Err(e);
+    }
+
+    let world_dir = saves.join(&world_name);
+    if world_dir.exists() {
+        let aside = saves.join(format!("{}_before_restore_{}", world_name, timestamp));
+        fs::rename(&world_dir, &aside)?;
+        log::info!("已有存档已移至 {}", aside.display());
+    }
+    fs::rename(&extracted, &world_dir)?;
+    let _ = fs::remove_dir_all(long_path::extended(&staging));
+    Ok(())
+}
+
+/// 解压存档目录下的条目到 `dest`
+fn extract_entries(
+    archive: &mut zip::ZipArchive<fs::File>,
+    dest: &Path,
+    world_name: &str,
+    password: Option<&str>,
+) -> Result<(), LauncherError> {
+    for i in 0..archive.len() {
+        let mut file = match password {
+            Some(pw) => archive.by_index_decrypt(i, pw.as_bytes())?,
+            None => archive.by_index(i)?,
+        };
+        let Some(rel) = file.enclosed_name() else {
+            continue;
+        };
+        if !rel.starts_with(world_name) {
+            continue;
+        }
+        let out = long_path::extended(&dest.join(rel));
+        if file.is_dir() {
+            fs::create_dir_all(&out)?;
+            continue;
+        }
+        if let Some(parent) = out.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        io::copy(&mut file, &mut fs::File::create(&out)?)?;
+    }
+    Ok(())
+}
+
+/// 测试备份位置：写入、列出并删除一个探测文件
+pub async fn test_backup_target(target: BackupTarget) -> Result<(), LauncherError> {
+    let probe = temp_dir()?.join(PROBE_FILE);
+    fs::write(&probe, b"ar1s")?;
+    let result = target.upload(PROBE_FILE, &probe).await;
+    let _ = fs::remove_file(&probe);
+    result?;
+    target.list().await?;
+    target.delete(PROBE_FILE).await
+}
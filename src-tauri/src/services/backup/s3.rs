@@ -0,0 +1,132 @@
+//! S3 兼容存储的 AWS Signature V4 签名（路径风格访问）
+
+use crate::errors::LauncherError;
+use hmac::{Hmac, Mac};
+use reqwest::header::CONTENT_LENGTH;
+use reqwest::{Body, Client, Method, RequestBuilder, Url};
+use sha2::{Digest, Sha256};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// S3 连接参数
+pub struct S3Config<'a> {
+    pub endpoint: &'a str,
+    pub bucket: &'a str,
+    pub region: &'a str,
+    pub access_key: &'a str,
+    pub secret_key: &'a str,
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC 接受任意长度的密钥");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// RFC 3986 编码，`keep_slash` 为 true 时保留路径分隔符
+pub(super) fn uri_encode(s: &str, keep_slash: bool) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            b'/' if keep_slash => "/".to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// 不对请求体签名时使用的载荷哈希（请求体为流时无法预先计算哈希）
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// 构建带签名的请求；`query` 需按参数名排序
+pub fn signed_request(
+    client: &Client,
+    cfg: &S3Config<'_>,
+    method: Method,
+    key: &str,
+    query: &[(&str, &str)],
+    body: Vec<u8>,
+) -> Result<RequestBuilder, LauncherError> {
+    let payload_hash = hex(&Sha256::digest(&body));
+    Ok(sign(client, cfg, method, key, query, &payload_hash)?.body(body))
+}
+
+/// 构建流式上传的 PUT 请求（请求体不参与签名，需提供长度）
+pub fn signed_upload(
+    client: &Client,
+    cfg: &S3Config<'_>,
+    key: &str,
+    body: Body,
+    content_length: u64,
+) -> Result<RequestBuilder, LauncherError> {
+    Ok(sign(client, cfg, Method::PUT, key, &[], UNSIGNED_PAYLOAD)?
+        .header(CONTENT_LENGTH, content_length)
+        .body(body))
+}
+
+fn sign(
+    client: &Client,
+    cfg: &S3Config<'_>,
+    method: Method,
+    key: &str,
+    query: &[(&str, &str)],
+    payload_hash: &str,
+) -> Result<RequestBuilder, LauncherError> {
+    let canonical_uri = format!("/{}/{}", uri_encode(cfg.bucket, false), uri_encode(key, true));
+    let canonical_query = query
+        .iter()
+        .map(|(k, v)| format!("{}={}", uri_encode(k, false), uri_encode(v, false)))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    let base = Url::parse(cfg.endpoint)
+        .map_err(|e| LauncherError::Custom(format!("S3 地址无效: {}", e)))?;
+    let host = match (base.host_str(), base.port()) {
+        (Some(h), Some(p)) => format!("{}:{}", h, p),
+        (Some(h), None) => h.to_string(),
+        _ => return Err(LauncherError::Custom("S3 地址缺少主机名".to_string())),
+    };
+
+    let now = chrono::Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+        method, canonical_uri, canonical_query, host, payload_hash, amz_date, payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, cfg.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let k_date = hmac(format!("AWS4{}", cfg.secret_key).as_bytes(), &date);
+    let k_region = hmac(&k_date, cfg.region);
+    let k_service = hmac(&k_region, "s3");
+    let k_signing = hmac(&k_service, "aws4_request");
+    let signature = hex(&hmac(&k_signing, &string_to_sign));
+
+    let mut url = format!("{}{}", cfg.endpoint.trim_end_matches('/'), canonical_uri);
+    if !canonical_query.is_empty() {
+        url.push('?');
+        url.push_str(&canonical_query);
+    }
+
+    Ok(client
+        .request(method, url)
+        .header("x-amz-date", amz_date)
+        .header("x-amz-content-sha256", payload_hash)
+        .header(
+            "Authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+                cfg.access_key, scope, signature
+            ),
+        ))
+}
@@ -0,0 +1,289 @@
+//! 备份存储位置：本地目录、WebDAV、S3 兼容存储
+
+use super::s3::{self, S3Config};
+use super::BackupInfo;
+use crate::errors::LauncherError;
+use crate::models::BackupTarget;
+use crate::services::http_client;
+use regex::Regex;
+use reqwest::header::CONTENT_LENGTH;
+use reqwest::{Body, Method, RequestBuilder, Response};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use tokio::io::AsyncWriteExt;
+use tokio_util::io::ReaderStream;
+
+/// 备份文件较大，上传下载使用更长的超时
+const TRANSFER_TIMEOUT_SECS: u64 = 30 * 60;
+
+static WEBDAV_RESPONSE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<(?:\w+:)?response\b.*?</(?:\w+:)?response>").unwrap());
+static WEBDAV_HREF: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<(?:\w+:)?href>([^<]+)</(?:\w+:)?href>").unwrap());
+static WEBDAV_LENGTH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"<(?:\w+:)?getcontentlength>(\d+)</(?:\w+:)?getcontentlength>").unwrap()
+});
+static S3_CONTENTS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<Contents>.*?</Contents>").unwrap());
+static S3_KEY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<Key>([^<]+)</Key>").unwrap());
+static S3_SIZE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<Size>(\d+)</Size>").unwrap());
+
+/// 默认本地备份目录
+fn default_local_dir() -> Result<PathBuf, LauncherError> {
+    let config = crate::services::config::load_config()?;
+    Ok(Path::new(&config.game_dir).join("backups"))
+}
+
+fn local_dir(path: &Option<String>) -> Result<PathBuf, LauncherError> {
+    match path.as_deref().filter(|p| !p.trim().is_empty()) {
+        Some(p) => Ok(PathBuf::from(p)),
+        None => default_local_dir(),
+    }
+}
+
+fn webdav_url(base: &str, name: &str) -> String {
+    format!("{}/{}", base.trim_end_matches('/'), s3::uri_encode(name, false))
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(b) = s.get(i + 1..i + 3).and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn with_auth(request: RequestBuilder, username: &Option<String>, password: &Option<String>) -> RequestBuilder {
+    match username {
+        Some(user) => request.basic_auth(user, password.as_ref()),
+        None => request,
+    }
+}
+
+fn s3_key(prefix: &str, name: &str) -> String {
+    let prefix = prefix.trim_matches('/');
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", prefix, name)
+    }
+}
+
+/// 以流的方式读取本地文件作为请求体，避免把整个备份读入内存
+async fn file_body(file: &Path) -> Result<(Body, u64), LauncherError> {
+    let file = tokio::fs::File::open(file).await?;
+    let length = file.metadata().await?.len();
+    Ok((Body::wrap_stream(ReaderStream::new(file)), length))
+}
+
+async fn check_status(response: Response, action: &str) -> Result<Response, LauncherError> {
+    if response.status().is_success() {
+        Ok(response)
+    } else {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        Err(LauncherError::Custom(format!(
+            "{}失败: HTTP {} {}",
+            action,
+            status,
+            body.chars().take(200).collect::<String>()
+        )))
+    }
+}
+
+impl BackupTarget {
+    fn s3_config(&self) -> Option<(S3Config<'_>, &str)> {
+        match self {
+            BackupTarget::S3 {
+                endpoint,
+                bucket,
+                region,
+                access_key,
+                secret_key,
+                prefix,
+            } => Some((
+                S3Config {
+                    endpoint,
+                    bucket,
+                    region,
+                    access_key,
+                    secret_key,
+                },
+                prefix.as_str(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// 上传备份文件
+    pub async fn upload(&self, name: &str, file: &Path) -> Result<(), LauncherError> {
+        match self {
+            BackupTarget::Local { path } => {
+                let dir = local_dir(path)?;
+                fs::create_dir_all(&dir)?;
+                let dest = dir.join(name);
+                if dest != file {
+                    tokio::fs::copy(file, dest).await?;
+                }
+                Ok(())
+            }
+            BackupTarget::WebDav { url, username, password } => {
+                let client = http_client::create_client_with_timeout(TRANSFER_TIMEOUT_SECS);
+                let (body, length) = file_body(file).await?;
+                let request = client
+                    .put(webdav_url(url, name))
+                    .header(CONTENT_LENGTH, length)
+                    .body(body);
+                check_status(with_auth(request, username, password).send().await?, "上传备份").await?;
+                Ok(())
+            }
+            BackupTarget::S3 { .. } => {
+                let (cfg, prefix) = self.s3_config().expect("S3 目标");
+                let client = http_client::create_client_with_timeout(TRANSFER_TIMEOUT_SECS);
+                let (body, length) = file_body(file).await?;
+                let request = s3::signed_upload(&client, &cfg, &s3_key(prefix, name), body, length)?;
+                check_status(request.send().await?, "上传备份").await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// 下载备份文件到本地路径
+    pub async fn download(&self, name: &str, dest: &Path) -> Result<(), LauncherError> {
+        let response = match self {
+            BackupTarget::Local { path } => {
+                tokio::fs::copy(local_dir(path)?.join(name), dest).await?;
+                return Ok(());
+            }
+            BackupTarget::WebDav { url, username, password } => {
+                let client = http_client::create_client_with_timeout(TRANSFER_TIMEOUT_SECS);
+                with_auth(client.get(webdav_url(url, name)), username, password)
+                    .send()
+                    .await?
+            }
+            BackupTarget::S3 { .. } => {
+                let (cfg, prefix) = self.s3_config().expect("S3 目标");
+                let client = http_client::create_client_with_timeout(TRANSFER_TIMEOUT_SECS);
+                s3::signed_request(&client, &cfg, Method::GET, &s3_key(prefix, name), &[], Vec::new())?
+                    .send()
+                    .await?
+            }
+        };
+        let mut response = check_status(response, "下载备份").await?;
+        let mut out = tokio::fs::File::create(dest).await?;
+        while let Some(chunk) = response.chunk().await? {
+            out.write_all(&chunk).await?;
+        }
+        out.flush().await?;
+        Ok(())
+    }
+
+    /// 删除备份文件
+    pub async fn delete(&self, name: &str) -> Result<(), LauncherError> {
+        match self {
+            BackupTarget::Local { path } => {
+                fs::remove_file(local_dir(path)?.join(name))?;
+            }
+            BackupTarget::WebDav { url, username, password } => {
+                let request = http_client::get_client().delete(webdav_url(url, name));
+                check_status(with_auth(request, username, password).send().await?, "删除备份").await?;
+            }
+            BackupTarget::S3 { .. } => {
+                let (cfg, prefix) = self.s3_config().expect("S3 目标");
+                let request = s3::signed_request(
+                    &http_client::get_client(),
+                    &cfg,
+                    Method::DELETE,
+                    &s3_key(prefix, name),
+                    &[],
+                    Vec::new(),
+                )?;
+                check_status(request.send().await?, "删除备份").await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 列出备份文件（仅 .zip）
+    pub async fn list(&self) -> Result<Vec<BackupInfo>, LauncherError> {
+        let mut backups = match self {
+            BackupTarget::Local { path } => {
+                let dir = local_dir(path)?;
+                let Ok(entries) = fs::read_dir(&dir) else {
+                    return Ok(Vec::new());
+                };
+                entries
+                    .flatten()
+                    .filter_map(|e| {
+                        let meta = e.metadata().ok()?;
+                        meta.is_file().then(|| BackupInfo {
+                            name: e.file_name().to_string_lossy().to_string(),
+                            size: meta.len(),
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            }
+            BackupTarget::WebDav { url, username, password } => {
+                let request = http_client::get_client()
+                    .request(Method::from_bytes(b"PROPFIND").expect("合法的 HTTP 方法"), url.as_str())
+                    .header("Depth", "1");
+                let body = check_status(with_auth(request, username, password).send().await?, "列出备份")
+                    .await?
+                    .text()
+                    .await?;
+                WEBDAV_RESPONSE
+                    .find_iter(&body)
+                    .filter_map(|block| {
+                        let block = block.as_str();
+                        let href = WEBDAV_HREF.captures(block)?.get(1)?.as_str();
+                        let name = percent_decode(href.trim_end_matches('/').rsplit('/').next()?);
+                        let size = WEBDAV_LENGTH
+                            .captures(block)
+                            .and_then(|c| c[1].parse().ok())
+                            .unwrap_or(0);
+                        Some(BackupInfo { name, size })
+                    })
+                    .collect()
+            }
+            BackupTarget::S3 { .. } => {
+                let (cfg, prefix) = self.s3_config().expect("S3 目标");
+                let list_prefix = s3_key(prefix, "");
+                let request = s3::signed_request(
+                    &http_client::get_client(),
+                    &cfg,
+                    Method::GET,
+                    "",
+                    &[("list-type", "2"), ("prefix", &list_prefix)],
+                    Vec::new(),
+                )?;
+                let body = check_status(request.send().await?, "列出备份").await?.text().await?;
+                S3_CONTENTS
+                    .find_iter(&body)
+                    .filter_map(|block| {
+                        let block = block.as_str();
+                        let key = S3_KEY.captures(block)?.get(1)?.as_str();
+                        let size = S3_SIZE.captures(block).and_then(|c| c[1].parse().ok()).unwrap_or(0);
+                        Some(BackupInfo {
+                            name: key.rsplit('/').next()?.to_string(),
+                            size,
+                        })
+                    })
+                    .collect()
+            }
+        };
+        backups.retain(|b| b.name.ends_with(".zip"));
+        backups.sort_by(|a, b| b.name.cmp(&a.name));
+        Ok(backups)
+    }
+}
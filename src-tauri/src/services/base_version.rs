@@ -0,0 +1,157 @@
+//! 实例基础版本校验
+//!
+//! 部分实例 JSON 继承自已下架的快照或其他启动器生成的自定义 id，这些版本不在官方清单中，
+//! 下载时只会得到含糊的失败。这里检查继承链的根版本能否从官方清单安装，
+//! 不能时给出最接近的官方版本，并支持将实例改为继承所选的官方版本。
+
+use crate::errors::LauncherError;
+use crate::models::VersionManifest;
+use crate::services::{config, download, instance_lock, paths, version_index, version_info};
+use crate::services::instance_lock::InstanceOperation;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::sync::LazyLock;
+
+/// 返回的候选版本数量
+const MAX_SUGGESTIONS: usize = 5;
+
+static RELEASE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\d+\.\d+(?:\.\d+)?").expect("版本号正则"));
+
+/// 实例基础版本的检查结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseVersionCheck {
+    pub instance_name: String,
+    /// 继承链的根版本（原版版本 id）
+    pub base_version: Option<String>,
+    /// 根版本 JSON 是否已在本地
+    pub installed: bool,
+    /// 根版本是否在官方清单中
+    pub official: bool,
+    /// 官方清单中的版本类型（release / snapshot / old_beta / old_alpha）
+    pub version_type: Option<String>,
+    /// 不在官方清单中时，最接近的官方版本
+    pub suggestions: Vec<String>,
+}
+
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.chars().zip(b.chars()).take_while(|(x, y)| x == y).count()
+}
+
+/// 按相似度给出最接近的官方版本：优先匹配 id 中包含的正式版本号，正式版优先于快照
+pub fn closest_versions(manifest: &VersionManifest, version: &str) -> Vec<String> {
+    let key = RELEASE_RE.find(version).map(|m| m.as_str()).unwrap_or(version);
+    let mut scored: Vec<(usize, &str)> = manifest
+        .versions
+        .iter()
+        .filter_map(|v| {
+            let prefix = common_prefix_len(&v.id, key);
+            if prefix < 3 {
+                return None;
+            }
+            let exact = if v.id == key { 100 } else { 0 };
+            let release = usize::from(v.version_type == "release");
+            Some((exact + prefix * 2 + release, v.id.as_str()))
+        })
+        .collect();
+    // 分数相同时保持清单顺序（新版本在前）
+    scored.sort_by(|a, b| b.0.cmp(&a.0));
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, id)| id.to_string())
+        .collect()
+}
+
+/// 继承链的根版本 id，以及根版本 JSON 是否已在本地（父版本缺失时为 false）
+fn chain_root(chain: &[Value]) -> (Option<String>, bool) {
+    let Some(last) = chain.last() else {
+        return (None, false);
+    };
+    match last["inheritsFrom"].as_str() {
+        Some(parent) => (Some(parent.to_string()), false),
+        None => (last["id"].as_str().map(String::from), true),
+    }
+}
+
+/// 检查实例继承的基础版本能否从官方清单安装
+pub async fn check_base_version(instance_name: &str) -> Result<BaseVersionCheck, LauncherError> {
+    let config = config::load_config()?;
+    let versions_dir = paths::instance_game_dir(&config, instance_name).join("versions");
+    let chain = version_info::load_chain(&versions_dir, instance_name);
+    if chain.is_empty() {
+        return Err(LauncherError::Custom(format!("实例 '{}' 不存在", instance_name)));
+    }
+    let (base_version, installed) = chain_root(&chain);
+
+    let manifest = download::get_versions().await?;
+    let official = base_version
+        .as_deref()
+        .and_then(|base| manifest.versions.iter().find(|v| v.id == base));
+    let suggestions = match (&base_version, official) {
+        (Some(base), None) => closest_versions(&manifest, base),
+        _ => Vec::new(),
+    };
+
+    Ok(BaseVersionCheck {
+        instance_name: instance_name.to_string(),
+        installed,
+        official: official.is_some(),
+        version_type: official.map(|v| v.version_type.clone()),
+        base_version,
+        suggestions,
+    })
+}
+
+/// 将实例改为继承指定的官方版本（替换继承链中缺失或非官方的根版本）
+pub async fn rebase_instance(instance_name: &str, new_base: &str) -> Result<(), LauncherError> {
+    let _lock = instance_lock::acquire(instance_name, InstanceOperation::Install)?;
+    let manifest = download::get_versions().await?;
+    if !manifest.versions.iter().any(|v| v.id == new_base) {
+        return Err(LauncherError::VersionNotInstallable {
+            version: new_base.to_string(),
+            required_by: None,
+            suggestions: closest_versions(&manifest, new_base),
+        });
+    }
+
+    let config = config::load_config()?;
+    let versions_dir = paths::instance_game_dir(&config, instance_name).join("versions");
+    let chain = version_info::load_chain(&versions_dir, instance_name);
+    // 修改继承链中最后一个能读取的、带 inheritsFrom 的 JSON
+    let Some(target) = chain.iter().rev().find(|j| j["inheritsFrom"].is_string()) else {
+        return Err(LauncherError::Custom(format!(
+            "实例 '{}' 没有继承其他版本，无需修改",
+            instance_name
+        )));
+    };
+    let target_id = target["id"]
+        .as_str()
+        .ok_or_else(|| LauncherError::Custom("版本 JSON 缺少 id".to_string()))?
+        .to_string();
+    let old_base = target["inheritsFrom"].as_str().unwrap_or_default().to_string();
+
+    let json_path = versions_dir.join(&target_id).join(format!("{}.json", target_id));
+    let mut json: Value = serde_json::from_str(&fs::read_to_string(&json_path)?)?;
+    json["inheritsFrom"] = Value::String(new_base.to_string());
+    // jar / clientVersion 指向旧基础版本时一并修改，否则会找不到客户端 JAR
+    for field in ["jar", "clientVersion"] {
+        if json[field].as_str() == Some(old_base.as_str()) {
+            json[field] = Value::String(new_base.to_string());
+        }
+    }
+    fs::write(&json_path, serde_json::to_string_pretty(&json)?)?;
+    version_index::invalidate(&versions_dir, instance_name);
+    version_index::invalidate(&versions_dir, &target_id);
+
+    log::info!(
+        "实例 {} 的基础版本已从 {} 改为 {}",
+        instance_name,
+        old_base,
+        new_base
+    );
+    Ok(())
+}
@@ -1,12 +1,14 @@
 use std::fs;
 use std::path::PathBuf;
-use std::sync::RwLock;
+use std::sync::{Mutex, RwLock};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use sysinfo::System;
-use tauri::Emitter;
 
 use crate::errors::LauncherError;
-use crate::models::{GameConfig, GameDirInfo};
+use crate::services::event_sink::EventSink;
+use crate::models::{GameConfig, GameDirInfo, LifecycleHooks};
+use crate::services::config_history;
 use crate::services::memory::{
     auto_set_memory_if_enabled, get_memory_warning_message, get_system_memory,
     is_memory_setting_safe, recommend_memory_for_game, AutoMemoryConfig, MemoryRecommendation,
@@ -20,6 +22,28 @@ static CONFIG_CACHE: std::sync::LazyLock<RwLock<Option<GameConfig>>> =
 // 标记配置是否已预加载
 static CONFIG_PRELOADED: AtomicBool = AtomicBool::new(false);
 
+// 启动器最近一次读取或写入的配置文件内容，用于识别外部编辑
+static LAST_SYNCED: Mutex<Option<String>> = Mutex::new(None);
+
+/// 配置文件轮询间隔
+const CONFIG_WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+fn set_last_synced(content: &str) {
+    if let Ok(mut last) = LAST_SYNCED.lock() {
+        *last = Some(content.to_string());
+    }
+}
+
+/// 磁盘上的配置文件已被外部修改（与启动器最近读写的内容不同）
+fn external_content() -> Option<String> {
+    let content = fs::read_to_string(get_config_path().ok()?).ok()?;
+    let last = LAST_SYNCED.lock().ok()?;
+    match last.as_deref() {
+        Some(synced) if synced != content => Some(content),
+        _ => None,
+    }
+}
+
 /// 预加载配置（应在应用启动时调用）
 /// 这会立即加载配置到缓存，避免后续的锁竞争
 pub fn preload_config() -> Result<(), LauncherError> {
@@ -57,6 +81,7 @@ pub async fn get_saved_username() -> Result<Option<String>, LauncherError> {
 // 设置保存的用户名
 pub async fn set_saved_username(username: String) -> Result<(), LauncherError> {
     let mut config = load_config()?;
+    crate::services::auth::validate_username_change(config.username.as_deref(), &username)?;
     config.username = Some(username);
     save_config(&config)?;
     Ok(())
@@ -105,7 +130,10 @@ fn load_config_internal() -> Result<GameConfig, LauncherError> {
         let content = fs::read_to_string(&config_path)?;
         // 如果配置文件内容为空或损坏，自动备份并重建默认配置
         match serde_json::from_str::<GameConfig>(&content) {
-            Ok(config) => Ok(config),
+            Ok(config) => {
+                set_last_synced(&content);
+                Ok(config)
+            }
             Err(_) => {
                 // 备份损坏的配置文件
                 let backup_path = config_path.with_extension("bak");
@@ -121,14 +149,51 @@ fn load_config_internal() -> Result<GameConfig, LauncherError> {
     }
 }
 
-/// 创建默认配置
-fn create_default_config(is_first_run: bool) -> Result<GameConfig, LauncherError> {
+/// 获取启动器数据目录（配置文件和默认游戏目录所在位置）
+/// 默认为可执行文件所在目录
+#[cfg(not(target_os = "macos"))]
+fn get_launcher_data_dir() -> Result<PathBuf, LauncherError> {
+    let exe_path = std::env::current_exe()?;
+    let exe_dir = exe_path
+        .parent()
+        .ok_or_else(|| LauncherError::Custom("无法获取可执行文件目录".to_string()))?;
+
+    Ok(exe_dir.to_path_buf())
+}
+
+/// 获取启动器数据目录（配置文件和默认游戏目录所在位置）
+/// macOS 上运行于 .app 包内时，包目录是只读且会被签名校验的，
+/// 此时改用 ~/Library/Application Support/ar1s_launcher
+#[cfg(target_os = "macos")]
+fn get_launcher_data_dir() -> Result<PathBuf, LauncherError> {
     let exe_path = std::env::current_exe()?;
     let exe_dir = exe_path
         .parent()
         .ok_or_else(|| LauncherError::Custom("无法获取可执行文件目录".to_string()))?;
 
-    let mc_dir = exe_dir.join(".minecraft");
+    let in_app_bundle = exe_dir.ends_with("Contents/MacOS")
+        && exe_dir
+            .ancestors()
+            .any(|p| p.extension().map(|e| e == "app").unwrap_or(false));
+    if !in_app_bundle {
+        return Ok(exe_dir.to_path_buf());
+    }
+
+    let home = std::env::var_os("HOME")
+        .ok_or_else(|| LauncherError::Custom("无法获取用户主目录".to_string()))?;
+    let data_dir = PathBuf::from(home)
+        .join("Library")
+        .join("Application Support")
+        .join("ar1s_launcher");
+    fs::create_dir_all(&data_dir)?;
+    Ok(data_dir)
+}
+
+/// 创建默认配置
+fn create_default_config(is_first_run: bool) -> Result<GameConfig, LauncherError> {
+    let data_dir = get_launcher_data_dir()?;
+
+    let mc_dir = data_dir.join(".minecraft");
     let mc_dir_str = mc_dir.to_string_lossy().into_owned();
 
     if !mc_dir.exists() {
@@ -148,6 +213,7 @@ fn create_default_config(is_first_run: bool) -> Result<GameConfig, LauncherError
 
     let mut config = GameConfig {
         game_dir: mc_dir_str,
+        game_dirs: Vec::new(),
         version_isolation: true,
         java_path: None,
         download_threads: 8,
@@ -165,6 +231,32 @@ fn create_default_config(is_first_run: bool) -> Result<GameConfig, LauncherError
         fullscreen: false,
         instance_last_played: std::collections::HashMap::new(),
         last_selected_version: None,
+        use_gamemode: true,
+        wayland_hints: false,
+        isolate_per_account: false,
+        background_prefetch: true,
+        startup_network: true,
+        uuid_strategy: crate::models::UuidStrategy::default(),
+        offline_uuids: std::collections::HashMap::new(),
+        offline_skin_server: false,
+        skin_server_port: crate::models::default_skin_server_port(),
+        skin_server_lan: false,
+        skin_server_url: None,
+        chunked_download_threshold_mb: crate::models::default_chunked_download_threshold_mb(),
+        chunked_download_parts: crate::models::default_chunked_download_parts(),
+        announcements_url: None,
+        telemetry_enabled: false,
+        telemetry_endpoint: None,
+        backup_target: None,
+        asset_mirrors: Vec::new(),
+        version_watcher: false,
+        custom_ca_cert: None,
+        modrinth_mirror: None,
+        hooks: Default::default(),
+        instance_hooks: std::collections::HashMap::new(),
+        jvm_flag_overrides: Default::default(),
+        extra_jvm_args: Vec::new(),
+        host_limits: Default::default(),
     };
 
     // 首次运行时自动检测Java
@@ -185,6 +277,16 @@ use crate::services::java::auto_detect_java;
 
 /// 保存配置文件（同时更新缓存）
 pub fn save_config(config: &GameConfig) -> Result<(), LauncherError> {
+    save_config_with_note(config, None)
+}
+
+/// 保存配置文件并在变更记录中附加备注
+pub fn save_config_with_note(config: &GameConfig, note: Option<String>) -> Result<(), LauncherError> {
+    // 记录与上一次配置相比的变更
+    if let Ok(old) = load_config() {
+        config_history::record_change(&old, config, note);
+    }
+
     save_config_internal(config)?;
     
     // 更新缓存
@@ -196,20 +298,76 @@ pub fn save_config(config: &GameConfig) -> Result<(), LauncherError> {
 }
 
 /// 内部保存函数（不更新缓存）
+///
+/// 若外部编辑尚未被重新加载，以本次写入为准并记录被覆盖的字段
 fn save_config_internal(config: &GameConfig) -> Result<(), LauncherError> {
+    if let Some(external) = external_content() {
+        let overwritten: Vec<String> = serde_json::from_str::<GameConfig>(&external)
+            .map(|disk| {
+                config_history::diff_configs(&disk, config)
+                    .into_iter()
+                    .map(|c| c.field)
+                    .collect()
+            })
+            .unwrap_or_default();
+        log::warn!(
+            "配置文件在重新加载前被外部修改，启动器的写入将覆盖这些字段: [{}]",
+            overwritten.join(", ")
+        );
+    }
+
     let config_path = get_config_path()?;
-    fs::write(config_path, serde_json::to_string_pretty(config)?)?;
+    let content = serde_json::to_string_pretty(config)?;
+    fs::write(config_path, &content)?;
+    set_last_synced(&content);
     Ok(())
 }
 
-/// 获取配置文件路径
-fn get_config_path() -> Result<PathBuf, LauncherError> {
-    let exe_path = std::env::current_exe()?;
-    let exe_dir = exe_path
-        .parent()
-        .ok_or_else(|| LauncherError::Custom("无法获取可执行文件目录".to_string()))?;
+/// 重新加载被外部编辑的配置文件，无变化或内容无法解析时返回 None
+pub fn reload_if_changed() -> Result<Option<GameConfig>, LauncherError> {
+    let Some(content) = external_content() else {
+        return Ok(None);
+    };
+    // 无论能否解析都视为已处理，避免每轮重复报告
+    set_last_synced(&content);
+
+    let config = match serde_json::from_str::<GameConfig>(&content) {
+        Ok(config) => config,
+        Err(e) => {
+            log::warn!("外部修改的配置文件无法解析，保留当前配置: {}", e);
+            return Ok(None);
+        }
+    };
 
-    Ok(exe_dir.join("ar1s.json"))
+    if let Ok(old) = load_config() {
+        config_history::record_change(&old, &config, Some("外部编辑".to_string()));
+    }
+    if let Ok(mut cache) = CONFIG_CACHE.write() {
+        *cache = Some(config.clone());
+    }
+    log::info!("已重新加载外部修改的配置文件");
+    Ok(Some(config))
+}
+
+/// 启动配置文件监视任务（在 setup 中调用），外部修改后发送 `config-reloaded`
+pub fn start_config_watcher(events: EventSink) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CONFIG_WATCH_INTERVAL).await;
+            match reload_if_changed() {
+                Ok(Some(config)) => {
+                    let _ = events.emit("config-reloaded", config);
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("重新加载配置文件失败: {}", e),
+            }
+        }
+    });
+}
+
+/// 获取配置文件路径
+pub(crate) fn get_config_path() -> Result<PathBuf, LauncherError> {
+    Ok(get_launcher_data_dir()?.join("ar1s.json"))
 }
 
 /// 配置键值映射定义
@@ -227,6 +385,18 @@ enum ConfigKey {
     Uuid,
     MaxMemory,
     DownloadMirror,
+    UseGamemode,
+    WaylandHints,
+    IsolatePerAccount,
+    BackgroundPrefetch,
+    StartupNetwork,
+    UuidStrategy,
+    ChunkedDownloadThresholdMb,
+    ChunkedDownloadParts,
+    AnnouncementsUrl,
+    TelemetryEnabled,
+    TelemetryEndpoint,
+    VersionWatcher,
 }
 
 impl ConfigKey {
@@ -244,6 +414,18 @@ impl ConfigKey {
             "uuid" => Some(Self::Uuid),
             "maxMemory" => Some(Self::MaxMemory),
             "downloadMirror" => Some(Self::DownloadMirror),
+            "useGamemode" => Some(Self::UseGamemode),
+            "waylandHints" => Some(Self::WaylandHints),
+            "isolatePerAccount" => Some(Self::IsolatePerAccount),
+            "backgroundPrefetch" => Some(Self::BackgroundPrefetch),
+            "startupNetwork" => Some(Self::StartupNetwork),
+            "uuidStrategy" => Some(Self::UuidStrategy),
+            "chunkedDownloadThresholdMb" => Some(Self::ChunkedDownloadThresholdMb),
+            "chunkedDownloadParts" => Some(Self::ChunkedDownloadParts),
+            "announcementsUrl" => Some(Self::AnnouncementsUrl),
+            "telemetryEnabled" => Some(Self::TelemetryEnabled),
+            "telemetryEndpoint" => Some(Self::TelemetryEndpoint),
+            "versionWatcher" => Some(Self::VersionWatcher),
             _ => None,
         }
     }
@@ -262,6 +444,20 @@ impl ConfigKey {
             Self::Uuid => config.uuid.clone(),
             Self::MaxMemory => Some(config.max_memory.to_string()),
             Self::DownloadMirror => config.download_mirror.clone(),
+            Self::UseGamemode => Some(config.use_gamemode.to_string()),
+            Self::WaylandHints => Some(config.wayland_hints.to_string()),
+            Self::IsolatePerAccount => Some(config.isolate_per_account.to_string()),
+            Self::BackgroundPrefetch => Some(config.background_prefetch.to_string()),
+            Self::StartupNetwork => Some(config.startup_network.to_string()),
+            Self::UuidStrategy => serde_json::to_value(config.uuid_strategy)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string)),
+            Self::ChunkedDownloadThresholdMb => Some(config.chunked_download_threshold_mb.to_string()),
+            Self::ChunkedDownloadParts => Some(config.chunked_download_parts.to_string()),
+            Self::AnnouncementsUrl => config.announcements_url.clone(),
+            Self::TelemetryEnabled => Some(config.telemetry_enabled.to_string()),
+            Self::TelemetryEndpoint => config.telemetry_endpoint.clone(),
+            Self::VersionWatcher => Some(config.version_watcher.to_string()),
         }
     }
 
@@ -303,6 +499,57 @@ impl ConfigKey {
                 })?
             }
             Self::DownloadMirror => config.download_mirror = Some(value),
+            Self::UseGamemode => {
+                config.use_gamemode = value.parse().map_err(|_| {
+                    LauncherError::Custom("GameMode 设置值无效".to_string())
+                })?
+            }
+            Self::WaylandHints => {
+                config.wayland_hints = value.parse().map_err(|_| {
+                    LauncherError::Custom("Wayland 提示设置值无效".to_string())
+                })?
+            }
+            Self::IsolatePerAccount => {
+                config.isolate_per_account = value.parse().map_err(|_| {
+                    LauncherError::Custom("账户隔离设置值无效".to_string())
+                })?
+            }
+            Self::BackgroundPrefetch => {
+                config.background_prefetch = value.parse().map_err(|_| {
+                    LauncherError::Custom("后台预取设置值无效".to_string())
+                })?
+            }
+            Self::StartupNetwork => {
+                config.startup_network = value.parse().map_err(|_| {
+                    LauncherError::Custom("启动联网设置值无效".to_string())
+                })?
+            }
+            Self::UuidStrategy => {
+                config.uuid_strategy = serde_json::from_value(serde_json::Value::String(value))
+                    .map_err(|_| LauncherError::Custom("UUID 策略设置值无效".to_string()))?
+            }
+            Self::ChunkedDownloadThresholdMb => {
+                config.chunked_download_threshold_mb = value.parse().map_err(|_| {
+                    LauncherError::Custom("分块下载阈值设置值无效".to_string())
+                })?
+            }
+            Self::ChunkedDownloadParts => {
+                config.chunked_download_parts = value.parse().map_err(|_| {
+                    LauncherError::Custom("分块连接数设置值无效".to_string())
+                })?
+            }
+            Self::AnnouncementsUrl => config.announcements_url = (!value.is_empty()).then_some(value),
+            Self::TelemetryEnabled => {
+                config.telemetry_enabled = value.parse().map_err(|_| {
+                    LauncherError::Custom("匿名统计开关设置值无效".to_string())
+                })?
+            }
+            Self::TelemetryEndpoint => config.telemetry_endpoint = (!value.is_empty()).then_some(value),
+            Self::VersionWatcher => {
+                config.version_watcher = value.parse().map_err(|_| {
+                    LauncherError::Custom("版本监视设置值无效".to_string())
+                })?
+            }
         }
         Ok(())
     }
@@ -356,43 +603,130 @@ pub fn get_game_dir() -> Result<String, LauncherError> {
     get_config_value(|config| config.game_dir.clone())
 }
 
-pub async fn get_game_dir_info() -> Result<GameDirInfo, LauncherError> {
-    let game_dir_str = get_game_dir()?;
+async fn game_dir_info(game_dir_str: String) -> Result<GameDirInfo, LauncherError> {
     let versions_dir = PathBuf::from(&game_dir_str).join("versions");
-    let mut versions = Vec::new();
-
-    if versions_dir.is_dir() {
-        for entry in fs::read_dir(versions_dir)? {
-            if let Ok(entry) = entry {
-                if entry.file_type()?.is_dir() {
-                    let version_id = entry.file_name().to_string_lossy().into_owned();
-                    let version_json_path = entry.path().join(format!("{}.json", version_id));
-                    if version_json_path.exists() {
-                        versions.push(version_id);
-                    }
-                }
-            }
-        }
-    }
+    let index = tokio::task::spawn_blocking(move || crate::services::version_index::refresh(&versions_dir)).await?;
 
     Ok(GameDirInfo {
         path: game_dir_str,
-        versions,
-        total_size: 0,
+        total_size: index.values().map(|v| v.size).sum(),
+        versions: index.into_keys().collect(),
     })
 }
 
-pub async fn set_game_dir(path: String, window: &tauri::Window) -> Result<(), LauncherError> {
+pub async fn get_game_dir_info() -> Result<GameDirInfo, LauncherError> {
+    game_dir_info(get_game_dir()?).await
+}
+
+pub async fn set_game_dir(path: String, window: &EventSink) -> Result<(), LauncherError> {
     let path_clone = path.clone();
     set_config_value(|config| config.game_dir = path_clone).await?;
     window.emit("game-dir-changed", &path)?;
     Ok(())
 }
 
+/// 所有已登记游戏目录的信息，默认目录在前
+pub async fn list_game_dirs() -> Result<Vec<GameDirInfo>, LauncherError> {
+    let config = load_config()?;
+    let mut infos = Vec::new();
+    for dir in crate::services::paths::game_dirs(&config) {
+        infos.push(game_dir_info(dir.to_string_lossy().to_string()).await?);
+    }
+    Ok(infos)
+}
+
+/// 登记额外的游戏目录（不存在时创建）
+pub async fn add_game_dir(path: String, window: &EventSink) -> Result<(), LauncherError> {
+    let dir = PathBuf::from(&path);
+    if !dir.is_absolute() {
+        return Err(LauncherError::Custom(format!("游戏目录必须是绝对路径: {}", path)));
+    }
+    fs::create_dir_all(dir.join("versions"))?;
+
+    let mut config = load_config()?;
+    if crate::services::paths::game_dirs(&config).contains(&dir) {
+        return Err(LauncherError::Custom(format!("游戏目录已登记: {}", path)));
+    }
+    config.game_dirs.push(path.clone());
+    save_config(&config)?;
+    log::info!("已登记游戏目录: {}", path);
+    window.emit("game-dirs-changed", &path)?;
+    Ok(())
+}
+
+/// 取消登记游戏目录（不删除其中的文件），默认目录不能移除
+pub async fn remove_game_dir(path: String, window: &EventSink) -> Result<(), LauncherError> {
+    let dir = PathBuf::from(&path);
+    let mut config = load_config()?;
+    if PathBuf::from(&config.game_dir) == dir {
+        return Err(LauncherError::Custom("不能移除默认游戏目录，请先将其他目录设为默认".to_string()));
+    }
+    let before = config.game_dirs.len();
+    config.game_dirs.retain(|d| PathBuf::from(d) != dir);
+    if config.game_dirs.len() == before {
+        return Err(LauncherError::Custom(format!("游戏目录未登记: {}", path)));
+    }
+    save_config(&config)?;
+    log::info!("已移除游戏目录: {}", path);
+    window.emit("game-dirs-changed", &path)?;
+    Ok(())
+}
+
+/// 将已登记的目录设为新安装的默认目录，原默认目录保留为额外目录
+pub async fn set_default_game_dir(path: String, window: &EventSink) -> Result<(), LauncherError> {
+    let dir = PathBuf::from(&path);
+    let mut config = load_config()?;
+    if PathBuf::from(&config.game_dir) == dir {
+        return Ok(());
+    }
+    let Some(pos) = config.game_dirs.iter().position(|d| PathBuf::from(d) == dir) else {
+        return Err(LauncherError::Custom(format!("游戏目录未登记: {}", path)));
+    };
+    let previous = std::mem::replace(&mut config.game_dir, config.game_dirs.remove(pos));
+    config.game_dirs.insert(0, previous);
+    save_config(&config)?;
+    window.emit("game-dir-changed", &path)?;
+    Ok(())
+}
+
+/// 导入自定义 CA 证书：校验后复制到启动器数据目录，并重建 HTTP 客户端
+pub async fn import_ca_certificate(path: String) -> Result<String, LauncherError> {
+    let source = PathBuf::from(&path);
+    crate::services::http_client::read_certificate(&source)?;
+    let target = get_config_path()?.with_file_name("ar1s_ca.crt");
+    fs::copy(&source, &target)?;
+    let target_str = target.to_string_lossy().to_string();
+    let stored = target_str.clone();
+    set_config_value(|config| config.custom_ca_cert = Some(stored)).await?;
+    crate::services::http_client::reset_clients();
+    log::info!("已导入自定义 CA 证书: {}", path);
+    Ok(target_str)
+}
+
+/// 移除自定义 CA 证书，恢复仅使用系统证书
+pub async fn clear_ca_certificate() -> Result<(), LauncherError> {
+    if let Some(path) = load_config()?.custom_ca_cert {
+        let _ = fs::remove_file(path);
+    }
+    set_config_value(|config| config.custom_ca_cert = None).await?;
+    crate::services::http_client::reset_clients();
+    Ok(())
+}
+
 pub async fn set_version_isolation(enabled: bool) -> Result<(), LauncherError> {
     set_config_value(|config| config.version_isolation = enabled).await
 }
 
+/// 启用或禁用离线皮肤服务
+pub async fn set_offline_skin_server(enabled: bool) -> Result<(), LauncherError> {
+    set_config_value(|config| config.offline_skin_server = enabled).await
+}
+
+/// 设置离线皮肤服务是否对局域网开放
+pub async fn set_skin_server_lan(enabled: bool) -> Result<(), LauncherError> {
+    set_config_value(|config| config.skin_server_lan = enabled).await
+}
+
 pub fn get_download_threads() -> Result<u8, LauncherError> {
     get_config_value(|config| config.download_threads)
 }
@@ -401,6 +735,89 @@ pub async fn set_download_threads(threads: u8) -> Result<(), LauncherError> {
     set_config_value(|config| config.download_threads = threads).await
 }
 
+/// 设置 Modrinth 文件镜像（为空时使用官方 CDN）
+pub async fn set_modrinth_mirror(mirror: Option<String>) -> Result<(), LauncherError> {
+    let mirror = mirror
+        .map(|m| m.trim().trim_end_matches('/').to_string())
+        .filter(|m| !m.is_empty());
+    if let Some(m) = &mirror {
+        if !m.starts_with("http://") && !m.starts_with("https://") {
+            return Err(LauncherError::Custom(format!("镜像地址必须以 http:// 或 https:// 开头: {}", m)));
+        }
+    }
+    set_config_value(|config| config.modrinth_mirror = mirror).await
+}
+
+/// 设置全局生命周期钩子
+pub async fn set_global_hooks(hooks: crate::models::LifecycleHooks) -> Result<(), LauncherError> {
+    set_config_value(|config| config.hooks = hooks).await
+}
+
+/// 设置默认 JVM 参数规则的覆盖，`enabled` 为空时恢复按版本自动判断
+pub async fn set_jvm_flag_override(id: String, enabled: Option<bool>) -> Result<(), LauncherError> {
+    if !crate::services::launcher::is_known_flag(&id) {
+        return Err(LauncherError::Custom(format!("未知的默认参数规则: {}", id)));
+    }
+    set_config_value(move |config| match enabled {
+        Some(enabled) => {
+            config.jvm_flag_overrides.insert(id, enabled);
+        }
+        None => {
+            config.jvm_flag_overrides.remove(&id);
+        }
+    })
+    .await
+}
+
+/// 整理自定义 JVM 参数：去掉空白项，每项必须以 `-` 开头；classpath 由启动器生成，不允许覆盖
+pub fn normalize_jvm_args(args: Vec<String>) -> Result<Vec<String>, LauncherError> {
+    let args: Vec<String> = args
+        .into_iter()
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty())
+        .collect();
+    for arg in &args {
+        if !arg.starts_with('-') {
+            return Err(LauncherError::Custom(format!("JVM 参数必须以 - 开头: {}", arg)));
+        }
+        if matches!(arg.as_str(), "-cp" | "-classpath" | "--class-path") {
+            return Err(LauncherError::Custom(format!("不能通过自定义参数设置 classpath: {}", arg)));
+        }
+    }
+    Ok(args)
+}
+
+/// 设置全局自定义 JVM 参数
+pub async fn set_extra_jvm_args(args: Vec<String>) -> Result<(), LauncherError> {
+    let args = normalize_jvm_args(args)?;
+    set_config_value(|config| config.extra_jvm_args = args).await
+}
+
+/// 设置下载主机的并发限制，`limit` 为空时恢复内置默认值
+pub async fn set_host_limit(host: String, limit: Option<crate::models::HostLimit>) -> Result<(), LauncherError> {
+    let host = host.trim().to_lowercase();
+    if host.is_empty() || host.contains('/') {
+        return Err(LauncherError::Custom(format!("无效的主机名: {}", host)));
+    }
+    if let Some(limit) = &limit {
+        if !(1..=64).contains(&limit.max_connections) {
+            return Err(LauncherError::Custom("并发连接数必须在 1 到 64 之间".to_string()));
+        }
+        if limit.delay_ms > 10_000 {
+            return Err(LauncherError::Custom("请求间隔不能超过 10 秒".to_string()));
+        }
+    }
+    set_config_value(move |config| match limit {
+        Some(limit) => {
+            config.host_limits.insert(host, limit);
+        }
+        None => {
+            config.host_limits.remove(&host);
+        }
+    })
+    .await
+}
+
 pub fn get_total_memory() -> u64 {
     let mut sys = System::new();
     sys.refresh_memory();
@@ -506,6 +923,37 @@ pub fn rename_instance_last_played(old_name: &str, new_name: &str) -> Result<(),
     Ok(())
 }
 
+/// 获取实例的生命周期钩子
+pub fn get_instance_hooks(instance_name: &str) -> LifecycleHooks {
+    load_config()
+        .ok()
+        .and_then(|config| config.instance_hooks.get(instance_name).cloned())
+        .unwrap_or_default()
+}
+
+/// 设置实例的生命周期钩子（全部为空时删除记录）
+pub fn set_instance_hooks(instance_name: &str, hooks: LifecycleHooks) -> Result<(), LauncherError> {
+    let mut config = load_config()?;
+    if hooks.is_empty() {
+        if config.instance_hooks.remove(instance_name).is_none() {
+            return Ok(());
+        }
+    } else {
+        config.instance_hooks.insert(instance_name.to_string(), hooks);
+    }
+    save_config(&config)
+}
+
+/// 重命名实例的生命周期钩子记录
+pub fn rename_instance_hooks(old_name: &str, new_name: &str) -> Result<(), LauncherError> {
+    let mut config = load_config()?;
+    if let Some(hooks) = config.instance_hooks.remove(old_name) {
+        config.instance_hooks.insert(new_name.to_string(), hooks);
+        save_config(&config)?;
+    }
+    Ok(())
+}
+
 /// 获取上次选择的游戏版本
 pub fn get_last_selected_version() -> Option<String> {
     load_config().ok().and_then(|c| c.last_selected_version)
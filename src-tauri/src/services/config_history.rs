@@ -0,0 +1,150 @@
+//! 配置变更记录与撤销
+//!
+//! 每次保存配置时记录字段级别的变更（时间、操作者、旧值 -> 新值），
+//! 保存在配置文件旁的 ar1s_history.json 中，只保留最近若干条。
+
+use crate::errors::LauncherError;
+use crate::models::GameConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// 保留的最大记录条数
+const MAX_ENTRIES: usize = 50;
+
+/// 不记录的高频字段（由启动器自动更新）
+const IGNORED_FIELDS: [&str; 2] = ["instance_last_played", "last_selected_version"];
+
+/// 历史文件读写锁
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// 单个字段的变更
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    pub field: String,
+    pub old_value: Value,
+    pub new_value: Value,
+}
+
+/// 一次配置保存产生的变更记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChangeEntry {
+    pub id: u64,
+    /// RFC3339 时间
+    pub timestamp: String,
+    /// 操作系统用户名
+    pub actor: String,
+    /// 备注（如撤销操作）
+    pub note: Option<String>,
+    pub changes: Vec<FieldChange>,
+}
+
+/// 历史文件路径
+fn history_path() -> Result<PathBuf, LauncherError> {
+    let config_path = crate::services::config::get_config_path()?;
+    Ok(config_path.with_file_name("ar1s_history.json"))
+}
+
+fn load_entries() -> Vec<ConfigChangeEntry> {
+    history_path()
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_entries(entries: &[ConfigChangeEntry]) -> Result<(), LauncherError> {
+    fs::write(history_path()?, serde_json::to_string_pretty(entries)?)?;
+    Ok(())
+}
+
+/// 当前操作系统用户名
+fn current_actor() -> String {
+    std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// 比较两份配置，返回发生变化的字段
+pub(crate) fn diff_configs(old: &GameConfig, new: &GameConfig) -> Vec<FieldChange> {
+    let (Ok(Value::Object(old)), Ok(Value::Object(new))) =
+        (serde_json::to_value(old), serde_json::to_value(new))
+    else {
+        return Vec::new();
+    };
+
+    new.iter()
+        .filter(|(field, _)| !IGNORED_FIELDS.contains(&field.as_str()))
+        .filter_map(|(field, new_value)| {
+            let old_value = old.get(field).cloned().unwrap_or(Value::Null);
+            (old_value != *new_value).then(|| FieldChange {
+                field: field.clone(),
+                old_value,
+                new_value: new_value.clone(),
+            })
+        })
+        .collect()
+}
+
+/// 记录一次配置变更（无变化时不记录）
+pub fn record_change(old: &GameConfig, new: &GameConfig, note: Option<String>) {
+    let changes = diff_configs(old, new);
+    if changes.is_empty() {
+        return;
+    }
+
+    let Ok(_guard) = HISTORY_LOCK.lock() else {
+        return;
+    };
+    let mut entries = load_entries();
+    let id = entries.last().map(|e| e.id + 1).unwrap_or(1);
+    entries.push(ConfigChangeEntry {
+        id,
+        timestamp: chrono::Local::now().to_rfc3339(),
+        actor: current_actor(),
+        note,
+        changes,
+    });
+    if entries.len() > MAX_ENTRIES {
+        let excess = entries.len() - MAX_ENTRIES;
+        entries.drain(..excess);
+    }
+    if let Err(e) = save_entries(&entries) {
+        log::warn!("保存配置变更记录失败: {}", e);
+    }
+}
+
+/// 获取配置变更历史（最新的在前）
+pub fn get_config_history() -> Vec<ConfigChangeEntry> {
+    let _guard = HISTORY_LOCK.lock();
+    let mut entries = load_entries();
+    entries.reverse();
+    entries
+}
+
+/// 撤销指定的配置变更：将该记录涉及的字段恢复为旧值
+pub fn revert_config_change(id: u64) -> Result<GameConfig, LauncherError> {
+    let entry = {
+        let _guard = HISTORY_LOCK.lock();
+        load_entries()
+            .into_iter()
+            .find(|e| e.id == id)
+            .ok_or_else(|| LauncherError::Custom(format!("找不到配置变更记录 #{}", id)))?
+    };
+
+    let current = crate::services::config::load_config()?;
+    let mut value = serde_json::to_value(&current)?;
+    if let Value::Object(map) = &mut value {
+        for change in &entry.changes {
+            map.insert(change.field.clone(), change.old_value.clone());
+        }
+    }
+    let reverted: GameConfig = serde_json::from_value(value)?;
+
+    crate::services::config::save_config_with_note(&reverted, Some(format!("撤销 #{}", id)))?;
+    Ok(reverted)
+}
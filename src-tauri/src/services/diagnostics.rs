@@ -0,0 +1,192 @@
+//! 诊断包导出和网络诊断
+//!
+//! 将性能指标、启动检查报告、脱敏后的配置、系统信息、最新的启动器日志和下载会话日志
+//! 打包为 zip，便于用户反馈问题。网络诊断逐步检查 DNS、TCP 和 TLS，
+//! 区分离线与 TLS 中间人拦截（学校/企业网络常见）。
+
+use crate::errors::LauncherError;
+use crate::models::BackupTarget;
+use crate::services::{config, download, health, http_client, metrics};
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use zip::write::SimpleFileOptions;
+
+/// 网络诊断使用的主机（版本清单所在）
+const PROBE_HOST: &str = "piston-meta.mojang.com";
+
+/// 网络诊断各步骤超时
+const PROBE_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// 网络诊断结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkStatus {
+    /// 连接正常
+    Online,
+    /// 无法解析域名
+    DnsFailure,
+    /// 无法建立 TCP 连接（离线或被防火墙阻止）
+    Offline,
+    /// TCP 可达但证书校验失败，HTTPS 流量被拦截
+    TlsInterception,
+    /// 连接建立但请求失败
+    HttpError,
+}
+
+/// 网络诊断结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkProbe {
+    pub host: String,
+    pub status: NetworkStatus,
+    pub detail: String,
+    /// 是否已导入自定义 CA 证书
+    pub custom_ca: bool,
+    /// 给用户的处理建议
+    pub suggestion: Option<String>,
+}
+
+/// 依次检查 DNS、TCP 和 HTTPS，判断网络问题类型
+pub async fn probe_network() -> Result<NetworkProbe, LauncherError> {
+    let custom_ca = config::load_config()?.custom_ca_cert.is_some();
+    let probe = |status, detail: String, suggestion: Option<&str>| NetworkProbe {
+        host: PROBE_HOST.to_string(),
+        status,
+        detail,
+        custom_ca,
+        suggestion: suggestion.map(String::from),
+    };
+
+    let addrs: Vec<_> = match tokio::time::timeout(
+        PROBE_TIMEOUT,
+        tokio::net::lookup_host((PROBE_HOST, 443)),
+    )
+    .await
+    {
+        Ok(Ok(addrs)) => addrs.collect(),
+        Ok(Err(e)) => return Ok(probe(NetworkStatus::DnsFailure, e.to_string(), Some("检查网络连接或 DNS 设置"))),
+        Err(_) => return Ok(probe(NetworkStatus::DnsFailure, "DNS 解析超时".to_string(), Some("检查网络连接或 DNS 设置"))),
+    };
+    let Some(addr) = addrs.first() else {
+        return Ok(probe(NetworkStatus::DnsFailure, "未解析到地址".to_string(), Some("检查网络连接或 DNS 设置")));
+    };
+
+    match tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect(addr)).await {
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => return Ok(probe(NetworkStatus::Offline, e.to_string(), Some("设备可能处于离线状态，或防火墙阻止了 443 端口"))),
+        Err(_) => return Ok(probe(NetworkStatus::Offline, "TCP 连接超时".to_string(), Some("设备可能处于离线状态，或防火墙阻止了 443 端口"))),
+    }
+
+    let url = format!("https://{}/mc/game/version_manifest_v2.json", PROBE_HOST);
+    match http_client::get_client().head(&url).send().await {
+        Ok(response) if response.status().is_success() => {
+            Ok(probe(NetworkStatus::Online, format!("HTTP {}", response.status()), None))
+        }
+        Ok(response) => Ok(probe(NetworkStatus::HttpError, format!("HTTP {}", response.status()), None)),
+        Err(e) if http_client::is_tls_error(&e) => {
+            let suggestion = if custom_ca {
+                "已导入的 CA 证书未能通过校验，请确认是否为当前网络使用的证书"
+            } else {
+                "当前网络在拦截 HTTPS 流量，请向网络管理员索取 CA 证书并在设置中导入"
+            };
+            Ok(probe(NetworkStatus::TlsInterception, e.to_string(), Some(suggestion)))
+        }
+        Err(e) => Ok(probe(NetworkStatus::HttpError, e.to_string(), None)),
+    }
+}
+
+/// 启动器日志目录（相对工作目录，与 logger 保持一致）
+const LAUNCHER_LOG_DIR: &str = "logs";
+
+/// 查找最新的启动器日志
+fn latest_launcher_log() -> Option<PathBuf> {
+    fs::read_dir(LAUNCHER_LOG_DIR)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .map(|n| n.to_string_lossy().starts_with("ar1s_launcher_"))
+                .unwrap_or(false)
+        })
+        .max_by_key(|p| fs::metadata(p).and_then(|m| m.modified()).ok())
+}
+
+/// 系统信息摘要
+fn system_summary() -> String {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_memory();
+    format!(
+        "os: {} {}\narch: {}\nlauncher: {}\ntotal_memory_mb: {}\navailable_memory_mb: {}\n",
+        std::env::consts::OS,
+        sysinfo::System::long_os_version().unwrap_or_default(),
+        std::env::consts::ARCH,
+        env!("CARGO_PKG_VERSION"),
+        sys.total_memory() / 1024 / 1024,
+        sys.available_memory() / 1024 / 1024,
+    )
+}
+
+/// 导出诊断包，返回 zip 文件路径
+pub fn export_diagnostics_bundle() -> Result<String, LauncherError> {
+    let cfg = config::load_config()?;
+    let out_dir = Path::new(&cfg.game_dir).join("diagnostics");
+    fs::create_dir_all(&out_dir)?;
+    let out_path = out_dir.join(format!(
+        "ar1s_diagnostics_{}.zip",
+        chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+    ));
+
+    // 脱敏：去掉用户名、UUID 和备份位置凭据
+    let mut redacted = cfg.clone();
+    redacted.username = redacted.username.map(|_| "<redacted>".to_string());
+    redacted.uuid = redacted.uuid.map(|_| "<redacted>".to_string());
+    redacted.offline_uuids.clear();
+    match redacted.backup_target.as_mut() {
+        Some(BackupTarget::WebDav { password, .. }) => {
+            *password = password.as_ref().map(|_| "<redacted>".to_string());
+        }
+        Some(BackupTarget::S3 {
+            access_key, secret_key, ..
+        }) => {
+            *access_key = "<redacted>".to_string();
+            *secret_key = "<redacted>".to_string();
+        }
+        _ => {}
+    }
+
+    let mut zip = zip::ZipWriter::new(fs::File::create(&out_path)?);
+    let options = SimpleFileOptions::default();
+
+    zip.start_file("metrics.json", options)?;
+    zip.write_all(serde_json::to_string_pretty(&metrics::snapshot())?.as_bytes())?;
+
+    zip.start_file("startup_report.json", options)?;
+    zip.write_all(serde_json::to_string_pretty(&health::get_startup_report()?)?.as_bytes())?;
+
+    zip.start_file("config.json", options)?;
+    zip.write_all(serde_json::to_string_pretty(&redacted)?.as_bytes())?;
+
+    zip.start_file("system.txt", options)?;
+    zip.write_all(system_summary().as_bytes())?;
+
+    if let Some(log_path) = latest_launcher_log() {
+        zip.start_file("launcher.log", options)?;
+        zip.write_all(&fs::read(log_path)?)?;
+    }
+
+    for journal in download::recent_journals(Path::new(&cfg.game_dir)) {
+        let Some(name) = journal.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        zip.start_file(format!("downloads/{}", name), options)?;
+        zip.write_all(&fs::read(&journal)?)?;
+    }
+
+    zip.finish()?;
+    log::info!("诊断包已导出: {}", out_path.display());
+    Ok(out_path.to_string_lossy().to_string())
+}
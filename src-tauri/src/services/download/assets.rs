@@ -0,0 +1,178 @@
+//! 资源索引差异计算
+//!
+//! 资源对象以哈希命名、按内容寻址，不同版本之间大部分共享。
+//! 入队前先与已校验对象数据库比对，只下载缺失或损坏的对象。
+
+use crate::errors::LauncherError;
+use crate::models::DownloadJob;
+use crate::utils::file_utils;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 已校验对象数据库文件名（位于 assets 目录下）
+const VERIFIED_DB_FILE: &str = "verified_objects.json";
+
+/// 资源对象差异统计
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetsDiff {
+    pub index_id: String,
+    pub total: usize,
+    pub skipped: usize,
+    pub skipped_bytes: u64,
+    pub queued: usize,
+    pub queued_bytes: u64,
+}
+
+/// 已校验对象数据库（哈希 -> 文件大小）
+struct VerifiedObjects {
+    path: PathBuf,
+    objects: HashMap<String, u64>,
+    dirty: bool,
+}
+
+impl VerifiedObjects {
+    fn load(assets_dir: &Path) -> Self {
+        let path = assets_dir.join(VERIFIED_DB_FILE);
+        let objects = fs::read_to_string(&path)
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            objects,
+            dirty: false,
+        }
+    }
+
+    /// 对象是否已校验且文件仍然存在、大小未变
+    fn is_verified(&self, hash: &str, file_path: &Path, size: u64) -> bool {
+        self.objects.get(hash) == Some(&size)
+            && fs::metadata(file_path).map(|m| m.len() == size).unwrap_or(false)
+    }
+
+    fn insert(&mut self, hash: String, size: u64) {
+        self.objects.insert(hash, size);
+        self.dirty = true;
+    }
+
+    fn save(&self) -> Result<(), LauncherError> {
+        if self.dirty {
+            fs::write(&self.path, serde_json::to_string(&self.objects)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// 比对资源索引与本地已有对象，返回需要下载的任务和统计信息
+///
+/// 不在数据库中但大小匹配的本地对象会计算一次 SHA1，通过后写入数据库，
+/// 之后的版本安装可直接跳过
+pub fn diff_asset_jobs(
+    assets_dir: &Path,
+    index_id: &str,
+    jobs: Vec<DownloadJob>,
+) -> Result<(Vec<DownloadJob>, AssetsDiff), LauncherError> {
+    let mut db = VerifiedObjects::load(assets_dir);
+    let total = jobs.len();
+
+    // 先用数据库快速筛选，剩余存在且大小匹配的文件并行计算哈希
+    let (known, unknown): (Vec<DownloadJob>, Vec<DownloadJob>) = jobs
+        .into_iter()
+        .partition(|job| db.is_verified(&job.hash, &job.path, job.size));
+
+    let checked: Vec<(DownloadJob, bool)> = unknown
+        .into_par_iter()
+        .map(|job| {
+            let size_matches = fs::metadata(&job.path)
+                .map(|m| m.len() == job.size)
+                .unwrap_or(false);
+            let valid = size_matches
+                && file_utils::verify_file(&job.path, &job.hash, job.size).unwrap_or(false);
+            (job, valid)
+        })
+        .collect();
+
+    let mut skipped = known.len();
+    let mut skipped_bytes: u64 = known.iter().map(|j| j.size).sum();
+    let mut queued = Vec::new();
+
+    for (job, valid) in checked {
+        if valid {
+            skipped += 1;
+            skipped_bytes += job.size;
+            db.insert(job.hash.clone(), job.size);
+        } else {
+            queued.push(job);
+        }
+    }
+
+    if let Err(e) = db.save() {
+        log::warn!("保存已校验资源数据库失败: {}", e);
+    }
+
+    let diff = AssetsDiff {
+        index_id: index_id.to_string(),
+        total,
+        skipped,
+        skipped_bytes,
+        queued: queued.len(),
+        queued_bytes: queued.iter().map(|j| j.size).sum(),
+    };
+
+    log::info!(
+        "资源索引 {}: 共 {} 个对象，跳过 {} 个已存在对象 ({:.1} MB)，需下载 {} 个",
+        diff.index_id,
+        diff.total,
+        diff.skipped,
+        diff.skipped_bytes as f64 / 1024.0 / 1024.0,
+        diff.queued
+    );
+
+    Ok((queued, diff))
+}
+
+/// 官方资源对象地址前缀
+const OFFICIAL_OBJECTS_URL: &str = "https://resources.download.minecraft.net";
+/// BMCLAPI 资源对象地址前缀
+const BMCLAPI_OBJECTS_URL: &str = "https://bmclapi2.bangbang93.com/assets";
+
+/// 从资源对象 URL 中取出对象哈希（非资源对象返回 None）
+fn object_hash_from_url(url: &str) -> Option<&str> {
+    let mut segments = url.rsplit('/');
+    let hash = segments.next()?;
+    let prefix = segments.next()?;
+    (hash.len() == 40 && hash.starts_with(prefix) && prefix.len() == 2 && hash.bytes().all(|b| b.is_ascii_hexdigit()))
+        .then_some(hash)
+}
+
+/// 资源对象的全部候选地址：首选源、另一官方/BMCLAPI 源、配置的自定义镜像
+///
+/// 每个候选下载后都会做 SHA1 校验，只有校验通过的内容才会被接受，
+/// 因此任一镜像返回损坏内容时会自动换下一个源。
+pub fn asset_object_candidates(job: &crate::models::DownloadJob, primary: &str) -> Vec<String> {
+    let mut candidates = vec![primary.to_string()];
+    let Some(hash) = object_hash_from_url(&job.url).filter(|h| h.eq_ignore_ascii_case(&job.hash)) else {
+        candidates.extend(job.fallback_url.iter().cloned());
+        return candidates;
+    };
+
+    let object_path = format!("{}/{}", &hash[..2], hash);
+    let custom = crate::services::config::load_config()
+        .map(|c| c.asset_mirrors)
+        .unwrap_or_default();
+    let sources = [OFFICIAL_OBJECTS_URL, BMCLAPI_OBJECTS_URL]
+        .into_iter()
+        .map(String::from)
+        .chain(custom.into_iter().filter(|m| !m.trim().is_empty()));
+    for base in sources {
+        let url = format!("{}/{}", base.trim().trim_end_matches('/'), object_path);
+        if !candidates.contains(&url) {
+            candidates.push(url);
+        }
+    }
+    candidates
+}
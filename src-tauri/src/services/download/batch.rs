@@ -1,49 +1,37 @@
 //! 批量下载逻辑（支持断点续传）
 
-use super::file::download_file;
+use super::file::{self, download_file};
+use super::host_limits;
 use super::http::get_http_client;
+use super::journal::DownloadJournal;
+use super::mirror_fallback::MirrorFallback;
 use super::state::DownloadState;
 use crate::errors::LauncherError;
-use crate::models::{DownloadJob, DownloadProgress, DownloadStatus};
+use crate::models::{DownloadJob, DownloadProgress, DownloadStatus, HostLimit, ProgressStage};
 use crate::services::config::load_config;
+use crate::services::{metrics, os_progress};
+use crate::services::tasks::{self, TaskKind};
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tauri::async_runtime;
-use tauri::{Emitter, Listener, Window};
+use crate::services::event_sink::EventSink;
 use tokio::sync::Mutex;
 
-/// 全局取消标志，用于跨下载会话的取消控制
-static CANCEL_FLAG: std::sync::OnceLock<Arc<AtomicBool>> = std::sync::OnceLock::new();
-
-/// 获取或初始化全局取消标志
-fn get_cancel_flag() -> Arc<AtomicBool> {
-    CANCEL_FLAG
-        .get_or_init(|| Arc::new(AtomicBool::new(false)))
-        .clone()
-}
-
-/// 重置取消标志（在开始新下载时调用）
-pub fn reset_cancel_flag() {
-    if let Some(flag) = CANCEL_FLAG.get() {
-        flag.store(false, Ordering::SeqCst);
-    }
-}
-
-/// 设置取消标志（在取消下载时调用）
-pub fn set_cancel_flag() {
-    get_cancel_flag().store(true, Ordering::SeqCst);
-}
+/// 任务栏进度中的下载操作
+const OS_PROGRESS_KEY: &str = "download";
 
 /// 批量下载所有文件（支持断点续传）
 pub async fn download_all_files(
     jobs: Vec<DownloadJob>,
-    window: &Window,
+    window: &EventSink,
     _total_files: u64,
     _mirror: Option<String>,
 ) -> Result<(), LauncherError> {
     let config = load_config()?;
     let threads = config.download_threads as usize;
+    let host_limits = Arc::new(host_limits::effective_host_limits(&config));
 
     // 使用全局 HTTP 客户端
     let http = get_http_client()?;
@@ -93,10 +81,11 @@ pub async fn download_all_files(
 
     // 计算总大小（包括已完成的）
     let total_size: u64 = jobs.iter().map(|j| j.size).sum();
+    let stage = download_stage(&jobs);
 
     if filtered_jobs.is_empty() {
         println!("DEBUG: All files already downloaded, skipping");
-        emit_completed_progress(window, total_size, total_size);
+        emit_completed_progress(window, stage, total_size, total_size);
         return Ok(());
     }
 
@@ -107,40 +96,28 @@ pub async fn download_all_files(
         resumed_bytes
     );
 
-    // 重置全局取消标志
-    reset_cancel_flag();
-    let global_cancel = get_cancel_flag();
+    // 记录本次下载会话，失败时错误信息附带日志路径
+    let journal = Arc::new(DownloadJournal::start(&game_dir, &version_id, &filtered_jobs, threads));
+
+    // 登记下载任务（处于安装任务中时共享其取消令牌）
+    let task = tasks::begin(TaskKind::Download, &version_id);
+    let _ = window.emit("task-started", task.info());
+    let cancel_token = task.token();
+    let global_cancel = cancel_token.flag();
+
+    // 镜像大量 404 时剩余任务改用官方源
+    let fallback_window = window.clone();
+    let mirror_fallback = Arc::new(MirrorFallback::new(&version_id, move |event| {
+        let _ = fallback_window.emit("download-mirror-fallback", &event);
+    }));
 
     // 创建共享状态
     let files_downloaded = Arc::new(AtomicU64::new(completed_count));
     let bytes_downloaded = Arc::new(AtomicU64::new(resumed_bytes));
     let bytes_since_last = Arc::new(AtomicU64::new(0));
     let state = Arc::new(AtomicBool::new(true));
-    let was_cancelled = Arc::new(AtomicBool::new(false));
     let error_occurred = Arc::new(tokio::sync::Mutex::new(None::<String>));
 
-    // 监听取消下载事件（使用 listen 而非 once，以支持多次取消尝试）
-    let state_clone = state.clone();
-    let was_cancelled_clone = was_cancelled.clone();
-    let download_state_clone = download_state.clone();
-    let state_file_clone = state_file.clone();
-    let listener_id = window.listen("cancel-download", move |_| {
-        // 检查是否已经取消，避免重复处理
-        if state_clone.swap(false, Ordering::SeqCst) {
-            was_cancelled_clone.store(true, Ordering::SeqCst);
-            // 取消时异步保存状态以便下次续传
-            let download_state = download_state_clone.clone();
-            let state_file = state_file_clone.clone();
-            // 使用 spawn_blocking 来处理可能阻塞的操作
-            std::thread::spawn(move || {
-                // 尝试获取锁并保存状态
-                if let Ok(state) = download_state.try_lock() {
-                    let _ = state.save_to_file(&state_file);
-                }
-            });
-        }
-    });
-
     // 创建进度报告器
     let reporter_handle = spawn_progress_reporter(
         files_downloaded.clone(),
@@ -149,6 +126,7 @@ pub async fn download_all_files(
         state.clone(),
         window.clone(),
         total_size,
+        stage,
     );
 
     // 定期保存状态（每 30 秒）
@@ -180,6 +158,9 @@ pub async fn download_all_files(
             bytes_since_last.clone(),
             error_occurred.clone(),
             download_state.clone(),
+            journal.clone(),
+            mirror_fallback.clone(),
+            host_limits.clone(),
             permit,
         );
         handles.push(handle);
@@ -195,9 +176,6 @@ pub async fn download_all_files(
     reporter_handle.await?;
     state_saver_handle.await?;
 
-    // 取消监听器
-    window.unlisten(listener_id);
-
     // 保存最终状态
     {
         let state = download_state.lock().await;
@@ -208,9 +186,10 @@ pub async fn download_all_files(
         }
     }
 
-    // 处理取消
-    if was_cancelled.load(Ordering::SeqCst) {
-        emit_cancelled_progress(window, bytes_downloaded.load(Ordering::SeqCst), total_size);
+    // 处理取消（已保存下载状态和 .part 文件，下次可续传）
+    if cancel_token.is_cancelled() {
+        journal.finish("cancelled", 0, None);
+        emit_cancelled_progress(window, stage, bytes_downloaded.load(Ordering::SeqCst), total_size);
         return Err(LauncherError::Custom("下载已取消".to_string()));
     }
 
@@ -221,8 +200,12 @@ pub async fn download_all_files(
     };
 
     if let Some(error_msg) = error_message {
+        let failed = download_state.lock().await.failed_files.len();
+        journal.finish("error", failed, Some(&error_msg));
+        let error_msg = format!("{}（下载日志: {}）", error_msg, journal.path().display());
         emit_error_progress(
             window,
+            stage,
             bytes_downloaded.load(Ordering::SeqCst),
             total_size,
             &error_msg,
@@ -236,14 +219,18 @@ pub async fn download_all_files(
         state.failed_files.clone()
     };
     if !failed_list.is_empty() {
+        journal.finish("partial", failed_list.len(), None);
         let _ = window.emit(
             "download-summary",
             &serde_json::json!({
                 "status": "partial",
                 "failed_count": failed_list.len(),
                 "failed": failed_list,
+                "journal": journal.path(),
             }),
         );
+    } else {
+        journal.finish("completed", 0, None);
     }
 
     // 下载完成，删除状态文件
@@ -258,19 +245,33 @@ pub async fn download_all_files(
     }
 
     // 发送完成事件
-    emit_completed_progress(window, bytes_downloaded.load(Ordering::SeqCst), total_size);
+    emit_completed_progress(window, stage, bytes_downloaded.load(Ordering::SeqCst), total_size);
 
     Ok(())
 }
 
+/// 下载任务对应的进度阶段：全部为资源文件时报告为下载资源
+fn download_stage(jobs: &[DownloadJob]) -> ProgressStage {
+    let all_assets = !jobs.is_empty()
+        && jobs
+            .iter()
+            .all(|job| job.path.components().any(|c| c.as_os_str() == "assets"));
+    if all_assets {
+        ProgressStage::DownloadingAssets
+    } else {
+        ProgressStage::DownloadingFiles
+    }
+}
+
 /// 启动进度报告器
 fn spawn_progress_reporter(
     files_downloaded: Arc<AtomicU64>,
     bytes_downloaded: Arc<AtomicU64>,
     bytes_since_last: Arc<AtomicU64>,
     state: Arc<AtomicBool>,
-    window: Window,
+    window: EventSink,
     total_size: u64,
+    stage: ProgressStage,
 ) -> tauri::async_runtime::JoinHandle<()> {
     let report_interval = Duration::from_millis(200);
 
@@ -292,6 +293,7 @@ fn spawn_progress_reporter(
             };
 
             let progress = DownloadProgress {
+                stage,
                 progress: current_bytes,
                 total: total_size,
                 speed,
@@ -302,6 +304,7 @@ fn spawn_progress_reporter(
                 error: None,
             };
             let _ = window.emit("download-progress", &progress);
+            os_progress::update(&window, OS_PROGRESS_KEY, progress_percent);
         }
     })
 }
@@ -344,6 +347,9 @@ fn spawn_download_task(
     bytes_since_last: Arc<AtomicU64>,
     error_occurred: Arc<tokio::sync::Mutex<Option<String>>>,
     download_state: Arc<Mutex<DownloadState>>,
+    journal: Arc<DownloadJournal>,
+    mirror_fallback: Arc<MirrorFallback>,
+    host_limits: Arc<BTreeMap<String, HostLimit>>,
     permit: tokio::sync::OwnedSemaphorePermit,
 ) -> tauri::async_runtime::JoinHandle<Result<(), LauncherError>> {
     async_runtime::spawn(async move {
@@ -369,11 +375,11 @@ fn spawn_download_task(
                 break;
             }
 
-            // 在重试时尝试切换到官方源
+            // 镜像已因大量 404 被跳过，或重试时，尝试切换到官方源
             let current_url = if retry >= 2 && job.url.contains("bmclapi2.bangbang93.com") {
                 job.fallback_url.as_deref().unwrap_or(&job.url)
             } else {
-                &job.url
+                mirror_fallback.primary_url(&job)
             };
 
             let attempt_str = if retry == 0 {
@@ -383,6 +389,10 @@ fn spawn_download_task(
             };
             println!("DEBUG: Downloading file: {} ({})", current_url, attempt_str);
 
+            // 按主机限制并发和请求间隔，许可在本次尝试结束后释放
+            let host_permit = host_limits::acquire(current_url, &host_limits).await;
+            let mut span = metrics::span(format!("download:{}", metrics::host_of(current_url)));
+            let attempt_started = Instant::now();
             match download_file(
                 http.clone(),
                 &job,
@@ -391,16 +401,20 @@ fn spawn_download_task(
                 &global_cancel,
                 &bytes_downloaded,
                 &bytes_since_last,
+                &mirror_fallback,
             )
             .await
             {
                 Ok(_) => {
+                    journal.attempt(&job.url, current_url, retry + 1, attempt_started, None);
                     files_downloaded.fetch_add(1, Ordering::SeqCst);
                     current_job_error = None;
                     job_succeeded = true;
                     break;
                 }
                 Err(e) => {
+                    drop(host_permit);
+                    span.fail();
                     // 如果是取消导致的错误，不需要重试
                     if e.to_string().contains("cancelled") {
                         break;
@@ -409,7 +423,13 @@ fn spawn_download_task(
                         "ERROR: Download failed: {} ({}) - {}",
                         current_url, attempt_str, e
                     );
+                    journal.attempt(&job.url, current_url, retry + 1, attempt_started, Some(e.to_string()));
+                    // 所有候选源都返回 404 时重试也无法恢复
+                    let not_found = file::is_not_found(&e);
                     current_job_error = Some(e);
+                    if not_found {
+                        break;
+                    }
                     if retry < MAX_JOB_RETRIES - 1 {
                         let backoff = Duration::from_secs(1 << retry);
                         println!("DEBUG: Waiting {:?} before next attempt", backoff);
@@ -442,7 +462,8 @@ fn spawn_download_task(
 }
 
 /// 发送取消进度事件
-fn emit_cancelled_progress(window: &Window, bytes: u64, total: u64) {
+fn emit_cancelled_progress(window: &EventSink, stage: ProgressStage, bytes: u64, total: u64) {
+    os_progress::finish(window, OS_PROGRESS_KEY, false);
     let percent = if total > 0 {
         (bytes as f64 / total as f64 * 100.0).round() as u8
     } else {
@@ -452,6 +473,7 @@ fn emit_cancelled_progress(window: &Window, bytes: u64, total: u64) {
     let _ = window.emit(
         "download-progress",
         &DownloadProgress {
+            stage,
             progress: bytes,
             total,
             speed: 0.0,
@@ -465,7 +487,8 @@ fn emit_cancelled_progress(window: &Window, bytes: u64, total: u64) {
 }
 
 /// 发送错误进度事件
-fn emit_error_progress(window: &Window, bytes: u64, total: u64, error_msg: &str) {
+fn emit_error_progress(window: &EventSink, stage: ProgressStage, bytes: u64, total: u64, error_msg: &str) {
+    os_progress::finish(window, OS_PROGRESS_KEY, true);
     let percent = if total > 0 {
         (bytes as f64 / total as f64 * 100.0).round() as u8
     } else {
@@ -475,6 +498,7 @@ fn emit_error_progress(window: &Window, bytes: u64, total: u64, error_msg: &str)
     let _ = window.emit(
         "download-progress",
         &DownloadProgress {
+            stage,
             progress: bytes,
             total,
             speed: 0.0,
@@ -488,10 +512,12 @@ fn emit_error_progress(window: &Window, bytes: u64, total: u64, error_msg: &str)
 }
 
 /// 发送完成进度事件
-fn emit_completed_progress(window: &Window, bytes: u64, total: u64) {
+fn emit_completed_progress(window: &EventSink, stage: ProgressStage, bytes: u64, total: u64) {
+    os_progress::finish(window, OS_PROGRESS_KEY, false);
     let _ = window.emit(
         "download-progress",
         &DownloadProgress {
+            stage,
             progress: bytes,
             total,
             speed: 0.0,
@@ -0,0 +1,177 @@
+//! 下载量预估（只枚举下载任务，不实际下载）
+
+use super::http::get_http_client;
+use super::version::{collect_client_jar, collect_libraries, fetch_version_json};
+use crate::errors::LauncherError;
+use crate::models::DownloadJob;
+use crate::services::config::load_config;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 单个类别的统计
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryEstimate {
+    pub files: u64,
+    pub bytes: u64,
+    /// 本地已存在（大小一致）的文件数
+    pub present_files: u64,
+    pub present_bytes: u64,
+    /// 未声明大小的文件数（无法计入字节数）
+    pub unknown_size_files: u64,
+}
+
+impl CategoryEstimate {
+    fn add_jobs(&mut self, jobs: &[DownloadJob]) {
+        for job in jobs {
+            self.add(&job.path, job.size);
+        }
+    }
+
+    fn add(&mut self, path: &Path, size: u64) {
+        self.files += 1;
+        self.bytes += size;
+        if size == 0 {
+            self.unknown_size_files += 1;
+        }
+        let present = fs::metadata(path)
+            .map(|m| size == 0 || m.len() == size)
+            .unwrap_or(false);
+        if present {
+            self.present_files += 1;
+            self.present_bytes += size;
+        }
+    }
+
+    fn merge(&mut self, other: &CategoryEstimate) {
+        self.files += other.files;
+        self.bytes += other.bytes;
+        self.present_files += other.present_files;
+        self.present_bytes += other.present_bytes;
+        self.unknown_size_files += other.unknown_size_files;
+    }
+}
+
+/// 版本下载预估
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadEstimate {
+    pub version_id: String,
+    pub client: CategoryEstimate,
+    pub libraries: CategoryEstimate,
+    pub assets: CategoryEstimate,
+    pub total_bytes: u64,
+    pub present_bytes: u64,
+    /// 还需要下载的字节数
+    pub download_bytes: u64,
+}
+
+impl DownloadEstimate {
+    fn finish(mut self) -> Self {
+        self.total_bytes = self.client.bytes + self.libraries.bytes + self.assets.bytes;
+        self.present_bytes =
+            self.client.present_bytes + self.libraries.present_bytes + self.assets.present_bytes;
+        self.download_bytes = self.total_bytes.saturating_sub(self.present_bytes);
+        self
+    }
+}
+
+/// 预估下载指定版本需要的文件数和字节数
+pub async fn estimate_version_download(
+    version_id: String,
+    mirror: Option<String>,
+) -> Result<DownloadEstimate, LauncherError> {
+    let is_mirror = mirror.is_some();
+    let base_url = if is_mirror {
+        "https://bmclapi2.bangbang93.com"
+    } else {
+        "https://launchermeta.mojang.com"
+    };
+
+    let config = load_config()?;
+    let game_dir = PathBuf::from(&config.game_dir);
+    let client = get_http_client()?;
+
+    let mut estimate = DownloadEstimate {
+        version_id: version_id.clone(),
+        ..Default::default()
+    };
+
+    // 沿继承链收集：本地 JSON 存在则使用本地，否则从版本清单获取
+    let mut current = Some(version_id);
+    while let Some(id) = current.take() {
+        let version_dir = game_dir.join("versions").join(&id);
+        let local_path = version_dir.join(format!("{}.json", id));
+        let version_json: serde_json::Value = if local_path.exists() {
+            serde_json::from_str(&fs::read_to_string(&local_path)?)?
+        } else {
+            fetch_version_json(&client, &id, is_mirror, base_url).await?.0
+        };
+
+        let mut libraries = Vec::new();
+        collect_libraries(&version_json, &game_dir.join("libraries"), is_mirror, base_url, &mut libraries)?;
+        estimate.libraries.add_jobs(&libraries);
+
+        if let Some(parent) = version_json["inheritsFrom"].as_str() {
+            current = Some(parent.to_string());
+            continue;
+        }
+
+        let mut client_jobs = Vec::new();
+        if collect_client_jar(&version_json, &version_dir, &id, is_mirror, base_url, &mut client_jobs).is_ok() {
+            estimate.client.add_jobs(&client_jobs);
+        }
+        let assets = estimate_assets(&client, &version_json, &game_dir.join("assets"), is_mirror, base_url).await?;
+        estimate.assets.merge(&assets);
+    }
+
+    Ok(estimate.finish())
+}
+
+/// 统计资源文件（资源索引不存在时从网络获取但不写入磁盘）
+async fn estimate_assets(
+    client: &reqwest::Client,
+    version_json: &serde_json::Value,
+    assets_dir: &Path,
+    is_mirror: bool,
+    base_url: &str,
+) -> Result<CategoryEstimate, LauncherError> {
+    let mut estimate = CategoryEstimate::default();
+    let (Some(index_id), Some(index_url)) = (
+        version_json["assetIndex"]["id"].as_str(),
+        version_json["assetIndex"]["url"].as_str(),
+    ) else {
+        return Ok(estimate);
+    };
+
+    let index_path = assets_dir.join("indexes").join(format!("{}.json", index_id));
+    let index_size = version_json["assetIndex"]["size"].as_u64().unwrap_or(0);
+    estimate.add(&index_path, index_size);
+
+    let index_text = if index_path.exists() {
+        fs::read_to_string(&index_path)?
+    } else {
+        let url = if is_mirror {
+            index_url
+                .replace("https://launchermeta.mojang.com", base_url)
+                .replace("https://piston-meta.mojang.com", base_url)
+        } else {
+            index_url.to_string()
+        };
+        client.get(&url).send().await?.text().await?
+    };
+    let index: serde_json::Value = serde_json::from_str(&index_text)?;
+
+    if let Some(objects) = index["objects"].as_object() {
+        for obj in objects.values() {
+            let Some(hash) = obj["hash"].as_str().filter(|h| h.len() > 2) else {
+                continue;
+            };
+            let size = obj["size"].as_u64().unwrap_or(0);
+            estimate.add(&assets_dir.join("objects").join(&hash[..2]).join(hash), size);
+        }
+    }
+
+    Ok(estimate)
+}
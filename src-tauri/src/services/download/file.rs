@@ -2,6 +2,8 @@
 
 use crate::errors::LauncherError;
 use crate::models::DownloadJob;
+use super::mirror_fallback::MirrorFallback;
+use super::mirror_health;
 use crate::utils::file_utils;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
@@ -16,6 +18,7 @@ pub async fn download_file(
     global_cancel: &Arc<AtomicBool>,
     bytes_downloaded: &Arc<AtomicU64>,
     bytes_since_last: &Arc<AtomicU64>,
+    fallback: &MirrorFallback,
 ) -> Result<(), LauncherError> {
     // 先检查取消状态
     if !state.load(Ordering::SeqCst) || global_cancel.load(Ordering::SeqCst) {
@@ -49,36 +52,43 @@ pub async fn download_file(
         }
     }
 
-    // 2. 尝试从指定 URL 下载（支持断点续传）
-    match download_with_resume(http.clone(), url, job, state, global_cancel, bytes_downloaded, bytes_since_last).await {
-        Ok(_) => Ok(()),
-        Err(e) => {
-            // 如果是取消导致的错误，直接返回
-            if e.to_string().contains("cancelled") {
-                return Err(e);
+    // 2. 依次尝试候选源：指定 URL、备用 URL，资源对象还包括其他镜像。
+    //    已因频繁截断被熔断的源排到最后；每个源下载的内容都需通过哈希校验才会被接受
+    let mut candidates = super::assets::asset_object_candidates(job, url);
+    candidates.sort_by_key(|c| mirror_health::is_tripped(c));
+
+    let mut last_error = None;
+    for (i, candidate) in candidates.iter().enumerate() {
+        if i > 0 {
+            println!(
+                "DEBUG: Trying next source for {}: {}",
+                job.path.display(),
+                candidate
+            );
+        }
+        // 3. 从当前源下载（截断时保留的 .part 会从下一个源续传）
+        match download_with_resume(http.clone(), candidate, job, state, global_cancel, bytes_downloaded, bytes_since_last).await {
+            Ok(()) => {
+                fallback.record(candidate, false);
+                return Ok(());
             }
-            // 3. 如果主 URL 失败，尝试备用 URL
-            if let Some(fallback_url) = &job.fallback_url {
-                if should_try_fallback(&e) {
-                    println!(
-                        "DEBUG: Primary URL {} failed ({}), trying fallback: {}",
-                        job.url, e, fallback_url
-                    );
-                    return download_with_resume(
-                        http.clone(),
-                        fallback_url,
-                        job,
-                        state,
-                        global_cancel,
-                        bytes_downloaded,
-                        bytes_since_last,
-                    )
-                    .await;
+            Err(e) => {
+                // 取消或不适合换源的错误直接返回
+                if e.to_string().contains("cancelled") || !should_try_fallback(&e) {
+                    return Err(e);
                 }
+                fallback.record(candidate, is_not_found(&e));
+                println!("DEBUG: Source {} failed: {}", candidate, e);
+                last_error = Some(e);
             }
-            Err(e)
         }
     }
+    Err(last_error.unwrap_or_else(|| LauncherError::Custom(format!("No download source for {}", job.path.display()))))
+}
+
+/// 是否为 404（镜像尚未同步或文件不存在，重试无法恢复）
+pub fn is_not_found(e: &LauncherError) -> bool {
+    matches!(e, LauncherError::Http(err) if err.status() == Some(reqwest::StatusCode::NOT_FOUND))
 }
 
 /// 检查是否应该尝试备用 URL
@@ -93,6 +103,7 @@ fn should_try_fallback(e: &LauncherError) -> bool {
     is_http_error
         || err_str.contains("size or hash mismatch")
         || err_str.contains("File size mismatch")
+        || err_str.contains("Truncated download")
         || err_str.contains("Unexpected Content-Length")
         || err_str.contains("Unexpected Content-Type")
 }
@@ -119,7 +130,7 @@ async fn download_with_resume(
             existing_size,
             tmp_path.display()
         );
-        if file_utils::verify_file(&tmp_path, &job.hash, job.size)? {
+        if file_utils::verify_file_async(&tmp_path, &job.hash, job.size).await? {
             // 文件完整，直接移动
             finalize_download(&tmp_path, &job.path).await?;
             bytes_downloaded.fetch_add(job.size, Ordering::SeqCst);
@@ -131,6 +142,23 @@ async fn download_with_resume(
         }
     }
 
+    // 大文件且没有部分下载时，尝试多连接分块下载
+    if existing_size == 0 {
+        if let Some(parts) = chunked_parts_for(job.size) {
+            match download_in_chunks(&client, url, job, parts, state, global_cancel, bytes_downloaded, bytes_since_last).await {
+                Ok(()) => return Ok(()),
+                Err(e) if e.to_string().contains("cancelled") => return Err(e),
+                Err(e) => {
+                    println!(
+                        "DEBUG: Chunked download failed ({}), falling back to single connection: {}",
+                        e, url
+                    );
+                    let _ = tokio::fs::remove_file(&tmp_path).await;
+                }
+            }
+        }
+    }
+
     // 尝试断点续传
     let resume_from = if existing_size > 0 && job.size > 0 && existing_size < job.size {
         // 检查服务器是否支持 Range 请求
@@ -163,6 +191,152 @@ async fn download_with_resume(
     .await
 }
 
+/// 根据配置判断是否分块下载，返回分块数
+fn chunked_parts_for(size: u64) -> Option<u64> {
+    let config = crate::services::config::load_config().ok()?;
+    let parts = config.chunked_download_parts as u64;
+    let threshold = config.chunked_download_threshold_mb.saturating_mul(1024 * 1024);
+    (parts > 1 && size > 0 && size >= threshold).then_some(parts)
+}
+
+/// 多连接分块下载：按 Range 将文件拆成若干段并行写入同一个临时文件
+#[allow(clippy::too_many_arguments)]
+async fn download_in_chunks(
+    client: &Arc<reqwest::Client>,
+    url: &str,
+    job: &DownloadJob,
+    parts: u64,
+    state: &Arc<AtomicBool>,
+    global_cancel: &Arc<AtomicBool>,
+    bytes_downloaded: &Arc<AtomicU64>,
+    bytes_since_last: &Arc<AtomicU64>,
+) -> Result<(), LauncherError> {
+    if !check_range_support(client, url).await {
+        return Err(LauncherError::Custom(format!("Range not supported for {}", url)));
+    }
+
+    let tmp_path = job.path.with_extension("part");
+    if let Some(parent) = job.path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::File::create(&tmp_path).await?.set_len(job.size).await?;
+
+    println!(
+        "DEBUG: Downloading {} in {} chunks: {}",
+        job.path.display(),
+        parts,
+        url
+    );
+
+    let added = Arc::new(AtomicU64::new(0));
+    let chunk_size = (job.size + parts - 1) / parts;
+    let mut tasks = tokio::task::JoinSet::new();
+    for i in 0..parts {
+        let start = i * chunk_size;
+        if start >= job.size {
+            break;
+        }
+        let end = (start + chunk_size).min(job.size) - 1;
+        let ctx = ChunkContext {
+            client: client.clone(),
+            url: url.to_string(),
+            path: tmp_path.clone(),
+            state: state.clone(),
+            global_cancel: global_cancel.clone(),
+            bytes_downloaded: bytes_downloaded.clone(),
+            bytes_since_last: bytes_since_last.clone(),
+            added: added.clone(),
+        };
+        tasks.spawn(download_range(ctx, start, end));
+    }
+
+    let mut result = Ok(());
+    while let Some(res) = tasks.join_next().await {
+        if let Err(e) = res.map_err(LauncherError::from).and_then(|r| r) {
+            // 任一分块失败则取消其余分块
+            tasks.abort_all();
+            result = Err(e);
+            break;
+        }
+    }
+
+    if result.is_ok() && !file_utils::verify_file_async(&tmp_path, &job.hash, job.size).await? {
+        result = Err(LauncherError::Custom(format!(
+            "File verification failed for {}: size or hash mismatch (chunked download).",
+            tmp_path.display()
+        )));
+    }
+
+    match result {
+        Ok(()) => finalize_download(&tmp_path, &job.path).await,
+        Err(e) => {
+            bytes_downloaded.fetch_sub(added.load(Ordering::SeqCst), Ordering::SeqCst);
+            let _ = tokio::fs::remove_file(&tmp_path).await;
+            Err(e)
+        }
+    }
+}
+
+/// 分块下载任务共享的上下文
+struct ChunkContext {
+    client: Arc<reqwest::Client>,
+    url: String,
+    path: std::path::PathBuf,
+    state: Arc<AtomicBool>,
+    global_cancel: Arc<AtomicBool>,
+    bytes_downloaded: Arc<AtomicU64>,
+    bytes_since_last: Arc<AtomicU64>,
+    /// 本次分块下载已计入进度的字节数（失败时回滚）
+    added: Arc<AtomicU64>,
+}
+
+/// 下载 [start, end] 字节区间并写入临时文件对应位置
+async fn download_range(ctx: ChunkContext, start: u64, end: u64) -> Result<(), LauncherError> {
+    let response = ctx
+        .client
+        .get(&ctx.url)
+        .header("Range", format!("bytes={}-{}", start, end))
+        .send()
+        .await?;
+    if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        return Err(LauncherError::Custom(format!(
+            "Range request returned {} for {}",
+            response.status(),
+            ctx.url
+        )));
+    }
+
+    let mut file = tokio::fs::OpenOptions::new().write(true).open(&ctx.path).await?;
+    file.seek(std::io::SeekFrom::Start(start)).await?;
+
+    let expected = end - start + 1;
+    let mut written = 0u64;
+    let mut response = response;
+    while let Some(chunk) = response.chunk().await? {
+        if !ctx.state.load(Ordering::SeqCst) || ctx.global_cancel.load(Ordering::SeqCst) {
+            return Err(LauncherError::Custom("Download cancelled".to_string()));
+        }
+        file.write_all(&chunk).await?;
+        let len = chunk.len() as u64;
+        written += len;
+        ctx.bytes_downloaded.fetch_add(len, Ordering::Relaxed);
+        ctx.bytes_since_last.fetch_add(len, Ordering::Relaxed);
+        ctx.added.fetch_add(len, Ordering::Relaxed);
+    }
+    file.flush().await?;
+
+    if written < expected {
+        mirror_health::record_truncation(&ctx.url);
+    }
+    if written != expected {
+        return Err(LauncherError::Custom(format!(
+            "File size mismatch for range {}-{}: got {} bytes",
+            start, end, written
+        )));
+    }
+    Ok(())
+}
+
 /// 获取已存在文件的大小
 async fn get_existing_file_size(path: &std::path::Path) -> u64 {
     tokio::fs::metadata(path)
@@ -288,8 +462,18 @@ async fn download_chunk_with_resume(
         file.flush().await?;
         drop(file);
 
+        // 内容被截断：保留已收到的有效前缀，供续传（或备用源续传）使用
+        let received = get_existing_file_size(&tmp_path).await;
+        if job.size > 0 && received < job.size {
+            mirror_health::record_truncation(url);
+            return Err(LauncherError::Custom(format!(
+                "Truncated download for {}: got {} of {} bytes",
+                url, received, job.size
+            )));
+        }
+
         // 验证文件
-        if !file_utils::verify_file(&tmp_path, &job.hash, job.size)? {
+        if !file_utils::verify_file_async(&tmp_path, &job.hash, job.size).await? {
             // 删除损坏的临时文件
             let _ = tokio::fs::remove_file(&tmp_path).await;
             return Err(LauncherError::Custom(format!(
@@ -300,6 +484,7 @@ async fn download_chunk_with_resume(
 
         // 移动文件到最终位置
         finalize_download(&tmp_path, &job.path).await?;
+        mirror_health::record_success(url);
 
         Ok::<(), LauncherError>(())
     }
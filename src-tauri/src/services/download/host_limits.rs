@@ -0,0 +1,89 @@
+//! 按主机的并发限制
+//!
+//! 全部线程同时请求 BMCLAPI 等镜像容易触发限流甚至封禁，而官方 CDN 能承受更多连接。
+//! 每个主机有独立的并发上限和相邻请求的最小间隔：已知主机使用内置默认值，可在配置中
+//! 按主机覆盖；未知主机只受全局下载线程数限制。限制在所有下载会话间共享。
+
+use crate::models::{GameConfig, HostLimit};
+use crate::services::metrics::host_of;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// 已知主机的默认限制（主机名, 最大并发, 请求间隔毫秒）
+const DEFAULT_LIMITS: &[(&str, u32, u64)] = &[
+    ("bmclapi2.bangbang93.com", 8, 20),
+    ("resources.download.minecraft.net", 32, 0),
+    ("libraries.minecraft.net", 16, 0),
+    ("piston-data.mojang.com", 16, 0),
+    ("piston-meta.mojang.com", 8, 0),
+    ("launcher.mojang.com", 8, 0),
+    ("cdn.modrinth.com", 8, 0),
+    ("maven.fabricmc.net", 8, 0),
+    ("maven.quiltmc.org", 8, 0),
+    ("maven.minecraftforge.net", 4, 50),
+    ("maven.neoforged.net", 4, 50),
+];
+
+struct HostGate {
+    limit: HostLimit,
+    permits: Arc<Semaphore>,
+    /// 下一个请求最早的开始时间
+    next_start: Mutex<Instant>,
+}
+
+static GATES: LazyLock<Mutex<HashMap<String, Arc<HostGate>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 生效的限制：内置默认值叠加配置中的覆盖
+pub fn effective_host_limits(config: &GameConfig) -> BTreeMap<String, HostLimit> {
+    let mut limits: BTreeMap<String, HostLimit> = DEFAULT_LIMITS
+        .iter()
+        .map(|(host, max_connections, delay_ms)| {
+            (
+                host.to_string(),
+                HostLimit {
+                    max_connections: *max_connections,
+                    delay_ms: *delay_ms,
+                },
+            )
+        })
+        .collect();
+    limits.extend(config.host_limits.iter().map(|(host, limit)| (host.clone(), *limit)));
+    limits
+}
+
+/// 主机对应的限流器；配置的限制变化后重新创建（已持有的许可不受影响）
+fn gate(host: &str, limits: &BTreeMap<String, HostLimit>) -> Option<Arc<HostGate>> {
+    let limit = *limits.get(host)?;
+    let mut gates = GATES.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(gate) = gates.get(host).filter(|g| g.limit == limit) {
+        return Some(gate.clone());
+    }
+    let gate = Arc::new(HostGate {
+        limit,
+        permits: Arc::new(Semaphore::new(limit.max_connections.max(1) as usize)),
+        next_start: Mutex::new(Instant::now()),
+    });
+    gates.insert(host.to_string(), gate.clone());
+    Some(gate)
+}
+
+/// 等待地址所在主机的下载许可（含请求间隔），下载结束后释放返回的许可；未限制的主机返回 None
+pub async fn acquire(url: &str, limits: &BTreeMap<String, HostLimit>) -> Option<OwnedSemaphorePermit> {
+    let gate = gate(host_of(url), limits)?;
+    let permit = gate.permits.clone().acquire_owned().await.ok()?;
+
+    let wait = {
+        let mut next_start = gate.next_start.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let start = (*next_start).max(now);
+        *next_start = start + Duration::from_millis(gate.limit.delay_ms);
+        start - now
+    };
+    if !wait.is_zero() {
+        tokio::time::sleep(wait).await;
+    }
+    Some(permit)
+}
@@ -1,18 +1,37 @@
 //! 全局 HTTP 客户端管理
 
 use crate::errors::LauncherError;
-use std::sync::Arc;
+use crate::services::http_client::apply_trust;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
-/// 全局 HTTP 客户端（懒加载单例）
-static HTTP_CLIENT: std::sync::OnceLock<Arc<reqwest::Client>> = std::sync::OnceLock::new();
+/// 全局 HTTP 客户端（懒加载，导入 CA 证书后重建）
+static HTTP_CLIENT: RwLock<Option<Arc<reqwest::Client>>> = RwLock::new(None);
+/// 版本清单客户端（懒加载，同上）
+static MANIFEST_CLIENT: RwLock<Option<reqwest::Client>> = RwLock::new(None);
 
 /// 获取全局 HTTP 客户端
 pub fn get_http_client() -> Result<Arc<reqwest::Client>, LauncherError> {
-    let client = HTTP_CLIENT.get_or_init(|| {
-        Arc::new(create_client(16)) // 默认支持 16 线程
-    });
-    Ok(client.clone())
+    if let Ok(guard) = HTTP_CLIENT.read() {
+        if let Some(client) = guard.as_ref() {
+            return Ok(client.clone());
+        }
+    }
+    let client = Arc::new(create_client(16)); // 默认支持 16 线程
+    if let Ok(mut guard) = HTTP_CLIENT.write() {
+        *guard = Some(client.clone());
+    }
+    Ok(client)
+}
+
+/// 丢弃已创建的下载客户端，下次使用时重建
+pub fn reset_http_client() {
+    if let Ok(mut guard) = HTTP_CLIENT.write() {
+        *guard = None;
+    }
+    if let Ok(mut guard) = MANIFEST_CLIENT.write() {
+        *guard = None;
+    }
 }
 
 /// 创建 HTTP 客户端
@@ -29,7 +48,7 @@ fn create_client(max_connections_per_host: usize) -> reqwest::Client {
         reqwest::header::HeaderValue::from_static("identity"),
     );
 
-    reqwest::Client::builder()
+    apply_trust(reqwest::Client::builder())
         .default_headers(default_headers)
         .no_gzip()
         .no_brotli()
@@ -43,11 +62,20 @@ fn create_client(max_connections_per_host: usize) -> reqwest::Client {
         .expect("Failed to create HTTP client")
 }
 
-/// 创建用于版本清单获取的客户端（较短超时）
+/// 获取用于版本清单获取的客户端（较短超时）
 pub fn get_manifest_client() -> Result<reqwest::Client, LauncherError> {
-    reqwest::Client::builder()
+    if let Ok(guard) = MANIFEST_CLIENT.read() {
+        if let Some(client) = guard.as_ref() {
+            return Ok(client.clone());
+        }
+    }
+    let client = apply_trust(reqwest::Client::builder())
         .timeout(Duration::from_secs(30))
         .connect_timeout(Duration::from_secs(10))
         .build()
-        .map_err(|e| LauncherError::Custom(format!("创建HTTP客户端失败: {}", e)))
+        .map_err(|e| LauncherError::Custom(format!("创建HTTP客户端失败: {}", e)))?;
+    if let Ok(mut guard) = MANIFEST_CLIENT.write() {
+        *guard = Some(client.clone());
+    }
+    Ok(client)
 }
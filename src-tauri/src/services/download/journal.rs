@@ -0,0 +1,169 @@
+//! 下载会话日志
+//!
+//! 每次批量下载记录一份 JSONL 日志（任务列表、实际使用的下载地址、重试、耗时和最终结果），
+//! 保存在 `<游戏目录>/logs/downloads/`，只保留最近几次。下载失败的错误信息会附带日志路径，
+//! 用户反馈问题时无需开启调试日志重新复现。
+
+use crate::models::DownloadJob;
+use serde::Serialize;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// 保留的会话日志数量
+const MAX_SESSIONS: usize = 5;
+
+/// 日志文件名前缀
+const JOURNAL_PREFIX: &str = "session-";
+
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+enum JournalEntry<'a> {
+    Session {
+        version: &'a str,
+        started_at: String,
+        jobs: usize,
+        total_bytes: u64,
+        threads: usize,
+    },
+    Job {
+        url: &'a str,
+        path: &'a Path,
+        size: u64,
+    },
+    Attempt {
+        url: &'a str,
+        /// 实际请求的地址（重试时可能切换到官方源）
+        source: &'a str,
+        attempt: usize,
+        elapsed_ms: u64,
+        error: Option<String>,
+    },
+    Finish {
+        status: &'a str,
+        elapsed_ms: u64,
+        failed: usize,
+        error: Option<&'a str>,
+    },
+}
+
+/// 单次下载会话的日志
+pub struct DownloadJournal {
+    path: PathBuf,
+    started: Instant,
+    writer: Mutex<Option<BufWriter<fs::File>>>,
+}
+
+/// 会话日志目录
+pub fn journal_dir(game_dir: &Path) -> PathBuf {
+    game_dir.join("logs").join("downloads")
+}
+
+/// 最近的会话日志，新的在前
+pub fn recent_journals(game_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(journal_dir(game_dir)) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(JOURNAL_PREFIX) && n.ends_with(".jsonl"))
+        })
+        .collect();
+    // 文件名以时间戳开头，按名称倒序即按时间倒序
+    files.sort();
+    files.reverse();
+    files
+}
+
+impl DownloadJournal {
+    /// 创建新的会话日志并清理旧日志；无法写入时日志静默失效，不影响下载
+    pub fn start(game_dir: &Path, version: &str, jobs: &[DownloadJob], threads: usize) -> Self {
+        let dir = journal_dir(game_dir);
+        let name = format!(
+            "{}{}-{}.jsonl",
+            JOURNAL_PREFIX,
+            chrono::Local::now().format("%Y%m%d-%H%M%S"),
+            version.replace(|c: char| !c.is_alphanumeric() && c != '.' && c != '-', "_")
+        );
+        let path = dir.join(name);
+        let writer = fs::create_dir_all(&dir)
+            .and_then(|_| fs::File::create(&path))
+            .map(BufWriter::new)
+            .map_err(|e| log::warn!("无法创建下载日志 {}: {}", path.display(), e))
+            .ok();
+
+        for old in recent_journals(game_dir).into_iter().skip(MAX_SESSIONS) {
+            let _ = fs::remove_file(old);
+        }
+
+        let journal = Self {
+            path,
+            started: Instant::now(),
+            writer: Mutex::new(writer),
+        };
+        journal.write(&JournalEntry::Session {
+            version,
+            started_at: chrono::Local::now().to_rfc3339(),
+            jobs: jobs.len(),
+            total_bytes: jobs.iter().map(|j| j.size).sum(),
+            threads,
+        });
+        for job in jobs {
+            journal.write(&JournalEntry::Job {
+                url: &job.url,
+                path: &job.path,
+                size: job.size,
+            });
+        }
+        journal
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn write(&self, entry: &JournalEntry) {
+        let mut guard = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(writer) = guard.as_mut() else {
+            return;
+        };
+        let written = serde_json::to_writer(&mut *writer, entry)
+            .map_err(std::io::Error::from)
+            .and_then(|_| writer.write_all(b"\n"));
+        if let Err(e) = written {
+            log::warn!("写入下载日志失败: {}", e);
+            *guard = None;
+        }
+    }
+
+    /// 记录一次下载尝试
+    pub fn attempt(&self, url: &str, source: &str, attempt: usize, started: Instant, error: Option<String>) {
+        self.write(&JournalEntry::Attempt {
+            url,
+            source,
+            attempt,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            error,
+        });
+    }
+
+    /// 记录会话结果并写入磁盘
+    pub fn finish(&self, status: &str, failed: usize, error: Option<&str>) {
+        self.write(&JournalEntry::Finish {
+            status,
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            failed,
+            error,
+        });
+        let mut guard = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(writer) = guard.as_mut() {
+            let _ = writer.flush();
+        }
+    }
+}
@@ -4,12 +4,26 @@ use super::http::get_manifest_client;
 use crate::errors::LauncherError;
 use crate::models::VersionManifest;
 use crate::services::config::load_config;
+use crate::services::{meta_cache, metrics};
 use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
 
-/// 获取 Minecraft 版本列表
+/// 版本清单缓存键
+const MANIFEST_CACHE_KEY: &str = "version_manifest";
+
+/// 获取 Minecraft 版本列表（优先使用缓存）
 pub async fn get_versions() -> Result<VersionManifest, LauncherError> {
+    meta_cache::get_or_fetch(MANIFEST_CACHE_KEY, meta_cache::DEFAULT_TTL, fetch_manifest).await
+}
+
+/// 重新获取版本列表并更新缓存
+pub async fn refresh_versions() -> Result<(), LauncherError> {
+    meta_cache::refresh(MANIFEST_CACHE_KEY, fetch_manifest).await
+}
+
+/// 依次尝试各个源获取版本列表
+async fn fetch_manifest() -> Result<VersionManifest, LauncherError> {
     let config = load_config()?;
     let log_dir = PathBuf::from(config.game_dir).join("logs");
     fs::create_dir_all(&log_dir)?;
@@ -36,14 +50,17 @@ pub async fn get_versions() -> Result<VersionManifest, LauncherError> {
         chrono::Local::now().to_rfc3339()
     )?;
 
+    let _total_span = metrics::span("manifest_fetch");
     for (i, url) in urls.iter().enumerate() {
         writeln!(log, "尝试第{}个源: {}", i + 1, url)?;
+        let mut span = metrics::span(format!("manifest_fetch:{}", metrics::host_of(url)));
         match fetch_versions(&client, url, &mut log).await {
             Ok(manifest) => {
                 writeln!(log, "成功获取版本列表，共{}个版本", manifest.versions.len())?;
                 return Ok(manifest);
             }
             Err(e) => {
+                span.fail();
                 writeln!(log, "获取失败: {}", e)?;
                 continue;
             }
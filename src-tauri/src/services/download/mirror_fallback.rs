@@ -0,0 +1,97 @@
+//! 镜像缺失文件时回退官方源
+//!
+//! BMCLAPI 对刚发布的快照同步较慢，选择镜像下载时每个文件都会先收到 404 再换源。
+//! 本次下载会话中镜像的 404 比例过高时，剩余任务直接使用官方源，并通知前端原因。
+
+use crate::models::DownloadJob;
+use crate::services::metrics::host_of;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+/// BMCLAPI 主机名
+const BMCLAPI_HOST: &str = "bmclapi2.bangbang93.com";
+/// 样本数达到该值后才判断是否切换
+const MIN_SAMPLES: u32 = 8;
+/// 404 比例达到该值时切换到官方源
+const SWITCH_RATE: f64 = 0.5;
+
+/// `download-mirror-fallback` 事件内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorFallbackEvent {
+    pub version_id: String,
+    pub host: String,
+    pub attempts: u32,
+    pub not_found: u32,
+    pub message: String,
+}
+
+/// 单次下载会话的镜像 404 统计
+pub struct MirrorFallback {
+    version_id: String,
+    attempts: AtomicU32,
+    not_found: AtomicU32,
+    switched: AtomicBool,
+    on_switch: Box<dyn Fn(MirrorFallbackEvent) + Send + Sync>,
+}
+
+impl MirrorFallback {
+    pub fn new(
+        version_id: &str,
+        on_switch: impl Fn(MirrorFallbackEvent) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            version_id: version_id.to_string(),
+            attempts: AtomicU32::new(0),
+            not_found: AtomicU32::new(0),
+            switched: AtomicBool::new(false),
+            on_switch: Box::new(on_switch),
+        }
+    }
+
+    fn is_mirror(url: &str) -> bool {
+        host_of(url) == BMCLAPI_HOST
+    }
+
+    /// 记录一次从镜像下载的结果，404 比例过高时切换到官方源
+    pub fn record(&self, url: &str, not_found: bool) {
+        if !Self::is_mirror(url) || self.switched.load(Ordering::SeqCst) {
+            return;
+        }
+        let attempts = self.attempts.fetch_add(1, Ordering::SeqCst) + 1;
+        let missing = if not_found {
+            self.not_found.fetch_add(1, Ordering::SeqCst) + 1
+        } else {
+            self.not_found.load(Ordering::SeqCst)
+        };
+        if attempts < MIN_SAMPLES || (missing as f64) < attempts as f64 * SWITCH_RATE {
+            return;
+        }
+        // 只通知一次
+        if self.switched.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let message = format!(
+            "镜像 {} 缺少版本 {} 的 {}/{} 个文件（可能尚未同步），本次剩余文件改用官方源下载",
+            BMCLAPI_HOST, self.version_id, missing, attempts
+        );
+        log::warn!("{}", message);
+        (self.on_switch)(MirrorFallbackEvent {
+            version_id: self.version_id.clone(),
+            host: BMCLAPI_HOST.to_string(),
+            attempts,
+            not_found: missing,
+            message,
+        });
+    }
+
+    /// 任务本次使用的主地址：已切换且任务有官方备用地址时使用备用地址
+    pub fn primary_url<'a>(&self, job: &'a DownloadJob) -> &'a str {
+        match job.fallback_url.as_deref() {
+            Some(fallback) if self.switched.load(Ordering::SeqCst) && Self::is_mirror(&job.url) => {
+                fallback
+            }
+            _ => &job.url,
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! 镜像健康统计
+//!
+//! 按主机记录下载次数和内容截断次数。截断率过高的镜像会被熔断，
+//! 之后的下载直接使用备用源。
+
+use crate::services::metrics::host_of;
+use crate::services::telemetry::{self, TelemetryEvent};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+/// 样本数达到该值后才判断熔断，避免偶发截断误伤
+const MIN_SAMPLES: u32 = 5;
+/// 截断率超过该值时熔断
+const TRIP_RATE: f64 = 0.5;
+
+#[derive(Debug, Default)]
+struct HostStats {
+    requests: u32,
+    truncations: u32,
+}
+
+static STATS: LazyLock<Mutex<HashMap<String, HostStats>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn update(url: &str, truncated: bool) {
+    if let Ok(mut stats) = STATS.lock() {
+        let entry = stats.entry(host_of(url).to_string()).or_default();
+        entry.requests += 1;
+        if truncated {
+            entry.truncations += 1;
+        }
+    }
+}
+
+/// 记录一次完整下载
+pub fn record_success(url: &str) {
+    update(url, false);
+}
+
+/// 记录一次内容截断
+pub fn record_truncation(url: &str) {
+    update(url, true);
+    telemetry::record(TelemetryEvent::MirrorFailure {
+        host: host_of(url).to_string(),
+        reason: "truncated".to_string(),
+    });
+    log::warn!(
+        "镜像 {} 返回了截断的内容，截断率 {:.0}%",
+        host_of(url),
+        truncation_rate(url) * 100.0
+    );
+}
+
+/// 主机的截断率
+pub fn truncation_rate(url: &str) -> f64 {
+    STATS
+        .lock()
+        .ok()
+        .and_then(|stats| {
+            stats
+                .get(host_of(url))
+                .map(|s| s.truncations as f64 / s.requests.max(1) as f64)
+        })
+        .unwrap_or(0.0)
+}
+
+/// 主机是否已被熔断
+pub fn is_tripped(url: &str) -> bool {
+    let Ok(stats) = STATS.lock() else {
+        return false;
+    };
+    stats.get(host_of(url)).is_some_and(|s| {
+        s.requests >= MIN_SAMPLES && s.truncations as f64 / s.requests as f64 > TRIP_RATE
+    })
+}
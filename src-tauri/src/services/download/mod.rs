@@ -5,15 +5,25 @@
 //! - 批量文件下载
 //! - 单文件下载
 //! - 版本清单获取
+//! - 下载会话日志
 
-pub mod batch;
+mod assets;
+mod batch;
+mod estimate;
 mod file;
+mod host_limits;
 mod http;
+mod journal;
 mod manifest;
+mod mirror_fallback;
+mod mirror_health;
 mod state;
 mod version;
 
 pub use batch::download_all_files;
-pub use http::get_http_client;
-pub use manifest::get_versions;
+pub use estimate::{estimate_version_download, DownloadEstimate};
+pub use host_limits::effective_host_limits;
+pub use http::{get_http_client, reset_http_client};
+pub use journal::recent_journals;
+pub use manifest::{get_versions, refresh_versions};
 pub use version::process_and_download_version;
@@ -5,17 +5,22 @@ use super::http::get_http_client;
 use crate::errors::LauncherError;
 use crate::models::{DownloadJob, VersionManifest};
 use crate::services::config::load_config;
+use crate::services::instance_lock::{self, InstanceOperation};
+use crate::services::{base_version, log_events, metrics};
+use crate::utils::maven::{self, MavenCoordinate};
 use log::info;
 use std::fs;
 use std::path::PathBuf;
-use tauri::Window;
+use crate::services::event_sink::EventSink;
 
 /// 处理并下载指定版本
 pub async fn process_and_download_version(
     version_id: String,
     mirror: Option<String>,
-    window: &Window,
+    window: &EventSink,
 ) -> Result<(), LauncherError> {
+    let _span = metrics::span("install_version");
+    let _lock = instance_lock::acquire(&version_id, InstanceOperation::Install)?;
     let is_mirror = mirror.is_some();
     let base_url = if is_mirror {
         "https://bmclapi2.bangbang93.com"
@@ -50,7 +55,19 @@ pub async fn process_and_download_version(
                 inherits_from.to_string(),
                 mirror.clone(),
                 window,
-            )).await?;
+            ))
+            .await
+            .map_err(|e| match e {
+                // 标明是哪个实例继承了无法安装的版本
+                LauncherError::VersionNotInstallable { version, required_by: None, suggestions } => {
+                    LauncherError::VersionNotInstallable {
+                        version,
+                        required_by: Some(version_id.clone()),
+                        suggestions,
+                    }
+                }
+                other => other,
+            })?;
             
             // 返回，因为基础版本已经下载完成
             // 整合包的库文件需要单独处理
@@ -60,34 +77,7 @@ pub async fn process_and_download_version(
         (version_id.clone(), local_json, local_text)
     } else {
         // 从网络获取版本信息
-        let manifest: VersionManifest = client
-            .get(&format!("{}/mc/game/version_manifest.json", base_url))
-            .send()
-            .await?
-            .json()
-            .await?;
-
-        let version = manifest
-            .versions
-            .iter()
-            .find(|v| v.id == version_id)
-            .ok_or_else(|| LauncherError::Custom(format!("版本 {} 不存在", version_id)))?;
-
-        // 获取版本 JSON
-        let version_json_url = if is_mirror {
-            version
-                .url
-                .replace("https://launchermeta.mojang.com", base_url)
-                .replace("https://piston-meta.mojang.com", base_url)
-        } else {
-            version.url.clone()
-        };
-
-        let text = client.get(&version_json_url).send().await?.text().await?;
-        let version_json: serde_json::Value = serde_json::from_str(&text)
-            .or_else(|_| serde_json::from_str(text.trim_start_matches('\u{feff}')))
-            .map_err(|_| LauncherError::Custom(format!("无法解析版本JSON for {}", version_id)))?;
-        
+        let (version_json, text) = fetch_version_json(&client, &version_id, is_mirror, base_url).await?;
         (version_id.clone(), version_json, text)
     };
 
@@ -97,7 +87,7 @@ pub async fn process_and_download_version(
     // 添加客户端 JAR
     collect_client_jar(&version_json, &version_dir, &actual_version_id, is_mirror, base_url, &mut downloads)?;
 
-    // 添加资源文件
+    // 添加资源文件（仅缺失或损坏的对象）
     collect_assets(
         &client,
         &version_json,
@@ -105,6 +95,7 @@ pub async fn process_and_download_version(
         is_mirror,
         base_url,
         &mut downloads,
+        window,
     )
     .await?;
 
@@ -117,6 +108,9 @@ pub async fn process_and_download_version(
             // 保存版本元数据文件
             let version_json_path = version_dir.join(format!("{}.json", actual_version_id));
             fs::write(version_json_path, text)?;
+            if let Some(versions_dir) = version_dir.parent() {
+                crate::services::version_index::invalidate(versions_dir, &actual_version_id);
+            }
             Ok(())
         }
         Err(e) => {
@@ -132,13 +126,55 @@ pub async fn process_and_download_version(
     }
 }
 
+/// 从版本清单获取指定版本的 JSON（返回解析结果和原始文本）
+pub(super) async fn fetch_version_json(
+    client: &reqwest::Client,
+    version_id: &str,
+    is_mirror: bool,
+    base_url: &str,
+) -> Result<(serde_json::Value, String), LauncherError> {
+    let manifest: VersionManifest = client
+        .get(&format!("{}/mc/game/version_manifest.json", base_url))
+        .send()
+        .await?
+        .json()
+        .await?;
+
+    let version = manifest
+        .versions
+        .iter()
+        .find(|v| v.id == version_id)
+        .ok_or_else(|| LauncherError::VersionNotInstallable {
+            version: version_id.to_string(),
+            required_by: None,
+            suggestions: base_version::closest_versions(&manifest, version_id),
+        })?;
+
+    // 获取版本 JSON
+    let version_json_url = if is_mirror {
+        version
+            .url
+            .replace("https://launchermeta.mojang.com", base_url)
+            .replace("https://piston-meta.mojang.com", base_url)
+    } else {
+        version.url.clone()
+    };
+
+    let text = client.get(&version_json_url).send().await?.text().await?;
+    let version_json: serde_json::Value = serde_json::from_str(&text)
+        .or_else(|_| serde_json::from_str(text.trim_start_matches('\u{feff}')))
+        .map_err(|_| LauncherError::Custom(format!("无法解析版本JSON for {}", version_id)))?;
+
+    Ok((version_json, text))
+}
+
 /// 下载整合包/mod加载器的库文件
 async fn download_modpack_libraries(
     version_json: &serde_json::Value,
     libraries_base_dir: &PathBuf,
     is_mirror: bool,
     base_url: &str,
-    window: &Window,
+    window: &EventSink,
 ) -> Result<(), LauncherError> {
     let mut downloads = Vec::new();
     
@@ -157,7 +193,7 @@ async fn download_modpack_libraries(
 }
 
 /// 收集客户端 JAR 下载任务
-fn collect_client_jar(
+pub(super) fn collect_client_jar(
     version_json: &serde_json::Value,
     version_dir: &PathBuf,
     version_id: &str,
@@ -202,6 +238,7 @@ async fn collect_assets(
     is_mirror: bool,
     base_url: &str,
     downloads: &mut Vec<DownloadJob>,
+    window: &EventSink,
 ) -> Result<(), LauncherError> {
     let assets_index_id = version_json["assetIndex"]["id"]
         .as_str()
@@ -232,6 +269,7 @@ async fn collect_assets(
     let index_content = fs::read_to_string(&assets_index_path)?;
     let index: serde_json::Value = serde_json::from_str(&index_content)?;
 
+    let mut asset_jobs = Vec::new();
     if let Some(objects) = index["objects"].as_object() {
         for (_path, obj) in objects {
             let hash = obj["hash"]
@@ -254,7 +292,7 @@ async fn collect_assets(
             };
             let file_path = assets_base_dir.join("objects").join(&hash[..2]).join(hash);
 
-            downloads.push(DownloadJob {
+            asset_jobs.push(DownloadJob {
                 url: download_url,
                 fallback_url: if is_mirror { Some(original_url) } else { None },
                 path: file_path,
@@ -264,11 +302,30 @@ async fn collect_assets(
         }
     }
 
+    // 与本地已校验对象比对，只入队缺失或损坏的对象
+    let assets_dir = assets_base_dir.clone();
+    let index_id = assets_index_id.to_string();
+    let (queued, diff) = tokio::task::spawn_blocking(move || {
+        super::assets::diff_asset_jobs(&assets_dir, &index_id, asset_jobs)
+    })
+    .await??;
+
+    log_events::emit(
+        window,
+        "log-debug",
+        format!(
+            "资源文件: 共 {} 个，已存在 {} 个，需下载 {} 个",
+            diff.total, diff.skipped, diff.queued
+        ),
+    );
+    let _ = window.emit("assets-diff", &diff);
+
+    downloads.extend(queued);
     Ok(())
 }
 
 /// 收集库文件下载任务
-fn collect_libraries(
+pub(super) fn collect_libraries(
     version_json: &serde_json::Value,
     libraries_base_dir: &PathBuf,
     is_mirror: bool,
@@ -305,27 +362,6 @@ fn collect_libraries(
     Ok(())
 }
 
-/// 将 Maven 坐标转换为文件路径
-fn maven_name_to_path(name: &str) -> Option<String> {
-    let parts: Vec<&str> = name.split(':').collect();
-    if parts.len() < 3 {
-        return None;
-    }
-    
-    let group = parts[0].replace('.', "/");
-    let artifact = parts[1];
-    let version = parts[2];
-    let classifier = if parts.len() > 3 { Some(parts[3]) } else { None };
-    
-    let filename = if let Some(c) = classifier {
-        format!("{}-{}-{}.jar", artifact, version, c)
-    } else {
-        format!("{}-{}.jar", artifact, version)
-    };
-    
-    Some(format!("{}/{}/{}/{}", group, artifact, version, filename))
-}
-
 /// 从库名称创建下载任务 (用于没有 downloads.artifact 的 Forge 库)
 fn create_library_job_from_name(
     lib: &serde_json::Value,
@@ -334,7 +370,7 @@ fn create_library_job_from_name(
     base_url: &str,
 ) -> Option<DownloadJob> {
     let name = lib["name"].as_str()?;
-    let maven_path = maven_name_to_path(name)?;
+    let maven_path = maven::maven_to_path(name)?;
     
     let target_path = libraries_base_dir.join(&maven_path);
     
@@ -510,31 +546,17 @@ fn create_natives_job_from_name(
     is_mirror: bool,
     base_url: &str,
 ) -> Option<DownloadJob> {
-    let name = lib["name"].as_str()?;
-    let parts: Vec<&str> = name.split(':').collect();
-    if parts.len() < 3 {
-        return None;
-    }
-
-    let group_id = parts[0].replace('.', "/");
-    let artifact_id = parts[1];
-    let version = parts[2];
+    let mut coordinate = MavenCoordinate::parse(lib["name"].as_str()?)?;
     let classifier = os_classifier.replace(
         "${arch}",
         if cfg!(target_pointer_width = "64") { "64" } else { "32" },
     );
 
-    let natives_path = if artifact_id == "lwjgl" {
-        format!(
-            "{}/{}-platform/{}/{}-platform-{}-{}.jar",
-            group_id, artifact_id, version, artifact_id, version, classifier
-        )
-    } else {
-        format!(
-            "{}/{}/{}/{}-{}-{}.jar",
-            group_id, artifact_id, version, artifact_id, version, classifier
-        )
-    };
+    // 旧版 LWJGL 的 natives 位于 lwjgl-platform 构件下
+    if coordinate.artifact == "lwjgl" {
+        coordinate.artifact = "lwjgl-platform".to_string();
+    }
+    let natives_path = coordinate.with_classifier(classifier).path();
 
     let natives_url = format!("https://libraries.minecraft.net/{}", natives_path);
     let download_url = if is_mirror {
@@ -0,0 +1,115 @@
+//! 进度和日志事件的接收方
+//!
+//! 核心服务通过 [`EventSink`] 发送进度、日志等事件，不直接依赖 Tauri 窗口。桌面端由
+//! 控制器层把 `tauri::Window` 包装成接收方；嵌入到其他程序或命令行工具时，实现
+//! [`ProgressSink`] 即可自行处理事件。
+
+use crate::errors::LauncherError;
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// 事件接收方
+pub trait ProgressSink: Send + Sync + 'static {
+    /// 接收事件（事件名与前端监听的事件名一致，内容为序列化后的 JSON）
+    fn send(&self, event: &str, payload: Value) -> Result<(), LauncherError>;
+
+    /// 显示系统级总进度（任务栏、程序坞等），默认忽略
+    fn show_os_progress(&self, _percent: u8) {}
+
+    /// 清除系统级进度，`failed` 为 true 时显示错误状态，默认忽略
+    fn clear_os_progress(&self, _failed: bool) {}
+}
+
+/// 忽略所有事件
+struct SilentSink;
+
+impl ProgressSink for SilentSink {
+    fn send(&self, _event: &str, _payload: Value) -> Result<(), LauncherError> {
+        Ok(())
+    }
+}
+
+/// 可在线程间共享的事件接收方句柄
+#[derive(Clone)]
+pub struct EventSink(Arc<dyn ProgressSink>);
+
+impl EventSink {
+    pub fn new(sink: impl ProgressSink) -> Self {
+        Self(Arc::new(sink))
+    }
+
+    /// 不处理任何事件
+    pub fn silent() -> Self {
+        Self::new(SilentSink)
+    }
+
+    /// 发送事件
+    pub fn emit<S: Serialize>(&self, event: &str, payload: S) -> Result<(), LauncherError> {
+        self.0.send(event, serde_json::to_value(payload)?)
+    }
+
+    pub fn show_os_progress(&self, percent: u8) {
+        self.0.show_os_progress(percent);
+    }
+
+    pub fn clear_os_progress(&self, failed: bool) {
+        self.0.clear_os_progress(failed);
+    }
+}
+
+/// 测试用：记录收到的所有事件和系统进度
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub(crate) struct CollectingSink {
+    pub events: Arc<std::sync::Mutex<Vec<(String, Value)>>>,
+    pub os_progress: Arc<std::sync::Mutex<Vec<Option<u8>>>>,
+}
+
+#[cfg(test)]
+impl ProgressSink for CollectingSink {
+    fn send(&self, event: &str, payload: Value) -> Result<(), LauncherError> {
+        self.events.lock().unwrap().push((event.to_string(), payload));
+        Ok(())
+    }
+
+    fn show_os_progress(&self, percent: u8) {
+        self.os_progress.lock().unwrap().push(Some(percent));
+    }
+
+    fn clear_os_progress(&self, _failed: bool) {
+        self.os_progress.lock().unwrap().push(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CollectingSink, EventSink};
+    use crate::services::os_progress;
+    use serde_json::json;
+
+    #[test]
+    fn emit_serializes_payload() {
+        let collector = CollectingSink::default();
+        let sink = EventSink::new(collector.clone());
+        sink.emit("log-info", "hello").unwrap();
+        sink.emit("download-progress", json!({ "percent": 50 })).unwrap();
+
+        let events = collector.events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0], ("log-info".to_string(), json!("hello")));
+        assert_eq!(events[1].1["percent"], 50);
+    }
+
+    #[test]
+    fn os_progress_averages_operations() {
+        let collector = CollectingSink::default();
+        let sink = EventSink::new(collector.clone());
+        os_progress::update(&sink, "sink-test-a", 20);
+        os_progress::update(&sink, "sink-test-b", 60);
+        os_progress::finish(&sink, "sink-test-a", false);
+        os_progress::finish(&sink, "sink-test-b", false);
+
+        assert_eq!(*collector.os_progress.lock().unwrap(), vec![Some(20), Some(40), Some(60), None]);
+    }
+}
@@ -1,12 +1,14 @@
 use crate::errors::LauncherError;
-use crate::models::DownloadJob;
+use crate::models::{DownloadJob, VerificationLevel};
 use crate::services::config::load_config;
-use crate::utils::file_utils;
+use crate::services::paths;
+use crate::utils::{file_utils, maven};
 use log::{debug, info};
 use reqwest::Client;
 use serde::Serialize;
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Serialize)]
 pub struct FileVerificationResult {
@@ -35,7 +37,7 @@ pub async fn verify_single_file(
         0
     };
 
-    let is_valid = file_utils::verify_file(&job.path, &job.hash, job.size)?;
+    let is_valid = file_utils::verify_file_async(&job.path, &job.hash, job.size).await?;
 
     Ok(FileVerificationResult {
         file_name,
@@ -129,23 +131,121 @@ pub async fn batch_repair_files(
     Ok(results)
 }
 
-pub async fn validate_version_files(version_id: String) -> Result<Vec<String>, LauncherError> {
-    let config = load_config()?;
-    let game_dir = PathBuf::from(&config.game_dir);
-    let version_dir = game_dir.join("versions").join(&version_id);
-    let version_json_path = version_dir.join(format!("{}.json", &version_id));
+/// 某个校验级别的预计耗时
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationEstimate {
+    pub level: VerificationLevel,
+    /// 需要检查的文件数
+    pub files: usize,
+    /// 需要计算哈希的字节数
+    pub hash_bytes: u64,
+    pub estimated_ms: u64,
+}
+
+/// Standard 级别抽样校验的资源文件数量
+const ASSET_SAMPLE_SIZE: usize = 256;
+
+/// 估算耗时用的吞吐量：每秒检查的文件数、每秒哈希的字节数
+const STAT_FILES_PER_SEC: u64 = 20_000;
+const HASH_BYTES_PER_SEC: u64 = 200 * 1024 * 1024;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FileKind {
+    Jar,
+    Library,
+    Natives,
+    Asset,
+}
+
+impl FileKind {
+    fn label(self) -> &'static str {
+        match self {
+            FileKind::Jar => "主游戏JAR文件",
+            FileKind::Library => "库文件",
+            FileKind::Natives => "Natives库文件",
+            FileKind::Asset => "资源文件",
+        }
+    }
+}
+
+/// 待校验的文件
+struct FileCheck {
+    kind: FileKind,
+    path: PathBuf,
+    sha1: Option<String>,
+    size: Option<u64>,
+}
+
+/// 从版本 JSON 收集到的待校验文件
+struct VersionFiles {
+    game_dir: PathBuf,
+    files: Vec<FileCheck>,
+    /// 版本 JSON 缺失等无法继续收集的问题
+    problems: Vec<String>,
+}
+
+impl VersionFiles {
+    /// 指定级别下需要计算哈希的文件（`files` 中的下标）
+    fn hashed(&self, level: VerificationLevel) -> HashSet<usize> {
+        let mut hashed = HashSet::new();
+        let mut assets = Vec::new();
+        for (i, file) in self.files.iter().enumerate().filter(|(_, f)| f.sha1.is_some()) {
+            match (level, file.kind) {
+                (VerificationLevel::Quick, _) => {}
+                (VerificationLevel::Standard, FileKind::Asset) => assets.push(i),
+                _ => {
+                    hashed.insert(i);
+                }
+            }
+        }
+        if !assets.is_empty() {
+            // 按步长抽样，起点随时间变化，多次校验可以覆盖不同的资源
+            let step = (assets.len() / ASSET_SAMPLE_SIZE).max(1);
+            let offset = chrono::Utc::now().timestamp().unsigned_abs() as usize % step;
+            hashed.extend(assets.into_iter().skip(offset).step_by(step));
+        }
+        hashed
+    }
+
+    fn estimate(&self, level: VerificationLevel) -> VerificationEstimate {
+        let hash_bytes: u64 = self
+            .hashed(level)
+            .into_iter()
+            .filter_map(|i| self.files[i].size)
+            .sum();
+        let estimated_ms = self.files.len() as u64 * 1000 / STAT_FILES_PER_SEC
+            + hash_bytes * 1000 / HASH_BYTES_PER_SEC;
+        VerificationEstimate {
+            level,
+            files: self.files.len(),
+            hash_bytes,
+            estimated_ms,
+        }
+    }
+}
 
-    let mut missing_files = Vec::new();
+/// 读取版本继承链，收集主 JAR、库文件和资源文件
+fn collect_version_files(version_id: &str) -> Result<VersionFiles, LauncherError> {
+    let config = load_config()?;
+    let game_dir = paths::instance_game_dir(&config, version_id);
+    let version_dir = game_dir.join("versions").join(version_id);
+    let version_json_path = version_dir.join(format!("{}.json", version_id));
+
+    let mut collected = VersionFiles {
+        game_dir: game_dir.clone(),
+        files: Vec::new(),
+        problems: Vec::new(),
+    };
 
-    info!("验证版本文件: {}", version_id);
     info!("版本 JSON 路径: {}", version_json_path.display());
 
     if !version_json_path.exists() {
-        missing_files.push(format!(
+        collected.problems.push(format!(
             "版本JSON文件不存在: {}",
             version_json_path.display()
         ));
-        return Ok(missing_files);
+        return Ok(collected);
     }
 
     let version_json_str = fs::read_to_string(&version_json_path)?;
@@ -163,20 +263,6 @@ pub async fn validate_version_files(version_id: String) -> Result<Vec<String>, L
     let jar_version = find_jar_version(&version_json, &game_dir)?;
     debug!("JAR 版本: {}", jar_version);
 
-    // 主游戏 JAR 文件路径
-    let main_game_jar_path = game_dir
-        .join("versions")
-        .join(&jar_version)
-        .join(format!("{}.jar", &jar_version));
-    
-    if !main_game_jar_path.exists() {
-        info!("主游戏JAR文件不存在: {}", main_game_jar_path.display());
-        missing_files.push(format!(
-            "主游戏JAR文件不存在: {}",
-            main_game_jar_path.display()
-        ));
-    }
-
     // 递归验证整个继承链的版本 JSON 文件，并检查所有库
     let mut versions_to_check = vec![version_json.clone()];
     let mut current_json = version_json.clone();
@@ -190,7 +276,7 @@ pub async fn validate_version_files(version_id: String) -> Result<Vec<String>, L
         
         if !base_version_json_path.exists() {
             info!("基础版本JSON文件不存在: {}", base_version_json_path.display());
-            missing_files.push(format!(
+            collected.problems.push(format!(
                 "基础版本JSON文件不存在: {}",
                 base_version_json_path.display()
             ));
@@ -204,26 +290,137 @@ pub async fn validate_version_files(version_id: String) -> Result<Vec<String>, L
         current_json = parent_json;
     }
 
+    // 主游戏 JAR 文件，哈希取自声明该 JAR 的版本 JSON
+    let client = versions_to_check
+        .iter()
+        .find(|v| v["id"].as_str() == Some(jar_version.as_str()))
+        .map(|v| &v["downloads"]["client"]);
+    collected.files.push(FileCheck {
+        kind: FileKind::Jar,
+        path: game_dir
+            .join("versions")
+            .join(&jar_version)
+            .join(format!("{}.jar", &jar_version)),
+        sha1: client.and_then(|c| c["sha1"].as_str()).map(String::from),
+        size: client.and_then(|c| c["size"].as_u64()),
+    });
+
     // 检查所有版本（包括继承链）中声明的库
     for ver_json in &versions_to_check {
         let ver_id = ver_json["id"].as_str().unwrap_or("unknown");
         if let Some(libraries) = ver_json["libraries"].as_array() {
             debug!("检查版本 {} 的 {} 个库", ver_id, libraries.len());
-            for lib in libraries {
-                check_library(lib, &libraries_base_dir, &mut missing_files);
-            }
+            collected
+                .files
+                .extend(libraries.iter().filter_map(|lib| library_file(lib, &libraries_base_dir)));
         } else {
             debug!("版本 {} 没有 libraries 数组", ver_id);
         }
     }
 
-    info!("验证完成，发现 {} 个缺失文件", missing_files.len());
+    // 资源索引由继承链中最近声明 assetIndex 的版本决定
+    if let Some(index_id) = versions_to_check.iter().find_map(|v| v["assetIndex"]["id"].as_str()) {
+        let assets_dir = game_dir.join("assets");
+        let index_path = assets_dir.join("indexes").join(format!("{}.json", index_id));
+        let index: Option<serde_json::Value> = fs::read_to_string(&index_path)
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok());
+        match index.as_ref().and_then(|i| i["objects"].as_object()) {
+            Some(objects) => {
+                for object in objects.values() {
+                    let Some(hash) = object["hash"].as_str().filter(|h| h.len() > 2) else {
+                        continue;
+                    };
+                    collected.files.push(FileCheck {
+                        kind: FileKind::Asset,
+                        path: assets_dir.join("objects").join(&hash[..2]).join(hash),
+                        sha1: Some(hash.to_string()),
+                        size: object["size"].as_u64(),
+                    });
+                }
+            }
+            None => collected
+                .problems
+                .push(format!("资源索引不存在或已损坏: {}", index_path.display())),
+        }
+    }
+
+    Ok(collected)
+}
+
+/// 按级别检查收集到的文件，返回问题列表
+fn check_files(collected: &VersionFiles, level: VerificationLevel) -> Vec<String> {
+    let mut problems = collected.problems.clone();
+    let hashed = collected.hashed(level);
+
+    for (i, file) in collected.files.iter().enumerate() {
+        let label = file.kind.label();
+        let Ok(metadata) = fs::metadata(&file.path) else {
+            debug!("{}缺失: {}", label, file.path.display());
+            problems.push(format!("{}不存在: {}", label, file.path.display()));
+            continue;
+        };
+        if file.size.is_some_and(|size| size > 0 && size != metadata.len()) {
+            problems.push(format!("{}大小不匹配: {}", label, file.path.display()));
+            continue;
+        }
+        if !hashed.contains(&i) {
+            continue;
+        }
+        let expected = file.sha1.as_deref().unwrap_or_default();
+        match file_utils::sha1_file(&file.path, None) {
+            Ok(hash) if hash.eq_ignore_ascii_case(expected) => {}
+            Ok(_) => problems.push(format!("{}哈希不匹配: {}", label, file.path.display())),
+            Err(e) => problems.push(format!("无法读取{} {}: {}", label, file.path.display(), e)),
+        }
+    }
+    problems
+}
+
+/// 按级别校验版本文件，返回缺失或损坏的文件列表
+pub async fn validate_version_files(
+    version_id: String,
+    level: VerificationLevel,
+) -> Result<Vec<String>, LauncherError> {
+    info!("验证版本文件: {} (级别: {:?})", version_id, level);
+
+    let id = version_id.clone();
+    let (game_dir, missing_files) = tokio::task::spawn_blocking(move || {
+        let collected = collect_version_files(&id)?;
+        Ok::<_, LauncherError>((collected.game_dir.clone(), check_files(&collected, level)))
+    })
+    .await??;
+
+    info!("验证完成，发现 {} 个问题", missing_files.len());
+    if missing_files.is_empty() {
+        crate::services::version_index::mark_verified(&game_dir.join("versions"), &version_id, level);
+    }
     Ok(missing_files)
 }
 
-/// 检查单个库文件是否存在
-fn check_library(lib: &serde_json::Value, libraries_base_dir: &PathBuf, missing_files: &mut Vec<String>) {
+/// 估算各校验级别的耗时
+pub async fn estimate_verification(version_id: String) -> Result<Vec<VerificationEstimate>, LauncherError> {
+    tokio::task::spawn_blocking(move || {
+        let collected = collect_version_files(&version_id)?;
+        Ok(VerificationLevel::ALL
+            .iter()
+            .map(|level| collected.estimate(*level))
+            .collect())
+    })
+    .await?
+}
+
+/// 库文件在当前系统上对应的文件（natives 库取当前系统的分类器，不适用于当前系统的库返回 None）
+fn library_file(lib: &serde_json::Value, libraries_base_dir: &Path) -> Option<FileCheck> {
     let lib_name = lib.get("name").and_then(|n| n.as_str()).unwrap_or("unknown");
+    let check = |kind, artifact: &serde_json::Value| {
+        Some(FileCheck {
+            kind,
+            path: libraries_base_dir.join(artifact["path"].as_str()?),
+            sha1: artifact["sha1"].as_str().map(String::from),
+            size: artifact["size"].as_u64(),
+        })
+    };
     
     if let Some(natives) = lib.get("natives") {
         let current_os = std::env::consts::OS;
@@ -234,94 +431,57 @@ fn check_library(lib: &serde_json::Value, libraries_base_dir: &PathBuf, missing_
             _ => "unknown",
         };
 
-        if let Some(os_classifier) = natives.get(os_key) {
-            if let Some(classifier_str) = os_classifier.as_str() {
-                let arch = if std::env::consts::ARCH.contains("64") {
-                    "64"
-                } else {
-                    "32"
-                };
-                let classifier = classifier_str.replace("${arch}", arch);
-
-                if let Some(artifact) = lib
-                    .get("downloads")
-                    .and_then(|d| d.get("classifiers"))
-                    .and_then(|c| c.get(&classifier))
-                {
-                    let lib_path =
-                        libraries_base_dir.join(artifact["path"].as_str().unwrap_or(""));
-                    if !lib_path.exists() {
-                        missing_files
-                            .push(format!("Natives库文件不存在: {}", lib_path.display()));
-                    }
-                }
-            }
-        }
-    } else {
-        // 检查 rules
-        if let Some(rules) = lib.get("rules").and_then(|r| r.as_array()) {
-            let mut allowed = true;
-            for rule in rules {
-                if let Some(os) = rule.get("os") {
-                    if let Some(name) = os["name"].as_str() {
-                        if name == std::env::consts::OS {
-                            allowed = rule["action"].as_str() == Some("allow");
-                        } else {
-                            allowed = rule["action"].as_str() != Some("allow");
-                        }
-                    }
-                }
-            }
-            if !allowed {
-                return;
-            }
-        }
-        
-        if let Some(path) = lib
-            .get("downloads")
-            .and_then(|d| d.get("artifact"))
-            .and_then(|a| a.get("path"))
-            .and_then(|p| p.as_str())
-        {
-            let lib_path = libraries_base_dir.join(path);
-            if !lib_path.exists() {
-                debug!("库文件缺失: {} -> {}", lib_name, lib_path.display());
-                missing_files.push(format!("库文件不存在: {}", lib_path.display()));
-            }
+        let classifier_str = natives.get(os_key)?.as_str()?;
+        let arch = if std::env::consts::ARCH.contains("64") {
+            "64"
         } else {
-            // 没有 downloads.artifact.path，尝试从 name 构建路径
-            if let Some(name) = lib.get("name").and_then(|n| n.as_str()) {
-                if let Some(path) = maven_name_to_path(name) {
-                    let lib_path = libraries_base_dir.join(&path);
-                    if !lib_path.exists() {
-                        debug!("库文件缺失 (从name构建): {} -> {}", name, lib_path.display());
-                        missing_files.push(format!("库文件不存在: {}", lib_path.display()));
+            "32"
+        };
+        let classifier = classifier_str.replace("${arch}", arch);
+
+        let artifact = lib
+            .get("downloads")
+            .and_then(|d| d.get("classifiers"))
+            .and_then(|c| c.get(&classifier))?;
+        return check(FileKind::Natives, artifact);
+    }
+
+    // 检查 rules
+    if let Some(rules) = lib.get("rules").and_then(|r| r.as_array()) {
+        let mut allowed = true;
+        for rule in rules {
+            if let Some(os) = rule.get("os") {
+                if let Some(name) = os["name"].as_str() {
+                    if name == std::env::consts::OS {
+                        allowed = rule["action"].as_str() == Some("allow");
+                    } else {
+                        allowed = rule["action"].as_str() != Some("allow");
                     }
                 }
             }
         }
+        if !allowed {
+            return None;
+        }
     }
-}
-
-/// 将 Maven 坐标转换为文件路径
-fn maven_name_to_path(name: &str) -> Option<String> {
-    let parts: Vec<&str> = name.split(':').collect();
-    if parts.len() < 3 {
-        return None;
-    }
-    
-    let group = parts[0].replace('.', "/");
-    let artifact = parts[1];
-    let version = parts[2];
-    let classifier = if parts.len() > 3 { Some(parts[3]) } else { None };
-    
-    let filename = if let Some(c) = classifier {
-        format!("{}-{}-{}.jar", artifact, version, c)
-    } else {
-        format!("{}-{}.jar", artifact, version)
-    };
     
-    Some(format!("{}/{}/{}/{}", group, artifact, version, filename))
+    if let Some(artifact) = lib
+        .get("downloads")
+        .and_then(|d| d.get("artifact"))
+        .filter(|a| a.get("path").and_then(|p| p.as_str()).is_some())
+    {
+        return check(FileKind::Library, artifact);
+    }
+
+    // 没有 downloads.artifact.path，尝试从 name 构建路径
+    let path = maven::maven_to_path(lib_name)?;
+    debug!("库文件路径从name构建: {} -> {}", lib_name, path);
+    Some(FileCheck {
+        kind: FileKind::Library,
+        path: libraries_base_dir.join(&path),
+        sha1: None,
+        size: None,
+    })
 }
 
 /// 递归查找最终的 JAR 版本（处理多层继承链）
@@ -0,0 +1,168 @@
+//! 受限的文件访问
+//!
+//! 代替通用 fs 插件，仅向前端提供所需的文件操作（读取实例文件、列出目录、打开路径），
+//! 所有路径都必须位于游戏目录或启动器目录内，防止路径穿越。
+
+use crate::errors::LauncherError;
+use crate::models::GameConfig;
+use crate::services::{config, paths};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// 可读取的文本文件大小上限
+const MAX_READ_SIZE: u64 = 2 * 1024 * 1024;
+
+/// 允许访问的根目录
+#[derive(Debug, Clone, Copy, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FsRoot {
+    /// 游戏目录（.minecraft）
+    #[default]
+    Game,
+    /// 启动器目录（配置和启动器日志）
+    Launcher,
+}
+
+/// 目录条目信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirEntryInfo {
+    pub name: String,
+    /// 相对于根目录的路径，使用 `/` 分隔
+    pub path: String,
+    pub is_dir: bool,
+    pub size: u64,
+    /// 修改时间（毫秒时间戳）
+    pub modified: Option<i64>,
+}
+
+/// 获取根目录的实际路径；指定实例时游戏目录为该实例的运行目录（实例可能位于任一已登记的游戏目录）
+fn root_dir(root: FsRoot, instance_name: Option<&str>) -> Result<PathBuf, LauncherError> {
+    match root {
+        FsRoot::Game => {
+            let config = config::load_config()?;
+            match instance_name {
+                Some(name) => Ok(instance_dirs(&config, name)?.1),
+                None => Ok(PathBuf::from(config.game_dir)),
+            }
+        }
+        FsRoot::Launcher => config::get_config_path()?
+            .parent()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| LauncherError::Custom("无法获取启动器目录".to_string())),
+    }
+}
+
+/// 将相对路径解析到根目录内，拒绝绝对路径、`..` 以及通过符号链接逃逸的路径
+pub fn resolve_within(base: &Path, relative: &str) -> Result<PathBuf, LauncherError> {
+    let rel = Path::new(relative);
+    for component in rel.components() {
+        match component {
+            Component::Normal(_) | Component::CurDir => {}
+            _ => {
+                return Err(LauncherError::Custom(format!(
+                    "不允许访问的路径: {}",
+                    relative
+                )))
+            }
+        }
+    }
+
+    let joined = base.join(rel);
+    let canonical_base = base.canonicalize()?;
+    let canonical = joined.canonicalize()?;
+    if !canonical.starts_with(&canonical_base) {
+        return Err(LauncherError::Custom(format!(
+            "路径超出允许范围: {}",
+            relative
+        )));
+    }
+    Ok(canonical)
+}
+
+/// 实例的版本目录和游戏运行目录，实例名不能逃出其所在游戏目录的 versions
+fn instance_dirs(config: &GameConfig, instance_name: &str) -> Result<(PathBuf, PathBuf), LauncherError> {
+    let versions_dir = paths::instance_game_dir(config, instance_name).join("versions");
+    resolve_within(&versions_dir, instance_name)?;
+    Ok((
+        paths::version_dir(config, instance_name),
+        paths::game_run_dir(config, instance_name, None),
+    ))
+}
+
+/// 读取实例的文本文件（如 options.txt、日志、配置文件），先在运行目录查找，再在版本目录查找
+pub fn read_instance_file(instance_name: &str, relative_path: &str) -> Result<String, LauncherError> {
+    let config = config::load_config()?;
+    let (version_dir, run_dir) = instance_dirs(&config, instance_name)?;
+    let path = resolve_within(&run_dir, relative_path)
+        .or_else(|_| resolve_within(&version_dir, relative_path))?;
+
+    let meta = fs::metadata(&path)?;
+    if !meta.is_file() {
+        return Err(LauncherError::Custom(format!("不是文件: {}", relative_path)));
+    }
+    if meta.len() > MAX_READ_SIZE {
+        return Err(LauncherError::Custom(format!(
+            "文件过大（{} KB），无法预览",
+            meta.len() / 1024
+        )));
+    }
+
+    let bytes = fs::read(&path)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// 列出根目录内的某个目录
+pub fn list_dir(
+    root: FsRoot,
+    instance_name: Option<&str>,
+    relative_path: &str,
+) -> Result<Vec<DirEntryInfo>, LauncherError> {
+    let base = root_dir(root, instance_name)?;
+    let dir = resolve_within(&base, relative_path)?;
+    let canonical_base = base.canonicalize()?;
+
+    let mut entries: Vec<DirEntryInfo> = fs::read_dir(&dir)?
+        .flatten()
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            let full = entry.path();
+            let rel = full
+                .strip_prefix(&canonical_base)
+                .ok()?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            Some(DirEntryInfo {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                path: rel,
+                is_dir: meta.is_dir(),
+                size: if meta.is_dir() { 0 } else { meta.len() },
+                modified: meta
+                    .modified()
+                    .ok()
+                    .map(|t| chrono::DateTime::<chrono::Utc>::from(t).timestamp_millis()),
+            })
+        })
+        .collect();
+
+    // 目录在前，按名称排序
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    Ok(entries)
+}
+
+/// 在文件管理器中打开根目录内的路径；指向文件时打开其所在目录，不直接运行文件
+pub fn open_path(root: FsRoot, instance_name: Option<&str>, relative_path: &str) -> Result<(), LauncherError> {
+    let base = root_dir(root, instance_name)?;
+    let path = resolve_within(&base, relative_path)?;
+    let dir = if path.is_dir() {
+        path
+    } else {
+        path.parent()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| LauncherError::Custom("无法打开路径: 找不到所在目录".to_string()))?
+    };
+    opener::open(&dir).map_err(|e| LauncherError::Custom(format!("无法打开路径: {}", e)))
+}
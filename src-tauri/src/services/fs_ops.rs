@@ -0,0 +1,58 @@
+//! 大目录的异步复制
+//!
+//! 基础版本、整合包 overrides 和存档可达数 GB，同步复制会阻塞异步运行时。
+//! 这里在阻塞线程池中流式复制，遵循当前任务的取消令牌，并通过
+//! `fs-operation-progress` 事件报告进度。
+
+use crate::errors::LauncherError;
+use crate::services::tasks;
+use crate::utils::file_utils::{self, CopyProgress};
+use serde::Serialize;
+use std::path::Path;
+use crate::services::event_sink::EventSink;
+
+/// `fs-operation-progress` 事件负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FsOperationProgress {
+    /// 操作标识，如 `instance-copy`、`modpack-overrides`
+    pub operation: String,
+    #[serde(flatten)]
+    pub progress: CopyProgress,
+}
+
+/// 在阻塞线程池中递归复制目录，每前进 1% 发送一次进度事件
+pub async fn copy_dir(
+    src: &Path,
+    dst: &Path,
+    operation: &str,
+    window: Option<&EventSink>,
+) -> Result<(), LauncherError> {
+    let src = src.to_path_buf();
+    let dst = dst.to_path_buf();
+    let operation = operation.to_string();
+    let window = window.cloned();
+    let cancel = tasks::current_token().map(|t| t.flag());
+
+    tokio::task::spawn_blocking(move || {
+        let mut last_percent = None;
+        let mut on_progress = |progress: &CopyProgress| {
+            let percent = progress.copied_bytes * 100 / progress.total_bytes.max(1);
+            if last_percent == Some(percent) {
+                return;
+            }
+            last_percent = Some(percent);
+            if let Some(window) = &window {
+                let _ = window.emit(
+                    "fs-operation-progress",
+                    FsOperationProgress {
+                        operation: operation.clone(),
+                        progress: progress.clone(),
+                    },
+                );
+            }
+        };
+        file_utils::copy_dir_with_progress(&src, &dst, cancel.as_deref(), &mut on_progress)
+    })
+    .await?
+}
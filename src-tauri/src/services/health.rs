@@ -0,0 +1,306 @@
+//! 启动健康检查与自修复
+//!
+//! 应用启动时执行快速检查：配置文件解析、数据目录可写、过期临时目录、
+//! 未完成的实例目录、孤立的 .part 下载文件，并自动修复可安全处理的问题（未完成的实例目录
+//! 可能含有用户数据，只报告不删除）。
+
+use crate::errors::LauncherError;
+use crate::services::config;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime};
+
+/// 临时目录条目过期时间
+const STALE_TEMP_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+/// 断点续传文件过期时间（超过后视为孤立文件）
+const STALE_PART_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// 未完成实例目录的最短存在时间，避免把正在创建的实例报告为损坏
+const STALE_STAGING_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// 最近一次启动检查报告
+static LAST_REPORT: RwLock<Option<StartupReport>> = RwLock::new(None);
+
+/// 单项检查结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheckItem {
+    /// 检查项标识
+    pub name: String,
+    /// 是否正常（自动修复后也视为正常）
+    pub ok: bool,
+    /// 说明信息
+    pub message: String,
+    /// 已执行的自动修复操作
+    pub fixes: Vec<String>,
+}
+
+impl HealthCheckItem {
+    fn ok(name: &str, message: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            ok: true,
+            message: message.into(),
+            fixes: Vec::new(),
+        }
+    }
+
+    fn failed(name: &str, message: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            ok: false,
+            message: message.into(),
+            fixes: Vec::new(),
+        }
+    }
+}
+
+/// 启动检查报告（通过 `startup-report` 事件发送到前端）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupReport {
+    pub items: Vec<HealthCheckItem>,
+    /// 自动修复的操作数
+    pub fixed_count: usize,
+    /// 仍需用户处理的问题数
+    pub issue_count: usize,
+    /// 检查耗时（毫秒）
+    pub duration_ms: u64,
+}
+
+/// 执行启动检查并缓存结果
+pub fn run_startup_checks() -> StartupReport {
+    let start = std::time::Instant::now();
+    let mut items = vec![check_config_file()];
+
+    match config::load_config() {
+        Ok(cfg) => {
+            let game_dir = PathBuf::from(&cfg.game_dir);
+            items.push(check_dir_writable(&game_dir));
+            items.push(clean_stale_temp(&game_dir));
+            items.push(check_staging_instances(&game_dir));
+            items.push(clean_orphaned_parts(&game_dir));
+        }
+        Err(e) => items.push(HealthCheckItem::failed(
+            "config",
+            format!("无法加载配置，跳过目录检查: {}", e),
+        )),
+    }
+
+    let report = StartupReport {
+        fixed_count: items.iter().map(|i| i.fixes.len()).sum(),
+        issue_count: items.iter().filter(|i| !i.ok).count(),
+        duration_ms: start.elapsed().as_millis() as u64,
+        items,
+    };
+
+    log::info!(
+        "启动检查完成: {} 项修复, {} 项问题, 耗时 {}ms",
+        report.fixed_count,
+        report.issue_count,
+        report.duration_ms
+    );
+
+    if let Ok(mut last) = LAST_REPORT.write() {
+        *last = Some(report.clone());
+    }
+    report
+}
+
+/// 获取最近一次启动检查报告（前端错过事件时使用）
+pub fn get_startup_report() -> Result<StartupReport, LauncherError> {
+    if let Ok(last) = LAST_REPORT.read() {
+        if let Some(report) = last.as_ref() {
+            return Ok(report.clone());
+        }
+    }
+    Ok(run_startup_checks())
+}
+
+/// 检查配置文件能否解析，损坏时由配置模块备份并重建
+fn check_config_file() -> HealthCheckItem {
+    let path = match config::get_config_path() {
+        Ok(p) => p,
+        Err(e) => return HealthCheckItem::failed("config", format!("无法定位配置文件: {}", e)),
+    };
+
+    if !path.exists() {
+        return HealthCheckItem::ok("config", "首次启动，将创建默认配置");
+    }
+
+    let parsed = fs::read_to_string(&path)
+        .map_err(LauncherError::from)
+        .and_then(|c| serde_json::from_str::<crate::models::GameConfig>(&c).map_err(Into::into));
+
+    match parsed {
+        Ok(_) => HealthCheckItem::ok("config", "配置文件正常"),
+        Err(e) => {
+            let mut item = HealthCheckItem::ok("config", format!("配置文件损坏: {}", e));
+            config::invalidate_config_cache();
+            if config::load_config().is_ok() {
+                item.fixes
+                    .push("已备份损坏的配置文件 (ar1s.bak) 并重建默认配置".to_string());
+            } else {
+                item.ok = false;
+            }
+            item
+        }
+    }
+}
+
+/// 检查游戏目录是否可写
+fn check_dir_writable(game_dir: &Path) -> HealthCheckItem {
+    let mut item = HealthCheckItem::ok("data_dir", format!("游戏目录可写: {}", game_dir.display()));
+
+    if !game_dir.exists() {
+        match fs::create_dir_all(game_dir) {
+            Ok(_) => item.fixes.push(format!("已创建游戏目录: {}", game_dir.display())),
+            Err(e) => return HealthCheckItem::failed("data_dir", format!("无法创建游戏目录: {}", e)),
+        }
+    }
+
+    let probe = game_dir.join(".ar1s_write_test");
+    match fs::write(&probe, b"ok") {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            item
+        }
+        Err(e) => HealthCheckItem::failed(
+            "data_dir",
+            format!("游戏目录不可写: {} ({})", game_dir.display(), e),
+        ),
+    }
+}
+
+/// 条目距上次修改是否已超过指定时间
+fn is_older_than(path: &Path, age: Duration) -> bool {
+    fs::symlink_metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| SystemTime::now().duration_since(t).ok())
+        .map(|elapsed| elapsed > age)
+        .unwrap_or(false)
+}
+
+/// 删除文件或目录
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// 清理过期的临时目录（加载器安装器、整合包解压残留）
+fn clean_stale_temp(game_dir: &Path) -> HealthCheckItem {
+    let mut item = HealthCheckItem::ok("temp", "没有过期的临时文件");
+    let temp_dir = game_dir.join("temp");
+
+    let Ok(entries) = fs::read_dir(&temp_dir) else {
+        return item;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !is_older_than(&path, STALE_TEMP_AGE) {
+            continue;
+        }
+        match remove_path(&path) {
+            Ok(_) => item.fixes.push(format!("已删除过期临时文件: {}", path.display())),
+            Err(e) => {
+                item.ok = false;
+                item.message = format!("无法删除临时文件 {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    if !item.fixes.is_empty() && item.ok {
+        item.message = format!("已清理 {} 个过期临时条目", item.fixes.len());
+    }
+    item
+}
+
+/// 检查未完成的实例目录（缺少版本 JSON，通常是创建过程中被中断）
+///
+/// 只报告不删除：目录中可能有账户隔离的存档、配置等用户数据，由用户确认后自行处理
+fn check_staging_instances(game_dir: &Path) -> HealthCheckItem {
+    let mut item = HealthCheckItem::ok("instances", "实例目录完整");
+    let versions_dir = game_dir.join("versions");
+
+    let Ok(entries) = fs::read_dir(&versions_dir) else {
+        return item;
+    };
+
+    let mut unresolved = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if path.join(format!("{}.json", name)).exists() {
+            continue;
+        }
+        // 刚创建的目录可能仍在安装中
+        if is_older_than(&path, STALE_STAGING_AGE) {
+            unresolved.push(name);
+        }
+    }
+
+    if !unresolved.is_empty() {
+        unresolved.sort();
+        item.ok = false;
+        item.message = format!(
+            "以下实例缺少版本文件，可能是未完成的安装或已损坏，请确认后删除或修复: {}",
+            unresolved.join(", ")
+        );
+    }
+    item
+}
+
+/// 递归收集目录下的 .part 文件
+fn collect_part_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_part_files(&path, out);
+        } else if path.extension().map(|e| e == "part").unwrap_or(false) {
+            out.push(path);
+        }
+    }
+}
+
+/// 清理孤立的 .part 下载文件（长时间未续传）
+fn clean_orphaned_parts(game_dir: &Path) -> HealthCheckItem {
+    let mut item = HealthCheckItem::ok("downloads", "没有孤立的下载文件");
+
+    let mut parts = Vec::new();
+    for dir in ["libraries", "assets", "versions"] {
+        collect_part_files(&game_dir.join(dir), &mut parts);
+    }
+
+    let mut freed = 0u64;
+    for part in parts {
+        if !is_older_than(&part, STALE_PART_AGE) {
+            continue;
+        }
+        let size = fs::metadata(&part).map(|m| m.len()).unwrap_or(0);
+        if fs::remove_file(&part).is_ok() {
+            freed += size;
+            item.fixes.push(format!("已删除孤立的下载文件: {}", part.display()));
+        }
+    }
+
+    if !item.fixes.is_empty() {
+        item.message = format!(
+            "已清理 {} 个孤立的下载文件，释放 {:.1} MB",
+            item.fixes.len(),
+            freed as f64 / 1024.0 / 1024.0
+        );
+    }
+    item
+}
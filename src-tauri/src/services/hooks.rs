@@ -0,0 +1,202 @@
+//! 生命周期钩子
+//!
+//! 在启动前、游戏退出后和整合包安装完成后执行用户配置的脚本，便于接入服务器同步工具
+//! 或自定义流程。脚本通过 `AR1S_*` 环境变量获取上下文，工作目录为实例目录，
+//! 输出写入启动器日志，超时后结束进程。实例钩子优先于全局钩子，两者都只保存在启动器配置中，
+//! 实例目录中的设置文件（可能来自整合包或分享包）不能设置钩子。
+
+use crate::errors::LauncherError;
+use crate::models::{HookCommand, LifecycleHooks};
+use crate::services::{config, instance, paths};
+use crate::utils::encoding::decode_process_output;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// 未设置超时时间时的默认值
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 钩子执行的节点
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookPoint {
+    PreLaunch,
+    PostExit,
+    PostModpackInstall,
+}
+
+impl HookPoint {
+    /// 传给脚本的 `AR1S_HOOK` 值
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::PreLaunch => "pre-launch",
+            Self::PostExit => "post-exit",
+            Self::PostModpackInstall => "post-modpack-install",
+        }
+    }
+
+    fn select(self, hooks: &LifecycleHooks) -> Option<&HookCommand> {
+        match self {
+            Self::PreLaunch => hooks.pre_launch.as_ref(),
+            Self::PostExit => hooks.post_exit.as_ref(),
+            Self::PostModpackInstall => hooks.post_modpack_install.as_ref(),
+        }
+    }
+}
+
+/// 实例在指定节点生效的钩子
+fn resolve_hook(instance_name: &str, point: HookPoint) -> Option<HookCommand> {
+    let settings = instance::get_instance_settings(instance_name).unwrap_or_default();
+    if let Some(hook) = point.select(&settings.hooks) {
+        return Some(hook.clone());
+    }
+    let config = config::load_config().ok()?;
+    point.select(&config.hooks).cloned()
+}
+
+/// 按脚本类型构建命令（Windows 批处理和 PowerShell 脚本需要通过解释器执行）
+fn build_command(hook: &HookCommand) -> Command {
+    let path = Path::new(&hook.command);
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let mut command = match ext.as_str() {
+        "bat" | "cmd" if cfg!(windows) => {
+            let mut c = Command::new("cmd");
+            c.arg("/C").arg(path);
+            c
+        }
+        "ps1" => {
+            let mut c = Command::new(if cfg!(windows) { "powershell" } else { "pwsh" });
+            c.args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-File"]).arg(path);
+            c
+        }
+        "sh" if !cfg!(windows) => {
+            let mut c = Command::new("sh");
+            c.arg(path);
+            c
+        }
+        _ => Command::new(path),
+    };
+    command.args(&hook.args);
+    #[cfg(windows)]
+    command.creation_flags(CREATE_NO_WINDOW);
+    command
+}
+
+/// 在后台线程读取输出，逐行写入日志
+fn log_output(
+    stream: Option<impl Read + Send + 'static>,
+    point: HookPoint,
+    is_stderr: bool,
+) -> Option<thread::JoinHandle<()>> {
+    let mut stream = stream?;
+    Some(thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let _ = stream.read_to_end(&mut bytes);
+        for line in decode_process_output(&bytes).lines().filter(|l| !l.trim().is_empty()) {
+            if is_stderr {
+                log::warn!("[钩子 {}] {}", point.as_str(), line);
+            } else {
+                log::info!("[钩子 {}] {}", point.as_str(), line);
+            }
+        }
+    }))
+}
+
+/// 执行实例在指定节点的钩子；未配置时直接返回
+///
+/// `extra_env` 为该节点额外的上下文（如退出码）。脚本返回非零或超时时返回错误
+pub fn run_hook(
+    instance_name: &str,
+    point: HookPoint,
+    extra_env: &[(&str, String)],
+) -> Result<(), LauncherError> {
+    let Some(hook) = resolve_hook(instance_name, point) else {
+        return Ok(());
+    };
+    let config = config::load_config()?;
+    let instance_dir = paths::version_dir(&config, instance_name);
+    let game_dir = paths::instance_game_dir(&config, instance_name);
+    let timeout = hook
+        .timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_TIMEOUT);
+
+    log::info!("执行钩子 {}: {} {:?}", point.as_str(), hook.command, hook.args);
+    let mut command = build_command(&hook);
+    command
+        .current_dir(if instance_dir.is_dir() { &instance_dir } else { &game_dir })
+        .env("AR1S_HOOK", point.as_str())
+        .env("AR1S_INSTANCE", instance_name)
+        .env("AR1S_INSTANCE_DIR", &instance_dir)
+        .env("AR1S_GAME_DIR", &game_dir)
+        .env("AR1S_LAUNCHER_VERSION", env!("CARGO_PKG_VERSION"))
+        .envs(extra_env.iter().map(|(k, v)| (*k, v)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = command.spawn().map_err(|e| {
+        LauncherError::Custom(format!("无法执行钩子 {} ({}): {}", point.as_str(), hook.command, e))
+    })?;
+    let readers = [
+        log_output(child.stdout.take(), point, false),
+        log_output(child.stderr.take(), point, true),
+    ];
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if started.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            break None;
+        }
+        thread::sleep(Duration::from_millis(100));
+    };
+    // 脚本启动的后台进程可能继续持有输出管道，最多等待片刻后不再等待
+    let deadline = Instant::now() + Duration::from_secs(2);
+    for reader in readers.into_iter().flatten() {
+        while !reader.is_finished() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+        }
+        if reader.is_finished() {
+            let _ = reader.join();
+        }
+    }
+
+    match status {
+        None => Err(LauncherError::Custom(format!(
+            "钩子 {} 执行超时（{} 秒）",
+            point.as_str(),
+            timeout.as_secs()
+        ))),
+        Some(status) if !status.success() => Err(LauncherError::Custom(format!(
+            "钩子 {} 执行失败（{}）",
+            point.as_str(),
+            status
+        ))),
+        Some(_) => {
+            log::info!("钩子 {} 执行完成，用时 {:?}", point.as_str(), started.elapsed());
+            Ok(())
+        }
+    }
+}
+
+/// 执行钩子，失败只记录日志（用于不影响主流程的节点）
+pub fn run_hook_logged(instance_name: &str, point: HookPoint, extra_env: &[(&str, String)]) {
+    if let Err(e) = run_hook(instance_name, point, extra_env) {
+        log::warn!("{}", e);
+    }
+}
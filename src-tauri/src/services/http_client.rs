@@ -1,9 +1,42 @@
-use reqwest::Client;
+//! 共享 HTTP 客户端
+//!
+//! 所有客户端都通过 [`apply_trust`] 加入用户导入的 CA 证书，以便在有 TLS 中间人检查的
+//! 学校/企业网络中正常访问。导入或移除证书后调用 [`reset_clients`] 重新创建客户端。
+
+use crate::errors::LauncherError;
+use crate::services::config;
+use reqwest::{Certificate, Client, ClientBuilder};
+use std::path::Path;
+use std::sync::RwLock;
 use std::time::Duration;
 
-/// 全局 HTTP 客户端（连接池复用）
-static HTTP_CLIENT: std::sync::LazyLock<Client> = std::sync::LazyLock::new(|| {
-    Client::builder()
+/// 全局 HTTP 客户端（连接池复用），导入证书后重建
+static HTTP_CLIENT: RwLock<Option<Client>> = RwLock::new(None);
+
+/// 读取 PEM 或 DER 格式的 CA 证书
+pub fn read_certificate(path: &Path) -> Result<Certificate, LauncherError> {
+    let bytes = std::fs::read(path)?;
+    Certificate::from_pem(&bytes)
+        .or_else(|_| Certificate::from_der(&bytes))
+        .map_err(|e| LauncherError::Custom(format!("无法解析证书 {}: {}", path.display(), e)))
+}
+
+/// 加入配置中的自定义 CA 证书（未配置或读取失败时原样返回）
+pub fn apply_trust(builder: ClientBuilder) -> ClientBuilder {
+    let Some(path) = config::load_config().ok().and_then(|c| c.custom_ca_cert) else {
+        return builder;
+    };
+    match read_certificate(Path::new(&path)) {
+        Ok(cert) => builder.add_root_certificate(cert),
+        Err(e) => {
+            log::warn!("加载自定义 CA 证书失败，使用系统证书: {}", e);
+            builder
+        }
+    }
+}
+
+fn build_client() -> Client {
+    apply_trust(Client::builder())
         .timeout(Duration::from_secs(30))
         .connect_timeout(Duration::from_secs(10))
         .pool_max_idle_per_host(10)
@@ -11,16 +44,47 @@ static HTTP_CLIENT: std::sync::LazyLock<Client> = std::sync::LazyLock::new(|| {
         .user_agent("Ar1s-Launcher/1.0")
         .build()
         .expect("Failed to create HTTP client")
-});
+}
 
 /// 获取全局 HTTP 客户端
-pub fn get_client() -> &'static Client {
-    &HTTP_CLIENT
+pub fn get_client() -> Client {
+    if let Ok(guard) = HTTP_CLIENT.read() {
+        if let Some(client) = guard.as_ref() {
+            return client.clone();
+        }
+    }
+    let client = build_client();
+    if let Ok(mut guard) = HTTP_CLIENT.write() {
+        *guard = Some(client.clone());
+    }
+    client
+}
+
+/// 丢弃已创建的客户端，下次使用时按当前证书配置重建
+pub fn reset_clients() {
+    if let Ok(mut guard) = HTTP_CLIENT.write() {
+        *guard = None;
+    }
+    crate::services::download::reset_http_client();
+}
+
+/// 请求是否因 TLS 证书校验失败（各平台 TLS 后端只给出文本描述，按错误链中的关键字判断）
+pub fn is_tls_error(err: &reqwest::Error) -> bool {
+    const KEYWORDS: [&str; 5] = ["certificate", "ssl", "tls", "handshake", "证书"];
+    let mut source: Option<&dyn std::error::Error> = Some(err);
+    while let Some(e) = source {
+        let text = e.to_string().to_lowercase();
+        if KEYWORDS.iter().any(|k| text.contains(k)) {
+            return true;
+        }
+        source = e.source();
+    }
+    false
 }
 
 /// 创建带自定义超时的客户端（用于特殊场景）
 pub fn create_client_with_timeout(timeout_secs: u64) -> Client {
-    Client::builder()
+    apply_trust(Client::builder())
         .timeout(Duration::from_secs(timeout_secs))
         .connect_timeout(Duration::from_secs(10))
         .pool_max_idle_per_host(5)
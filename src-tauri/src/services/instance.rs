@@ -1,17 +1,25 @@
 use crate::errors::LauncherError;
-use crate::models::{DownloadJob, InstanceInfo, LaunchOptions};
-use crate::services::{config, download, launcher, loaders::{self, LoaderType}};
+use crate::models::{DownloadJob, GameProxy, InstanceInfo, InstanceSettings, LaunchOptions, LaunchOverrides, ProgressStage};
+use crate::services::{config, download, fs_ops, http_client, launcher, instance_archive, instance_health, instance_lock, loaders::{self, LoaderType}, log_events, metrics, modpack_health, paths, server_ping, tasks, version_index};
+use crate::services::modrinth::ModrinthService;
+use crate::services::paths::ContentKind;
+use crate::services::server_ping::ServerStatus;
+use crate::services::instance_lock::InstanceOperation;
+use crate::utils::maven::MavenCoordinate;
+use crate::utils::nbt;
 use crate::utils::file_utils::{self, validate_instance_name_or_error, validate_instance_name, InstanceNameValidation};
 use log::{info, warn};
 use serde::Serialize;
 use serde_json::Value;
+use sha1::{Digest, Sha1};
 use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
-use tauri::{Emitter, Window};
+use crate::services::event_sink::EventSink;
 
 #[derive(Clone, Serialize)]
 struct InstallProgress {
+    stage: ProgressStage,
     progress: u8,
     message: String,
     indeterminate: bool,
@@ -25,6 +33,165 @@ fn get_dirs() -> Result<(PathBuf, PathBuf), LauncherError> {
     Ok((game_dir, versions_dir))
 }
 
+/// 获取实例所在的游戏目录和 versions 目录（实例不存在时为默认目录）
+pub(crate) fn instance_dirs(instance_name: &str) -> Result<(PathBuf, PathBuf), LauncherError> {
+    let config = config::load_config()?;
+    let game_dir = paths::instance_game_dir(&config, instance_name);
+    let versions_dir = game_dir.join("versions");
+    Ok((game_dir, versions_dir))
+}
+
+/// 实例名是否已被任一已登记游戏目录中的实例占用
+pub(crate) fn instance_name_taken(dirs: &[PathBuf], name: &str) -> bool {
+    dirs.iter().any(|dir| dir.join("versions").join(name).exists())
+}
+
+/// 实例独立设置文件名
+pub(crate) const INSTANCE_SETTINGS_FILE: &str = "ar1s_instance.json";
+
+/// 读取实例独立设置（不存在时返回默认值）
+///
+/// 钩子取自启动器配置；实例目录可能来自整合包或分享包，文件中的钩子一律忽略
+pub fn get_instance_settings(instance_name: &str) -> Result<InstanceSettings, LauncherError> {
+    let (_, versions_dir) = instance_dirs(instance_name)?;
+    let path = versions_dir.join(instance_name).join(INSTANCE_SETTINGS_FILE);
+    let mut settings = if path.exists() {
+        serde_json::from_str::<InstanceSettings>(&fs::read_to_string(path)?)?
+    } else {
+        InstanceSettings::default()
+    };
+    if !settings.hooks.is_empty() {
+        warn!("忽略实例 '{}' 设置文件中的钩子，钩子需要在启动器中重新设置", instance_name);
+    }
+    settings.hooks = config::get_instance_hooks(instance_name);
+    Ok(settings)
+}
+
+/// 检查游戏代理设置（主机名不能包含会破坏 JVM 参数的字符）
+fn validate_game_proxy(proxy: &GameProxy) -> Result<(), LauncherError> {
+    let host = proxy.host.trim();
+    if host.is_empty() || host.contains(|c: char| c.is_whitespace() || c == '/' || c == '@') {
+        return Err(LauncherError::Custom(format!("代理主机名无效: '{}'", proxy.host)));
+    }
+    if proxy.port == 0 {
+        return Err(LauncherError::Custom("代理端口无效".to_string()));
+    }
+    if proxy.password.as_deref().is_some_and(|p| !p.is_empty())
+        && proxy.username.as_deref().is_none_or(|u| u.trim().is_empty())
+    {
+        return Err(LauncherError::Custom("设置代理密码时需要同时填写用户名".to_string()));
+    }
+    Ok(())
+}
+
+/// 保存实例独立设置
+pub fn set_instance_settings(instance_name: &str, settings: &InstanceSettings) -> Result<(), LauncherError> {
+    let (_, versions_dir) = instance_dirs(instance_name)?;
+    let instance_dir = versions_dir.join(instance_name);
+    if !instance_dir.exists() {
+        return Err(LauncherError::Custom(format!("实例 '{}' 不存在", instance_name)));
+    }
+    if let Some(proxy) = settings.proxy.as_ref() {
+        validate_game_proxy(proxy)?;
+    }
+    if let Some(java_path) = settings.java_path.as_deref().filter(|p| !p.trim().is_empty()) {
+        if !Path::new(java_path).exists() {
+            return Err(LauncherError::Custom(format!("Java 路径不存在: {}", java_path)));
+        }
+    }
+    config::normalize_jvm_args(settings.jvm_args.clone())?;
+    if settings.max_memory.is_some_and(|m| m < 512) {
+        return Err(LauncherError::Custom("最大内存不能低于 512 MB".to_string()));
+    }
+    config::set_instance_hooks(instance_name, settings.hooks.clone())?;
+    let file_settings = InstanceSettings {
+        hooks: Default::default(),
+        ..settings.clone()
+    };
+    fs::write(
+        instance_dir.join(INSTANCE_SETTINGS_FILE),
+        serde_json::to_string_pretty(&file_settings)?,
+    )?;
+    Ok(())
+}
+
+/// 游戏实际读取的实例内容目录（不存在时创建）
+pub fn instance_content_dir(instance_name: &str, kind: ContentKind) -> Result<PathBuf, LauncherError> {
+    validate_instance_name_or_error(instance_name)?;
+    let config = config::load_config()?;
+    let dir = paths::content_dir(&config, instance_name, kind);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// 记录启动时自愈补齐的库（已记录的不重复添加）
+pub fn record_healed_libraries(instance_name: &str, libraries: &[String]) -> Result<(), LauncherError> {
+    let mut settings = get_instance_settings(instance_name)?;
+    let before = settings.healed_libraries.len();
+    for lib in libraries {
+        if !settings.healed_libraries.contains(lib) {
+            settings.healed_libraries.push(lib.clone());
+        }
+    }
+    if settings.healed_libraries.len() != before {
+        set_instance_settings(instance_name, &settings)?;
+    }
+    Ok(())
+}
+
+/// 将自愈记录的库永久写入实例版本 JSON，返回新增的库数量
+pub fn rebuild_instance_classpath(instance_name: &str) -> Result<usize, LauncherError> {
+    let (game_dir, versions_dir) = instance_dirs(instance_name)?;
+    let libraries_dir = game_dir.join("libraries");
+    let json_path = versions_dir.join(instance_name).join(format!("{}.json", instance_name));
+    if !json_path.exists() {
+        return Err(LauncherError::Custom(format!("实例 '{}' 不存在", instance_name)));
+    }
+
+    let mut settings = get_instance_settings(instance_name)?;
+    if settings.healed_libraries.is_empty() {
+        return Ok(0);
+    }
+
+    let mut json: Value = serde_json::from_str(&fs::read_to_string(&json_path)?)?;
+    let libraries = json["libraries"]
+        .as_array_mut()
+        .ok_or_else(|| LauncherError::Custom("版本 JSON 缺少 libraries".to_string()))?;
+
+    let mut added = 0;
+    for relative in &settings.healed_libraries {
+        let jar_path = libraries_dir.join(relative);
+        let Some(name) = MavenCoordinate::from_path(relative).map(|c| c.to_string()) else {
+            warn!("无法从路径推导库坐标，跳过: {}", relative);
+            continue;
+        };
+        if !jar_path.exists() {
+            warn!("自愈记录的库文件已不存在，跳过: {}", relative);
+            continue;
+        }
+        if libraries.iter().any(|lib| lib["name"].as_str() == Some(name.as_str())) {
+            continue;
+        }
+        libraries.push(serde_json::json!({
+            "name": name,
+            "downloads": {
+                "artifact": {
+                    "path": relative,
+                    "sha1": file_utils::sha1_file(&jar_path, None)?,
+                    "size": fs::metadata(&jar_path)?.len(),
+                }
+            }
+        }));
+        added += 1;
+    }
+
+    fs::write(&json_path, serde_json::to_string_pretty(&json)?)?;
+    settings.healed_libraries.clear();
+    set_instance_settings(instance_name, &settings)?;
+    info!("实例 {} 已写入 {} 个自愈库", instance_name, added);
+    Ok(added)
+}
+
 /// 检查实例名称是否可用（验证格式并检查是否已存在）
 pub fn check_instance_name_available(name: &str) -> InstanceNameValidation {
     // 首先验证名称格式
@@ -33,10 +200,9 @@ pub fn check_instance_name_available(name: &str) -> InstanceNameValidation {
         return validation;
     }
     
-    // 然后检查实例是否已存在
-    if let Ok((_, versions_dir)) = get_dirs() {
-        let instance_dir = versions_dir.join(name);
-        if instance_dir.exists() {
+    // 然后检查实例是否已存在于任一游戏目录
+    if let Ok(config) = config::load_config() {
+        if instance_name_taken(&paths::game_dirs(&config), name) {
             return InstanceNameValidation {
                 is_valid: false,
                 error_message: Some(format!("名为 '{}' 的实例已存在，请使用其他名称", name)),
@@ -55,19 +221,22 @@ pub async fn create_instance(
     new_instance_name: String,
     base_version_id: String,
     loader: Option<LoaderType>,
-    window: &Window,
+    window: &EventSink,
 ) -> Result<(), LauncherError> {
+    let _span = metrics::span("create_instance");
     // 验证实例名称
     validate_instance_name_or_error(&new_instance_name)?;
+    let _lock = instance_lock::acquire(&new_instance_name, InstanceOperation::Install)?;
     
     let (game_dir, versions_dir) = get_dirs()?;
     let source_dir = versions_dir.join(&base_version_id);
     let dest_dir = versions_dir.join(&new_instance_name);
 
-    let send_progress = |progress: u8, message: &str, indeterminate: bool| {
+    let send_progress = |stage: ProgressStage, progress: u8, message: &str, indeterminate: bool| {
         let _ = window.emit(
             "instance-install-progress",
             InstallProgress {
+                stage,
                 progress,
                 message: message.to_string(),
                 indeterminate,
@@ -75,14 +244,24 @@ pub async fn create_instance(
         );
     };
 
-    if dest_dir.exists() {
+    if instance_name_taken(&paths::game_dirs(&config::load_config()?), &new_instance_name) {
         return Err(LauncherError::Custom(format!("实例 '{}' 已存在", new_instance_name)));
     }
 
-    send_progress(5, "检查基础版本...", false);
+    send_progress(ProgressStage::Preparing, 5, "检查基础版本...", false);
+
+    let base_problems = if source_dir.exists() {
+        verify_base_version(&game_dir, &base_version_id).await?
+    } else {
+        Vec::new()
+    };
+    if !base_problems.is_empty() {
+        warn!("基础版本 {} 不完整，正在修复: {}", base_version_id, base_problems.join("; "));
+        log_events::emit(window, "log-warning", format!("基础版本不完整，正在修复: {}", base_problems.join("; ")));
+    }
 
-    if !source_dir.exists() {
-        send_progress(10, "下载基础版本...", true);
+    if !source_dir.exists() || !base_problems.is_empty() {
+        send_progress(ProgressStage::DownloadingFiles, 10, if base_problems.is_empty() { "下载基础版本..." } else { "修复基础版本..." }, true);
         let config = config::load_config()?;
         download::process_and_download_version(
             base_version_id.clone(),
@@ -100,14 +279,15 @@ pub async fn create_instance(
         file_utils::cleanup_instance_creation(&game_dir, &new_instance_name, &base_version_id);
     };
 
-    send_progress(30, "复制基础文件...", false);
+    tasks::check_cancelled()?;
+    send_progress(ProgressStage::CopyingFiles, 30, "复制基础文件...", false);
 
-    if let Err(e) = file_utils::copy_dir_all(&source_dir, &dest_dir) {
+    if let Err(e) = fs_ops::copy_dir(&source_dir, &dest_dir, "instance-copy", Some(window)).await {
         cleanup();
-        return Err(e.into());
+        return Err(e);
     }
 
-    send_progress(40, "配置实例...", false);
+    send_progress(ProgressStage::Configuring, 40, "配置实例...", false);
 
     let old_json_path = dest_dir.join(format!("{}.json", base_version_id));
     let new_json_path = dest_dir.join(format!("{}.json", new_instance_name));
@@ -131,6 +311,10 @@ pub async fn create_instance(
         let mut json: Value = serde_json::from_str(&json_str)
             .map_err(|e| LauncherError::Custom(format!("解析 JSON 失败: {}", e)))?;
         json["id"] = Value::String(new_instance_name.clone());
+        // 记录原版版本号，改名后仍可识别
+        if json.get("clientVersion").is_none() && json.get("inheritsFrom").is_none() {
+            json["clientVersion"] = Value::String(base_version_id.clone());
+        }
         fs::write(&new_json_path, serde_json::to_string_pretty(&json)?)?;
         Ok(())
     };
@@ -141,184 +325,823 @@ pub async fn create_instance(
     }
 
     if let Some(ref loader_type) = loader {
-        send_progress(60, &format!("安装 {} 加载器...", loader_type.name()), true);
+        if let Err(e) = tasks::check_cancelled() {
+            cleanup();
+            return Err(e);
+        }
+        send_progress(ProgressStage::InstallingLoader, 60, &format!("安装 {} 加载器...", loader_type.name()), true);
         
         if let Err(e) = loaders::install_loader(loader_type, &new_instance_name, &game_dir).await {
             cleanup();
             return Err(e);
         }
 
-        // 对于 Forge，需要合并配置
-        if let LoaderType::Forge { mc_version, loader_version } = loader_type {
-            let forge_id_prefix = format!("{}-forge", mc_version);
-            let forge_id_exact = format!("{}-forge-{}", mc_version, loader_version);
-            
-            let found_forge_id = fs::read_dir(&versions_dir)
-                .ok()
-                .and_then(|entries| {
-                    entries.flatten()
-                        .filter_map(|e| e.file_name().to_str().map(String::from))
-                        .find(|name| name == &forge_id_exact || name.starts_with(&forge_id_prefix))
-                });
-
-            if let Some(fid) = found_forge_id {
-                let forge_json_path = versions_dir.join(&fid).join(format!("{}.json", fid));
-                let base_json_path = versions_dir.join(&base_version_id).join(format!("{}.json", base_version_id));
-
-                if forge_json_path.exists() && base_json_path.exists() {
-                    send_progress(70, "合并配置并补全依赖...", true);
-                    
-                    if let Err(e) = merge_and_complete_instance(
-                        &new_instance_name,
-                        &new_json_path,
-                        &base_json_path,
-                        &forge_json_path,
-                        &game_dir,
-                        window
-                    ).await {
-                        cleanup();
-                        return Err(e);
-                    }
-
-                    let forge_dir = versions_dir.join(&fid);
-                    if forge_dir.exists() && forge_dir != dest_dir {
-                        let _ = fs::remove_dir_all(forge_dir);
-                    }
-                } else {
-                    warn!("未找到 Forge 或 基础版本的 JSON 文件，跳过合并");
+        // 对于 Forge，安装器已将实例 JSON 替换为 Forge 版本 JSON，需要与基础版本合并并补全依赖
+        if let LoaderType::Forge { .. } = loader_type {
+            let base_json_path = versions_dir.join(&base_version_id).join(format!("{}.json", base_version_id));
+
+            if new_json_path.exists() && base_json_path.exists() {
+                send_progress(ProgressStage::InstallingLoader, 70, "合并配置并补全依赖...", true);
+
+                if let Err(e) = merge_and_complete_instance(
+                    &new_instance_name,
+                    &new_json_path,
+                    &base_json_path,
+                    &new_json_path,
+                    &game_dir,
+                    window
+                ).await {
+                    cleanup();
+                    return Err(e);
                 }
             } else {
-                warn!("未找到安装后的 Forge 目录");
+                warn!("未找到 Forge 实例或基础版本的 JSON 文件，跳过合并");
+            }
+        }
+    }
+
+    version_index::invalidate(&versions_dir, &new_instance_name);
+    send_progress(ProgressStage::Completed, 100, "实例创建完成！", false);
+    Ok(())
+}
+
+/// 重新生成版本 JSON 的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegeneratedVersionJson {
+    pub mc_version: String,
+    pub loader_type: Option<String>,
+    pub loader_version: Option<String>,
+    /// 从原 JSON 保留下来的自定义 JVM 参数
+    pub preserved_jvm_args: Vec<String>,
+    /// 从原 JSON 保留下来的自定义游戏参数
+    pub preserved_game_args: Vec<String>,
+    /// 原 JSON 的备份路径
+    pub backup_path: Option<String>,
+}
+
+/// 版本 JSON 中的字符串参数（带规则的参数由版本本身提供，不视为自定义参数）
+fn string_args(json: &Value, kind: &str) -> Vec<String> {
+    let mut args: Vec<String> = json["arguments"][kind]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|a| a.as_str().map(String::from))
+        .collect();
+    if kind == "game" {
+        if let Some(legacy) = json["minecraftArguments"].as_str() {
+            args.extend(legacy.split_whitespace().map(String::from));
+        }
+    }
+    args
+}
+
+/// 将参数分组为单元，`--key value` 视为一个单元
+fn arg_units(args: &[String]) -> Vec<Vec<String>> {
+    let mut units: Vec<Vec<String>> = Vec::new();
+    for arg in args {
+        match units.last_mut() {
+            Some(unit) if unit.len() == 1 && unit[0].starts_with("--") && !arg.starts_with('-') => {
+                unit.push(arg.clone())
+            }
+            _ => units.push(vec![arg.clone()]),
+        }
+    }
+    units
+}
+
+/// 原 JSON 中有、重新生成的继承链中没有的参数
+fn custom_args(old: &Value, new_chain: &[Value], kind: &str) -> Vec<String> {
+    let known: HashSet<Vec<String>> = new_chain
+        .iter()
+        .flat_map(|json| arg_units(&string_args(json, kind)))
+        .collect();
+    arg_units(&string_args(old, kind))
+        .into_iter()
+        .filter(|unit| !known.contains(unit))
+        .flatten()
+        .collect()
+}
+
+/// 将自定义参数追加到版本 JSON
+fn append_custom_args(json: &mut Value, chain: &[Value], jvm: &[String], game: &[String]) {
+    let push = |json: &mut Value, kind: &str, args: &[String]| {
+        if !json["arguments"].is_object() {
+            json["arguments"] = serde_json::json!({});
+        }
+        if !json["arguments"][kind].is_array() {
+            json["arguments"][kind] = serde_json::json!([]);
+        }
+        if let Some(list) = json["arguments"][kind].as_array_mut() {
+            list.extend(args.iter().cloned().map(Value::String));
+        }
+    };
+    if !jvm.is_empty() {
+        push(json, "jvm", jvm);
+    }
+    if game.is_empty() {
+        return;
+    }
+    // 旧版使用 minecraftArguments，子版本的值会覆盖父版本，需要带上完整参数
+    let legacy = chain.iter().find_map(|j| j["minecraftArguments"].as_str()).map(String::from);
+    match legacy {
+        Some(legacy) if !json["arguments"]["game"].is_array() => {
+            json["minecraftArguments"] = Value::String(format!("{} {}", legacy, game.join(" ")));
+        }
+        _ => push(json, "game", game),
+    }
+}
+
+/// 加载器名称和版本转换为安装用的加载器类型
+fn loader_from_record(loader_type: &str, mc_version: &str, loader_version: Option<&str>) -> Result<Option<LoaderType>, LauncherError> {
+    if loader_type.eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+    let loader_version = loader_version
+        .ok_or_else(|| LauncherError::Custom(format!("未记录 {} 的加载器版本，无法重新生成", loader_type)))?
+        .to_string();
+    let mc_version = mc_version.to_string();
+    Ok(Some(match loader_type.to_lowercase().as_str() {
+        "forge" => LoaderType::Forge { mc_version, loader_version },
+        "fabric" => LoaderType::Fabric { mc_version, loader_version },
+        "quilt" => LoaderType::Quilt { mc_version, loader_version },
+        "neoforge" => LoaderType::NeoForge { mc_version, loader_version },
+        other => {
+            return Err(LauncherError::Custom(format!("不支持重新生成 {} 实例的版本 JSON", other)));
+        }
+    }))
+}
+
+/// 根据记录的游戏版本和加载器重新生成实例的版本 JSON
+///
+/// 原 JSON 备份为 `<实例>.json.broken-<时间>`；原 JSON 仍可解析时，其中用户添加的参数会保留到新 JSON
+pub async fn regenerate_version_json(
+    instance_name: String,
+    window: &EventSink,
+) -> Result<RegeneratedVersionJson, LauncherError> {
+    let (game_dir, versions_dir) = instance_dirs(&instance_name)?;
+    let instance_dir = versions_dir.join(&instance_name);
+    if !instance_dir.exists() {
+        return Err(LauncherError::Custom(format!("实例 '{}' 不存在", instance_name)));
+    }
+    ensure_instance_stopped(&instance_name, &instance_dir, false).await?;
+    let _lock = instance_lock::acquire(&instance_name, InstanceOperation::Install)?;
+
+    let json_path = instance_dir.join(format!("{}.json", instance_name));
+    let old_json: Option<Value> = fs::read_to_string(&json_path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok());
+
+    // 优先使用索引中的记录（JSON 损坏后仍保留），其次从当前 JSON 解析
+    let record = version_index::recorded(&versions_dir, &instance_name);
+    let detected = crate::services::version_info::detect(&versions_dir, &instance_name);
+    let mc_version = record
+        .as_ref()
+        .and_then(|r| r.mc_version.clone())
+        .or(detected.mc_version.clone())
+        .ok_or_else(|| LauncherError::Custom("无法确定实例的游戏版本，请重新创建实例".to_string()))?;
+    let (loader_type, loader_version) = match record.as_ref().and_then(|r| r.loader_type.clone()) {
+        Some(loader_type) => (loader_type, record.as_ref().and_then(|r| r.loader_version.clone())),
+        None => (detected.loader_type.clone(), detected.loader_version.clone()),
+    };
+    let loader = loader_from_record(&loader_type, &mc_version, loader_version.as_deref())?;
+    info!(
+        "重新生成实例 {} 的版本 JSON: MC {} + {} {:?}",
+        instance_name, mc_version, loader_type, loader_version
+    );
+
+    // 确保基础版本完整
+    let base_dir = versions_dir.join(&mc_version);
+    let base_json_path = base_dir.join(format!("{}.json", mc_version));
+    if !base_json_path.exists() {
+        let config = config::load_config()?;
+        download::process_and_download_version(mc_version.clone(), config.download_mirror, window).await?;
+        if !base_json_path.exists() {
+            return Err(LauncherError::Custom(format!("基础版本 '{}' 下载后仍未找到", mc_version)));
+        }
+    }
+    tasks::check_cancelled()?;
+
+    let backup_path = if json_path.exists() {
+        let backup = instance_dir.join(format!(
+            "{}.json.broken-{}",
+            instance_name,
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        fs::copy(&json_path, &backup)?;
+        Some(backup)
+    } else {
+        None
+    };
+
+    // 与创建实例相同：先写入基础版本 JSON，再安装加载器
+    let mut base_json: Value = serde_json::from_str(&fs::read_to_string(&base_json_path)?)?;
+    base_json["id"] = Value::String(instance_name.clone());
+    if base_json.get("clientVersion").is_none() && base_json.get("inheritsFrom").is_none() {
+        base_json["clientVersion"] = Value::String(mc_version.clone());
+    }
+    fs::write(&json_path, serde_json::to_string_pretty(&base_json)?)?;
+    let jar_path = instance_dir.join(format!("{}.jar", instance_name));
+    let base_jar_path = base_dir.join(format!("{}.jar", mc_version));
+    if !jar_path.exists() && base_jar_path.exists() {
+        fs::copy(&base_jar_path, &jar_path)?;
+    }
+
+    if let Some(loader) = &loader {
+        loaders::install_loader(loader, &instance_name, &game_dir).await?;
+        if let LoaderType::Forge { .. } = loader {
+            merge_and_complete_instance(&instance_name, &json_path, &base_json_path, &json_path, &game_dir, window)
+                .await?;
+        }
+    }
+
+    // 保留原 JSON 中用户添加的参数
+    let chain = crate::services::version_info::load_chain(&versions_dir, &instance_name);
+    let (preserved_jvm_args, preserved_game_args) = match &old_json {
+        Some(old) => (custom_args(old, &chain, "jvm"), custom_args(old, &chain, "game")),
+        None => (Vec::new(), Vec::new()),
+    };
+    if !preserved_jvm_args.is_empty() || !preserved_game_args.is_empty() {
+        let mut json: Value = serde_json::from_str(&fs::read_to_string(&json_path)?)?;
+        append_custom_args(&mut json, &chain, &preserved_jvm_args, &preserved_game_args);
+        fs::write(&json_path, serde_json::to_string_pretty(&json)?)?;
+        info!("保留自定义参数: JVM {:?}，游戏 {:?}", preserved_jvm_args, preserved_game_args);
+    }
+
+    version_index::invalidate(&versions_dir, &instance_name);
+    launcher::clear_merged_cache();
+    info!("实例 {} 的版本 JSON 已重新生成", instance_name);
+
+    Ok(RegeneratedVersionJson {
+        mc_version,
+        loader_type: loader.as_ref().map(|l| l.name().to_string()),
+        loader_version: loader.as_ref().map(|l| l.loader_version().to_string()),
+        preserved_jvm_args,
+        preserved_game_args,
+        backup_path: backup_path.map(|p| p.to_string_lossy().to_string()),
+    })
+}
+
+/// 存档信息（来自 level.dat）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldInfo {
+    pub level_name: String,
+    /// 存档最后一次打开时的游戏版本（1.9 之前的存档不记录）
+    pub mc_version: Option<String>,
+    pub data_version: Option<i64>,
+}
+
+/// 读取存档的 level.dat
+pub fn read_world_info(world_dir: &Path) -> Result<WorldInfo, LauncherError> {
+    let level_dat = world_dir.join("level.dat");
+    if !level_dat.exists() {
+        return Err(LauncherError::Custom(format!(
+            "{} 不是有效的存档目录（缺少 level.dat）",
+            world_dir.display()
+        )));
+    }
+    let nbt = nbt::read_gzip_file(&level_dat)
+        .map_err(|e| LauncherError::Custom(format!("读取 level.dat 失败: {}", e)))?;
+
+    let level_name = nbt
+        .get_path(&["Data", "LevelName"])
+        .and_then(|t| t.as_str())
+        .map(String::from)
+        .unwrap_or_else(|| {
+            world_dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+
+    Ok(WorldInfo {
+        level_name,
+        mc_version: nbt
+            .get_path(&["Data", "Version", "Name"])
+            .and_then(|t| t.as_str())
+            .map(String::from),
+        data_version: nbt.get_path(&["Data", "DataVersion"]).and_then(|t| t.as_i64()),
+    })
+}
+
+/// 为存档生成可用的实例名称（名称冲突时追加序号）
+fn unique_instance_name(game_dirs: &[PathBuf], base: &str) -> String {
+    let sanitized: String = base
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+        .take(48)
+        .collect();
+    let base = if sanitized.trim_matches('_').is_empty() { "world".to_string() } else { sanitized };
+    let mut name = base.clone();
+    let mut n = 2;
+    while instance_name_taken(game_dirs, &name) {
+        name = format!("{}-{}", base, n);
+        n += 1;
+    }
+    name
+}
+
+/// 根据存档的 level.dat 创建匹配版本的实例，并将存档复制进去，返回实例名称
+pub async fn create_instance_for_world(
+    world_path: String,
+    instance_name: Option<String>,
+    window: &EventSink,
+) -> Result<String, LauncherError> {
+    let world_dir = PathBuf::from(&world_path);
+    let world = read_world_info(&world_dir)?;
+    let mc_version = world.mc_version.clone().ok_or_else(|| {
+        LauncherError::Custom("存档未记录游戏版本（1.9 之前的存档），请手动创建实例后导入".to_string())
+    })?;
+    info!("存档 {} 最后使用的版本: {}", world.level_name, mc_version);
+
+    let game_dirs = paths::game_dirs(&config::load_config()?);
+    let instance_name = match instance_name {
+        Some(name) => name,
+        None => unique_instance_name(&game_dirs, &format!("{}-{}", world.level_name, mc_version)),
+    };
+
+    create_instance(instance_name.clone(), mc_version, None, window).await?;
+
+    let config = config::load_config()?;
+    let saves_dir = paths::content_dir(&config, &instance_name, ContentKind::Saves);
+    let dir_name = world_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| world.level_name.clone());
+    let mut target = saves_dir.join(&dir_name);
+    let mut n = 2;
+    while target.exists() {
+        target = saves_dir.join(format!("{}-{}", dir_name, n));
+        n += 1;
+    }
+
+    let _ = window.emit(
+        "instance-install-progress",
+        InstallProgress {
+            stage: ProgressStage::CopyingFiles,
+            progress: 100,
+            message: "复制存档...".to_string(),
+            indeterminate: true,
+        },
+    );
+    fs_ops::copy_dir(&world_dir, &target, "world-copy", Some(window)).await?;
+    info!("存档已复制到 {}", target.display());
+
+    Ok(instance_name)
+}
+
+/// 按服务器创建实例的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerInstanceReport {
+    pub instance_name: String,
+    pub server: ServerStatus,
+    /// 从 Modrinth 安装的模组文件
+    pub installed_mods: Vec<String>,
+    /// 服务器列出但未能在 Modrinth 找到的模组 ID
+    pub missing_mods: Vec<String>,
+}
+
+/// 服务器模组列表中不需要单独安装的条目
+const SERVER_BUILTIN_MODS: [&str; 5] = ["minecraft", "forge", "neoforge", "fml", "mcp"];
+
+/// 为服务器选择加载器版本：优先使用服务器报告的版本，否则取最新版本
+async fn loader_for_server(server: &ServerStatus, mc_version: &str) -> Result<Option<LoaderType>, LauncherError> {
+    let reported = |id: &str| {
+        server
+            .mods
+            .iter()
+            .find(|m| m.mod_id == id)
+            .and_then(|m| m.version.clone())
+            .filter(|v| v != "ANY" && v != "OHNOES")
+    };
+    let loader = match server.loader.as_deref() {
+        Some("forge") => {
+            let versions = loaders::get_forge_versions(mc_version).await?;
+            let version = reported("forge")
+                .filter(|r| versions.iter().any(|v| &v.version == r))
+                .or_else(|| versions.first().map(|v| v.version.clone()))
+                .ok_or_else(|| LauncherError::Custom(format!("未找到适用于 {} 的 Forge", mc_version)))?;
+            LoaderType::Forge { mc_version: mc_version.to_string(), loader_version: version }
+        }
+        Some("neoforge") => {
+            let versions = loaders::get_neoforge_versions(mc_version).await?;
+            let version = reported("neoforge")
+                .filter(|r| versions.iter().any(|v| &v.version == r))
+                .or_else(|| versions.last().map(|v| v.version.clone()))
+                .ok_or_else(|| LauncherError::Custom(format!("未找到适用于 {} 的 NeoForge", mc_version)))?;
+            LoaderType::NeoForge { mc_version: mc_version.to_string(), loader_version: version }
+        }
+        Some("fabric") => {
+            let versions = loaders::get_fabric_versions(mc_version).await?;
+            let version = versions
+                .iter()
+                .find(|v| v.stable)
+                .or(versions.first())
+                .map(|v| v.version.clone())
+                .ok_or_else(|| LauncherError::Custom(format!("未找到适用于 {} 的 Fabric", mc_version)))?;
+            LoaderType::Fabric { mc_version: mc_version.to_string(), loader_version: version }
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(loader))
+}
+
+/// 按服务器模组列表从 Modrinth 安装同名项目的兼容版本，返回（已安装文件, 未找到的模组）
+async fn install_server_mods(
+    server: &ServerStatus,
+    instance_name: &str,
+    mc_version: &str,
+    loader: &str,
+) -> Result<(Vec<String>, Vec<String>), LauncherError> {
+    let mods_dir = instance_content_dir(instance_name, ContentKind::Mods)?;
+    let modrinth = ModrinthService::new();
+    let (mut installed, mut missing) = (Vec::new(), Vec::new());
+
+    let builtin = |id: &str| SERVER_BUILTIN_MODS.iter().any(|b| b.eq_ignore_ascii_case(id));
+    for server_mod in server.mods.iter().filter(|m| !builtin(&m.mod_id)) {
+        tasks::check_cancelled()?;
+        let versions = modrinth
+            .get_modpack_versions(
+                &server_mod.mod_id,
+                Some(vec![mc_version.to_string()]),
+                Some(vec![loader.to_string()]),
+            )
+            .await
+            .unwrap_or_default();
+        let file = versions
+            .first()
+            .and_then(|v| v.files.iter().find(|f| f.primary).or(v.files.first()));
+        let Some(file) = file else {
+            missing.push(server_mod.mod_id.clone());
+            continue;
+        };
+        match modrinth
+            .download_modpack_file(&file.url, &mods_dir.join(&file.filename), Some(&file.hashes.sha1), |_, _| {
+                tasks::check_cancelled().is_ok()
+            })
+            .await {
+            Ok(()) => installed.push(file.filename.clone()),
+            Err(e) => {
+                warn!("下载服务器模组 {} 失败: {}", server_mod.mod_id, e);
+                missing.push(server_mod.mod_id.clone());
             }
         }
     }
+    Ok((installed, missing))
+}
 
-    send_progress(100, "实例创建完成！", false);
+/// 查询服务器状态并创建匹配版本（及加载器）的实例；Forge/NeoForge 服务器报告模组列表时，
+/// 从 Modrinth 安装同名模组
+pub async fn create_instance_for_server(
+    address: String,
+    instance_name: Option<String>,
+    window: &EventSink,
+) -> Result<ServerInstanceReport, LauncherError> {
+    let server = server_ping::ping_server(&address).await?;
+    let mc_version = server.mc_version.clone().ok_or_else(|| {
+        LauncherError::Custom(format!("无法从服务器版本 '{}' 识别 Minecraft 版本", server.version_name))
+    })?;
+    info!(
+        "服务器 {} 版本: {} (加载器: {:?}, 模组 {} 个)",
+        address,
+        server.version_name,
+        server.loader,
+        server.mods.len()
+    );
+
+    let game_dirs = paths::game_dirs(&config::load_config()?);
+    let instance_name = match instance_name {
+        Some(name) => name,
+        None => {
+            let host = address.split(':').next().unwrap_or(&address);
+            unique_instance_name(&game_dirs, &format!("{}-{}", host, mc_version))
+        }
+    };
+
+    let loader = loader_for_server(&server, &mc_version).await?;
+    create_instance(instance_name.clone(), mc_version.clone(), loader.clone(), window).await?;
+
+    let (installed_mods, missing_mods) = match (&loader, server.mods.is_empty()) {
+        (Some(loader), false) => {
+            let _ = window.emit(
+                "instance-install-progress",
+                InstallProgress {
+                    stage: ProgressStage::DownloadingMods,
+                    progress: 100,
+                    message: "安装服务器模组...".to_string(),
+                    indeterminate: true,
+                },
+            );
+            let loader_name = loader.name().to_lowercase();
+            install_server_mods(&server, &instance_name, &mc_version, &loader_name).await?
+        }
+        _ => (Vec::new(), Vec::new()),
+    };
+    if !missing_mods.is_empty() {
+        warn!("以下服务器模组未能自动安装: {}", missing_mods.join(", "));
+    }
+
+    Ok(ServerInstanceReport {
+        instance_name,
+        server,
+        installed_mods,
+        missing_mods,
+    })
+}
+
+/// 检查自定义版本 JSON 的基本结构，确保能交给常规下载流程处理
+fn check_custom_version_json(json: &Value) -> Result<(), LauncherError> {
+    let invalid = |reason: &str| Err(LauncherError::Custom(format!("版本 JSON 无效: {}", reason)));
+    if !json.is_object() {
+        return invalid("不是 JSON 对象");
+    }
+    if json.get("libraries").is_some_and(|l| !l.is_array()) {
+        return invalid("libraries 不是数组");
+    }
+    if json["inheritsFrom"].as_str().is_some() {
+        return Ok(());
+    }
+    // 不继承其他版本时需要自带客户端和资源索引
+    if json["mainClass"].as_str().is_none() {
+        return invalid("缺少 mainClass");
+    }
+    if json["downloads"]["client"]["url"].as_str().is_none() {
+        return invalid("缺少 downloads.client.url");
+    }
+    if json["assetIndex"]["url"].as_str().is_none() {
+        return invalid("缺少 assetIndex.url");
+    }
     Ok(())
 }
 
+/// 从版本 JSON 地址安装自定义版本（如服务器提供的定制客户端）
+///
+/// JSON 的 id 会改为实例名，库文件、资源和父版本通过常规下载流程获取，
+/// 来源地址和内容哈希记录在实例目录的 instance.json 中
+pub async fn install_version_from_url(
+    json_url: String,
+    instance_name: String,
+    window: &EventSink,
+) -> Result<(), LauncherError> {
+    let _span = metrics::span("install_version_from_url");
+    validate_instance_name_or_error(&instance_name)?;
+    let url = reqwest::Url::parse(json_url.trim())
+        .ok()
+        .filter(|u| matches!(u.scheme(), "http" | "https"))
+        .ok_or_else(|| LauncherError::Custom(format!("无效的版本 JSON 地址: {}", json_url)))?;
+
+    let config = config::load_config()?;
+    if instance_name_taken(&paths::game_dirs(&config), &instance_name) {
+        return Err(LauncherError::Custom(format!("实例 '{}' 已存在", instance_name)));
+    }
+
+    info!("从 {} 安装自定义版本 {}", url, instance_name);
+    let text = http_client::get_client()
+        .get(url.clone())
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let mut json: Value = serde_json::from_str(text.trim_start_matches('\u{feff}'))
+        .map_err(|e| LauncherError::Custom(format!("无法解析版本 JSON: {}", e)))?;
+    check_custom_version_json(&json)?;
+
+    let original_id = json["id"].as_str().map(String::from);
+    json["id"] = Value::String(instance_name.clone());
+    // 不继承其他版本时客户端 JAR 按实例名保存，原 jar 字段不再适用
+    if json["inheritsFrom"].is_null() {
+        if let Some(obj) = json.as_object_mut() {
+            obj.remove("jar");
+        }
+    }
+
+    let version_dir = PathBuf::from(&config.game_dir).join("versions").join(&instance_name);
+    fs::create_dir_all(&version_dir)?;
+    fs::write(
+        version_dir.join(format!("{}.json", instance_name)),
+        serde_json::to_string_pretty(&json)?,
+    )?;
+
+    let installed = async {
+        download::process_and_download_version(instance_name.clone(), config.download_mirror.clone(), window).await?;
+        let fatal: Vec<String> = launcher::validate_version_json(&instance_name)?
+            .into_iter()
+            .filter(|i| i.fatal)
+            .map(|i| format!("[{}] {}", i.location, i.message))
+            .collect();
+        if !fatal.is_empty() {
+            return Err(LauncherError::Custom(format!("版本 JSON 校验失败:\n{}", fatal.join("\n"))));
+        }
+        Ok(())
+    }
+    .await;
+    if let Err(e) = installed {
+        warn!("自定义版本 {} 安装失败，正在清理: {}", instance_name, e);
+        let _ = fs::remove_dir_all(&version_dir);
+        return Err(e);
+    }
+
+    let provenance = serde_json::json!({
+        "id": instance_name,
+        "name": original_id.clone().unwrap_or_else(|| instance_name.clone()),
+        "type": "custom",
+        "source": "url",
+        "source_url": url.to_string(),
+        "original_id": original_id,
+        "json_sha1": format!("{:x}", Sha1::digest(text.as_bytes())),
+        "created": chrono::Utc::now().to_rfc3339(),
+    });
+    fs::write(
+        version_dir.join("instance.json"),
+        serde_json::to_string_pretty(&provenance)?,
+    )?;
+    if let Some(versions_dir) = version_dir.parent() {
+        version_index::invalidate(versions_dir, &instance_name);
+    }
+    info!("自定义版本 {} 安装完成", instance_name);
+    Ok(())
+}
+
+/// 复制前快速检查基础版本：JSON 可解析、客户端 JAR 哈希一致、资源索引存在
+///
+/// 返回发现的问题，空表示完整。损坏的版本 JSON 会被删除，以便修复时重新获取。
+async fn verify_base_version(game_dir: &Path, version_id: &str) -> Result<Vec<String>, LauncherError> {
+    let version_dir = game_dir.join("versions").join(version_id);
+    let json_path = version_dir.join(format!("{}.json", version_id));
+    let mut problems = Vec::new();
+
+    let json: Value = match fs::read_to_string(&json_path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+    {
+        Some(json) => json,
+        None => {
+            let _ = fs::remove_file(&json_path);
+            problems.push("版本 JSON 缺失或损坏".to_string());
+            return Ok(problems);
+        }
+    };
+
+    // 继承版本的 JAR 和资源由父版本提供，修复时会递归处理
+    if json.get("inheritsFrom").is_some() {
+        return Ok(problems);
+    }
+
+    let jar_path = version_dir.join(format!("{}.jar", version_id));
+    let client = &json["downloads"]["client"];
+    if let Some(sha1) = client["sha1"].as_str() {
+        let size = client["size"].as_u64().unwrap_or(0);
+        if !file_utils::verify_file_async(&jar_path, sha1, size).await? {
+            problems.push("客户端 JAR 缺失或哈希不匹配".to_string());
+        }
+    } else if !jar_path.exists() {
+        problems.push("客户端 JAR 缺失".to_string());
+    }
+
+    if let Some(index_id) = json["assetIndex"]["id"].as_str() {
+        let index_path = game_dir.join("assets").join("indexes").join(format!("{}.json", index_id));
+        let index_ok = fs::read_to_string(&index_path)
+            .ok()
+            .is_some_and(|c| serde_json::from_str::<Value>(&c).is_ok());
+        if !index_ok {
+            problems.push(format!("资源索引 {} 缺失或损坏", index_id));
+        }
+    }
+
+    Ok(problems)
+}
+
 /// 获取实例列表（使用 spawn_blocking 避免阻塞异步运行时）
 pub async fn get_instances() -> Result<Vec<InstanceInfo>, LauncherError> {
-    let (_, versions_dir) = get_dirs()?;
+    let game_dirs = paths::game_dirs(&config::load_config()?);
     
     // 将 CPU 密集型的文件系统操作和 JSON 解析移到阻塞线程池
     let instances = tokio::task::spawn_blocking(move || {
-        get_instances_sync(&versions_dir)
+        let mut instances = Vec::new();
+        for game_dir in &game_dirs {
+            // 同名实例以先登记的目录为准，与 paths::instance_game_dir 的解析一致
+            for instance in get_instances_sync(game_dir)? {
+                if !instances.iter().any(|i: &InstanceInfo| i.name == instance.name) {
+                    instances.push(instance);
+                }
+            }
+        }
+        // 已归档的实例仍然列出，版本目录已恢复的以实际目录为准
+        for archived in instance_archive::list_archived(&game_dirs) {
+            if !instances.iter().any(|i| i.name == archived.name) {
+                instances.push(archived.to_info());
+            }
+        }
+        Ok::<_, LauncherError>(instances)
     }).await.map_err(|e| LauncherError::Custom(format!("获取实例列表失败: {}", e)))??;
     
     Ok(instances)
 }
 
-/// 同步获取实例列表（在阻塞线程池中执行）
-fn get_instances_sync(versions_dir: &Path) -> Result<Vec<InstanceInfo>, LauncherError> {
+/// 同步获取单个游戏目录下的实例列表（在阻塞线程池中执行）
+fn get_instances_sync(game_dir: &Path) -> Result<Vec<InstanceInfo>, LauncherError> {
     let mut instances = Vec::new();
+    let versions_dir = game_dir.join("versions");
 
     if !versions_dir.exists() {
         return Ok(instances);
     }
 
-    if let Ok(entries) = fs::read_dir(versions_dir) {
-        for entry in entries.flatten() {
-            if let Ok(file_type) = entry.file_type() {
-                if file_type.is_dir() {
-                    let name = entry.file_name().to_string_lossy().to_string();
-                    let path = entry.path();
-                    let json_path = path.join(format!("{}.json", name));
-
-                    if json_path.exists() {
-                        let json_content = fs::read_to_string(&json_path).ok();
-                        let json_value = json_content
-                            .as_ref()
-                            .and_then(|c| serde_json::from_str::<Value>(c).ok());
-
-                        let version_id = json_value
-                            .as_ref()
-                            .and_then(|v| v["id"].as_str().map(String::from))
-                            .unwrap_or_else(|| name.clone());
-
-                        // 解析加载器类型和游戏版本
-                        let (loader_type, game_version) = json_value
-                            .as_ref()
-                            .map(|v| {
-                                let inherits = v["inheritsFrom"].as_str();
-                                let id = v["id"].as_str().unwrap_or("");
-                                
-                                if let Some(base_version) = inherits {
-                                    // 有继承关系，说明是模组加载器版本
-                                    let loader = if id.contains("forge") || id.contains("Forge") {
-                                        "Forge"
-                                    } else if id.contains("fabric") || id.contains("Fabric") {
-                                        "Fabric"
-                                    } else if id.contains("quilt") || id.contains("Quilt") {
-                                        "Quilt"
-                                    } else if id.contains("neoforge") || id.contains("NeoForge") {
-                                        "NeoForge"
-                                    } else {
-                                        "None"
-                                    };
-                                    (Some(loader.to_string()), Some(base_version.to_string()))
-                                } else {
-                                    (Some("None".to_string()), Some(version_id.clone()))
-                                }
-                            })
-                            .unwrap_or((None, None));
-
-                        let created = entry.metadata()
-                            .and_then(|m| m.created())
-                            .ok()
-                            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                            .map(|d| d.as_secs().to_string());
-
-                        instances.push(InstanceInfo {
-                            id: name.clone(),
-                            name: name.clone(),
-                            version: version_id,
-                            path: path.to_string_lossy().to_string(),
-                            created_time: created,
-                            loader_type,
-                            game_version,
-                            last_played: config::get_instance_last_played(&name),
-                        });
-                    }
-                }
+    for (name, indexed) in version_index::refresh(&versions_dir) {
+        let path = versions_dir.join(&name);
+        let created = fs::metadata(&path)
+            .and_then(|m| m.created())
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs().to_string());
+
+        instances.push(InstanceInfo {
+            id: name.clone(),
+            name: name.clone(),
+            version: indexed.id,
+            path: path.to_string_lossy().to_string(),
+            created_time: created,
+            loader_type: indexed.loader_type,
+            game_version: indexed.mc_version.clone(),
+            last_played: config::get_instance_last_played(&name),
+            mc_version: indexed.mc_version,
+            loader_version: indexed.loader_version,
+            version_type: indexed.version_type,
+            missing_base: indexed.missing_parent,
+            verified_levels: indexed.verified_levels,
+            game_dir: game_dir.to_string_lossy().to_string(),
+            health: instance_health::cached_status(&name)
+                .or_else(|| modpack_health::read_health_status(&path)),
+            archived: false,
+        });
+    }
+    Ok(instances)
+}
+
+/// 游戏正在运行时拒绝操作；`force` 为 true 时先结束游戏进程
+pub(crate) async fn ensure_instance_stopped(instance_name: &str, instance_dir: &Path, force: bool) -> Result<(), LauncherError> {
+    let (name, dir) = (instance_name.to_string(), instance_dir.to_path_buf());
+    tokio::task::spawn_blocking(move || launcher::ensure_not_running(&name, &dir, force)).await?
+}
+
+/// 删除目录，游戏刚退出时 natives 等文件可能仍被短暂占用（Windows），失败后重试几次
+pub(crate) async fn remove_dir_with_retry(dir: &Path) -> std::io::Result<()> {
+    let mut attempt = 0;
+    loop {
+        match fs::remove_dir_all(dir) {
+            Err(e) if attempt < 5 && dir.exists() => {
+                attempt += 1;
+                warn!("删除 {} 失败（第 {} 次），稍后重试: {}", dir.display(), attempt, e);
+                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
             }
+            result => return result,
         }
     }
-    Ok(instances)
 }
 
-/// 删除实例
-pub async fn delete_instance(instance_name: String) -> Result<(), LauncherError> {
-    let (_, versions_dir) = get_dirs()?;
+/// 删除实例（游戏运行中时需 `force` 才会结束游戏后删除）
+pub async fn delete_instance(instance_name: String, force: bool) -> Result<(), LauncherError> {
+    let (_, versions_dir) = instance_dirs(&instance_name)?;
     let instance_dir = versions_dir.join(&instance_name);
 
     if !instance_dir.exists() {
         return Err(LauncherError::Custom(format!("实例 '{}' 不存在", instance_name)));
     }
+    ensure_instance_stopped(&instance_name, &instance_dir, force).await?;
+    let _lock = instance_lock::acquire(&instance_name, InstanceOperation::Delete)?;
 
-    fs::remove_dir_all(&instance_dir)
+    remove_dir_with_retry(&instance_dir)
+        .await
         .map_err(|e| LauncherError::Custom(format!("删除实例失败: {}", e)))?;
     
-    // 删除上次启动时间记录
+    // 删除上次启动时间和钩子记录
     let _ = config::remove_instance_last_played(&instance_name);
+    let _ = config::set_instance_hooks(&instance_name, Default::default());
+    version_index::invalidate(&versions_dir, &instance_name);
+    launcher::clear_merged_cache();
     
     info!("实例 {} 已删除", instance_name);
     Ok(())
 }
 
-/// 重命名实例
-pub async fn rename_instance(old_name: String, new_name: String) -> Result<(), LauncherError> {
+/// 重命名实例（游戏运行中时需 `force` 才会结束游戏后重命名）
+pub async fn rename_instance(old_name: String, new_name: String, force: bool) -> Result<(), LauncherError> {
     // 验证新实例名称
     validate_instance_name_or_error(&new_name)?;
-    
-    let (_, versions_dir) = get_dirs()?;
+    let (_, versions_dir) = instance_dirs(&old_name)?;
     let old_dir = versions_dir.join(&old_name);
     let new_dir = versions_dir.join(&new_name);
 
     if !old_dir.exists() {
         return Err(LauncherError::Custom(format!("原实例 '{}' 不存在", old_name)));
     }
-    if new_dir.exists() {
+    ensure_instance_stopped(&old_name, &old_dir, force).await?;
+    let _old_lock = instance_lock::acquire(&old_name, InstanceOperation::Rename)?;
+    let _new_lock = instance_lock::acquire(&new_name, InstanceOperation::Rename)?;
+    if instance_name_taken(&paths::game_dirs(&config::load_config()?), &new_name) {
         return Err(LauncherError::Custom(format!("目标实例名 '{}' 已存在", new_name)));
     }
 
@@ -345,15 +1168,19 @@ pub async fn rename_instance(old_name: String, new_name: String) -> Result<(), L
         }
     }
 
-    // 重命名上次启动时间记录
+    // 重命名上次启动时间和钩子记录
     let _ = config::rename_instance_last_played(&old_name, &new_name);
+    let _ = config::rename_instance_hooks(&old_name, &new_name);
+    version_index::invalidate(&versions_dir, &old_name);
+    version_index::invalidate(&versions_dir, &new_name);
+    launcher::clear_merged_cache();
 
     Ok(())
 }
 
 /// 打开实例文件夹
 pub async fn open_instance_folder(instance_name: String) -> Result<(), LauncherError> {
-    let (_, versions_dir) = get_dirs()?;
+    let (_, versions_dir) = instance_dirs(&instance_name)?;
     let instance_dir = versions_dir.join(&instance_name);
 
     if !instance_dir.exists() {
@@ -366,29 +1193,140 @@ pub async fn open_instance_folder(instance_name: String) -> Result<(), LauncherE
     Ok(())
 }
 
-/// 启动实例
-pub async fn launch_instance(instance_name: String, window: Window) -> Result<(), LauncherError> {
+/// 打开游戏实际读取的实例子目录（mods/config/saves 等，遵循版本隔离设置）
+pub async fn open_instance_content_folder(instance_name: String, kind: ContentKind) -> Result<(), LauncherError> {
+    let (_, versions_dir) = instance_dirs(&instance_name)?;
+    if !versions_dir.join(&instance_name).exists() {
+        return Err(LauncherError::Custom(format!("实例 '{}' 不存在", instance_name)));
+    }
+
+    let dir = instance_content_dir(&instance_name, kind)?;
+    opener::open(&dir)
+        .map_err(|e| LauncherError::Custom(format!("无法打开文件夹: {}", e)))?;
+
+    Ok(())
+}
+
+/// 在实例的游戏运行目录中打开终端
+pub async fn open_instance_terminal(instance_name: String) -> Result<(), LauncherError> {
+    validate_instance_name_or_error(&instance_name)?;
     let config = config::load_config()?;
-    let (_, versions_dir) = get_dirs()?;
-    let instance_dir = versions_dir.join(&instance_name);
+    if !paths::version_dir(&config, &instance_name).exists() {
+        return Err(LauncherError::Custom(format!("实例 '{}' 不存在", instance_name)));
+    }
+
+    let dir = paths::game_run_dir(&config, &instance_name, None);
+    fs::create_dir_all(&dir)?;
+    spawn_terminal(&dir)
+}
+
+#[cfg(target_os = "windows")]
+fn spawn_terminal(dir: &Path) -> Result<(), LauncherError> {
+    std::process::Command::new("cmd")
+        .args(["/C", "start", "", "cmd"])
+        .current_dir(dir)
+        .spawn()
+        .map_err(|e| LauncherError::Custom(format!("无法打开终端: {}", e)))?;
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn spawn_terminal(dir: &Path) -> Result<(), LauncherError> {
+    std::process::Command::new("open")
+        .args(["-a", "Terminal"])
+        .arg(dir)
+        .spawn()
+        .map_err(|e| LauncherError::Custom(format!("无法打开终端: {}", e)))?;
+    Ok(())
+}
+
+/// Linux: 依次尝试常见终端模拟器
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn spawn_terminal(dir: &Path) -> Result<(), LauncherError> {
+    const TERMINALS: [&str; 6] = [
+        "x-terminal-emulator",
+        "gnome-terminal",
+        "konsole",
+        "xfce4-terminal",
+        "alacritty",
+        "xterm",
+    ];
+    let mut candidates: Vec<String> = std::env::var("TERMINAL").into_iter().collect();
+    candidates.extend(TERMINALS.iter().map(|t| t.to_string()));
+
+    for terminal in &candidates {
+        if std::process::Command::new(terminal).current_dir(dir).spawn().is_ok() {
+            return Ok(());
+        }
+    }
+    Err(LauncherError::Custom("未找到可用的终端程序，可通过 TERMINAL 环境变量指定".to_string()))
+}
+
+/// 启动实例，`overrides` 只对本次启动生效，不会保存
+///
+/// 这是唯一的启动入口：versions 下任何带版本 JSON 的目录都视为实例，
+/// 启动选项总是由全局配置和实例设置生成，锁、上次启动时间等统计由启动器统一处理
+pub async fn launch_instance(
+    instance_name: String,
+    overrides: Option<LaunchOverrides>,
+    window: EventSink,
+) -> Result<(), LauncherError> {
+    let mut launch_options = instance_launch_options(&instance_name)?;
+    if let Some(overrides) = overrides {
+        apply_launch_overrides(&mut launch_options, overrides);
+    }
+
+    launcher::launch_minecraft(launch_options, window).await
+}
+
+/// 由全局配置和实例独立设置生成实例的启动选项
+pub fn instance_launch_options(instance_name: &str) -> Result<LaunchOptions, LauncherError> {
+    let config = config::load_config()?;
+    let (_, versions_dir) = instance_dirs(instance_name)?;
+    let instance_dir = versions_dir.join(instance_name);
 
     if !instance_dir.join(format!("{}.json", instance_name)).exists() {
         return Err(LauncherError::Custom(format!("实例 '{}' 的配置文件不存在", instance_name)));
     }
 
-    // 更新上次启动时间
-    let _ = config::update_instance_last_played(&instance_name);
+    // 实例独立设置优先于全局配置
+    let settings = get_instance_settings(instance_name).unwrap_or_else(|e| {
+        warn!("读取实例 {} 的设置失败，使用全局配置: {}", instance_name, e);
+        InstanceSettings::default()
+    });
 
-    let launch_options = LaunchOptions {
-        version: instance_name,
+    Ok(LaunchOptions {
+        version: instance_name.to_string(),
         username: config.username.unwrap_or_else(|| "Player".to_string()),
-        memory: Some(config.max_memory),
-        window_width: config.window_width,
-        window_height: config.window_height,
-        fullscreen: Some(config.fullscreen),
-    };
+        memory: Some(settings.max_memory.unwrap_or(config.max_memory)),
+        java_path: settings.java_path.filter(|p| !p.trim().is_empty()),
+        window_width: settings.window_width.or(config.window_width),
+        window_height: settings.window_height.or(config.window_height),
+        fullscreen: Some(settings.fullscreen.unwrap_or(config.fullscreen)),
+        window_title: settings.window_title,
+        extra_jvm_args: settings.jvm_args,
+        extra_game_args: settings.game_args,
+        server: None,
+        transient: false,
+        troubleshooting: Vec::new(),
+    })
+}
 
-    launcher::launch_minecraft(launch_options, window).await
+/// 将单次启动的覆盖项合并到启动选项
+fn apply_launch_overrides(options: &mut LaunchOptions, overrides: LaunchOverrides) {
+    if let Some(username) = overrides.username.filter(|u| !u.trim().is_empty()) {
+        options.username = username;
+        options.transient = true;
+    }
+    options.memory = overrides.memory.or(options.memory);
+    options.window_width = overrides.window_width.or(options.window_width);
+    options.window_height = overrides.window_height.or(options.window_height);
+    options.fullscreen = overrides.fullscreen.or(options.fullscreen);
+    // 单次启动的参数追加在实例参数之后
+    options.extra_jvm_args.extend(overrides.extra_jvm_args);
+    options.extra_game_args.extend(overrides.extra_game_args);
+    options.server = overrides.server.filter(|s| !s.trim().is_empty());
+    options.troubleshooting = overrides.troubleshooting;
 }
 
 // --- 下面是合并 JSON 和收集下载任务的私有辅助函数 ---
@@ -399,7 +1337,7 @@ async fn merge_and_complete_instance(
     base_json_path: &Path,
     forge_json_path: &Path,
     game_dir: &Path,
-    window: &Window,
+    window: &EventSink,
 ) -> Result<(), LauncherError> {
     let base_content = fs::read_to_string(base_json_path)?;
     let forge_content = fs::read_to_string(forge_json_path)?;
@@ -0,0 +1,277 @@
+//! 实例归档（冷存储）
+//!
+//! 不常玩的实例压缩为 `<游戏目录>/archives/<实例名>.zip`（zstd 压缩）后删除版本目录，
+//! 同名的 `.json` 保存实例元数据，实例列表中仍显示为已归档；解档时解压回 versions 目录。
+//! 库文件和资源文件由多个实例共享，不包含在归档中，解档后启动前缺失的文件会自动补全。
+
+use crate::errors::LauncherError;
+use crate::models::InstanceInfo;
+use crate::services::instance_lock::{self, InstanceOperation};
+use crate::services::{config, instance, launcher, paths, version_index};
+use crate::utils::long_path;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+
+/// 归档目录（位于游戏目录）
+const ARCHIVE_DIR: &str = "archives";
+
+/// 已归档实例的元数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedInstance {
+    pub name: String,
+    /// 版本 JSON 中的 id
+    pub version: String,
+    pub mc_version: Option<String>,
+    pub loader_type: Option<String>,
+    pub loader_version: Option<String>,
+    /// 归档前所在的游戏目录
+    pub game_dir: String,
+    pub archive_path: String,
+    pub archived_at: String,
+    /// 归档前的目录大小（字节）
+    pub original_size: u64,
+    pub archive_size: u64,
+}
+
+impl ArchivedInstance {
+    /// 实例列表中的条目
+    pub fn to_info(&self) -> InstanceInfo {
+        InstanceInfo {
+            id: self.name.clone(),
+            name: self.name.clone(),
+            version: self.version.clone(),
+            path: self.archive_path.clone(),
+            created_time: None,
+            loader_type: self.loader_type.clone(),
+            game_version: self.mc_version.clone(),
+            last_played: config::get_instance_last_played(&self.name),
+            mc_version: self.mc_version.clone(),
+            loader_version: self.loader_version.clone(),
+            game_dir: self.game_dir.clone(),
+            health: None,
+            version_type: None,
+            missing_base: None,
+            verified_levels: Default::default(),
+            archived: true,
+        }
+    }
+}
+
+fn archive_paths(game_dir: &Path, instance_name: &str) -> (PathBuf, PathBuf) {
+    let dir = game_dir.join(ARCHIVE_DIR);
+    (
+        dir.join(format!("{}.zip", instance_name)),
+        dir.join(format!("{}.json", instance_name)),
+    )
+}
+
+/// 递归写入目录
+fn zip_dir(
+    zip: &mut zip::ZipWriter<fs::File>,
+    dir: &Path,
+    prefix: &str,
+    options: SimpleFileOptions,
+) -> Result<(), LauncherError> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let name = if prefix.is_empty() {
+            file_name
+        } else {
+            format!("{}/{}", prefix, file_name)
+        };
+        if path.is_dir() {
+            zip.add_directory(name.as_str(), options)?;
+            zip_dir(zip, &path, &name, options)?;
+        } else {
+            zip.start_file(name.as_str(), options)?;
+            io::copy(&mut fs::File::open(&path)?, zip)?;
+        }
+    }
+    Ok(())
+}
+
+/// 写入临时文件，完成后再改名，避免中断时留下不完整的归档
+pub(crate) fn write_archive(instance_dir: &Path, archive_path: &Path) -> Result<u64, LauncherError> {
+    let part = archive_path.with_extension("zip.part");
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Zstd)
+        .large_file(true);
+    let result = (|| {
+        let mut zip = zip::ZipWriter::new(fs::File::create(&part)?);
+        zip_dir(&mut zip, &long_path::extended(instance_dir), "", options)?;
+        zip.finish()?;
+        fs::rename(&part, archive_path)?;
+        Ok::<_, LauncherError>(fs::metadata(archive_path)?.len())
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&part);
+    }
+    result
+}
+
+pub(crate) fn extract_archive(archive_path: &Path, dest: &Path) -> Result<(), LauncherError> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(archive_path)?)?;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let Some(rel) = file.enclosed_name() else {
+            continue;
+        };
+        let out = long_path::extended(&dest.join(rel));
+        if file.is_dir() {
+            fs::create_dir_all(&out)?;
+            continue;
+        }
+        if let Some(parent) = out.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        io::copy(&mut file, &mut fs::File::create(&out)?)?;
+    }
+    Ok(())
+}
+
+/// 归档实例：压缩版本目录后删除（游戏运行中时需 `force` 才会结束游戏后归档）
+pub async fn archive_instance(instance_name: String, force: bool) -> Result<ArchivedInstance, LauncherError> {
+    let (game_dir, versions_dir) = instance::instance_dirs(&instance_name)?;
+    let instance_dir = versions_dir.join(&instance_name);
+    if !instance_dir.exists() {
+        return Err(LauncherError::Custom(format!("实例 '{}' 不存在", instance_name)));
+    }
+    instance::ensure_instance_stopped(&instance_name, &instance_dir, force).await?;
+    let _lock = instance_lock::acquire(&instance_name, InstanceOperation::Archive)?;
+
+    let (archive_path, meta_path) = archive_paths(&game_dir, &instance_name);
+    if archive_path.exists() {
+        return Err(LauncherError::Custom(format!(
+            "已存在同名归档: {}",
+            archive_path.display()
+        )));
+    }
+    fs::create_dir_all(archive_path.parent().unwrap_or(&game_dir))?;
+
+    let indexed = version_index::recorded(&versions_dir, &instance_name);
+    let archive_size = {
+        let (dir, path) = (instance_dir.clone(), archive_path.clone());
+        tokio::task::spawn_blocking(move || write_archive(&dir, &path)).await??
+    };
+    let archived = ArchivedInstance {
+        name: instance_name.clone(),
+        version: indexed
+            .as_ref()
+            .map(|v| v.id.clone())
+            .unwrap_or_else(|| instance_name.clone()),
+        mc_version: indexed.as_ref().and_then(|v| v.mc_version.clone()),
+        loader_type: indexed.as_ref().and_then(|v| v.loader_type.clone()),
+        loader_version: indexed.as_ref().and_then(|v| v.loader_version.clone()),
+        game_dir: game_dir.to_string_lossy().to_string(),
+        archive_path: archive_path.to_string_lossy().to_string(),
+        archived_at: chrono::Local::now().to_rfc3339(),
+        original_size: indexed.as_ref().map(|v| v.size).unwrap_or(0),
+        archive_size,
+    };
+    fs::write(&meta_path, serde_json::to_string_pretty(&archived)?)?;
+
+    instance::remove_dir_with_retry(&instance_dir).await.map_err(|e| {
+        LauncherError::Custom(format!(
+            "归档已保存到 {}，但删除实例目录失败: {}",
+            archive_path.display(),
+            e
+        ))
+    })?;
+    version_index::invalidate(&versions_dir, &instance_name);
+    launcher::clear_merged_cache();
+
+    log::info!(
+        "实例 {} 已归档: {} 字节 -> {} 字节 ({})",
+        instance_name,
+        archived.original_size,
+        archived.archive_size,
+        archive_path.display()
+    );
+    Ok(archived)
+}
+
+/// 解档实例：解压回归档前所在的游戏目录（该目录已移除时使用默认游戏目录）
+pub async fn unarchive_instance(instance_name: String) -> Result<(), LauncherError> {
+    let config = config::load_config()?;
+    let game_dirs = paths::game_dirs(&config);
+    let (archived, meta_path) = game_dirs
+        .iter()
+        .find_map(|dir| {
+            let (_, meta_path) = archive_paths(dir, &instance_name);
+            let content = fs::read_to_string(&meta_path).ok()?;
+            Some((serde_json::from_str::<ArchivedInstance>(&content).ok()?, meta_path))
+        })
+        .ok_or_else(|| LauncherError::Custom(format!("没有名为 '{}' 的归档", instance_name)))?;
+
+    let _lock = instance_lock::acquire(&instance_name, InstanceOperation::Archive)?;
+    if instance::instance_name_taken(&game_dirs, &instance_name) {
+        return Err(LauncherError::Custom(format!("实例名 '{}' 已被占用", instance_name)));
+    }
+    let original_dir = PathBuf::from(&archived.game_dir);
+    let game_dir = if game_dirs.contains(&original_dir) {
+        original_dir
+    } else {
+        PathBuf::from(&config.game_dir)
+    };
+    let versions_dir = game_dir.join("versions");
+    let instance_dir = versions_dir.join(&instance_name);
+    let staging = versions_dir.join(format!(".{}.unarchiving", instance_name));
+    let archive_path = PathBuf::from(&archived.archive_path);
+
+    {
+        let (archive_path, staging, instance_dir) = (archive_path.clone(), staging.clone(), instance_dir.clone());
+        tokio::task::spawn_blocking(move || {
+            let _ = fs::remove_dir_all(&staging);
+            fs::create_dir_all(&staging)?;
+            let result = extract_archive(&archive_path, &staging)
+                .and_then(|_| fs::rename(&staging, &instance_dir).map_err(LauncherError::from));
+            if result.is_err() {
+                let _ = fs::remove_dir_all(&staging);
+            }
+            result
+        })
+        .await??;
+    }
+
+    let _ = fs::remove_file(&archive_path);
+    let _ = fs::remove_file(&meta_path);
+    version_index::invalidate(&versions_dir, &instance_name);
+    launcher::clear_merged_cache();
+    log::info!("实例 {} 已从归档恢复到 {}", instance_name, instance_dir.display());
+    Ok(())
+}
+
+/// 各游戏目录中的已归档实例
+pub fn list_archived(game_dirs: &[PathBuf]) -> Vec<ArchivedInstance> {
+    let mut archived = Vec::new();
+    for dir in game_dirs {
+        let Ok(entries) = fs::read_dir(dir.join(ARCHIVE_DIR)) else {
+            continue;
+        };
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            match fs::read_to_string(&path)
+                .map_err(LauncherError::from)
+                .and_then(|c| serde_json::from_str::<ArchivedInstance>(&c).map_err(LauncherError::from))
+            {
+                Ok(entry) if Path::new(&entry.archive_path).exists() => archived.push(entry),
+                Ok(_) => log::warn!("归档文件缺失: {}", path.display()),
+                Err(e) => log::warn!("读取归档信息失败 {}: {}", path.display(), e),
+            }
+        }
+    }
+    archived
+}
+
+/// 当前配置的所有游戏目录中的已归档实例
+pub fn get_archived_instances() -> Result<Vec<ArchivedInstance>, LauncherError> {
+    Ok(list_archived(&paths::game_dirs(&config::load_config()?)))
+}
@@ -0,0 +1,304 @@
+//! 实例健康检查
+//!
+//! 汇总版本 JSON 校验、游戏文件快速校验、加载器产出文件校验、模组前置检查和 Java 版本
+//! 兼容性，给出评分和逐项问题及修复建议，供实例列表显示状态标记。结果按实例缓存，
+//! 实例目录、版本 JSON、mods 目录、实例设置或全局配置的修改时间变化后重新检查。
+
+use crate::errors::LauncherError;
+use crate::models::modpack::ModpackHealthStatus;
+use crate::models::VerificationLevel;
+use crate::services::loaders::forge;
+use crate::services::paths::{self, ContentKind};
+use crate::services::{config, file_verification, instance, java, launcher, modpack_health, version_info};
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// 缓存的最长有效期（库文件等不在指纹范围内的变化也能被发现）
+const CACHE_MAX_AGE: Duration = Duration::from_secs(10 * 60);
+/// 列出的缺失文件上限
+const MAX_LISTED_FILES: usize = 5;
+
+/// 建议的修复操作
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum HealthFix {
+    /// 校验并补全游戏文件
+    RepairFiles,
+    /// 重新安装模组加载器
+    ReinstallLoader,
+    /// 安装缺少的前置模组
+    InstallDependencies,
+    /// 更换为指定主版本的 Java
+    ChangeJava { major: u32 },
+}
+
+/// 单项检查发现的问题
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthFinding {
+    /// 检查项：version / files / loader / dependency / java
+    pub category: String,
+    pub message: String,
+    /// 是否会导致无法启动
+    pub fatal: bool,
+    /// 修复建议
+    pub suggestion: Option<String>,
+    pub fix: Option<HealthFix>,
+}
+
+/// 实例健康报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceHealth {
+    pub instance_name: String,
+    pub status: ModpackHealthStatus,
+    /// 0~100，每个致命问题扣 40 分，其他问题扣 10 分
+    pub score: u8,
+    pub checked_at: String,
+    pub findings: Vec<HealthFinding>,
+}
+
+struct CachedHealth {
+    fingerprint: u64,
+    created: Instant,
+    report: InstanceHealth,
+}
+
+static CACHE: LazyLock<Mutex<HashMap<String, CachedHealth>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn finding(
+    category: &str,
+    message: String,
+    fatal: bool,
+    suggestion: Option<&str>,
+    fix: Option<HealthFix>,
+) -> HealthFinding {
+    HealthFinding {
+        category: category.to_string(),
+        message,
+        fatal,
+        suggestion: suggestion.map(str::to_string),
+        fix,
+    }
+}
+
+/// 影响检查结果的文件的修改时间指纹
+fn fingerprint(instance_name: &str) -> Result<u64, LauncherError> {
+    let config = config::load_config()?;
+    let version_dir = paths::version_dir(&config, instance_name);
+    let paths = [
+        version_dir.clone(),
+        version_dir.join(format!("{}.json", instance_name)),
+        version_dir.join(instance::INSTANCE_SETTINGS_FILE),
+        paths::content_dir(&config, instance_name, ContentKind::Mods),
+        config::get_config_path()?,
+    ];
+    let mut hasher = DefaultHasher::new();
+    for path in &paths {
+        fs::metadata(path).and_then(|m| m.modified()).ok().hash(&mut hasher);
+    }
+    Ok(hasher.finish())
+}
+
+/// 从 `java -version` 输出的版本号解析主版本（`1.8.0_392` -> 8，`17.0.9` -> 17）
+fn java_major(version: &str) -> Option<u32> {
+    let mut parts = version.split(['.', '_', '-', '+']);
+    match parts.next()?.parse().ok()? {
+        1 => parts.next()?.parse().ok(),
+        major => Some(major),
+    }
+}
+
+async fn check_files(instance_name: &str, findings: &mut Vec<HealthFinding>) {
+    match file_verification::validate_version_files(instance_name.to_string(), VerificationLevel::Quick).await {
+        Ok(problems) if problems.is_empty() => {}
+        Ok(problems) => {
+            let listed = problems.iter().take(MAX_LISTED_FILES).cloned().collect::<Vec<_>>().join("；");
+            findings.push(finding(
+                "files",
+                format!("{} 个游戏文件缺失或损坏: {}", problems.len(), listed),
+                true,
+                Some("校验并补全游戏文件"),
+                Some(HealthFix::RepairFiles),
+            ));
+        }
+        Err(e) => log::warn!("实例 {} 文件校验失败: {}", instance_name, e),
+    }
+}
+
+async fn check_loader(instance_name: &str, loader_type: &str, findings: &mut Vec<HealthFinding>) {
+    if !matches!(loader_type, "forge" | "neoforge") {
+        return;
+    }
+    // 没有产出文件记录（旧版本安装的实例）时跳过
+    let Ok(report) = forge::verify_forge_install(instance_name.to_string()).await else {
+        return;
+    };
+    if !report.is_intact() {
+        findings.push(finding(
+            "loader",
+            format!(
+                "加载器安装不完整：缺失 {} 个、损坏 {} 个产出文件",
+                report.missing.len(),
+                report.corrupted.len()
+            ),
+            true,
+            Some("重新安装模组加载器"),
+            Some(HealthFix::ReinstallLoader),
+        ));
+    }
+}
+
+async fn check_java(instance_name: &str, findings: &mut Vec<HealthFinding>) -> Result<(), LauncherError> {
+    let Some(required) = launcher::required_java_major(instance_name)? else {
+        return Ok(());
+    };
+    let java_path = instance::get_instance_settings(instance_name)?
+        .java_path
+        .or(config::load_config()?.java_path)
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| "java".to_string());
+    let Some(actual) = java::get_java_version(java_path.clone()).await.ok().and_then(|v| java_major(&v)) else {
+        findings.push(finding(
+            "java",
+            format!("无法获取 Java 版本: {}", java_path),
+            true,
+            Some("在设置中选择有效的 Java"),
+            Some(HealthFix::ChangeJava { major: required }),
+        ));
+        return Ok(());
+    };
+    if actual < required {
+        findings.push(finding(
+            "java",
+            format!("该版本需要 Java {}，当前为 Java {}", required, actual),
+            true,
+            Some("更换为更高版本的 Java"),
+            Some(HealthFix::ChangeJava { major: required }),
+        ));
+    } else if required <= 8 && actual > 8 {
+        // 旧版本（尤其是旧版 Forge）在 Java 9+ 上常因类加载器变化崩溃
+        findings.push(finding(
+            "java",
+            format!("该版本为 Java {} 设计，当前为 Java {}，可能无法启动", required, actual),
+            false,
+            Some("建议使用 Java 8"),
+            Some(HealthFix::ChangeJava { major: required }),
+        ));
+    }
+    Ok(())
+}
+
+async fn run_checks(instance_name: &str) -> Result<InstanceHealth, LauncherError> {
+    let config = config::load_config()?;
+    let mut findings = Vec::new();
+
+    let version_issues = launcher::validate_version_json(instance_name)?;
+    let version_ok = !version_issues.iter().any(|i| i.fatal);
+    for issue in version_issues {
+        findings.push(finding(
+            "version",
+            format!("{}: {}", issue.location, issue.message),
+            issue.fatal,
+            issue.fatal.then_some("重新安装该版本或加载器"),
+            issue.fatal.then_some(HealthFix::ReinstallLoader),
+        ));
+    }
+
+    // 版本 JSON 无法解析时其余检查没有意义
+    if version_ok {
+        check_files(instance_name, &mut findings).await;
+        let versions_dir = paths::instance_game_dir(&config, instance_name).join("versions");
+        let loader_type = version_info::detect(&versions_dir, instance_name).loader_type.to_lowercase();
+        check_loader(instance_name, &loader_type, &mut findings).await;
+        check_java(instance_name, &mut findings).await?;
+    }
+
+    let mods_dir = paths::content_dir(&config, instance_name, ContentKind::Mods);
+    let dependency_issues = tokio::task::spawn_blocking(move || {
+        let mut issues = Vec::new();
+        modpack_health::check_dependencies(&mods_dir, &mut issues);
+        issues
+    })
+    .await?;
+    findings.extend(dependency_issues.into_iter().map(|i| {
+        finding(
+            "dependency",
+            i.message,
+            i.fatal,
+            Some("安装缺少的前置模组"),
+            Some(HealthFix::InstallDependencies),
+        )
+    }));
+
+    let penalty: u32 = findings.iter().map(|f| if f.fatal { 40 } else { 10 }).sum();
+    let status = if findings.iter().any(|f| f.fatal) {
+        ModpackHealthStatus::Red
+    } else if findings.is_empty() {
+        ModpackHealthStatus::Green
+    } else {
+        ModpackHealthStatus::Yellow
+    };
+    Ok(InstanceHealth {
+        instance_name: instance_name.to_string(),
+        status,
+        score: 100u32.saturating_sub(penalty) as u8,
+        checked_at: chrono::Utc::now().to_rfc3339(),
+        findings,
+    })
+}
+
+/// 获取实例健康报告；缓存有效时直接返回，`refresh` 为 true 时强制重新检查
+pub async fn get_instance_health(instance_name: &str, refresh: bool) -> Result<InstanceHealth, LauncherError> {
+    if !paths::version_dir(&config::load_config()?, instance_name).exists() {
+        return Err(LauncherError::Custom(format!("实例 '{}' 不存在", instance_name)));
+    }
+    let fingerprint = fingerprint(instance_name)?;
+    if !refresh {
+        let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(cached) = cache.get(instance_name) {
+            if cached.fingerprint == fingerprint && cached.created.elapsed() < CACHE_MAX_AGE {
+                return Ok(cached.report.clone());
+            }
+        }
+    }
+
+    let report = run_checks(instance_name).await?;
+    log::info!(
+        "实例 {} 健康检查: {:?}，{} 分，{} 个问题",
+        instance_name,
+        report.status,
+        report.score,
+        report.findings.len()
+    );
+    CACHE.lock().unwrap_or_else(|e| e.into_inner()).insert(
+        instance_name.to_string(),
+        CachedHealth {
+            fingerprint,
+            created: Instant::now(),
+            report: report.clone(),
+        },
+    );
+    Ok(report)
+}
+
+/// 实例列表使用的状态：只读取仍然有效的缓存，不触发检查
+pub fn cached_status(instance_name: &str) -> Option<ModpackHealthStatus> {
+    let fingerprint = fingerprint(instance_name).ok()?;
+    let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache
+        .get(instance_name)
+        .filter(|c| c.fingerprint == fingerprint)
+        .map(|c| c.report.status)
+}
+
+/// 使实例的缓存失效（安装、删除、重命名后调用）
+pub fn invalidate(instance_name: &str) {
+    CACHE.lock().unwrap_or_else(|e| e.into_inner()).remove(instance_name);
+}
+
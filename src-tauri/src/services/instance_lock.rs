@@ -0,0 +1,89 @@
+//! 实例级操作锁
+//!
+//! 防止对同一实例同时执行冲突的操作（如整合包安装时启动、游戏运行时删除），
+//! 冲突时返回 `LauncherError::InstanceBusy`，带上正在进行的操作名称。
+
+use crate::errors::LauncherError;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+/// 实例上正在进行的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceOperation {
+    Install,
+    ModpackInstall,
+    Launch,
+    Running,
+    Delete,
+    Rename,
+    Share,
+    Archive,
+    Sync,
+}
+
+impl InstanceOperation {
+    /// 操作的显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Install => "安装",
+            Self::ModpackInstall => "安装整合包",
+            Self::Launch => "启动",
+            Self::Running => "运行游戏",
+            Self::Delete => "删除",
+            Self::Rename => "重命名",
+            Self::Share => "分享",
+            Self::Archive => "归档",
+            Self::Sync => "同步",
+        }
+    }
+}
+
+static LOCKS: LazyLock<Mutex<HashMap<String, InstanceOperation>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 实例锁，离开作用域时自动释放
+#[derive(Debug)]
+pub struct InstanceLockGuard {
+    instance: String,
+}
+
+impl InstanceLockGuard {
+    /// 切换当前持有的操作（如启动完成后转为运行中）
+    pub fn set_operation(&self, operation: InstanceOperation) {
+        if let Ok(mut locks) = LOCKS.lock() {
+            locks.insert(self.instance.clone(), operation);
+        }
+    }
+}
+
+impl Drop for InstanceLockGuard {
+    fn drop(&mut self) {
+        if let Ok(mut locks) = LOCKS.lock() {
+            locks.remove(&self.instance);
+        }
+    }
+}
+
+/// 获取实例锁，实例正被其他操作占用时返回 Busy 错误
+pub fn acquire(instance: &str, operation: InstanceOperation) -> Result<InstanceLockGuard, LauncherError> {
+    let mut locks = LOCKS
+        .lock()
+        .map_err(|_| LauncherError::Custom("实例锁已损坏".to_string()))?;
+    if let Some(current) = locks.get(instance) {
+        return Err(LauncherError::InstanceBusy {
+            instance: instance.to_string(),
+            operation: current.name().to_string(),
+        });
+    }
+    locks.insert(instance.to_string(), operation);
+    Ok(InstanceLockGuard {
+        instance: instance.to_string(),
+    })
+}
+
+/// 查询实例当前正在进行的操作
+pub fn current_operation(instance: &str) -> Option<InstanceOperation> {
+    LOCKS.lock().ok()?.get(instance).copied()
+}
@@ -0,0 +1,440 @@
+//! 局域网实例分享
+//!
+//! 发送方将实例（含加载器库文件）打包，通过带令牌的临时 HTTP 服务提供下载；
+//! 接收方输入分享码（或扫描二维码得到的地址）下载并导入。模组和配置都来自局域网，
+//! 只有原版游戏文件和资源仍按正常流程从网络补全。
+
+use crate::errors::LauncherError;
+use crate::services::instance_lock::{self, InstanceOperation};
+use crate::services::{
+    config, download, http_client, instance, paths, version_index, version_info,
+};
+use crate::utils::file_utils::validate_instance_name_or_error;
+use crate::utils::maven;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use crate::services::event_sink::EventSink;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use zip::write::SimpleFileOptions;
+
+/// 分享包中的清单文件
+const SHARE_MANIFEST: &str = "ar1s_share.json";
+
+/// 分享包格式版本
+const SHARE_FORMAT: u32 = 1;
+
+/// 不随实例分享的目录（个人存档、日志和运行时生成的文件）
+const EXCLUDED_DIRS: [&str; 7] = [
+    "saves",
+    "screenshots",
+    "logs",
+    "crash-reports",
+    "natives",
+    "profiles",
+    "backups",
+];
+
+/// 分享服务在无人停止时自动关闭的时间
+const SHARE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// 请求头大小上限
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+/// 正在进行的分享（打包文件 + 停止信号）
+static SHARING: Mutex<Option<(PathBuf, tokio::sync::watch::Sender<bool>)>> = Mutex::new(None);
+
+/// 分享包清单
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ShareManifest {
+    format: u32,
+    instance_name: String,
+    created_at: String,
+}
+
+/// 正在分享的实例信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareInfo {
+    pub instance_name: String,
+    /// 分享码：`<地址>:<端口>/<令牌>`
+    pub code: String,
+    /// 完整下载地址，用于生成二维码
+    pub url: String,
+    pub pack_size: u64,
+    pub expires_at: String,
+}
+
+/// 接收进度
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReceiveProgress {
+    received: u64,
+    total: u64,
+}
+
+/// 随机令牌（128 位系统随机数）
+fn random_token() -> Result<String, LauncherError> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes)
+        .map_err(|e| LauncherError::Custom(format!("无法生成分享令牌: {}", e)))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// 本机在局域网中的地址（UDP connect 不会实际发送数据）
+fn lan_ip() -> String {
+    std::net::UdpSocket::bind(("0.0.0.0", 0))
+        .and_then(|socket| {
+            socket.connect(("8.8.8.8", 80))?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_else(|_| "127.0.0.1".to_string())
+}
+
+/// 实例版本 JSON 继承链引用的库文件（相对 libraries 目录）
+fn referenced_libraries(versions_dir: &Path, instance_name: &str) -> HashSet<String> {
+    let mut libraries = HashSet::new();
+    for json in version_info::load_chain(versions_dir, instance_name) {
+        for lib in json["libraries"].as_array().into_iter().flatten() {
+            let artifact = lib["downloads"]["artifact"]["path"]
+                .as_str()
+                .map(String::from)
+                .or_else(|| lib["name"].as_str().and_then(maven::maven_to_path));
+            libraries.extend(artifact);
+            if let Some(classifiers) = lib["downloads"]["classifiers"].as_object() {
+                libraries.extend(
+                    classifiers
+                        .values()
+                        .filter_map(|c| c["path"].as_str().map(String::from)),
+                );
+            }
+        }
+    }
+    libraries
+}
+
+fn zip_instance_dir<W: Write + io::Seek>(
+    zip: &mut zip::ZipWriter<W>,
+    dir: &Path,
+    prefix: &str,
+    options: SimpleFileOptions,
+) -> Result<(), LauncherError> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let name = format!("{}/{}", prefix, file_name);
+        // 实例设置含本机 Java 路径、JVM 参数和代理等，不随实例分享
+        if prefix == "instance" && file_name == instance::INSTANCE_SETTINGS_FILE {
+            continue;
+        }
+        if path.is_dir() {
+            if prefix == "instance" && EXCLUDED_DIRS.contains(&file_name.as_str()) {
+                continue;
+            }
+            zip.add_directory(name.as_str(), options)?;
+            zip_instance_dir(zip, &path, &name, options)?;
+        } else {
+            zip.start_file(name.as_str(), options)?;
+            io::copy(&mut fs::File::open(&path)?, zip)?;
+        }
+    }
+    Ok(())
+}
+
+/// 打包实例：`instance/` 为版本目录内容，`libraries/` 为本地已有的库文件
+fn build_pack(game_dir: &Path, instance_name: &str, out_path: &Path) -> Result<u64, LauncherError> {
+    let versions_dir = game_dir.join("versions");
+    // 模组 JAR 已经压缩过，直接存储更快
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let mut zip = zip::ZipWriter::new(fs::File::create(out_path)?);
+
+    let manifest = ShareManifest {
+        format: SHARE_FORMAT,
+        instance_name: instance_name.to_string(),
+        created_at: chrono::Local::now().to_rfc3339(),
+    };
+    zip.start_file(SHARE_MANIFEST, options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+
+    zip_instance_dir(&mut zip, &versions_dir.join(instance_name), "instance", options)?;
+
+    let libraries_dir = game_dir.join("libraries");
+    for relative in referenced_libraries(&versions_dir, instance_name) {
+        let path = libraries_dir.join(&relative);
+        if path.is_file() {
+            zip.start_file(format!("libraries/{}", relative), options)?;
+            io::copy(&mut fs::File::open(&path)?, &mut zip)?;
+        }
+    }
+
+    zip.finish()?;
+    Ok(fs::metadata(out_path)?.len())
+}
+
+/// 停止分享并删除临时打包文件
+pub fn stop_sharing() {
+    if let Ok(mut sharing) = SHARING.lock() {
+        if let Some((pack, tx)) = sharing.take() {
+            let _ = tx.send(true);
+            let _ = fs::remove_file(pack);
+        }
+    }
+}
+
+/// 开始在局域网中分享实例
+pub async fn start_sharing(instance_name: String) -> Result<ShareInfo, LauncherError> {
+    stop_sharing();
+    let config = config::load_config()?;
+    if !paths::version_dir(&config, &instance_name).exists() {
+        return Err(LauncherError::Custom(format!("实例 '{}' 不存在", instance_name)));
+    }
+    let _lock = instance_lock::acquire(&instance_name, InstanceOperation::Share)?;
+
+    let game_dir = paths::instance_game_dir(&config, &instance_name);
+    let temp_dir = game_dir.join("temp");
+    fs::create_dir_all(&temp_dir)?;
+    let pack_path = temp_dir.join(format!("share_{}.zip", instance_name));
+    let pack_size = {
+        let (game_dir, instance_name, pack_path) = (game_dir.clone(), instance_name.clone(), pack_path.clone());
+        tokio::task::spawn_blocking(move || build_pack(&game_dir, &instance_name, &pack_path)).await??
+    };
+
+    // 监听所有网卡，以便同一局域网内的设备访问
+    let listener = TcpListener::bind(("0.0.0.0", 0)).await?;
+    let port = listener.local_addr()?.port();
+    let token = random_token()?;
+    let ip = lan_ip();
+    let (tx, mut rx) = tokio::sync::watch::channel(false);
+    if let Ok(mut sharing) = SHARING.lock() {
+        *sharing = Some((pack_path.clone(), tx));
+    }
+
+    let served_token = token.clone();
+    let served_pack = pack_path.clone();
+    tauri::async_runtime::spawn(async move {
+        let deadline = tokio::time::sleep(SHARE_TIMEOUT);
+        tokio::pin!(deadline);
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    if let Ok((stream, peer)) = accepted {
+                        let (token, pack) = (served_token.clone(), served_pack.clone());
+                        tokio::spawn(async move {
+                            if let Err(e) = serve_pack(stream, &token, &pack).await {
+                                log::debug!("向 {} 发送实例失败: {}", peer, e);
+                            }
+                        });
+                    }
+                }
+                _ = rx.changed() => break,
+                _ = &mut deadline => {
+                    log::info!("实例分享已超时");
+                    stop_sharing();
+                    break;
+                }
+            }
+        }
+        log::info!("实例分享服务已停止");
+    });
+
+    log::info!("正在局域网分享实例 {}: {}:{}", instance_name, ip, port);
+    Ok(ShareInfo {
+        code: format!("{}:{}/{}", ip, port, token),
+        url: format!("http://{}:{}/pack?token={}", ip, port, token),
+        expires_at: (chrono::Local::now() + chrono::Duration::seconds(SHARE_TIMEOUT.as_secs() as i64))
+            .to_rfc3339(),
+        instance_name,
+        pack_size,
+    })
+}
+
+/// 处理一次下载请求：令牌正确时发送打包文件
+/// 以固定时间比较令牌，避免通过响应耗时逐位猜测
+fn tokens_match(given: &str, expected: &str) -> bool {
+    let (given, expected) = (given.as_bytes(), expected.as_bytes());
+    given.len() == expected.len() && given.iter().zip(expected).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+async fn serve_pack(mut stream: TcpStream, token: &str, pack: &Path) -> Result<(), LauncherError> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 || buf.len() > MAX_REQUEST_SIZE {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    let head = String::from_utf8_lossy(&buf);
+    let target = head.split_whitespace().nth(1).unwrap_or_default();
+    let authorized = target
+        .strip_prefix("/pack?token=")
+        .is_some_and(|t| tokens_match(t, token));
+
+    if !authorized {
+        stream
+            .write_all(b"HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .await?;
+        return Ok(());
+    }
+
+    let mut file = tokio::fs::File::open(pack).await?;
+    let len = file.metadata().await?.len();
+    let header = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/zip\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        len
+    );
+    stream.write_all(header.as_bytes()).await?;
+    tokio::io::copy(&mut file, &mut stream).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+/// 将分享码或二维码中的地址转换为下载地址
+fn share_url(code: &str) -> Result<String, LauncherError> {
+    let code = code.trim();
+    if code.starts_with("http://") {
+        return Ok(code.to_string());
+    }
+    let (addr, token) = code
+        .rsplit_once('/')
+        .filter(|(addr, token)| addr.contains(':') && !token.is_empty())
+        .ok_or_else(|| LauncherError::Custom("分享码格式无效".to_string()))?;
+    Ok(format!("http://{}/pack?token={}", addr, token))
+}
+
+fn read_manifest<R: io::Read + io::Seek>(archive: &mut zip::ZipArchive<R>) -> Result<ShareManifest, LauncherError> {
+    let mut entry = archive
+        .by_name(SHARE_MANIFEST)
+        .map_err(|_| LauncherError::Custom("不是有效的实例分享包".to_string()))?;
+    let mut content = String::new();
+    io::Read::read_to_string(&mut entry, &mut content)?;
+    let manifest: ShareManifest = serde_json::from_str(&content)?;
+    if manifest.format > SHARE_FORMAT {
+        return Err(LauncherError::Custom("分享包来自更新版本的启动器，请先升级".to_string()));
+    }
+    // 实例名来自发送方，用于拼接文件路径前必须校验
+    validate_instance_name_or_error(&manifest.instance_name)?;
+    Ok(manifest)
+}
+
+/// 解压分享包（未指定名称时使用原实例名），返回导入后的实例名
+fn extract_pack(pack: &Path, game_dir: &Path, instance_name: Option<String>) -> Result<String, LauncherError> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(pack)?)?;
+    let manifest = read_manifest(&mut archive)?;
+    let name = instance_name.unwrap_or_else(|| manifest.instance_name.clone());
+    let validation = instance::check_instance_name_available(&name);
+    if !validation.is_valid {
+        return Err(LauncherError::Custom(validation.error_message.unwrap_or_default()));
+    }
+    let instance_dir = game_dir.join("versions").join(&name);
+    let libraries_dir = game_dir.join("libraries");
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        let target = if let Ok(rest) = relative.strip_prefix("instance") {
+            // 旧版本打包的实例设置不导入
+            if rest == Path::new(instance::INSTANCE_SETTINGS_FILE) {
+                continue;
+            }
+            instance_dir.join(rest)
+        } else if let Ok(rest) = relative.strip_prefix("libraries") {
+            // 已有的库文件不覆盖
+            let target = libraries_dir.join(rest);
+            if target.exists() {
+                continue;
+            }
+            target
+        } else {
+            continue;
+        };
+        if entry.is_dir() {
+            fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        io::copy(&mut entry, &mut fs::File::create(&target)?)?;
+    }
+
+    // 以新名称导入时同步修改版本文件名和 id
+    if name != manifest.instance_name {
+        let old = &manifest.instance_name;
+        let _ = fs::rename(instance_dir.join(format!("{}.jar", old)), instance_dir.join(format!("{}.jar", name)));
+        let old_json = instance_dir.join(format!("{}.json", old));
+        if old_json.exists() {
+            let mut json: Value = serde_json::from_str(&fs::read_to_string(&old_json)?)?;
+            json["id"] = Value::String(name.clone());
+            fs::write(instance_dir.join(format!("{}.json", name)), serde_json::to_string_pretty(&json)?)?;
+            fs::remove_file(old_json)?;
+        }
+    }
+    Ok(name)
+}
+
+/// 通过分享码接收实例，返回导入后的实例名
+pub async fn receive_instance(
+    code: String,
+    instance_name: Option<String>,
+    window: &EventSink,
+) -> Result<String, LauncherError> {
+    let url = share_url(&code)?;
+    let config = config::load_config()?;
+    let game_dir = PathBuf::from(&config.game_dir);
+    let temp_dir = game_dir.join("temp");
+    fs::create_dir_all(&temp_dir)?;
+    let pack_path = temp_dir.join(format!("receive_{}.zip", chrono::Local::now().format("%Y%m%d%H%M%S")));
+
+    let mut response = http_client::get_client()
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()
+        .map_err(|e| LauncherError::Custom(format!("分享码无效或分享已结束: {}", e)))?;
+    let total = response.content_length().unwrap_or(0);
+    let mut file = tokio::fs::File::create(&pack_path).await?;
+    let mut received = 0u64;
+    let mut last_percent = 0;
+    while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk).await?;
+        received += chunk.len() as u64;
+        let percent = if total > 0 { received * 100 / total } else { 0 };
+        if percent != last_percent {
+            last_percent = percent;
+            let _ = window.emit("instance-receive-progress", ReceiveProgress { received, total });
+        }
+    }
+    file.flush().await?;
+    drop(file);
+
+    let result = async {
+        if let Some(name) = &instance_name {
+            validate_instance_name_or_error(name)?;
+        }
+        let (pack, dir) = (pack_path.clone(), game_dir.clone());
+        let name = tokio::task::spawn_blocking(move || extract_pack(&pack, &dir, instance_name)).await??;
+        version_index::invalidate(&game_dir.join("versions"), &name);
+        // 补全原版游戏文件和资源
+        download::process_and_download_version(name.clone(), config.download_mirror.clone(), window).await?;
+        Ok::<_, LauncherError>(name)
+    }
+    .await;
+
+    let _ = fs::remove_file(&pack_path);
+    let name = result?;
+    log::info!("已从局域网接收实例 {}", name);
+    Ok(name)
+}
@@ -1,4 +1,6 @@
-use crate::{load_config, save_config, LauncherError};
+use crate::errors::LauncherError;
+use crate::services::config::{load_config, save_config};
+use crate::utils::encoding::decode_process_output;
 use rayon::prelude::*;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -83,8 +85,8 @@ fn is_valid_java_executable(java_path: &Path) -> bool {
             }
 
             // 检查输出中是否包含Java版本信息
-            let stderr_str = String::from_utf8_lossy(&output.stderr);
-            let stdout_str = String::from_utf8_lossy(&output.stdout);
+            let stderr_str = decode_process_output(&output.stderr);
+            let stdout_str = decode_process_output(&output.stdout);
 
             // 检查常见的Java版本标识
             stderr_str.contains("java version")
@@ -535,8 +537,8 @@ pub async fn get_java_version(path: String) -> Result<String, LauncherError> {
     match command.output() {
         Ok(output) => {
             // Java 版本信息通常输出到 stderr
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = decode_process_output(&output.stderr);
+            let stdout = decode_process_output(&output.stdout);
             let version_output = if stderr.contains("version") { stderr } else { stdout };
 
             // 提取版本号
@@ -0,0 +1,344 @@
+//! 托管 Java 运行时（Mojang java-runtime）
+//!
+//! 运行时安装在 `<游戏目录>/runtime/<组件名>`，安装时将期望的文件清单
+//! （路径、SHA1、大小）保存在 `<组件名>.manifest.json`。清理工具误删部分
+//! 文件后，可通过 `verify_runtime` 校验，修复时只重新下载缺失或损坏的文件。
+
+use crate::errors::LauncherError;
+use crate::services::{config, http_client};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// Mojang 运行时总清单
+const RUNTIME_INDEX_URL: &str = "https://launchermeta.mojang.com/v1/products/java-runtime/2ec0cc96c44e5a76b9c8b7c39df7210883d12871/all.json";
+
+/// 清单中的单个文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeFile {
+    pub sha1: String,
+    pub size: u64,
+    pub url: String,
+    #[serde(default)]
+    pub executable: bool,
+}
+
+/// 保存在本地的运行时清单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredManifest {
+    component: String,
+    version: String,
+    files: BTreeMap<String, RuntimeFile>,
+    #[serde(default)]
+    links: BTreeMap<String, String>,
+}
+
+/// 已安装的托管运行时
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedRuntime {
+    pub id: String,
+    pub version: String,
+    pub java_path: String,
+}
+
+/// 运行时校验结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeVerifyReport {
+    pub id: String,
+    pub total_files: usize,
+    pub missing: Vec<String>,
+    pub corrupted: Vec<String>,
+}
+
+impl RuntimeVerifyReport {
+    pub fn is_intact(&self) -> bool {
+        self.missing.is_empty() && self.corrupted.is_empty()
+    }
+}
+
+fn runtimes_dir() -> Result<PathBuf, LauncherError> {
+    Ok(Path::new(&config::load_config()?.game_dir).join("runtime"))
+}
+
+fn manifest_path(runtimes_dir: &Path, id: &str) -> PathBuf {
+    runtimes_dir.join(format!("{}.manifest.json", id))
+}
+
+fn load_manifest(runtimes_dir: &Path, id: &str) -> Result<StoredManifest, LauncherError> {
+    let path = manifest_path(runtimes_dir, id);
+    let content = fs::read_to_string(&path)
+        .map_err(|_| LauncherError::Custom(format!("运行时 {} 未安装或缺少文件清单", id)))?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Mojang 清单中的平台名
+fn platform_key() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("windows", "x86_64") => Some("windows-x64"),
+        ("windows", "x86") => Some("windows-x86"),
+        ("windows", "aarch64") => Some("windows-arm64"),
+        ("linux", "x86_64") => Some("linux"),
+        ("linux", "x86") => Some("linux-i386"),
+        ("macos", "x86_64") => Some("mac-os"),
+        ("macos", "aarch64") => Some("mac-os-arm64"),
+        _ => None,
+    }
+}
+
+/// 运行时中的 Java 可执行文件（相对路径）
+fn java_executable(manifest: &StoredManifest) -> Option<&str> {
+    let name = if cfg!(windows) { "bin/javaw.exe" } else { "bin/java" };
+    manifest
+        .files
+        .keys()
+        .filter(|p| p.ends_with(name))
+        .min_by_key(|p| p.len())
+        .map(String::as_str)
+}
+
+/// 托管运行时对应的 Java 路径属于哪个运行时
+pub fn managed_runtime_of(java_path: &str) -> Option<String> {
+    let dir = runtimes_dir().ok()?;
+    let relative = Path::new(java_path).strip_prefix(&dir).ok()?;
+    let id = relative.components().next()?.as_os_str().to_string_lossy().to_string();
+    manifest_path(&dir, &id).exists().then_some(id)
+}
+
+/// 列出已安装的托管运行时
+pub fn list_runtimes() -> Result<Vec<ManagedRuntime>, LauncherError> {
+    let dir = runtimes_dir()?;
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+    let mut runtimes: Vec<ManagedRuntime> = entries
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let id = name.strip_suffix(".manifest.json")?;
+            let manifest = load_manifest(&dir, id).ok()?;
+            let java = java_executable(&manifest)?;
+            Some(ManagedRuntime {
+                id: id.to_string(),
+                version: manifest.version.clone(),
+                java_path: dir.join(id).join(java).to_string_lossy().to_string(),
+            })
+        })
+        .collect();
+    runtimes.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(runtimes)
+}
+
+/// 校验运行时文件；`full` 为 false 时只检查存在性和大小（启动前快速检查）
+fn check_files(runtimes_dir: &Path, id: &str, full: bool) -> Result<RuntimeVerifyReport, LauncherError> {
+    let manifest = load_manifest(runtimes_dir, id)?;
+    let root = runtimes_dir.join(id);
+    let mut report = RuntimeVerifyReport {
+        id: id.to_string(),
+        total_files: manifest.files.len(),
+        ..Default::default()
+    };
+    for (path, file) in &manifest.files {
+        let full_path = root.join(path);
+        match fs::metadata(&full_path) {
+            Err(_) => report.missing.push(path.clone()),
+            Ok(meta) if meta.len() != file.size => report.corrupted.push(path.clone()),
+            Ok(_) if full => {
+                if !crate::utils::file_utils::verify_file(&full_path, &file.sha1, file.size)? {
+                    report.corrupted.push(path.clone());
+                }
+            }
+            Ok(_) => {}
+        }
+    }
+    Ok(report)
+}
+
+/// 完整校验运行时（计算所有文件的 SHA1）
+pub async fn verify_runtime(id: String) -> Result<RuntimeVerifyReport, LauncherError> {
+    let dir = runtimes_dir()?;
+    tokio::task::spawn_blocking(move || check_files(&dir, &id, true)).await?
+}
+
+/// 下载单个运行时文件并校验哈希
+async fn download_runtime_file(client: &reqwest::Client, dest: &Path, file: &RuntimeFile) -> Result<(), LauncherError> {
+    let response = client.get(&file.url).send().await?.error_for_status()?;
+    let bytes = response.bytes().await?;
+    let actual = format!("{:x}", Sha1::digest(&bytes));
+    if !actual.eq_ignore_ascii_case(&file.sha1) {
+        return Err(LauncherError::Custom(format!(
+            "运行时文件哈希不匹配: {} (期望 {}, 实际 {})",
+            dest.display(),
+            file.sha1,
+            actual
+        )));
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(dest, &bytes)?;
+    #[cfg(unix)]
+    if file.executable {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(dest, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+/// 只下载指定的文件，并补齐符号链接
+async fn download_files(runtimes_dir: &Path, manifest: &StoredManifest, paths: &[String]) -> Result<(), LauncherError> {
+    let root = runtimes_dir.join(&manifest.component);
+    let threads = config::load_config()?.download_threads.max(1) as usize;
+    let semaphore = Arc::new(Semaphore::new(threads));
+    let client = http_client::get_client();
+
+    let mut tasks = tokio::task::JoinSet::new();
+    for path in paths {
+        let Some(file) = manifest.files.get(path).cloned() else {
+            continue;
+        };
+        let dest = root.join(path);
+        let semaphore = semaphore.clone();
+        let client = client.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            download_runtime_file(&client, &dest, &file).await
+        });
+    }
+    while let Some(result) = tasks.join_next().await {
+        if let Err(e) = result? {
+            tasks.abort_all();
+            return Err(e);
+        }
+    }
+
+    #[cfg(unix)]
+    for (link, target) in &manifest.links {
+        let link_path = root.join(link);
+        if fs::symlink_metadata(&link_path).is_err() {
+            if let Some(parent) = link_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            std::os::unix::fs::symlink(target, &link_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// 修复运行时：只重新下载缺失或损坏的文件
+pub async fn repair_runtime(id: String) -> Result<RuntimeVerifyReport, LauncherError> {
+    let report = verify_runtime(id.clone()).await?;
+    if report.is_intact() {
+        return Ok(report);
+    }
+    let dir = runtimes_dir()?;
+    let manifest = load_manifest(&dir, &id)?;
+    let broken: Vec<String> = report.missing.iter().chain(&report.corrupted).cloned().collect();
+    log::info!("修复运行时 {}: 重新下载 {} 个文件", id, broken.len());
+    download_files(&dir, &manifest, &broken).await?;
+    Ok(report)
+}
+
+/// 启动前快速检查托管运行时，发现缺失文件时自动修复
+pub async fn ensure_runtime_intact(id: &str) -> Result<Option<RuntimeVerifyReport>, LauncherError> {
+    let dir = runtimes_dir()?;
+    let quick = {
+        let dir = dir.clone();
+        let id = id.to_string();
+        tokio::task::spawn_blocking(move || check_files(&dir, &id, false)).await??
+    };
+    if quick.is_intact() {
+        return Ok(None);
+    }
+    log::warn!(
+        "托管运行时 {} 不完整（缺失 {}，损坏 {}），正在修复",
+        id,
+        quick.missing.len(),
+        quick.corrupted.len()
+    );
+    let manifest = load_manifest(&dir, id)?;
+    let broken: Vec<String> = quick.missing.iter().chain(&quick.corrupted).cloned().collect();
+    download_files(&dir, &manifest, &broken).await?;
+    Ok(Some(quick))
+}
+
+/// 安装 Mojang 运行时组件（如 `java-runtime-gamma`），返回 Java 路径
+pub async fn install_runtime(component: String) -> Result<ManagedRuntime, LauncherError> {
+    let platform = platform_key()
+        .ok_or_else(|| LauncherError::Custom("当前平台没有可用的托管 Java 运行时".to_string()))?;
+    let client = http_client::get_client();
+
+    let index: serde_json::Value = client.get(RUNTIME_INDEX_URL).send().await?.json().await?;
+    let entry = index[platform][&component]
+        .as_array()
+        .and_then(|list| list.first())
+        .ok_or_else(|| LauncherError::Custom(format!("平台 {} 没有运行时组件 {}", platform, component)))?;
+    let manifest_url = entry["manifest"]["url"]
+        .as_str()
+        .ok_or_else(|| LauncherError::Custom(format!("运行时 {} 缺少清单地址", component)))?;
+    let version = entry["version"]["name"].as_str().unwrap_or_default().to_string();
+
+    let raw: serde_json::Value = client.get(manifest_url).send().await?.json().await?;
+    let mut manifest = StoredManifest {
+        component: component.clone(),
+        version,
+        files: BTreeMap::new(),
+        links: BTreeMap::new(),
+    };
+    for (path, item) in raw["files"].as_object().into_iter().flatten() {
+        match item["type"].as_str() {
+            Some("file") => {
+                let raw_download = &item["downloads"]["raw"];
+                let (Some(sha1), Some(size), Some(url)) = (
+                    raw_download["sha1"].as_str(),
+                    raw_download["size"].as_u64(),
+                    raw_download["url"].as_str(),
+                ) else {
+                    continue;
+                };
+                manifest.files.insert(
+                    path.clone(),
+                    RuntimeFile {
+                        sha1: sha1.to_string(),
+                        size,
+                        url: url.to_string(),
+                        executable: item["executable"].as_bool().unwrap_or(false),
+                    },
+                );
+            }
+            Some("link") => {
+                if let Some(target) = item["target"].as_str() {
+                    manifest.links.insert(path.clone(), target.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let dir = runtimes_dir()?;
+    fs::create_dir_all(&dir)?;
+    fs::write(manifest_path(&dir, &component), serde_json::to_string_pretty(&manifest)?)?;
+
+    let report = {
+        let dir = dir.clone();
+        let component = component.clone();
+        tokio::task::spawn_blocking(move || check_files(&dir, &component, true)).await??
+    };
+    let needed: Vec<String> = report.missing.iter().chain(&report.corrupted).cloned().collect();
+    log::info!("安装运行时 {} {}: 下载 {} 个文件", component, manifest.version, needed.len());
+    download_files(&dir, &manifest, &needed).await?;
+
+    let java = java_executable(&manifest)
+        .ok_or_else(|| LauncherError::Custom(format!("运行时 {} 中未找到 Java 可执行文件", component)))?;
+    Ok(ManagedRuntime {
+        java_path: dir.join(&component).join(java).to_string_lossy().to_string(),
+        version: manifest.version.clone(),
+        id: component,
+    })
+}
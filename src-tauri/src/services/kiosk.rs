@@ -0,0 +1,186 @@
+//! 管理员锁定（机房 / 网吧等共享电脑使用）
+//!
+//! 管理员设置 PIN 后进入锁定模式：修改设置、删除或重命名实例、修改账户、启动时附加 JVM/游戏参数、
+//! 打开终端、恢复备份、导入本地整合包、安装模组和 Java 运行时、分享或接收实例和管理定时任务等都需要先用 PIN 解锁。
+//! 解锁只在本次运行中有效，超时或手动锁定后恢复。PIN 以加盐 SHA-256 保存在配置目录中的
+//! 独立文件里，不会随 `get_config` 返回给前端；该文件损坏或无法读取时保持锁定。
+
+use crate::errors::LauncherError;
+use crate::services::config;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// 解锁后的有效时间
+const UNLOCK_DURATION: Duration = Duration::from_secs(10 * 60);
+/// 连续输错该次数后暂时禁止尝试
+const MAX_FAILED_ATTEMPTS: u32 = 5;
+/// 输错过多后的等待时间
+const LOCKOUT_DURATION: Duration = Duration::from_secs(60);
+
+/// 解锁截止时间
+static UNLOCKED_UNTIL: Mutex<Option<Instant>> = Mutex::new(None);
+/// 连续输错次数和最后一次输错时间
+static FAILED_ATTEMPTS: Mutex<(u32, Option<Instant>)> = Mutex::new((0, None));
+
+/// 保存的 PIN
+#[derive(Serialize, Deserialize)]
+struct AdminPin {
+    salt: String,
+    hash: String,
+}
+
+/// 锁定状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KioskStatus {
+    /// 是否设置了管理员 PIN
+    pub enabled: bool,
+    /// 本次运行是否已解锁
+    pub unlocked: bool,
+    /// 解锁剩余秒数
+    pub unlock_remaining_secs: Option<u64>,
+}
+
+fn pin_path() -> Result<PathBuf, LauncherError> {
+    Ok(config::get_config_path()?.with_file_name("ar1s_admin.json"))
+}
+
+/// 读取保存的 PIN；文件存在却无法读取或已损坏时返回错误，调用方按锁定处理
+fn read_pin() -> Result<Option<AdminPin>, LauncherError> {
+    let path = pin_path()?;
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(pin_file_error(&path, e)),
+    };
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| pin_file_error(&path, e))
+}
+
+fn pin_file_error(path: &Path, e: impl std::fmt::Display) -> LauncherError {
+    log::error!("读取管理员 PIN 文件失败: {}", e);
+    LauncherError::Custom(format!(
+        "管理员 PIN 文件无法读取或已损坏，启动器保持锁定。如需重置请手动删除 {}",
+        path.display()
+    ))
+}
+
+fn hash_pin(salt: &str, pin: &str) -> String {
+    format!("{:x}", Sha256::digest(format!("{}:{}", salt, pin).as_bytes()))
+}
+
+fn new_salt() -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos());
+    format!("{:016x}", hasher.finish())
+}
+
+fn validate_pin(pin: &str) -> Result<(), LauncherError> {
+    if !(4..=12).contains(&pin.len()) || !pin.chars().all(|c| c.is_ascii_digit()) {
+        return Err(LauncherError::Custom("管理员 PIN 必须是 4~12 位数字".to_string()));
+    }
+    Ok(())
+}
+
+fn unlock_remaining() -> Option<Duration> {
+    let until = (*UNLOCKED_UNTIL.lock().ok()?)?;
+    until.checked_duration_since(Instant::now())
+}
+
+/// 当前锁定状态
+pub fn kiosk_status() -> KioskStatus {
+    // PIN 文件损坏时也视为已启用锁定
+    let enabled = !matches!(read_pin(), Ok(None));
+    let remaining = enabled.then(unlock_remaining).flatten();
+    KioskStatus {
+        enabled,
+        unlocked: !enabled || remaining.is_some(),
+        unlock_remaining_secs: remaining.map(|d| d.as_secs()),
+    }
+}
+
+/// 受保护的操作调用前检查：未设置 PIN 或已解锁时通过
+pub fn require_admin() -> Result<(), LauncherError> {
+    if read_pin()?.is_none() || unlock_remaining().is_some() {
+        return Ok(());
+    }
+    Err(LauncherError::Custom(
+        "启动器已被管理员锁定，请先输入管理员 PIN 解锁".to_string(),
+    ))
+}
+
+/// 用 PIN 解锁本次运行中的管理操作
+pub fn unlock_admin(pin: &str) -> Result<KioskStatus, LauncherError> {
+    let Some(saved) = read_pin()? else {
+        return Ok(kiosk_status());
+    };
+    let mut failed = FAILED_ATTEMPTS
+        .lock()
+        .map_err(|_| LauncherError::Custom("锁定状态不可用".to_string()))?;
+    if let (count, Some(last)) = *failed {
+        if count >= MAX_FAILED_ATTEMPTS {
+            if last.elapsed() < LOCKOUT_DURATION {
+                let wait = (LOCKOUT_DURATION - last.elapsed()).as_secs() + 1;
+                return Err(LauncherError::Custom(format!("PIN 错误次数过多，请 {} 秒后再试", wait)));
+            }
+            *failed = (0, None);
+        }
+    }
+
+    if hash_pin(&saved.salt, pin) != saved.hash {
+        failed.0 += 1;
+        failed.1 = Some(Instant::now());
+        log::warn!("管理员解锁失败（第 {} 次）", failed.0);
+        return Err(LauncherError::Custom("管理员 PIN 错误".to_string()));
+    }
+    *failed = (0, None);
+    drop(failed);
+
+    if let Ok(mut until) = UNLOCKED_UNTIL.lock() {
+        *until = Some(Instant::now() + UNLOCK_DURATION);
+    }
+    log::info!("管理员已解锁");
+    Ok(kiosk_status())
+}
+
+/// 立即恢复锁定
+pub fn lock_admin() -> KioskStatus {
+    if let Ok(mut until) = UNLOCKED_UNTIL.lock() {
+        *until = None;
+    }
+    kiosk_status()
+}
+
+/// 设置或清除管理员 PIN（已设置 PIN 时需先解锁）
+pub fn set_admin_pin(pin: Option<&str>) -> Result<KioskStatus, LauncherError> {
+    require_admin()?;
+    let path = pin_path()?;
+    match pin {
+        Some(pin) => {
+            validate_pin(pin)?;
+            let salt = new_salt();
+            let saved = AdminPin {
+                hash: hash_pin(&salt, pin),
+                salt,
+            };
+            fs::write(&path, serde_json::to_string_pretty(&saved)?)?;
+            log::info!("已设置管理员 PIN，启动器进入锁定模式");
+        }
+        None => {
+            if path.exists() {
+                fs::remove_file(&path)?;
+            }
+            log::info!("已清除管理员 PIN，退出锁定模式");
+        }
+    }
+    // 设置后保持锁定，清除后无需解锁
+    lock_admin();
+    Ok(kiosk_status())
+}
@@ -1,8 +1,17 @@
 //! JVM 和游戏参数构建逻辑
 
-use crate::models::{GameConfig, LaunchOptions};
+use super::rules::{rules_allow, RuleEnv};
+use crate::models::LaunchOptions;
+use crate::services::auth::AuthSession;
 use std::path::Path;
 
+/// 启动器名称（${launcher_name}）
+const LAUNCHER_NAME: &str = "ar1s_launcher";
+
+/// 未设置分辨率时的默认窗口大小（与原版启动器一致）
+const DEFAULT_WINDOW_WIDTH: u32 = 854;
+const DEFAULT_WINDOW_HEIGHT: u32 = 480;
+
 /// 从版本 JSON 中获取基础 Minecraft 版本名
 /// 用于 Forge 的 ignoreList 参数（需要原版 MC jar 文件名）
 fn get_base_minecraft_version(version_json: &serde_json::Value, fallback: &str) -> String {
@@ -41,39 +50,52 @@ fn get_base_minecraft_version(version_json: &serde_json::Value, fallback: &str)
 /// 构建 JVM 和游戏参数
 pub fn build_arguments(
     version_json: &serde_json::Value,
-    config: &GameConfig,
     options: &LaunchOptions,
-    uuid: &str,
-    version_dir: &Path,
+    session: &AuthSession,
+    run_dir: &Path,
     game_dir: &Path,
     assets_dir: &Path,
     assets_index: &str,
+    natives_dir: &Path,
     current_os: &str,
     classpath: &[std::path::PathBuf],
     emit: &impl Fn(&str, String),
 ) -> (Vec<String>, Vec<String>) {
     let libraries_dir = game_dir.join("libraries");
     let classpath_separator = if cfg!(windows) { ";" } else { ":" };
+    let classpath_str = classpath
+        .iter()
+        .map(|p| p.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(classpath_separator);
+    let rule_env = RuleEnv::new(current_os);
     
     // 获取原版 Minecraft 版本名（用于 Forge 的 ignoreList）
     // 优先使用 jar 字段，其次从 inheritsFrom 链中查找基础 MC 版本
     let base_mc_version = get_base_minecraft_version(version_json, &options.version);
     
+    let resolution_width = options.window_width.unwrap_or(DEFAULT_WINDOW_WIDTH).to_string();
+    let resolution_height = options.window_height.unwrap_or(DEFAULT_WINDOW_HEIGHT).to_string();
+    // 1.6 之前的版本使用 virtual/legacy 资源目录
+    let game_assets = if assets_index == "legacy" || assets_index == "pre-1.6" {
+        assets_dir.join("virtual").join("legacy")
+    } else {
+        assets_dir.to_path_buf()
+    };
+    let legacy_session = session.legacy_session();
+    let unknown_placeholder = regex::Regex::new(r"\$\{[A-Za-z0-9_.]+\}").ok();
+
     let replace_placeholders = |arg: &str| -> String {
-        let actual_game_dir = if config.version_isolation {
-            version_dir.to_string_lossy().to_string()
-        } else {
-            game_dir.to_string_lossy().to_string()
-        };
+        let actual_game_dir = run_dir.to_string_lossy().to_string();
 
-        arg.replace("${auth_player_name}", &options.username)
+        let replaced = arg.replace("${auth_player_name}", &session.username)
             .replace("${version_name}", &base_mc_version)
             .replace("${game_directory}", &actual_game_dir)
             .replace("${assets_root}", &assets_dir.to_string_lossy())
             .replace("${assets_index_name}", assets_index)
-            .replace("${auth_uuid}", uuid)
-            .replace("${auth_access_token}", "0")
-            .replace("${user_type}", "mojang")
+            .replace("${auth_uuid}", &session.uuid)
+            .replace("${auth_access_token}", &session.access_token)
+            .replace("${user_type}", session.user_type.as_str())
             .replace(
                 "${version_type}",
                 version_json["type"].as_str().unwrap_or("release"),
@@ -82,6 +104,28 @@ pub fn build_arguments(
             // 新版 Forge (1.13+) 需要的占位符
             .replace("${library_directory}", &libraries_dir.to_string_lossy())
             .replace("${classpath_separator}", classpath_separator)
+            .replace("${natives_directory}", &natives_dir.to_string_lossy())
+            .replace("${classpath}", &classpath_str)
+            // 1.19+ 与启动器信息相关的占位符
+            .replace("${clientid}", session.client_id.as_deref().unwrap_or("0"))
+            .replace("${auth_xuid}", session.xuid.as_deref().unwrap_or("0"))
+            .replace("${auth_session}", &legacy_session)
+            .replace("${game_assets}", &game_assets.to_string_lossy())
+            .replace("${resolution_width}", &resolution_width)
+            .replace("${resolution_height}", &resolution_height)
+            .replace("${launcher_name}", LAUNCHER_NAME)
+            .replace("${launcher_version}", env!("CARGO_PKG_VERSION"));
+
+        // 去掉仍未识别的占位符，避免原样传给游戏
+        match &unknown_placeholder {
+            Some(re) if re.is_match(&replaced) => {
+                for m in re.find_iter(&replaced) {
+                    emit("log-warning", format!("未知的启动参数占位符 {}，已移除", m.as_str()));
+                }
+                re.replace_all(&replaced, "").into_owned()
+            }
+            _ => replaced,
+        }
     };
 
     let mut jvm_args = vec![];
@@ -89,8 +133,8 @@ pub fn build_arguments(
 
     // 处理新版 (1.13+) `arguments` 格式
     if let Some(arguments) = version_json.get("arguments") {
-        jvm_args = parse_jvm_arguments(arguments, current_os, &replace_placeholders);
-        game_args_vec = parse_game_arguments(arguments, &replace_placeholders);
+        jvm_args = parse_jvm_arguments(arguments, &rule_env, &replace_placeholders);
+        game_args_vec = parse_game_arguments(arguments, &rule_env, &replace_placeholders);
     }
     // 处理旧版 `minecraftArguments` 格式
     else if let Some(mc_args) = version_json["minecraftArguments"].as_str() {
@@ -112,75 +156,113 @@ pub fn build_arguments(
 /// 解析 JVM 参数
 fn parse_jvm_arguments(
     arguments: &serde_json::Value,
-    current_os: &str,
+    env: &RuleEnv,
     replace_placeholders: &impl Fn(&str) -> String,
 ) -> Vec<String> {
-    let mut jvm_args = vec![];
+    collect_rule_arguments(&arguments["jvm"], env, replace_placeholders)
+}
+
+/// 解析游戏参数
+fn parse_game_arguments(
+    arguments: &serde_json::Value,
+    env: &RuleEnv,
+    replace_placeholders: &impl Fn(&str) -> String,
+) -> Vec<String> {
+    collect_rule_arguments(&arguments["game"], env, replace_placeholders)
+}
 
-    let Some(jvm) = arguments["jvm"].as_array() else {
-        return jvm_args;
+/// 收集参数列表，字符串直接加入，带规则的对象按规则求值后加入其 value
+fn collect_rule_arguments(
+    list: &serde_json::Value,
+    env: &RuleEnv,
+    replace_placeholders: &impl Fn(&str) -> String,
+) -> Vec<String> {
+    let mut args = vec![];
+
+    let Some(list) = list.as_array() else {
+        return args;
     };
 
-    for arg in jvm {
+    for arg in list {
         if let Some(s) = arg.as_str() {
-            jvm_args.push(replace_placeholders(s));
-        } else if let Some(obj) = arg.as_object() {
-            if is_rule_allowed(obj, current_os) {
-                if let Some(value) = obj.get("value") {
-                    if let Some(s) = value.as_str() {
-                        jvm_args.push(replace_placeholders(s));
-                    } else if let Some(arr) = value.as_array() {
-                        for item in arr {
-                            if let Some(s) = item.as_str() {
-                                jvm_args.push(replace_placeholders(s));
-                            }
-                        }
-                    }
+            args.push(replace_placeholders(s));
+        } else if arg.is_object() {
+            if !rules_allow(arg.get("rules"), env) {
+                continue;
+            }
+            match arg.get("value") {
+                Some(serde_json::Value::String(s)) => args.push(replace_placeholders(s)),
+                Some(serde_json::Value::Array(arr)) => {
+                    args.extend(arr.iter().filter_map(|v| v.as_str()).map(replace_placeholders));
                 }
+                _ => {}
             }
         }
     }
 
-    jvm_args
+    args
 }
 
-/// 解析游戏参数
-fn parse_game_arguments(
-    arguments: &serde_json::Value,
-    replace_placeholders: &impl Fn(&str) -> String,
-) -> Vec<String> {
-    let mut game_args = vec![];
-
-    if let Some(game) = arguments["game"].as_array() {
-        for arg in game {
-            if let Some(s) = arg.as_str() {
-                game_args.push(replace_placeholders(s));
-            }
+/// 提取 JVM 参数的"键"，用于判断两个参数是否设置同一项
+/// 例如 `-Dfile.encoding=UTF-8` -> `-Dfile.encoding`，`-Xss1M` -> `-Xss`
+fn jvm_flag_key(arg: &str) -> String {
+    if arg == "-cp" || arg == "-classpath" || arg == "--class-path" {
+        return "-cp".to_string();
+    }
+    if let Some(rest) = arg.strip_prefix("-XX:") {
+        let name = rest.trim_start_matches(['+', '-']);
+        let name = name.split('=').next().unwrap_or(name);
+        return format!("-XX:{}", name);
+    }
+    if arg.starts_with("-D") {
+        return arg.split('=').next().unwrap_or(arg).to_string();
+    }
+    for prefix in ["-Xmx", "-Xms", "-Xss", "-Xmn"] {
+        if arg.starts_with(prefix) {
+            return prefix.to_string();
         }
     }
+    arg.to_string()
+}
 
-    game_args
+/// 合并启动器默认 JVM 参数与版本 JSON 提供的 JVM 参数
+/// 版本 JSON 已经设置的项优先，默认参数中的同名项会被丢弃
+pub(super) fn merge_jvm_args(defaults: Vec<String>, json_args: Vec<String>) -> Vec<String> {
+    let provided: std::collections::HashSet<String> =
+        json_args.iter().map(|a| jvm_flag_key(a)).collect();
+
+    let mut merged: Vec<String> = defaults
+        .into_iter()
+        .filter(|a| !provided.contains(&jvm_flag_key(a)))
+        .collect();
+    merged.extend(json_args);
+    merged
 }
 
-/// 检查规则是否允许
-fn is_rule_allowed(obj: &serde_json::Map<String, serde_json::Value>, current_os: &str) -> bool {
-    let Some(rules) = obj.get("rules").and_then(|r| r.as_array()) else {
-        return true;
-    };
+/// 参数列表中是否已包含 classpath 参数
+pub(super) fn has_classpath_arg(args: &[String]) -> bool {
+    args.iter().any(|a| jvm_flag_key(a) == "-cp")
+}
 
-    let mut allowed = true;
-    for rule in rules {
-        if let Some(os) = rule.get("os") {
-            if let Some(name) = os["name"].as_str() {
-                if name == current_os {
-                    allowed = rule["action"].as_str() == Some("allow");
-                } else {
-                    allowed = rule["action"].as_str() != Some("allow");
-                }
-            }
-        }
+/// 启动后直接加入服务器的参数：支持 Quick Play 的版本 (1.20+) 使用 `--quickPlayMultiplayer`，
+/// 更早的版本使用 `--server`/`--port`
+pub(super) fn server_join_args(version_json: &serde_json::Value, server: &str) -> Vec<String> {
+    let quick_play = version_json["arguments"]["game"]
+        .to_string()
+        .contains("--quickPlayMultiplayer");
+    if quick_play {
+        return vec!["--quickPlayMultiplayer".to_string(), server.to_string()];
     }
-    allowed
+    let (host, port) = match server.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') && port.parse::<u16>().is_ok() => (host, port),
+        _ => (server, "25565"),
+    };
+    vec![
+        "--server".to_string(),
+        host.to_string(),
+        "--port".to_string(),
+        port.to_string(),
+    ]
 }
 
 /// 自动补齐 tweakClass（仅在 LaunchWrapper 主类下）
@@ -247,3 +329,240 @@ fn auto_add_tweak_class(
     game_args.insert(0, tweaker.to_string());
     game_args.insert(0, "--tweakClass".to_string());
 }
+
+#[cfg(test)]
+mod tests {
+    //! 参数构建的黄金测试：fixtures 为真实版本 JSON（只保留与参数相关的字段和部分库），
+    //! 断言完整的参数列表，重构时任何顺序或占位符替换的变化都会被发现。
+
+    use super::build_arguments;
+    use crate::models::LaunchOptions;
+    use crate::services::auth::AuthSession;
+    use crate::services::launcher::version_json::load_and_merge_version_json;
+    use std::path::{Path, PathBuf};
+
+    const FORGE_1_7_10: &str = "1.7.10-Forge10.13.4.1614-1.7.10";
+    const FABRIC_1_20_4: &str = "fabric-loader-0.15.3-1.20.4";
+
+    fn fixtures_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures")
+    }
+
+    fn game_dir() -> PathBuf {
+        PathBuf::from("/mc")
+    }
+
+    fn s(path: &Path) -> String {
+        path.to_string_lossy().to_string()
+    }
+
+    fn load(version: &str) -> serde_json::Value {
+        load_and_merge_version_json(&fixtures_dir(), version).expect("fixture 应能加载")
+    }
+
+    fn options(version: &str) -> LaunchOptions {
+        serde_json::from_value(serde_json::json!({ "version": version, "username": "Steve" }))
+            .unwrap()
+    }
+
+    /// 以固定环境构建参数
+    fn build(
+        version_json: &serde_json::Value,
+        version: &str,
+        assets_index: &str,
+        os: &str,
+    ) -> (Vec<String>, Vec<String>) {
+        let game_dir = game_dir();
+        let session = AuthSession::offline("Steve", "uuid");
+        let classpath = vec![game_dir.join("libraries").join("a.jar")];
+        build_arguments(
+            version_json,
+            &options(version),
+            &session,
+            &game_dir.join("versions").join(version),
+            &game_dir,
+            &game_dir.join("assets"),
+            assets_index,
+            &game_dir.join("natives"),
+            os,
+            &classpath,
+            &|_, _| {},
+        )
+    }
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn forge_1_7_10_uses_parent_arguments_and_injects_tweak_class() {
+        let game_dir = game_dir();
+        let (jvm, game) = build(&load(FORGE_1_7_10), FORGE_1_7_10, "1.7.10", "linux");
+
+        assert!(jvm.is_empty());
+        let run_dir = s(&game_dir.join("versions").join(FORGE_1_7_10));
+        let assets = s(&game_dir.join("assets"));
+        let mut expected = strings(&[
+            "--tweakClass",
+            "cpw.mods.fml.common.launcher.FMLTweaker",
+            "--username",
+            "Steve",
+            "--version",
+            "1.7.10",
+            "--gameDir",
+        ]);
+        expected.push(run_dir);
+        expected.push("--assetsDir".to_string());
+        expected.push(assets);
+        expected.extend(strings(&[
+            "--assetIndex",
+            "1.7.10",
+            "--uuid",
+            "uuid",
+            "--accessToken",
+            "0",
+            "--userProperties",
+            "{}",
+            "--userType",
+            "legacy",
+        ]));
+        assert_eq!(game, expected);
+    }
+
+    #[test]
+    fn existing_tweak_class_is_not_duplicated() {
+        // 不合并父版本时直接使用 Forge 自带的 minecraftArguments（已包含 --tweakClass）
+        let path = fixtures_dir()
+            .join("versions")
+            .join(FORGE_1_7_10)
+            .join(format!("{}.json", FORGE_1_7_10));
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let (_, game) = build(&json, FORGE_1_7_10, "1.7.10", "linux");
+
+        assert_eq!(game.iter().filter(|a| *a == "--tweakClass").count(), 1);
+        assert_eq!(game.last().map(String::as_str), Some("cpw.mods.fml.common.launcher.FMLTweaker"));
+    }
+
+    #[test]
+    fn tweak_class_is_skipped_without_forge_libraries() {
+        let mut json = load("1.12.2");
+        json["mainClass"] = "net.minecraft.launchwrapper.Launch".into();
+        let (_, game) = build(&json, "1.12.2", "1.12", "linux");
+
+        assert!(!game.iter().any(|a| a == "--tweakClass"));
+    }
+
+    #[test]
+    fn vanilla_1_12_2_legacy_arguments() {
+        let game_dir = game_dir();
+        let (jvm, game) = build(&load("1.12.2"), "1.12.2", "1.12", "linux");
+
+        assert!(jvm.is_empty());
+        let mut expected = strings(&["--username", "Steve", "--version", "1.12.2", "--gameDir"]);
+        expected.push(s(&game_dir.join("versions").join("1.12.2")));
+        expected.push("--assetsDir".to_string());
+        expected.push(s(&game_dir.join("assets")));
+        expected.extend(strings(&[
+            "--assetIndex",
+            "1.12",
+            "--uuid",
+            "uuid",
+            "--accessToken",
+            "0",
+            "--userType",
+            "legacy",
+            "--versionType",
+            "release",
+        ]));
+        assert_eq!(game, expected);
+    }
+
+    #[test]
+    fn vanilla_1_16_5_evaluates_rules() {
+        let game_dir = game_dir();
+        let natives = s(&game_dir.join("natives"));
+        let (jvm, game) = build(&load("1.16.5"), "1.16.5", "1.16", "linux");
+
+        // 系统和架构不匹配的规则被跳过，未启用的特性（自定义分辨率、试玩）不加参数
+        assert_eq!(
+            jvm,
+            vec![
+                format!("-Djava.library.path={}", natives),
+                "-Dminecraft.launcher.brand=ar1s_launcher".to_string(),
+                format!("-Dminecraft.launcher.version={}", env!("CARGO_PKG_VERSION")),
+                "-cp".to_string(),
+                s(&game_dir.join("libraries").join("a.jar")),
+            ]
+        );
+        let mut expected = strings(&["--username", "Steve", "--version", "1.16.5", "--gameDir"]);
+        expected.push(s(&game_dir.join("versions").join("1.16.5")));
+        expected.push("--assetsDir".to_string());
+        expected.push(s(&game_dir.join("assets")));
+        expected.extend(strings(&[
+            "--assetIndex",
+            "1.16",
+            "--uuid",
+            "uuid",
+            "--accessToken",
+            "0",
+            "--userType",
+            "legacy",
+            "--versionType",
+            "release",
+        ]));
+        assert_eq!(game, expected);
+    }
+
+    #[test]
+    fn os_rules_select_platform_arguments() {
+        let (jvm, _) = build(&load("1.16.5"), "1.16.5", "1.16", "osx");
+        assert_eq!(jvm.first().map(String::as_str), Some("-XstartOnFirstThread"));
+        assert!(!jvm.iter().any(|a| a.starts_with("-XX:HeapDumpPath=")));
+    }
+
+    #[test]
+    fn fabric_1_20_4_merges_parent_arguments() {
+        let game_dir = game_dir();
+        let natives = s(&game_dir.join("natives"));
+        let (jvm, game) = build(&load(FABRIC_1_20_4), FABRIC_1_20_4, "12", "linux");
+
+        // 父版本的 JVM 参数在前，Fabric 的参数追加在后
+        assert_eq!(
+            jvm,
+            vec![
+                format!("-Djava.library.path={}", natives),
+                format!("-Djna.tmpdir={}", natives),
+                format!("-Dorg.lwjgl.system.SharedLibraryExtractPath={}", natives),
+                format!("-Dio.netty.native.workdir={}", natives),
+                "-Dminecraft.launcher.brand=ar1s_launcher".to_string(),
+                format!("-Dminecraft.launcher.version={}", env!("CARGO_PKG_VERSION")),
+                "-cp".to_string(),
+                s(&game_dir.join("libraries").join("a.jar")),
+                "-DFabricMcEmu= net.minecraft.client.main.Main ".to_string(),
+            ]
+        );
+        // ${version_name} 使用原版版本号，离线账户的 clientId / xuid 为 0，Quick Play 参数不启用
+        let mut expected = strings(&["--username", "Steve", "--version", "1.20.4", "--gameDir"]);
+        expected.push(s(&game_dir.join("versions").join(FABRIC_1_20_4)));
+        expected.push("--assetsDir".to_string());
+        expected.push(s(&game_dir.join("assets")));
+        expected.extend(strings(&[
+            "--assetIndex",
+            "12",
+            "--uuid",
+            "uuid",
+            "--accessToken",
+            "0",
+            "--clientId",
+            "0",
+            "--xuid",
+            "0",
+            "--userType",
+            "legacy",
+            "--versionType",
+            "release",
+        ]));
+        assert_eq!(game, expected);
+    }
+}
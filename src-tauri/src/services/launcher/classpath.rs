@@ -1,6 +1,9 @@
 //! Classpath 构建和库预检逻辑
 
+use super::progress::LaunchProgress;
+use crate::models::ProgressStage;
 use crate::errors::LauncherError;
+use crate::utils::maven::MavenCoordinate;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -114,16 +117,37 @@ pub fn build_classpath(
     version: &str,
     current_os: &str,
     emit: &impl Fn(&str, String),
+    progress: &impl Fn(LaunchProgress),
 ) -> Result<Vec<PathBuf>, LauncherError> {
     let mut classpath = vec![];
+    let arch_skipped = super::natives::arch_mismatched_natives(version_json);
 
     if let Some(libraries) = version_json["libraries"].as_array() {
-        for lib in libraries {
+        let total = libraries.len();
+        for (index, lib) in libraries.iter().enumerate() {
+            // 大型整合包有数百个库，每 16 个汇报一次
+            if index % 16 == 0 {
+                progress(LaunchProgress::new(
+                    ProgressStage::BuildingClasspath,
+                    index,
+                    total,
+                    format!("正在构建 Classpath ({}/{})", index, total),
+                ));
+            }
+
             // 跳过 Natives 库
             if lib.get("natives").is_some() {
                 continue;
             }
 
+            // 跳过其他 CPU 架构的 natives 库
+            if let Some(name) = lib["name"].as_str() {
+                if arch_skipped.contains(name) {
+                    emit("log-debug", format!("跳过非当前架构的Natives库: {}", name));
+                    continue;
+                }
+            }
+
             if !should_include_library(lib, current_os) {
                 continue;
             }
@@ -163,6 +187,12 @@ pub fn build_classpath(
     }
 
     classpath.push(main_game_jar_path);
+    progress(LaunchProgress::new(
+        ProgressStage::BuildingClasspath,
+        1,
+        1,
+        format!("Classpath 共 {} 个库", classpath.len()),
+    ));
     Ok(classpath)
 }
 
@@ -217,9 +247,7 @@ fn resolve_library_path(
 
     // 回退：根据 maven 坐标构建本地路径
     let name = lib.get("name").and_then(|n| n.as_str())?;
-    let parts: Vec<&str> = name.split(':').collect();
-
-    if parts.len() < 3 {
+    let Some(coordinate) = MavenCoordinate::parse(name) else {
         emit(
             "log-error",
             format!(
@@ -228,16 +256,9 @@ fn resolve_library_path(
             ),
         );
         return None;
-    }
+    };
 
-    let group = parts[0].replace('.', "/");
-    let artifact = parts[1];
-    let version = parts[2];
-    let candidate = libraries_base_dir
-        .join(&group)
-        .join(artifact)
-        .join(version)
-        .join(format!("{}-{}.jar", artifact, version));
+    let candidate = libraries_base_dir.join(coordinate.path());
 
     emit(
         "log-debug",
@@ -259,12 +280,22 @@ fn resolve_library_path(
     }
 }
 
-/// 执行 LaunchWrapper 相关的库预检
+/// 自愈后校验：自动找到的 JAR 必须是可读取的 zip，否则不加入 Classpath
+fn verify_healed_jar(path: &Path) -> bool {
+    fs::File::open(path)
+        .ok()
+        .and_then(|f| zip::ZipArchive::new(f).ok())
+        .is_some_and(|archive| !archive.is_empty())
+}
+
+/// 执行 LaunchWrapper 相关的库预检，返回通过自愈加入 Classpath 的库
 pub fn precheck_launchwrapper_libraries(
     classpath: &mut Vec<PathBuf>,
     libraries_base_dir: &Path,
     emit: &impl Fn(&str, String),
-) -> Result<(), LauncherError> {
+) -> Result<Vec<PathBuf>, LauncherError> {
+    let original_len = classpath.len();
+
     // 预检 LaunchWrapper
     if !precheck_and_heal_library(
         classpath,
@@ -316,5 +347,16 @@ pub fn precheck_launchwrapper_libraries(
         emit,
     );
 
-    Ok(())
+    let healed: Vec<PathBuf> = classpath.split_off(original_len);
+    let (valid, invalid): (Vec<PathBuf>, Vec<PathBuf>) =
+        healed.into_iter().partition(|p| verify_healed_jar(p));
+    for jar in &invalid {
+        emit(
+            "log-warning",
+            format!("自愈找到的库文件已损坏，已忽略: {}", jar.display()),
+        );
+    }
+    classpath.extend(valid.iter().cloned());
+
+    Ok(valid)
 }
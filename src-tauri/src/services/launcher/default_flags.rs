@@ -0,0 +1,208 @@
+//! 按版本范围生效的默认 JVM 参数
+//!
+//! 不同年代的版本需要不同的兼容参数：LWJGL 2 在部分系统上需要允许软件 OpenGL，
+//! 旧版 Forge 需要跳过证书和补丁校验，受 Log4Shell 影响的版本需要关闭消息查找，
+//! macOS 上 LWJGL 3 需要在主线程创建窗口。规则集中在这张表里，
+//! 配置中的 `jvm_flag_overrides` 可以按规则 ID 强制启用或禁用。
+
+use crate::services::version_info::VersionInfo;
+use crate::utils::maven::compare_versions;
+use serde::Serialize;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// 默认参数规则
+struct FlagRule {
+    id: &'static str,
+    description: &'static str,
+    /// 适用的最低游戏版本（含）
+    min_version: Option<&'static str>,
+    /// 适用的最高游戏版本（不含）
+    max_version: Option<&'static str>,
+    /// 只对指定 LWJGL 主版本生效
+    lwjgl: Option<u8>,
+    /// 只对 Forge 系加载器生效
+    forge_only: bool,
+    /// 只在指定系统上生效（`std::env::consts::OS`）
+    os: Option<&'static str>,
+    args: &'static [&'static str],
+}
+
+const RULES: &[FlagRule] = &[
+    FlagRule {
+        id: "file-encoding",
+        description: "统一使用 UTF-8 编码，避免中文路径和聊天乱码",
+        min_version: None,
+        max_version: None,
+        lwjgl: None,
+        forge_only: false,
+        os: None,
+        args: &["-Dfile.encoding=UTF-8"],
+    },
+    FlagRule {
+        id: "openal-mapping",
+        description: "关闭 OpenAL 设备映射，避免部分声卡初始化失败",
+        min_version: None,
+        max_version: None,
+        lwjgl: None,
+        forge_only: false,
+        os: None,
+        args: &["-Dorg.lwjgl.openal.mapping.use=false"],
+    },
+    FlagRule {
+        id: "lwjgl2-software-opengl",
+        description: "LWJGL 2 允许软件 OpenGL，避免虚拟机和远程桌面中无法创建窗口",
+        min_version: None,
+        max_version: None,
+        lwjgl: Some(2),
+        forge_only: false,
+        os: None,
+        args: &["-Dorg.lwjgl.opengl.Display.allowSoftwareOpenGL=true"],
+    },
+    FlagRule {
+        id: "legacy-forge-checks",
+        description: "旧版 Forge 跳过客户端证书和补丁差异校验，避免修改过的客户端无法加载",
+        min_version: None,
+        max_version: Some("1.13"),
+        lwjgl: None,
+        forge_only: true,
+        os: None,
+        args: &[
+            "-Dfml.ignoreInvalidMinecraftCertificates=true",
+            "-Dfml.ignorePatchDiscrepancies=true",
+        ],
+    },
+    FlagRule {
+        id: "log4j-no-lookups",
+        description: "关闭 Log4j 消息查找（CVE-2021-44228）",
+        min_version: Some("1.7"),
+        max_version: Some("1.18.1"),
+        lwjgl: None,
+        forge_only: false,
+        os: None,
+        args: &["-Dlog4j2.formatMsgNoLookups=true"],
+    },
+    FlagRule {
+        id: "macos-dock-name",
+        description: "程序坞中显示为 Minecraft",
+        min_version: None,
+        max_version: None,
+        lwjgl: None,
+        forge_only: false,
+        os: Some("macos"),
+        args: &["-Xdock:name=Minecraft"],
+    },
+    FlagRule {
+        id: "macos-first-thread",
+        description: "LWJGL 3 要求在主线程创建窗口",
+        min_version: None,
+        max_version: None,
+        lwjgl: Some(3),
+        forge_only: false,
+        os: Some("macos"),
+        args: &["-XstartOnFirstThread"],
+    },
+];
+
+/// 判断规则是否适用所需的版本特征
+pub(super) struct VersionTraits<'a> {
+    mc_version: Option<&'a str>,
+    lwjgl: Option<u8>,
+    loader_type: &'a str,
+}
+
+impl<'a> VersionTraits<'a> {
+    pub(super) fn new(version_json: &Value, info: &'a VersionInfo) -> Self {
+        Self {
+            mc_version: info.mc_version.as_deref(),
+            lwjgl: lwjgl_major(version_json),
+            loader_type: &info.loader_type,
+        }
+    }
+}
+
+/// 默认参数规则的说明和当前状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefaultJvmFlagInfo {
+    pub id: &'static str,
+    pub description: &'static str,
+    pub args: Vec<String>,
+    /// 按版本范围是否适用
+    pub applies: bool,
+    /// 考虑配置覆盖后是否生效
+    pub enabled: bool,
+    /// 配置中的覆盖值
+    pub override_value: Option<bool>,
+}
+
+/// 版本使用的 LWJGL 主版本（LWJGL 2 的组名为 `org.lwjgl.lwjgl`）
+fn lwjgl_major(version_json: &Value) -> Option<u8> {
+    version_json["libraries"].as_array()?.iter().find_map(|lib| {
+        let name = lib["name"].as_str()?;
+        if name.starts_with("org.lwjgl.lwjgl:lwjgl:2.") {
+            Some(2)
+        } else if name.starts_with("org.lwjgl:lwjgl:3.") {
+            Some(3)
+        } else {
+            None
+        }
+    })
+}
+
+/// 只比较正式版编号，快照等无法比较的版本不匹配带版本范围的规则
+fn in_range(mc_version: Option<&str>, min: Option<&str>, max: Option<&str>) -> bool {
+    if min.is_none() && max.is_none() {
+        return true;
+    }
+    let Some(version) = mc_version.filter(|v| v.chars().all(|c| c.is_ascii_digit() || c == '.')) else {
+        return false;
+    };
+    min.is_none_or(|min| compare_versions(version, min) != Ordering::Less)
+        && max.is_none_or(|max| compare_versions(version, max) == Ordering::Less)
+}
+
+fn applies(rule: &FlagRule, traits: &VersionTraits) -> bool {
+    in_range(traits.mc_version, rule.min_version, rule.max_version)
+        && rule.lwjgl.is_none_or(|major| traits.lwjgl == Some(major))
+        && (!rule.forge_only
+            || matches!(traits.loader_type.to_lowercase().as_str(), "forge" | "neoforge"))
+        && rule.os.is_none_or(|os| os == std::env::consts::OS)
+}
+
+/// 是否为已知的规则 ID
+pub fn is_known_flag(id: &str) -> bool {
+    RULES.iter().any(|rule| rule.id == id)
+}
+
+/// 所有规则在指定版本上的状态
+pub(super) fn describe(
+    traits: &VersionTraits,
+    overrides: &BTreeMap<String, bool>,
+) -> Vec<DefaultJvmFlagInfo> {
+    RULES
+        .iter()
+        .map(|rule| {
+            let applies = applies(rule, traits);
+            let override_value = overrides.get(rule.id).copied();
+            DefaultJvmFlagInfo {
+                id: rule.id,
+                description: rule.description,
+                args: rule.args.iter().map(|a| a.to_string()).collect(),
+                applies,
+                enabled: override_value.unwrap_or(applies),
+                override_value,
+            }
+        })
+        .collect()
+}
+
+/// 指定版本生效的默认 JVM 参数
+pub(super) fn jvm_args(traits: &VersionTraits, overrides: &BTreeMap<String, bool>) -> Vec<String> {
+    describe(traits, overrides)
+        .into_iter()
+        .filter(|flag| flag.enabled)
+        .flat_map(|flag| flag.args)
+        .collect()
+}
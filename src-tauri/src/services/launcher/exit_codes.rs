@@ -0,0 +1,125 @@
+//! 游戏退出码知识库
+//!
+//! 将常见的退出码/信号映射为可读的原因说明，随 `minecraft-exited` 事件发送给前端。
+//! 显卡驱动崩溃等偶发问题标记为可重启，实例开启自动重启时会重新启动一次。
+
+use serde::Serialize;
+use std::process::ExitStatus;
+
+/// 退出原因说明
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExitExplanation {
+    /// 简短标题
+    pub title: &'static str,
+    /// 详细说明和建议
+    pub description: &'static str,
+    /// 是否为偶发问题，重新启动通常可以恢复
+    pub restartable: bool,
+}
+
+const fn explanation(
+    title: &'static str,
+    description: &'static str,
+    restartable: bool,
+) -> ExitExplanation {
+    ExitExplanation {
+        title,
+        description,
+        restartable,
+    }
+}
+
+/// 按退出码查找说明
+fn explain_code(code: i32) -> Option<ExitExplanation> {
+    let explanation = match code {
+        // 0xC0000409 STATUS_STACK_BUFFER_OVERRUN
+        -1073740791 => explanation(
+            "显卡驱动崩溃",
+            "游戏进程因显卡驱动故障 (0xC0000409) 退出，通常是驱动不稳定。建议更新或回退显卡驱动，关闭光影后重试。",
+            true,
+        ),
+        // 0xC0000005 STATUS_ACCESS_VIOLATION
+        -1073741819 => explanation(
+            "内存访问冲突",
+            "本地代码发生访问冲突 (0xC0000005)，常见于显卡驱动、覆盖层软件（录屏、游戏加速器）或不兼容的本地库。",
+            true,
+        ),
+        // 0xC00000FD STATUS_STACK_OVERFLOW
+        -1073741571 => explanation(
+            "栈溢出",
+            "Java 进程栈溢出 (0xC00000FD)，可能是模组递归调用异常，请查看崩溃报告。",
+            false,
+        ),
+        // 0xC0000135 STATUS_DLL_NOT_FOUND
+        -1073741515 => explanation(
+            "缺少系统组件",
+            "找不到所需的 DLL (0xC0000135)，请安装 Visual C++ 运行库或重新安装 Java。",
+            false,
+        ),
+        // 0xCFFFFFFF：窗口无响应后被系统结束
+        -805306369 => explanation(
+            "游戏无响应被结束",
+            "游戏窗口长时间无响应后被关闭，可能是加载卡死或内存不足。",
+            false,
+        ),
+        // 128 + SIGKILL：经 gamemoderun 等包装脚本启动时的表现
+        137 => return explain_signal(9),
+        139 => return explain_signal(11),
+        134 => return explain_signal(6),
+        1 => explanation(
+            "游戏崩溃",
+            "游戏因未处理的异常退出，请查看 crash-reports 目录下的崩溃报告。",
+            false,
+        ),
+        _ => return None,
+    };
+    Some(explanation)
+}
+
+/// 按终止信号查找说明（Unix）
+fn explain_signal(signal: i32) -> Option<ExitExplanation> {
+    let explanation = match signal {
+        9 => explanation(
+            "进程被强制结束",
+            "游戏被 SIGKILL 结束，在 Linux 上通常是系统内存耗尽触发了 OOM Killer。建议降低最大内存或关闭其他程序。",
+            false,
+        ),
+        11 => explanation(
+            "段错误",
+            "本地代码发生段错误 (SIGSEGV)，常见于显卡驱动或不兼容的 LWJGL 本地库。",
+            true,
+        ),
+        6 => explanation(
+            "进程中止",
+            "Java 虚拟机中止 (SIGABRT)，请查看游戏目录下的 hs_err_pid 日志。",
+            false,
+        ),
+        _ => return None,
+    };
+    Some(explanation)
+}
+
+/// 终止进程的信号（Unix）
+#[cfg(unix)]
+pub fn exit_signal(status: &ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+/// 终止进程的信号（Unix）
+#[cfg(not(unix))]
+pub fn exit_signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
+
+/// 解释进程退出状态，正常退出或未知退出码返回 None
+pub fn explain(status: &ExitStatus) -> Option<ExitExplanation> {
+    if status.success() {
+        return None;
+    }
+    match status.code() {
+        Some(code) => explain_code(code),
+        None => exit_signal(status).and_then(explain_signal),
+    }
+}
@@ -0,0 +1,176 @@
+//! JVM 崩溃日志（hs_err_pid*.log）收集
+//!
+//! JVM 自身崩溃时会在工作目录写入 `hs_err_pid<PID>.log`，用户通常找不到。
+//! 游戏异常退出后将本次启动产生的日志移动到 crash-reports，并解析出错的本地帧，
+//! 出错模块是已知显卡驱动时给出对应建议。
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// 只读取日志开头部分（关键信息都在文件头）
+const HEADER_LINES: usize = 80;
+
+/// 已知的显卡驱动模块（小写前缀 -> 厂商）
+const GPU_DRIVERS: [(&str, &str); 12] = [
+    ("atio6axx", "AMD"),
+    ("atioglxx", "AMD"),
+    ("amdxx", "AMD"),
+    ("atig6pxx", "AMD"),
+    ("nvoglv", "NVIDIA"),
+    ("libnvidia-glcore", "NVIDIA"),
+    ("libnvidia-eglcore", "NVIDIA"),
+    ("ig9icd", "Intel"),
+    ("ig7icd", "Intel"),
+    ("igxelpicd", "Intel"),
+    ("iris_dri", "Intel (Mesa)"),
+    ("radeonsi_dri", "AMD (Mesa)"),
+];
+
+/// JVM 崩溃日志的解析结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JvmCrashReport {
+    /// 移动后的日志路径
+    pub file: String,
+    /// 错误类型，如 `EXCEPTION_ACCESS_VIOLATION (0xc0000005)`
+    pub error: Option<String>,
+    /// 出错的本地帧，如 `C  [atio6axx.dll+0x1b2c4f]`
+    pub problematic_frame: Option<String>,
+    /// 出错的模块，如 `atio6axx.dll`
+    pub module: Option<String>,
+    /// 出错模块属于显卡驱动时的厂商
+    pub gpu_vendor: Option<String>,
+    pub jre_version: Option<String>,
+    /// 给用户的建议
+    pub suggestion: String,
+}
+
+/// 工作目录下本次启动后产生的 hs_err 日志
+fn fresh_logs(working_dir: &Path, since: SystemTime) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(working_dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            name.starts_with("hs_err_pid") && name.ends_with(".log")
+        })
+        .filter(|e| e.metadata().and_then(|m| m.modified()).is_ok_and(|t| t >= since))
+        .map(|e| e.path())
+        .collect()
+}
+
+/// 移动文件（跨盘时复制后删除）
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)?;
+    fs::remove_file(from)
+}
+
+/// 出错帧中的模块名：`C  [atio6axx.dll+0x1b2c4f]` -> `atio6axx.dll`
+fn frame_module(frame: &str) -> Option<String> {
+    let inner = frame.split_once('[')?.1.split_once(']')?.0;
+    let module = inner.split(['+', ' ']).next()?.trim();
+    (!module.is_empty()).then(|| module.to_string())
+}
+
+fn gpu_vendor(module: &str) -> Option<&'static str> {
+    let module = module.to_lowercase();
+    GPU_DRIVERS
+        .iter()
+        .find(|(prefix, _)| module.starts_with(prefix))
+        .map(|(_, vendor)| *vendor)
+}
+
+/// 解析日志头部
+fn parse(content: &str, file: String) -> JvmCrashReport {
+    let lines: Vec<&str> = content.lines().take(HEADER_LINES).collect();
+    let comment = |line: &str| line.trim_start_matches('#').trim().to_string();
+
+    // 错误类型紧跟在 "A fatal error has been detected" 之后的第一行非空注释
+    let error = lines
+        .iter()
+        .position(|l| l.contains("A fatal error has been detected"))
+        .and_then(|i| lines[i + 1..].iter().map(|l| comment(l)).find(|l| !l.is_empty()));
+    let problematic_frame = lines
+        .iter()
+        .position(|l| l.contains("Problematic frame:"))
+        .and_then(|i| lines.get(i + 1))
+        .map(|l| comment(l))
+        .filter(|l| !l.is_empty());
+    let jre_version = lines
+        .iter()
+        .find_map(|l| l.trim_start_matches('#').trim().strip_prefix("JRE version:"))
+        .map(|v| v.trim().to_string());
+    let module = problematic_frame.as_deref().and_then(frame_module);
+    let vendor = module.as_deref().and_then(gpu_vendor);
+
+    let suggestion = match (vendor, &module) {
+        (Some(vendor), _) => format!(
+            "JVM 在 {} 显卡驱动中崩溃。请更新或回退显卡驱动，关闭光影和覆盖层软件后重试",
+            vendor
+        ),
+        (None, Some(module)) if module.ends_with(".dll") || module.contains(".so") => format!(
+            "JVM 在本地库 {} 中崩溃，可能是不兼容的本地库或系统组件，可尝试更换 Java 版本",
+            module
+        ),
+        _ => "JVM 自身崩溃，可尝试更换 Java 版本或降低最大内存后重试".to_string(),
+    };
+
+    JvmCrashReport {
+        file,
+        error,
+        problematic_frame,
+        module,
+        gpu_vendor: vendor.map(String::from),
+        jre_version,
+        suggestion,
+    }
+}
+
+/// 收集本次启动产生的 JVM 崩溃日志，移动到 `crash-reports` 并解析最新的一份
+pub fn collect(working_dir: &Path, since: SystemTime) -> Option<JvmCrashReport> {
+    let logs = fresh_logs(working_dir, since);
+    if logs.is_empty() {
+        return None;
+    }
+    let crash_dir = working_dir.join("crash-reports");
+    if let Err(e) = fs::create_dir_all(&crash_dir) {
+        log::warn!("无法创建崩溃报告目录 {}: {}", crash_dir.display(), e);
+        return None;
+    }
+
+    let mut latest: Option<(SystemTime, PathBuf)> = None;
+    for log_path in logs {
+        let modified = fs::metadata(&log_path).and_then(|m| m.modified()).unwrap_or(since);
+        let target = crash_dir.join(log_path.file_name()?);
+        let path = match move_file(&log_path, &target) {
+            Ok(()) => target,
+            Err(e) => {
+                log::warn!("移动 JVM 崩溃日志失败 {}: {}", log_path.display(), e);
+                log_path
+            }
+        };
+        if latest.as_ref().is_none_or(|(t, _)| modified >= *t) {
+            latest = Some((modified, path));
+        }
+    }
+
+    let (_, path) = latest?;
+    let content = fs::read(&path).ok()?;
+    let report = parse(
+        &String::from_utf8_lossy(&content),
+        path.to_string_lossy().to_string(),
+    );
+    log::info!(
+        "已收集 JVM 崩溃日志 {}，出错帧: {:?}",
+        report.file,
+        report.problematic_frame
+    );
+    Some(report)
+}
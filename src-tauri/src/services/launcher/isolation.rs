@@ -1,9 +1,10 @@
 //! 版本隔离目录准备
 
 use crate::models::GameConfig;
+use crate::services::paths;
 use std::fs;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// 准备版本隔离目录
 pub fn prepare_isolated_version_directory(
@@ -37,3 +38,63 @@ pub fn prepare_isolated_version_directory(
 
     Ok(())
 }
+
+/// 创建指向共享目录的链接（Unix 使用符号链接，Windows 使用目录联接，无需管理员权限）
+fn link_shared_dir(target: &Path, link: &Path) -> Result<(), io::Error> {
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(target, link)
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        let status = std::process::Command::new("cmd")
+            .args(["/C", "mklink", "/J"])
+            .arg(link)
+            .arg(target)
+            .creation_flags(0x08000000)
+            .status()?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!(
+                "创建目录联接失败: {}",
+                link.display()
+            )))
+        }
+    }
+}
+
+/// 准备按账户隔离的游戏目录
+///
+/// 返回 version_dir/profiles/<账户名>，存档、截图和 options.txt 均按账户独立，
+/// mods/config 等目录通过链接指回实例目录，保证同一实例内玩家共享模组
+pub fn prepare_account_profile_directory(
+    version_dir: &Path,
+    account: &str,
+) -> Result<PathBuf, io::Error> {
+    let profile_dir = paths::account_profile_dir(version_dir, account);
+
+    for dir_name in ["saves", "screenshots"] {
+        fs::create_dir_all(profile_dir.join(dir_name))?;
+    }
+
+    for dir_name in paths::SHARED_PROFILE_DIRS {
+        let target = version_dir.join(dir_name);
+        let link = profile_dir.join(dir_name);
+        if link.symlink_metadata().is_ok() {
+            continue;
+        }
+        fs::create_dir_all(&target)?;
+        link_shared_dir(&target, &link)?;
+    }
+
+    // 首次使用时继承实例的 options.txt
+    let options_src = version_dir.join("options.txt");
+    let options_dst = profile_dir.join("options.txt");
+    if options_src.exists() && !options_dst.exists() {
+        fs::copy(&options_src, &options_dst)?;
+    }
+
+    Ok(profile_dir)
+}
@@ -1,38 +1,46 @@
-//! Java 路径解析和 UUID 生成
+//! Java 路径解析
 
 use crate::errors::LauncherError;
 use crate::models::GameConfig;
-use crate::services::config::load_config;
+use crate::services::java_runtime;
 use std::path::PathBuf;
 use std::process::Command;
-use uuid::Uuid;
 
-/// 生成离线模式 UUID
-pub fn generate_offline_uuid(username: &str) -> String {
-    // 首先检查配置中是否已有保存的 UUID
-    if let Ok(config) = load_config() {
-        // 如果用户名匹配且已有 UUID，则直接返回保存的 UUID
-        if let (Some(saved_username), Some(saved_uuid)) = (&config.username, &config.uuid) {
-            if saved_username == username {
-                return saved_uuid.clone();
-            }
+/// 托管运行时先做快速完整性检查，文件被误删则自动补齐
+async fn ensure_managed_intact(java_path: &str) -> Result<(), LauncherError> {
+    if let Some(runtime_id) = java_runtime::managed_runtime_of(java_path) {
+        if let Some(report) = java_runtime::ensure_runtime_intact(&runtime_id).await? {
+            log::info!(
+                "托管运行时 {} 已修复 {} 个文件",
+                runtime_id,
+                report.missing.len() + report.corrupted.len()
+            );
         }
     }
-
-    // 离线模式：UUID v3 (MD5) 基于 "OfflinePlayer:{username}"
-    Uuid::new_v3(
-        &Uuid::NAMESPACE_DNS,
-        format!("OfflinePlayer:{}", username).as_bytes(),
-    )
-    .to_string()
+    Ok(())
 }
 
 /// 解析 Java 可执行文件路径
-pub fn resolve_java_path(config: &GameConfig) -> Result<String, LauncherError> {
+///
+/// `preferred` 为实例设置的 Java 路径，优先于全局配置；实例指定的路径不存在时直接报错，
+/// 避免静默换用其他版本的 Java
+pub async fn resolve_java_path(config: &GameConfig, preferred: Option<&str>) -> Result<String, LauncherError> {
+    if let Some(instance_path) = preferred.filter(|p| !p.is_empty()) {
+        ensure_managed_intact(instance_path).await?;
+        if PathBuf::from(instance_path).exists() {
+            return Ok(instance_path.to_string());
+        }
+        return Err(LauncherError::Custom(format!(
+            "实例设置的 Java 路径不存在: {}",
+            instance_path
+        )));
+    }
+
     // 1. 首先尝试使用配置中的 Java 路径
-    if let Some(config_path) = &config.java_path {
-        if !config_path.is_empty() && PathBuf::from(config_path).exists() {
-            return Ok(config_path.clone());
+    if let Some(config_path) = config.java_path.as_deref().filter(|p| !p.is_empty()) {
+        ensure_managed_intact(config_path).await?;
+        if PathBuf::from(config_path).exists() {
+            return Ok(config_path.to_string());
         }
     }
 
@@ -9,51 +9,207 @@
 
 mod arguments;
 mod classpath;
+mod default_flags;
+mod exit_codes;
+mod hs_err;
 mod isolation;
 mod java;
 mod natives;
+mod natives_cleanup;
+mod platform;
 mod process;
+mod progress;
+mod proxy;
+mod rules;
+mod running;
+mod script;
+mod session;
+mod troubleshooting;
+mod validation;
 mod version_json;
 
 use crate::errors::LauncherError;
-use crate::models::LaunchOptions;
+use crate::models::{GameConfig, LaunchOptions, ProgressStage, TroubleshootingToggle};
+use crate::services::paths::ContentKind;
+use crate::services::{accounts, auth, instance, instance_lock, log_events, metrics, paths, skin_server, version_info};
+use crate::services::hooks::{self, HookPoint};
+use crate::services::instance_lock::InstanceOperation;
 use crate::services::config::{load_config, save_config, update_instance_last_played, set_last_selected_version};
 use crate::services::memory::{is_memory_setting_safe, optimize_jvm_memory_args};
+use crate::utils::client_jar;
 use std::path::PathBuf;
-use tauri::Emitter;
+use crate::services::event_sink::EventSink;
 
 pub use classpath::find_library_jar;
+pub use default_flags::{is_known_flag, DefaultJvmFlagInfo};
+pub(crate) use natives::NATIVES_DIR;
+pub use natives_cleanup::{list_orphaned_natives, prune_natives, NativesDirInfo, NativesPruneResult};
+pub use progress::LaunchProgress;
+pub use running::{ensure_not_running, running_games, running_pid};
+pub use script::{ExportedLaunchScript, ScriptFormat};
+pub use session::{capture_log as capture_session_log, SessionInfo};
+pub use troubleshooting::TroubleshootingToggleInfo;
+pub use validation::ValidationIssue;
+pub use version_json::clear_merged_cache;
+
+/// 校验指定版本的 JSON（含继承链）
+pub fn validate_version_json(version: &str) -> Result<Vec<ValidationIssue>, LauncherError> {
+    let config = load_config()?;
+    validation::validate_version(&paths::instance_game_dir(&config, version), version)
+}
+
+/// 实例的启动会话（最新的在前）
+pub fn list_sessions(instance: &str) -> Result<Vec<SessionInfo>, LauncherError> {
+    let config = load_config()?;
+    Ok(session::list_sessions(&paths::version_dir(&config, instance)))
+}
+
+/// 实例的默认 JVM 参数规则及其状态
+pub fn default_jvm_flags(instance: &str) -> Result<Vec<DefaultJvmFlagInfo>, LauncherError> {
+    let config = load_config()?;
+    let game_dir = paths::instance_game_dir(&config, instance);
+    let version_json = version_json::load_and_merge_version_json(&game_dir, instance)?;
+    let detail = version_info::detect(&game_dir.join("versions"), instance);
+    Ok(default_flags::describe(
+        &default_flags::VersionTraits::new(&version_json, &detail),
+        &config.jvm_flag_overrides,
+    ))
+}
+
+/// 实例版本 JSON 声明的 Java 主版本（`javaVersion.majorVersion`）
+pub fn required_java_major(instance: &str) -> Result<Option<u32>, LauncherError> {
+    let config = load_config()?;
+    let game_dir = paths::instance_game_dir(&config, instance);
+    let version_json = version_json::load_and_merge_version_json(&game_dir, instance)?;
+    Ok(version_json["javaVersion"]["majorVersion"].as_u64().map(|v| v as u32))
+}
+
+/// 实例可用的故障排查启动选项（取决于加载器）
+pub fn troubleshooting_toggles(instance: &str) -> Result<Vec<TroubleshootingToggleInfo>, LauncherError> {
+    let config = load_config()?;
+    let versions_dir = paths::instance_game_dir(&config, instance).join("versions");
+    Ok(troubleshooting::available_toggles(&version_info::detect(&versions_dir, instance).loader_type))
+}
 
 /// 启动 Minecraft 游戏
-pub async fn launch_minecraft(
+///
+/// 只通过 `instance::launch_instance` 调用，启动选项已合并实例设置
+pub(crate) async fn launch_minecraft(
     options: LaunchOptions,
-    window: tauri::Window,
+    window: EventSink,
 ) -> Result<(), LauncherError> {
-    let emit = |event: &str, msg: String| {
-        let _ = window.emit(event, msg);
+    let emit = |event: &str, msg: String| log_events::emit(&window, event, msg);
+    let progress = |p: LaunchProgress| {
+        let _ = window.emit("launch-progress", p);
     };
+    let _launch_span = metrics::span("launch_prepare");
+    let lock = instance_lock::acquire(&options.version, InstanceOperation::Launch)?;
 
-    // 保存用户名和 UUID 到配置文件
-    let uuid = java::generate_offline_uuid(&options.username);
+    // 校验用户名，按策略获取 UUID（正版账户使用保存的令牌）并保存到配置文件
+    let session = accounts::launch_session(&options.username)?;
+    let uuid = session.uuid.clone();
     let mut config = load_config()?;
     config.username = Some(options.username.clone());
     config.uuid = Some(uuid.clone());
-    save_config(&config)?;
+    if !options.transient {
+        save_config(&config)?;
+    }
 
     // 更新实例的上次启动时间
     let _ = update_instance_last_played(&options.version);
     // 保存上次选择的版本
     let _ = set_last_selected_version(&options.version);
 
+    // 启动前钩子（如同步服务器模组），失败时取消启动
+    let (instance_name, username) = (options.version.clone(), options.username.clone());
+    tokio::task::spawn_blocking(move || {
+        hooks::run_hook(&instance_name, HookPoint::PreLaunch, &[("AR1S_USERNAME", username)])
+    })
+    .await??;
+
+    let plan = prepare_launch(&options, &session, &config, false, &emit, &progress).await?;
+
+    // 7. 启动游戏
+    progress(LaunchProgress::new(ProgressStage::Launching, 0, 1, "正在启动游戏进程"));
+    let auto_restart = instance::get_instance_settings(&options.version)
+        .map(|s| s.auto_restart_on_crash)
+        .unwrap_or(false);
+
+    // 上次安全模式启动时启动器提前退出，先恢复残留的模组
+    let mods_dir = paths::content_dir(&config, &options.version, ContentKind::Mods);
+    troubleshooting::restore_mods(&mods_dir);
+    let disabled_mods = if options.troubleshooting.contains(&TroubleshootingToggle::DisableMods) {
+        let count = troubleshooting::disable_mods(&mods_dir)?;
+        emit("log-warning", format!("安全模式：本次启动临时禁用了 {} 个模组", count));
+        Some(mods_dir)
+    } else {
+        None
+    };
+
+    let game_process = process::GameProcess {
+        java_path: plan.java_path,
+        args: plan.args,
+        working_dir: plan.run_dir,
+        instance: options.version.clone(),
+        loader_type: plan.loader_type,
+        troubleshooting: options.troubleshooting.clone(),
+        disabled_mods: disabled_mods.clone(),
+    };
+    let result = process::spawn_and_monitor_process(game_process, &config, auto_restart, lock, window);
+    if let (Err(_), Some(mods_dir)) = (&result, disabled_mods) {
+        troubleshooting::restore_mods(&mods_dir);
+    }
+    result
+}
+
+/// 准备好的启动命令
+struct LaunchPlan {
+    java_path: String,
+    args: Vec<String>,
+    run_dir: PathBuf,
+    loader_type: String,
+}
+
+/// 构建完整的启动命令（解压 natives、构建 classpath 和参数），不启动进程
+///
+/// `for_export` 为 true 时跳过皮肤服务器等仅在启动器运行期间有效的参数
+async fn prepare_launch(
+    options: &LaunchOptions,
+    session: &auth::AuthSession,
+    config: &GameConfig,
+    for_export: bool,
+    emit: &impl Fn(&str, String),
+    progress: &impl Fn(LaunchProgress),
+) -> Result<LaunchPlan, LauncherError> {
     // 设置路径
-    let game_dir = PathBuf::from(&config.game_dir);
+    let game_dir = paths::instance_game_dir(config, &options.version);
     let version_dir = game_dir.join("versions").join(&options.version);
 
     emit("log-debug", format!("尝试启动版本: {}", options.version));
     emit("log-debug", format!("游戏目录: {}", game_dir.display()));
 
+    // 实例独立设置（启动选项未指定的项使用实例设置，其次使用全局配置）
+    let instance_settings = instance::get_instance_settings(&options.version).unwrap_or_default();
+
     // 加载版本 JSON
+    progress(LaunchProgress::new(ProgressStage::VerifyingFiles, 0, 1, "正在校验版本文件"));
     let version_json = version_json::load_and_merge_version_json(&game_dir, &options.version)?;
+    let issues = validation::validate_version_json(&version_json);
+    for issue in issues.iter().filter(|i| !i.fatal) {
+        emit("log-warning", format!("版本 JSON 警告 [{}]: {}", issue.location, issue.message));
+    }
+    let fatal: Vec<String> = issues
+        .iter()
+        .filter(|i| i.fatal)
+        .map(|i| format!("[{}] {}", i.location, i.message))
+        .collect();
+    if !fatal.is_empty() {
+        return Err(LauncherError::Custom(format!(
+            "版本 {} 的 JSON 无效:\n{}",
+            options.version,
+            fatal.join("\n")
+        )));
+    }
 
     let libraries_base_dir = game_dir.join("libraries");
     let assets_base_dir = game_dir.join("assets");
@@ -75,55 +231,108 @@ pub async fn launch_minecraft(
     };
 
     // 1. 准备隔离和 Natives 目录
-    isolation::prepare_isolated_version_directory(&config, &game_dir, &version_dir)?;
+    isolation::prepare_isolated_version_directory(config, &game_dir, &version_dir)?;
+    let natives_span = metrics::span("natives_extract");
     let natives_dir = natives::extract_natives(
         &version_json,
         &version_dir,
         &libraries_base_dir,
         current_os,
-        &emit,
-    )?;
+        emit,
+        progress,
+    )
+    .await?;
+
+    drop(natives_span);
 
     // 2. 构建 Classpath
+    let classpath_span = metrics::span("classpath_build");
     let mut classpath = classpath::build_classpath(
         &version_json,
         &libraries_base_dir,
         &version_dir,
         &options.version,
         current_os,
-        &emit,
+        emit,
+        progress,
     )?;
 
+    drop(classpath_span);
+
+    // 去除客户端 JAR 签名：build_classpath 最后加入的是主游戏 JAR
+    if instance_settings.strip_jar_signatures {
+        if let Some(main_jar) = classpath.last_mut() {
+            let unsigned = version_dir.join(format!("{}-unsigned.jar", options.version));
+            client_jar::unsigned_copy(main_jar, &unsigned)?;
+            emit(
+                "log-warning",
+                "已启用“去除 JAR 签名”：使用去签名的客户端 JAR 启动，仅用于兼容修改过原版文件的旧版客户端，\
+                 可能掩盖客户端文件损坏"
+                    .to_string(),
+            );
+            *main_jar = unsigned;
+        }
+    }
+
     // 3. 获取主类并执行库预检
     let main_class = version_json["mainClass"]
         .as_str()
         .ok_or_else(|| LauncherError::Custom("无法在json中找到mainClass".to_string()))?;
 
     if main_class == "net.minecraft.launchwrapper.Launch" {
-        classpath::precheck_launchwrapper_libraries(&mut classpath, &libraries_base_dir, &emit)?;
+        let healed =
+            classpath::precheck_launchwrapper_libraries(&mut classpath, &libraries_base_dir, emit)?;
+        if !healed.is_empty() {
+            let relative: Vec<String> = healed
+                .iter()
+                .filter_map(|p| p.strip_prefix(&libraries_base_dir).ok())
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+                .collect();
+            match crate::services::instance::record_healed_libraries(&options.version, &relative) {
+                Ok(()) => emit(
+                    "log-warning",
+                    format!(
+                        "本次启动自动补齐了 {} 个库，可执行“修复 Classpath”将其写入版本 JSON",
+                        relative.len()
+                    ),
+                ),
+                Err(e) => emit("log-warning", format!("记录自愈库失败: {}", e)),
+            }
+        }
+    }
+
+    // 4. 确定游戏运行目录（版本隔离 / 按账户隔离）
+    let run_dir = paths::game_run_dir(config, &options.version, Some(&options.username));
+    if config.version_isolation && config.isolate_per_account {
+        isolation::prepare_account_profile_directory(&version_dir, &options.username)?;
+        emit(
+            "log-debug",
+            format!("按账户隔离游戏目录: {}", run_dir.display()),
+        );
     }
 
-    // 4. 构建参数
+    // 5. 构建参数
+    progress(LaunchProgress::new(ProgressStage::PreparingArguments, 0, 1, "正在构建启动参数"));
     let assets_index = version_json["assetIndex"]["id"]
         .as_str()
         .unwrap_or(&options.version);
 
     let (jvm_args, game_args_vec) = arguments::build_arguments(
         &version_json,
-        &config,
-        &options,
-        &uuid,
-        &version_dir,
+        options,
+        session,
+        &run_dir,
         &game_dir,
         &assets_base_dir,
         assets_index,
+        &natives_dir,
         current_os,
         &classpath,
-        &emit,
+        emit,
     );
 
-    // 5. 组装 Java 启动参数
-    let java_path = java::resolve_java_path(&config)?;
+    // 6. 组装 Java 启动参数
+    let java_path = java::resolve_java_path(config, options.java_path.as_deref()).await?;
     emit("log-debug", format!("使用的Java路径: {}", java_path));
 
     let lwjgl_lib_path = natives_dir.to_string_lossy().to_string();
@@ -134,17 +343,39 @@ pub async fn launch_minecraft(
         emit("log-warning", format!("内存设置警告: {}", e));
     }
 
-    // 生成优化的 JVM 内存参数
-    let mut final_args = optimize_jvm_memory_args(memory_mb, &options.version);
-
-    // 添加其他必要的 JVM 参数
-    final_args.extend([
+    // 生成优化的 JVM 内存参数和其他必要的 JVM 参数
+    let mut default_jvm_args = optimize_jvm_memory_args(memory_mb, &options.version);
+    default_jvm_args.extend([
         format!("-Djava.library.path={}", lwjgl_lib_path),
         format!("-Dorg.lwjgl.librarypath={}", lwjgl_lib_path),
-        "-Dfile.encoding=UTF-8".to_string(),
-        "-Dorg.lwjgl.openal.mapping.use=false".to_string(),
     ]);
-    final_args.extend(jvm_args);
+    let version_detail = version_info::detect(&game_dir.join("versions"), &options.version);
+    default_jvm_args.extend(default_flags::jvm_args(
+        &default_flags::VersionTraits::new(&version_json, &version_detail),
+        &config.jvm_flag_overrides,
+    ));
+    if !for_export {
+        default_jvm_args.extend(skin_server::launch_jvm_args(emit).await?);
+    }
+
+    // 版本 JSON 已提供的参数优先，避免重复
+    let json_has_classpath = arguments::has_classpath_arg(&jvm_args);
+    let mut final_args = arguments::merge_jvm_args(default_jvm_args, jvm_args);
+
+    // 自定义窗口标题：覆盖启动器品牌
+    let window_title = options.window_title.clone().or(instance_settings.window_title.clone());
+    if let Some(title) = window_title.as_deref().filter(|t| !t.trim().is_empty()) {
+        final_args.retain(|a| !a.starts_with("-Dminecraft.launcher.brand="));
+        final_args.push(format!("-Dminecraft.launcher.brand={}", title));
+    }
+    final_args.extend(troubleshooting::jvm_args(&options.troubleshooting, &version_detail.loader_type));
+    if let Some(game_proxy) = instance_settings.proxy.as_ref() {
+        emit("log-debug", format!("游戏代理: {}:{}", game_proxy.host, game_proxy.port));
+        final_args.extend(proxy::jvm_args(game_proxy));
+    }
+    // 自定义参数：全局配置在前，实例和单次启动的参数在后，同名参数以后者为准
+    final_args.extend(config.extra_jvm_args.iter().cloned());
+    final_args.extend(options.extra_jvm_args.iter().cloned());
 
     // 构建 Classpath 字符串
     let classpath_str = classpath
@@ -155,30 +386,75 @@ pub async fn launch_minecraft(
 
     emit("log-debug", format!("最终Classpath: {}", classpath_str));
 
-    final_args.push("-cp".to_string());
-    final_args.push(classpath_str);
+    if !json_has_classpath {
+        final_args.push("-cp".to_string());
+        final_args.push(classpath_str);
+    }
     final_args.push(main_class.to_string());
     final_args.extend(game_args_vec);
 
     // 添加窗口大小参数
-    if let Some(width) = options.window_width.or(config.window_width) {
+    if let Some(width) = options
+        .window_width
+        .or(instance_settings.window_width)
+        .or(config.window_width)
+    {
         final_args.push("--width".to_string());
         final_args.push(width.to_string());
     }
-    if let Some(height) = options.window_height.or(config.window_height) {
+    if let Some(height) = options
+        .window_height
+        .or(instance_settings.window_height)
+        .or(config.window_height)
+    {
         final_args.push("--height".to_string());
         final_args.push(height.to_string());
     }
-    if options.fullscreen.unwrap_or(config.fullscreen) {
+    if options
+        .fullscreen
+        .or(instance_settings.fullscreen)
+        .unwrap_or(config.fullscreen)
+    {
         final_args.push("--fullscreen".to_string());
     }
+    if let Some(server) = options.server.as_deref() {
+        final_args.extend(arguments::server_join_args(&version_json, server.trim()));
+    }
+    if let Some(game_proxy) = instance_settings.proxy.as_ref() {
+        final_args.extend(proxy::game_args(game_proxy));
+    }
+    final_args.extend(options.extra_game_args.iter().cloned());
 
-    // 6. 启动游戏
-    let working_dir = if config.version_isolation {
-        version_dir
-    } else {
-        game_dir
-    };
+    Ok(LaunchPlan {
+        java_path,
+        args: final_args,
+        run_dir,
+        loader_type: version_detail.loader_type,
+    })
+}
+
+/// 导出实例的启动脚本（.bat/.sh）或单行启动命令
+pub async fn export_launch_script(
+    instance_name: &str,
+    format: script::ScriptFormat,
+) -> Result<script::ExportedLaunchScript, LauncherError> {
+    let options = instance::instance_launch_options(instance_name)?;
+    let _lock = instance_lock::acquire(instance_name, InstanceOperation::Launch)?;
+    let config = load_config()?;
+    // 脚本会写入磁盘，不包含正版账户的访问令牌
+    let session = auth::offline_session(&options.username)?;
+    let quiet = |_: &str, _: String| {};
+    let plan = prepare_launch(&options, &session, &config, true, &quiet, &|_| {}).await?;
+    let command = platform::build_game_command(&plan.java_path, &config, &quiet);
+    let content = script::render(&command, &plan.run_dir, format);
 
-    process::spawn_and_monitor_process(&java_path, final_args, &working_dir, window)
+    let path = match format.file_extension() {
+        Some(ext) => {
+            let path = paths::version_dir(&config, instance_name).join(format!("launch.{}", ext));
+            script::write_script(&path, &content)?;
+            Some(path.to_string_lossy().to_string())
+        }
+        None => None,
+    };
+    Ok(script::ExportedLaunchScript { content, path })
 }
@@ -1,24 +1,63 @@
 //! Natives 库解压逻辑
+//!
+//! 整合包版本往往有几十个 natives jar，逐个同步解压会拖慢每次启动。各 jar 在阻塞线程池中
+//! 并行读取和解压；解压来源（jar 路径、大小、修改时间和排除规则）的哈希记录在 natives 目录中，
+//! 下次启动来源未变化时直接复用已解压的文件。
 
+use super::progress::LaunchProgress;
+use crate::models::ProgressStage;
 use crate::errors::LauncherError;
+use sha1::{Digest, Sha1};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// 版本目录下的 natives 目录名
+pub(crate) const NATIVES_DIR: &str = "natives";
+/// natives 目录中记录解压来源哈希的文件（每次启动时更新，修改时间即上次启动时间）
+pub(super) const NATIVES_HASH_FILE: &str = ".ar1s_natives_hash";
+
+/// 待解压的 native jar
+#[derive(Clone)]
+struct NativeJar {
+    path: PathBuf,
+    exclude: Vec<String>,
+}
 
 /// 解压 Natives 库文件
-pub fn extract_natives(
+pub async fn extract_natives(
     version_json: &serde_json::Value,
     version_dir: &Path,
     libraries_base_dir: &Path,
     current_os: &str,
     emit: &impl Fn(&str, String),
+    progress: &impl Fn(LaunchProgress),
 ) -> Result<PathBuf, LauncherError> {
-    let natives_dir = version_dir.join("natives");
+    let natives_dir = version_dir.join(NATIVES_DIR);
     emit(
         "log-debug",
         format!("Natives目录: {}", natives_dir.display()),
     );
 
+    let jars = collect_native_jars(version_json, libraries_base_dir, current_os, emit)?;
+    let total = jars.len();
+    let hash = natives_hash(&jars);
+    let hash_file = natives_dir.join(NATIVES_HASH_FILE);
+    if fs::read_to_string(&hash_file).is_ok_and(|cached| cached.trim() == hash) {
+        emit("log-debug", "Natives 来源未变化，跳过解压".to_string());
+        // 更新修改时间，供残留 natives 清理判断上次使用时间
+        let _ = fs::write(&hash_file, &hash);
+        progress(LaunchProgress::new(
+            ProgressStage::ExtractingNatives,
+            total,
+            total,
+            format!("已复用 {} 个 Natives 库", total),
+        ));
+        return Ok(natives_dir);
+    }
+
     if natives_dir.exists() {
         emit(
             "log-debug",
@@ -28,15 +67,75 @@ pub fn extract_natives(
     }
     fs::create_dir_all(&natives_dir)?;
 
-    let Some(libraries) = version_json["libraries"].as_array() else {
-        return Ok(natives_dir);
+    let report = |done: usize| {
+        progress(LaunchProgress::new(
+            ProgressStage::ExtractingNatives,
+            done,
+            total,
+            format!("正在解压 Natives ({}/{})", done, total),
+        ))
     };
+    report(0);
 
-    for lib in libraries {
-        let Some(natives) = lib.get("natives") else {
-            continue;
-        };
+    // 先并行读取各 jar 的条目，同名文件以版本 JSON 中靠后的库为准（与逐个解压时的覆盖顺序一致）
+    let listings = run_blocking(
+        jars.iter()
+            .cloned()
+            .map(|jar| move || list_native_entries(&jar))
+            .collect(),
+        |_| {},
+    )
+    .await?;
+    let mut owner: HashMap<&str, usize> = HashMap::new();
+    for (index, entries) in listings.iter().enumerate() {
+        for (_, out_name) in entries {
+            owner.insert(out_name.as_str(), index);
+        }
+    }
+    let tasks = listings
+        .iter()
+        .enumerate()
+        .map(|(index, entries)| {
+            let entries: Vec<(String, String)> = entries
+                .iter()
+                .filter(|(_, out_name)| owner.get(out_name.as_str()) == Some(&index))
+                .cloned()
+                .collect();
+            let (lib_path, natives_dir) = (jars[index].path.clone(), natives_dir.clone());
+            move || extract_native_jar(&lib_path, &natives_dir, &entries)
+        })
+        .collect::<Vec<_>>();
+    let extracted = run_blocking(tasks, report).await?;
+
+    for line in extracted.into_iter().flatten() {
+        emit("log-debug", line);
+    }
+    log_natives_dir_contents(&natives_dir, emit);
+    fs::write(&hash_file, &hash)?;
+
+    progress(LaunchProgress::new(
+        ProgressStage::ExtractingNatives,
+        total,
+        total,
+        format!("已解压 {} 个 Natives 库", total),
+    ));
+    Ok(natives_dir)
+}
+
+/// 收集当前系统需要解压的 native jar
+fn collect_native_jars(
+    version_json: &serde_json::Value,
+    libraries_base_dir: &Path,
+    current_os: &str,
+    emit: &impl Fn(&str, String),
+) -> Result<Vec<NativeJar>, LauncherError> {
+    let Some(libraries) = version_json["libraries"].as_array() else {
+        return Ok(Vec::new());
+    };
 
+    let mut jars = Vec::new();
+    for lib in libraries.iter().filter(|lib| lib.get("natives").is_some()) {
+        let natives = &lib["natives"];
         emit("log-debug", format!("发现Natives库: {:?}", lib));
 
         let Some(os_classifier) = natives.get(current_os).and_then(|v| v.as_str()) else {
@@ -70,11 +169,6 @@ pub fn extract_natives(
         emit("log-debug", format!("Natives Artifact: {:?}", artifact));
 
         let lib_path = libraries_base_dir.join(artifact["path"].as_str().unwrap_or(""));
-        emit(
-            "log-debug",
-            format!("尝试解压Natives库: {}", lib_path.display()),
-        );
-
         if !lib_path.exists() {
             emit(
                 "log-error",
@@ -86,76 +180,190 @@ pub fn extract_natives(
             )));
         }
 
-        extract_native_jar(&lib_path, &natives_dir, lib, emit)?;
-        log_natives_dir_contents(&natives_dir, emit);
+        jars.push(NativeJar {
+            path: lib_path,
+            exclude: exclude_rules(lib),
+        });
     }
+    Ok(jars)
+}
 
-    Ok(natives_dir)
+/// 解压来源的哈希：jar 路径、大小、修改时间、排除规则以及系统和架构
+fn natives_hash(jars: &[NativeJar]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(format!("{}|{}\n", std::env::consts::OS, std::env::consts::ARCH));
+    for jar in jars {
+        let metadata = fs::metadata(&jar.path).ok();
+        let size = metadata.as_ref().map_or(0, |m| m.len());
+        let modified = metadata
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs());
+        hasher.update(format!(
+            "{}|{}|{}|{}\n",
+            jar.path.display(),
+            size,
+            modified,
+            jar.exclude.join(",")
+        ));
+    }
+    format!("{:x}", hasher.finalize())
 }
 
-/// 解压单个 native jar 文件
-fn extract_native_jar(
-    lib_path: &Path,
-    natives_dir: &Path,
-    lib: &serde_json::Value,
-    emit: &impl Fn(&str, String),
-) -> Result<(), LauncherError> {
-    let file = fs::File::open(lib_path)?;
-    let mut archive = zip::ZipArchive::new(file)?;
+/// 在阻塞线程池中并行执行，每完成一个调用一次 `on_done`，结果按输入顺序返回
+async fn run_blocking<T, F>(
+    tasks: Vec<F>,
+    mut on_done: impl FnMut(usize),
+) -> Result<Vec<T>, LauncherError>
+where
+    F: FnOnce() -> Result<T, LauncherError> + Send + 'static,
+    T: Send + 'static,
+{
+    let mut set = tokio::task::JoinSet::new();
+    let mut results: Vec<Option<T>> = Vec::with_capacity(tasks.len());
+    for (index, task) in tasks.into_iter().enumerate() {
+        results.push(None);
+        set.spawn_blocking(move || (index, task()));
+    }
+    let mut done = 0;
+    while let Some(joined) = set.join_next().await {
+        let (index, result) = joined?;
+        results[index] = Some(result?);
+        done += 1;
+        on_done(done);
+    }
+    Ok(results.into_iter().flatten().collect())
+}
+
+/// 拆分 natives 分类器中的架构后缀
+/// 例如 `natives-macos-arm64` -> (`natives-macos`, Some("arm64"))
+fn split_natives_arch(classifier: &str) -> (&str, Option<&str>) {
+    for suffix in ["-arm64", "-aarch64", "-x86"] {
+        if let Some(base) = classifier.strip_suffix(suffix) {
+            return (base, Some(&suffix[1..]));
+        }
+    }
+    (classifier, None)
+}
 
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i)?;
-        let entry_name = file.name().to_string();
+/// 计算新版（1.19+）按架构拆分的 natives 库中不适用于当前架构的库名
+///
+/// 新版 JSON 会同时列出 `natives-macos` 与 `natives-macos-arm64`，两者包含同名的
+/// 动态库，必须只保留与当前 CPU 架构对应的一份，否则 Apple Silicon 上会加载 x86_64 库失败
+pub fn arch_mismatched_natives(version_json: &serde_json::Value) -> HashSet<String> {
+    let mut skipped = HashSet::new();
+    let Some(libraries) = version_json["libraries"].as_array() else {
+        return skipped;
+    };
 
-        // 检查是否需要排除
-        if should_exclude_entry(&entry_name, lib) {
+    let names: Vec<&str> = libraries
+        .iter()
+        .filter_map(|lib| lib["name"].as_str())
+        .collect();
+
+    // 记录存在特定架构变体的库（去掉架构后缀后的完整名称）
+    let arch_variants: HashSet<(String, &str)> = names
+        .iter()
+        .filter_map(|name| {
+            let (prefix, classifier) = name.rsplit_once(':')?;
+            let (base, arch) = split_natives_arch(classifier);
+            arch.map(|a| {
+                let a = if a == "aarch64" { "arm64" } else { a };
+                (format!("{}:{}", prefix, base), a)
+            })
+        })
+        .collect();
+
+    let current_arch = match std::env::consts::ARCH {
+        "aarch64" => "arm64",
+        other => other,
+    };
+
+    for name in names {
+        let Some((prefix, classifier)) = name.rsplit_once(':') else {
+            continue;
+        };
+        if !classifier.starts_with("natives-") {
             continue;
         }
 
-        // 跳过文件夹条目
-        if entry_name.ends_with('/') {
-            continue;
+        let (base, arch) = split_natives_arch(classifier);
+        let arch = arch.map(|a| if a == "aarch64" { "arm64" } else { a });
+        let keep = match arch {
+            // 带架构后缀的库只在对应架构上保留
+            Some(a) => a == current_arch,
+            // 无后缀的库是 x86_64 版本，若存在当前架构的专用变体则跳过
+            None => {
+                !arch_variants.contains(&(format!("{}:{}", prefix, base), current_arch))
+            }
+        };
+
+        if !keep {
+            skipped.insert(name.to_string());
         }
+    }
 
-        // 取出最后一段文件名，避免嵌套目录
-        let file_stem = Path::new(&entry_name)
-            .file_name()
-            .and_then(|s| s.to_str())
-            .unwrap_or(&entry_name);
+    skipped
+}
 
-        let outpath = natives_dir.join(file_stem);
+/// 列出 jar 中需要解压的条目：(条目名, 解压后的文件名)
+fn list_native_entries(jar: &NativeJar) -> Result<Vec<(String, String)>, LauncherError> {
+    let archive = zip::ZipArchive::new(fs::File::open(&jar.path)?)?;
+    Ok(archive
+        .file_names()
+        // 跳过文件夹条目和排除的条目
+        .filter(|name| !name.ends_with('/') && !jar.exclude.iter().any(|e| name.starts_with(e.as_str())))
+        .map(|name| {
+            // 取出最后一段文件名，避免嵌套目录
+            let file_stem = Path::new(name)
+                .file_name()
+                .and_then(|s| s.to_str())
+                .unwrap_or(name);
+            (name.to_string(), file_stem.to_string())
+        })
+        .collect())
+}
 
-        if let Some(p) = outpath.parent() {
-            if !p.exists() {
-                fs::create_dir_all(p)?;
-            }
-        }
+/// 解压单个 native jar 中的指定条目，返回解压日志
+fn extract_native_jar(
+    lib_path: &Path,
+    natives_dir: &Path,
+    entries: &[(String, String)],
+) -> Result<Vec<String>, LauncherError> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(lib_path)?)?;
+    let mut logs = Vec::with_capacity(entries.len());
 
+    for (entry_name, file_name) in entries {
+        let mut file = archive.by_name(entry_name)?;
+        let outpath = natives_dir.join(file_name);
         let mut outfile = fs::File::create(&outpath)?;
         io::copy(&mut file, &mut outfile)?;
 
-        emit(
-            "log-debug",
-            format!("解压Natives文件: {} -> {}", entry_name, outpath.display()),
-        );
+        // Linux 上确保共享库具有可执行权限，部分发行版挂载选项下缺少权限会加载失败
+        #[cfg(target_os = "linux")]
+        if entry_name.ends_with(".so") {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&outpath, fs::Permissions::from_mode(0o755))?;
+        }
+
+        logs.push(format!("解压Natives文件: {} -> {}", entry_name, outpath.display()));
     }
 
-    Ok(())
+    Ok(logs)
 }
 
-/// 检查条目是否应该被排除
-fn should_exclude_entry(entry_name: &str, lib: &serde_json::Value) -> bool {
-    let Some(extract_rules) = lib.get("extract") else {
-        return false;
-    };
-
-    let Some(exclude) = extract_rules.get("exclude").and_then(|e| e.as_array()) else {
-        return false;
-    };
-
-    exclude
-        .iter()
-        .any(|v| entry_name.starts_with(v.as_str().unwrap_or("")))
+/// 库的解压排除规则
+fn exclude_rules(lib: &serde_json::Value) -> Vec<String> {
+    lib.get("extract")
+        .and_then(|rules| rules.get("exclude"))
+        .and_then(|e| e.as_array())
+        .map(|exclude| {
+            exclude
+                .iter()
+                .map(|v| v.as_str().unwrap_or("").to_string())
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 /// 记录 natives 目录内容
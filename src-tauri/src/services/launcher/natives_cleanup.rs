@@ -0,0 +1,194 @@
+//! 残留 natives 目录和临时解压目录清理
+//!
+//! natives 位于各版本目录中，版本被外部删除或重命名中断后，残留的版本目录里
+//! 只剩 natives；长期不玩的实例也会一直占用空间。这里找出不属于任何已安装版本、
+//! 或超过指定天数未启动（按启动器记录的上次启动时间）的 natives 目录，以及过期的临时目录。
+
+use super::natives::{NATIVES_DIR, NATIVES_HASH_FILE};
+use super::running::running_pid;
+use crate::errors::LauncherError;
+use crate::services::{config, paths};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// 未指定时按 30 天未启动判断
+const DEFAULT_UNUSED_DAYS: u32 = 30;
+
+/// 可清理目录的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NativesDirReason {
+    /// 所在版本目录没有版本 JSON（版本已删除或重命名）
+    Orphaned,
+    /// 实例超过指定天数未启动
+    Unused,
+    /// 游戏目录下 temp 中过期的解压残留
+    Temp,
+}
+
+/// 可清理的 natives 或临时目录
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NativesDirInfo {
+    pub path: String,
+    /// 所属版本目录名，临时目录为空
+    pub instance_name: Option<String>,
+    pub size: u64,
+    /// 上次使用时间（Unix 毫秒）
+    pub last_used: Option<i64>,
+    pub reason: NativesDirReason,
+}
+
+/// 清理结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NativesPruneResult {
+    pub removed: usize,
+    pub freed_bytes: u64,
+    /// 删除失败的目录和原因
+    pub failed: Vec<String>,
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    };
+    entries
+        .flatten()
+        .map(|e| match e.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&e.path()),
+            _ => e.metadata().map(|m| m.len()).unwrap_or(0),
+        })
+        .sum()
+}
+
+/// 修改时间（Unix 毫秒）
+fn modified_millis(path: &Path) -> Option<i64> {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+}
+
+fn scan_game_dir(
+    game_dir: &Path,
+    config: &crate::models::GameConfig,
+    cutoff: i64,
+    found: &mut Vec<NativesDirInfo>,
+) {
+    if let Ok(entries) = fs::read_dir(game_dir.join("versions")) {
+        for entry in entries.flatten() {
+            let version_dir = entry.path();
+            let natives_dir = version_dir.join(NATIVES_DIR);
+            if !natives_dir.is_dir() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            // 每次启动都会更新哈希文件，其修改时间即上次启动时间
+            let last_used = config
+                .instance_last_played
+                .get(&name)
+                .copied()
+                .or_else(|| modified_millis(&natives_dir.join(NATIVES_HASH_FILE)))
+                .or_else(|| modified_millis(&natives_dir));
+
+            let reason = if !version_dir.join(format!("{}.json", name)).exists() {
+                NativesDirReason::Orphaned
+            } else if last_used.is_some_and(|t| t < cutoff) {
+                NativesDirReason::Unused
+            } else {
+                continue;
+            };
+            // 正在运行的游戏仍在使用 natives
+            if running_pid(&name, &version_dir).is_some() {
+                continue;
+            }
+            found.push(NativesDirInfo {
+                path: natives_dir.to_string_lossy().to_string(),
+                instance_name: Some(name),
+                size: dir_size(&natives_dir),
+                last_used,
+                reason,
+            });
+        }
+    }
+
+    if let Ok(entries) = fs::read_dir(game_dir.join("temp")) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let last_used = modified_millis(&path);
+            if !last_used.is_some_and(|t| t < cutoff) {
+                continue;
+            }
+            found.push(NativesDirInfo {
+                path: path.to_string_lossy().to_string(),
+                instance_name: None,
+                size: dir_size(&path),
+                last_used,
+                reason: NativesDirReason::Temp,
+            });
+        }
+    }
+}
+
+/// 列出可清理的 natives 目录和过期临时目录
+pub fn list_orphaned_natives(unused_days: Option<u32>) -> Result<Vec<NativesDirInfo>, LauncherError> {
+    let config = config::load_config()?;
+    let days = unused_days.unwrap_or(DEFAULT_UNUSED_DAYS) as i64;
+    let cutoff = chrono::Utc::now().timestamp_millis() - days * 24 * 60 * 60 * 1000;
+
+    let mut found = Vec::new();
+    for game_dir in paths::game_dirs(&config) {
+        scan_game_dir(&game_dir, &config, cutoff, &mut found);
+    }
+    found.sort_by(|a, b| b.size.cmp(&a.size));
+    Ok(found)
+}
+
+/// 清理目录；指定 `paths` 时只清理列表中的目录（必须是当前可清理的目录）
+pub fn prune_natives(
+    unused_days: Option<u32>,
+    paths: Option<Vec<String>>,
+) -> Result<NativesPruneResult, LauncherError> {
+    let candidates = list_orphaned_natives(unused_days)?;
+    let mut result = NativesPruneResult {
+        removed: 0,
+        freed_bytes: 0,
+        failed: Vec::new(),
+    };
+
+    for dir in candidates {
+        if paths.as_ref().is_some_and(|p| !p.contains(&dir.path)) {
+            continue;
+        }
+        let path = PathBuf::from(&dir.path);
+        let removed = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        match removed {
+            Ok(()) => {
+                result.removed += 1;
+                result.freed_bytes += dir.size;
+                // 孤立版本目录删除 natives 后为空时一并删除
+                if dir.reason == NativesDirReason::Orphaned {
+                    if let Some(version_dir) = path.parent() {
+                        let _ = fs::remove_dir(version_dir);
+                    }
+                }
+            }
+            Err(e) => result.failed.push(format!("{}: {}", dir.path, e)),
+        }
+    }
+
+    log::info!(
+        "已清理 {} 个 natives/临时目录，释放 {} 字节",
+        result.removed,
+        result.freed_bytes
+    );
+    Ok(result)
+}
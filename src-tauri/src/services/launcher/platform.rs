@@ -0,0 +1,86 @@
+//! 平台相关的游戏进程启动调整
+//!
+//! - Linux: 检测 Wayland/X11 会话并设置环境提示，可用时通过 gamemoderun 启动
+
+use crate::models::GameConfig;
+use std::process::Command;
+
+/// 图形会话类型
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionType {
+    Wayland,
+    X11,
+    Unknown,
+}
+
+/// 检测当前图形会话类型
+#[cfg(target_os = "linux")]
+pub fn detect_session_type() -> SessionType {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        return SessionType::Wayland;
+    }
+    match std::env::var("XDG_SESSION_TYPE").as_deref() {
+        Ok("wayland") => SessionType::Wayland,
+        Ok("x11") => SessionType::X11,
+        _ if std::env::var_os("DISPLAY").is_some() => SessionType::X11,
+        _ => SessionType::Unknown,
+    }
+}
+
+/// 在 PATH 中查找可执行文件
+#[cfg(target_os = "linux")]
+fn find_in_path(program: &str) -> Option<std::path::PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
+/// 构建游戏进程命令，应用平台相关的包装程序和环境变量
+#[cfg(target_os = "linux")]
+pub fn build_game_command(
+    java_path: &str,
+    config: &GameConfig,
+    emit: &impl Fn(&str, String),
+) -> Command {
+    let mut command = match config
+        .use_gamemode
+        .then(|| find_in_path("gamemoderun"))
+        .flatten()
+    {
+        Some(gamemoderun) => {
+            emit(
+                "log-debug",
+                format!("通过 GameMode 启动: {}", gamemoderun.display()),
+            );
+            let mut command = Command::new(gamemoderun);
+            command.arg(java_path);
+            command
+        }
+        None => Command::new(java_path),
+    };
+
+    let session = detect_session_type();
+    emit("log-debug", format!("图形会话类型: {:?}", session));
+
+    if session == SessionType::Wayland && config.wayland_hints {
+        // SDL 优先使用 Wayland，失败时回退 X11；GLFW 仍走 XWayland 以保证兼容性
+        command.env("SDL_VIDEODRIVER", "wayland,x11");
+        // 修复 AWT 窗口（如 Forge 早期加载界面）在非重排窗口管理器下空白的问题
+        command.env("_JAVA_AWT_WM_NONREPARENTING", "1");
+        emit("log-debug", "已为 Wayland 会话设置环境提示".to_string());
+    }
+
+    command
+}
+
+/// 构建游戏进程命令
+#[cfg(not(target_os = "linux"))]
+pub fn build_game_command(
+    java_path: &str,
+    _config: &GameConfig,
+    _emit: &impl Fn(&str, String),
+) -> Command {
+    Command::new(java_path)
+}
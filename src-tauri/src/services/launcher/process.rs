@@ -1,26 +1,74 @@
 //! 游戏进程启动和监控逻辑
 
+use super::exit_codes::{self, ExitExplanation};
+use super::hs_err::{self, JvmCrashReport};
+use super::platform;
+use super::running;
+use super::session::Session;
+use super::troubleshooting::{self, TroubleshootingSuggestion};
 use crate::errors::LauncherError;
-use std::path::Path;
-use std::process::{Child, Command, Stdio};
+use crate::models::{GameConfig, TroubleshootingToggle};
+use crate::services::hooks::{self, HookPoint};
+use crate::services::instance_lock::{InstanceLockGuard, InstanceOperation};
+use crate::services::{log_events, paths};
+use crate::services::telemetry::{self, TelemetryEvent};
+use crate::utils::encoding::{decode_process_output, truncate_str};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::process::{Child, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
-use tauri::Emitter;
+use std::time::{Duration, Instant, SystemTime};
+use crate::services::event_sink::EventSink;
 
 /// 游戏进程最大运行时间（24 小时）
 const MAX_GAME_RUNTIME: Duration = Duration::from_secs(24 * 60 * 60);
 
+/// `minecraft-exited` 事件内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameExitInfo {
+    pub instance: String,
+    pub exit_code: Option<i32>,
+    /// 终止进程的信号（Unix）
+    pub signal: Option<i32>,
+    pub message: String,
+    pub explanation: Option<ExitExplanation>,
+    /// JVM 自身崩溃时的 hs_err 日志解析结果
+    pub jvm_crash: Option<JvmCrashReport>,
+    /// 连续崩溃时推荐的故障排查选项（此时不再自动重启）
+    pub troubleshooting: Option<TroubleshootingSuggestion>,
+}
+
+/// 游戏进程的启动命令
+#[derive(Debug, Clone)]
+pub struct GameProcess {
+    pub java_path: String,
+    pub args: Vec<String>,
+    pub working_dir: PathBuf,
+    pub instance: String,
+    /// 加载器类型，用于崩溃循环时推荐故障排查选项
+    pub loader_type: String,
+    /// 本次启用的故障排查选项
+    pub troubleshooting: Vec<TroubleshootingToggle>,
+    /// 安全模式下移走了模组的 mods 目录，游戏退出后恢复
+    pub disabled_mods: Option<PathBuf>,
+}
+
 /// 启动并监控游戏进程
+///
+/// `auto_restart` 为 true 时，若游戏因显卡驱动崩溃等偶发问题退出，会自动重新启动一次
 pub fn spawn_and_monitor_process(
-    java_path: &str,
-    final_args: Vec<String>,
-    working_dir: &Path,
-    window: tauri::Window,
+    process: GameProcess,
+    config: &GameConfig,
+    auto_restart: bool,
+    lock: InstanceLockGuard,
+    window: EventSink,
 ) -> Result<(), LauncherError> {
-    let mut command = Command::new(java_path);
-    command.args(&final_args);
-    command.current_dir(working_dir);
+    let emit = |event: &str, msg: String| log_events::emit(&window, event, msg);
+    let mut command = platform::build_game_command(&process.java_path, config, &emit);
+    command.args(&process.args);
+    command.current_dir(&process.working_dir);
 
     // 在 Windows 上隐藏命令行窗口
     #[cfg(target_os = "windows")]
@@ -30,30 +78,51 @@ pub fn spawn_and_monitor_process(
         command.creation_flags(0x08000000);
     }
 
-    let _ = window.emit("log-debug", format!("最终启动命令: {:?}", command));
+    log_events::emit(&window, "log-debug", format!("最终启动命令: {:?}", command));
     window.emit("launch-command", format!("{:?}", command))?;
 
     // 启动游戏进程但不等待它结束
+    let launched_at = SystemTime::now();
     let child = command
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()?;
 
     let pid = child.id();
-    let _ = window.emit("log-debug", format!("游戏已启动，PID: {}", pid));
+    running::register(&process.instance, pid);
+    let session = Session::start(
+        &paths::version_dir(config, &process.instance),
+        &process.instance,
+        &format!("{:?}", command),
+        pid,
+    );
+    log_events::emit(&window, "log-debug", format!("游戏已启动，PID: {}", pid));
 
     // 发送游戏启动成功的事件到前端
     window.emit("minecraft-launched", format!("游戏已启动，PID: {}", pid))?;
 
-    // 在后台线程中监控游戏进程（带超时）
-    spawn_monitor_thread(child, window, pid);
+    // 在后台线程中监控游戏进程（带超时），游戏退出前保持实例锁
+    lock.set_operation(InstanceOperation::Running);
+    let restart = auto_restart.then(|| config.clone());
+    spawn_monitor_thread(child, window, pid, process, session, launched_at, lock, restart);
 
     Ok(())
 }
 
 /// 启动监控线程（带超时机制）
-fn spawn_monitor_thread(mut child: Child, window: tauri::Window, pid: u32) {
+#[allow(clippy::too_many_arguments)]
+fn spawn_monitor_thread(
+    mut child: Child,
+    window: EventSink,
+    pid: u32,
+    process: GameProcess,
+    session: Option<Session>,
+    launched_at: SystemTime,
+    lock: InstanceLockGuard,
+    restart: Option<GameConfig>,
+) {
     std::thread::spawn(move || {
+        let instance = process.instance.clone();
         let start_time = Instant::now();
         let is_running = Arc::new(AtomicBool::new(true));
 
@@ -70,7 +139,8 @@ fn spawn_monitor_thread(mut child: Child, window: tauri::Window, pid: u32) {
 
                 let elapsed = start_time.elapsed();
                 if elapsed > MAX_GAME_RUNTIME {
-                    let _ = window_clone.emit(
+                    log_events::emit(
+                        &window_clone,
                         "log-warning",
                         format!(
                             "游戏运行时间超过 {} 小时，监控线程将停止",
@@ -86,12 +156,60 @@ fn spawn_monitor_thread(mut child: Child, window: tauri::Window, pid: u32) {
         match wait_for_process_with_timeout(&mut child, MAX_GAME_RUNTIME) {
             Ok(Some(output)) => {
                 is_running.store(false, Ordering::SeqCst);
-                handle_process_exit(output, &window);
+                running::unregister(&instance, pid);
+                let crashed = !output.status.success();
+                // JVM 自身崩溃时收集工作目录中的 hs_err 日志
+                let jvm_crash = crashed
+                    .then(|| hs_err::collect(&process.working_dir, launched_at))
+                    .flatten();
+                if let Some(session) = session {
+                    session.write_output(&output);
+                    session.finish(&process.working_dir, launched_at, output.status.code(), crashed);
+                }
+                let mut exit = handle_process_exit(output, &instance, jvm_crash, &window);
+
+                // 崩溃循环保护：连续崩溃时不再自动重启，改为推荐故障排查选项
+                let crashes = troubleshooting::record_exit(&instance, crashed, start_time.elapsed());
+                exit.troubleshooting = troubleshooting::crash_loop_suggestion(
+                    crashes,
+                    &process.loader_type,
+                    &process.troubleshooting,
+                );
+                if exit.troubleshooting.is_some() {
+                    log_events::emit(
+                        &window,
+                        "log-warning",
+                        format!("实例 {} 已连续崩溃 {} 次，建议使用故障排查选项启动", instance, crashes),
+                    );
+                }
+
+                let restartable = exit.explanation.as_ref().is_some_and(|e| e.restartable)
+                    && exit.troubleshooting.is_none();
+                match restart {
+                    Some(config) if restartable => {
+                        restart_once(process, &config, exit, lock, window);
+                        return;
+                    }
+                    _ => {
+                        restore_disabled_mods(&process);
+                        let hook_env = [
+                            ("AR1S_EXIT_CODE", exit.exit_code.map(|c| c.to_string()).unwrap_or_default()),
+                            ("AR1S_CRASHED", crashed.to_string()),
+                        ];
+                        let _ = window.emit("minecraft-exited", exit);
+                        // 退出后钩子在释放实例锁之前执行，避免与下一次启动同时修改实例
+                        hooks::run_hook_logged(&instance, HookPoint::PostExit, &hook_env);
+                    }
+                }
             }
             Ok(None) => {
-                // 超时，进程仍在运行
+                // 超时，进程仍在运行（保留进程登记，删除等操作仍会检查）
                 is_running.store(false, Ordering::SeqCst);
-                let _ = window.emit(
+                if let Some(session) = session {
+                    session.finish(&process.working_dir, launched_at, None, false);
+                }
+                log_events::emit(
+                    &window,
                     "log-warning",
                     format!("游戏进程 (PID: {}) 运行超时，停止监控", pid),
                 );
@@ -102,7 +220,12 @@ fn spawn_monitor_thread(mut child: Child, window: tauri::Window, pid: u32) {
             }
             Err(e) => {
                 is_running.store(false, Ordering::SeqCst);
-                let _ = window.emit("log-error", format!("监控游戏进程时出错: {}", e));
+                running::unregister(&instance, pid);
+                if let Some(session) = session {
+                    session.finish(&process.working_dir, launched_at, None, false);
+                }
+                restore_disabled_mods(&process);
+                log_events::emit(&window, "log-error", format!("监控游戏进程时出错: {}", e));
                 let _ = window.emit("minecraft-error", format!("监控游戏进程时出错: {}", e));
             }
         }
@@ -165,33 +288,72 @@ fn wait_for_process_with_timeout(
     }
 }
 
-/// 处理进程退出
-fn handle_process_exit(output: std::process::Output, window: &tauri::Window) {
+/// 恢复安全模式下移走的模组
+fn restore_disabled_mods(process: &GameProcess) {
+    if let Some(mods_dir) = &process.disabled_mods {
+        troubleshooting::restore_mods(mods_dir);
+    }
+}
+
+/// 偶发崩溃后重新启动一次（重启后的进程不再自动重启，安全模式下移走的模组保持移走状态）
+fn restart_once(
+    process: GameProcess,
+    config: &GameConfig,
+    exit: GameExitInfo,
+    lock: InstanceLockGuard,
+    window: EventSink,
+) {
+    log_events::emit(
+        &window,
+        "log-warning",
+        format!("{}，正在自动重新启动实例 {}", exit.message, exit.instance),
+    );
+    let _ = window.emit("minecraft-restarting", &exit);
+    let disabled_mods = process.disabled_mods.clone();
+    let result = spawn_and_monitor_process(process, config, false, lock, window.clone());
+    if let Err(e) = result {
+        if let Some(mods_dir) = disabled_mods {
+            troubleshooting::restore_mods(&mods_dir);
+        }
+        let _ = window.emit("minecraft-error", format!("自动重新启动失败: {}", e));
+        let _ = window.emit("minecraft-exited", exit);
+    }
+}
+
+/// 处理进程退出，返回退出信息（由调用方发送 `minecraft-exited`）
+fn handle_process_exit(
+    output: std::process::Output,
+    instance: &str,
+    jvm_crash: Option<JvmCrashReport>,
+    window: &EventSink,
+) -> GameExitInfo {
     let status = output.status;
+    let explanation = exit_codes::explain(&status);
 
     // 输出 stdout（限制大小避免内存问题）
     if !output.stdout.is_empty() {
-        let stdout_str = String::from_utf8_lossy(&output.stdout);
+        let stdout_str = decode_process_output(&output.stdout);
         let truncated = if stdout_str.len() > 10000 {
-            format!("{}...[truncated]", &stdout_str[..10000])
+            format!("{}...[truncated]", truncate_str(&stdout_str, 10000))
         } else {
             stdout_str.to_string()
         };
-        let _ = window.emit("log-debug", format!("游戏 stdout:\n{}", truncated));
+        log_events::emit(window, "log-debug", format!("游戏 stdout:\n{}", truncated));
     }
 
     // 输出 stderr（限制大小）
     if !output.stderr.is_empty() {
-        let stderr_str = String::from_utf8_lossy(&output.stderr);
+        let stderr_str = decode_process_output(&output.stderr);
         let truncated = if stderr_str.len() > 10000 {
-            format!("{}...[truncated]", &stderr_str[..10000])
+            format!("{}...[truncated]", truncate_str(&stderr_str, 10000))
         } else {
             stderr_str.to_string()
         };
-        let _ = window.emit("log-error", format!("游戏 stderr:\n{}", truncated));
+        log_events::emit(window, "log-error", format!("游戏 stderr:\n{}", truncated));
     }
 
-    let _ = window.emit(
+    log_events::emit(
+        window,
         "log-debug",
         format!("游戏进程退出，状态码: {:?}", status.code()),
     );
@@ -201,9 +363,9 @@ fn handle_process_exit(output: std::process::Output, window: &tauri::Window) {
         let mut combined = String::new();
         if !output.stdout.is_empty() {
             combined.push_str("[stdout]\n");
-            let stdout_str = String::from_utf8_lossy(&output.stdout);
+            let stdout_str = decode_process_output(&output.stdout);
             if stdout_str.len() > 5000 {
-                combined.push_str(&stdout_str[..5000]);
+                combined.push_str(truncate_str(&stdout_str, 5000));
                 combined.push_str("...[truncated]");
             } else {
                 combined.push_str(&stdout_str);
@@ -212,27 +374,53 @@ fn handle_process_exit(output: std::process::Output, window: &tauri::Window) {
         }
         if !output.stderr.is_empty() {
             combined.push_str("[stderr]\n");
-            let stderr_str = String::from_utf8_lossy(&output.stderr);
+            let stderr_str = decode_process_output(&output.stderr);
             if stderr_str.len() > 5000 {
-                combined.push_str(&stderr_str[..5000]);
+                combined.push_str(truncate_str(&stderr_str, 5000));
                 combined.push_str("...[truncated]");
             } else {
                 combined.push_str(&stderr_str);
             }
         }
+        let mut reason = explanation
+            .as_ref()
+            .map(|e| format!("{}：{}\n", e.title, e.description))
+            .unwrap_or_default();
+        if let Some(crash) = &jvm_crash {
+            reason.push_str(&format!(
+                "JVM 崩溃（出错帧: {}）：{}\n崩溃日志: {}\n",
+                crash.problematic_frame.as_deref().unwrap_or("未知"),
+                crash.suggestion,
+                crash.file
+            ));
+        }
         let _ = window.emit(
             "minecraft-error",
             format!(
-                "游戏以非零退出 (code={:?})，输出:\n{}",
+                "游戏以非零退出 (code={:?})，{}输出:\n{}",
                 status.code(),
+                reason,
                 combined
             ),
         );
     }
 
-    // 发送游戏退出事件
-    let _ = window.emit(
-        "minecraft-exited",
-        format!("游戏已退出，状态码: {:?}", status.code()),
-    );
+    telemetry::record(TelemetryEvent::LaunchResult {
+        success: status.success(),
+        exit_code: status.code(),
+    });
+
+    let message = match &explanation {
+        Some(e) => format!("游戏已退出，状态码: {:?}（{}）", status.code(), e.title),
+        None => format!("游戏已退出，状态码: {:?}", status.code()),
+    };
+    GameExitInfo {
+        instance: instance.to_string(),
+        exit_code: status.code(),
+        signal: exit_codes::exit_signal(&status),
+        message,
+        explanation,
+        jvm_crash,
+        troubleshooting: None,
+    }
 }
@@ -0,0 +1,51 @@
+//! 启动阶段进度
+//!
+//! 从点击启动到游戏窗口出现，整合包实例可能需要 10~20 秒，
+//! 这里按阶段发送 `launch-progress` 事件，供前端显示启动进度条。
+
+use crate::models::ProgressStage;
+use serde::Serialize;
+
+/// 启动阶段在整体进度中所占的百分比区间
+fn stage_range(stage: ProgressStage) -> (u8, u8) {
+    match stage {
+        ProgressStage::VerifyingFiles => (0, 10),
+        ProgressStage::ExtractingNatives => (10, 40),
+        ProgressStage::BuildingClasspath => (40, 80),
+        ProgressStage::PreparingArguments => (80, 95),
+        ProgressStage::Launching => (95, 100),
+        _ => (0, 100),
+    }
+}
+
+/// `launch-progress` 事件内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchProgress {
+    pub stage: ProgressStage,
+    /// 当前阶段已处理的项目数
+    pub current: usize,
+    /// 当前阶段的项目总数（0 表示不可计数）
+    pub total: usize,
+    /// 整体进度百分比
+    pub percent: u8,
+    pub message: String,
+}
+
+impl LaunchProgress {
+    pub fn new(stage: ProgressStage, current: usize, total: usize, message: impl Into<String>) -> Self {
+        let (start, end) = stage_range(stage);
+        let fraction = if total > 0 {
+            current.min(total) as f64 / total as f64
+        } else {
+            0.0
+        };
+        Self {
+            stage,
+            current,
+            total,
+            percent: start + ((end - start) as f64 * fraction).round() as u8,
+            message: message.into(),
+        }
+    }
+}
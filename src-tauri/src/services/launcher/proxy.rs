@@ -0,0 +1,71 @@
+//! 游戏进程代理
+//!
+//! 启动器的下载代理不会传给游戏。网络受限的玩家需要让游戏本身通过代理连接
+//! 认证 / 会话服务器，这里把实例的代理设置转换为 Java 网络属性；SOCKS5 代理同时通过
+//! `--proxyHost` 等游戏参数传给 Minecraft，由其交给 authlib 使用。
+
+use crate::models::{GameProxy, GameProxyKind};
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// 代理对应的 JVM 参数
+pub(super) fn jvm_args(proxy: &GameProxy) -> Vec<String> {
+    let host = proxy.host.trim();
+    let mut args = vec!["-Djava.net.useSystemProxies=false".to_string()];
+    match proxy.kind {
+        GameProxyKind::Http => {
+            for scheme in ["http", "https"] {
+                args.push(format!("-D{}.proxyHost={}", scheme, host));
+                args.push(format!("-D{}.proxyPort={}", scheme, proxy.port));
+                if let Some(user) = non_empty(&proxy.username) {
+                    args.push(format!("-D{}.proxyUser={}", scheme, user));
+                }
+                if let Some(password) = non_empty(&proxy.password) {
+                    args.push(format!("-D{}.proxyPassword={}", scheme, password));
+                }
+            }
+            if non_empty(&proxy.username).is_some() {
+                // JDK 默认禁止 HTTPS 隧道使用 Basic 认证
+                args.push("-Djdk.http.auth.tunneling.disabledSchemes=".to_string());
+            }
+            if !proxy.non_proxy_hosts.is_empty() {
+                // https 复用 http.nonProxyHosts
+                args.push(format!("-Dhttp.nonProxyHosts={}", proxy.non_proxy_hosts.join("|")));
+            }
+        }
+        GameProxyKind::Socks5 => {
+            args.push(format!("-DsocksProxyHost={}", host));
+            args.push(format!("-DsocksProxyPort={}", proxy.port));
+            args.push("-DsocksProxyVersion=5".to_string());
+            if let Some(user) = non_empty(&proxy.username) {
+                args.push(format!("-Djava.net.socks.username={}", user));
+            }
+            if let Some(password) = non_empty(&proxy.password) {
+                args.push(format!("-Djava.net.socks.password={}", password));
+            }
+        }
+    }
+    args
+}
+
+/// 代理对应的游戏参数（Minecraft 只支持 SOCKS 代理参数）
+pub(super) fn game_args(proxy: &GameProxy) -> Vec<String> {
+    if proxy.kind != GameProxyKind::Socks5 {
+        return Vec::new();
+    }
+    let mut args = vec![
+        "--proxyHost".to_string(),
+        proxy.host.trim().to_string(),
+        "--proxyPort".to_string(),
+        proxy.port.to_string(),
+    ];
+    if let Some(user) = non_empty(&proxy.username) {
+        args.extend(["--proxyUser".to_string(), user.to_string()]);
+    }
+    if let Some(password) = non_empty(&proxy.password) {
+        args.extend(["--proxyPass".to_string(), password.to_string()]);
+    }
+    args
+}
@@ -0,0 +1,92 @@
+//! 版本 JSON 中 `rules` 规则的求值逻辑
+//!
+//! 语义与官方启动器保持一致：
+//! - 没有 `rules` 字段时默认允许
+//! - 存在 `rules` 时默认禁止，按顺序匹配，最后一条匹配的规则决定结果
+//! - 一条规则的 `os`（name / arch / version）与 `features` 条件必须全部满足才算匹配
+
+use regex::Regex;
+use serde_json::Value;
+use std::sync::LazyLock;
+
+/// 当前系统版本号（用于匹配 `os.version` 正则）
+static OS_VERSION: LazyLock<String> =
+    LazyLock::new(|| sysinfo::System::os_version().unwrap_or_default());
+
+/// 规则求值环境
+pub(super) struct RuleEnv<'a> {
+    /// 官方命名的操作系统名称（windows / osx / linux）
+    pub os_name: &'a str,
+    /// 启用的特性（如 has_custom_resolution），未列出的特性视为关闭
+    pub features: &'a [&'a str],
+}
+
+impl<'a> RuleEnv<'a> {
+    pub fn new(os_name: &'a str) -> Self {
+        Self {
+            os_name,
+            features: &[],
+        }
+    }
+}
+
+/// 官方 JSON 使用的架构名称
+fn current_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86" => "x86",
+        "x86_64" => "x86_64",
+        "aarch64" => "arm64",
+        other => other,
+    }
+}
+
+/// 判断一组规则是否允许
+pub(super) fn rules_allow(rules: Option<&Value>, env: &RuleEnv) -> bool {
+    let Some(rules) = rules.and_then(|r| r.as_array()) else {
+        return true;
+    };
+
+    let mut allowed = false;
+    for rule in rules {
+        if rule_matches(rule, env) {
+            allowed = rule["action"].as_str() == Some("allow");
+        }
+    }
+    allowed
+}
+
+/// 判断单条规则的条件是否与当前环境匹配
+fn rule_matches(rule: &Value, env: &RuleEnv) -> bool {
+    if let Some(os) = rule.get("os") {
+        if let Some(name) = os["name"].as_str() {
+            if name != env.os_name {
+                return false;
+            }
+        }
+        if let Some(arch) = os["arch"].as_str() {
+            if arch != current_arch() {
+                return false;
+            }
+        }
+        if let Some(pattern) = os["version"].as_str() {
+            // 无法解析的正则视为不匹配，避免误加参数
+            let matched = Regex::new(pattern)
+                .map(|re| re.is_match(&OS_VERSION))
+                .unwrap_or(false);
+            if !matched {
+                return false;
+            }
+        }
+    }
+
+    if let Some(features) = rule.get("features").and_then(|f| f.as_object()) {
+        for (name, expected) in features {
+            let enabled = env.features.contains(&name.as_str());
+            if expected.as_bool() != Some(enabled) {
+                return false;
+            }
+        }
+    }
+
+    true
+}
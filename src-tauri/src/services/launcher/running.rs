@@ -0,0 +1,125 @@
+//! 运行中的游戏进程登记
+//!
+//! 实例锁只在监控线程存活期间有效：监控超时、或启动器重启后再次打开时，游戏可能仍在运行。
+//! 这里登记本次启动的进程 PID，并通过进程命令行识别其他来源启动的游戏，
+//! 供删除、重命名等破坏性操作在执行前检查。
+
+use crate::errors::LauncherError;
+use crate::services::instance_lock;
+use crate::services::instance_lock::InstanceOperation;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+/// 强制结束游戏后等待进程退出的时间
+const KILL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 本次会话启动的游戏进程（实例名 -> PID）
+static RUNNING: LazyLock<Mutex<HashMap<String, u32>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 登记游戏进程
+pub(super) fn register(instance: &str, pid: u32) {
+    if let Ok(mut running) = RUNNING.lock() {
+        running.insert(instance.to_string(), pid);
+    }
+}
+
+/// 游戏退出后取消登记（自动重启后 PID 已变化时不覆盖新进程）
+pub(super) fn unregister(instance: &str, pid: u32) {
+    if let Ok(mut running) = RUNNING.lock() {
+        if running.get(instance) == Some(&pid) {
+            running.remove(instance);
+        }
+    }
+}
+
+/// 本次会话启动且仍在登记中的游戏进程（实例名, PID）
+pub fn running_games() -> Vec<(String, u32)> {
+    RUNNING
+        .lock()
+        .map(|r| r.iter().map(|(name, pid)| (name.clone(), *pid)).collect())
+        .unwrap_or_default()
+}
+
+fn process_system() -> System {
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing()
+            .with_cmd(UpdateKind::OnlyIfNotSet)
+            .with_cwd(UpdateKind::OnlyIfNotSet),
+    );
+    sys
+}
+
+/// 参数是否引用了实例版本目录（natives 路径、游戏目录等）
+fn references_dir(arg: &str, dir: &str) -> bool {
+    arg.match_indices(dir).any(|(i, _)| {
+        arg[i + dir.len()..]
+            .chars()
+            .next()
+            .is_none_or(|c| c == '/' || c == '\\' || c == '"' || c == ';' || c == ':')
+    })
+}
+
+/// 查找正在使用实例的游戏进程 PID
+pub fn running_pid(instance: &str, instance_dir: &Path) -> Option<u32> {
+    let sys = process_system();
+    let registered = RUNNING.lock().ok().and_then(|r| r.get(instance).copied());
+    if let Some(pid) = registered {
+        if sys.process(Pid::from_u32(pid)).is_some() {
+            return Some(pid);
+        }
+        unregister(instance, pid);
+    }
+
+    // 其他会话或其他启动器启动的游戏：natives 路径和 --gameDir 都位于实例版本目录下
+    let dir = instance_dir.to_string_lossy();
+    sys.processes()
+        .iter()
+        .find(|(_, process)| {
+            process.cwd().is_some_and(|cwd| cwd.starts_with(instance_dir))
+                || process
+                    .cmd()
+                    .iter()
+                    .any(|arg| references_dir(&arg.to_string_lossy(), &dir))
+        })
+        .map(|(pid, _)| pid.as_u32())
+}
+
+/// 破坏性操作前检查实例是否正在运行；`force` 为 true 时结束游戏进程并等待其退出
+pub fn ensure_not_running(instance: &str, instance_dir: &Path, force: bool) -> Result<(), LauncherError> {
+    let Some(pid) = running_pid(instance, instance_dir) else {
+        return Ok(());
+    };
+    if !force {
+        return Err(LauncherError::InstanceRunning {
+            instance: instance.to_string(),
+            pid,
+        });
+    }
+
+    log::warn!("正在强制结束实例 {} 的游戏进程 (PID: {})", instance, pid);
+    let sys = process_system();
+    if let Some(process) = sys.process(Pid::from_u32(pid)) {
+        process.kill();
+    }
+
+    // 等待进程退出，以及监控线程释放实例锁
+    let start = Instant::now();
+    while running_pid(instance, instance_dir).is_some()
+        || instance_lock::current_operation(instance) == Some(InstanceOperation::Running)
+    {
+        if start.elapsed() > KILL_TIMEOUT {
+            return Err(LauncherError::Custom(format!(
+                "无法结束实例 '{}' 的游戏进程 (PID: {})",
+                instance, pid
+            )));
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+    Ok(())
+}
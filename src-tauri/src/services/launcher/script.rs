@@ -0,0 +1,133 @@
+//! 启动脚本导出
+//!
+//! 将启动流程生成的命令（含包装程序、环境变量和工作目录）渲染为
+//! Windows 批处理、POSIX shell 脚本或单行命令，便于脱离启动器运行或附在问题反馈中。
+
+use crate::errors::LauncherError;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptFormat {
+    /// Windows 批处理
+    Bat,
+    /// POSIX shell 脚本
+    Sh,
+    /// 单行命令（供复制到剪贴板）
+    Command,
+}
+
+impl ScriptFormat {
+    /// 脚本文件扩展名，单行命令不写入文件
+    pub fn file_extension(self) -> Option<&'static str> {
+        match self {
+            Self::Bat => Some("bat"),
+            Self::Sh => Some("sh"),
+            Self::Command => None,
+        }
+    }
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedLaunchScript {
+    pub content: String,
+    /// 脚本文件路径（单行命令为空）
+    pub path: Option<String>,
+}
+
+/// POSIX shell 单引号转义
+fn quote_sh(s: &str) -> String {
+    if !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@".contains(c))
+    {
+        return s.to_string();
+    }
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// cmd 参数转义（批处理文件中 % 需要写成 %%）
+fn quote_cmd(s: &str, in_batch: bool) -> String {
+    let s = if in_batch { s.replace('%', "%%") } else { s.to_string() };
+    if !s.is_empty() && !s.chars().any(|c| c.is_whitespace() || "&|<>^()\"".contains(c)) {
+        return s;
+    }
+    format!("\"{}\"", s.replace('"', "\\\""))
+}
+
+fn command_parts(command: &Command) -> Vec<String> {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|a| a.to_string_lossy().to_string())
+        .collect()
+}
+
+fn command_envs(command: &Command) -> Vec<(String, String)> {
+    command
+        .get_envs()
+        .filter_map(|(k, v)| Some((k, v?)))
+        .map(|(k, v): (&OsStr, &OsStr)| (k.to_string_lossy().to_string(), v.to_string_lossy().to_string()))
+        .collect()
+}
+
+/// 渲染启动命令
+pub fn render(command: &Command, working_dir: &Path, format: ScriptFormat) -> String {
+    let parts = command_parts(command);
+    let envs = command_envs(command);
+    let dir = working_dir.to_string_lossy();
+
+    match format {
+        ScriptFormat::Bat => {
+            let mut out = String::from("@echo off\r\nchcp 65001 >nul\r\n");
+            out.push_str(&format!("cd /d {}\r\n", quote_cmd(&dir, true)));
+            for (k, v) in &envs {
+                out.push_str(&format!("set \"{}={}\"\r\n", k, v.replace('%', "%%")));
+            }
+            let line: Vec<String> = parts.iter().map(|p| quote_cmd(p, true)).collect();
+            out.push_str(&line.join(" "));
+            out.push_str("\r\n");
+            out
+        }
+        ScriptFormat::Sh => {
+            let mut out = String::from("#!/bin/sh\n");
+            out.push_str(&format!("cd {} || exit 1\n", quote_sh(&dir)));
+            for (k, v) in &envs {
+                out.push_str(&format!("export {}={}\n", k, quote_sh(v)));
+            }
+            let line: Vec<String> = parts.iter().map(|p| quote_sh(p)).collect();
+            out.push_str("exec ");
+            out.push_str(&line.join(" "));
+            out.push('\n');
+            out
+        }
+        ScriptFormat::Command => {
+            let line: Vec<String> = if cfg!(windows) {
+                parts.iter().map(|p| quote_cmd(p, false)).collect()
+            } else {
+                envs.iter()
+                    .map(|(k, v)| format!("{}={}", k, quote_sh(v)))
+                    .chain(parts.iter().map(|p| quote_sh(p)))
+                    .collect()
+            };
+            line.join(" ")
+        }
+    }
+}
+
+/// 写入脚本文件（Unix 上设置可执行权限）
+pub fn write_script(path: &Path, content: &str) -> Result<(), LauncherError> {
+    fs::write(path, content)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,209 @@
+//! 启动会话目录
+//!
+//! 每次启动在 `<版本目录>/ar1s_sessions/<时间>` 下建立一个会话目录，集中保存本次的
+//! 启动命令、运行期间的启动器日志、游戏完整输出和崩溃报告，排查问题时只需查看一个文件夹。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
+
+/// 会话目录名
+const SESSIONS_DIR: &str = "ar1s_sessions";
+/// 会话信息文件
+const SESSION_FILE: &str = "session.json";
+/// 每个实例保留的会话数量
+const MAX_SESSIONS: usize = 20;
+
+/// 正在记录启动器日志的会话（会话目录 -> launcher.log）
+static ACTIVE: LazyLock<Mutex<HashMap<PathBuf, File>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 会话信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionInfo {
+    pub id: String,
+    pub instance: String,
+    pub path: String,
+    pub pid: u32,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub exit_code: Option<i32>,
+    pub crashed: bool,
+    /// 会话目录中的文件
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
+/// 进行中的会话
+pub(super) struct Session {
+    dir: PathBuf,
+    info: SessionInfo,
+}
+
+fn sessions_dir(version_dir: &Path) -> PathBuf {
+    version_dir.join(SESSIONS_DIR)
+}
+
+/// 删除超出保留数量的旧会话
+fn prune(root: &Path) {
+    let Ok(entries) = fs::read_dir(root) else {
+        return;
+    };
+    let mut dirs: Vec<PathBuf> = entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect();
+    if dirs.len() < MAX_SESSIONS {
+        return;
+    }
+    // 目录名为时间，按名称排序即按时间排序
+    dirs.sort();
+    let excess = dirs.len() + 1 - MAX_SESSIONS;
+    for dir in dirs.into_iter().take(excess) {
+        if ACTIVE.lock().is_ok_and(|active| active.contains_key(&dir)) {
+            continue;
+        }
+        let _ = fs::remove_dir_all(dir);
+    }
+}
+
+/// 写入启动器日志（由日志系统对每条记录调用）
+pub fn capture_log(record: &log::Record) {
+    let Ok(mut active) = ACTIVE.lock() else {
+        return;
+    };
+    for file in active.values_mut() {
+        let _ = writeln!(file, "{}", record.args());
+    }
+}
+
+impl Session {
+    /// 开始新会话，写入启动命令；失败时返回 None，不影响启动
+    pub(super) fn start(version_dir: &Path, instance: &str, command: &str, pid: u32) -> Option<Self> {
+        let root = sessions_dir(version_dir);
+        prune(&root);
+        let id = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+        let mut dir = root.join(&id);
+        let mut n = 2;
+        while dir.exists() {
+            dir = root.join(format!("{}-{}", id, n));
+            n += 1;
+        }
+        let created = fs::create_dir_all(&dir)
+            .and_then(|_| fs::write(dir.join("command.txt"), command))
+            .and_then(|_| OpenOptions::new().create(true).append(true).open(dir.join("launcher.log")));
+        let log_file = match created {
+            Ok(file) => file,
+            Err(e) => {
+                log::warn!("创建启动会话目录失败 {}: {}", dir.display(), e);
+                return None;
+            }
+        };
+
+        let session = Self {
+            info: SessionInfo {
+                id: dir.file_name()?.to_string_lossy().to_string(),
+                instance: instance.to_string(),
+                path: dir.to_string_lossy().to_string(),
+                pid,
+                started_at: chrono::Local::now().to_rfc3339(),
+                ended_at: None,
+                exit_code: None,
+                crashed: false,
+                files: Vec::new(),
+            },
+            dir,
+        };
+        session.save();
+        if let Ok(mut active) = ACTIVE.lock() {
+            active.insert(session.dir.clone(), log_file);
+        }
+        Some(session)
+    }
+
+    fn save(&self) {
+        let result = serde_json::to_string_pretty(&self.info)
+            .map_err(std::io::Error::other)
+            .and_then(|content| fs::write(self.dir.join(SESSION_FILE), content));
+        if let Err(e) = result {
+            log::warn!("保存会话信息失败: {}", e);
+        }
+    }
+
+    /// 保存游戏的完整输出（不截断）
+    pub(super) fn write_output(&self, output: &std::process::Output) {
+        for (name, bytes) in [("stdout.log", &output.stdout), ("stderr.log", &output.stderr)] {
+            if !bytes.is_empty() {
+                let _ = fs::write(self.dir.join(name), bytes);
+            }
+        }
+    }
+
+    /// 结束会话：复制本次启动后产生的崩溃报告，停止记录启动器日志
+    pub(super) fn finish(mut self, working_dir: &Path, launched_at: SystemTime, exit_code: Option<i32>, crashed: bool) {
+        if let Ok(entries) = fs::read_dir(working_dir.join("crash-reports")) {
+            let crash_dir = self.dir.join("crash-reports");
+            for entry in entries.flatten() {
+                let fresh = entry
+                    .metadata()
+                    .and_then(|m| m.modified())
+                    .is_ok_and(|t| t >= launched_at);
+                if fresh && fs::create_dir_all(&crash_dir).is_ok() {
+                    let _ = fs::copy(entry.path(), crash_dir.join(entry.file_name()));
+                }
+            }
+        }
+        if let Ok(mut active) = ACTIVE.lock() {
+            active.remove(&self.dir);
+        }
+
+        self.info.ended_at = Some(chrono::Local::now().to_rfc3339());
+        self.info.exit_code = exit_code;
+        self.info.crashed = crashed;
+        self.info.files = session_files(&self.dir);
+        self.save();
+    }
+}
+
+/// 会话目录中的文件（相对路径）
+fn session_files(dir: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if let Ok(relative) = path.strip_prefix(dir) {
+                files.push(relative.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// 列出实例的启动会话（最新的在前）
+pub fn list_sessions(version_dir: &Path) -> Vec<SessionInfo> {
+    let Ok(entries) = fs::read_dir(sessions_dir(version_dir)) else {
+        return Vec::new();
+    };
+    let mut sessions: Vec<SessionInfo> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let content = fs::read_to_string(entry.path().join(SESSION_FILE)).ok()?;
+            let mut info: SessionInfo = serde_json::from_str(&content).ok()?;
+            // 进行中的会话文件列表尚未写入
+            if info.ended_at.is_none() {
+                info.files = session_files(&entry.path());
+            }
+            Some(info)
+        })
+        .collect();
+    sessions.sort_by(|a, b| b.id.cmp(&a.id));
+    sessions
+}
@@ -0,0 +1,244 @@
+//! 故障排查启动选项（安全模式）
+//!
+//! Fabric / Forge 提供了一些系统属性用于排查模组问题；另外可以临时把非核心模组移出
+//! mods 目录（退出后恢复），快速判断问题是否由模组引起。实例连续崩溃时，
+//! 崩溃循环保护会停止自动重启，并根据加载器推荐可以尝试的选项。
+
+use crate::errors::LauncherError;
+use crate::models::TroubleshootingToggle;
+use crate::utils::mod_metadata;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// 禁用模组时移入的目录（与 mods 同级，加载器不会扫描）
+const DISABLED_MODS_DIR: &str = "mods.safe-mode";
+
+/// 禁用模组时保留的核心模组（前置库）
+const CORE_MOD_IDS: [&str; 6] = [
+    "fabric-api",
+    "fabric",
+    "fabric-language-kotlin",
+    "quilted_fabric_api",
+    "qsl",
+    "kotlinforforge",
+];
+
+/// 连续崩溃达到该次数视为崩溃循环
+const CRASH_LOOP_THRESHOLD: u32 = 3;
+
+/// 运行超过该时间后退出不计入连续崩溃
+const CRASH_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+/// 各实例的连续崩溃次数和上次崩溃时间
+static CRASHES: LazyLock<Mutex<HashMap<String, (u32, Instant)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 故障排查选项说明
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TroubleshootingToggleInfo {
+    pub toggle: TroubleshootingToggle,
+    pub title: &'static str,
+    pub description: &'static str,
+}
+
+/// 崩溃循环时给出的排查建议
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TroubleshootingSuggestion {
+    pub consecutive_crashes: u32,
+    pub toggles: Vec<TroubleshootingToggle>,
+}
+
+fn is_fabric_like(loader_type: &str) -> bool {
+    matches!(loader_type.to_lowercase().as_str(), "fabric" | "quilt")
+}
+
+fn is_forge_like(loader_type: &str) -> bool {
+    matches!(loader_type.to_lowercase().as_str(), "forge" | "neoforge")
+}
+
+fn has_mods(loader_type: &str) -> bool {
+    is_fabric_like(loader_type) || is_forge_like(loader_type)
+}
+
+fn describe(toggle: TroubleshootingToggle) -> TroubleshootingToggleInfo {
+    let (title, description) = match toggle {
+        TroubleshootingToggle::DisableMods => (
+            "禁用非核心模组",
+            "临时移走除前置库以外的模组，游戏退出后自动恢复",
+        ),
+        TroubleshootingToggle::DisableModShuffle => (
+            "固定模组加载顺序",
+            "关闭 Fabric 开发环境下的模组顺序随机化，便于复现加载顺序相关的问题",
+        ),
+        TroubleshootingToggle::MixinDebug => (
+            "Mixin 调试",
+            "输出 Mixin 详细日志，注入失败时导出目标类到 .mixin.out",
+        ),
+        TroubleshootingToggle::DisableEarlyWindow => (
+            "关闭早期加载窗口",
+            "不显示 Forge / NeoForge 的早期加载进度窗口，可绕过部分显卡驱动问题",
+        ),
+    };
+    TroubleshootingToggleInfo {
+        toggle,
+        title,
+        description,
+    }
+}
+
+/// 指定加载器可用的故障排查选项
+pub fn available_toggles(loader_type: &str) -> Vec<TroubleshootingToggleInfo> {
+    let mut toggles = Vec::new();
+    if has_mods(loader_type) {
+        toggles.push(TroubleshootingToggle::DisableMods);
+        toggles.push(TroubleshootingToggle::MixinDebug);
+    }
+    if is_fabric_like(loader_type) {
+        toggles.push(TroubleshootingToggle::DisableModShuffle);
+    }
+    if is_forge_like(loader_type) {
+        toggles.push(TroubleshootingToggle::DisableEarlyWindow);
+    }
+    toggles.into_iter().map(describe).collect()
+}
+
+/// 故障排查选项对应的 JVM 参数
+pub(super) fn jvm_args(toggles: &[TroubleshootingToggle], loader_type: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    for toggle in toggles {
+        match toggle {
+            TroubleshootingToggle::DisableModShuffle if is_fabric_like(loader_type) => {
+                args.push("-Dfabric.debug.disableModShuffle=true".to_string());
+            }
+            TroubleshootingToggle::MixinDebug if has_mods(loader_type) => {
+                args.extend([
+                    "-Dmixin.debug.verbose=true".to_string(),
+                    "-Dmixin.debug.export=true".to_string(),
+                    "-Dmixin.dumpTargetOnFailure=true".to_string(),
+                ]);
+            }
+            TroubleshootingToggle::DisableEarlyWindow if is_forge_like(loader_type) => {
+                args.push("-Dfml.earlyprogresswindow=false".to_string());
+            }
+            _ => {}
+        }
+    }
+    args
+}
+
+fn disabled_mods_dir(mods_dir: &Path) -> PathBuf {
+    mods_dir.with_file_name(DISABLED_MODS_DIR)
+}
+
+/// 是否为需要保留的核心模组
+fn is_core_mod(path: &Path) -> bool {
+    match mod_metadata::read_mod_metadata(path) {
+        Ok(Some(meta)) => {
+            CORE_MOD_IDS.contains(&meta.mod_id.as_str())
+                || meta.provides.iter().any(|id| CORE_MOD_IDS.contains(&id.as_str()))
+        }
+        _ => false,
+    }
+}
+
+/// 把上次安全模式移走的模组放回 mods 目录，返回恢复的数量
+///
+/// 游戏退出后调用；启动器在游戏运行期间退出时，下次启动该实例前也会调用
+pub(super) fn restore_mods(mods_dir: &Path) -> usize {
+    let disabled_dir = disabled_mods_dir(mods_dir);
+    let Ok(entries) = fs::read_dir(&disabled_dir) else {
+        return 0;
+    };
+    let mut restored = 0;
+    for entry in entries.flatten() {
+        let target = mods_dir.join(entry.file_name());
+        if target.exists() {
+            log::warn!("恢复模组时目标已存在，保留在 {}: {}", DISABLED_MODS_DIR, target.display());
+            continue;
+        }
+        match fs::rename(entry.path(), &target) {
+            Ok(()) => restored += 1,
+            Err(e) => log::warn!("恢复模组 {} 失败: {}", target.display(), e),
+        }
+    }
+    let _ = fs::remove_dir(&disabled_dir);
+    if restored > 0 {
+        log::info!("已恢复 {} 个安全模式下禁用的模组", restored);
+    }
+    restored
+}
+
+/// 移走非核心模组，返回移走的数量
+pub(super) fn disable_mods(mods_dir: &Path) -> Result<usize, LauncherError> {
+    let Ok(entries) = fs::read_dir(mods_dir) else {
+        return Ok(0);
+    };
+    let disabled_dir = disabled_mods_dir(mods_dir);
+    fs::create_dir_all(&disabled_dir)?;
+
+    let mut disabled = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() || is_core_mod(&path) {
+            continue;
+        }
+        if let Err(e) = fs::rename(&path, disabled_dir.join(entry.file_name())) {
+            // 部分移动失败时先恢复，避免实例处于不完整状态
+            restore_mods(mods_dir);
+            return Err(LauncherError::Custom(format!(
+                "无法禁用模组 {}: {}",
+                path.display(),
+                e
+            )));
+        }
+        disabled += 1;
+    }
+    log::info!("安全模式：已临时禁用 {} 个模组", disabled);
+    Ok(disabled)
+}
+
+/// 记录一次游戏退出，返回当前连续崩溃次数（正常退出或运行较久后退出时清零）
+pub(super) fn record_exit(instance: &str, crashed: bool, runtime: Duration) -> u32 {
+    let Ok(mut crashes) = CRASHES.lock() else {
+        return 0;
+    };
+    if !crashed || runtime > CRASH_WINDOW {
+        crashes.remove(instance);
+        return 0;
+    }
+    let now = Instant::now();
+    let entry = crashes.entry(instance.to_string()).or_insert((0, now));
+    // 距上次崩溃太久的不算连续
+    if now.duration_since(entry.1) > CRASH_WINDOW * 2 {
+        entry.0 = 0;
+    }
+    entry.0 += 1;
+    entry.1 = now;
+    entry.0
+}
+
+/// 崩溃循环保护：连续崩溃达到阈值时返回推荐的故障排查选项
+pub(super) fn crash_loop_suggestion(
+    consecutive_crashes: u32,
+    loader_type: &str,
+    active: &[TroubleshootingToggle],
+) -> Option<TroubleshootingSuggestion> {
+    if consecutive_crashes < CRASH_LOOP_THRESHOLD {
+        return None;
+    }
+    let toggles: Vec<TroubleshootingToggle> = available_toggles(loader_type)
+        .into_iter()
+        .map(|info| info.toggle)
+        .filter(|t| !active.contains(t))
+        .collect();
+    Some(TroubleshootingSuggestion {
+        consecutive_crashes,
+        toggles,
+    })
+}
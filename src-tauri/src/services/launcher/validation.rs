@@ -0,0 +1,207 @@
+//! 版本 JSON 结构校验
+//!
+//! 手动修改或损坏的版本 JSON 在启动时往往只会得到含糊的 serde 错误，
+//! 这里逐项检查关键字段并给出具体位置的提示。
+
+use crate::errors::LauncherError;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// 单条校验问题
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    /// 问题所在位置，如 `libraries[3].name`
+    pub location: String,
+    pub message: String,
+    /// 是否会导致无法启动
+    pub fatal: bool,
+}
+
+impl ValidationIssue {
+    fn error(location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            location: location.into(),
+            message: message.into(),
+            fatal: true,
+        }
+    }
+
+    fn warning(location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            location: location.into(),
+            message: message.into(),
+            fatal: false,
+        }
+    }
+}
+
+/// 校验指定版本：先检查 JSON 语法（含继承链），再检查合并后的结构
+pub fn validate_version(game_dir: &Path, version: &str) -> Result<Vec<ValidationIssue>, LauncherError> {
+    let mut issues = Vec::new();
+    let mut current = Some(version.to_string());
+
+    while let Some(id) = current.take() {
+        let path = game_dir.join("versions").join(&id).join(format!("{}.json", id));
+        if !path.exists() {
+            issues.push(ValidationIssue::error(
+                format!("{}.json", id),
+                if id == version {
+                    "版本 JSON 文件不存在".to_string()
+                } else {
+                    format!("继承的父版本 {} 未安装", id)
+                },
+            ));
+            return Ok(issues);
+        }
+        let content = fs::read_to_string(&path)?;
+        match serde_json::from_str::<Value>(&content) {
+            Ok(json) => {
+                current = json
+                    .get("inheritsFrom")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string);
+            }
+            Err(e) => {
+                issues.push(ValidationIssue::error(
+                    format!("{}.json:{}:{}", id, e.line(), e.column()),
+                    format!("JSON 语法错误: {}", e),
+                ));
+                return Ok(issues);
+            }
+        }
+    }
+
+    let merged = super::version_json::load_and_merge_version_json(game_dir, version)?;
+    issues.extend(validate_version_json(&merged));
+    Ok(issues)
+}
+
+/// 校验合并后的版本 JSON 结构
+pub fn validate_version_json(json: &Value) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    if !json.is_object() {
+        issues.push(ValidationIssue::error("$", "版本 JSON 顶层必须是对象"));
+        return issues;
+    }
+
+    match json.get("mainClass") {
+        Some(Value::String(s)) if !s.trim().is_empty() => {}
+        Some(Value::String(_)) => issues.push(ValidationIssue::error("mainClass", "mainClass 为空")),
+        Some(_) => issues.push(ValidationIssue::error("mainClass", "mainClass 必须是字符串")),
+        None => issues.push(ValidationIssue::error("mainClass", "缺少 mainClass")),
+    }
+
+    check_libraries(json, &mut issues);
+    check_asset_index(json, &mut issues);
+    check_arguments(json, &mut issues);
+
+    issues
+}
+
+fn check_libraries(json: &Value, issues: &mut Vec<ValidationIssue>) {
+    let Some(libs) = json.get("libraries") else {
+        issues.push(ValidationIssue::error("libraries", "缺少 libraries"));
+        return;
+    };
+    let Some(libs) = libs.as_array() else {
+        issues.push(ValidationIssue::error("libraries", "libraries 必须是数组"));
+        return;
+    };
+
+    for (i, lib) in libs.iter().enumerate() {
+        let loc = format!("libraries[{}]", i);
+        if !lib.is_object() {
+            issues.push(ValidationIssue::error(loc, "库条目必须是对象"));
+            continue;
+        }
+        match lib.get("name").and_then(|n| n.as_str()) {
+            Some(name) if name.split(':').count() >= 3 => {}
+            Some(name) => issues.push(ValidationIssue::error(
+                format!("{}.name", loc),
+                format!("库名称 \"{}\" 不是有效的 Maven 坐标 (group:artifact:version)", name),
+            )),
+            None => issues.push(ValidationIssue::error(format!("{}.name", loc), "库条目缺少 name")),
+        }
+        if let Some(artifact) = lib.get("downloads").and_then(|d| d.get("artifact")) {
+            if artifact.get("path").is_some_and(|p| !p.is_string()) {
+                issues.push(ValidationIssue::error(
+                    format!("{}.downloads.artifact.path", loc),
+                    "path 必须是字符串",
+                ));
+            }
+            if artifact.get("url").is_some_and(|u| !u.is_string()) {
+                issues.push(ValidationIssue::warning(
+                    format!("{}.downloads.artifact.url", loc),
+                    "url 不是字符串，将无法下载该库",
+                ));
+            }
+        }
+        if lib.get("rules").is_some_and(|r| !r.is_array()) {
+            issues.push(ValidationIssue::error(format!("{}.rules", loc), "rules 必须是数组"));
+        }
+    }
+}
+
+fn check_asset_index(json: &Value, issues: &mut Vec<ValidationIssue>) {
+    let Some(index) = json.get("assetIndex") else {
+        issues.push(ValidationIssue::warning(
+            "assetIndex",
+            "缺少 assetIndex，游戏资源（声音、语言文件）可能无法加载",
+        ));
+        return;
+    };
+    if !index.is_object() {
+        issues.push(ValidationIssue::error("assetIndex", "assetIndex 必须是对象"));
+        return;
+    }
+    if !index.get("id").is_some_and(|v| v.is_string()) {
+        issues.push(ValidationIssue::error("assetIndex.id", "assetIndex 缺少 id"));
+    }
+    if !index.get("url").is_some_and(|v| v.is_string()) {
+        issues.push(ValidationIssue::warning("assetIndex.url", "assetIndex 缺少 url，无法补全资源"));
+    }
+}
+
+fn check_arguments(json: &Value, issues: &mut Vec<ValidationIssue>) {
+    let legacy = json.get("minecraftArguments");
+    if legacy.is_some_and(|v| !v.is_string()) {
+        issues.push(ValidationIssue::error("minecraftArguments", "minecraftArguments 必须是字符串"));
+    }
+
+    let Some(arguments) = json.get("arguments") else {
+        if legacy.is_none() {
+            issues.push(ValidationIssue::error(
+                "arguments",
+                "缺少 arguments 和 minecraftArguments，无法构建游戏参数",
+            ));
+        }
+        return;
+    };
+
+    for key in ["game", "jvm"] {
+        let Some(list) = arguments.get(key) else {
+            continue;
+        };
+        let Some(list) = list.as_array() else {
+            issues.push(ValidationIssue::error(format!("arguments.{}", key), "必须是数组"));
+            continue;
+        };
+        for (i, arg) in list.iter().enumerate() {
+            let valid = match arg {
+                Value::String(_) => true,
+                Value::Object(obj) => matches!(obj.get("value"), Some(Value::String(_)) | Some(Value::Array(_))),
+                _ => false,
+            };
+            if !valid {
+                issues.push(ValidationIssue::error(
+                    format!("arguments.{}[{}]", key, i),
+                    "参数必须是字符串或包含 value 的规则对象",
+                ));
+            }
+        }
+    }
+}
@@ -1,15 +1,75 @@
 //! 版本 JSON 加载和合并逻辑
+//!
+//! 合并结果按继承链上各文件的修改时间和大小缓存在内存中，启动前的校验和实际启动
+//! 共用同一次合并；链上任一文件变化（包括缺失的父版本被安装）时重新合并。
 
 use crate::errors::LauncherError;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
+
+/// 继承链上一个文件的状态（不存在时为 None）
+type FileStamp = (PathBuf, Option<(SystemTime, u64)>);
+
+struct CachedMerge {
+    chain: Vec<FileStamp>,
+    json: serde_json::Value,
+}
+
+/// 合并结果缓存（(游戏目录, 版本 ID) -> 合并结果）
+static MERGED_CACHE: LazyLock<Mutex<HashMap<(PathBuf, String), CachedMerge>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn stamp(path: PathBuf) -> FileStamp {
+    let state = fs::metadata(&path)
+        .ok()
+        .and_then(|m| Some((m.modified().ok()?, m.len())));
+    (path, state)
+}
+
+fn chain_unchanged(chain: &[FileStamp]) -> bool {
+    chain.iter().all(|(path, state)| stamp(path.clone()).1 == *state)
+}
+
+/// 清空合并结果缓存（删除、重命名实例后调用）
+pub fn clear_merged_cache() {
+    if let Ok(mut cache) = MERGED_CACHE.lock() {
+        cache.clear();
+    }
+}
 
 /// 加载并合并版本 JSON 文件，处理 `inheritsFrom` 继承关系
 pub fn load_and_merge_version_json(
     game_dir: &Path,
     version: &str,
 ) -> Result<serde_json::Value, LauncherError> {
+    let key = (game_dir.to_path_buf(), version.to_string());
+    if let Ok(cache) = MERGED_CACHE.lock() {
+        if let Some(cached) = cache.get(&key).filter(|c| chain_unchanged(&c.chain)) {
+            return Ok(cached.json.clone());
+        }
+    }
+
+    let (json, chain) = merge_version_chain(game_dir, version)?;
+    if let Ok(mut cache) = MERGED_CACHE.lock() {
+        cache.insert(
+            key,
+            CachedMerge {
+                chain,
+                json: json.clone(),
+            },
+        );
+    }
+    Ok(json)
+}
+
+/// 读取并合并整条继承链，同时返回链上各文件的状态
+fn merge_version_chain(
+    game_dir: &Path,
+    version: &str,
+) -> Result<(serde_json::Value, Vec<FileStamp>), LauncherError> {
     let version_dir = game_dir.join("versions").join(version);
     let version_json_path = version_dir.join(format!("{}.json", version));
 
@@ -20,6 +80,7 @@ pub fn load_and_merge_version_json(
         )));
     }
 
+    let mut chain = vec![stamp(version_json_path.clone())];
     let version_json_str = fs::read_to_string(&version_json_path)?;
     let mut version_json: serde_json::Value = serde_json::from_str(&version_json_str)?;
 
@@ -35,6 +96,8 @@ pub fn load_and_merge_version_json(
             let parent_json_path = versions_base
                 .join(&parent_id)
                 .join(format!("{}.json", &parent_id));
+            // 缺失的父版本也记录下来，安装后缓存失效
+            chain.push(stamp(parent_json_path.clone()));
             if !parent_json_path.exists() {
                 break;
             }
@@ -53,7 +116,7 @@ pub fn load_and_merge_version_json(
             }
         }
     }
-    Ok(version_json)
+    Ok((version_json, chain))
 }
 
 /// 合并 libraries 数组（去重）
@@ -1,6 +1,7 @@
 //! Fabric 加载器安装
 
 use crate::errors::LauncherError;
+use crate::services::meta_cache;
 use log::info;
 use reqwest::Client;
 use serde_json::Value;
@@ -69,6 +70,24 @@ pub async fn install_fabric(
 
 /// 获取 Fabric 加载器版本列表
 pub async fn get_fabric_versions(mc_version: &str) -> Result<Vec<FabricLoaderVersion>, LauncherError> {
+    meta_cache::get_or_fetch(
+        &format!("fabric_versions:{}", mc_version),
+        meta_cache::DEFAULT_TTL,
+        || fetch_fabric_versions(mc_version),
+    )
+    .await
+}
+
+/// 重新获取 Fabric 版本列表并更新缓存
+pub async fn refresh_fabric_versions(mc_version: &str) -> Result<(), LauncherError> {
+    meta_cache::refresh(&format!("fabric_versions:{}", mc_version), || {
+        fetch_fabric_versions(mc_version)
+    })
+    .await
+}
+
+/// 从 Fabric Meta 获取指定 MC 版本的加载器列表
+async fn fetch_fabric_versions(mc_version: &str) -> Result<Vec<FabricLoaderVersion>, LauncherError> {
     let client = Client::new();
     let url = format!("{}/versions/loader/{}", FABRIC_META_URL, mc_version);
 
@@ -144,7 +163,7 @@ struct FabricGameVersion {
 }
 
 /// Fabric 加载器版本信息
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct FabricLoaderVersion {
     pub version: String,
     pub stable: bool,
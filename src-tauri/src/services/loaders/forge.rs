@@ -4,6 +4,11 @@
 
 use crate::errors::LauncherError;
 use crate::services::config;
+use crate::services::meta_cache;
+use crate::services::paths;
+use crate::services::tasks;
+use crate::utils::encoding::decode_process_output;
+use crate::utils::maven;
 use log::{debug, error, info, warn};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -27,6 +32,9 @@ const MAVEN_FORGE: &str = "https://maven.minecraftforge.net";
 const MAVEN_CENTRAL: &str = "https://repo1.maven.org/maven2";
 const MAVEN_MINECRAFT: &str = "https://libraries.minecraft.net";
 
+/// 实例目录下记录 Forge processor 产出文件的清单
+const FORGE_ARTIFACTS_FILE: &str = "ar1s_forge_artifacts.json";
+
 /// Forge 版本信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ForgeVersion {
@@ -34,6 +42,44 @@ pub struct ForgeVersion {
     pub mcversion: String,
 }
 
+/// install_profile `data` 中声明的 processor 产出文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgeArtifact {
+    /// data 键名，如 `PATCHED`、`MC_SRG`
+    pub key: String,
+    /// 相对 libraries 目录的路径
+    pub path: String,
+    /// processor `outputs` 中声明的 SHA1（若有）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha1: Option<String>,
+}
+
+/// 单个产出文件的校验结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgeArtifactStatus {
+    pub key: String,
+    pub path: String,
+    pub size: Option<u64>,
+}
+
+/// Forge 安装校验报告
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgeInstallReport {
+    pub instance_name: String,
+    pub artifacts: Vec<ForgeArtifactStatus>,
+    pub missing: Vec<String>,
+    pub corrupted: Vec<String>,
+}
+
+impl ForgeInstallReport {
+    pub fn is_intact(&self) -> bool {
+        self.missing.is_empty() && self.corrupted.is_empty()
+    }
+}
+
 /// 安装 Forge 加载器（统一入口）
 pub async fn install_forge(
     mc_version: &str,
@@ -94,6 +140,15 @@ pub async fn install_forge(
         }
     }
 
+    // 记录 processor 产出文件，安装器删除后仍可校验
+    let artifacts = if is_new_forge(mc_version) {
+        read_install_profile(&installer_path)
+            .map(|profile| collect_forge_artifacts(&profile, mc_version, forge_version))
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
     // 清理安装器
     if installer_path.exists() {
         fs::remove_file(&installer_path).ok();
@@ -126,12 +181,174 @@ pub async fn install_forge(
         }
     }
 
+    if !artifacts.is_empty() {
+        fs::create_dir_all(&instance_dir)?;
+        fs::write(
+            instance_dir.join(FORGE_ARTIFACTS_FILE),
+            serde_json::to_string_pretty(&artifacts)?,
+        )?;
+
+        // 只检查存在性和大小，补丁后的客户端 jar 可达数百 MB，完整哈希留给 verify_forge_install
+        let report = check_artifacts(game_dir, instance_name, &artifacts, false)?;
+        if !report.is_intact() {
+            return Err(LauncherError::Custom(format!(
+                "Forge processor 产出文件不完整: 缺失 [{}], 损坏 [{}]",
+                report.missing.join(", "),
+                report.corrupted.join(", ")
+            )));
+        }
+        info!("Forge: {} 个 processor 产出文件校验通过", report.artifacts.len());
+    }
+
     info!("Forge: 安装完成");
     Ok(())
 }
 
+/// 按安装时记录的清单校验实例的 Forge processor 产出文件（含 SHA1）
+pub async fn verify_forge_install(instance_name: String) -> Result<ForgeInstallReport, LauncherError> {
+    let game_dir = paths::instance_game_dir(&config::load_config()?, &instance_name);
+    let manifest = game_dir
+        .join("versions")
+        .join(&instance_name)
+        .join(FORGE_ARTIFACTS_FILE);
+    let content = fs::read_to_string(&manifest).map_err(|_| {
+        LauncherError::Custom(format!("实例 {} 没有 Forge 产出文件记录", instance_name))
+    })?;
+    let artifacts: Vec<ForgeArtifact> = serde_json::from_str(&content)?;
+    tokio::task::spawn_blocking(move || check_artifacts(&game_dir, &instance_name, &artifacts, true))
+        .await?
+}
+
+fn check_artifacts(
+    game_dir: &Path,
+    instance_name: &str,
+    artifacts: &[ForgeArtifact],
+    full: bool,
+) -> Result<ForgeInstallReport, LauncherError> {
+    let libraries_dir = game_dir.join("libraries");
+    let mut report = ForgeInstallReport {
+        instance_name: instance_name.to_string(),
+        ..Default::default()
+    };
+    for artifact in artifacts {
+        let full_path = libraries_dir.join(&artifact.path);
+        let size = fs::metadata(&full_path).ok().map(|m| m.len());
+        info!(
+            "Forge: 检查产出文件 {} -> {} ({})",
+            artifact.key,
+            artifact.path,
+            size.map(|s| format!("{} 字节", s)).unwrap_or_else(|| "缺失".to_string())
+        );
+        match (size, artifact.sha1.as_deref()) {
+            (None, _) => report.missing.push(artifact.path.clone()),
+            (Some(0), _) => report.corrupted.push(artifact.path.clone()),
+            (Some(_), Some(sha1)) if full => {
+                if !crate::utils::file_utils::verify_file(&full_path, sha1, 0)? {
+                    report.corrupted.push(artifact.path.clone());
+                }
+            }
+            _ => {}
+        }
+        report.artifacts.push(ForgeArtifactStatus {
+            key: artifact.key.clone(),
+            path: artifact.path.clone(),
+            size,
+        });
+    }
+    Ok(report)
+}
+
+fn read_install_profile(installer_path: &Path) -> Result<Value, LauncherError> {
+    let file = fs::File::open(installer_path)?;
+    let mut archive = ZipArchive::new(file)?;
+    let mut content = String::new();
+    archive
+        .by_name("install_profile.json")
+        .map_err(|_| LauncherError::Custom("未找到 install_profile.json".to_string()))?
+        .read_to_string(&mut content)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// 从 install_profile 的 `data` 中收集以 Maven 坐标声明的客户端产出文件，
+/// SHA1 取自各 processor 的 `outputs`
+fn collect_forge_artifacts(profile: &Value, mc_version: &str, forge_version: &str) -> Vec<ForgeArtifact> {
+    let Some(data) = profile.get("data").and_then(|d| d.as_object()) else {
+        return Vec::new();
+    };
+    let client_value = |key: &str| -> Option<String> {
+        let value = data.get(key)?;
+        value
+            .get("client")
+            .and_then(|v| v.as_str())
+            .or_else(|| value.as_str())
+            .map(|v| {
+                v.replace("{MINECRAFT_VERSION}", mc_version)
+                    .replace("{FORGE_VERSION}", forge_version)
+            })
+    };
+
+    // outputs: { "{MC_SLIM}": "{MC_SLIM_SHA}" } 或直接给出 SHA1 字面量
+    let mut hashes = std::collections::HashMap::new();
+    for processor in profile
+        .get("processors")
+        .and_then(|p| p.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let Some(outputs) = processor.get("outputs").and_then(|o| o.as_object()) else {
+            continue;
+        };
+        for (target, sha) in outputs {
+            let Some(key) = target.strip_prefix('{').and_then(|t| t.strip_suffix('}')) else {
+                continue;
+            };
+            let Some(sha) = sha.as_str() else { continue };
+            let sha = match sha.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(sha_key) => client_value(sha_key),
+                None => Some(sha.to_string()),
+            };
+            if let Some(sha) = sha {
+                hashes.insert(key.to_string(), sha.trim_matches('\'').to_string());
+            }
+        }
+    }
+
+    let mut artifacts: Vec<ForgeArtifact> = data
+        .keys()
+        .filter_map(|key| {
+            let value = client_value(key)?;
+            let descriptor = value.strip_prefix('[')?.strip_suffix(']')?;
+            Some(ForgeArtifact {
+                key: key.clone(),
+                path: maven::maven_to_path(descriptor)?,
+                sha1: hashes.get(key).cloned(),
+            })
+        })
+        .collect();
+    artifacts.sort_by(|a, b| a.key.cmp(&b.key));
+    artifacts
+}
+
 /// 获取 Forge 版本列表
 pub async fn get_forge_versions(mc_version: &str) -> Result<Vec<ForgeVersion>, LauncherError> {
+    meta_cache::get_or_fetch(
+        &format!("forge_versions:{}", mc_version),
+        meta_cache::DEFAULT_TTL,
+        || fetch_forge_versions(mc_version),
+    )
+    .await
+}
+
+/// 重新获取 Forge 版本列表并更新缓存
+pub async fn refresh_forge_versions(mc_version: &str) -> Result<(), LauncherError> {
+    meta_cache::refresh(&format!("forge_versions:{}", mc_version), || {
+        fetch_forge_versions(mc_version)
+    })
+    .await
+}
+
+/// 从 BMCLAPI 获取指定 MC 版本的 Forge 列表
+async fn fetch_forge_versions(mc_version: &str) -> Result<Vec<ForgeVersion>, LauncherError> {
     let client = Client::new();
     let url = format!("{}/forge/minecraft/{}", BMCL_API_BASE_URL, mc_version);
 
@@ -148,7 +365,7 @@ pub async fn get_forge_versions(mc_version: &str) -> Result<Vec<ForgeVersion>, L
     let mut versions: Vec<ForgeVersion> = response.json().await?;
 
     // 排序：最新版本在前
-    versions.sort_by(|a, b| compare_forge_versions(&b.version, &a.version));
+    versions.sort_by(|a, b| maven::compare_versions(&b.version, &a.version));
 
     Ok(versions)
 }
@@ -171,26 +388,6 @@ fn get_forge_version_id(mc_version: &str, forge_version: &str) -> String {
     format!("{}-forge-{}", mc_version, forge_version)
 }
 
-/// 比较 Forge 版本号
-fn compare_forge_versions(a: &str, b: &str) -> std::cmp::Ordering {
-    let parse = |s: &str| -> Vec<u32> {
-        s.split('.').filter_map(|p| p.parse().ok()).collect()
-    };
-
-    let a_parts = parse(a);
-    let b_parts = parse(b);
-
-    for i in 0..std::cmp::max(a_parts.len(), b_parts.len()) {
-        let a_num = a_parts.get(i).unwrap_or(&0);
-        let b_num = b_parts.get(i).unwrap_or(&0);
-        match a_num.cmp(b_num) {
-            std::cmp::Ordering::Equal => continue,
-            ord => return ord,
-        }
-    }
-    std::cmp::Ordering::Equal
-}
-
 /// 下载 Forge 安装器
 async fn download_forge_installer(
     forge_version: &ForgeVersion,
@@ -285,7 +482,7 @@ async fn run_official_installer(
         .output()
         .map_err(|e| LauncherError::Custom(format!("执行安装器失败: {}", e)))?;
 
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stderr = decode_process_output(&output.stderr);
 
     if stderr.contains("not a recognized option") || stderr.contains("UnrecognizedOptionException")
     {
@@ -303,7 +500,7 @@ async fn run_official_installer(
             .map_err(|e| LauncherError::Custom(format!("执行安装器失败: {}", e)))?;
 
         if !output2.status.success() {
-            let stderr2 = String::from_utf8_lossy(&output2.stderr);
+            let stderr2 = decode_process_output(&output2.stderr);
             if stderr2.contains("HeadlessException") {
                 return Err(LauncherError::Custom(
                     "安装器需要 GUI，切换到手动安装".to_string(),
@@ -457,24 +654,6 @@ async fn download_lzma_library(
 
 // ============ 手动安装逻辑 ============
 
-/// 从 Maven 坐标解析路径
-fn maven_to_path(name: &str, classifier: Option<&str>, extension: &str) -> Option<String> {
-    let parts: Vec<&str> = name.split(':').collect();
-    if parts.len() < 3 {
-        return None;
-    }
-
-    let (group, artifact, version) = (parts[0], parts[1], parts[2]);
-    let group_path = group.replace('.', "/");
-
-    let filename = match classifier {
-        Some(c) => format!("{}-{}-{}.{}", artifact, version, c, extension),
-        None => format!("{}-{}.{}", artifact, version, extension),
-    };
-
-    Some(format!("{}/{}/{}/{}", group_path, artifact, version, filename))
-}
-
 /// 从 install_profile 下载库
 async fn download_library_from_profile(
     library: &Value,
@@ -532,7 +711,7 @@ async fn download_library_from_profile(
     }
 
     // 回退到从 name 构建路径
-    if let Some(maven_path) = maven_to_path(name, None, "jar") {
+    if let Some(maven_path) = maven::maven_to_path(name) {
         let target_path = libraries_dir.join(&maven_path);
         if target_path.exists() {
             return Ok(());
@@ -835,6 +1014,7 @@ async fn run_forge_processors(
     info!("Forge: 执行 {} 个 processors", processors.len());
 
     for (idx, processor) in processors.iter().enumerate() {
+        tasks::check_cancelled()?;
         if let Some(sides) = processor.get("sides").and_then(|s| s.as_array()) {
             if !sides.iter().any(|s| s.as_str() == Some("client")) {
                 continue;
@@ -846,7 +1026,7 @@ async fn run_forge_processors(
             None => continue,
         };
 
-        let jar_path = match maven_to_path(jar_name, None, "jar") {
+        let jar_path = match maven::maven_to_path(jar_name) {
             Some(p) => libraries_dir.join(p),
             None => continue,
         };
@@ -860,7 +1040,7 @@ async fn run_forge_processors(
         if let Some(cp) = processor.get("classpath").and_then(|c| c.as_array()) {
             for lib in cp {
                 if let Some(lib_name) = lib.as_str() {
-                    if let Some(lib_path) = maven_to_path(lib_name, None, "jar") {
+                    if let Some(lib_path) = maven::maven_to_path(lib_name) {
                         let full_path = libraries_dir.join(&lib_path);
                         if full_path.exists() {
                             classpath.push(full_path.to_string_lossy().to_string());
@@ -911,7 +1091,7 @@ async fn run_forge_processors(
         let output = cmd.output()?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = decode_process_output(&output.stderr);
             error!("Forge: Processor {} 失败: {}", idx, stderr);
         }
     }
@@ -954,7 +1134,7 @@ fn resolve_processor_arg(
         }
     } else if arg.starts_with('[') && arg.ends_with(']') {
         let artifact = &arg[1..arg.len() - 1];
-        if let Some(path) = maven_to_path(artifact, None, "jar") {
+        if let Some(path) = maven::maven_to_path(artifact) {
             return libraries_dir.join(path).to_string_lossy().to_string();
         }
     }
@@ -970,7 +1150,7 @@ fn resolve_data_value(
 ) -> String {
     if value.starts_with('[') && value.ends_with(']') {
         let artifact = &value[1..value.len() - 1];
-        if let Some(path) = maven_to_path(artifact, None, "jar") {
+        if let Some(path) = maven::maven_to_path(artifact) {
             return libraries_dir.join(path).to_string_lossy().to_string();
         }
     }
@@ -80,6 +80,7 @@ pub async fn install_loader(
     instance_name: &str,
     game_dir: &Path,
 ) -> Result<(), LauncherError> {
+    crate::services::tasks::check_cancelled()?;
     match loader {
         LoaderType::Forge { mc_version, loader_version } => {
             forge::install_forge(mc_version, loader_version, instance_name, game_dir).await
@@ -1,6 +1,8 @@
 //! NeoForge 加载器安装
 
 use crate::errors::LauncherError;
+use crate::services::meta_cache;
+use crate::utils::maven;
 use log::{info, warn};
 use reqwest::Client;
 use serde_json::Value;
@@ -12,6 +14,8 @@ use std::path::Path;
 const NEOFORGE_MAVEN_URL: &str = "https://maven.neoforged.net/releases";
 /// BMCLAPI 镜像
 const BMCLAPI_NEOFORGE_URL: &str = "https://bmclapi2.bangbang93.com/neoforge";
+/// NeoForge 官方 Maven 的版本列表接口
+const NEOFORGE_MAVEN_API_URL: &str = "https://maven.neoforged.net/api/maven/versions/releases/net/neoforged";
 
 /// 安装 NeoForge 加载器
 pub async fn install_neoforge(
@@ -161,33 +165,88 @@ pub async fn install_neoforge(
     Ok(())
 }
 
-/// 获取 NeoForge 版本列表
+/// 获取 NeoForge 版本列表（最新版本在前）
 pub async fn get_neoforge_versions(mc_version: &str) -> Result<Vec<NeoForgeVersion>, LauncherError> {
+    meta_cache::get_or_fetch(
+        &format!("neoforge_versions:{}", mc_version),
+        meta_cache::DEFAULT_TTL,
+        || fetch_neoforge_versions(mc_version),
+    )
+    .await
+}
+
+/// 先尝试 BMCLAPI，失败时从 NeoForge 官方 Maven 的版本列表中筛选
+async fn fetch_neoforge_versions(mc_version: &str) -> Result<Vec<NeoForgeVersion>, LauncherError> {
     let client = Client::new();
-    
-    // 尝试 BMCLAPI
-    let bmclapi_url = format!("{}/list/{}", BMCLAPI_NEOFORGE_URL, mc_version);
-    
-    if let Ok(response) = client.get(&bmclapi_url).send().await {
-        if response.status().is_success() {
-            if let Ok(versions) = response.json::<Vec<BmclapiNeoForgeVersion>>().await {
-                return Ok(versions
-                    .into_iter()
-                    .map(|v| NeoForgeVersion {
-                        version: v.version,
-                        mc_version: v.mc_version,
-                    })
-                    .collect());
-            }
+    let mut versions = match fetch_bmclapi_versions(&client, mc_version).await {
+        Ok(versions) => versions,
+        Err(e) => {
+            warn!("NeoForge: BMCLAPI 获取版本列表失败，改用官方 Maven: {}", e);
+            fetch_maven_versions(&client, mc_version).await?
         }
+    };
+    // 排序：最新版本在前（beta 排在同号正式版之后）
+    versions.sort_by(|a, b| maven::compare_versions(&b.version, &a.version));
+    Ok(versions)
+}
+
+async fn fetch_bmclapi_versions(client: &Client, mc_version: &str) -> Result<Vec<NeoForgeVersion>, LauncherError> {
+    let url = format!("{}/list/{}", BMCLAPI_NEOFORGE_URL, mc_version);
+    let response = client.get(&url).send().await?;
+    if !response.status().is_success() {
+        return Err(LauncherError::Custom(format!("HTTP {}", response.status())));
     }
+    let versions: Vec<BmclapiNeoForgeVersion> = response.json().await?;
+    Ok(versions
+        .into_iter()
+        .map(|v| NeoForgeVersion {
+            version: v.version,
+            mc_version: v.mc_version,
+        })
+        .collect())
+}
+
+/// 官方 Maven 的版本列表不区分 MC 版本，按版本号前缀筛选：
+/// 1.20.1 使用旧的 `forge` 构件（`1.20.1-47.1.x`），之后的版本号为 MC 次版本号和修订号（1.20.4 -> `20.4.x`）
+async fn fetch_maven_versions(client: &Client, mc_version: &str) -> Result<Vec<NeoForgeVersion>, LauncherError> {
+    let (artifact, prefix) = if mc_version == "1.20.1" {
+        ("forge", format!("{}-", mc_version))
+    } else {
+        let mut parts = mc_version.split('.').skip(1);
+        let minor = parts
+            .next()
+            .ok_or_else(|| LauncherError::Custom(format!("无法识别的 Minecraft 版本: {}", mc_version)))?;
+        let patch = parts.next().unwrap_or("0");
+        ("neoforge", format!("{}.{}.", minor, patch))
+    };
 
-    warn!("BMCLAPI 获取 NeoForge 版本失败，返回空列表");
-    Ok(vec![])
+    let url = format!("{}/{}", NEOFORGE_MAVEN_API_URL, artifact);
+    let response = client.get(&url).send().await?;
+    if !response.status().is_success() {
+        return Err(LauncherError::Custom(format!(
+            "获取 NeoForge 版本列表失败: {}",
+            response.status()
+        )));
+    }
+    let listing: MavenVersionListing = response.json().await?;
+    Ok(listing
+        .versions
+        .into_iter()
+        .filter(|v| v.starts_with(&prefix))
+        .map(|version| NeoForgeVersion {
+            version,
+            mc_version: mc_version.to_string(),
+        })
+        .collect())
 }
 
 // --- 内部数据结构 ---
 
+#[derive(serde::Deserialize)]
+struct MavenVersionListing {
+    versions: Vec<String>,
+}
+
 #[derive(serde::Deserialize)]
 struct BmclapiNeoForgeVersion {
     version: String,
@@ -196,7 +255,7 @@ struct BmclapiNeoForgeVersion {
 }
 
 /// NeoForge 版本信息
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct NeoForgeVersion {
     pub version: String,
     pub mc_version: String,
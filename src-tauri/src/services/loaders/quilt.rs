@@ -1,7 +1,9 @@
 //! Quilt 加载器安装
 
 use crate::errors::LauncherError;
-use log::info;
+use crate::services::meta_cache;
+use crate::utils::maven;
+use log::{info, warn};
 use reqwest::Client;
 use serde_json::Value;
 use std::fs;
@@ -9,6 +11,8 @@ use std::path::Path;
 
 /// Quilt Meta API 基础 URL
 const QUILT_META_URL: &str = "https://meta.quiltmc.org/v3";
+/// BMCLAPI 的 Quilt Meta 镜像
+const BMCLAPI_QUILT_META_URL: &str = "https://bmclapi2.bangbang93.com/quilt-meta/v3";
 
 /// 安装 Quilt 加载器
 pub async fn install_quilt(
@@ -67,13 +71,46 @@ pub async fn install_quilt(
     Ok(())
 }
 
-/// 获取 Quilt 加载器版本列表
+/// 获取 Quilt 加载器版本列表（最新版本在前）
 pub async fn get_quilt_versions(mc_version: &str) -> Result<Vec<QuiltLoaderVersion>, LauncherError> {
+    meta_cache::get_or_fetch(
+        &format!("quilt_versions:{}", mc_version),
+        meta_cache::DEFAULT_TTL,
+        || fetch_quilt_versions(mc_version),
+    )
+    .await
+}
+
+/// 从 Quilt Meta 获取加载器列表，失败时改用 BMCLAPI 镜像
+async fn fetch_quilt_versions(mc_version: &str) -> Result<Vec<QuiltLoaderVersion>, LauncherError> {
     let client = Client::new();
-    let url = format!("{}/versions/loader/{}", QUILT_META_URL, mc_version);
+    let mut last_error = None;
+    for base in [QUILT_META_URL, BMCLAPI_QUILT_META_URL] {
+        let url = format!("{}/versions/loader/{}", base, mc_version);
+        match fetch_loader_list(&client, &url).await {
+            Ok(versions) => {
+                let mut versions: Vec<QuiltLoaderVersion> = versions
+                    .into_iter()
+                    .map(|v| QuiltLoaderVersion {
+                        version: v.loader.version,
+                    })
+                    .collect();
+                // 排序：最新版本在前（beta 排在同号正式版之后）
+                versions.sort_by(|a, b| maven::compare_versions(&b.version, &a.version));
+                return Ok(versions);
+            }
+            Err(e) => {
+                warn!("Quilt: 从 {} 获取版本列表失败: {}", base, e);
+                last_error = Some(e);
+            }
+        }
+    }
+    Err(last_error.unwrap_or_else(|| LauncherError::Custom("获取 Quilt 版本列表失败".to_string())))
+}
 
+async fn fetch_loader_list(client: &Client, url: &str) -> Result<Vec<QuiltLoaderInfo>, LauncherError> {
     let response = client
-        .get(&url)
+        .get(url)
         .send()
         .await
         .map_err(|e| LauncherError::Custom(format!("获取 Quilt 版本列表失败: {}", e)))?;
@@ -85,17 +122,10 @@ pub async fn get_quilt_versions(mc_version: &str) -> Result<Vec<QuiltLoaderVersi
         )));
     }
 
-    let versions: Vec<QuiltLoaderInfo> = response
+    response
         .json()
         .await
-        .map_err(|e| LauncherError::Custom(format!("解析 Quilt 版本列表失败: {}", e)))?;
-
-    Ok(versions
-        .into_iter()
-        .map(|v| QuiltLoaderVersion {
-            version: v.loader.version,
-        })
-        .collect())
+        .map_err(|e| LauncherError::Custom(format!("解析 Quilt 版本列表失败: {}", e)))
 }
 
 /// 获取支持 Quilt 的 Minecraft 版本列表
@@ -142,7 +172,7 @@ struct QuiltGameVersion {
 }
 
 /// Quilt 加载器版本信息
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct QuiltLoaderVersion {
     pub version: String,
 }
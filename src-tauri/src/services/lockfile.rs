@@ -0,0 +1,275 @@
+//! 实例依赖锁定文件
+//!
+//! 记录实例的 MC 版本、加载器版本以及游戏实际读取的 mods 目录中每个模组的哈希和下载地址，
+//! 朋友之间共享锁定文件即可还原出一致的环境，无需导出整个实例。
+
+use crate::errors::LauncherError;
+use crate::services::paths::{self, ContentKind};
+use crate::services::{config, http_client, modrinth, version_info};
+use crate::utils::file_utils;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 锁定文件名（位于实例目录）
+pub const LOCKFILE_NAME: &str = "ar1s.lock.json";
+const LOCKFILE_FORMAT: u32 = 1;
+/// 与锁定文件不一致的多余模组会追加该后缀停用
+const DISABLED_SUFFIX: &str = ".disabled";
+
+/// 锁定的单个文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockedFile {
+    /// 相对实例目录的路径，如 `mods/sodium.jar`
+    pub path: String,
+    pub sha1: String,
+    pub size: u64,
+    /// 已知的下载地址（手动添加的模组可能为空）
+    #[serde(default)]
+    pub downloads: Vec<String>,
+}
+
+/// 实例锁定文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceLockfile {
+    pub format_version: u32,
+    pub minecraft: Option<String>,
+    pub loader: String,
+    pub loader_version: Option<String>,
+    pub generated_at: String,
+    pub files: Vec<LockedFile>,
+}
+
+/// 同步结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockSyncReport {
+    /// 重新下载的文件
+    pub downloaded: Vec<String>,
+    /// 不在锁定文件中而被停用的模组
+    pub disabled: Vec<String>,
+    /// 无法还原的文件
+    pub failed: Vec<String>,
+    /// 版本或加载器不一致等需要用户处理的问题
+    pub warnings: Vec<String>,
+}
+
+fn instance_dir(instance_name: &str) -> Result<PathBuf, LauncherError> {
+    let config = config::load_config()?;
+    let dir = paths::version_dir(&config, instance_name);
+    if !dir.exists() {
+        return Err(LauncherError::Custom(format!("实例 '{}' 不存在", instance_name)));
+    }
+    Ok(dir)
+}
+
+/// 读取实例当前的锁定文件
+fn read_lockfile(path: &Path) -> Option<InstanceLockfile> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// 游戏实际读取的 mods 目录（关闭版本隔离时为公共目录）
+fn mods_dir(instance_name: &str) -> Result<PathBuf, LauncherError> {
+    let config = config::load_config()?;
+    Ok(paths::content_dir(&config, instance_name, ContentKind::Mods))
+}
+
+/// 模组文件在锁定文件中的路径（`mods/<文件名>`）对应的实际路径
+fn resolve_mod_path(mods_dir: &Path, relative: &str) -> PathBuf {
+    mods_dir.join(relative.strip_prefix("mods/").unwrap_or(relative))
+}
+
+/// mods 目录中的模组文件（`mods/<文件名>` 形式）
+fn list_mod_files(mods_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(mods_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.path().is_file())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| name.ends_with(".jar"))
+        .map(|name| format!("mods/{}", name))
+        .collect();
+    files.sort();
+    files
+}
+
+/// 为实例生成锁定文件
+///
+/// `known_downloads` 为已知的 路径→下载地址（如整合包索引），
+/// 未提供的沿用旧锁定文件中同哈希文件的地址。
+pub fn generate_lockfile(
+    instance_dir: &Path,
+    instance_name: &str,
+    known_downloads: &HashMap<String, Vec<String>>,
+) -> Result<InstanceLockfile, LauncherError> {
+    let lock_path = instance_dir.join(LOCKFILE_NAME);
+    let previous: HashMap<String, Vec<String>> = read_lockfile(&lock_path)
+        .map(|lock| lock.files.into_iter().map(|f| (f.sha1, f.downloads)).collect())
+        .unwrap_or_default();
+
+    let mods_dir = mods_dir(instance_name)?;
+    let mut files = Vec::new();
+    for relative in list_mod_files(&mods_dir) {
+        let path = resolve_mod_path(&mods_dir, &relative);
+        let sha1 = file_utils::sha1_file(&path, None)?;
+        let downloads = known_downloads
+            .get(&relative)
+            .or_else(|| previous.get(&sha1))
+            .cloned()
+            .unwrap_or_default();
+        files.push(LockedFile {
+            size: fs::metadata(&path)?.len(),
+            path: relative,
+            sha1,
+            downloads,
+        });
+    }
+
+    let versions_dir = instance_dir.parent().unwrap_or(instance_dir);
+    let info = version_info::detect(versions_dir, instance_name);
+    let lockfile = InstanceLockfile {
+        format_version: LOCKFILE_FORMAT,
+        minecraft: info.mc_version,
+        loader: info.loader_type,
+        loader_version: info.loader_version,
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        files,
+    };
+    fs::write(&lock_path, serde_json::to_string_pretty(&lockfile)?)?;
+    info!("已生成实例 {} 的锁定文件，共 {} 个模组", instance_name, lockfile.files.len());
+    Ok(lockfile)
+}
+
+/// 重新生成指定实例的锁定文件
+pub fn generate_instance_lockfile(instance_name: &str) -> Result<InstanceLockfile, LauncherError> {
+    generate_lockfile(&instance_dir(instance_name)?, instance_name, &HashMap::new())
+}
+
+/// 从任一下载地址获取文件并校验 SHA1
+pub(crate) async fn fetch_locked_file(file: &LockedFile, dest: &Path) -> Result<(), LauncherError> {
+    let tmp = dest.with_extension("part");
+    let mut last_error = "没有可用的下载地址".to_string();
+    // 配置了 Modrinth 镜像时镜像优先；内容需通过下方的哈希校验
+    let urls = file.downloads.iter().flat_map(|url| modrinth::download_candidates(url));
+    for url in urls {
+        let response = match http_client::get_client().get(&url).send().await {
+            Ok(r) if r.status().is_success() => r,
+            Ok(r) => {
+                last_error = format!("HTTP {}", r.status());
+                continue;
+            }
+            Err(e) => {
+                last_error = e.to_string();
+                continue;
+            }
+        };
+        fs::write(&tmp, response.bytes().await?)?;
+        if file_utils::verify_file_async(&tmp, &file.sha1, file.size).await? {
+            fs::rename(&tmp, dest)?;
+            return Ok(());
+        }
+        let _ = fs::remove_file(&tmp);
+        last_error = format!("{} 哈希不匹配", url);
+    }
+    Err(LauncherError::Custom(last_error))
+}
+
+/// 将实例还原到锁定状态
+///
+/// 指定 `lockfile_path` 时先导入该锁定文件（好友共享的文件），否则使用实例已有的锁定文件。
+pub async fn sync_instance_to_lockfile(
+    instance_name: &str,
+    lockfile_path: Option<&str>,
+) -> Result<LockSyncReport, LauncherError> {
+    let instance_dir = instance_dir(instance_name)?;
+    let lock_path = instance_dir.join(LOCKFILE_NAME);
+    if let Some(src) = lockfile_path {
+        let content = fs::read_to_string(src)?;
+        serde_json::from_str::<InstanceLockfile>(&content)
+            .map_err(|e| LauncherError::Custom(format!("锁定文件格式无效: {}", e)))?;
+        fs::write(&lock_path, content)?;
+    }
+    let lockfile = read_lockfile(&lock_path)
+        .ok_or_else(|| LauncherError::Custom(format!("实例 '{}' 没有有效的锁定文件", instance_name)))?;
+
+    let mut report = LockSyncReport::default();
+
+    let versions_dir = instance_dir.parent().unwrap_or(&instance_dir);
+    let current = version_info::detect(versions_dir, instance_name);
+    if lockfile.minecraft.is_some() && current.mc_version != lockfile.minecraft {
+        report.warnings.push(format!(
+            "游戏版本不一致：实例为 {}，锁定为 {}",
+            current.mc_version.as_deref().unwrap_or("未知"),
+            lockfile.minecraft.as_deref().unwrap_or("未知")
+        ));
+    }
+    if current.loader_type != lockfile.loader || current.loader_version != lockfile.loader_version {
+        report.warnings.push(format!(
+            "加载器不一致：实例为 {} {}，锁定为 {} {}",
+            current.loader_type,
+            current.loader_version.as_deref().unwrap_or(""),
+            lockfile.loader,
+            lockfile.loader_version.as_deref().unwrap_or("")
+        ));
+    }
+
+    let mods_dir = mods_dir(instance_name)?;
+    fs::create_dir_all(&mods_dir)?;
+    for file in &lockfile.files {
+        // 锁定文件可能来自他人，只接受 mods 目录下的普通文件名
+        let valid = file
+            .path
+            .strip_prefix("mods/")
+            .is_some_and(|name| !name.is_empty() && !name.contains(['/', '\\']) && name != "..");
+        if !valid {
+            report.failed.push(file.path.clone());
+            continue;
+        }
+        let dest = resolve_mod_path(&mods_dir, &file.path);
+        if file_utils::verify_file_async(&dest, &file.sha1, file.size).await? {
+            continue;
+        }
+        // 之前被停用的同一文件直接恢复
+        let disabled = PathBuf::from(format!("{}{}", dest.display(), DISABLED_SUFFIX));
+        if file_utils::verify_file_async(&disabled, &file.sha1, file.size).await? {
+            fs::rename(&disabled, &dest)?;
+            continue;
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        match fetch_locked_file(file, &dest).await {
+            Ok(()) => report.downloaded.push(file.path.clone()),
+            Err(e) => {
+                warn!("还原 {} 失败: {}", file.path, e);
+                report.failed.push(file.path.clone());
+            }
+        }
+    }
+
+    // 停用不在锁定文件中的模组
+    for relative in list_mod_files(&mods_dir) {
+        if lockfile.files.iter().any(|f| f.path == relative) {
+            continue;
+        }
+        let path = resolve_mod_path(&mods_dir, &relative);
+        fs::rename(&path, format!("{}{}", path.display(), DISABLED_SUFFIX))?;
+        report.disabled.push(relative);
+    }
+
+    info!(
+        "实例 {} 已同步到锁定文件：下载 {}，停用 {}，失败 {}",
+        instance_name,
+        report.downloaded.len(),
+        report.disabled.len(),
+        report.failed.len()
+    );
+    Ok(report)
+}
@@ -0,0 +1,149 @@
+//! 发往前端的日志事件节流
+//!
+//! 下载和启动期间后端每秒会发出上百条 `log-*` 事件，前端逐条渲染会让 WebView 卡顿。
+//! 日志先完整写入文件日志，再进入队列，由后台线程每 100ms 批量发出：连续重复的消息合并为
+//! 一条并附带重复次数；调试 / 信息日志每秒超过上限的部分丢弃，并在下一批中提示省略的条数。
+//! 警告和错误不受上限限制。
+
+use crate::services::event_sink::EventSink;
+use std::sync::{LazyLock, Mutex, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 批量发送间隔
+const FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+/// 每秒最多发往前端的调试 / 信息日志条数
+const MAX_PER_SECOND: u32 = 50;
+
+struct Entry {
+    event: &'static str,
+    message: String,
+    repeat: u32,
+}
+
+struct Pending {
+    window: Option<EventSink>,
+    entries: Vec<Entry>,
+    second_start: Instant,
+    sent_this_second: u32,
+    dropped: u32,
+}
+
+static PENDING: LazyLock<Mutex<Pending>> = LazyLock::new(|| {
+    Mutex::new(Pending {
+        window: None,
+        entries: Vec::new(),
+        second_start: Instant::now(),
+        sent_this_second: 0,
+        dropped: 0,
+    })
+});
+
+static FLUSHER: Once = Once::new();
+
+/// 日志事件名规范化为静态字符串，非日志事件返回 None
+fn log_event(event: &str) -> Option<&'static str> {
+    match event {
+        "log-debug" => Some("log-debug"),
+        "log-info" => Some("log-info"),
+        "log-warning" => Some("log-warning"),
+        "log-error" => Some("log-error"),
+        _ => None,
+    }
+}
+
+/// 写入文件日志（不截断、不去重）
+fn write_file_log(event: &str, message: &str) {
+    match event {
+        "log-debug" => log::debug!("{}", message),
+        "log-info" => log::info!("{}", message),
+        "log-warning" => log::warn!("{}", message),
+        _ => log::error!("{}", message),
+    }
+}
+
+fn flush() {
+    let (window, entries, dropped) = {
+        let Ok(mut pending) = PENDING.lock() else {
+            return;
+        };
+        if pending.entries.is_empty() && pending.dropped == 0 {
+            return;
+        }
+        let Some(window) = pending.window.clone() else {
+            return;
+        };
+        let entries = std::mem::take(&mut pending.entries);
+        // 省略提示等到本秒结束后再发，避免每批都提示一次
+        let dropped = if pending.second_start.elapsed() >= Duration::from_secs(1) {
+            std::mem::take(&mut pending.dropped)
+        } else {
+            0
+        };
+        (window, entries, dropped)
+    };
+
+    for entry in entries {
+        let message = if entry.repeat > 1 {
+            format!("{}（重复 {} 次）", entry.message, entry.repeat)
+        } else {
+            entry.message
+        };
+        let _ = window.emit(entry.event, message);
+    }
+    if dropped > 0 {
+        let _ = window.emit(
+            "log-info",
+            format!("日志过多，已省略 {} 条调试信息（完整内容见日志文件）", dropped),
+        );
+    }
+}
+
+fn start_flusher() {
+    FLUSHER.call_once(|| {
+        thread::spawn(|| loop {
+            thread::sleep(FLUSH_INTERVAL);
+            flush();
+        });
+    });
+}
+
+/// 发送事件到前端；`log-*` 事件写入文件日志后经节流队列批量发送，其他事件直接发送
+pub fn emit(window: &EventSink, event: &str, message: impl Into<String>) {
+    let message = message.into();
+    let Some(event) = log_event(event) else {
+        let _ = window.emit(event, message);
+        return;
+    };
+    write_file_log(event, &message);
+    start_flusher();
+
+    let Ok(mut pending) = PENDING.lock() else {
+        return;
+    };
+    pending.window = Some(window.clone());
+
+    // 与上一条相同时只增加重复次数
+    if let Some(last) = pending.entries.last_mut() {
+        if last.event == event && last.message == message {
+            last.repeat += 1;
+            return;
+        }
+    }
+
+    if pending.second_start.elapsed() >= Duration::from_secs(1) {
+        pending.second_start = Instant::now();
+        pending.sent_this_second = 0;
+    }
+    let limited = matches!(event, "log-debug" | "log-info");
+    if limited && pending.sent_this_second >= MAX_PER_SECOND {
+        pending.dropped += 1;
+        return;
+    }
+    pending.sent_this_second += 1;
+    pending.entries.push(Entry {
+        event,
+        message,
+        repeat: 1,
+    });
+}
@@ -1,6 +1,9 @@
 use crate::errors::LauncherError;
-use sysinfo::{System, MemoryRefreshKind};
+use crate::services::launcher;
+use sysinfo::{MemoryRefreshKind, Pid, ProcessRefreshKind, ProcessesToUpdate, System};
 use std::sync::Mutex;
+use std::time::Duration;
+use crate::services::event_sink::EventSink;
 use lazy_static::lazy_static;
 use serde::{Serialize, Deserialize};
 
@@ -8,6 +11,14 @@ lazy_static! {
     static ref MEMORY_SYSTEM: Mutex<System> = Mutex::new(System::new());
 }
 
+/// 实时采样的间隔范围（毫秒）
+const MIN_SAMPLE_INTERVAL_MS: u64 = 250;
+const MAX_SAMPLE_INTERVAL_MS: u64 = 10_000;
+const DEFAULT_SAMPLE_INTERVAL_MS: u64 = 1000;
+
+/// 正在运行的采样任务的停止信号
+static SAMPLER: Mutex<Option<tokio::sync::watch::Sender<bool>>> = Mutex::new(None);
+
 /// 内存使用统计
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryStats {
@@ -345,4 +356,101 @@ mod tests {
         assert!(args.iter().any(|arg| arg.contains("-Xmx2048M")));
         assert!(args.iter().any(|arg| arg.contains("-Xms1024M")));
     }
-}
\ No newline at end of file
+}
+/// 游戏进程的内存占用
+#[derive(Debug, Clone, Serialize)]
+pub struct GameMemoryUsage {
+    pub instance: String,
+    pub pid: u32,
+    pub memory_mb: u64,
+}
+
+/// `memory-stats` 事件内容
+#[derive(Debug, Clone, Serialize)]
+pub struct MemorySample {
+    /// 采样时间（Unix 毫秒）
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub system: MemoryStats,
+    /// 启动器自身占用
+    pub launcher_memory_mb: u64,
+    /// 本次会话启动的游戏
+    pub games: Vec<GameMemoryUsage>,
+}
+
+fn sample(processes: &mut System, launcher_pid: Option<Pid>) -> MemorySample {
+    let games = launcher::running_games();
+    let pids: Vec<Pid> = games
+        .iter()
+        .map(|(_, pid)| Pid::from_u32(*pid))
+        .chain(launcher_pid)
+        .collect();
+    processes.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&pids),
+        true,
+        ProcessRefreshKind::nothing().with_memory(),
+    );
+    let memory_mb = |pid: Pid| {
+        processes
+            .process(pid)
+            .map(|p| p.memory() / 1024 / 1024)
+    };
+
+    MemorySample {
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        system: get_system_memory(),
+        launcher_memory_mb: launcher_pid.and_then(memory_mb).unwrap_or(0),
+        games: games
+            .into_iter()
+            .filter_map(|(instance, pid)| {
+                Some(GameMemoryUsage {
+                    memory_mb: memory_mb(Pid::from_u32(pid))?,
+                    instance,
+                    pid,
+                })
+            })
+            .collect(),
+    }
+}
+
+/// 停止实时内存采样
+pub fn stop_memory_sampling() {
+    if let Ok(mut sampler) = SAMPLER.lock() {
+        if let Some(tx) = sampler.take() {
+            let _ = tx.send(true);
+        }
+    }
+}
+
+/// 开始实时内存采样，按间隔发送 `memory-stats` 事件，直到调用 [`stop_memory_sampling`]
+///
+/// 已在采样时以新的间隔重新开始，返回实际使用的间隔（毫秒）
+pub fn start_memory_sampling(interval_ms: Option<u64>, window: EventSink) -> u64 {
+    stop_memory_sampling();
+    let interval_ms = interval_ms
+        .unwrap_or(DEFAULT_SAMPLE_INTERVAL_MS)
+        .clamp(MIN_SAMPLE_INTERVAL_MS, MAX_SAMPLE_INTERVAL_MS);
+    let (tx, mut rx) = tokio::sync::watch::channel(false);
+    if let Ok(mut sampler) = SAMPLER.lock() {
+        *sampler = Some(tx);
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let launcher_pid = sysinfo::get_current_pid().ok();
+        let mut processes = System::new();
+        let mut ticker = tokio::time::interval(Duration::from_millis(interval_ms));
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    let sample = sample(&mut processes, launcher_pid);
+                    if window.emit("memory-stats", sample).is_err() {
+                        break;
+                    }
+                }
+                _ = rx.changed() => break,
+            }
+        }
+        log::debug!("内存采样已停止");
+    });
+    interval_ms
+}
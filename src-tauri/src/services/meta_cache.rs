@@ -0,0 +1,91 @@
+//! 元数据内存缓存
+//!
+//! 缓存版本清单、加载器版本列表等网络元数据，减少页面打开时的等待。
+//! 数据以 JSON 形式存储，按键区分，带过期时间。
+
+use crate::errors::LauncherError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{LazyLock, RwLock};
+use std::time::{Duration, Instant};
+
+/// 元数据默认缓存时间
+pub const DEFAULT_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// 缓存条目
+struct CacheEntry {
+    fetched_at: Instant,
+    value: serde_json::Value,
+}
+
+static CACHE: LazyLock<RwLock<HashMap<String, CacheEntry>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// 读取未过期的缓存
+pub fn get<T: DeserializeOwned>(key: &str, ttl: Duration) -> Option<T> {
+    let cache = CACHE.read().ok()?;
+    let entry = cache.get(key)?;
+    if entry.fetched_at.elapsed() > ttl {
+        return None;
+    }
+    serde_json::from_value(entry.value.clone()).ok()
+}
+
+/// 写入缓存
+pub fn put<T: Serialize>(key: &str, value: &T) {
+    let Ok(value) = serde_json::to_value(value) else {
+        return;
+    };
+    if let Ok(mut cache) = CACHE.write() {
+        cache.insert(
+            key.to_string(),
+            CacheEntry {
+                fetched_at: Instant::now(),
+                value,
+            },
+        );
+    }
+}
+
+/// 清除指定缓存
+pub fn invalidate(key: &str) {
+    if let Ok(mut cache) = CACHE.write() {
+        cache.remove(key);
+    }
+}
+
+/// 清除全部缓存
+pub fn clear() {
+    if let Ok(mut cache) = CACHE.write() {
+        cache.clear();
+    }
+}
+
+/// 优先返回缓存，未命中或过期时调用 fetch 获取并写入缓存
+pub async fn get_or_fetch<T, F, Fut>(key: &str, ttl: Duration, fetch: F) -> Result<T, LauncherError>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, LauncherError>>,
+{
+    if let Some(value) = get(key, ttl) {
+        return Ok(value);
+    }
+    let value = fetch().await?;
+    put(key, &value);
+    Ok(value)
+}
+
+/// 强制重新获取并更新缓存（获取失败时保留旧缓存）
+pub async fn refresh<T, F, Fut>(key: &str, fetch: F) -> Result<(), LauncherError>
+where
+    T: Serialize,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, LauncherError>>,
+{
+    let value = fetch().await?;
+    put(key, &value);
+    Ok(())
+}
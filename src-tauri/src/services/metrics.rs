@@ -0,0 +1,120 @@
+//! 性能指标
+//!
+//! 为耗时操作（版本清单获取、Classpath 构建、Natives 解压、安装总耗时、
+//! 各镜像下载耗时等）计时，在内存中聚合统计，供性能分析和诊断包使用。
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// 单项操作的聚合统计
+#[derive(Debug, Clone, Default)]
+struct Aggregate {
+    count: u64,
+    failures: u64,
+    total: Duration,
+    min: Option<Duration>,
+    max: Duration,
+    last: Duration,
+}
+
+static METRICS: LazyLock<Mutex<HashMap<String, Aggregate>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 导出给前端的统计数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationMetrics {
+    pub name: String,
+    pub count: u64,
+    pub failures: u64,
+    pub total_ms: u64,
+    pub avg_ms: u64,
+    pub min_ms: u64,
+    pub max_ms: u64,
+    pub last_ms: u64,
+}
+
+/// 计时区间，离开作用域时自动记录耗时
+pub struct Span {
+    name: String,
+    start: Instant,
+    failed: bool,
+}
+
+impl Span {
+    /// 标记本次操作失败（仍记录耗时）
+    pub fn fail(&mut self) {
+        self.failed = true;
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        record(&self.name, self.start.elapsed(), !self.failed);
+    }
+}
+
+/// 开始一个计时区间
+pub fn span(name: impl Into<String>) -> Span {
+    Span {
+        name: name.into(),
+        start: Instant::now(),
+        failed: false,
+    }
+}
+
+/// 记录一次操作耗时
+pub fn record(name: &str, elapsed: Duration, success: bool) {
+    let Ok(mut metrics) = METRICS.lock() else {
+        return;
+    };
+    let agg = metrics.entry(name.to_string()).or_default();
+    agg.count += 1;
+    if !success {
+        agg.failures += 1;
+    }
+    agg.total += elapsed;
+    agg.min = Some(agg.min.map_or(elapsed, |m| m.min(elapsed)));
+    agg.max = agg.max.max(elapsed);
+    agg.last = elapsed;
+}
+
+/// 从 URL 中提取主机名，用于按镜像区分下载耗时
+pub fn host_of(url: &str) -> &str {
+    url.split("://")
+        .nth(1)
+        .and_then(|rest| rest.split('/').next())
+        .unwrap_or("unknown")
+}
+
+/// 获取所有指标快照（按总耗时降序）
+pub fn snapshot() -> Vec<OperationMetrics> {
+    let Ok(metrics) = METRICS.lock() else {
+        return Vec::new();
+    };
+
+    let mut list: Vec<OperationMetrics> = metrics
+        .iter()
+        .map(|(name, agg)| OperationMetrics {
+            name: name.clone(),
+            count: agg.count,
+            failures: agg.failures,
+            total_ms: agg.total.as_millis() as u64,
+            avg_ms: (agg.total / agg.count.max(1) as u32).as_millis() as u64,
+            min_ms: agg.min.unwrap_or_default().as_millis() as u64,
+            max_ms: agg.max.as_millis() as u64,
+            last_ms: agg.last.as_millis() as u64,
+        })
+        .collect();
+    list.sort_by_key(|m| std::cmp::Reverse(m.total_ms));
+    list
+}
+
+/// 清空指标
+pub fn reset() {
+    if let Ok(mut metrics) = METRICS.lock() {
+        metrics.clear();
+    }
+}
@@ -1,15 +1,55 @@
+pub mod accounts;
+pub mod announcements;
+pub mod asset_gc;
+pub mod auth;
+pub mod backup;
+pub mod base_version;
 pub mod config;
+pub mod config_history;
+pub mod diagnostics;
 pub mod download;
+pub mod event_sink;
 pub mod http_client;
 pub mod java;
+pub mod java_runtime;
 pub mod launcher;
+pub mod log_events;
 pub mod instance;
+pub mod instance_archive;
+pub mod instance_health;
+pub mod instance_lock;
+pub mod instance_share;
+pub mod kiosk;
 pub mod loaders;  // 新的统一加载器模块
+pub mod lockfile;
 pub mod file_verification;
+pub mod fs_access;
+pub mod fs_ops;
+pub mod health;
+pub mod hooks;
 pub mod memory;
+pub mod meta_cache;
+pub mod metrics;
+pub mod mod_import;
+pub mod mod_install;
+pub mod mod_list;
 pub mod modrinth;
+pub mod modpack_health;
 pub mod modpack_installer;
-
-// 保留旧的 forge 模块以保持向后兼容（已弃用）
-#[deprecated(note = "请使用 loaders::forge 代替")]
-pub mod forge;
+pub mod modpack_update;
+pub mod os_progress;
+pub mod paths;
+pub mod prefetch;
+pub mod resourcepack_dedupe;
+pub mod scheduler;
+pub mod server_ping;
+pub mod server_sync;
+pub mod shortcut;
+pub mod skin_server;
+pub mod skins;
+pub mod startup;
+pub mod tasks;
+pub mod telemetry;
+pub mod version_index;
+pub mod version_info;
+pub mod version_watcher;
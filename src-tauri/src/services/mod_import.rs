@@ -0,0 +1,201 @@
+//! 从已有 .minecraft 导入模组
+//!
+//! 复制模组 JAR 到实例的 mods 目录，解析元数据检查与实例 MC 版本/加载器是否兼容，
+//! 可选按哈希匹配 Modrinth 项目并写入锁定文件，使这些模组之后可以被追踪和更新。
+
+use crate::errors::LauncherError;
+use crate::models::modpack::ModrinthFileMatch;
+use crate::services::instance_lock::{self, InstanceOperation};
+use crate::services::modrinth::ModrinthService;
+use crate::services::paths::{self, ContentKind};
+use crate::services::{config, lockfile, version_info};
+use crate::utils::file_utils;
+use crate::utils::mod_metadata::{self, ModMetadata};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 单个模组的导入结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedMod {
+    pub file_name: String,
+    pub sha1: String,
+    /// 是否已复制（同名且内容相同的文件会跳过）
+    pub copied: bool,
+    pub metadata: Option<ModMetadata>,
+    /// 与实例不兼容等问题
+    pub issues: Vec<String>,
+    pub modrinth: Option<ModrinthFileMatch>,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModImportReport {
+    pub instance_name: String,
+    pub minecraft: Option<String>,
+    pub loader: String,
+    pub mods: Vec<ImportedMod>,
+}
+
+/// 传入 .minecraft 目录时使用其下的 mods 目录
+fn source_mods_dir(path: &Path) -> PathBuf {
+    let nested = path.join("mods");
+    if nested.is_dir() {
+        nested
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// 检查模组与实例加载器、MC 版本的兼容性
+fn compatibility_issues(meta: &ModMetadata, loader: &str, mc_version: Option<&str>) -> Vec<String> {
+    let mut issues = Vec::new();
+    let loader = loader.to_lowercase();
+    let loader_ok = match loader.as_str() {
+        "none" | "optifine" => {
+            issues.push("实例未安装模组加载器".to_string());
+            true
+        }
+        // Quilt 可加载 Fabric 模组；1.20.1 的 NeoForge 仍兼容 Forge 模组
+        "quilt" => meta.loaders.iter().any(|l| l == "quilt" || l == "fabric"),
+        "neoforge" => meta
+            .loaders
+            .iter()
+            .any(|l| l == "neoforge" || (l == "forge" && mc_version == Some("1.20.1"))),
+        other => meta.loaders.iter().any(|l| l == other),
+    };
+    if !loader_ok {
+        issues.push(format!("模组适用于 {}，与实例的加载器 {} 不兼容", meta.loaders.join("/"), loader));
+    }
+    if let Some(mc) = mc_version {
+        if !mod_metadata::minecraft_matches(mc, &meta.minecraft) {
+            issues.push(format!("模组要求 Minecraft {}，实例为 {}", meta.minecraft.join(" 或 "), mc));
+        }
+    }
+    issues
+}
+
+/// 从文件夹（.minecraft 或其 mods 目录）导入模组到实例
+pub async fn import_mods_from_folder(
+    instance_name: String,
+    path: String,
+    match_modrinth: bool,
+) -> Result<ModImportReport, LauncherError> {
+    let _lock = instance_lock::acquire(&instance_name, InstanceOperation::Install)?;
+    let config = config::load_config()?;
+    let versions_dir = paths::instance_game_dir(&config, &instance_name).join("versions");
+    let instance_dir = versions_dir.join(&instance_name);
+    if !instance_dir.exists() {
+        return Err(LauncherError::Custom(format!("实例 '{}' 不存在", instance_name)));
+    }
+
+    let source = source_mods_dir(Path::new(&path));
+    let dest_dir = paths::content_dir(&config, &instance_name, ContentKind::Mods);
+    if source == dest_dir {
+        return Err(LauncherError::Custom("源目录就是实例的 mods 目录".to_string()));
+    }
+    let info = version_info::detect(&versions_dir, &instance_name);
+
+    let mods = {
+        let source = source.clone();
+        let dest_dir = dest_dir.clone();
+        let loader = info.loader_type.clone();
+        let mc_version = info.mc_version.clone();
+        tokio::task::spawn_blocking(move || copy_mods(&source, &dest_dir, &loader, mc_version.as_deref())).await??
+    };
+
+    let mut report = ModImportReport {
+        instance_name: instance_name.clone(),
+        minecraft: info.mc_version,
+        loader: info.loader_type,
+        mods,
+    };
+
+    if match_modrinth && !report.mods.is_empty() {
+        let hashes: Vec<String> = report.mods.iter().map(|m| m.sha1.clone()).collect();
+        match ModrinthService::new().get_versions_by_sha1(&hashes).await {
+            Ok(mut matches) => {
+                let mut known_downloads = HashMap::new();
+                for m in &mut report.mods {
+                    m.modrinth = matches.remove(&m.sha1);
+                    if let Some(found) = &m.modrinth {
+                        known_downloads.insert(format!("mods/{}", m.file_name), vec![found.url.clone()]);
+                    }
+                }
+                // 记录到锁定文件，使匹配到的模组之后可以按 Modrinth 项目更新
+                let instance_name = instance_name.clone();
+                tokio::task::spawn_blocking(move || {
+                    lockfile::generate_lockfile(&instance_dir, &instance_name, &known_downloads)
+                })
+                .await??;
+            }
+            Err(e) => log::warn!("匹配 Modrinth 项目失败: {}", e),
+        }
+    }
+
+    log::info!(
+        "已从 {} 导入 {} 个模组到实例 {}",
+        source.display(),
+        report.mods.iter().filter(|m| m.copied).count(),
+        instance_name
+    );
+    Ok(report)
+}
+
+fn copy_mods(
+    source: &Path,
+    dest_dir: &Path,
+    loader: &str,
+    mc_version: Option<&str>,
+) -> Result<Vec<ImportedMod>, LauncherError> {
+    let mut jars: Vec<PathBuf> = fs::read_dir(source)?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e.eq_ignore_ascii_case("jar")))
+        .collect();
+    jars.sort();
+    fs::create_dir_all(dest_dir)?;
+
+    let mut mods = Vec::new();
+    for jar in jars {
+        let file_name = jar.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let sha1 = file_utils::sha1_file(&jar, None)?;
+        let dest = dest_dir.join(&file_name);
+
+        let mut issues = Vec::new();
+        let copied = if !dest.exists() {
+            fs::copy(&jar, &dest)?;
+            true
+        } else {
+            if file_utils::sha1_file(&dest, None)? != sha1 {
+                issues.push("实例中已存在同名但内容不同的文件，未覆盖".to_string());
+            }
+            false
+        };
+
+        let metadata = match mod_metadata::read_mod_metadata(&jar) {
+            Ok(meta) => meta,
+            Err(e) => {
+                issues.push(format!("无法读取模组元数据: {}", e));
+                None
+            }
+        };
+        match &metadata {
+            Some(meta) => issues.extend(compatibility_issues(meta, loader, mc_version)),
+            None => issues.push("未识别的模组格式".to_string()),
+        }
+
+        mods.push(ImportedMod {
+            file_name,
+            sha1,
+            copied,
+            metadata,
+            issues,
+            modrinth: None,
+        });
+    }
+    Ok(mods)
+}
@@ -0,0 +1,219 @@
+//! 从 Modrinth 安装单个模组
+//!
+//! 按实例的 MC 版本和加载器选择兼容版本下载到实例的 mods 目录。安装完成后读取该版本的
+//! 可选依赖（如 Mod Menu、Cloth Config），整理成推荐列表返回给前端，可一键安装。
+
+use crate::errors::LauncherError;
+use crate::models::modpack::{ModrinthFile, ModrinthModpackVersion};
+use crate::services::instance_lock::{self, InstanceOperation};
+use crate::services::modrinth::ModrinthService;
+use crate::services::paths::{self, ContentKind};
+use crate::services::{config, lockfile, version_info};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// 推荐安装的可选依赖
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModSuggestion {
+    pub project_id: String,
+    pub title: String,
+    pub description: String,
+    pub icon_url: Option<String>,
+    /// 与实例兼容的版本
+    pub version_id: String,
+    pub version_number: String,
+}
+
+/// 模组安装结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModInstallResult {
+    pub project_id: String,
+    pub version_id: String,
+    pub version_number: String,
+    pub file_name: String,
+    /// 实例中已有同名文件，未重新下载
+    pub already_installed: bool,
+    /// 尚未安装的可选依赖
+    pub suggestions: Vec<ModSuggestion>,
+}
+
+/// 实例可以加载的 Modrinth 加载器标识
+fn compatible_loaders(
+    loader_type: &str,
+    mc_version: Option<&str>,
+) -> Result<Vec<String>, LauncherError> {
+    let loaders = match loader_type.to_lowercase().as_str() {
+        "fabric" => vec!["fabric"],
+        // Quilt 可加载 Fabric 模组；1.20.1 的 NeoForge 仍兼容 Forge 模组
+        "quilt" => vec!["quilt", "fabric"],
+        "neoforge" if mc_version == Some("1.20.1") => vec!["neoforge", "forge"],
+        "neoforge" => vec!["neoforge"],
+        "forge" => vec!["forge"],
+        _ => return Err(LauncherError::Custom("实例未安装模组加载器".to_string())),
+    };
+    Ok(loaders.into_iter().map(String::from).collect())
+}
+
+/// 查找与实例兼容的版本；指定版本 ID 时只接受该版本
+async fn compatible_version(
+    modrinth: &ModrinthService,
+    project_id: &str,
+    version_id: Option<&str>,
+    mc_version: &str,
+    loaders: &[String],
+) -> Result<Option<ModrinthModpackVersion>, LauncherError> {
+    let versions = modrinth
+        .get_modpack_versions(
+            project_id,
+            Some(vec![mc_version.to_string()]),
+            Some(loaders.to_vec()),
+        )
+        .await?;
+    Ok(match version_id {
+        Some(id) => versions.into_iter().find(|v| v.id == id),
+        None => versions.into_iter().next(),
+    })
+}
+
+fn primary_file(version: &ModrinthModpackVersion) -> Option<&ModrinthFile> {
+    version
+        .files
+        .iter()
+        .find(|f| f.primary)
+        .or(version.files.first())
+}
+
+/// 整理可选依赖：跳过已安装和没有兼容版本的项目
+async fn optional_dependencies(
+    modrinth: &ModrinthService,
+    version: &ModrinthModpackVersion,
+    mods_dir: &Path,
+    mc_version: &str,
+    loaders: &[String],
+) -> Vec<ModSuggestion> {
+    let mut suggestions = Vec::new();
+    for dependency in version
+        .dependencies
+        .iter()
+        .filter(|d| d.dependency_type == "optional")
+    {
+        let Some(project_id) = dependency.project_id.as_deref() else {
+            continue;
+        };
+        let found = compatible_version(modrinth, project_id, None, mc_version, loaders).await;
+        let Ok(Some(candidate)) = found else {
+            continue;
+        };
+        if primary_file(&candidate).is_some_and(|f| mods_dir.join(&f.filename).exists()) {
+            continue;
+        }
+        let project = match modrinth.get_modpack(project_id).await {
+            Ok(project) => project,
+            Err(e) => {
+                log::warn!("获取可选依赖 {} 的信息失败: {}", project_id, e);
+                continue;
+            }
+        };
+        suggestions.push(ModSuggestion {
+            project_id: project_id.to_string(),
+            title: project.title,
+            description: project.description,
+            icon_url: project.icon_url,
+            version_id: candidate.id,
+            version_number: candidate.version_number,
+        });
+    }
+    suggestions
+}
+
+/// 安装 Modrinth 模组到实例，返回结果和推荐安装的可选依赖
+pub async fn install_modrinth_mod(
+    instance_name: String,
+    project_id: String,
+    version_id: Option<String>,
+) -> Result<ModInstallResult, LauncherError> {
+    let _lock = instance_lock::acquire(&instance_name, InstanceOperation::Install)?;
+    let config = config::load_config()?;
+    let versions_dir = paths::instance_game_dir(&config, &instance_name).join("versions");
+    if !versions_dir.join(&instance_name).exists() {
+        return Err(LauncherError::Custom(format!(
+            "实例 '{}' 不存在",
+            instance_name
+        )));
+    }
+    let info = version_info::detect(&versions_dir, &instance_name);
+    let mc_version = info
+        .mc_version
+        .clone()
+        .ok_or_else(|| LauncherError::Custom("无法确定实例的游戏版本".to_string()))?;
+    let loaders = compatible_loaders(&info.loader_type, Some(&mc_version))?;
+
+    let modrinth = ModrinthService::new();
+    let version = compatible_version(
+        &modrinth,
+        &project_id,
+        version_id.as_deref(),
+        &mc_version,
+        &loaders,
+    )
+    .await?
+    .ok_or_else(|| {
+        LauncherError::Custom(format!(
+            "没有适用于 Minecraft {} + {} 的版本",
+            mc_version, info.loader_type
+        ))
+    })?;
+    let file = primary_file(&version)
+        .ok_or_else(|| LauncherError::Custom("该版本没有可下载的文件".to_string()))?;
+
+    let mods_dir = paths::content_dir(&config, &instance_name, ContentKind::Mods);
+    fs::create_dir_all(&mods_dir)?;
+    let dest = mods_dir.join(&file.filename);
+    let already_installed = dest.exists();
+    if !already_installed {
+        modrinth
+            .download_modpack_file(&file.url, &dest, Some(&file.hashes.sha1), |_, _| true)
+            .await?;
+        log::info!("已安装模组 {} 到实例 {}", file.filename, instance_name);
+
+        // 记录下载地址，之后可以按 Modrinth 项目更新
+        let instance_dir = versions_dir.join(&instance_name);
+        let known_downloads =
+            HashMap::from([(format!("mods/{}", file.filename), vec![file.url.clone()])]);
+        let name = instance_name.clone();
+        let recorded = tokio::task::spawn_blocking(move || {
+            lockfile::generate_lockfile(&instance_dir, &name, &known_downloads)
+        })
+        .await?;
+        if let Err(e) = recorded {
+            log::warn!("更新锁定文件失败: {}", e);
+        }
+    }
+
+    let suggestions =
+        optional_dependencies(&modrinth, &version, &mods_dir, &mc_version, &loaders).await;
+    if !suggestions.is_empty() {
+        log::info!(
+            "模组 {} 推荐安装: {}",
+            file.filename,
+            suggestions
+                .iter()
+                .map(|s| s.title.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    Ok(ModInstallResult {
+        project_id,
+        version_id: version.id.clone(),
+        version_number: version.version_number.clone(),
+        file_name: file.filename.clone(),
+        already_installed,
+        suggestions,
+    })
+}
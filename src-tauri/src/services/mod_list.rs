@@ -0,0 +1,189 @@
+//! 导出实例模组列表
+//!
+//! 读取 mods 目录中每个模组的元数据（名称、版本），按 SHA1 匹配 Modrinth 项目生成链接，
+//! 渲染为 Markdown 表格或 CSV 并写入实例目录，便于服务器管理员公布模组清单或编写整合包文档。
+//! 匹配失败（如离线）时仍然导出，链接留空；CurseForge 查询需要 API 密钥，暂不生成链接。
+
+use crate::errors::LauncherError;
+use crate::services::modrinth::ModrinthService;
+use crate::services::paths::{self, ContentKind};
+use crate::services::{config, version_info};
+use crate::utils::file_utils;
+use crate::utils::mod_metadata;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModListFormat {
+    Markdown,
+    Csv,
+}
+
+impl ModListFormat {
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::Markdown => "mods.md",
+            Self::Csv => "mods.csv",
+        }
+    }
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedModList {
+    pub content: String,
+    /// 写入的文件路径
+    pub path: String,
+    pub mod_count: usize,
+    /// 匹配到 Modrinth 项目的模组数量
+    pub linked_count: usize,
+}
+
+struct ModEntry {
+    file_name: String,
+    name: String,
+    version: String,
+    mod_id: String,
+    sha1: String,
+    link: Option<String>,
+}
+
+/// 读取 mods 目录中的模组（按名称排序）
+fn scan_mods(mods_dir: &Path) -> Result<Vec<ModEntry>, LauncherError> {
+    let Ok(entries) = fs::read_dir(mods_dir) else {
+        return Ok(Vec::new());
+    };
+    let mut mods = Vec::new();
+    for path in entries.flatten().map(|e| e.path()) {
+        if !path.is_file() || path.extension().is_none_or(|ext| ext != "jar") {
+            continue;
+        }
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let meta = mod_metadata::read_mod_metadata(&path).unwrap_or_else(|e| {
+            log::warn!("读取模组元数据失败 {}: {}", path.display(), e);
+            None
+        });
+        mods.push(ModEntry {
+            name: meta
+                .as_ref()
+                .and_then(|m| m.name.clone())
+                .unwrap_or_else(|| file_name.trim_end_matches(".jar").to_string()),
+            version: meta.as_ref().and_then(|m| m.version.clone()).unwrap_or_default(),
+            mod_id: meta.map(|m| m.mod_id).unwrap_or_default(),
+            sha1: file_utils::sha1_file(&path, None)?,
+            file_name,
+            link: None,
+        });
+    }
+    mods.sort_by_key(|m| m.name.to_lowercase());
+    Ok(mods)
+}
+
+/// Markdown 表格单元格（转义竖线，去掉换行）
+fn md_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+/// CSV 字段（含逗号、引号或换行时加引号）
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn render_markdown(instance_name: &str, summary: &str, mods: &[ModEntry]) -> String {
+    let mut out = format!("# {} 模组列表\n\n{}，共 {} 个模组\n\n", md_cell(instance_name), summary, mods.len());
+    out.push_str("| 名称 | 版本 | 文件 | 链接 |\n| --- | --- | --- | --- |\n");
+    for m in mods {
+        let link = m
+            .link
+            .as_deref()
+            .map(|url| format!("[Modrinth]({})", url))
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            md_cell(&m.name),
+            md_cell(&m.version),
+            md_cell(&m.file_name),
+            link
+        ));
+    }
+    out
+}
+
+fn render_csv(mods: &[ModEntry]) -> String {
+    let mut out = String::from("名称,版本,文件,模组 ID,SHA1,链接\n");
+    for m in mods {
+        let row = [
+            m.name.as_str(),
+            m.version.as_str(),
+            m.file_name.as_str(),
+            m.mod_id.as_str(),
+            m.sha1.as_str(),
+            m.link.as_deref().unwrap_or_default(),
+        ];
+        out.push_str(&row.map(csv_field).join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// 导出实例的模组列表，写入实例目录的 `mods.md` / `mods.csv`
+pub async fn export_mod_list(instance_name: &str, format: ModListFormat) -> Result<ExportedModList, LauncherError> {
+    let config = config::load_config()?;
+    let version_dir = paths::version_dir(&config, instance_name);
+    if !version_dir.exists() {
+        return Err(LauncherError::Custom(format!("实例 '{}' 不存在", instance_name)));
+    }
+    let mods_dir = paths::content_dir(&config, instance_name, ContentKind::Mods);
+    let mut mods = tokio::task::spawn_blocking(move || scan_mods(&mods_dir)).await??;
+
+    if !mods.is_empty() {
+        let hashes: Vec<String> = mods.iter().map(|m| m.sha1.clone()).collect();
+        match ModrinthService::new().get_versions_by_sha1(&hashes).await {
+            Ok(mut matches) => {
+                for m in &mut mods {
+                    m.link = matches
+                        .remove(&m.sha1)
+                        .map(|found| format!("https://modrinth.com/mod/{}", found.project_id));
+                }
+            }
+            Err(e) => log::warn!("匹配 Modrinth 项目失败，导出的列表不含链接: {}", e),
+        }
+    }
+
+    let versions_dir = paths::instance_game_dir(&config, instance_name).join("versions");
+    let info = version_info::detect(&versions_dir, instance_name);
+    let summary = format!(
+        "Minecraft {} · {}",
+        info.mc_version.as_deref().unwrap_or("未知版本"),
+        info.loader_type
+    );
+    let content = match format {
+        ModListFormat::Markdown => render_markdown(instance_name, &summary, &mods),
+        ModListFormat::Csv => render_csv(&mods),
+    };
+    let path = version_dir.join(format.file_name());
+    fs::write(&path, &content)?;
+
+    let linked_count = mods.iter().filter(|m| m.link.is_some()).count();
+    log::info!(
+        "已导出实例 {} 的模组列表（{} 个模组，{} 个有链接）: {}",
+        instance_name,
+        mods.len(),
+        linked_count,
+        path.display()
+    );
+    Ok(ExportedModList {
+        content,
+        path: path.to_string_lossy().to_string(),
+        mod_count: mods.len(),
+        linked_count,
+    })
+}
@@ -0,0 +1,154 @@
+//! 整合包安装后校验
+//!
+//! 安装完成后检查加载器版本 JSON 能否解析（含继承链）、索引中的文件是否齐全且哈希一致、
+//! 模组声明的必需前置是否都已安装，结果保存在实例目录中，供实例列表显示健康标记。
+
+use crate::errors::LauncherError;
+use crate::models::modpack::{ModpackHealthIssue, ModpackHealthReport, ModpackHealthStatus};
+use crate::services::paths::{self, ContentKind};
+use crate::services::{config, launcher};
+use crate::utils::file_utils;
+use crate::utils::mod_metadata;
+use log::{info, warn};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// 校验报告文件名（位于实例版本目录）
+const HEALTH_FILE: &str = "ar1s_health.json";
+
+/// 整合包索引中的文件（相对实例目录的路径和 SHA1）
+pub struct ExpectedFile {
+    pub path: String,
+    pub sha1: String,
+}
+
+fn issue(category: &str, message: String, fatal: bool) -> ModpackHealthIssue {
+    ModpackHealthIssue {
+        category: category.to_string(),
+        message,
+        fatal,
+    }
+}
+
+/// 检查索引中的文件是否存在且哈希一致
+fn check_files(instance_dir: &Path, expected: &[ExpectedFile], issues: &mut Vec<ModpackHealthIssue>) {
+    for file in expected {
+        let path = instance_dir.join(&file.path);
+        if !path.is_file() {
+            issues.push(issue("file", format!("文件缺失: {}", file.path), true));
+            continue;
+        }
+        match file_utils::sha1_file(&path, None) {
+            Ok(hash) if hash.eq_ignore_ascii_case(&file.sha1) => {}
+            Ok(_) => issues.push(issue("file", format!("文件哈希不匹配: {}", file.path), true)),
+            Err(e) => issues.push(issue("file", format!("无法读取文件 {}: {}", file.path, e), true)),
+        }
+    }
+}
+
+/// 检查模组的必需前置，返回检查的模组数量
+pub(crate) fn check_dependencies(mods_dir: &Path, issues: &mut Vec<ModpackHealthIssue>) -> usize {
+    let Ok(entries) = fs::read_dir(mods_dir) else {
+        return 0;
+    };
+    let mods: Vec<_> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "jar"))
+        .filter_map(|p| match mod_metadata::read_mod_metadata(&p) {
+            Ok(meta) => meta,
+            Err(e) => {
+                warn!("读取模组元数据失败 {}: {}", p.display(), e);
+                None
+            }
+        })
+        .collect();
+
+    let available: HashSet<String> = mods
+        .iter()
+        .flat_map(|m| std::iter::once(&m.mod_id).chain(&m.provides))
+        .map(|id| id.to_lowercase())
+        .collect();
+    for meta in &mods {
+        for dep in &meta.dependencies {
+            if !available.contains(&dep.to_lowercase()) {
+                let name = meta.name.as_deref().unwrap_or(&meta.mod_id);
+                issues.push(issue(
+                    "dependency",
+                    format!("{} 需要前置模组 {}，但未安装，可能导致游戏无法启动", name, dep),
+                    false,
+                ));
+            }
+        }
+    }
+    mods.len()
+}
+
+/// 校验整合包实例并保存报告
+pub fn validate_modpack_instance(
+    instance_name: &str,
+    expected: &[ExpectedFile],
+) -> Result<ModpackHealthReport, LauncherError> {
+    let config = config::load_config()?;
+    let instance_dir = paths::version_dir(&config, instance_name);
+    let mut issues = Vec::new();
+
+    for problem in launcher::validate_version_json(instance_name)? {
+        issues.push(issue(
+            "version",
+            format!("{}: {}", problem.location, problem.message),
+            problem.fatal,
+        ));
+    }
+    check_files(&instance_dir, expected, &mut issues);
+    let mods_checked = check_dependencies(
+        &paths::content_dir(&config, instance_name, ContentKind::Mods),
+        &mut issues,
+    );
+
+    let status = if issues.iter().any(|i| i.fatal) {
+        ModpackHealthStatus::Red
+    } else if issues.is_empty() {
+        ModpackHealthStatus::Green
+    } else {
+        ModpackHealthStatus::Yellow
+    };
+    let report = ModpackHealthReport {
+        instance_name: instance_name.to_string(),
+        status,
+        checked_at: chrono::Utc::now().to_rfc3339(),
+        files_checked: expected.len(),
+        mods_checked,
+        issues,
+    };
+    fs::write(
+        instance_dir.join(HEALTH_FILE),
+        serde_json::to_string_pretty(&report)?,
+    )?;
+    info!(
+        "整合包 {} 校验完成: {:?}，{} 个问题",
+        instance_name,
+        report.status,
+        report.issues.len()
+    );
+    Ok(report)
+}
+
+/// 读取实例保存的校验报告，非整合包实例或未校验时返回 None
+pub fn get_health_report(instance_name: &str) -> Result<Option<ModpackHealthReport>, LauncherError> {
+    let config = config::load_config()?;
+    let path = paths::version_dir(&config, instance_name).join(HEALTH_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+}
+
+/// 实例列表使用的健康标记（只读取报告文件，不重新校验）
+pub fn read_health_status(instance_dir: &Path) -> Option<ModpackHealthStatus> {
+    let content = fs::read_to_string(instance_dir.join(HEALTH_FILE)).ok()?;
+    serde_json::from_str::<ModpackHealthReport>(&content)
+        .ok()
+        .map(|r| r.status)
+}
@@ -1,40 +1,33 @@
 use crate::errors::LauncherError;
 use crate::models::modpack::*;
-use crate::services::{config, download, loaders, modrinth};
+use crate::models::ProgressStage;
+use crate::services::{config, download, fs_ops, http_client, instance, instance_lock, loaders, lockfile, meta_cache, modpack_health, modpack_update, modrinth, os_progress, paths, tasks};
+use crate::services::hooks::{self, HookPoint};
+use crate::services::instance_lock::InstanceOperation;
 use crate::utils::file_utils::{self, validate_instance_name_or_error};
+use crate::utils::long_path;
 use log::{debug, error, info, warn};
 use reqwest::Client;
+use sha1::{Digest, Sha1};
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use tauri::Emitter;
-
-/// 全局取消标志
-static MODPACK_CANCEL_FLAG: std::sync::OnceLock<Arc<AtomicBool>> = std::sync::OnceLock::new();
-
-/// 获取或初始化取消标志
-fn get_cancel_flag() -> Arc<AtomicBool> {
-    MODPACK_CANCEL_FLAG
-        .get_or_init(|| Arc::new(AtomicBool::new(false)))
-        .clone()
-}
-
-/// 重置取消标志
-pub fn reset_modpack_cancel_flag() {
-    // 确保标志已初始化，然后重置为 false
-    get_cancel_flag().store(false, Ordering::SeqCst);
-}
-
-/// 设置取消标志
-pub fn set_modpack_cancel_flag() {
-    get_cancel_flag().store(true, Ordering::SeqCst);
-}
-
-/// 检查是否已取消
+use std::path::{Component, Path, PathBuf};
+use crate::services::event_sink::EventSink;
+
+/// 任务栏进度中的整合包安装操作
+const OS_PROGRESS_KEY: &str = "modpack";
+/// CurseForge API 镜像（MCIM，无需 API 密钥）
+const CURSEFORGE_API_MIRROR: &str = "https://mod.mcimirror.top/curseforge/v1";
+/// CurseForge 文件 CDN
+const CURSEFORGE_CDN: &str = "https://edge.forgecdn.net/files";
+/// CurseForge 文件 CDN 镜像
+const CURSEFORGE_CDN_MIRROR: &str = "https://mod.mcimirror.top/files";
+
+/// 检查当前安装任务是否已取消
 fn is_cancelled() -> bool {
-    get_cancel_flag().load(Ordering::SeqCst)
+    tasks::current_token().is_some_and(|t| t.is_cancelled())
 }
 
 /// 检查取消状态，如果已取消则返回错误
@@ -48,9 +41,50 @@ fn check_cancelled() -> Result<(), LauncherError> {
 
 #[derive(Clone, serde::Serialize)]
 pub struct ModpackInstallProgress {
+    pub stage: ProgressStage,
     pub progress: u8,
     pub message: String,
     pub indeterminate: bool,
+    /// 下载整合包文件时的已下载字节数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downloaded_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_bytes: Option<u64>,
+}
+
+fn emit_progress(window: &EventSink, stage: ProgressStage, progress: u8, message: &str, indeterminate: bool) {
+    os_progress::update(window, OS_PROGRESS_KEY, progress);
+    let _ = window.emit(
+        "modpack-install-progress",
+        ModpackInstallProgress {
+            stage,
+            progress,
+            message: message.to_string(),
+            indeterminate,
+            downloaded_bytes: None,
+            total_bytes: None,
+        },
+    );
+}
+
+/// 整合包来源
+enum PackOrigin {
+    /// Modrinth 上的整合包版本
+    Modrinth { modpack_id: String, version_id: String },
+    /// 本地的 .mrpack 或 CurseForge 整合包 zip
+    LocalFile(PathBuf),
+}
+
+/// 整合包信息，写入实例配置
+struct PackMeta {
+    name: String,
+    /// modrinth / local
+    source: &'static str,
+    modpack_id: Option<String>,
+    version: String,
+    loaders: Vec<String>,
+    /// 索引中没有 Minecraft 版本时使用
+    game_version: Option<String>,
 }
 
 /// Modrinth index.json 中的文件定义
@@ -104,6 +138,119 @@ struct ModrinthDependencies {
     neoforge: Option<String>,
 }
 
+/// CurseForge manifest.json 结构
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgeManifest {
+    minecraft: CurseForgeMinecraft,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    files: Vec<CurseForgeManifestFile>,
+    #[serde(default = "default_overrides")]
+    overrides: String,
+}
+
+fn default_overrides() -> String {
+    "overrides".to_string()
+}
+
+/// manifest.json 中的 overrides 只能是整合包根目录下的单个目录名
+fn curseforge_overrides_dir(overrides: &str) -> Result<String, LauncherError> {
+    let mut components = Path::new(overrides).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(name)), None) => Ok(name.to_string_lossy().to_string()),
+        _ => Err(LauncherError::Custom(format!("整合包 manifest.json 中的 overrides 目录无效: {}", overrides))),
+    }
+}
+
+/// 校验 `modrinth.index.json` 中文件的相对路径，只允许普通路径段，拒绝绝对路径和 `..`
+fn modrinth_file_path(path: &str) -> Result<PathBuf, LauncherError> {
+    let relative = Path::new(path);
+    let valid = relative.components().next().is_some()
+        && relative.components().all(|c| matches!(c, Component::Normal(_)));
+    if valid {
+        Ok(relative.to_path_buf())
+    } else {
+        Err(LauncherError::Custom(format!("整合包索引中的文件路径无效: {}", path)))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgeMinecraft {
+    version: String,
+    #[serde(default)]
+    mod_loaders: Vec<CurseForgeModLoader>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CurseForgeModLoader {
+    /// 如 `forge-47.2.0`、`fabric-0.15.7`
+    id: String,
+    #[serde(default)]
+    primary: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct CurseForgeManifestFile {
+    #[serde(rename = "projectID")]
+    project_id: u64,
+    #[serde(rename = "fileID")]
+    file_id: u64,
+    #[serde(default = "default_required")]
+    required: bool,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+struct CurseForgeResponse<T> {
+    data: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgeFile {
+    id: u64,
+    mod_id: u64,
+    file_name: String,
+    #[serde(default)]
+    download_url: Option<String>,
+    #[serde(default)]
+    hashes: Vec<CurseForgeFileHash>,
+    #[serde(default)]
+    file_length: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CurseForgeFileHash {
+    value: String,
+    /// 1 为 SHA1，2 为 MD5
+    algo: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgeMod {
+    id: u64,
+    #[serde(default)]
+    class_id: Option<u32>,
+}
+
+/// CurseForge 项目分类对应的安装目录
+fn curseforge_class_dir(class_id: Option<u32>) -> &'static str {
+    match class_id {
+        Some(12) => "resourcepacks",
+        Some(6552) => "shaderpacks",
+        _ => "mods",
+    }
+}
+
 pub struct ModpackInstaller {
     modrinth_service: modrinth::ModrinthService,
     http_client: Client,
@@ -113,7 +260,7 @@ impl ModpackInstaller {
     pub fn new() -> Self {
         Self {
             modrinth_service: modrinth::ModrinthService::new(),
-            http_client: Client::builder()
+            http_client: http_client::apply_trust(Client::builder())
                 .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
                 .build()
                 .unwrap_or_else(|_| Client::new()),
@@ -124,31 +271,70 @@ impl ModpackInstaller {
     pub async fn install_modrinth_modpack(
         &self,
         options: ModpackInstallOptions,
-        window: &tauri::Window,
+        window: &EventSink,
+    ) -> Result<(), LauncherError> {
+        let origin = PackOrigin::Modrinth {
+            modpack_id: options.modpack_id,
+            version_id: options.version_id,
+        };
+        self.install_modpack(&options.instance_name, origin, window).await
+    }
+
+    /// 从本地文件安装整合包（.mrpack 或 CurseForge 整合包 zip，按包内的索引文件识别）
+    pub async fn install_modpack_from_file(
+        &self,
+        file_path: &str,
+        instance_name: &str,
+        window: &EventSink,
+    ) -> Result<(), LauncherError> {
+        let origin = PackOrigin::LocalFile(PathBuf::from(file_path));
+        self.install_modpack(instance_name, origin, window).await
+    }
+
+    async fn install_modpack(
+        &self,
+        instance_name: &str,
+        origin: PackOrigin,
+        window: &EventSink,
     ) -> Result<(), LauncherError> {
-        // 重置取消标志
-        reset_modpack_cancel_flag();
-        
         // 验证实例名称
-        validate_instance_name_or_error(&options.instance_name)?;
+        validate_instance_name_or_error(instance_name)?;
+        let _lock = instance_lock::acquire(instance_name, InstanceOperation::ModpackInstall)?;
         
         let config = config::load_config()?;
         let game_dir = PathBuf::from(&config.game_dir);
-        let instance_dir = game_dir.join("versions").join(&options.instance_name);
+        let instance_dir = game_dir.join("versions").join(instance_name);
         let temp_dir = game_dir.join("temp");
-        let extract_dir = temp_dir.join(format!("{}_extract", &options.instance_name));
+        let extract_dir = temp_dir.join(format!("{}_extract", instance_name));
 
-        // 1. 检查实例是否已存在
-        if instance_dir.exists() {
+        // 1. 检查实例是否已存在（任一已登记的游戏目录中）
+        if instance::instance_name_taken(&paths::game_dirs(&config), instance_name) {
             return Err(LauncherError::Custom(format!(
                 "名为 '{}' 的实例已存在，请使用其他名称",
-                options.instance_name
+                instance_name
             )));
         }
 
         // 执行安装，如果失败或取消则清理
-        let result = self.do_install_modrinth_modpack(&options, window, &game_dir, &instance_dir, &temp_dir, &extract_dir).await;
+        let result = self
+            .do_install_modpack(instance_name, &origin, window, &game_dir, &instance_dir, &temp_dir, &extract_dir)
+            .await;
         
+        os_progress::finish(window, OS_PROGRESS_KEY, result.is_err() && !is_cancelled());
+
+        if let Ok(meta) = &result {
+            let instance_name = instance_name.to_string();
+            let (modpack_id, version) = match &origin {
+                PackOrigin::Modrinth { modpack_id, version_id } => (modpack_id.clone(), version_id.clone()),
+                PackOrigin::LocalFile(_) => (String::new(), meta.version.clone()),
+            };
+            let hook_env = [("AR1S_MODPACK_ID", modpack_id), ("AR1S_MODPACK_VERSION", version)];
+            let _ = tokio::task::spawn_blocking(move || {
+                hooks::run_hook_logged(&instance_name, HookPoint::PostModpackInstall, &hook_env)
+            })
+            .await;
+        }
+
         // 如果安装失败或被取消，清理已创建的目录
         if result.is_err() {
             info!("安装失败或被取消，清理已创建的文件...");
@@ -168,60 +354,43 @@ impl ModpackInstaller {
             }
         }
         
-        result
+        result.map(|_| ())
     }
-    
-    /// 执行实际的整合包安装逻辑
-    async fn do_install_modrinth_modpack(
+
+    /// 获取 Modrinth 整合包信息并下载指定版本的 .mrpack
+    async fn download_modrinth_pack(
         &self,
-        options: &ModpackInstallOptions,
-        window: &tauri::Window,
-        game_dir: &PathBuf,
-        instance_dir: &PathBuf,
+        modpack_id: &str,
+        version_id: &str,
         temp_dir: &PathBuf,
-        extract_dir: &PathBuf,
-    ) -> Result<(), LauncherError> {
-        // 发送进度更新
-        let send_progress = |progress: u8, message: &str, indeterminate: bool| {
-            let _ = window.emit(
-                "modpack-install-progress",
-                ModpackInstallProgress {
-                    progress,
-                    message: message.to_string(),
-                    indeterminate,
-                },
-            );
-        };
-
-        send_progress(5, "检查实例目录...", false);
-        check_cancelled()?;
-
-        send_progress(10, "获取整合包信息...", false);
+        window: &EventSink,
+    ) -> Result<(PathBuf, PackMeta), LauncherError> {
+        emit_progress(window, ProgressStage::FetchingManifest, 10, "获取整合包信息...", false);
         check_cancelled()?;
 
         // 2. 获取整合包详细信息
         let modpack = self
             .modrinth_service
-            .get_modpack(&options.modpack_id)
+            .get_modpack(modpack_id)
             .await
             .map_err(|e| LauncherError::Custom(format!("获取整合包信息失败: {}", e)))?;
 
-        send_progress(15, "获取整合包版本...", false);
+        emit_progress(window, ProgressStage::FetchingManifest, 15, "获取整合包版本...", false);
         check_cancelled()?;
 
         // 3. 获取指定版本信息
         let versions = self
             .modrinth_service
-            .get_modpack_versions(&options.modpack_id, None, None)
+            .get_modpack_versions(modpack_id, None, None)
             .await
             .map_err(|e| LauncherError::Custom(format!("获取整合包版本失败: {}", e)))?;
 
         let selected_version = versions
             .iter()
-            .find(|v| v.id == options.version_id)
+            .find(|v| v.id == version_id)
             .ok_or_else(|| LauncherError::Custom("未找到指定的整合包版本".to_string()))?;
 
-        send_progress(20, "下载整合包文件...", false);
+        emit_progress(window, ProgressStage::DownloadingModpack, 20, "下载整合包文件...", false);
         check_cancelled()?;
 
         // 4. 下载整合包文件
@@ -238,12 +407,215 @@ impl ModpackInstaller {
 
         let modpack_file_path = temp_dir.join(&primary_file.filename);
 
-        self.modrinth_service
-            .download_modpack_file(&primary_file.url, &modpack_file_path)
+        // 下载占 20%~35% 的进度，按时间节流避免事件过多
+        let mut last_emit = std::time::Instant::now();
+        let download = self
+            .modrinth_service
+            .download_modpack_file(
+                &primary_file.url,
+                &modpack_file_path,
+                Some(&primary_file.hashes.sha1),
+                |downloaded, total| {
+                    if last_emit.elapsed() >= std::time::Duration::from_millis(200) || downloaded == total {
+                        last_emit = std::time::Instant::now();
+                        let ratio = if total > 0 { downloaded as f64 / total as f64 } else { 0.0 };
+                        os_progress::update(window, OS_PROGRESS_KEY, 20 + (ratio * 15.0) as u8);
+                        let _ = window.emit(
+                            "modpack-install-progress",
+                            ModpackInstallProgress {
+                                stage: ProgressStage::DownloadingModpack,
+                                progress: 20 + (ratio * 15.0) as u8,
+                                message: format!(
+                                    "下载整合包文件... {:.1}/{:.1} MB",
+                                    downloaded as f64 / 1024.0 / 1024.0,
+                                    total as f64 / 1024.0 / 1024.0
+                                ),
+                                indeterminate: total == 0,
+                                downloaded_bytes: Some(downloaded),
+                                total_bytes: Some(total),
+                            },
+                        );
+                    }
+                    !is_cancelled()
+                },
+            )
+            .await;
+        check_cancelled()?;
+        download.map_err(|e| LauncherError::Custom(format!("下载整合包文件失败: {}", e)))?;
+
+        let meta = PackMeta {
+            name: modpack.title.clone(),
+            source: "modrinth",
+            modpack_id: Some(modpack.slug.clone()),
+            version: selected_version.version_number.clone(),
+            loaders: selected_version.loaders.clone(),
+            game_version: selected_version.game_versions.first().cloned(),
+        };
+        Ok((modpack_file_path, meta))
+    }
+
+    /// 将 CurseForge 清单转换为 Modrinth 索引：通过 API 镜像查询文件的下载地址和 SHA1
+    async fn curseforge_index(&self, manifest: CurseForgeManifest) -> Result<ModrinthIndex, LauncherError> {
+        let required: Vec<&CurseForgeManifestFile> = manifest.files.iter().filter(|f| f.required).collect();
+        let files: Vec<CurseForgeFile> = if required.is_empty() {
+            Vec::new()
+        } else {
+            let file_ids: Vec<u64> = required.iter().map(|f| f.file_id).collect();
+            self.curseforge_post("mods/files", &serde_json::json!({ "fileIds": file_ids }))
+                .await?
+        };
+        let found: HashMap<u64, u64> = files.iter().map(|f| (f.id, f.mod_id)).collect();
+        let mut missing: Vec<String> = required
+            .iter()
+            .filter(|f| !found.contains_key(&f.file_id))
+            .map(|f| format!("{}/{}", f.project_id, f.file_id))
+            .collect();
+
+        // 资源包、光影包按项目分类放入对应目录，查询失败时全部视为模组
+        let mod_ids: Vec<u64> = required.iter().map(|f| f.project_id).collect();
+        let classes: HashMap<u64, Option<u32>> = match self
+            .curseforge_post::<CurseForgeMod>("mods", &serde_json::json!({ "modIds": mod_ids }))
             .await
-            .map_err(|e| LauncherError::Custom(format!("下载整合包文件失败: {}", e)))?;
+        {
+            Ok(mods) => mods.into_iter().map(|m| (m.id, m.class_id)).collect(),
+            Err(e) => {
+                warn!("查询 CurseForge 项目分类失败: {}", e);
+                HashMap::new()
+            }
+        };
+
+        let mut index_files = Vec::with_capacity(files.len());
+        for file in files {
+            if file.file_name.contains(['/', '\\']) || file.file_name.contains("..") {
+                warn!("跳过可疑的 CurseForge 文件名: {}", file.file_name);
+                missing.push(format!("{}/{}", file.mod_id, file.id));
+                continue;
+            }
+            let Some(sha1) = file.hashes.iter().find(|h| h.algo == 1).map(|h| h.value.to_lowercase()) else {
+                warn!("CurseForge 文件 {} 没有 SHA1，跳过", file.file_name);
+                missing.push(format!("{}/{}", file.mod_id, file.id));
+                continue;
+            };
+            let cdn_path = format!("{}/{}/{}", file.id / 1000, file.id % 1000, file.file_name);
+            let mut downloads: Vec<String> = file.download_url.into_iter().collect();
+            for url in [
+                format!("{}/{}", CURSEFORGE_CDN, cdn_path),
+                format!("{}/{}", CURSEFORGE_CDN_MIRROR, cdn_path),
+            ] {
+                if !downloads.contains(&url) {
+                    downloads.push(url);
+                }
+            }
+            let dir = curseforge_class_dir(classes.get(&file.mod_id).copied().flatten());
+            index_files.push(ModrinthIndexFile {
+                path: format!("{}/{}", dir, file.file_name),
+                hashes: ModrinthIndexHashes { sha1, sha512: None },
+                downloads,
+                file_size: file.file_length,
+            });
+        }
+
+        // 缺少文件的整合包无法正常运行，不能静默安装
+        if !missing.is_empty() {
+            return Err(LauncherError::Custom(format!(
+                "CurseForge 整合包中有 {} 个文件无法获取（项目ID/文件ID）: {}",
+                missing.len(),
+                missing.join(", ")
+            )));
+        }
+
+        let mut dependencies = ModrinthDependencies {
+            minecraft: manifest.minecraft.version.clone(),
+            forge: None,
+            fabric: None,
+            fabric_loader: None,
+            quilt: None,
+            quilt_loader: None,
+            neoforge: None,
+        };
+        let loaders = &manifest.minecraft.mod_loaders;
+        let loader = loaders.iter().find(|l| l.primary).or_else(|| loaders.first());
+        if let Some((kind, version)) = loader.and_then(|l| l.id.split_once('-')) {
+            let version = Some(version.to_string());
+            match kind {
+                "forge" => dependencies.forge = version,
+                "neoforge" => dependencies.neoforge = version,
+                "fabric" => dependencies.fabric_loader = version,
+                "quilt" => dependencies.quilt_loader = version,
+                other => warn!("不支持的加载器: {}", other),
+            }
+        }
+
+        Ok(ModrinthIndex {
+            format_version: 1,
+            game: "minecraft".to_string(),
+            version_id: manifest.version.unwrap_or_default(),
+            name: manifest.name.unwrap_or_default(),
+            files: index_files,
+            dependencies,
+        })
+    }
+
+    async fn curseforge_post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+    ) -> Result<Vec<T>, LauncherError> {
+        let response = self
+            .http_client
+            .post(format!("{}/{}", CURSEFORGE_API_MIRROR, path))
+            .json(body)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(LauncherError::Custom(format!("查询 CurseForge 失败: HTTP {}", response.status())));
+        }
+        Ok(response.json::<CurseForgeResponse<T>>().await?.data)
+    }
+
+    /// 执行实际的整合包安装逻辑
+    #[allow(clippy::too_many_arguments)]
+    async fn do_install_modpack(
+        &self,
+        instance_name: &str,
+        origin: &PackOrigin,
+        window: &EventSink,
+        game_dir: &PathBuf,
+        instance_dir: &PathBuf,
+        temp_dir: &PathBuf,
+        extract_dir: &PathBuf,
+    ) -> Result<PackMeta, LauncherError> {
+        // 发送进度更新
+        let send_progress = |stage: ProgressStage, progress: u8, message: &str, indeterminate: bool| {
+            emit_progress(window, stage, progress, message, indeterminate);
+        };
+
+        send_progress(ProgressStage::Preparing, 5, "检查实例目录...", false);
+        check_cancelled()?;
+
+        // 本地文件由用户保管，安装完成后不删除
+        let (modpack_file_path, mut meta, is_temp_file) = match origin {
+            PackOrigin::Modrinth { modpack_id, version_id } => {
+                let (path, meta) = self.download_modrinth_pack(modpack_id, version_id, temp_dir, window).await?;
+                (path, meta, true)
+            }
+            PackOrigin::LocalFile(path) => {
+                if !path.is_file() {
+                    return Err(LauncherError::Custom(format!("整合包文件不存在: {}", path.display())));
+                }
+                let meta = PackMeta {
+                    name: path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default(),
+                    source: "local",
+                    modpack_id: None,
+                    version: String::new(),
+                    loaders: Vec::new(),
+                    game_version: None,
+                };
+                (path.clone(), meta, false)
+            }
+        };
 
-        send_progress(35, "解压整合包...", false);
+        send_progress(ProgressStage::Extracting, 35, "解压整合包...", false);
         check_cancelled()?;
 
         // 5. 解压整合包
@@ -256,71 +628,84 @@ impl ModpackInstaller {
             .await
             .map_err(|e| LauncherError::Custom(format!("解压整合包失败: {}", e)))?;
 
-        send_progress(45, "处理整合包配置...", false);
+        send_progress(ProgressStage::Configuring, 45, "处理整合包配置...", false);
         check_cancelled()?;
 
-        // 6. 处理整合包配置
+        // 6. 处理整合包配置：Modrinth 使用 modrinth.index.json，CurseForge 使用 manifest.json
         let index_path = extract_dir.join("modrinth.index.json");
-        let modrinth_index = if index_path.exists() {
+        let curseforge_manifest_path = extract_dir.join("manifest.json");
+        let (modrinth_index, override_dirs) = if index_path.exists() {
             let content = fs::read_to_string(&index_path)?;
-            Some(
-                serde_json::from_str::<ModrinthIndex>(&content)
-                    .map_err(|e| LauncherError::Custom(format!("解析 modrinth.index.json 失败: {}", e)))?,
-            )
+            let index = serde_json::from_str::<ModrinthIndex>(&content)
+                .map_err(|e| LauncherError::Custom(format!("解析 modrinth.index.json 失败: {}", e)))?;
+            (Some(index), vec!["overrides".to_string(), "client-overrides".to_string()])
+        } else if curseforge_manifest_path.exists() {
+            let content = fs::read_to_string(&curseforge_manifest_path)?;
+            let manifest = serde_json::from_str::<CurseForgeManifest>(&content)
+                .map_err(|e| LauncherError::Custom(format!("解析 manifest.json 失败: {}", e)))?;
+            send_progress(ProgressStage::FetchingManifest, 47, "获取 CurseForge 文件信息...", false);
+            let overrides = curseforge_overrides_dir(&manifest.overrides)?;
+            (Some(self.curseforge_index(manifest).await?), vec![overrides])
+        } else if matches!(origin, PackOrigin::LocalFile(_)) {
+            return Err(LauncherError::Custom(
+                "无法识别的整合包格式：缺少 modrinth.index.json 或 manifest.json".to_string(),
+            ));
         } else {
-            None
+            (None, vec!["overrides".to_string(), "client-overrides".to_string()])
         };
 
+        if let (PackOrigin::LocalFile(_), Some(index)) = (origin, &modrinth_index) {
+            if !index.name.is_empty() {
+                meta.name = index.name.clone();
+            }
+            meta.version = index.version_id.clone();
+        }
+
         // 创建实例目录
         fs::create_dir_all(&instance_dir)?;
 
-        send_progress(50, "复制整合包文件...", false);
+        send_progress(ProgressStage::CopyingFiles, 50, "复制整合包文件...", false);
         check_cancelled()?;
 
-        // 7. 复制 overrides 目录内容
-        let overrides_dir = extract_dir.join("overrides");
-        if overrides_dir.exists() {
-            info!("复制 overrides 目录到实例");
-            file_utils::copy_dir_all(&overrides_dir, &instance_dir)?;
-        }
-
-        // 也检查 client-overrides (某些整合包使用)
-        let client_overrides_dir = extract_dir.join("client-overrides");
-        if client_overrides_dir.exists() {
-            info!("复制 client-overrides 目录到实例");
-            file_utils::copy_dir_all(&client_overrides_dir, &instance_dir)?;
+        // 7. 复制 overrides 目录内容（Modrinth 还有 client-overrides，CurseForge 可自定义目录名）
+        for dir_name in &override_dirs {
+            let overrides_dir = extract_dir.join(dir_name);
+            if overrides_dir.is_dir() {
+                info!("复制 {} 目录到实例", dir_name);
+                fs_ops::copy_dir(&overrides_dir, &instance_dir, &format!("modpack-{}", dir_name), Some(window)).await?;
+            }
         }
 
         // 8. 下载 mods 和其他依赖文件
         if let Some(ref index) = modrinth_index {
-            send_progress(55, "下载模组文件...", false);
+            send_progress(ProgressStage::DownloadingMods, 55, "下载模组文件...", false);
             check_cancelled()?;
             self.download_modpack_files(&index.files, &instance_dir, window)
                 .await?;
         }
 
-        send_progress(75, "安装游戏版本...", false);
+        send_progress(ProgressStage::InstallingLoader, 75, "安装游戏版本...", false);
         check_cancelled()?;
 
         // 9. 安装基础游戏版本和加载器
         if let Some(ref index) = modrinth_index {
             self.install_game_and_loader(
                 &index.dependencies,
-                &options.instance_name,
+                instance_name,
                 &game_dir,
                 window,
             )
             .await?;
         }
 
-        send_progress(90, "创建实例配置...", false);
+        send_progress(ProgressStage::Configuring, 90, "创建实例配置...", false);
         check_cancelled()?;
 
         // 10. 创建实例配置文件
         let mc_version = modrinth_index
             .as_ref()
             .map(|i| i.dependencies.minecraft.clone())
-            .or_else(|| selected_version.game_versions.first().cloned())
+            .or_else(|| meta.game_version.clone())
             .unwrap_or_default();
 
         let loader_type = if modrinth_index.as_ref().map(|i| i.dependencies.forge.is_some()).unwrap_or(false) {
@@ -335,34 +720,102 @@ impl ModpackInstaller {
             None
         };
 
+        if meta.loaders.is_empty() {
+            meta.loaders = loader_type.map(|l| vec![l.to_string()]).unwrap_or_default();
+        }
+
+        // 本地导入的整合包没有 Modrinth 项目 ID，不参与更新检查
         let instance_config = serde_json::json!({
-            "id": options.instance_name.clone(),
-            "name": modpack.title.clone(),
+            "id": instance_name,
+            "name": meta.name.clone(),
             "type": "modpack",
-            "source": "modrinth",
-            "modpack_id": modpack.slug.clone(),
-            "modpack_version": selected_version.version_number.clone(),
+            "source": meta.source,
+            "format": if index_path.exists() { "modrinth" } else { "curseforge" },
+            "modpack_id": meta.modpack_id.clone(),
+            "modpack_version": meta.version.clone(),
             "minecraft": mc_version,
             "loader": loader_type,
-            "loaders": selected_version.loaders.clone(),
+            "loaders": meta.loaders.clone(),
             "created": chrono::Utc::now().to_rfc3339(),
         });
 
         let config_path = instance_dir.join("instance.json");
         fs::write(config_path, serde_json::to_string_pretty(&instance_config)?)?;
 
-        // 11. 清理临时文件
-        if modpack_file_path.exists() {
+        // 生成锁定文件，记录整合包索引中的下载地址
+        let known_downloads: HashMap<String, Vec<String>> = modrinth_index
+            .as_ref()
+            .map(|index| {
+                index
+                    .files
+                    .iter()
+                    .map(|f| (f.path.replace('\\', "/"), f.downloads.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let Err(e) = lockfile::generate_lockfile(instance_dir, instance_name, &known_downloads) {
+            warn!("生成锁定文件失败: {}", e);
+        }
+
+        // 12. 校验安装结果，报告保存到实例目录供实例列表显示
+        send_progress(ProgressStage::VerifyingFiles, 95, "校验安装结果...", false);
+        let expected: Vec<modpack_health::ExpectedFile> = modrinth_index
+            .as_ref()
+            .map(|index| {
+                index
+                    .files
+                    .iter()
+                    .map(|f| modpack_health::ExpectedFile {
+                        path: f.path.clone(),
+                        sha1: f.hashes.sha1.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let health_instance = instance_name.to_string();
+        match tokio::task::spawn_blocking(move || {
+            modpack_health::validate_modpack_instance(&health_instance, &expected)
+        })
+        .await?
+        {
+            Ok(report) => {
+                let _ = window.emit("modpack-health", &report);
+            }
+            Err(e) => warn!("校验整合包安装结果失败: {}", e),
+        }
+
+        // 记录安装的文件，供更新前比对（CurseForge 整合包的模组不在压缩包索引中，从转换后的索引补充）
+        match modpack_update::pack_files(&modpack_file_path) {
+            Ok(mut files) => {
+                for file in modrinth_index.iter().flat_map(|index| &index.files) {
+                    let path = file.path.replace('\\', "/");
+                    if !files.iter().any(|f| f.path == path) {
+                        files.push(ModpackFileRecord {
+                            path,
+                            sha1: file.hashes.sha1.to_lowercase(),
+                            size: file.file_size.unwrap_or(0),
+                        });
+                    }
+                }
+                if let Err(e) = modpack_update::write_install_record(instance_dir, &files) {
+                    warn!("保存整合包安装记录失败: {}", e);
+                }
+            }
+            Err(e) => warn!("读取整合包文件列表失败: {}", e),
+        }
+
+        // 13. 清理临时文件
+        if is_temp_file && modpack_file_path.exists() {
             let _ = fs::remove_file(&modpack_file_path);
         }
         if extract_dir.exists() {
             let _ = fs::remove_dir_all(&extract_dir);
         }
 
-        send_progress(100, "整合包安装完成！", false);
-        info!("整合包 {} 安装完成", options.instance_name);
+        send_progress(ProgressStage::Completed, 100, "整合包安装完成！", false);
+        info!("整合包 {} 安装完成", instance_name);
 
-        Ok(())
+        Ok(meta)
     }
 
 
@@ -371,7 +824,7 @@ impl ModpackInstaller {
         &self,
         files: &[ModrinthIndexFile],
         instance_dir: &PathBuf,
-        window: &tauri::Window,
+        window: &EventSink,
     ) -> Result<(), LauncherError> {
         let total_files = files.len();
         info!("开始下载 {} 个文件", total_files);
@@ -384,13 +837,16 @@ impl ModpackInstaller {
             let _ = window.emit(
                 "modpack-install-progress",
                 ModpackInstallProgress {
+                    stage: ProgressStage::DownloadingMods,
                     progress,
                     message: format!("下载文件 ({}/{}): {}", index + 1, total_files, file.path),
                     indeterminate: false,
+                    downloaded_bytes: None,
+                    total_bytes: None,
                 },
             );
 
-            let dest_path = instance_dir.join(&file.path);
+            let dest_path = long_path::extended(&instance_dir.join(modrinth_file_path(&file.path)?));
 
             // 创建父目录
             if let Some(parent) = dest_path.parent() {
@@ -399,19 +855,25 @@ impl ModpackInstaller {
 
             // 如果文件已存在且哈希匹配，跳过下载
             if dest_path.exists() {
-                debug!("文件已存在，跳过: {}", file.path);
-                continue;
+                match file_utils::sha1_file(&dest_path, None) {
+                    Ok(actual) if actual.eq_ignore_ascii_case(&file.hashes.sha1) => {
+                        debug!("文件已存在，跳过: {}", file.path);
+                        continue;
+                    }
+                    _ => warn!("已存在的文件校验失败，重新下载: {}", file.path),
+                }
             }
 
-            // 尝试从所有下载源下载
+            // 尝试从所有下载源下载（配置了 Modrinth 镜像时镜像优先）
             let mut downloaded = false;
-            for url in &file.downloads {
+            let urls = file.downloads.iter().flat_map(|url| modrinth::download_candidates(url));
+            for url in urls {
                 // 每次下载前检查取消状态
                 if is_cancelled() {
                     return Err(LauncherError::Custom("安装已取消".to_string()));
                 }
                 
-                match self.download_file_with_retry(url, &dest_path, 3).await {
+                match self.download_file_with_retry(&url, &dest_path, &file.hashes.sha1, 3).await {
                     Ok(_) => {
                         downloaded = true;
                         debug!("下载成功: {}", file.path);
@@ -425,18 +887,19 @@ impl ModpackInstaller {
 
             if !downloaded {
                 error!("无法下载文件: {}", file.path);
-                // 继续下载其他文件，不中断整个过程
+                return Err(LauncherError::Custom(format!("无法下载整合包文件: {}", file.path)));
             }
         }
 
         Ok(())
     }
 
-    /// 带重试的文件下载
+    /// 带重试的文件下载，内容需与索引中的 SHA1 一致（防止镜像返回被篡改的文件）
     async fn download_file_with_retry(
         &self,
         url: &str,
         dest: &PathBuf,
+        sha1: &str,
         max_retries: u32,
     ) -> Result<(), LauncherError> {
         let mut last_error = None;
@@ -451,6 +914,14 @@ impl ModpackInstaller {
                     if response.status().is_success() {
                         match response.bytes().await {
                             Ok(bytes) => {
+                                let actual = format!("{:x}", Sha1::digest(&bytes));
+                                if !actual.eq_ignore_ascii_case(sha1) {
+                                    // 内容错误时重试同一地址没有意义
+                                    return Err(LauncherError::Custom(format!(
+                                        "哈希不匹配（期望 {}，实际 {}）",
+                                        sha1, actual
+                                    )));
+                                }
                                 fs::write(dest, &bytes)?;
                                 return Ok(());
                             }
@@ -479,7 +950,7 @@ impl ModpackInstaller {
         deps: &ModrinthDependencies,
         instance_name: &str,
         game_dir: &PathBuf,
-        window: &tauri::Window,
+        window: &EventSink,
     ) -> Result<(), LauncherError> {
         let mc_version = &deps.minecraft;
         info!("安装 Minecraft {}", mc_version);
@@ -609,6 +1080,7 @@ impl ModpackInstaller {
             }
             
             let outpath = extract_dir.join(&file_name);
+            let long_outpath = long_path::extended(&outpath);
             
             // 确保解压路径在目标目录内
             let canonical_extract = extract_dir.canonicalize().unwrap_or_else(|_| extract_dir.clone());
@@ -620,14 +1092,14 @@ impl ModpackInstaller {
             }
 
             if file_name.ends_with('/') {
-                fs::create_dir_all(&outpath)?;
+                fs::create_dir_all(&long_outpath)?;
             } else {
-                if let Some(p) = outpath.parent() {
+                if let Some(p) = long_outpath.parent() {
                     if !p.exists() {
                         fs::create_dir_all(p)?;
                     }
                 }
-                let mut outfile = fs::File::create(&outpath)?;
+                let mut outfile = fs::File::create(&long_outpath)?;
                 std::io::copy(&mut file, &mut outfile)?;
             }
         }
@@ -658,8 +1130,34 @@ impl ModpackInstaller {
         game_versions: Option<Vec<String>>,
         loaders: Option<Vec<String>>,
     ) -> Result<Vec<ModrinthModpackVersion>, LauncherError> {
-        self.modrinth_service
-            .get_modpack_versions(project_id, game_versions, loaders)
-            .await
+        let key = modpack_versions_cache_key(project_id, &game_versions, &loaders);
+        meta_cache::get_or_fetch(&key, meta_cache::DEFAULT_TTL, || {
+            self.modrinth_service
+                .get_modpack_versions(project_id, game_versions, loaders)
+        })
+        .await
+    }
+
+    /// 重新获取整合包的全部版本并更新缓存（用于后台检查更新）
+    pub async fn refresh_modpack_versions(&self, project_id: &str) -> Result<(), LauncherError> {
+        let key = modpack_versions_cache_key(project_id, &None, &None);
+        meta_cache::refresh(&key, || {
+            self.modrinth_service.get_modpack_versions(project_id, None, None)
+        })
+        .await
     }
 }
+
+/// 整合包版本列表的缓存键
+fn modpack_versions_cache_key(
+    project_id: &str,
+    game_versions: &Option<Vec<String>>,
+    loaders: &Option<Vec<String>>,
+) -> String {
+    format!(
+        "modrinth_versions:{}:{}:{}",
+        project_id,
+        game_versions.as_ref().map(|v| v.join(",")).unwrap_or_default(),
+        loaders.as_ref().map(|v| v.join(",")).unwrap_or_default()
+    )
+}
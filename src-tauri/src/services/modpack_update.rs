@@ -0,0 +1,260 @@
+//! 整合包更新预览
+//!
+//! 安装整合包时记录安装的每个文件及其哈希（`ar1s_modpack_files.json`）。
+//! 更新前下载新版本的 .mrpack，与记录和实例当前的文件比对，列出新增、移除和变化的文件，
+//! 并标出用户修改过的文件（如调整过的配置），让用户在更新前知道哪些改动会被覆盖。
+
+use crate::errors::LauncherError;
+use crate::models::modpack::{ModpackFileDiff, ModpackFileRecord, ModpackUpdatePreview};
+use crate::services::{config, modpack_installer, modrinth, paths};
+use crate::utils::file_utils;
+use serde::Deserialize;
+use sha1::{Digest, Sha1};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// 安装记录文件名（位于实例版本目录）
+const INSTALL_RECORD_FILE: &str = "ar1s_modpack_files.json";
+
+/// 覆盖目录前缀（按整合包中的优先级，后者覆盖前者）
+const OVERRIDE_PREFIXES: [&str; 2] = ["overrides/", "client-overrides/"];
+
+#[derive(Deserialize)]
+struct IndexFile {
+    path: String,
+    hashes: IndexHashes,
+    #[serde(rename = "fileSize")]
+    file_size: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct IndexHashes {
+    sha1: String,
+}
+
+#[derive(Deserialize)]
+struct PackIndex {
+    files: Vec<IndexFile>,
+}
+
+/// 读取 .mrpack 中会安装到实例的全部文件（索引文件和覆盖目录）
+pub fn pack_files(mrpack: &Path) -> Result<Vec<ModpackFileRecord>, LauncherError> {
+    read_pack(mrpack).map(|(files, _)| files)
+}
+
+/// 读取整合包文件列表，同时返回需要单独下载的索引文件路径
+fn read_pack(mrpack: &Path) -> Result<(Vec<ModpackFileRecord>, HashSet<String>), LauncherError> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(mrpack)?)?;
+    let mut files: HashMap<String, ModpackFileRecord> = HashMap::new();
+
+    if let Ok(mut entry) = archive.by_name("modrinth.index.json") {
+        let mut content = String::new();
+        entry.read_to_string(&mut content)?;
+        let index: PackIndex = serde_json::from_str(&content)
+            .map_err(|e| LauncherError::Custom(format!("解析 modrinth.index.json 失败: {}", e)))?;
+        for file in index.files {
+            let path = file.path.replace('\\', "/");
+            files.insert(
+                path.clone(),
+                ModpackFileRecord {
+                    path,
+                    sha1: file.hashes.sha1.to_lowercase(),
+                    size: file.file_size.unwrap_or(0),
+                },
+            );
+        }
+    }
+
+    let mut indexed: HashSet<String> = files.keys().cloned().collect();
+
+    for prefix in OVERRIDE_PREFIXES {
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            let Some(path) = entry.name().strip_prefix(prefix).map(str::to_string) else {
+                continue;
+            };
+            if entry.is_dir() || path.is_empty() || path.contains("..") {
+                continue;
+            }
+            let mut data = Vec::with_capacity(entry.size() as usize);
+            entry.read_to_end(&mut data)?;
+            // 覆盖目录中的同名文件取代索引文件，随整合包一起下载
+            indexed.remove(&path);
+            files.insert(
+                path.clone(),
+                ModpackFileRecord {
+                    path,
+                    sha1: format!("{:x}", Sha1::digest(&data)),
+                    size: data.len() as u64,
+                },
+            );
+        }
+    }
+
+    let mut files: Vec<ModpackFileRecord> = files.into_values().collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok((files, indexed))
+}
+
+/// 保存整合包安装记录
+pub fn write_install_record(instance_dir: &Path, files: &[ModpackFileRecord]) -> Result<(), LauncherError> {
+    fs::write(
+        instance_dir.join(INSTALL_RECORD_FILE),
+        serde_json::to_string_pretty(files)?,
+    )?;
+    Ok(())
+}
+
+fn read_install_record(instance_dir: &Path) -> Option<Vec<ModpackFileRecord>> {
+    let content = fs::read_to_string(instance_dir.join(INSTALL_RECORD_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// 实例中文件的当前大小和哈希（不存在时为 None）
+fn current_file(instance_dir: &Path, path: &str) -> Option<(u64, String)> {
+    let full = instance_dir.join(path);
+    let size = fs::metadata(&full).ok().filter(|m| m.is_file())?.len();
+    let sha1 = file_utils::sha1_file(&full, None).ok()?;
+    Some((size, sha1.to_lowercase()))
+}
+
+/// 比对新版本的文件与实例当前状态
+fn diff_files(
+    instance_dir: &Path,
+    new_files: &[ModpackFileRecord],
+    record: Option<&[ModpackFileRecord]>,
+) -> (Vec<ModpackFileDiff>, Vec<ModpackFileDiff>, Vec<ModpackFileDiff>, usize) {
+    let installed: HashMap<&str, &ModpackFileRecord> = record
+        .unwrap_or_default()
+        .iter()
+        .map(|f| (f.path.as_str(), f))
+        .collect();
+    // 与安装时内容不同即视为用户修改；没有安装记录时无法判断
+    let user_modified = |path: &str, current: &Option<(u64, String)>| {
+        match (installed.get(path), current) {
+            (Some(original), Some((_, sha1))) => !original.sha1.eq_ignore_ascii_case(sha1),
+            _ => false,
+        }
+    };
+
+    let (mut added, mut changed) = (Vec::new(), Vec::new());
+    let mut unchanged = 0;
+    for file in new_files {
+        let current = current_file(instance_dir, &file.path);
+        match &current {
+            None => added.push(ModpackFileDiff {
+                path: file.path.clone(),
+                old_size: None,
+                new_size: Some(file.size),
+                user_modified: false,
+            }),
+            Some((_, sha1)) if sha1.eq_ignore_ascii_case(&file.sha1) => unchanged += 1,
+            Some((size, _)) => changed.push(ModpackFileDiff {
+                path: file.path.clone(),
+                old_size: Some(*size),
+                new_size: Some(file.size),
+                user_modified: user_modified(&file.path, &current),
+            }),
+        }
+    }
+
+    // 上次安装的文件不在新版本中时会被移除（已被用户删除的忽略）
+    let new_paths: HashSet<&str> = new_files.iter().map(|f| f.path.as_str()).collect();
+    let mut removed = Vec::new();
+    for (path, _) in installed.iter().filter(|(p, _)| !new_paths.contains(**p)) {
+        let current = current_file(instance_dir, path);
+        if let Some((size, _)) = &current {
+            removed.push(ModpackFileDiff {
+                path: path.to_string(),
+                old_size: Some(*size),
+                new_size: None,
+                user_modified: user_modified(*path, &current),
+            });
+        }
+    }
+    removed.sort_by(|a, b| a.path.cmp(&b.path));
+    (added, removed, changed, unchanged)
+}
+
+/// 读取实例的整合包来源，返回 (实例目录, 整合包 ID, 当前版本号)
+fn modpack_source(instance_name: &str) -> Result<(PathBuf, String, Option<String>), LauncherError> {
+    let config = config::load_config()?;
+    let instance_dir = paths::version_dir(&config, instance_name);
+    let content = fs::read_to_string(instance_dir.join("instance.json"))
+        .map_err(|_| LauncherError::Custom(format!("实例 '{}' 不是整合包实例", instance_name)))?;
+    let json: serde_json::Value = serde_json::from_str(&content)?;
+    if json["source"].as_str() != Some("modrinth") {
+        return Err(LauncherError::Custom(format!(
+            "实例 '{}' 不是 Modrinth 整合包实例",
+            instance_name
+        )));
+    }
+    let modpack_id = json["modpack_id"]
+        .as_str()
+        .ok_or_else(|| LauncherError::Custom("实例缺少整合包 ID".to_string()))?
+        .to_string();
+    let current_version = json["modpack_version"].as_str().map(str::to_string);
+    Ok((instance_dir, modpack_id, current_version))
+}
+
+/// 预览把整合包实例更新到指定版本（版本 ID 或版本号）会带来的文件变化
+pub async fn preview_modpack_update(
+    instance_name: &str,
+    new_version: &str,
+) -> Result<ModpackUpdatePreview, LauncherError> {
+    let (instance_dir, modpack_id, current_version) = modpack_source(instance_name)?;
+
+    let versions = modpack_installer::ModpackInstaller::new()
+        .get_modpack_versions(&modpack_id, None, None)
+        .await?;
+    let version = versions
+        .iter()
+        .find(|v| v.id == new_version || v.version_number == new_version)
+        .ok_or_else(|| LauncherError::Custom(format!("未找到整合包版本 {}", new_version)))?;
+    let pack = version
+        .files
+        .iter()
+        .find(|f| f.primary)
+        .or_else(|| version.files.first())
+        .ok_or_else(|| LauncherError::Custom("整合包没有可用的文件".to_string()))?;
+
+    // 下载到临时目录，实际更新时可直接复用
+    let config = config::load_config()?;
+    let temp_dir = PathBuf::from(&config.game_dir).join("temp");
+    fs::create_dir_all(&temp_dir)?;
+    let mrpack = temp_dir.join(&pack.filename);
+    modrinth::ModrinthService::new()
+        .download_modpack_file(&pack.url, &mrpack, Some(&pack.hashes.sha1), |_, _| true)
+        .await?;
+
+    let pack_size = pack.size;
+    let (instance, version_number) = (instance_name.to_string(), version.version_number.clone());
+    tokio::task::spawn_blocking(move || {
+        let (new_files, indexed) = read_pack(&mrpack)?;
+        let record = read_install_record(&instance_dir);
+        let (added, removed, changed, unchanged) =
+            diff_files(&instance_dir, &new_files, record.as_deref());
+        // 覆盖目录中的文件包含在整合包文件里，无需单独下载
+        let download_bytes = pack_size
+            + added
+                .iter()
+                .chain(&changed)
+                .filter(|f| indexed.contains(&f.path))
+                .filter_map(|f| f.new_size)
+                .sum::<u64>();
+        Ok(ModpackUpdatePreview {
+            instance_name: instance,
+            current_version,
+            new_version: version_number,
+            added,
+            removed,
+            changed,
+            unchanged,
+            download_bytes,
+            has_install_record: record.is_some(),
+        })
+    })
+    .await?
+}
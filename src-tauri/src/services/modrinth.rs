@@ -1,12 +1,39 @@
 use crate::errors::LauncherError;
 use crate::models::modpack::*;
+use crate::utils::file_utils;
+use log::warn;
 use reqwest::Client;
 use serde_json::Value;
 use std::collections::HashMap;
+use tokio::io::AsyncWriteExt;
 
 const MODRINTH_API_BASE: &str = "https://api.modrinth.com/v2";
+/// Modrinth 文件 CDN 地址
+const MODRINTH_CDN: &str = "https://cdn.modrinth.com";
+
+/// 下载中断后的最大尝试次数
+const MAX_DOWNLOAD_ATTEMPTS: usize = 5;
 const USER_AGENT: &str = "Ar1sLauncher/1.0.0 (https://github.com/your-username/ar1s-launcher)";
 
+/// 按镜像模板改写 Modrinth CDN 地址，非 CDN 地址返回 None
+pub fn rewrite_cdn_url(url: &str, mirror: &str) -> Option<String> {
+    let path = url.strip_prefix(MODRINTH_CDN)?;
+    Some(if mirror.contains("{path}") {
+        mirror.replace("{path}", path)
+    } else {
+        format!("{}{}", mirror.trim_end_matches('/'), path)
+    })
+}
+
+/// 文件的下载地址候选：配置了镜像时镜像优先，官方地址兜底
+pub fn download_candidates(url: &str) -> Vec<String> {
+    let mirror = crate::services::config::load_config()
+        .ok()
+        .and_then(|c| c.modrinth_mirror)
+        .and_then(|m| rewrite_cdn_url(url, &m));
+    mirror.into_iter().chain(std::iter::once(url.to_string())).collect()
+}
+
 pub struct ModrinthService {
     client: Client,
 }
@@ -340,36 +367,163 @@ impl ModrinthService {
             .collect()
     }
 
-    /// 下载整合包文件
-    pub async fn download_modpack_file(
+    /// 按 SHA1 批量查找文件对应的 Modrinth 版本
+    pub async fn get_versions_by_sha1(
         &self,
-        file_url: &str,
-        destination: &std::path::Path,
-    ) -> Result<(), LauncherError> {
+        hashes: &[String],
+    ) -> Result<HashMap<String, ModrinthFileMatch>, LauncherError> {
+        if hashes.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let url = format!("{}/version_files", MODRINTH_API_BASE);
         let response = self
             .client
-            .get(file_url)
+            .post(&url)
             .header("User-Agent", USER_AGENT)
+            .json(&serde_json::json!({ "hashes": hashes, "algorithm": "sha1" }))
             .send()
             .await
-            .map_err(|e| LauncherError::Custom(format!("下载文件失败: {}", e)))?;
-        
+            .map_err(|e| LauncherError::Custom(format!("按哈希查找 Modrinth 版本失败: {}", e)))?;
+
         if !response.status().is_success() {
             return Err(LauncherError::Custom(format!(
-                "下载文件失败: {}",
+                "按哈希查找 Modrinth 版本失败: {}",
                 response.status()
             )));
         }
-        
-        let content = response
-            .bytes()
+
+        let versions: HashMap<String, Value> = response
+            .json()
             .await
-            .map_err(|e| LauncherError::Custom(format!("读取文件内容失败: {}", e)))?;
-        
-        tokio::fs::write(destination, content)
+            .map_err(|e| LauncherError::Custom(format!("解析响应失败: {}", e)))?;
+
+        Ok(versions
+            .into_iter()
+            .filter_map(|(sha1, version)| {
+                let file_url = version["files"]
+                    .as_array()?
+                    .iter()
+                    .find(|f| f["hashes"]["sha1"].as_str() == Some(sha1.as_str()))?["url"]
+                    .as_str()?
+                    .to_string();
+                let found = ModrinthFileMatch {
+                    project_id: version["project_id"].as_str()?.to_string(),
+                    version_id: version["id"].as_str()?.to_string(),
+                    version_number: version["version_number"].as_str().unwrap_or_default().to_string(),
+                    url: file_url,
+                };
+                Some((sha1, found))
+            })
+            .collect())
+    }
+
+    /// 下载整合包文件（流式写入 `.part` 文件，中断后续传，完成后校验 SHA1）
+    ///
+    /// `on_progress` 收到已下载和总字节数，返回 false 时中止下载（保留 `.part` 供下次续传）
+    pub async fn download_modpack_file(
+        &self,
+        file_url: &str,
+        destination: &std::path::Path,
+        expected_sha1: Option<&str>,
+        mut on_progress: impl FnMut(u64, u64) -> bool,
+    ) -> Result<(), LauncherError> {
+        let verify = |path: &std::path::Path| match expected_sha1 {
+            Some(expected) => file_utils::sha1_file(path, None)
+                .map(|hash| hash.eq_ignore_ascii_case(expected))
+                .unwrap_or(false),
+            None => true,
+        };
+        // 上次已完整下载的文件直接复用
+        if expected_sha1.is_some() && destination.exists() && verify(destination) {
+            return Ok(());
+        }
+
+        let part_path = destination.with_extension("part");
+        // 镜像和官方地址轮流尝试；镜像返回的内容被篡改时哈希校验失败，会从官方地址重新下载
+        let candidates = download_candidates(file_url);
+        let mut last_error = None;
+        for (attempt, url) in (1..=MAX_DOWNLOAD_ATTEMPTS).zip(candidates.iter().cycle()) {
+            if attempt > 1 {
+                warn!("下载 {} 中断，第 {} 次重试", url, attempt);
+                tokio::time::sleep(std::time::Duration::from_secs(attempt as u64)).await;
+            }
+            match self.download_part(url, &part_path, &mut on_progress).await {
+                Ok(true) => {}
+                Ok(false) => return Err(LauncherError::Custom("下载已取消".to_string())),
+                Err(e) => {
+                    last_error = Some(e);
+                    continue;
+                }
+            }
+            if verify(&part_path) {
+                tokio::fs::rename(&part_path, destination)
+                    .await
+                    .map_err(|e| LauncherError::Custom(format!("保存文件失败: {}", e)))?;
+                return Ok(());
+            }
+            // 续传内容损坏时从头下载
+            warn!("下载的文件哈希不匹配，将重新下载: {}", url);
+            let _ = tokio::fs::remove_file(&part_path).await;
+            last_error = Some(LauncherError::Custom("文件哈希校验失败".to_string()));
+        }
+        Err(LauncherError::Custom(format!(
+            "下载文件失败: {}",
+            last_error.map(|e| e.to_string()).unwrap_or_default()
+        )))
+    }
+
+    /// 从 `.part` 文件已有的长度继续下载，被 `on_progress` 中止时返回 false
+    async fn download_part(
+        &self,
+        file_url: &str,
+        part_path: &std::path::Path,
+        on_progress: &mut impl FnMut(u64, u64) -> bool,
+    ) -> Result<bool, LauncherError> {
+        let mut offset = tokio::fs::metadata(part_path).await.map(|m| m.len()).unwrap_or(0);
+        let mut request = self.client.get(file_url).header("User-Agent", USER_AGENT);
+        if offset > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+        }
+        let mut response = request
+            .send()
             .await
-            .map_err(|e| LauncherError::Custom(format!("保存文件失败: {}", e)))?;
-        
-        Ok(())
+            .map_err(|e| LauncherError::Custom(format!("下载文件失败: {}", e)))?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            // 已下载完整，交给调用方校验
+            return Ok(true);
+        }
+        if !status.is_success() {
+            return Err(LauncherError::Custom(format!("下载文件失败: {}", status)));
+        }
+        // 服务器不支持 Range 时从头写入
+        if status != reqwest::StatusCode::PARTIAL_CONTENT {
+            offset = 0;
+        }
+        let total = offset + response.content_length().unwrap_or(0);
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(offset > 0)
+            .truncate(offset == 0)
+            .open(part_path)
+            .await?;
+
+        let mut downloaded = offset;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| LauncherError::Custom(format!("读取文件内容失败: {}", e)))?
+        {
+            file.write_all(&chunk).await?;
+            downloaded += chunk.len() as u64;
+            if !on_progress(downloaded, total.max(downloaded)) {
+                file.flush().await?;
+                return Ok(false);
+            }
+        }
+        file.flush().await?;
+        Ok(true)
     }
 }
\ No newline at end of file
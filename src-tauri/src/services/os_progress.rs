@@ -0,0 +1,39 @@
+//! 系统级进度显示
+//!
+//! 下载和整合包安装时将总进度同步到任务栏（Windows）/ 程序坞（macOS）进度条和窗口标题，
+//! 启动器最小化时也能看到进度。多个操作同时进行时显示平均进度。具体显示方式由事件接收方实现。
+
+use crate::services::event_sink::EventSink;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+/// 进行中的操作（操作名 -> 百分比）
+static OPERATIONS: LazyLock<Mutex<HashMap<String, u8>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 更新操作进度（0~100）
+pub fn update(window: &EventSink, operation: &str, percent: u8) {
+    let average = {
+        let Ok(mut operations) = OPERATIONS.lock() else {
+            return;
+        };
+        operations.insert(operation.to_string(), percent.min(100));
+        operations.values().map(|p| *p as u32).sum::<u32>() / operations.len() as u32
+    };
+    window.show_os_progress(average as u8);
+}
+
+/// 操作结束；全部结束后清除进度，最后一个操作失败时任务栏显示错误状态
+pub fn finish(window: &EventSink, operation: &str, failed: bool) {
+    let remaining = {
+        let Ok(mut operations) = OPERATIONS.lock() else {
+            return;
+        };
+        operations.remove(operation);
+        (!operations.is_empty())
+            .then(|| operations.values().map(|p| *p as u32).sum::<u32>() / operations.len() as u32)
+    };
+    match remaining {
+        Some(average) => window.show_os_progress(average as u8),
+        None => window.clear_os_progress(failed),
+    }
+}
@@ -0,0 +1,136 @@
+//! 游戏内容目录解析
+//!
+//! 可以登记多个游戏目录（如 SSD 放常玩整合包、HDD 放归档），`game_dir` 为新安装的默认目录。
+//! 每个实例属于包含其 versions/<实例> 的目录，库文件、资源和运行目录都按实例所在目录解析。
+//!
+//! 游戏实际读取的 mods/resourcepacks/saves 等目录取决于版本隔离设置：
+//! - 关闭版本隔离：同一游戏目录下的实例共享该目录下的内容
+//! - 开启版本隔离：versions/<实例>/ 下的目录
+//! - 按账户隔离：存档、截图等位于 versions/<实例>/profiles/<账户>/，
+//!   mods/config 等仍在实例目录（通过链接共享）
+//!
+//! 模组管理、存档等操作和启动器都应通过这里解析路径，保证操作的是游戏真正读取的目录。
+
+use crate::models::GameConfig;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// 按账户隔离时仍在实例内共享的目录
+pub const SHARED_PROFILE_DIRS: [&str; 6] = [
+    "mods",
+    "config",
+    "resourcepacks",
+    "shaderpacks",
+    "defaultconfigs",
+    "kubejs",
+];
+
+/// 游戏内容目录类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentKind {
+    Mods,
+    ResourcePacks,
+    ShaderPacks,
+    Saves,
+    Screenshots,
+    Config,
+    Logs,
+    #[serde(rename = "crash-reports")]
+    CrashReports,
+}
+
+impl ContentKind {
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            Self::Mods => "mods",
+            Self::ResourcePacks => "resourcepacks",
+            Self::ShaderPacks => "shaderpacks",
+            Self::Saves => "saves",
+            Self::Screenshots => "screenshots",
+            Self::Config => "config",
+            Self::Logs => "logs",
+            Self::CrashReports => "crash-reports",
+        }
+    }
+}
+
+/// 将账户名转换为安全的目录名
+pub fn sanitize_account_name(account: &str) -> String {
+    let name: String = account
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+        .collect();
+    if name.is_empty() {
+        "default".to_string()
+    } else {
+        name
+    }
+}
+
+/// 所有已登记的游戏目录，默认目录在前，已去重
+pub fn game_dirs(config: &GameConfig) -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from(&config.game_dir)];
+    for dir in &config.game_dirs {
+        let dir = PathBuf::from(dir);
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// 实例所在的游戏目录：第一个包含 versions/<实例> 的已登记目录，都不存在时为默认目录
+pub fn instance_game_dir(config: &GameConfig, instance: &str) -> PathBuf {
+    let dirs = game_dirs(config);
+    dirs.iter()
+        .find(|dir| dir.join("versions").join(instance).is_dir())
+        .cloned()
+        .unwrap_or_else(|| dirs[0].clone())
+}
+
+/// 实例的版本目录
+pub fn version_dir(config: &GameConfig, instance: &str) -> PathBuf {
+    instance_game_dir(config, instance).join("versions").join(instance)
+}
+
+/// 按账户隔离时的账户目录
+pub fn account_profile_dir(version_dir: &Path, account: &str) -> PathBuf {
+    version_dir.join("profiles").join(sanitize_account_name(account))
+}
+
+/// 游戏运行目录（--gameDir）
+///
+/// `account` 为空时使用配置中的当前账户
+pub fn game_run_dir(config: &GameConfig, instance: &str, account: Option<&str>) -> PathBuf {
+    if !config.version_isolation {
+        return instance_game_dir(config, instance);
+    }
+    let version_dir = version_dir(config, instance);
+    if config.isolate_per_account {
+        let account = account.or(config.username.as_deref()).unwrap_or_default();
+        return account_profile_dir(&version_dir, account);
+    }
+    version_dir
+}
+
+/// 运行目录中相对路径（如 `mods/a.jar`、`options.txt`）对应的实际文件
+pub fn run_file(config: &GameConfig, instance: &str, relative: &str) -> PathBuf {
+    let top = relative.split('/').next().unwrap_or_default();
+    if config.version_isolation && config.isolate_per_account && SHARED_PROFILE_DIRS.contains(&top) {
+        return version_dir(config, instance).join(relative);
+    }
+    game_run_dir(config, instance, None).join(relative)
+}
+
+/// 游戏实际读取的内容目录
+pub fn content_dir(config: &GameConfig, instance: &str, kind: ContentKind) -> PathBuf {
+    let dir_name = kind.dir_name();
+    if config.version_isolation
+        && config.isolate_per_account
+        && SHARED_PROFILE_DIRS.contains(&dir_name)
+    {
+        return version_dir(config, instance).join(dir_name);
+    }
+    game_run_dir(config, instance, None).join(dir_name)
+}
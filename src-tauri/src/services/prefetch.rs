@@ -0,0 +1,112 @@
+//! 后台元数据预取
+//!
+//! 定时刷新版本清单、Fabric/Forge 版本列表和已安装整合包的更新信息，
+//! 保持缓存常热，使前端页面打开时无需等待网络请求。
+
+use crate::services::{config, download, loaders, modpack_installer::ModpackInstaller, paths};
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 基础刷新间隔（需小于缓存过期时间）
+const BASE_INTERVAL: Duration = Duration::from_secs(20 * 60);
+/// 最大随机抖动
+const MAX_JITTER_SECS: u64 = 180;
+/// 启动后首次预取的延迟，避免与启动流程争抢网络
+const INITIAL_DELAY: Duration = Duration::from_secs(10);
+
+/// 计算带随机抖动的等待时间，避免多个客户端同时请求
+fn jittered(base: Duration) -> Duration {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    base + Duration::from_secs(nanos % MAX_JITTER_SECS)
+}
+
+/// 启动后台预取任务（在 setup 中调用）；关闭启动联网时首轮推迟一个刷新周期
+pub fn start_background_prefetch(startup_network: bool) {
+    let initial_delay = if startup_network { INITIAL_DELAY } else { BASE_INTERVAL };
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(jittered(initial_delay)).await;
+        loop {
+            let enabled = config::load_config()
+                .map(|c| c.background_prefetch)
+                .unwrap_or(false);
+            if enabled {
+                prefetch_once().await;
+            }
+            tokio::time::sleep(jittered(BASE_INTERVAL)).await;
+        }
+    });
+}
+
+/// 执行一轮预取，单项失败不影响其他项
+async fn prefetch_once() {
+    log::debug!("后台预取元数据...");
+
+    if let Err(e) = download::refresh_versions().await {
+        log::warn!("预取版本清单失败: {}", e);
+        return;
+    }
+
+    // 预取最新正式版及上次选择版本的加载器列表
+    let mut mc_versions = HashSet::new();
+    if let Ok(manifest) = download::get_versions().await {
+        mc_versions.insert(manifest.latest.release.clone());
+        if let Some(last) = config::get_last_selected_version() {
+            if manifest.versions.iter().any(|v| v.id == last) {
+                mc_versions.insert(last);
+            }
+        }
+    }
+
+    for mc_version in &mc_versions {
+        if let Err(e) = loaders::refresh_fabric_versions(mc_version).await {
+            log::debug!("预取 Fabric 版本列表失败 ({}): {}", mc_version, e);
+        }
+        if let Err(e) = loaders::refresh_forge_versions(mc_version).await {
+            log::debug!("预取 Forge 版本列表失败 ({}): {}", mc_version, e);
+        }
+    }
+
+    // 预取已安装整合包的版本列表，用于检查更新
+    let Ok(cfg) = config::load_config() else {
+        return;
+    };
+    let installer = ModpackInstaller::new();
+    let modpack_ids: HashSet<String> = paths::game_dirs(&cfg)
+        .iter()
+        .flat_map(|dir| installed_modpack_ids(&dir.join("versions")))
+        .collect();
+    for modpack_id in modpack_ids {
+        if let Err(e) = installer.refresh_modpack_versions(&modpack_id).await {
+            log::debug!("预取整合包版本失败 ({}): {}", modpack_id, e);
+        }
+    }
+
+    log::debug!("后台预取完成");
+}
+
+/// 扫描来自 Modrinth 的整合包实例
+fn installed_modpack_ids(versions_dir: &Path) -> HashSet<String> {
+    let mut ids = HashSet::new();
+    let Ok(entries) = std::fs::read_dir(versions_dir) else {
+        return ids;
+    };
+
+    for entry in entries.flatten() {
+        let Ok(content) = std::fs::read_to_string(entry.path().join("instance.json")) else {
+            continue;
+        };
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) else {
+            continue;
+        };
+        if json["source"].as_str() == Some("modrinth") {
+            if let Some(id) = json["modpack_id"].as_str() {
+                ids.insert(id.to_string());
+            }
+        }
+    }
+    ids
+}
@@ -0,0 +1,273 @@
+//! 资源包去重
+//!
+//! 开启版本隔离后每个实例都有自己的 resourcepacks 目录，同一个资源包常被复制到多个实例。
+//! 这里按内容哈希找出各实例和公共目录中重复的资源包，统计浪费的空间，
+//! 并可以把重复的文件合并到公共 resourcepacks 目录，实例中的副本替换为指向它的硬链接。
+
+use crate::errors::LauncherError;
+use crate::services::{config, paths};
+use crate::utils::file_utils;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const RESOURCEPACKS_DIR: &str = "resourcepacks";
+
+/// 资源包所在位置
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackLocation {
+    pub path: String,
+    /// 所属实例，公共目录为空
+    pub instance_name: Option<String>,
+}
+
+/// 一组内容相同的资源包
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicatePackGroup {
+    pub sha1: String,
+    pub size: u64,
+    pub game_dir: String,
+    pub locations: Vec<PackLocation>,
+    /// 合并后可以节省的空间（已经是硬链接的副本不计）
+    pub wasted_bytes: u64,
+}
+
+/// 扫描结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourcePackScan {
+    pub scanned_files: usize,
+    pub total_bytes: u64,
+    pub groups: Vec<DuplicatePackGroup>,
+    pub wasted_bytes: u64,
+}
+
+/// 合并结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsolidateResult {
+    /// 替换为链接的副本数量
+    pub linked: usize,
+    pub freed_bytes: u64,
+    pub failed: Vec<String>,
+}
+
+struct PackFile {
+    path: PathBuf,
+    instance: Option<String>,
+    size: u64,
+}
+
+/// 文件的物理标识，用于识别同一文件的多个硬链接（Windows 上无法获取时为空）
+#[cfg(unix)]
+fn file_id(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_path: &Path) -> Option<(u64, u64)> {
+    None
+}
+
+/// 目录中的资源包文件（文件夹形式的资源包不参与去重）
+fn pack_files(dir: &Path, instance: Option<&str>, out: &mut Vec<PackFile>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if meta.is_file() && meta.len() > 0 {
+            out.push(PackFile {
+                path: entry.path(),
+                instance: instance.map(str::to_string),
+                size: meta.len(),
+            });
+        }
+    }
+}
+
+/// 游戏目录中的全部资源包：公共目录和各实例目录
+fn collect_packs(game_dir: &Path) -> Vec<PackFile> {
+    let mut packs = Vec::new();
+    pack_files(&game_dir.join(RESOURCEPACKS_DIR), None, &mut packs);
+    let Ok(entries) = fs::read_dir(game_dir.join("versions")) else {
+        return packs;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let dir = entry.path().join(RESOURCEPACKS_DIR);
+        // 按账户隔离时 resourcepacks 是指向实例目录的链接，同样只扫描实例目录
+        pack_files(&dir, Some(&name), &mut packs);
+    }
+    packs
+}
+
+/// 按大小预筛后计算哈希，返回重复的分组
+fn find_duplicates(game_dir: &Path, packs: Vec<PackFile>) -> Vec<DuplicatePackGroup> {
+    let mut by_size: HashMap<u64, Vec<PackFile>> = HashMap::new();
+    for pack in packs {
+        by_size.entry(pack.size).or_default().push(pack);
+    }
+
+    let mut groups = Vec::new();
+    for (size, candidates) in by_size.into_iter().filter(|(_, c)| c.len() > 1) {
+        let mut by_hash: HashMap<String, Vec<PackFile>> = HashMap::new();
+        for pack in candidates {
+            match file_utils::sha1_file(&pack.path, None) {
+                Ok(hash) => by_hash.entry(hash).or_default().push(pack),
+                Err(e) => log::warn!("计算资源包哈希失败 {}: {}", pack.path.display(), e),
+            }
+        }
+        for (sha1, files) in by_hash.into_iter().filter(|(_, f)| f.len() > 1) {
+            // 同一物理文件的多个链接只占一份空间
+            let mut ids = HashSet::new();
+            let copies = files
+                .iter()
+                .filter(|f| file_id(&f.path).is_none_or(|id| ids.insert(id)))
+                .count() as u64;
+            let mut locations: Vec<PackLocation> = files
+                .into_iter()
+                .map(|f| PackLocation {
+                    path: f.path.to_string_lossy().to_string(),
+                    instance_name: f.instance,
+                })
+                .collect();
+            locations.sort_by(|a, b| a.path.cmp(&b.path));
+            groups.push(DuplicatePackGroup {
+                sha1,
+                size,
+                game_dir: game_dir.to_string_lossy().to_string(),
+                locations,
+                wasted_bytes: size * copies.saturating_sub(1),
+            });
+        }
+    }
+    groups
+}
+
+/// 扫描所有游戏目录中重复的资源包
+pub async fn scan_duplicate_resourcepacks() -> Result<ResourcePackScan, LauncherError> {
+    let config = config::load_config()?;
+    tokio::task::spawn_blocking(move || {
+        let mut scan = ResourcePackScan {
+            scanned_files: 0,
+            total_bytes: 0,
+            groups: Vec::new(),
+            wasted_bytes: 0,
+        };
+        for game_dir in paths::game_dirs(&config) {
+            let packs = collect_packs(&game_dir);
+            scan.scanned_files += packs.len();
+            scan.total_bytes += packs.iter().map(|p| p.size).sum::<u64>();
+            scan.groups.extend(find_duplicates(&game_dir, packs));
+        }
+        scan.groups.sort_by(|a, b| b.wasted_bytes.cmp(&a.wasted_bytes));
+        scan.wasted_bytes = scan.groups.iter().map(|g| g.wasted_bytes).sum();
+        Ok(scan)
+    })
+    .await?
+}
+
+/// 公共目录中用于保存该组文件的路径：已有内容相同的文件时直接使用，同名不同内容时加上哈希前缀
+fn shared_target(shared_dir: &Path, group: &DuplicatePackGroup) -> PathBuf {
+    if let Some(existing) = group
+        .locations
+        .iter()
+        .find(|l| l.instance_name.is_none())
+    {
+        return PathBuf::from(&existing.path);
+    }
+    let first = Path::new(&group.locations[0].path);
+    let name = first.file_name().unwrap_or_default();
+    let target = shared_dir.join(name);
+    if !target.exists() {
+        return target;
+    }
+    let stem = first.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = first
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    shared_dir.join(format!("{}-{}{}", stem, &group.sha1[..8], ext))
+}
+
+/// 用指向 target 的硬链接替换 path（先链接到临时名再替换，失败时保留原文件）
+fn replace_with_link(target: &Path, path: &Path) -> std::io::Result<()> {
+    let temp = path.with_extension("ar1s-link");
+    let _ = fs::remove_file(&temp);
+    fs::hard_link(target, &temp)?;
+    if let Err(e) = fs::rename(&temp, path) {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+    Ok(())
+}
+
+fn consolidate_group(group: &DuplicatePackGroup, result: &mut ConsolidateResult) {
+    let shared_dir = Path::new(&group.game_dir).join(RESOURCEPACKS_DIR);
+    if let Err(e) = fs::create_dir_all(&shared_dir) {
+        result.failed.push(format!("{}: {}", shared_dir.display(), e));
+        return;
+    }
+    let target = shared_target(&shared_dir, group);
+    if !target.exists() {
+        // 公共目录中还没有：复制一份（各实例的副本稍后替换为链接）
+        if let Err(e) = fs::copy(&group.locations[0].path, &target) {
+            result.failed.push(format!("{}: {}", target.display(), e));
+            return;
+        }
+    }
+
+    let target_id = file_id(&target);
+    for location in group.locations.iter().filter(|l| l.instance_name.is_some()) {
+        let path = Path::new(&location.path);
+        if target_id.is_some() && file_id(path) == target_id {
+            continue;
+        }
+        // 替换前再次确认内容没有变化
+        if !file_utils::sha1_file(path, None).is_ok_and(|h| h == group.sha1) {
+            result.failed.push(format!("{}: 文件已变化，跳过", location.path));
+            continue;
+        }
+        match replace_with_link(&target, path) {
+            Ok(()) => {
+                result.linked += 1;
+                result.freed_bytes += group.size;
+            }
+            Err(e) => result.failed.push(format!("{}: {}", location.path, e)),
+        }
+    }
+}
+
+/// 把重复的资源包合并到公共目录，`sha1s` 为空时处理全部重复组
+pub async fn consolidate_resourcepacks(
+    sha1s: Option<Vec<String>>,
+) -> Result<ConsolidateResult, LauncherError> {
+    let scan = scan_duplicate_resourcepacks().await?;
+    tokio::task::spawn_blocking(move || {
+        let selected: Option<HashSet<String>> = sha1s.map(|s| s.into_iter().collect());
+        let mut result = ConsolidateResult::default();
+        for group in scan
+            .groups
+            .iter()
+            .filter(|g| selected.as_ref().is_none_or(|s| s.contains(&g.sha1)))
+        {
+            consolidate_group(group, &mut result);
+        }
+        log::info!(
+            "资源包去重完成：替换 {} 个副本，释放 {} 字节，失败 {} 个",
+            result.linked,
+            result.freed_bytes,
+            result.failed.len()
+        );
+        Ok(result)
+    })
+    .await?
+}
@@ -0,0 +1,374 @@
+//! 定时任务
+//!
+//! 用户自定义的周期任务（定时备份实例存档、检查整合包更新、清理缓存），
+//! 保存在配置文件同目录的 `ar1s_scheduled_tasks.json`，启动器运行期间每分钟
+//! 检查一次到期任务并执行，结果写入历史记录。
+
+use crate::errors::LauncherError;
+use crate::services::modpack_installer::ModpackInstaller;
+use crate::services::paths::{self, ContentKind};
+use crate::services::event_sink::EventSink;
+use crate::services::{asset_gc, backup, config, meta_cache};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+/// 检查到期任务的间隔
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+/// 保留的历史记录条数
+const MAX_HISTORY: usize = 100;
+
+/// 任务内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduledTaskKind {
+    /// 备份实例存档（未指定存档时备份全部）
+    Backup {
+        instance_name: String,
+        #[serde(default)]
+        world_name: Option<String>,
+    },
+    /// 检查已安装整合包的更新
+    ModpackUpdateCheck,
+    /// 清理下载状态、临时文件、元数据缓存和未被引用的资源文件
+    CacheCleanup,
+}
+
+/// 定时任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTask {
+    pub id: String,
+    pub name: String,
+    pub kind: ScheduledTaskKind,
+    /// 执行间隔（小时），如每天 24、每周 168
+    pub interval_hours: u32,
+    pub enabled: bool,
+    /// 上次执行时间（Unix 秒）
+    pub last_run: Option<i64>,
+}
+
+impl ScheduledTask {
+    fn is_due(&self, now: i64) -> bool {
+        self.enabled
+            && self
+                .last_run
+                .map_or(true, |last| now - last >= i64::from(self.interval_hours.max(1)) * 3600)
+    }
+}
+
+/// 单次执行记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskRunRecord {
+    pub task_id: String,
+    pub task_name: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SchedulerStore {
+    tasks: Vec<ScheduledTask>,
+    history: Vec<TaskRunRecord>,
+}
+
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+/// 正在执行的任务，避免同一任务并发执行
+static RUNNING: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+fn store_path() -> Result<PathBuf, LauncherError> {
+    Ok(config::get_config_path()?.with_file_name("ar1s_scheduled_tasks.json"))
+}
+
+/// 在存储锁内读取、修改并保存
+fn with_store<T>(f: impl FnOnce(&mut SchedulerStore) -> Result<T, LauncherError>) -> Result<T, LauncherError> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = store_path()?;
+    let mut store: SchedulerStore = fs::read_to_string(&path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default();
+    let result = f(&mut store)?;
+    fs::write(&path, serde_json::to_string_pretty(&store)?)?;
+    Ok(result)
+}
+
+/// 列出定时任务
+pub fn list_scheduled_tasks() -> Result<Vec<ScheduledTask>, LauncherError> {
+    with_store(|store| Ok(store.tasks.clone()))
+}
+
+/// 添加定时任务
+pub fn add_scheduled_task(
+    name: String,
+    kind: ScheduledTaskKind,
+    interval_hours: u32,
+) -> Result<ScheduledTask, LauncherError> {
+    if interval_hours == 0 {
+        return Err(LauncherError::Custom("执行间隔至少为 1 小时".to_string()));
+    }
+    let task = ScheduledTask {
+        id: format!("sched-{}", chrono::Utc::now().timestamp_millis()),
+        name,
+        kind,
+        interval_hours,
+        enabled: true,
+        last_run: None,
+    };
+    with_store(|store| {
+        store.tasks.push(task.clone());
+        Ok(())
+    })?;
+    Ok(task)
+}
+
+/// 删除定时任务
+pub fn remove_scheduled_task(task_id: &str) -> Result<(), LauncherError> {
+    with_store(|store| {
+        let before = store.tasks.len();
+        store.tasks.retain(|t| t.id != task_id);
+        if store.tasks.len() == before {
+            return Err(LauncherError::Custom(format!("定时任务 {} 不存在", task_id)));
+        }
+        Ok(())
+    })
+}
+
+/// 启用或停用定时任务
+pub fn set_scheduled_task_enabled(task_id: &str, enabled: bool) -> Result<(), LauncherError> {
+    with_store(|store| {
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| LauncherError::Custom(format!("定时任务 {} 不存在", task_id)))?;
+        task.enabled = enabled;
+        Ok(())
+    })
+}
+
+/// 执行历史（最新在前），可按任务筛选
+pub fn get_task_history(task_id: Option<&str>) -> Result<Vec<TaskRunRecord>, LauncherError> {
+    with_store(|store| {
+        Ok(store
+            .history
+            .iter()
+            .rev()
+            .filter(|r| task_id.map_or(true, |id| r.task_id == id))
+            .cloned()
+            .collect())
+    })
+}
+
+/// 立即执行指定任务
+pub async fn run_task_now(task_id: String, events: &EventSink) -> Result<TaskRunRecord, LauncherError> {
+    let task = with_store(|store| Ok(store.tasks.iter().find(|t| t.id == task_id).cloned()))?
+        .ok_or_else(|| LauncherError::Custom(format!("定时任务 {} 不存在", task_id)))?;
+    execute(task, events).await
+}
+
+/// 执行任务并记录结果
+async fn execute(task: ScheduledTask, events: &EventSink) -> Result<TaskRunRecord, LauncherError> {
+    {
+        let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+        if !running.insert(task.id.clone()) {
+            return Err(LauncherError::Custom(format!("定时任务 {} 正在执行", task.name)));
+        }
+    }
+
+    let started_at = chrono::Local::now();
+    log::info!("执行定时任务: {}", task.name);
+    let result = run_kind(&task.kind, events).await;
+    RUNNING.lock().unwrap_or_else(|e| e.into_inner()).remove(&task.id);
+
+    let record = TaskRunRecord {
+        task_id: task.id.clone(),
+        task_name: task.name.clone(),
+        started_at: started_at.to_rfc3339(),
+        finished_at: chrono::Local::now().to_rfc3339(),
+        success: result.is_ok(),
+        message: result.unwrap_or_else(|e| e.to_string()),
+    };
+    if !record.success {
+        log::warn!("定时任务 {} 失败: {}", task.name, record.message);
+    }
+
+    let saved = record.clone();
+    with_store(move |store| {
+        if let Some(t) = store.tasks.iter_mut().find(|t| t.id == saved.task_id) {
+            t.last_run = Some(started_at.timestamp());
+        }
+        store.history.push(saved);
+        let overflow = store.history.len().saturating_sub(MAX_HISTORY);
+        store.history.drain(..overflow);
+        Ok(())
+    })?;
+
+    let _ = events.emit("scheduled-task-finished", &record);
+    Ok(record)
+}
+
+/// 执行任务内容，返回结果描述
+async fn run_kind(kind: &ScheduledTaskKind, events: &EventSink) -> Result<String, LauncherError> {
+    match kind {
+        ScheduledTaskKind::Backup {
+            instance_name,
+            world_name,
+        } => {
+            let worlds = match world_name {
+                Some(world) => vec![world.clone()],
+                None => list_worlds(instance_name)?,
+            };
+            let mut names = Vec::new();
+            for world in worlds {
+                names.push(backup::backup_world(instance_name.clone(), world, None).await?.name);
+            }
+            Ok(format!("已备份 {} 个存档: {}", names.len(), names.join(", ")))
+        }
+        ScheduledTaskKind::ModpackUpdateCheck => {
+            let updates = check_modpack_updates().await?;
+            for update in &updates {
+                let _ = events.emit("modpack-update-available", update);
+            }
+            Ok(if updates.is_empty() {
+                "所有整合包均为最新版本".to_string()
+            } else {
+                updates
+                    .iter()
+                    .map(|u| format!("{}: {} -> {}", u.instance_name, u.current_version, u.latest_version))
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            })
+        }
+        ScheduledTaskKind::CacheCleanup => {
+            let freed = tokio::task::spawn_blocking(cleanup_caches).await??;
+            Ok(format!("已清理 {:.1} MB", freed as f64 / 1024.0 / 1024.0))
+        }
+    }
+}
+
+/// 实例中的全部存档
+fn list_worlds(instance_name: &str) -> Result<Vec<String>, LauncherError> {
+    let config = config::load_config()?;
+    let saves = paths::content_dir(&config, instance_name, ContentKind::Saves);
+    let Ok(entries) = fs::read_dir(&saves) else {
+        return Ok(Vec::new());
+    };
+    Ok(entries
+        .flatten()
+        .filter(|e| e.path().join("level.dat").exists())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect())
+}
+
+/// 可用的整合包更新
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackUpdate {
+    pub instance_name: String,
+    pub modpack_id: String,
+    pub current_version: String,
+    pub latest_version: String,
+}
+
+/// 比对已安装整合包实例与 Modrinth 最新版本
+async fn check_modpack_updates() -> Result<Vec<ModpackUpdate>, LauncherError> {
+    let config = config::load_config()?;
+    let installer = ModpackInstaller::new();
+    let mut updates = Vec::new();
+
+    let entries: Vec<_> = paths::game_dirs(&config)
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir.join("versions")).ok())
+        .flat_map(|entries| entries.flatten())
+        .collect();
+    for entry in entries {
+        let Some(json) = fs::read_to_string(entry.path().join("instance.json"))
+            .ok()
+            .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+        else {
+            continue;
+        };
+        let (Some("modrinth"), Some(modpack_id), Some(current)) = (
+            json["source"].as_str(),
+            json["modpack_id"].as_str(),
+            json["modpack_version"].as_str(),
+        ) else {
+            continue;
+        };
+        installer.refresh_modpack_versions(modpack_id).await?;
+        let versions = installer.get_modpack_versions(modpack_id, None, None).await?;
+        // Modrinth 按发布时间倒序返回
+        if let Some(latest) = versions.first().filter(|v| v.version_number != current) {
+            updates.push(ModpackUpdate {
+                instance_name: entry.file_name().to_string_lossy().to_string(),
+                modpack_id: modpack_id.to_string(),
+                current_version: current.to_string(),
+                latest_version: latest.version_number.clone(),
+            });
+        }
+    }
+    Ok(updates)
+}
+
+fn remove_dir_contents(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    let mut freed = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            freed += remove_dir_contents(&path);
+            let _ = fs::remove_dir(&path);
+        } else {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            if fs::remove_file(&path).is_ok() {
+                freed += size;
+            }
+        }
+    }
+    freed
+}
+
+/// 清理下载状态、备份临时文件、元数据缓存和未被引用的资源文件，返回释放的字节数
+fn cleanup_caches() -> Result<u64, LauncherError> {
+    let config = config::load_config()?;
+    let game_dir = Path::new(&config.game_dir);
+    let mut freed = remove_dir_contents(&game_dir.join(".download_state"))
+        + remove_dir_contents(&game_dir.join("backups").join(".tmp"));
+    meta_cache::clear();
+    match asset_gc::collect_asset_garbage(false) {
+        Ok(report) => freed += report.freed_bytes,
+        Err(e) => log::warn!("跳过资源文件回收: {}", e),
+    }
+    Ok(freed)
+}
+
+/// 启动定时任务循环（在 setup 中调用）
+pub fn start_scheduler(events: EventSink) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(TICK_INTERVAL).await;
+            let now = chrono::Utc::now().timestamp();
+            let due: Vec<ScheduledTask> = match list_scheduled_tasks() {
+                Ok(tasks) => tasks.into_iter().filter(|t| t.is_due(now)).collect(),
+                Err(e) => {
+                    log::warn!("读取定时任务失败: {}", e);
+                    continue;
+                }
+            };
+            for task in due {
+                if let Err(e) = execute(task, &events).await {
+                    log::warn!("{}", e);
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,238 @@
+//! 服务器状态查询（Server List Ping）
+//!
+//! 通过握手 + 状态请求获取服务器报告的版本、玩家数和 MOTD。Forge/NeoForge 服务器
+//! 在响应中附带 `forgeData`（1.13+）或 `modinfo`（1.12.2-）模组列表，用于创建匹配的实例。
+
+use crate::errors::LauncherError;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// 默认端口
+const DEFAULT_PORT: u16 = 25565;
+
+/// 连接和读写超时
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 状态响应最大长度
+const MAX_RESPONSE_LEN: usize = 2 * 1024 * 1024;
+
+/// 握手时使用的协议号（-1 表示仅查询状态）
+const STATUS_PROTOCOL: i32 = -1;
+
+static MC_VERSION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b1\.\d+(?:\.\d+)?\b").expect("版本号正则"));
+
+/// 服务器上的模组
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerMod {
+    pub mod_id: String,
+    pub version: Option<String>,
+}
+
+/// 服务器状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStatus {
+    pub address: String,
+    /// 服务器报告的版本名，如 `Paper 1.20.1`
+    pub version_name: String,
+    pub protocol: i64,
+    /// 版本名中的服务端名称，如 `Paper`
+    pub brand: Option<String>,
+    /// 从版本名中识别出的 Minecraft 版本
+    pub mc_version: Option<String>,
+    /// 识别出的模组加载器：forge / neoforge / fabric
+    pub loader: Option<String>,
+    pub mods: Vec<ServerMod>,
+    pub motd: String,
+    pub players_online: i64,
+    pub players_max: i64,
+    pub latency_ms: u64,
+}
+
+/// 拆分 `host[:port]`
+fn parse_address(address: &str) -> Result<(String, u16), LauncherError> {
+    let address = address.trim();
+    if address.is_empty() {
+        return Err(LauncherError::Custom("服务器地址为空".to_string()));
+    }
+    match address.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => {
+            let port = port
+                .parse()
+                .map_err(|_| LauncherError::Custom(format!("无效的端口: {}", port)))?;
+            Ok((host.to_string(), port))
+        }
+        _ => Ok((address.to_string(), DEFAULT_PORT)),
+    }
+}
+
+fn write_varint(buf: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7F == 0 {
+            buf.push(value as u8);
+            return;
+        }
+        buf.push((value as u8 & 0x7F) | 0x80);
+        value >>= 7;
+    }
+}
+
+fn write_string(buf: &mut Vec<u8>, value: &str) {
+    write_varint(buf, value.len() as i32);
+    buf.extend_from_slice(value.as_bytes());
+}
+
+async fn read_varint(stream: &mut TcpStream) -> Result<i32, LauncherError> {
+    let mut value = 0u32;
+    for i in 0..5 {
+        let byte = stream.read_u8().await?;
+        value |= ((byte & 0x7F) as u32) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok(value as i32);
+        }
+    }
+    Err(LauncherError::Custom("服务器返回的 VarInt 过长".to_string()))
+}
+
+/// 给数据包加上长度前缀
+fn frame(packet: Vec<u8>) -> Vec<u8> {
+    let mut framed = Vec::with_capacity(packet.len() + 5);
+    write_varint(&mut framed, packet.len() as i32);
+    framed.extend(packet);
+    framed
+}
+
+/// 发送握手和状态请求，返回状态 JSON
+async fn request_status(host: &str, port: u16) -> Result<Value, LauncherError> {
+    let mut stream = TcpStream::connect((host, port)).await?;
+
+    let mut handshake = Vec::new();
+    write_varint(&mut handshake, 0x00);
+    write_varint(&mut handshake, STATUS_PROTOCOL);
+    write_string(&mut handshake, host);
+    handshake.extend_from_slice(&port.to_be_bytes());
+    write_varint(&mut handshake, 1);
+    stream.write_all(&frame(handshake)).await?;
+    stream.write_all(&frame(vec![0x00])).await?;
+
+    let _packet_len = read_varint(&mut stream).await?;
+    let packet_id = read_varint(&mut stream).await?;
+    if packet_id != 0x00 {
+        return Err(LauncherError::Custom(format!("意外的数据包 ID: {}", packet_id)));
+    }
+    let json_len = read_varint(&mut stream).await?;
+    let json_len = usize::try_from(json_len)
+        .ok()
+        .filter(|len| *len <= MAX_RESPONSE_LEN)
+        .ok_or_else(|| LauncherError::Custom("服务器状态响应长度无效".to_string()))?;
+    let mut json = vec![0u8; json_len];
+    stream.read_exact(&mut json).await?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+/// MOTD 可能是字符串或聊天组件
+fn motd_text(description: &Value) -> String {
+    match description {
+        Value::String(s) => s.clone(),
+        Value::Object(obj) => {
+            let mut text = obj.get("text").and_then(|t| t.as_str()).unwrap_or_default().to_string();
+            for extra in obj.get("extra").and_then(|e| e.as_array()).into_iter().flatten() {
+                text.push_str(&motd_text(extra));
+            }
+            text
+        }
+        _ => String::new(),
+    }
+}
+
+/// 从 `forgeData`（1.13+）或 `modinfo`（1.12.2-）中读取模组列表
+fn read_mods(status: &Value) -> Vec<ServerMod> {
+    if let Some(mods) = status.pointer("/forgeData/mods").and_then(|m| m.as_array()) {
+        return mods
+            .iter()
+            .filter_map(|m| {
+                Some(ServerMod {
+                    mod_id: m.get("modId")?.as_str()?.to_string(),
+                    version: m.get("modmarker").and_then(|v| v.as_str()).map(String::from),
+                })
+            })
+            .collect();
+    }
+    status
+        .pointer("/modinfo/modList")
+        .and_then(|m| m.as_array())
+        .map(|mods| {
+            mods.iter()
+                .filter_map(|m| {
+                    Some(ServerMod {
+                        mod_id: m.get("modid")?.as_str()?.to_string(),
+                        version: m.get("version").and_then(|v| v.as_str()).map(String::from),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn detect_loader(status: &Value, version_name: &str, mods: &[ServerMod]) -> Option<String> {
+    if mods.iter().any(|m| m.mod_id == "neoforge") {
+        return Some("neoforge".to_string());
+    }
+    if status.get("forgeData").is_some() || status.get("modinfo").is_some() {
+        return Some("forge".to_string());
+    }
+    let name = version_name.to_lowercase();
+    ["neoforge", "forge", "fabric"]
+        .into_iter()
+        .find(|loader| name.contains(loader))
+        .map(String::from)
+}
+
+/// 查询服务器状态
+pub async fn ping_server(address: &str) -> Result<ServerStatus, LauncherError> {
+    let (host, port) = parse_address(address)?;
+    let start = Instant::now();
+    let status = tokio::time::timeout(PING_TIMEOUT, request_status(&host, port))
+        .await
+        .map_err(|_| LauncherError::Custom(format!("连接服务器 {} 超时", address)))??;
+    let latency_ms = start.elapsed().as_millis() as u64;
+
+    let version_name = status
+        .pointer("/version/name")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let mc_version = MC_VERSION_RE
+        .find_iter(&version_name)
+        .last()
+        .map(|m| m.as_str().to_string());
+    let brand = version_name
+        .split_whitespace()
+        .next()
+        .filter(|word| !MC_VERSION_RE.is_match(word))
+        .map(String::from);
+    let mods = read_mods(&status);
+    let loader = detect_loader(&status, &version_name, &mods);
+
+    Ok(ServerStatus {
+        address: address.to_string(),
+        protocol: status.pointer("/version/protocol").and_then(|p| p.as_i64()).unwrap_or(-1),
+        brand,
+        mc_version,
+        loader,
+        mods,
+        motd: status.get("description").map(motd_text).unwrap_or_default(),
+        players_online: status.pointer("/players/online").and_then(|p| p.as_i64()).unwrap_or(0),
+        players_max: status.pointer("/players/max").and_then(|p| p.as_i64()).unwrap_or(0),
+        latency_ms,
+        version_name,
+    })
+}
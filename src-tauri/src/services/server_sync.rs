@@ -0,0 +1,430 @@
+//! 服务器整合包同步
+//!
+//! 私服定期更新整合包时，服主在网站上发布一个清单（JSON，列出每个文件的相对路径、SHA1 和
+//! 下载地址），玩家的实例按清单增量同步：只下载缺失或变化的文件，删除上次同步安装但清单中
+//! 已移除的文件，以及清单声明由服务器管理的目录（如 `mods`）中多出的文件。
+//! 存档、选项等受保护路径和 `versions`、`libraries` 等启动器目录永远不会被修改；未开启版本隔离时
+//! 不清理管理目录。上次同步的地址和文件列表记录在实例目录的
+//! `ar1s_server_sync.json`，之后同步时可以省略地址。
+//!
+//! ```json
+//! {
+//!   "name": "My Server", "version": "2026-10-12",
+//!   "minecraft": "1.20.1", "loader": "forge", "loaderVersion": "47.2.0",
+//!   "managedDirs": ["mods"],
+//!   "protected": ["config/xaerominimap.txt"],
+//!   "files": [
+//!     { "path": "mods/create.jar", "sha1": "…", "size": 123, "url": "files/create.jar" }
+//!   ]
+//! }
+//! ```
+
+use crate::errors::LauncherError;
+use crate::services::instance_lock::{self, InstanceOperation};
+use crate::services::lockfile::{self, LockedFile};
+use crate::services::{config, http_client, instance, paths, version_info};
+use crate::utils::file_utils;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// 同步记录文件名（位于实例目录）
+const SYNC_RECORD_FILE: &str = "ar1s_server_sync.json";
+
+/// 默认受保护的路径（玩家自己的数据）
+const DEFAULT_PROTECTED: [&str; 9] = [
+    "saves",
+    "options.txt",
+    "optionsof.txt",
+    "optionsshaders.txt",
+    "servers.dat",
+    "screenshots",
+    "logs",
+    "crash-reports",
+    "profiles",
+];
+
+/// 清单中的文件
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackFile {
+    path: String,
+    sha1: String,
+    #[serde(default)]
+    size: u64,
+    /// 下载地址，相对地址按清单地址解析；未提供时使用清单所在目录下的同名路径
+    #[serde(default)]
+    url: Option<String>,
+}
+
+/// 服务器整合包清单
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackManifest {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    minecraft: Option<String>,
+    #[serde(default)]
+    loader: Option<String>,
+    #[serde(default)]
+    loader_version: Option<String>,
+    /// 由服务器管理的目录，其中不在清单内的文件会被删除
+    #[serde(default)]
+    managed_dirs: Vec<String>,
+    /// 服主额外指定的受保护路径
+    #[serde(default)]
+    protected: Vec<String>,
+    files: Vec<PackFile>,
+}
+
+/// 上次同步的记录
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncRecord {
+    manifest_url: String,
+    #[serde(default)]
+    pack_version: Option<String>,
+    synced_at: String,
+    /// 上次同步安装的文件
+    #[serde(default)]
+    files: Vec<String>,
+    /// 玩家额外指定的受保护路径
+    #[serde(default)]
+    protected_paths: Vec<String>,
+}
+
+/// 同步结果；`dry_run` 为 true 时各列表表示将要执行的操作
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerSyncReport {
+    pub manifest_url: String,
+    pub pack_name: Option<String>,
+    pub pack_version: Option<String>,
+    pub dry_run: bool,
+    /// 下载的文件
+    pub downloaded: Vec<String>,
+    /// 删除的文件
+    pub removed: Vec<String>,
+    /// 已是最新的文件数
+    pub unchanged: usize,
+    /// 清单要求修改但位于受保护路径而跳过的文件
+    pub protected: Vec<String>,
+    /// 下载失败或路径无效的文件
+    pub failed: Vec<String>,
+    /// 版本或加载器不一致等需要用户处理的问题
+    pub warnings: Vec<String>,
+}
+
+/// 启动器管理的顶层目录：未开启版本隔离时运行目录就是游戏目录，清单不能触及这些目录
+const RESERVED_TOP_DIRS: [&str; 5] = ["versions", "libraries", "assets", "runtime", "natives"];
+
+/// 规范化清单中的相对路径，拒绝绝对路径、`..` 和启动器管理的顶层目录
+fn normalize_relative(path: &str) -> Option<String> {
+    let path = path.replace('\\', "/");
+    let parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty() && *p != ".").collect();
+    let valid = !path.starts_with('/')
+        && !parts.is_empty()
+        && parts.iter().all(|p| *p != ".." && !p.contains(':'))
+        && !RESERVED_TOP_DIRS.iter().any(|d| parts[0].eq_ignore_ascii_case(d));
+    valid.then(|| parts.join("/"))
+}
+
+/// 需要清理多余文件的管理目录
+///
+/// 未开启版本隔离时管理目录位于共享的游戏目录，会波及其他实例，因此不处理
+fn managed_dirs(
+    manifest: &PackManifest,
+    isolated: bool,
+    protected: &[String],
+    warnings: &mut Vec<String>,
+) -> Vec<String> {
+    if !isolated {
+        if !manifest.managed_dirs.is_empty() {
+            warnings.push("实例未开启版本隔离，管理目录与其他实例共享，已跳过清理".to_string());
+        }
+        return Vec::new();
+    }
+    let mut dirs = Vec::new();
+    for dir in &manifest.managed_dirs {
+        match normalize_relative(dir) {
+            Some(dir) if is_protected(&dir, protected) => {
+                warnings.push(format!("管理目录 {} 位于受保护路径，已忽略", dir));
+            }
+            Some(dir) => dirs.push(dir),
+            None => warnings.push(format!("管理目录 {} 无效，已忽略", dir)),
+        }
+    }
+    dirs
+}
+
+fn is_protected(relative: &str, protected: &[String]) -> bool {
+    protected.iter().any(|p| {
+        let p = p.trim_matches('/');
+        relative == p || relative.strip_prefix(p).is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+/// 启动器自己的文件（版本 JSON、实例设置等）也视为受保护，避免清单覆盖
+fn collect_protected(instance_name: &str, manifest: &PackManifest, record: &SyncRecord) -> Vec<String> {
+    DEFAULT_PROTECTED
+        .iter()
+        .map(|p| p.to_string())
+        .chain([
+            format!("{}.json", instance_name),
+            format!("{}.jar", instance_name),
+            "natives".to_string(),
+            instance::INSTANCE_SETTINGS_FILE.to_string(),
+            lockfile::LOCKFILE_NAME.to_string(),
+            SYNC_RECORD_FILE.to_string(),
+        ])
+        .chain(manifest.protected.iter().filter_map(|p| normalize_relative(p)))
+        .chain(record.protected_paths.iter().filter_map(|p| normalize_relative(p)))
+        .collect()
+}
+
+fn read_record(instance_dir: &Path) -> Option<SyncRecord> {
+    let content = fs::read_to_string(instance_dir.join(SYNC_RECORD_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+async fn fetch_manifest(url: &reqwest::Url) -> Result<PackManifest, LauncherError> {
+    let response = http_client::get_client().get(url.clone()).send().await?;
+    if !response.status().is_success() {
+        return Err(LauncherError::Custom(format!("获取同步清单失败: HTTP {}", response.status())));
+    }
+    let content = response.text().await?;
+    serde_json::from_str(&content).map_err(|e| LauncherError::Custom(format!("同步清单格式无效: {}", e)))
+}
+
+/// 目录中的全部文件，返回以 `relative` 为前缀的相对路径
+fn list_dir_files(dir: &Path, relative: &str, out: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let child = format!("{}/{}", relative, entry.file_name().to_string_lossy());
+        if entry.path().is_dir() {
+            list_dir_files(&entry.path(), &child, out);
+        } else {
+            out.push(child);
+        }
+    }
+}
+
+fn version_warnings(instance_dir: &Path, instance_name: &str, manifest: &PackManifest) -> Vec<String> {
+    let versions_dir = instance_dir.parent().unwrap_or(instance_dir);
+    let current = version_info::detect(versions_dir, instance_name);
+    let mut warnings = Vec::new();
+    if manifest.minecraft.is_some() && current.mc_version != manifest.minecraft {
+        warnings.push(format!(
+            "游戏版本不一致：实例为 {}，服务器为 {}",
+            current.mc_version.as_deref().unwrap_or("未知"),
+            manifest.minecraft.as_deref().unwrap_or("未知")
+        ));
+    }
+    if let Some(loader) = &manifest.loader {
+        if !current.loader_type.eq_ignore_ascii_case(loader)
+            || (manifest.loader_version.is_some() && current.loader_version != manifest.loader_version)
+        {
+            warnings.push(format!(
+                "加载器不一致：实例为 {} {}，服务器为 {} {}",
+                current.loader_type,
+                current.loader_version.as_deref().unwrap_or(""),
+                loader,
+                manifest.loader_version.as_deref().unwrap_or("")
+            ));
+        }
+    }
+    warnings
+}
+
+/// 按服务器清单同步实例
+///
+/// `manifest_url` 为空时使用上次同步的地址；`protected_paths` 会与上次的设置合并并保存，
+/// 之后的同步同样生效。`dry_run` 为 true 时只比对，不修改任何文件。
+pub async fn sync_instance_from_server(
+    instance_name: &str,
+    manifest_url: Option<String>,
+    protected_paths: Vec<String>,
+    dry_run: bool,
+) -> Result<ServerSyncReport, LauncherError> {
+    let config = config::load_config()?;
+    let instance_dir = paths::version_dir(&config, instance_name);
+    if !instance_dir.exists() {
+        return Err(LauncherError::Custom(format!("实例 '{}' 不存在", instance_name)));
+    }
+    let mut record = read_record(&instance_dir).unwrap_or_default();
+    let manifest_url = manifest_url
+        .filter(|u| !u.trim().is_empty())
+        .or_else(|| Some(record.manifest_url.clone()).filter(|u| !u.is_empty()))
+        .ok_or_else(|| LauncherError::Custom("请提供同步清单地址".to_string()))?;
+    let base = reqwest::Url::parse(manifest_url.trim())
+        .ok()
+        .filter(|u| matches!(u.scheme(), "http" | "https"))
+        .ok_or_else(|| LauncherError::Custom(format!("无效的同步清单地址: {}", manifest_url)))?;
+    for path in protected_paths.iter().filter_map(|p| normalize_relative(p)) {
+        if !record.protected_paths.contains(&path) {
+            record.protected_paths.push(path);
+        }
+    }
+
+    let _lock = if dry_run {
+        None
+    } else {
+        instance::ensure_instance_stopped(instance_name, &instance_dir, false).await?;
+        Some(instance_lock::acquire(instance_name, InstanceOperation::Sync)?)
+    };
+
+    let manifest = fetch_manifest(&base).await?;
+    let protected = collect_protected(instance_name, &manifest, &record);
+    let mut report = ServerSyncReport {
+        manifest_url: base.to_string(),
+        pack_name: manifest.name.clone(),
+        pack_version: manifest.version.clone(),
+        dry_run,
+        warnings: version_warnings(&instance_dir, instance_name, &manifest),
+        ..Default::default()
+    };
+
+    let mut wanted = BTreeSet::new();
+    for file in &manifest.files {
+        let Some(relative) = normalize_relative(&file.path) else {
+            report.failed.push(file.path.clone());
+            continue;
+        };
+        wanted.insert(relative.clone());
+        let dest = paths::run_file(&config, instance_name, &relative);
+        if file_utils::verify_file_async(&dest, &file.sha1, file.size).await? {
+            report.unchanged += 1;
+            continue;
+        }
+        if is_protected(&relative, &protected) {
+            report.protected.push(relative);
+            continue;
+        }
+        let url = match base.join(file.url.as_deref().unwrap_or(&relative)) {
+            Ok(url) => url,
+            Err(e) => {
+                warn!("同步文件 {} 的下载地址无效: {}", relative, e);
+                report.failed.push(relative);
+                continue;
+            }
+        };
+        if dry_run {
+            report.downloaded.push(relative);
+            continue;
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let locked = LockedFile {
+            path: relative.clone(),
+            sha1: file.sha1.to_lowercase(),
+            size: file.size,
+            downloads: vec![url.to_string()],
+        };
+        match lockfile::fetch_locked_file(&locked, &dest).await {
+            Ok(()) => report.downloaded.push(relative),
+            Err(e) => {
+                warn!("同步 {} 失败: {}", relative, e);
+                report.failed.push(relative);
+            }
+        }
+    }
+
+    // 上次同步安装、本次清单已移除的文件，以及管理目录中多余的文件
+    let mut stale: BTreeSet<String> = record
+        .files
+        .iter()
+        .filter_map(|f| normalize_relative(f))
+        .filter(|f| !wanted.contains(f))
+        .collect();
+    for dir in managed_dirs(&manifest, config.version_isolation, &protected, &mut report.warnings) {
+        let mut files = Vec::new();
+        list_dir_files(&paths::run_file(&config, instance_name, &dir), &dir, &mut files);
+        stale.extend(files.into_iter().filter(|f| !wanted.contains(f)));
+    }
+    for relative in stale {
+        if is_protected(&relative, &protected) {
+            continue;
+        }
+        let path = paths::run_file(&config, instance_name, &relative);
+        if !path.is_file() {
+            continue;
+        }
+        if !dry_run {
+            if let Err(e) = fs::remove_file(&path) {
+                warn!("删除 {} 失败: {}", path.display(), e);
+                report.failed.push(relative);
+                continue;
+            }
+        }
+        report.removed.push(relative);
+    }
+
+    if !dry_run {
+        record.manifest_url = report.manifest_url.clone();
+        record.pack_version = manifest.version.clone();
+        record.synced_at = chrono::Local::now().to_rfc3339();
+        record.files = wanted.into_iter().collect();
+        fs::write(instance_dir.join(SYNC_RECORD_FILE), serde_json::to_string_pretty(&record)?)?;
+        info!(
+            "实例 {} 已同步到 {} {}：下载 {}，删除 {}，失败 {}",
+            instance_name,
+            manifest.name.as_deref().unwrap_or("服务器整合包"),
+            manifest.version.as_deref().unwrap_or(""),
+            report.downloaded.len(),
+            report.removed.len(),
+            report.failed.len()
+        );
+    }
+    Ok(report)
+}
+
+/// 实例上次同步的清单地址
+pub fn last_sync_url(instance_name: &str) -> Result<Option<String>, LauncherError> {
+    let dir = paths::version_dir(&config::load_config()?, instance_name);
+    Ok(read_record(&dir).map(|r| r.manifest_url).filter(|u| !u.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(managed_dirs: &[&str]) -> PackManifest {
+        serde_json::from_value(serde_json::json!({
+            "managedDirs": managed_dirs,
+            "files": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn normalize_rejects_escapes_and_launcher_dirs() {
+        assert_eq!(normalize_relative("mods\\./create.jar").as_deref(), Some("mods/create.jar"));
+        assert_eq!(normalize_relative("/etc/passwd"), None);
+        assert_eq!(normalize_relative("mods/../../x"), None);
+        assert_eq!(normalize_relative("C:/x"), None);
+        for path in ["versions/other/other.json", "libraries/a.jar", "Assets/indexes/1.json", "runtime", "natives/x.dll"] {
+            assert_eq!(normalize_relative(path), None, "{}", path);
+        }
+    }
+
+    #[test]
+    fn managed_dirs_skipped_without_isolation() {
+        let manifest = manifest(&["mods", "config"]);
+        let mut warnings = Vec::new();
+        assert!(managed_dirs(&manifest, false, &[], &mut warnings).is_empty());
+        assert_eq!(warnings.len(), 1);
+
+        let mut warnings = Vec::new();
+        let protected = vec!["config".to_string()];
+        assert_eq!(managed_dirs(&manifest, true, &protected, &mut warnings), vec!["mods".to_string()]);
+        assert_eq!(warnings.len(), 1);
+    }
+}
@@ -0,0 +1,183 @@
+//! 实例桌面快捷方式
+//!
+//! 为实例生成系统快捷方式（Windows .lnk、Linux .desktop、macOS .command），
+//! 快捷方式以 `--launch <实例名>` 参数启动启动器，直接进入无界面启动流程。
+
+use crate::errors::LauncherError;
+use crate::services::{config, paths};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 快捷方式位置
+#[derive(Debug, Clone, Copy, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutLocation {
+    /// 桌面
+    #[default]
+    Desktop,
+    /// 开始菜单 / 应用程序菜单
+    StartMenu,
+}
+
+/// 获取用户主目录
+fn home_dir() -> Result<PathBuf, LauncherError> {
+    let var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    std::env::var_os(var)
+        .map(PathBuf::from)
+        .ok_or_else(|| LauncherError::Custom("无法获取用户主目录".to_string()))
+}
+
+/// 快捷方式文件名中不允许的字符替换为下划线
+fn shortcut_file_stem(instance_name: &str) -> String {
+    let name: String = instance_name
+        .chars()
+        .map(|c| if "\\/:*?\"<>|".contains(c) { '_' } else { c })
+        .collect();
+    format!("Minecraft - {}", name)
+}
+
+/// 为实例创建快捷方式，返回生成的文件路径
+pub fn create_instance_shortcut(
+    instance_name: &str,
+    location: ShortcutLocation,
+) -> Result<String, LauncherError> {
+    let cfg = config::load_config()?;
+    let instance_dir = paths::version_dir(&cfg, instance_name);
+    if !instance_dir.join(format!("{}.json", instance_name)).exists() {
+        return Err(LauncherError::Custom(format!("实例 '{}' 不存在", instance_name)));
+    }
+
+    let exe = std::env::current_exe()?;
+    let path = write_shortcut(&exe, instance_name, location)?;
+    log::info!("已为实例 {} 创建快捷方式: {}", instance_name, path.display());
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Windows: 通过 WScript.Shell 生成 .lnk
+#[cfg(target_os = "windows")]
+fn write_shortcut(
+    exe: &Path,
+    instance_name: &str,
+    location: ShortcutLocation,
+) -> Result<PathBuf, LauncherError> {
+    use std::os::windows::process::CommandExt;
+
+    let dir = match location {
+        ShortcutLocation::Desktop => home_dir()?.join("Desktop"),
+        ShortcutLocation::StartMenu => std::env::var_os("APPDATA")
+            .map(PathBuf::from)
+            .ok_or_else(|| LauncherError::Custom("无法获取 APPDATA 目录".to_string()))?
+            .join("Microsoft")
+            .join("Windows")
+            .join("Start Menu")
+            .join("Programs"),
+    };
+    fs::create_dir_all(&dir)?;
+    let lnk = dir.join(format!("{}.lnk", shortcut_file_stem(instance_name)));
+
+    // PowerShell 单引号字符串中单引号需要成对转义
+    let ps_quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+    let script = format!(
+        "$s = (New-Object -ComObject WScript.Shell).CreateShortcut({lnk}); \
+         $s.TargetPath = {exe}; \
+         $s.Arguments = {args}; \
+         $s.WorkingDirectory = {cwd}; \
+         $s.IconLocation = {exe}; \
+         $s.Save()",
+        lnk = ps_quote(&lnk.to_string_lossy()),
+        exe = ps_quote(&exe.to_string_lossy()),
+        args = ps_quote(&format!("--launch \"{}\"", instance_name)),
+        cwd = ps_quote(&exe.parent().unwrap_or(exe).to_string_lossy()),
+    );
+
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .creation_flags(0x08000000)
+        .output()?;
+    if !output.status.success() {
+        return Err(LauncherError::Custom(format!(
+            "创建快捷方式失败: {}",
+            crate::utils::encoding::decode_process_output(&output.stderr)
+        )));
+    }
+    Ok(lnk)
+}
+
+/// Linux: 生成 .desktop 文件
+#[cfg(target_os = "linux")]
+fn write_shortcut(
+    exe: &Path,
+    instance_name: &str,
+    location: ShortcutLocation,
+) -> Result<PathBuf, LauncherError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = home_dir()?;
+    let dir = match location {
+        ShortcutLocation::Desktop => std::env::var_os("XDG_DESKTOP_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join("Desktop")),
+        ShortcutLocation::StartMenu => std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(".local").join("share"))
+            .join("applications"),
+    };
+    fs::create_dir_all(&dir)?;
+
+    // Exec 字段中的参数需要用双引号包裹并转义特殊字符
+    let quote = |s: &str| {
+        let escaped: String = s
+            .chars()
+            .flat_map(|c| match c {
+                '"' | '`' | '$' | '\\' => vec!['\\', c],
+                '%' => vec!['%', '%'],
+                _ => vec![c],
+            })
+            .collect();
+        format!("\"{}\"", escaped)
+    };
+
+    let file_name: String = instance_name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    let desktop = dir.join(format!("ar1s-{}.desktop", file_name));
+    let content = format!(
+        "[Desktop Entry]\nType=Application\nName={name}\nComment=Ar1s Launcher\nExec={exe} --launch {instance}\nIcon=ar1s-launcher\nTerminal=false\nCategories=Game;\n",
+        name = shortcut_file_stem(instance_name),
+        exe = quote(&exe.to_string_lossy()),
+        instance = quote(instance_name),
+    );
+    fs::write(&desktop, content)?;
+    fs::set_permissions(&desktop, fs::Permissions::from_mode(0o755))?;
+    Ok(desktop)
+}
+
+/// macOS: 生成可双击执行的 .command 脚本（别名无法携带启动参数）
+#[cfg(target_os = "macos")]
+fn write_shortcut(
+    exe: &Path,
+    instance_name: &str,
+    location: ShortcutLocation,
+) -> Result<PathBuf, LauncherError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = home_dir()?;
+    let dir = match location {
+        ShortcutLocation::Desktop => home.join("Desktop"),
+        ShortcutLocation::StartMenu => home.join("Applications"),
+    };
+    fs::create_dir_all(&dir)?;
+
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "'\\''"));
+    let script_path = dir.join(format!("{}.command", shortcut_file_stem(instance_name)));
+    let content = format!(
+        "#!/bin/sh\nexec {} --launch {} >/dev/null 2>&1 &\n",
+        quote(&exe.to_string_lossy()),
+        quote(instance_name)
+    );
+    fs::write(&script_path, content)?;
+    fs::set_permissions(&script_path, fs::Permissions::from_mode(0o755))?;
+    Ok(script_path)
+}
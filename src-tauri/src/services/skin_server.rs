@@ -0,0 +1,580 @@
+//! 本地 Yggdrasil 皮肤服务
+//!
+//! 为离线账户提供一个轻量的 Yggdrasil 兼容档案服务器，配合 authlib-injector
+//! 使局域网/离线联机的玩家也能看到彼此设置的皮肤。
+//!
+//! 说明：服务不对材质属性签名，仅适用于不强制校验签名的场景（局域网、离线服务器）。
+//! 服务默认只监听本机；开启 `skin_server_lan` 后，局域网内多名玩家可将 `skin_server_url`
+//! 指向同一台主机的服务以共享皮肤。
+
+use crate::errors::LauncherError;
+use crate::services::auth::{self, UserType};
+use crate::services::{accounts, config};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha1::{Digest, Sha1};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// authlib-injector 最新版本信息（BMCLAPI 镜像）
+const AUTHLIB_INJECTOR_LATEST_URL: &str =
+    "https://bmclapi2.bangbang93.com/mirrors/authlib-injector/artifact/latest.json";
+/// 请求体大小上限
+const MAX_REQUEST_SIZE: usize = 64 * 1024;
+
+/// 正在运行的服务（端口 + 停止信号）
+static RUNNING: Mutex<Option<(u16, tokio::sync::watch::Sender<bool>)>> = Mutex::new(None);
+
+/// 皮肤模型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SkinModel {
+    /// 经典（Steve）
+    #[default]
+    Classic,
+    /// 纤细（Alex）
+    Slim,
+}
+
+/// 用户名对应的皮肤
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineSkin {
+    pub username: String,
+    /// 材质文件 SHA1（同时作为文件名）
+    pub texture_hash: String,
+    pub model: SkinModel,
+}
+
+/// 服务状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkinServerStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub port: Option<u16>,
+    /// 是否对局域网开放
+    pub lan: bool,
+    /// 游戏实际使用的服务地址
+    pub api_url: Option<String>,
+    pub skins: Vec<OfflineSkin>,
+}
+
+/// 皮肤存储目录
+fn skins_dir() -> Result<PathBuf, LauncherError> {
+    let dir = PathBuf::from(config::load_config()?.game_dir).join("offline_skins");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn load_skins(dir: &Path) -> HashMap<String, OfflineSkin> {
+    fs::read_to_string(dir.join("skins.json"))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_skins(dir: &Path, skins: &HashMap<String, OfflineSkin>) -> Result<(), LauncherError> {
+    fs::write(dir.join("skins.json"), serde_json::to_string_pretty(skins)?)?;
+    Ok(())
+}
+
+/// 为用户名设置皮肤（PNG 文件）
+pub fn set_offline_skin(
+    username: &str,
+    file_path: &str,
+    model: SkinModel,
+) -> Result<OfflineSkin, LauncherError> {
+    auth::validate_existing_username(username)?;
+
+    let bytes = fs::read(file_path)?;
+    if !bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Err(LauncherError::Custom("皮肤文件必须是 PNG 图片".to_string()));
+    }
+
+    let hash = format!("{:x}", Sha1::digest(&bytes));
+    let dir = skins_dir()?;
+    fs::write(dir.join(format!("{}.png", hash)), &bytes)?;
+
+    let skin = OfflineSkin {
+        username: username.to_string(),
+        texture_hash: hash,
+        model,
+    };
+    let mut skins = load_skins(&dir);
+    skins.insert(username.to_lowercase(), skin.clone());
+    save_skins(&dir, &skins)?;
+    Ok(skin)
+}
+
+/// 用户名当前的皮肤及材质文件路径
+pub fn offline_skin(username: &str) -> Result<Option<(OfflineSkin, PathBuf)>, LauncherError> {
+    let dir = skins_dir()?;
+    Ok(load_skins(&dir).remove(&username.to_lowercase()).map(|skin| {
+        let path = dir.join(format!("{}.png", skin.texture_hash));
+        (skin, path)
+    }))
+}
+
+/// 修改用户名皮肤的模型
+pub fn set_offline_skin_model(username: &str, model: SkinModel) -> Result<OfflineSkin, LauncherError> {
+    let dir = skins_dir()?;
+    let mut skins = load_skins(&dir);
+    let skin = skins
+        .get_mut(&username.to_lowercase())
+        .ok_or_else(|| LauncherError::Custom(format!("{} 尚未设置皮肤", username)))?;
+    skin.model = model;
+    let skin = skin.clone();
+    save_skins(&dir, &skins)?;
+    Ok(skin)
+}
+
+/// 移除用户名的皮肤
+pub fn remove_offline_skin(username: &str) -> Result<(), LauncherError> {
+    let dir = skins_dir()?;
+    let mut skins = load_skins(&dir);
+    if let Some(removed) = skins.remove(&username.to_lowercase()) {
+        // 没有其他用户使用同一材质时删除文件
+        if !skins.values().any(|s| s.texture_hash == removed.texture_hash) {
+            let _ = fs::remove_file(dir.join(format!("{}.png", removed.texture_hash)));
+        }
+        save_skins(&dir, &skins)?;
+    }
+    Ok(())
+}
+
+/// 启用或禁用皮肤服务
+pub async fn set_skin_server_enabled(enabled: bool) -> Result<SkinServerStatus, LauncherError> {
+    config::set_offline_skin_server(enabled).await?;
+    let cfg = config::load_config()?;
+
+    if enabled {
+        ensure_running(cfg.skin_server_port, cfg.skin_server_lan).await?;
+    } else {
+        stop();
+    }
+    get_status()
+}
+
+/// 设置本机服务是否对局域网开放（服务运行中时按新设置重新监听）
+pub async fn set_skin_server_lan(enabled: bool) -> Result<SkinServerStatus, LauncherError> {
+    config::set_skin_server_lan(enabled).await?;
+    let cfg = config::load_config()?;
+
+    if running_port().is_some() {
+        stop();
+        // 等待旧的监听结束后再绑定同一端口
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        ensure_running(cfg.skin_server_port, enabled).await?;
+    }
+    get_status()
+}
+
+/// 获取服务状态
+pub fn get_status() -> Result<SkinServerStatus, LauncherError> {
+    let cfg = config::load_config()?;
+    let port = running_port();
+    let mut skins: Vec<OfflineSkin> = load_skins(&skins_dir()?).into_values().collect();
+    skins.sort_by(|a, b| a.username.cmp(&b.username));
+
+    Ok(SkinServerStatus {
+        enabled: cfg.offline_skin_server,
+        running: port.is_some(),
+        port,
+        lan: cfg.skin_server_lan,
+        api_url: api_url(&cfg, port),
+        skins,
+    })
+}
+
+/// 游戏应使用的 Yggdrasil API 地址（优先使用配置的外部地址）
+fn api_url(cfg: &crate::models::GameConfig, port: Option<u16>) -> Option<String> {
+    cfg.skin_server_url
+        .clone()
+        .filter(|u| !u.is_empty())
+        .or_else(|| port.map(|p| format!("http://127.0.0.1:{}", p)))
+}
+
+fn running_port() -> Option<u16> {
+    RUNNING.lock().ok().and_then(|r| r.as_ref().map(|(p, _)| *p))
+}
+
+/// 停止服务
+pub fn stop() {
+    if let Ok(mut running) = RUNNING.lock() {
+        if let Some((_, tx)) = running.take() {
+            let _ = tx.send(true);
+        }
+    }
+}
+
+/// 确保服务已启动，返回监听端口
+///
+/// `lan` 为 true 时监听所有网卡供局域网内其他玩家访问，否则只监听本机
+pub async fn ensure_running(port: u16, lan: bool) -> Result<u16, LauncherError> {
+    if let Some(port) = running_port() {
+        return Ok(port);
+    }
+
+    let host = if lan { "0.0.0.0" } else { "127.0.0.1" };
+    let listener = TcpListener::bind((host, port))
+        .await
+        .map_err(|e| LauncherError::Custom(format!("皮肤服务端口 {} 启动失败: {}", port, e)))?;
+    let port = listener.local_addr()?.port();
+    let (tx, mut rx) = tokio::sync::watch::channel(false);
+
+    if let Ok(mut running) = RUNNING.lock() {
+        *running = Some((port, tx));
+    }
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    if let Ok((stream, _)) = accepted {
+                        tokio::spawn(async move {
+                            if let Err(e) = handle_connection(stream, port).await {
+                                log::debug!("皮肤服务请求处理失败: {}", e);
+                            }
+                        });
+                    }
+                }
+                _ = rx.changed() => break,
+            }
+        }
+        log::info!("皮肤服务已停止");
+    });
+
+    log::info!("皮肤服务已启动，端口 {}", port);
+    Ok(port)
+}
+
+/// 启动游戏时使用的 JVM 参数（未启用时返回空）
+pub async fn launch_jvm_args(
+    emit: &impl Fn(&str, String),
+) -> Result<Vec<String>, LauncherError> {
+    let cfg = config::load_config()?;
+    if !cfg.offline_skin_server {
+        return Ok(Vec::new());
+    }
+
+    // 皮肤服务不可用时只影响皮肤显示，照常启动游戏
+    let port = if cfg.skin_server_url.as_deref().unwrap_or("").is_empty() {
+        match ensure_running(cfg.skin_server_port, cfg.skin_server_lan).await {
+            Ok(port) => Some(port),
+            Err(e) => {
+                log::warn!("{}，本次启动不加载皮肤服务", e);
+                emit("log-debug", format!("皮肤服务启动失败，已跳过: {}", e));
+                return Ok(Vec::new());
+            }
+        }
+    } else {
+        None
+    };
+    let Some(url) = api_url(&cfg, port) else {
+        return Ok(Vec::new());
+    };
+
+    let agent = match ensure_authlib_injector(Path::new(&cfg.game_dir)).await {
+        Ok(agent) => agent,
+        Err(e) => {
+            log::warn!("无法获取 authlib-injector，本次启动不加载皮肤服务: {}", e);
+            emit("log-debug", format!("authlib-injector 不可用，已跳过皮肤服务: {}", e));
+            return Ok(Vec::new());
+        }
+    };
+    emit("log-debug", format!("启用离线皮肤服务: {}", url));
+
+    Ok(vec![
+        format!("-javaagent:{}={}", agent.to_string_lossy(), url),
+        "-Dauthlibinjector.side=client".to_string(),
+    ])
+}
+
+/// 确保 authlib-injector 已下载
+///
+/// 按 latest.json 提供的 SHA-256 校验后再从临时文件重命名，中断的下载不会被当作完整文件使用
+async fn ensure_authlib_injector(game_dir: &Path) -> Result<PathBuf, LauncherError> {
+    let jar = game_dir.join("authlib-injector.jar");
+    if jar.exists() {
+        return Ok(jar);
+    }
+
+    let client = crate::services::http_client::get_client();
+    let latest: Value = client
+        .get(AUTHLIB_INJECTOR_LATEST_URL)
+        .send()
+        .await?
+        .json()
+        .await?;
+    let url = latest["download_url"]
+        .as_str()
+        .ok_or_else(|| LauncherError::Custom("无法获取 authlib-injector 下载地址".to_string()))?;
+    let expected = latest["checksums"]["sha256"]
+        .as_str()
+        .ok_or_else(|| LauncherError::Custom("authlib-injector 版本信息缺少 SHA-256".to_string()))?
+        .to_lowercase();
+    let bytes = client.get(url).send().await?.error_for_status()?.bytes().await?;
+    let actual = format!("{:x}", Sha256::digest(&bytes));
+    if actual != expected {
+        return Err(LauncherError::Custom(format!(
+            "authlib-injector 校验失败: 期望 {}，实际 {}",
+            expected, actual
+        )));
+    }
+    let part = jar.with_extension("jar.part");
+    fs::write(&part, &bytes)?;
+    fs::rename(&part, &jar)?;
+    Ok(jar)
+}
+
+// ============ HTTP 处理 ============
+
+/// 简单的 HTTP 响应
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn json(value: Value) -> Self {
+        Self {
+            status: "200 OK",
+            content_type: "application/json; charset=utf-8",
+            body: value.to_string().into_bytes(),
+        }
+    }
+
+    fn no_content() -> Self {
+        Self {
+            status: "204 No Content",
+            content_type: "text/plain",
+            body: Vec::new(),
+        }
+    }
+
+    fn not_found() -> Self {
+        Self {
+            status: "404 Not Found",
+            content_type: "text/plain",
+            body: Vec::new(),
+        }
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, port: u16) -> Result<(), LauncherError> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    // 读取请求头
+    let header_end = loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if buf.len() > MAX_REQUEST_SIZE {
+            return Ok(());
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut lines = head.lines();
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or("/").to_string();
+
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+        .collect();
+    let header = |name: &str| headers.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone());
+
+    let content_length = header("content-length")
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(0)
+        .min(MAX_REQUEST_SIZE);
+    // 材质地址使用请求中的主机名，保证局域网内其他玩家也能访问
+    let host = header("host").unwrap_or_else(|| format!("127.0.0.1:{}", port));
+
+    // 读取请求体
+    while buf.len() < header_end + content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    let body_end = buf.len().min(header_end + content_length);
+    let body: Value = serde_json::from_slice(&buf[header_end..body_end]).unwrap_or(Value::Null);
+
+    let response = route(&method, &target, &body, &host).unwrap_or_else(|e| {
+        log::debug!("皮肤服务处理 {} {} 失败: {}", method, target, e);
+        Response::not_found()
+    });
+
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.content_type,
+        response.body.len()
+    );
+    stream.write_all(header.as_bytes()).await?;
+    stream.write_all(&response.body).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+/// 去掉连字符的 UUID
+fn compact_uuid(uuid: &str) -> String {
+    uuid.replace('-', "")
+}
+
+/// 所有已知的离线档案（用户名 -> 无连字符 UUID），按优先级排列：
+/// 离线账户中保存的 UUID、本机保留的 UUID，最后是只设置了皮肤的用户名的标准 UUID
+fn known_profiles() -> Result<Vec<(String, String)>, LauncherError> {
+    let cfg = config::load_config()?;
+    let skins = load_skins(&skins_dir()?);
+
+    let mut profiles: Vec<(String, String)> = accounts::get_accounts()?
+        .into_iter()
+        .filter(|a| a.account_type == UserType::Legacy)
+        .map(|a| (a.username, compact_uuid(&a.uuid)))
+        .collect();
+    for (name, uuid) in &cfg.offline_uuids {
+        profiles.push((name.clone(), compact_uuid(uuid)));
+    }
+    for skin in skins.values() {
+        profiles.push((skin.username.clone(), compact_uuid(&auth::standard_offline_uuid(&skin.username))));
+    }
+    Ok(profiles)
+}
+
+/// 用户名对应的 UUID（与启动游戏时使用的一致），未知用户名使用标准离线 UUID
+fn profile_uuid(profiles: &[(String, String)], name: &str) -> String {
+    profiles
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, u)| u.clone())
+        .unwrap_or_else(|| compact_uuid(&auth::standard_offline_uuid(name)))
+}
+
+/// 构造档案 JSON（包含材质属性）
+fn profile_json(name: &str, uuid: &str, host: &str) -> Result<Value, LauncherError> {
+    let skins = load_skins(&skins_dir()?);
+    let mut properties = Vec::new();
+
+    if let Some(skin) = skins.get(&name.to_lowercase()) {
+        let mut skin_obj = json!({
+            "url": format!("http://{}/textures/{}", host, skin.texture_hash),
+        });
+        if skin.model == SkinModel::Slim {
+            skin_obj["metadata"] = json!({ "model": "slim" });
+        }
+        let textures = json!({
+            "timestamp": chrono::Utc::now().timestamp_millis(),
+            "profileId": uuid,
+            "profileName": name,
+            "textures": { "SKIN": skin_obj },
+        });
+        properties.push(json!({
+            "name": "textures",
+            "value": base64::engine::general_purpose::STANDARD.encode(textures.to_string()),
+        }));
+    }
+
+    Ok(json!({ "id": uuid, "name": name, "properties": properties }))
+}
+
+fn route(method: &str, target: &str, body: &Value, host: &str) -> Result<Response, LauncherError> {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    match (method, path) {
+        ("GET", "/") => Ok(Response::json(json!({
+            "meta": {
+                "serverName": "Ar1s Offline Skins",
+                "implementationName": "ar1s_launcher",
+                "implementationVersion": env!("CARGO_PKG_VERSION"),
+                "feature.non_email_login": true,
+            },
+            "skinDomains": ["127.0.0.1", "localhost", host.split(':').next().unwrap_or(host)],
+        }))),
+
+        // 离线账户不校验密码，仅返回与用户名对应的档案
+        ("POST", "/authserver/authenticate") | ("POST", "/authserver/refresh") => {
+            let name = body["username"]
+                .as_str()
+                .or_else(|| body["selectedProfile"]["name"].as_str())
+                .unwrap_or("Player");
+            let profile = json!({ "id": profile_uuid(&known_profiles()?, name), "name": name });
+            Ok(Response::json(json!({
+                "accessToken": uuid::Uuid::new_v3(&uuid::Uuid::NAMESPACE_URL, name.as_bytes()).simple().to_string(),
+                "clientToken": body["clientToken"].as_str().unwrap_or(""),
+                "availableProfiles": [profile.clone()],
+                "selectedProfile": profile,
+            })))
+        }
+        ("POST", "/authserver/validate")
+        | ("POST", "/authserver/invalidate")
+        | ("POST", "/authserver/signout")
+        | ("POST", "/sessionserver/session/minecraft/join") => Ok(Response::no_content()),
+
+        ("GET", "/sessionserver/session/minecraft/hasJoined") => {
+            let name = query
+                .split('&')
+                .filter_map(|kv| kv.split_once('='))
+                .find(|(k, _)| *k == "username")
+                .map(|(_, v)| v.to_string())
+                .unwrap_or_default();
+            let uuid = profile_uuid(&known_profiles()?, &name);
+            Ok(Response::json(profile_json(&name, &uuid, host)?))
+        }
+
+        ("POST", "/api/profiles/minecraft") => {
+            let profiles = known_profiles()?;
+            let names: Vec<&str> = body
+                .as_array()
+                .map(|a| a.iter().filter_map(|v| v.as_str()).collect())
+                .unwrap_or_default();
+            let found: Vec<Value> = names
+                .iter()
+                .map(|name| json!({ "id": profile_uuid(&profiles, name), "name": name }))
+                .collect();
+            Ok(Response::json(Value::Array(found)))
+        }
+
+        ("GET", p) if p.starts_with("/sessionserver/session/minecraft/profile/") => {
+            let uuid = compact_uuid(p.trim_start_matches("/sessionserver/session/minecraft/profile/"));
+            match known_profiles()?.into_iter().find(|(_, u)| *u == uuid) {
+                Some((name, uuid)) => Ok(Response::json(profile_json(&name, &uuid, host)?)),
+                None => Ok(Response::no_content()),
+            }
+        }
+
+        ("GET", p) if p.starts_with("/textures/") => {
+            let hash = p.trim_start_matches("/textures/");
+            if !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Ok(Response::not_found());
+            }
+            let bytes = fs::read(skins_dir()?.join(format!("{}.png", hash)))?;
+            Ok(Response {
+                status: "200 OK",
+                content_type: "image/png",
+                body: bytes,
+            })
+        }
+
+        _ => Ok(Response::not_found()),
+    }
+}
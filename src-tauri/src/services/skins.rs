@@ -0,0 +1,320 @@
+//! 账户皮肤
+//!
+//! 正版账户通过 Minecraft 服务 API 读取和上传皮肤、切换模型和披风；离线账户使用本地皮肤服务
+//! 保存的皮肤。材质缓存在配置文件同目录的 `skin_cache` 中，前端直接读取本地文件渲染 3D 预览。
+
+use crate::errors::LauncherError;
+use crate::services::auth::{AuthSession, UserType};
+use crate::services::skin_server::{self, SkinModel};
+use crate::services::{accounts, config, http_client};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fs;
+use std::path::PathBuf;
+
+/// 玩家档案（含皮肤和披风）
+const PROFILE_URL: &str = "https://api.minecraftservices.com/minecraft/profile";
+/// 上传或切换皮肤
+const SKINS_URL: &str = "https://api.minecraftservices.com/minecraft/profile/skins";
+/// 当前披风
+const ACTIVE_CAPE_URL: &str = "https://api.minecraftservices.com/minecraft/profile/capes/active";
+/// 上传表单分隔符
+const MULTIPART_BOUNDARY: &str = "----Ar1sLauncherSkinUpload";
+
+/// 皮肤来源
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkinSource {
+    /// 正版账户的档案
+    Mojang,
+    /// 本地皮肤服务
+    Offline,
+    /// 未设置皮肤，使用游戏默认皮肤
+    Default,
+}
+
+/// 披风
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapeInfo {
+    pub id: String,
+    pub alias: Option<String>,
+    pub url: String,
+    /// 本地缓存的材质
+    pub path: Option<String>,
+    pub active: bool,
+}
+
+/// 账户当前的皮肤
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkinInfo {
+    pub account_id: String,
+    pub username: String,
+    pub uuid: String,
+    pub source: SkinSource,
+    pub model: SkinModel,
+    pub skin_url: Option<String>,
+    /// 本地缓存的材质
+    pub skin_path: Option<String>,
+    pub capes: Vec<CapeInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProfileTexture {
+    id: String,
+    state: String,
+    url: String,
+    #[serde(default)]
+    variant: Option<String>,
+    #[serde(default)]
+    alias: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MinecraftProfile {
+    #[serde(default)]
+    skins: Vec<ProfileTexture>,
+    #[serde(default)]
+    capes: Vec<ProfileTexture>,
+}
+
+impl MinecraftProfile {
+    fn active_skin(&self) -> Option<&ProfileTexture> {
+        self.skins.iter().find(|s| s.state == "ACTIVE")
+    }
+}
+
+fn cache_dir() -> Result<PathBuf, LauncherError> {
+    let dir = config::get_config_path()?.with_file_name("skin_cache");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// 下载材质到本地缓存（材质地址以内容哈希结尾，同一地址只下载一次）
+async fn cache_texture(url: &str) -> Result<PathBuf, LauncherError> {
+    let name: String = url
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect();
+    if name.is_empty() {
+        return Err(LauncherError::Custom(format!("无效的材质地址: {}", url)));
+    }
+    let path = cache_dir()?.join(format!("{}.png", name));
+    if !path.exists() {
+        let response = http_client::get_client().get(url).send().await?.error_for_status()?;
+        let bytes = response.bytes().await?;
+        fs::write(&path, &bytes)?;
+    }
+    Ok(path)
+}
+
+/// 缓存失败不影响返回皮肤信息，前端可以改用地址加载
+async fn cached_path(url: &str) -> Option<String> {
+    match cache_texture(url).await {
+        Ok(path) => Some(path.to_string_lossy().to_string()),
+        Err(e) => {
+            log::warn!("缓存材质失败 {}: {}", url, e);
+            None
+        }
+    }
+}
+
+fn model_from_variant(variant: Option<&str>) -> SkinModel {
+    match variant {
+        Some(v) if v.eq_ignore_ascii_case("slim") => SkinModel::Slim,
+        _ => SkinModel::Classic,
+    }
+}
+
+fn variant_name(model: SkinModel) -> &'static str {
+    match model {
+        SkinModel::Classic => "classic",
+        SkinModel::Slim => "slim",
+    }
+}
+
+fn is_online(session: &AuthSession) -> bool {
+    session.user_type != UserType::Legacy
+}
+
+/// 检查 Minecraft 服务 API 的响应，令牌过期时提示重新登录
+async fn check_response(response: reqwest::Response, action: &str) -> Result<reqwest::Response, LauncherError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(LauncherError::Custom("账户登录已过期，请重新登录".to_string()));
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(LauncherError::Custom(format!("{}失败: HTTP {} {}", action, status, body)))
+}
+
+async fn fetch_profile(session: &AuthSession) -> Result<MinecraftProfile, LauncherError> {
+    let response = http_client::get_client()
+        .get(PROFILE_URL)
+        .bearer_auth(&session.access_token)
+        .send()
+        .await?;
+    Ok(check_response(response, "获取档案").await?.json().await?)
+}
+
+async fn online_skin_info(account_id: String, session: AuthSession, profile: MinecraftProfile) -> SkinInfo {
+    let skin = profile.active_skin();
+    let skin_path = match skin {
+        Some(skin) => cached_path(&skin.url).await,
+        None => None,
+    };
+    let mut capes = Vec::with_capacity(profile.capes.len());
+    for cape in &profile.capes {
+        capes.push(CapeInfo {
+            id: cape.id.clone(),
+            alias: cape.alias.clone(),
+            url: cape.url.clone(),
+            path: cached_path(&cape.url).await,
+            active: cape.state == "ACTIVE",
+        });
+    }
+    SkinInfo {
+        account_id,
+        username: session.username,
+        uuid: session.uuid,
+        source: if skin.is_some() { SkinSource::Mojang } else { SkinSource::Default },
+        model: model_from_variant(skin.and_then(|s| s.variant.as_deref())),
+        skin_url: skin.map(|s| s.url.clone()),
+        skin_path,
+        capes,
+    }
+}
+
+fn offline_skin_info(account_id: String, session: AuthSession) -> Result<SkinInfo, LauncherError> {
+    let skin = skin_server::offline_skin(&session.username)?;
+    Ok(SkinInfo {
+        account_id,
+        username: session.username,
+        uuid: session.uuid,
+        source: if skin.is_some() { SkinSource::Offline } else { SkinSource::Default },
+        model: skin.as_ref().map(|(s, _)| s.model).unwrap_or_default(),
+        skin_url: None,
+        skin_path: skin.map(|(_, path)| path.to_string_lossy().to_string()),
+        capes: Vec::new(),
+    })
+}
+
+/// 获取账户（未指定时为默认账户）当前的皮肤
+pub async fn get_skin(account_id: Option<String>) -> Result<SkinInfo, LauncherError> {
+    let (account_id, session) = accounts::account_session(account_id.as_deref())?;
+    if !is_online(&session) {
+        return offline_skin_info(account_id, session);
+    }
+    let profile = fetch_profile(&session).await?;
+    Ok(online_skin_info(account_id, session, profile).await)
+}
+
+/// 读取并校验皮肤文件：PNG，64×64 或旧版 64×32
+fn read_skin_file(file_path: &str) -> Result<Vec<u8>, LauncherError> {
+    let bytes = fs::read(file_path)?;
+    if bytes.len() < 24 || !bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Err(LauncherError::Custom("皮肤文件必须是 PNG 图片".to_string()));
+    }
+    // IHDR 块中的宽和高
+    let width = u32::from_be_bytes([bytes[16], bytes[17], bytes[18], bytes[19]]);
+    let height = u32::from_be_bytes([bytes[20], bytes[21], bytes[22], bytes[23]]);
+    if width != 64 || (height != 64 && height != 32) {
+        return Err(LauncherError::Custom(format!(
+            "皮肤尺寸必须为 64×64 或 64×32，当前为 {}×{}",
+            width, height
+        )));
+    }
+    Ok(bytes)
+}
+
+/// 上传皮肤表单（模型 + PNG 文件）
+fn skin_upload_body(model: SkinModel, png: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(png.len() + 256);
+    body.extend_from_slice(
+        format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"variant\"\r\n\r\n{v}\r\n\
+             --{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"skin.png\"\r\n\
+             Content-Type: image/png\r\n\r\n",
+            b = MULTIPART_BOUNDARY,
+            v = variant_name(model)
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(png);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", MULTIPART_BOUNDARY).as_bytes());
+    body
+}
+
+/// 上传新皮肤；离线账户保存到本地皮肤服务
+pub async fn upload_skin(
+    account_id: Option<String>,
+    file_path: String,
+    model: SkinModel,
+) -> Result<SkinInfo, LauncherError> {
+    let (account_id, session) = accounts::account_session(account_id.as_deref())?;
+    let png = read_skin_file(&file_path)?;
+    if !is_online(&session) {
+        skin_server::set_offline_skin(&session.username, &file_path, model)?;
+        return offline_skin_info(account_id, session);
+    }
+
+    let response = http_client::get_client()
+        .post(SKINS_URL)
+        .bearer_auth(&session.access_token)
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY),
+        )
+        .body(skin_upload_body(model, &png))
+        .send()
+        .await?;
+    let profile: MinecraftProfile = check_response(response, "上传皮肤").await?.json().await?;
+    log::info!("已为账户 {} 上传皮肤", session.username);
+    Ok(online_skin_info(account_id, session, profile).await)
+}
+
+/// 切换皮肤模型（经典 / 纤细），保留当前材质
+pub async fn set_skin_variant(account_id: Option<String>, model: SkinModel) -> Result<SkinInfo, LauncherError> {
+    let (account_id, session) = accounts::account_session(account_id.as_deref())?;
+    if !is_online(&session) {
+        skin_server::set_offline_skin_model(&session.username, model)?;
+        return offline_skin_info(account_id, session);
+    }
+
+    let current = fetch_profile(&session).await?;
+    let url = current
+        .active_skin()
+        .map(|s| s.url.clone())
+        .ok_or_else(|| LauncherError::Custom("账户尚未设置皮肤".to_string()))?;
+    let response = http_client::get_client()
+        .post(SKINS_URL)
+        .bearer_auth(&session.access_token)
+        .json(&json!({ "variant": variant_name(model), "url": url }))
+        .send()
+        .await?;
+    let profile: MinecraftProfile = check_response(response, "切换皮肤模型").await?.json().await?;
+    Ok(online_skin_info(account_id, session, profile).await)
+}
+
+/// 选择显示的披风（只能选择账户已拥有的披风），`cape_id` 为空时隐藏披风
+pub async fn set_active_cape(account_id: Option<String>, cape_id: Option<String>) -> Result<SkinInfo, LauncherError> {
+    let (account_id, session) = accounts::account_session(account_id.as_deref())?;
+    if !is_online(&session) {
+        return Err(LauncherError::Custom("离线账户不支持披风".to_string()));
+    }
+
+    let client = http_client::get_client();
+    let request = match &cape_id {
+        Some(id) => client.put(ACTIVE_CAPE_URL).json(&json!({ "capeId": id })),
+        None => client.delete(ACTIVE_CAPE_URL),
+    };
+    let response = request.bearer_auth(&session.access_token).send().await?;
+    let profile: MinecraftProfile = check_response(response, "切换披风").await?.json().await?;
+    Ok(online_skin_info(account_id, session, profile).await)
+}
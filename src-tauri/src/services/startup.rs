@@ -0,0 +1,74 @@
+//! 应用启动初始化
+//!
+//! 在 setup 中按顺序执行必要的同步步骤（健康检查、配置预加载），其余工作（HTTP 客户端、
+//! Java 检测缓存、后台任务）放到后台，使窗口尽快显示。每个步骤的耗时记录到性能指标
+//! （`startup:*`），可在诊断页面查看。关闭配置中的启动联网后，启动过程不发出任何网络请求。
+
+use crate::services::event_sink::EventSink;
+use crate::services::{config, download, health, http_client, java, metrics, prefetch, scheduler, skin_server, version_watcher};
+use std::time::Instant;
+
+/// 记录启动步骤耗时
+fn timed<T>(step: &str, f: impl FnOnce() -> T) -> T {
+    let _span = metrics::span(format!("startup:{}", step));
+    f()
+}
+
+/// 初始化共享状态并启动后台任务
+pub fn initialize(events: &EventSink) {
+    let started = Instant::now();
+
+    // 启动健康检查（需在配置预加载前执行，以便报告配置文件损坏等问题）
+    let report = timed("health_check", health::run_startup_checks);
+    let _ = events.emit("startup-report", report);
+
+    timed("preload_config", || {
+        if let Err(e) = config::preload_config() {
+            log::error!("配置预加载失败: {}", e);
+        }
+    });
+    let cfg = config::load_config().ok();
+    let startup_network = cfg.as_ref().map(|c| c.startup_network).unwrap_or(true);
+    if !startup_network {
+        log::info!("已关闭启动联网，后台联网任务推迟到下一个周期");
+    }
+
+    // 后台创建共享 HTTP 客户端（加载证书较慢），之后的命令直接复用
+    tauri::async_runtime::spawn_blocking(|| {
+        timed("http_clients", || {
+            http_client::get_client();
+            let _ = download::get_http_client();
+        });
+    });
+
+    // 后台预热 Java 检测缓存
+    tauri::async_runtime::spawn(async {
+        let _span = metrics::span("startup:java_detection");
+        if let Err(e) = java::find_java_installations_command().await {
+            log::warn!("Java 缓存预热失败: {}", e);
+        }
+    });
+
+    // 启用离线皮肤服务时随启动器一同启动（未开放局域网时只监听本地端口）
+    if let Some(cfg) = cfg.as_ref().filter(|c| c.offline_skin_server) {
+        let (port, lan) = (cfg.skin_server_port, cfg.skin_server_lan);
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = skin_server::ensure_running(port, lan).await {
+                log::warn!("{}", e);
+            }
+        });
+    }
+
+    timed("background_tasks", || {
+        // 监视配置文件的外部编辑
+        config::start_config_watcher(events.clone());
+        // 后台预取元数据，保持缓存常热
+        prefetch::start_background_prefetch(startup_network);
+        // 新版本监视（未开启时每轮跳过）
+        version_watcher::start_version_watcher(events.clone(), startup_network);
+        // 用户定义的定时任务
+        scheduler::start_scheduler(events.clone());
+    });
+
+    log::info!("启动初始化完成，耗时 {:?}", started.elapsed());
+}
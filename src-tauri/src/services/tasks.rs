@@ -0,0 +1,160 @@
+//! 可取消的后台任务
+//!
+//! 每个下载/安装任务持有一个取消令牌并登记在全局表中，前端通过
+//! `cancel_task(task_id)` 取消。令牌通过 task-local 传递给嵌套调用
+//! （批量下载、加载器安装、Forge 处理器），无需逐层传参。
+//!
+//! 取消后的文件处理策略：
+//! - 版本/资源下载保留 `.part` 文件和下载状态，下次可断点续传
+//! - 整合包和加载器安装清理已创建的实例目录
+
+use crate::errors::LauncherError;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+
+/// 取消令牌
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// 底层标志，供需要 `Arc<AtomicBool>` 的下载代码使用
+    pub fn flag(&self) -> Arc<AtomicBool> {
+        self.0.clone()
+    }
+}
+
+/// 任务类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    Download,
+    ModpackInstall,
+    InstanceCreate,
+}
+
+/// 前端可见的任务信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskInfo {
+    pub id: String,
+    pub kind: TaskKind,
+    pub label: String,
+}
+
+struct TaskEntry {
+    info: TaskInfo,
+    token: CancellationToken,
+}
+
+static TASKS: LazyLock<Mutex<HashMap<String, TaskEntry>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+tokio::task_local! {
+    static CURRENT_TOKEN: CancellationToken;
+}
+
+/// 已登记的任务，离开作用域时自动注销
+pub struct TaskGuard {
+    info: TaskInfo,
+    token: CancellationToken,
+}
+
+impl TaskGuard {
+    pub fn info(&self) -> &TaskInfo {
+        &self.info
+    }
+
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// 在该任务的取消令牌作用域内执行
+    pub async fn run<F: Future>(&self, fut: F) -> F::Output {
+        CURRENT_TOKEN.scope(self.token.clone(), fut).await
+    }
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        if let Ok(mut tasks) = TASKS.lock() {
+            tasks.remove(&self.info.id);
+        }
+    }
+}
+
+/// 登记一个新任务；若当前已处于某任务作用域内，则共享其令牌，使外层取消能传递下来
+pub fn begin(kind: TaskKind, label: impl Into<String>) -> TaskGuard {
+    let info = TaskInfo {
+        id: format!("task-{}", NEXT_ID.fetch_add(1, Ordering::SeqCst)),
+        kind,
+        label: label.into(),
+    };
+    let token = current_token().unwrap_or_default();
+    if let Ok(mut tasks) = TASKS.lock() {
+        tasks.insert(
+            info.id.clone(),
+            TaskEntry {
+                info: info.clone(),
+                token: token.clone(),
+            },
+        );
+    }
+    TaskGuard { info, token }
+}
+
+/// 当前作用域的取消令牌
+pub fn current_token() -> Option<CancellationToken> {
+    CURRENT_TOKEN.try_with(|t| t.clone()).ok()
+}
+
+/// 当前任务已取消时返回错误
+pub fn check_cancelled() -> Result<(), LauncherError> {
+    match current_token() {
+        Some(token) if token.is_cancelled() => Err(LauncherError::Custom("任务已取消".to_string())),
+        _ => Ok(()),
+    }
+}
+
+/// 取消指定任务
+pub fn cancel_task(task_id: &str) -> Result<(), LauncherError> {
+    let tasks = TASKS
+        .lock()
+        .map_err(|_| LauncherError::Custom("任务表已损坏".to_string()))?;
+    let entry = tasks
+        .get(task_id)
+        .ok_or_else(|| LauncherError::Custom(format!("任务 {} 不存在或已结束", task_id)))?;
+    entry.token.cancel();
+    Ok(())
+}
+
+/// 取消某类型的所有任务，返回取消的数量
+pub fn cancel_kind(kind: TaskKind) -> usize {
+    let Ok(tasks) = TASKS.lock() else {
+        return 0;
+    };
+    tasks
+        .values()
+        .filter(|e| e.info.kind == kind)
+        .inspect(|e| e.token.cancel())
+        .count()
+}
+
+/// 列出正在进行的任务
+pub fn list_tasks() -> Vec<TaskInfo> {
+    TASKS
+        .lock()
+        .map(|tasks| tasks.values().map(|e| e.info.clone()).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,163 @@
+//! 匿名使用统计（默认关闭，需用户主动开启）
+//!
+//! 只记录不含个人信息的事件：启动结果和退出码、安装耗时、镜像故障的主机名。
+//! 不包含用户名、UUID、路径或实例名称。事件先在内存中排队，攒够一批后上报到配置的地址；
+//! 前端可通过 `get_telemetry_status` 查看待发送的完整内容。
+
+use crate::errors::LauncherError;
+use crate::services::{config, http_client};
+use serde::Serialize;
+use std::sync::{LazyLock, Mutex};
+
+/// 攒够该数量的事件后上报
+const BATCH_SIZE: usize = 20;
+/// 队列上限，上报持续失败时丢弃最旧的事件
+const MAX_PENDING: usize = 200;
+
+/// 统计事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TelemetryEvent {
+    /// 游戏进程退出
+    LaunchResult { success: bool, exit_code: Option<i32> },
+    /// 安装操作耗时
+    InstallDuration { kind: String, millis: u64, success: bool },
+    /// 镜像下载故障
+    MirrorFailure { host: String, reason: String },
+}
+
+/// 待上报的记录
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryRecord {
+    pub timestamp: String,
+    pub launcher_version: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+    #[serde(flatten)]
+    pub event: TelemetryEvent,
+}
+
+/// 统计状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryStatus {
+    pub enabled: bool,
+    pub endpoint: Option<String>,
+    /// 下次上报将发送的完整内容
+    pub pending: Vec<TelemetryRecord>,
+}
+
+static PENDING: LazyLock<Mutex<Vec<TelemetryRecord>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+fn is_enabled() -> bool {
+    config::load_config().map(|c| c.telemetry_enabled).unwrap_or(false)
+}
+
+/// 记录事件（未开启时直接丢弃）
+pub fn record(event: TelemetryEvent) {
+    if !is_enabled() {
+        return;
+    }
+    let should_flush = {
+        let Ok(mut pending) = PENDING.lock() else {
+            return;
+        };
+        pending.push(TelemetryRecord {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            launcher_version: env!("CARGO_PKG_VERSION"),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            event,
+        });
+        if pending.len() > MAX_PENDING {
+            let overflow = pending.len() - MAX_PENDING;
+            pending.drain(..overflow);
+        }
+        pending.len() >= BATCH_SIZE
+    };
+    if should_flush {
+        tauri::async_runtime::spawn(async {
+            if let Err(e) = flush().await {
+                log::debug!("上报统计失败: {}", e);
+            }
+        });
+    }
+}
+
+/// 执行安装操作并记录耗时和结果
+pub async fn timed_install<T, F>(kind: &str, fut: F) -> Result<T, LauncherError>
+where
+    F: std::future::Future<Output = Result<T, LauncherError>>,
+{
+    let start = std::time::Instant::now();
+    let result = fut.await;
+    record(TelemetryEvent::InstallDuration {
+        kind: kind.to_string(),
+        millis: start.elapsed().as_millis() as u64,
+        success: result.is_ok(),
+    });
+    result
+}
+
+/// 上报所有待发送事件，失败时放回队列
+pub async fn flush() -> Result<(), LauncherError> {
+    let config = config::load_config()?;
+    let Some(endpoint) = config.telemetry_endpoint.filter(|_| config.telemetry_enabled) else {
+        return Ok(());
+    };
+    let batch: Vec<TelemetryRecord> = match PENDING.lock() {
+        Ok(mut pending) => pending.drain(..).collect(),
+        Err(_) => return Ok(()),
+    };
+    if batch.is_empty() {
+        return Ok(());
+    }
+
+    let result = http_client::get_client()
+        .post(&endpoint)
+        .json(&serde_json::json!({ "events": batch }))
+        .send()
+        .await
+        .map_err(LauncherError::from)
+        .and_then(|r| {
+            if r.status().is_success() {
+                Ok(())
+            } else {
+                Err(LauncherError::Custom(format!("HTTP {}", r.status())))
+            }
+        });
+
+    if result.is_err() {
+        if let Ok(mut pending) = PENDING.lock() {
+            let newer = std::mem::replace(&mut *pending, batch);
+            pending.extend(newer);
+            let overflow = pending.len().saturating_sub(MAX_PENDING);
+            pending.drain(..overflow);
+        }
+    }
+    result
+}
+
+/// 获取统计状态和待发送内容
+pub fn get_telemetry_status() -> Result<TelemetryStatus, LauncherError> {
+    let config = config::load_config()?;
+    Ok(TelemetryStatus {
+        enabled: config.telemetry_enabled,
+        endpoint: config.telemetry_endpoint,
+        pending: PENDING.lock().map(|p| p.clone()).unwrap_or_default(),
+    })
+}
+
+/// 开启或关闭统计，关闭时清空待发送事件
+pub fn set_telemetry_enabled(enabled: bool) -> Result<(), LauncherError> {
+    let mut config = config::load_config()?;
+    config.telemetry_enabled = enabled;
+    config::save_config(&config)?;
+    if !enabled {
+        if let Ok(mut pending) = PENDING.lock() {
+            pending.clear();
+        }
+    }
+    Ok(())
+}
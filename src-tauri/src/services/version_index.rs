@@ -0,0 +1,230 @@
+//! 已安装版本索引
+//!
+//! 实例列表和游戏目录信息原本每次都要扫描目录并解析整条继承链。这里将
+//! 解析结果（版本号、加载器、占用空间、上次校验时间）缓存到
+//! `<游戏目录>/ar1s_version_index.json`：列出时只读取目录名以及版本 JSON、游戏 jar
+//! 和 natives 目录的修改时间，未变化的条目直接复用，安装、删除、重命名时主动失效对应条目。
+
+use crate::errors::LauncherError;
+use crate::models::VerificationLevel;
+use crate::services::{launcher, version_info};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::UNIX_EPOCH;
+
+/// 索引文件名（位于游戏目录）
+const INDEX_FILE: &str = "ar1s_version_index.json";
+
+/// 单个已安装版本的元数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedVersion {
+    /// 版本 JSON 中的 id
+    pub id: String,
+    pub mc_version: Option<String>,
+    pub loader_type: Option<String>,
+    pub loader_version: Option<String>,
+    /// 继承链根版本 JSON 的类型（release / snapshot / old_beta / old_alpha），未声明时为 custom
+    #[serde(default)]
+    pub version_type: Option<String>,
+    /// 继承链中缺失的父版本 id
+    #[serde(default)]
+    pub missing_parent: Option<String>,
+    /// 版本目录占用空间（字节）
+    pub size: u64,
+    /// 上次文件校验通过的时间（Unix 秒）
+    #[serde(default)]
+    pub last_verified: Option<u64>,
+    /// 各校验级别上次通过的时间（Unix 秒）
+    #[serde(default)]
+    pub verified_levels: BTreeMap<VerificationLevel, u64>,
+    /// 建立条目时版本 JSON 的修改时间，用于判断是否需要重新解析
+    json_modified: u64,
+    /// 建立条目时游戏 jar 和 natives 目录的最新修改时间，变化后重新统计占用空间
+    #[serde(default)]
+    files_modified: Option<u64>,
+}
+
+type Index = BTreeMap<String, IndexedVersion>;
+
+/// 内存中的索引，按游戏目录区分
+static INDEX: LazyLock<Mutex<HashMap<PathBuf, Index>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn index_path(versions_dir: &Path) -> PathBuf {
+    versions_dir.parent().unwrap_or(versions_dir).join(INDEX_FILE)
+}
+
+fn modified_secs(path: &Path) -> Option<u64> {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+}
+
+/// 版本目录中影响占用空间的大文件（游戏 jar、解压的 natives）的最新修改时间
+fn files_modified(version_dir: &Path, name: &str) -> u64 {
+    [
+        modified_secs(&version_dir.join(format!("{}.jar", name))),
+        modified_secs(&version_dir.join(launcher::NATIVES_DIR)),
+    ]
+    .into_iter()
+    .flatten()
+    .max()
+    .unwrap_or(0)
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|e| match e.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&e.path()),
+            Ok(t) if t.is_file() => e.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// 解析单个版本并建立索引条目
+fn build_entry(versions_dir: &Path, name: &str, json_modified: u64) -> IndexedVersion {
+    let chain = version_info::load_chain(versions_dir, name);
+    let info = version_info::detect_from_chain(&chain);
+    IndexedVersion {
+        id: chain
+            .first()
+            .and_then(|v| v["id"].as_str().map(String::from))
+            .unwrap_or_else(|| name.to_string()),
+        mc_version: info.mc_version,
+        loader_type: (!chain.is_empty()).then_some(info.loader_type),
+        loader_version: info.loader_version,
+        version_type: Some(
+            chain
+                .last()
+                .and_then(|root| root["type"].as_str())
+                .unwrap_or("custom")
+                .to_string(),
+        ),
+        missing_parent: chain
+            .last()
+            .and_then(|root| root["inheritsFrom"].as_str())
+            .map(String::from),
+        size: dir_size(&versions_dir.join(name)),
+        last_verified: None,
+        verified_levels: BTreeMap::new(),
+        json_modified,
+        files_modified: Some(files_modified(&versions_dir.join(name), name)),
+    }
+}
+
+/// 在索引锁内执行操作；首次访问某个游戏目录时从磁盘加载
+fn with_index<T>(versions_dir: &Path, f: impl FnOnce(&mut Index) -> T) -> T {
+    let mut guard = INDEX.lock().unwrap_or_else(|e| e.into_inner());
+    let index = guard.entry(versions_dir.to_path_buf()).or_insert_with(|| {
+        fs::read_to_string(index_path(versions_dir))
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default()
+    });
+    f(index)
+}
+
+fn save(versions_dir: &Path, index: &Index) {
+    let result = serde_json::to_string(index)
+        .map_err(LauncherError::from)
+        .and_then(|c| fs::write(index_path(versions_dir), c).map_err(LauncherError::from));
+    if let Err(e) = result {
+        log::warn!("保存版本索引失败: {}", e);
+    }
+}
+
+/// 同步索引与 versions 目录并返回全部已安装版本
+///
+/// 只有新增或版本 JSON 被修改过的条目会重新解析；外部工具直接改动目录时也能自动更新。
+pub fn refresh(versions_dir: &Path) -> Index {
+    let mut present = BTreeMap::new();
+    if let Ok(entries) = fs::read_dir(versions_dir) {
+        for entry in entries.flatten() {
+            if !entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(modified) = modified_secs(&entry.path().join(format!("{}.json", name))) {
+                present.insert(name, modified);
+            }
+        }
+    }
+
+    with_index(versions_dir, |index| {
+        let before = index.len();
+        index.retain(|name, _| present.contains_key(name));
+        let mut changed = index.len() != before;
+
+        for (name, modified) in &present {
+            // 缺少 version_type 的是旧版索引条目；父版本缺失的条目在父版本安装后需要重新解析
+            let fresh = index.get(name).is_some_and(|e| {
+                e.json_modified == *modified && e.version_type.is_some() && e.missing_parent.is_none()
+            });
+            let files = Some(files_modified(&versions_dir.join(name), name));
+            if let Some(entry) = index.get_mut(name).filter(|e| fresh && e.files_modified != files) {
+                // 只有 jar 或 natives 变化时保留解析结果和校验记录，只重新统计占用空间
+                entry.size = dir_size(&versions_dir.join(name));
+                entry.files_modified = files;
+                changed = true;
+            }
+            if !fresh {
+                // 版本 JSON 变化后之前的校验结果不再有效，新条目不保留 last_verified
+                let mut entry = build_entry(versions_dir, name, *modified);
+                // JSON 损坏无法解析时保留之前记录的版本和加载器，供重新生成版本 JSON 使用
+                if let Some(old) = index.get(name).filter(|_| entry.loader_type.is_none()) {
+                    entry.mc_version = old.mc_version.clone();
+                    entry.loader_type = old.loader_type.clone();
+                    entry.loader_version = old.loader_version.clone();
+                }
+                index.insert(name.clone(), entry);
+                changed = true;
+            }
+        }
+
+        if changed {
+            save(versions_dir, index);
+        }
+        index.clone()
+    })
+}
+
+/// 索引中记录的版本信息（先与目录同步）
+pub fn recorded(versions_dir: &Path, name: &str) -> Option<IndexedVersion> {
+    refresh(versions_dir).remove(name)
+}
+
+/// 使指定版本的条目失效（安装、修改后调用，下次列出时重新解析）
+pub fn invalidate(versions_dir: &Path, name: &str) {
+    crate::services::instance_health::invalidate(name);
+    with_index(versions_dir, |index| {
+        if index.remove(name).is_some() {
+            save(versions_dir, index);
+        }
+    });
+}
+
+/// 记录版本文件校验通过的时间；高级别校验通过时同时覆盖更低的级别
+pub fn mark_verified(versions_dir: &Path, name: &str, level: VerificationLevel) {
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    refresh(versions_dir);
+    with_index(versions_dir, |index| {
+        if let Some(entry) = index.get_mut(name) {
+            entry.last_verified = Some(now);
+            for passed in VerificationLevel::ALL.into_iter().filter(|l| *l <= level) {
+                entry.verified_levels.insert(passed, now);
+            }
+            save(versions_dir, index);
+        }
+    });
+}
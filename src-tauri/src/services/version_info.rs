@@ -0,0 +1,157 @@
+//! 从版本 JSON 继承链中解析 Minecraft 版本和加载器信息
+
+use crate::utils::maven::MavenCoordinate;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// 继承链最大深度，防止循环继承
+const MAX_CHAIN_DEPTH: usize = 8;
+
+/// 版本的游戏版本与加载器信息
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionInfo {
+    pub mc_version: Option<String>,
+    /// Forge / NeoForge / Fabric / Quilt / OptiFine / None
+    pub loader_type: String,
+    pub loader_version: Option<String>,
+}
+
+/// 读取版本 JSON 及其 inheritsFrom 继承链（子版本在前）
+pub fn load_chain(versions_dir: &Path, version_id: &str) -> Vec<Value> {
+    let mut chain = Vec::new();
+    let mut current = Some(version_id.to_string());
+
+    while let Some(id) = current.take() {
+        if chain.len() >= MAX_CHAIN_DEPTH {
+            break;
+        }
+        let path = versions_dir.join(&id).join(format!("{}.json", id));
+        let Some(json) = fs::read_to_string(path)
+            .ok()
+            .and_then(|c| serde_json::from_str::<Value>(&c).ok())
+        else {
+            break;
+        };
+        current = json["inheritsFrom"].as_str().map(String::from);
+        chain.push(json);
+    }
+    chain
+}
+
+/// 解析指定版本的信息
+pub fn detect(versions_dir: &Path, version_id: &str) -> VersionInfo {
+    detect_from_chain(&load_chain(versions_dir, version_id))
+}
+
+/// 从继承链中解析版本信息
+pub fn detect_from_chain(chain: &[Value]) -> VersionInfo {
+    let libraries: Vec<&str> = chain
+        .iter()
+        .filter_map(|j| j["libraries"].as_array())
+        .flatten()
+        .filter_map(|lib| lib["name"].as_str())
+        .collect();
+    let game_args: Vec<&str> = chain
+        .iter()
+        .flat_map(|j| {
+            let modern = j["arguments"]["game"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|a| a.as_str());
+            let legacy = j["minecraftArguments"]
+                .as_str()
+                .into_iter()
+                .flat_map(|s| s.split_whitespace());
+            modern.chain(legacy)
+        })
+        .collect();
+
+    let (loader_type, loader_version, loader_mc) = detect_loader(&libraries, &game_args);
+    // 继承链根版本的 id 通常就是原版版本号（复制创建的实例已改名，依赖 clientVersion）
+    let mc_version = detect_mc_version(chain, &game_args)
+        .or(loader_mc)
+        .or_else(|| chain.last()?["id"].as_str().map(String::from));
+
+    VersionInfo {
+        mc_version,
+        loader_type,
+        loader_version,
+    }
+}
+
+/// 参数列表中 `--key value` 形式的值
+fn arg_value<'a>(args: &[&'a str], key: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| *a == key)
+        .and_then(|i| args.get(i + 1))
+        .copied()
+}
+
+/// 查找指定 group:artifact 的库版本
+fn library_version(libraries: &[&str], group_artifact: &str) -> Option<String> {
+    let (group, artifact) = group_artifact.split_once(':')?;
+    libraries
+        .iter()
+        .filter_map(|name| MavenCoordinate::parse(name))
+        .find(|c| c.group == group && c.artifact == artifact)
+        .map(|c| c.version)
+}
+
+/// 解析加载器类型、加载器版本，以及从加载器信息中能推断出的 MC 版本
+fn detect_loader(libraries: &[&str], game_args: &[&str]) -> (String, Option<String>, Option<String>) {
+    if let Some(v) = library_version(libraries, "net.fabricmc:fabric-loader") {
+        let mc = library_version(libraries, "net.fabricmc:intermediary");
+        return ("Fabric".to_string(), Some(v), mc);
+    }
+    if let Some(v) = library_version(libraries, "org.quiltmc:quilt-loader") {
+        let mc = library_version(libraries, "net.fabricmc:intermediary");
+        return ("Quilt".to_string(), Some(v), mc);
+    }
+    if let Some(v) = arg_value(game_args, "--fml.neoForgeVersion")
+        .map(String::from)
+        .or_else(|| library_version(libraries, "net.neoforged:neoforge"))
+    {
+        let mc = arg_value(game_args, "--fml.mcVersion").map(String::from);
+        return ("NeoForge".to_string(), Some(v), mc);
+    }
+    if let Some(v) = arg_value(game_args, "--fml.forgeVersion") {
+        let mc = arg_value(game_args, "--fml.mcVersion").map(String::from);
+        return ("Forge".to_string(), Some(v.to_string()), mc);
+    }
+    // 旧版 Forge：net.minecraftforge:forge:<mc>-<forge>
+    if let Some(v) = library_version(libraries, "net.minecraftforge:forge")
+        .or_else(|| library_version(libraries, "net.minecraftforge:minecraftforge"))
+    {
+        return match v.split_once('-') {
+            Some((mc, forge)) => ("Forge".to_string(), Some(forge.to_string()), Some(mc.to_string())),
+            None => ("Forge".to_string(), Some(v), None),
+        };
+    }
+    if let Some(v) = library_version(libraries, "optifine:OptiFine") {
+        return match v.split_once('_') {
+            Some((mc, of)) => ("OptiFine".to_string(), Some(of.to_string()), Some(mc.to_string())),
+            None => ("OptiFine".to_string(), Some(v), None),
+        };
+    }
+    ("None".to_string(), None, None)
+}
+
+/// 从显式字段或继承关系解析 MC 版本
+fn detect_mc_version(chain: &[Value], game_args: &[&str]) -> Option<String> {
+    if let Some(v) = chain.iter().find_map(|j| j["clientVersion"].as_str()) {
+        return Some(v.to_string());
+    }
+    if let Some(v) = arg_value(game_args, "--fml.mcVersion") {
+        return Some(v.to_string());
+    }
+    if let Some(v) = chain.iter().find_map(|j| j["jar"].as_str()) {
+        return Some(v.to_string());
+    }
+    // 有继承关系时，根版本 id 即为原版版本号
+    if chain.len() > 1 {
+        return chain.last()?["id"].as_str().map(String::from);
+    }
+    None
+}
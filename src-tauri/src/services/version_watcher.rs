@@ -0,0 +1,95 @@
+//! 新版本监视
+//!
+//! 定时获取版本清单，与上次记录的版本列表比对，发现新版本（如新快照）时
+//! 发送 `new-version-available` 事件，便于快照玩家第一时间安装。
+
+use crate::errors::LauncherError;
+use crate::models::MinecraftVersion;
+use crate::services::event_sink::EventSink;
+use crate::services::{config, download};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// 检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(30 * 60);
+/// 启动后首次检查的延迟
+const INITIAL_DELAY: Duration = Duration::from_secs(30);
+
+/// `new-version-available` 事件内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewVersionInfo {
+    pub id: String,
+    pub version_type: String,
+    pub release_time: String,
+    pub url: String,
+}
+
+impl From<&MinecraftVersion> for NewVersionInfo {
+    fn from(v: &MinecraftVersion) -> Self {
+        Self {
+            id: v.id.clone(),
+            version_type: v.version_type.clone(),
+            release_time: v.release_time.clone(),
+            url: v.url.clone(),
+        }
+    }
+}
+
+/// 已知版本列表文件（与配置文件同目录）
+fn known_versions_path() -> Result<PathBuf, LauncherError> {
+    Ok(config::get_config_path()?.with_file_name("ar1s_known_versions.json"))
+}
+
+/// 重新获取版本清单，返回相对上次记录新增的版本（首次运行只记录不通知）
+pub async fn check_new_versions() -> Result<Vec<NewVersionInfo>, LauncherError> {
+    download::refresh_versions().await?;
+    let manifest = download::get_versions().await?;
+
+    let path = known_versions_path()?;
+    let known: Option<HashSet<String>> = fs::read_to_string(&path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok());
+
+    let new_versions: Vec<NewVersionInfo> = match &known {
+        Some(known) => manifest
+            .versions
+            .iter()
+            .filter(|v| !known.contains(&v.id))
+            .map(NewVersionInfo::from)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    if known.is_none() || !new_versions.is_empty() {
+        let ids: Vec<&str> = manifest.versions.iter().map(|v| v.id.as_str()).collect();
+        fs::write(&path, serde_json::to_string(&ids)?)?;
+    }
+    Ok(new_versions)
+}
+
+/// 启动版本监视任务（在 setup 中调用，未开启时每轮跳过）；关闭启动联网时首轮推迟一个检查周期
+pub fn start_version_watcher(events: EventSink, startup_network: bool) {
+    let initial_delay = if startup_network { INITIAL_DELAY } else { CHECK_INTERVAL };
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(initial_delay).await;
+        loop {
+            let enabled = config::load_config().map(|c| c.version_watcher).unwrap_or(false);
+            if enabled {
+                match check_new_versions().await {
+                    Ok(versions) => {
+                        for version in versions {
+                            log::info!("发现新版本: {} ({})", version.id, version.version_type);
+                            let _ = events.emit("new-version-available", version);
+                        }
+                    }
+                    Err(e) => log::warn!("检查新版本失败: {}", e),
+                }
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
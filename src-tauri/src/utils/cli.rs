@@ -0,0 +1,79 @@
+//! 命令行参数解析
+//!
+//! 支持的参数：
+//! - `--launch <实例名>`：无界面模式，直接启动指定实例（供桌面快捷方式使用）
+//! - `--completions <bash|zsh|fish>`：输出 shell 补全脚本
+//! - `--print-game-dir`：输出当前游戏目录（供补全脚本读取实例列表）
+
+/// 启动参数
+#[derive(Debug, Default, Clone)]
+pub struct CliArgs {
+    /// 无界面模式下直接启动的实例
+    pub launch_instance: Option<String>,
+    /// 需要输出补全脚本的 shell
+    pub completions: Option<String>,
+    /// 输出游戏目录后退出
+    pub print_game_dir: bool,
+}
+
+/// 解析命令行参数
+pub fn parse_args() -> CliArgs {
+    parse_from(std::env::args().skip(1))
+}
+
+fn parse_from(mut args: impl Iterator<Item = String>) -> CliArgs {
+    let mut cli = CliArgs::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--launch" => cli.launch_instance = args.next(),
+            "--completions" => cli.completions = args.next(),
+            "--print-game-dir" => cli.print_game_dir = true,
+            _ => {
+                if let Some(name) = arg.strip_prefix("--launch=") {
+                    cli.launch_instance = Some(name.to_string());
+                }
+            }
+        }
+    }
+    cli
+}
+
+/// 生成 shell 补全脚本，实例名从游戏目录的 versions 下动态读取
+pub fn completion_script(shell: &str, program: &str) -> Option<String> {
+    let script = match shell {
+        "bash" => format!(
+            r#"_{p}_instances() {{ ls "$({p} --print-game-dir 2>/dev/null)/versions" 2>/dev/null; }}
+_{p}() {{
+    local cur prev
+    cur="${{COMP_WORDS[COMP_CWORD]}}"
+    prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    case "$prev" in
+        --launch) COMPREPLY=($(compgen -W "$(_{p}_instances)" -- "$cur")); return ;;
+        --completions) COMPREPLY=($(compgen -W "bash zsh fish" -- "$cur")); return ;;
+    esac
+    COMPREPLY=($(compgen -W "--launch --completions --print-game-dir" -- "$cur"))
+}}
+complete -F _{p} {p}
+"#,
+            p = program
+        ),
+        "zsh" => format!(
+            r#"#compdef {p}
+_arguments \
+    '--launch[启动指定实例]:instance:($(ls "$({p} --print-game-dir 2>/dev/null)/versions" 2>/dev/null))' \
+    '--completions[输出补全脚本]:shell:(bash zsh fish)' \
+    '--print-game-dir[输出游戏目录]'
+"#,
+            p = program
+        ),
+        "fish" => format!(
+            r#"complete -c {p} -l launch -d '启动指定实例' -xa '(ls ({p} --print-game-dir 2>/dev/null)/versions 2>/dev/null)'
+complete -c {p} -l completions -d '输出补全脚本' -xa 'bash zsh fish'
+complete -c {p} -l print-game-dir -d '输出游戏目录'
+"#,
+            p = program
+        ),
+        _ => return None,
+    };
+    Some(script)
+}
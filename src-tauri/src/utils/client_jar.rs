@@ -0,0 +1,91 @@
+//! 客户端 JAR 处理
+//!
+//! 部分旧版模组客户端直接修改了原版 class，而原版 JAR 带有签名，JVM 校验时会抛出
+//! `SecurityException`。这里生成去掉 META-INF 签名的副本用于启动，原版 JAR 保持不变。
+
+use crate::errors::LauncherError;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+
+const MANIFEST_NAME: &str = "META-INF/MANIFEST.MF";
+
+/// 是否为签名文件（`META-INF/*.SF`、`*.RSA`、`*.DSA`、`*.EC`、`SIG-*`）
+fn is_signature_file(name: &str) -> bool {
+    let Some(file) = name.strip_prefix("META-INF/") else {
+        return false;
+    };
+    if file.contains('/') {
+        return false;
+    }
+    let upper = file.to_uppercase();
+    upper.starts_with("SIG-")
+        || [".SF", ".RSA", ".DSA", ".EC"].iter().any(|ext| upper.ends_with(ext))
+}
+
+/// 去掉清单中的逐文件摘要，只保留主属性段
+fn strip_manifest_digests(manifest: &str) -> String {
+    let main: Vec<&str> = manifest
+        .lines()
+        .map(|l| l.trim_end_matches('\r'))
+        .take_while(|l| !l.is_empty())
+        .collect();
+    let mut result = main.join("\r\n");
+    result.push_str("\r\n\r\n");
+    result
+}
+
+/// 复制 JAR 并去掉签名，返回移除的签名文件数量
+pub fn strip_signatures(src: &Path, dest: &Path) -> Result<usize, LauncherError> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(src)?)?;
+    let temp = dest.with_extension("jar.tmp");
+    let mut writer = zip::ZipWriter::new(fs::File::create(&temp)?);
+    let mut removed = 0;
+
+    let result = (|| -> Result<(), LauncherError> {
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)?;
+            let name = file.name().to_string();
+            if is_signature_file(&name) {
+                removed += 1;
+                continue;
+            }
+            if name.eq_ignore_ascii_case(MANIFEST_NAME) {
+                let mut manifest = String::new();
+                file.read_to_string(&mut manifest)?;
+                writer.start_file(name, SimpleFileOptions::default())?;
+                writer.write_all(strip_manifest_digests(&manifest).as_bytes())?;
+                continue;
+            }
+            // 其余条目原样复制，不重新压缩
+            writer.raw_copy_file(file)?;
+        }
+        writer.finish()?;
+        Ok(())
+    })();
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+    fs::rename(&temp, dest)?;
+    Ok(removed)
+}
+
+/// 获取去签名的 JAR 副本；副本比原 JAR 新时直接复用
+pub fn unsigned_copy(src: &Path, dest: &Path) -> Result<(), LauncherError> {
+    let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+    if let (Some(src_time), Some(dest_time)) = (modified(src), modified(dest)) {
+        if dest_time >= src_time {
+            return Ok(());
+        }
+    }
+    let removed = strip_signatures(src, dest)?;
+    log::info!(
+        "已生成去签名的客户端 JAR {}（移除 {} 个签名文件）",
+        dest.display(),
+        removed
+    );
+    Ok(())
+}
@@ -0,0 +1,30 @@
+//! 子进程输出解码
+//!
+//! 中文 Windows 的控制台代码页为 GBK (936)，Java 和安装器的 stderr 常以 GBK 输出，
+//! 直接按 UTF-8 解码会变成乱码。这里优先按 UTF-8 解码，失败时尝试 GBK。
+
+use std::borrow::Cow;
+
+/// 解码子进程输出：合法 UTF-8 直接返回，否则尝试 GBK，仍失败则有损解码
+pub fn decode_process_output(bytes: &[u8]) -> Cow<'_, str> {
+    if let Ok(s) = std::str::from_utf8(bytes) {
+        return Cow::Borrowed(s);
+    }
+    let (decoded, had_errors) = encoding_rs::GBK.decode_without_bom_handling(bytes);
+    if !had_errors {
+        return decoded;
+    }
+    String::from_utf8_lossy(bytes)
+}
+
+/// 按字节长度截断字符串，保证不截断多字节字符
+pub fn truncate_str(s: &str, max_len: usize) -> &str {
+    if s.len() <= max_len {
+        return s;
+    }
+    let mut end = max_len;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
@@ -1,5 +1,6 @@
 use crate::errors::LauncherError;
 use crate::models::DownloadJob;
+use crate::utils::long_path;
 use serde_json::Value;
 use sha1::{Digest, Sha1};
 use std::fs;
@@ -122,21 +123,133 @@ pub fn validate_instance_name_or_error(name: &str) -> Result<(), LauncherError>
     Ok(())
 }
 
-/// 递归复制目录及其所有内容
-pub fn copy_dir_all(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> Result<(), std::io::Error> {
-    fs::create_dir_all(&dst)?;
-    for entry in fs::read_dir(src)? {
+/// 目录复制进度
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyProgress {
+    pub copied_bytes: u64,
+    pub total_bytes: u64,
+    pub copied_files: u64,
+    pub total_files: u64,
+}
+
+/// 复制缓冲区大小
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// 统计目录下的文件数和总字节数
+fn dir_totals(path: &Path) -> std::io::Result<(u64, u64)> {
+    let (mut files, mut bytes) = (0, 0);
+    for entry in fs::read_dir(path)? {
         let entry = entry?;
-        let ty = entry.file_type()?;
-        if ty.is_dir() {
-            copy_dir_all(entry.path(), dst.as_ref().join(entry.file_name()))?;
+        if entry.file_type()?.is_dir() {
+            let (f, b) = dir_totals(&entry.path())?;
+            files += f;
+            bytes += b;
         } else {
-            fs::copy(entry.path(), dst.as_ref().join(entry.file_name()))?;
+            files += 1;
+            bytes += entry.metadata()?.len();
         }
     }
+    Ok((files, bytes))
+}
+
+/// 流式递归复制目录，按块报告进度；`cancel` 置位时中止并返回错误
+///
+/// 已复制的文件保留在目标目录，由调用方决定是否清理。
+pub fn copy_dir_with_progress(
+    src: &Path,
+    dst: &Path,
+    cancel: Option<&std::sync::atomic::AtomicBool>,
+    on_progress: &mut dyn FnMut(&CopyProgress),
+) -> Result<(), LauncherError> {
+    let (src, dst) = (long_path::extended(src), long_path::extended(dst));
+    let (total_files, total_bytes) = dir_totals(&src)?;
+    let mut progress = CopyProgress {
+        total_files,
+        total_bytes,
+        ..Default::default()
+    };
+    let mut buf = vec![0u8; COPY_BUFFER_SIZE];
+    copy_dir_inner(&src, &dst, cancel, &mut buf, &mut progress, on_progress)?;
+    on_progress(&progress);
     Ok(())
 }
 
+fn copy_dir_inner(
+    src: &Path,
+    dst: &Path,
+    cancel: Option<&std::sync::atomic::AtomicBool>,
+    buf: &mut [u8],
+    progress: &mut CopyProgress,
+    on_progress: &mut dyn FnMut(&CopyProgress),
+) -> Result<(), LauncherError> {
+    use std::io::{Read, Write};
+    use std::sync::atomic::Ordering;
+
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_inner(&entry.path(), &target, cancel, buf, progress, on_progress)?;
+            continue;
+        }
+
+        let mut reader = fs::File::open(entry.path())?;
+        let mut writer = fs::File::create(&target)?;
+        loop {
+            if cancel.is_some_and(|c| c.load(Ordering::SeqCst)) {
+                return Err(LauncherError::Custom("任务已取消".to_string()));
+            }
+            let n = reader.read(buf)?;
+            if n == 0 {
+                break;
+            }
+            writer.write_all(&buf[..n])?;
+            progress.copied_bytes += n as u64;
+            on_progress(progress);
+        }
+        fs::set_permissions(&target, reader.metadata()?.permissions())?;
+        progress.copied_files += 1;
+    }
+    Ok(())
+}
+
+/// 哈希读取缓冲区大小
+const HASH_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// 超过该大小的文件在哈希时报告进度
+const LARGE_FILE_THRESHOLD: u64 = 256 * 1024 * 1024;
+
+/// 流式计算文件 SHA1，可选进度回调 (已处理字节, 总字节)
+pub fn sha1_file(
+    path: &Path,
+    mut on_progress: Option<&mut dyn FnMut(u64, u64)>,
+) -> Result<String, LauncherError> {
+    use std::io::Read;
+
+    let file = fs::File::open(path)?;
+    let total = file.metadata()?.len();
+    let mut reader = std::io::BufReader::with_capacity(HASH_BUFFER_SIZE, file);
+    let mut hasher = Sha1::new();
+    let mut buf = vec![0u8; HASH_BUFFER_SIZE];
+    let mut processed = 0u64;
+
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        processed += n as u64;
+        if let Some(cb) = on_progress.as_mut() {
+            cb(processed, total);
+        }
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
 /// 验证文件完整性和哈希值
 pub fn verify_file(
     path: &std::path::Path,
@@ -148,31 +261,50 @@ pub fn verify_file(
         return Ok(false);
     }
     
-    // 检查文件大小
+    // 检查文件大小（快速预检）
     let actual_size = std::fs::metadata(path)?.len();
     if expected_size > 0 && actual_size != expected_size {
         println!("文件大小不匹配: 期望 {} 字节, 实际 {} 字节", expected_size, actual_size);
         return Ok(false);
     }
     
-    // 如果提供了哈希值，验证文件哈希
-    if !expected_hash.is_empty() {
-        let mut file = std::fs::File::open(path)?;
-        let mut hasher = Sha1::new();
-        std::io::copy(&mut file, &mut hasher)?;
-        let actual_hash = hasher.finalize();
-        let actual_hash_str = format!("{:x}", actual_hash);
-        let is_valid = actual_hash_str.to_lowercase() == expected_hash.to_lowercase();
-        
-        if !is_valid {
-            println!("文件哈希不匹配: 期望 {}, 实际 {}", expected_hash, actual_hash_str);
+    // 如果没有提供哈希值，只检查大小
+    if expected_hash.is_empty() {
+        return Ok(true);
+    }
+
+    // 大文件每 10% 记录一次进度
+    let mut last_decile = 0;
+    let mut report = |done: u64, total: u64| {
+        let decile = done * 10 / total.max(1);
+        if decile > last_decile {
+            last_decile = decile;
+            log::debug!("正在校验 {}: {}%", path.display(), decile * 10);
         }
-        
-        Ok(is_valid)
+    };
+    let progress: Option<&mut dyn FnMut(u64, u64)> = if actual_size >= LARGE_FILE_THRESHOLD {
+        Some(&mut report)
     } else {
-        // 如果没有提供哈希值，只检查大小
-        Ok(true)
+        None
+    };
+
+    let actual_hash = sha1_file(path, progress)?;
+    let is_valid = actual_hash.eq_ignore_ascii_case(expected_hash);
+    if !is_valid {
+        println!("文件哈希不匹配: 期望 {}, 实际 {}", expected_hash, actual_hash);
     }
+    Ok(is_valid)
+}
+
+/// 在阻塞线程池中验证文件，避免大文件哈希占用异步工作线程
+pub async fn verify_file_async(
+    path: &Path,
+    expected_hash: &str,
+    expected_size: u64,
+) -> Result<bool, LauncherError> {
+    let path = path.to_path_buf();
+    let expected_hash = expected_hash.to_string();
+    tokio::task::spawn_blocking(move || verify_file(&path, &expected_hash, expected_size)).await?
 }
 
 /// 增强的文件验证和恢复机制
@@ -189,7 +321,7 @@ pub async fn verify_and_repair_file(
     }
     
     // 2. 验证文件完整性
-    if verify_file(path, &job.hash, job.size)? {
+    if verify_file_async(path, &job.hash, job.size).await? {
         println!("文件验证通过: {}", path.display());
         return Ok(true);
     }
@@ -229,7 +361,7 @@ pub async fn verify_and_repair_file(
     std::fs::write(path, &content)?;
     
     // 3.4 验证重新下载的文件
-    if verify_file(path, &job.hash, job.size)? {
+    if verify_file_async(path, &job.hash, job.size).await? {
         println!("文件修复成功: {}", path.display());
         // 删除备份文件
         let _ = std::fs::remove_file(&backup_path);
@@ -479,51 +611,3 @@ pub fn cleanup_instance_creation(
 
     println!("file_utils: 清理完成");
 }
-
-/// 清理Forge安装过程中创建的文件和目录
-pub fn cleanup_forge_installation(
-    instance_path: &PathBuf,
-    game_dir: &PathBuf,
-    forge_version: &crate::models::ForgeVersion,
-    installer_path: &PathBuf,
-) {
-    println!("file_utils: 开始清理Forge安装过程中的文件和目录");
-
-    // 1. 清理版本文件夹
-    let version_id = format!(
-        "{}-forge-{}",
-        forge_version.mcversion, forge_version.version
-    );
-    let version_dir = game_dir.join("versions").join(&version_id);
-
-    if version_dir.exists() {
-        println!("file_utils: 清理版本文件夹: {}", version_dir.display());
-        if let Err(e) = fs::remove_dir_all(&version_dir) {
-            println!("file_utils: 清理版本文件夹失败: {}", e);
-        } else {
-            println!("file_utils: 版本文件夹清理完成");
-        }
-    }
-
-    // 2. 清理实例目录（如果创建了）
-    if instance_path.exists() {
-        println!("file_utils: 清理实例目录: {}", instance_path.display());
-        if let Err(e) = fs::remove_dir_all(instance_path) {
-            println!("file_utils: 清理实例目录失败: {}", e);
-        } else {
-            println!("file_utils: 实例目录清理完成");
-        }
-    }
-
-    // 3. 清理临时安装器文件
-    if installer_path.exists() {
-        println!("file_utils: 清理临时安装器文件: {}", installer_path.display());
-        if let Err(e) = fs::remove_file(installer_path) {
-            println!("file_utils: 清理安装器文件失败: {}", e);
-        } else {
-            println!("file_utils: 临时安装器文件清理完成");
-        }
-    }
-
-    println!("file_utils: 清理完成");
-}
\ No newline at end of file
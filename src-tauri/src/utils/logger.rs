@@ -25,6 +25,8 @@ pub fn setup_logger() -> Result<(), fern::InitError> {
         .level(LevelFilter::Debug)
         .chain(std::io::stdout())
         .chain(fern::log_file(&log_file)?)
+        // 游戏运行期间的日志同时写入启动会话目录
+        .chain(fern::Output::call(crate::services::launcher::capture_session_log))
         .apply()?;
 
     Ok(())
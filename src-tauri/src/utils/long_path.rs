@@ -0,0 +1,154 @@
+//! Windows 长路径支持
+//!
+//! 整合包的配置目录嵌套很深，拼接实例路径后常超过 Windows 的 MAX_PATH (260 字符)，
+//! 导致复制和解压失败。复制/解压等文件操作前通过 [`extended`] 将路径转换为
+//! `\\?\` 扩展长度形式；其他平台原样返回。
+//!
+//! 扩展长度路径不经过系统规范化，因此转换时自行处理 `.`、`..` 和分隔符。
+//! 转换结果只用于文件系统调用，不要写入配置或传给 Java 等外部程序。
+
+use std::path::{Path, PathBuf};
+
+/// 转换为扩展长度路径（仅 Windows）
+#[cfg(windows)]
+pub fn extended(path: &Path) -> PathBuf {
+    use std::ffi::OsString;
+    use std::path::{Component, Prefix};
+
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        match std::env::current_dir() {
+            Ok(cwd) => cwd.join(path),
+            Err(_) => return path.to_path_buf(),
+        }
+    };
+
+    let mut prefix = None;
+    let mut parts: Vec<OsString> = Vec::new();
+    for component in absolute.components() {
+        match component {
+            Component::Prefix(p) => match p.kind() {
+                Prefix::Disk(drive) => prefix = Some(format!(r"\\?\{}:", drive as char)),
+                Prefix::UNC(server, share) => {
+                    prefix = Some(format!(
+                        r"\\?\UNC\{}\{}",
+                        server.to_string_lossy(),
+                        share.to_string_lossy()
+                    ))
+                }
+                // 已是扩展路径或设备路径
+                _ => return absolute,
+            },
+            Component::RootDir | Component::CurDir => {}
+            Component::ParentDir => {
+                parts.pop();
+            }
+            Component::Normal(part) => parts.push(part.to_os_string()),
+        }
+    }
+
+    let Some(prefix) = prefix else {
+        return absolute;
+    };
+    let mut out = OsString::from(prefix);
+    if parts.is_empty() {
+        out.push(r"\");
+    }
+    for part in parts {
+        out.push(r"\");
+        out.push(part);
+    }
+    PathBuf::from(out)
+}
+
+/// 转换为扩展长度路径（仅 Windows）
+#[cfg(not(windows))]
+pub fn extended(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::extended;
+    use crate::services::instance_archive;
+    use crate::utils::file_utils;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    /// 测试用的临时目录（每个测试独立）
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("ar1s_long_path_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(extended(&root));
+        root
+    }
+
+    /// 相对路径超过 260 字符的嵌套文件
+    fn nested_relative() -> PathBuf {
+        let relative: PathBuf = (0..10).map(|i| format!("deeply_nested_config_dir_{:02}", i)).collect();
+        let relative = relative.join("settings.json");
+        assert!(relative.as_os_str().len() > 260);
+        relative
+    }
+
+    fn write_nested(root: &Path, relative: &Path) {
+        let file = extended(&root.join(relative));
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(&file, b"{\"long\":true}").unwrap();
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn extended_adds_disk_and_unc_prefixes() {
+        assert_eq!(
+            extended(Path::new(r"C:\games\.minecraft\versions\pack")),
+            PathBuf::from(r"\\?\C:\games\.minecraft\versions\pack")
+        );
+        assert_eq!(
+            extended(Path::new(r"\\server\share\mc\versions")),
+            PathBuf::from(r"\\?\UNC\server\share\mc\versions")
+        );
+        assert_eq!(extended(Path::new(r"\\?\C:\already")), PathBuf::from(r"\\?\C:\already"));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn extended_resolves_dot_components() {
+        assert_eq!(
+            extended(Path::new(r"C:\games\.\mc\versions\..\libraries")),
+            PathBuf::from(r"\\?\C:\games\mc\libraries")
+        );
+        assert_eq!(
+            extended(Path::new(r"\\server\share\mc\..\backup\.\worlds")),
+            PathBuf::from(r"\\?\UNC\server\share\backup\worlds")
+        );
+    }
+
+    #[test]
+    fn copy_round_trip_over_max_path() {
+        let root = temp_root("copy");
+        let relative = nested_relative();
+        write_nested(&root.join("src"), &relative);
+
+        file_utils::copy_dir_with_progress(&root.join("src"), &root.join("dst"), None, &mut |_| {}).unwrap();
+
+        let copied = fs::read(extended(&root.join("dst").join(&relative))).unwrap();
+        assert_eq!(copied, b"{\"long\":true}");
+        fs::remove_dir_all(extended(&root)).unwrap();
+    }
+
+    #[test]
+    fn archive_extract_round_trip_over_max_path() {
+        let root = temp_root("archive");
+        let relative = nested_relative();
+        write_nested(&root.join("instance"), &relative);
+
+        let archive = root.join("instance.zip");
+        instance_archive::write_archive(&root.join("instance"), &archive).unwrap();
+        instance_archive::extract_archive(&archive, &root.join("restored")).unwrap();
+
+        let restored = fs::read(extended(&root.join("restored").join(&relative))).unwrap();
+        assert_eq!(restored, b"{\"long\":true}");
+        fs::remove_dir_all(extended(&root)).unwrap();
+    }
+}
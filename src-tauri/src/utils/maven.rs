@@ -0,0 +1,203 @@
+//! Maven 坐标和版本号工具
+//!
+//! 版本 JSON、Forge 安装配置和处理器都用 `group:artifact:version[:classifier][@ext]`
+//! 描述库文件，这里统一解析坐标、构建 `libraries` 下的相对路径，并提供加载器版本排序。
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// 解析后的 Maven 坐标
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MavenCoordinate {
+    pub group: String,
+    pub artifact: String,
+    pub version: String,
+    pub classifier: Option<String>,
+    /// 文件扩展名，默认 `jar`
+    pub extension: String,
+}
+
+impl MavenCoordinate {
+    /// 解析 `group:artifact:version[:classifier][@ext]`
+    pub fn parse(descriptor: &str) -> Option<Self> {
+        let (coords, extension) = descriptor.split_once('@').unwrap_or((descriptor, "jar"));
+        let mut parts = coords.splitn(4, ':');
+        let group = parts.next().filter(|s| !s.is_empty())?;
+        let artifact = parts.next().filter(|s| !s.is_empty())?;
+        let version = parts.next().filter(|s| !s.is_empty())?;
+        Some(Self {
+            group: group.to_string(),
+            artifact: artifact.to_string(),
+            version: version.to_string(),
+            classifier: parts.next().filter(|s| !s.is_empty()).map(String::from),
+            extension: extension.to_string(),
+        })
+    }
+
+    /// 由库文件相对路径（`group/artifact/version/artifact-version[-classifier].ext`）推导坐标
+    pub fn from_path(relative: &str) -> Option<Self> {
+        let parts: Vec<&str> = relative.split('/').collect();
+        if parts.len() < 4 {
+            return None;
+        }
+        let (file, extension) = parts[parts.len() - 1].rsplit_once('.')?;
+        let version = parts[parts.len() - 2];
+        let artifact = parts[parts.len() - 3];
+        let base = format!("{}-{}", artifact, version);
+        let classifier = match file.strip_prefix(&base)? {
+            "" => None,
+            rest => Some(rest.strip_prefix('-')?.to_string()),
+        };
+        Some(Self {
+            group: parts[..parts.len() - 3].join("."),
+            artifact: artifact.to_string(),
+            version: version.to_string(),
+            classifier,
+            extension: extension.to_string(),
+        })
+    }
+
+    /// 替换分类器（如 natives 库的 `natives-windows`）
+    pub fn with_classifier(mut self, classifier: impl Into<String>) -> Self {
+        self.classifier = Some(classifier.into());
+        self
+    }
+
+    /// 文件名 `artifact-version[-classifier].ext`
+    pub fn file_name(&self) -> String {
+        match &self.classifier {
+            Some(c) => format!("{}-{}-{}.{}", self.artifact, self.version, c, self.extension),
+            None => format!("{}-{}.{}", self.artifact, self.version, self.extension),
+        }
+    }
+
+    /// `libraries` 目录下的相对路径（使用 `/` 分隔，也用于拼接下载地址）
+    pub fn path(&self) -> String {
+        format!(
+            "{}/{}/{}/{}",
+            self.group.replace('.', "/"),
+            self.artifact,
+            self.version,
+            self.file_name()
+        )
+    }
+}
+
+impl fmt::Display for MavenCoordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.group, self.artifact, self.version)?;
+        if let Some(c) = &self.classifier {
+            write!(f, ":{}", c)?;
+        }
+        if self.extension != "jar" {
+            write!(f, "@{}", self.extension)?;
+        }
+        Ok(())
+    }
+}
+
+/// Maven 描述符转换为 `libraries` 下的相对路径
+pub fn maven_to_path(descriptor: &str) -> Option<String> {
+    MavenCoordinate::parse(descriptor).map(|c| c.path())
+}
+
+/// 版本号中的一段
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum Segment<'a> {
+    /// 预发布标记（alpha / beta / pre / rc 等），排在同号正式版之前
+    Text(&'a str),
+    Number(u64),
+}
+
+fn segments(version: &str) -> Vec<Segment<'_>> {
+    // `+` 之后是构建元数据（如 Fabric 模组的 `0.15.0+1.20.1`），不参与比较
+    let version = version.split('+').next().unwrap_or(version);
+    version
+        .split(['.', '-', '_'])
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().map(Segment::Number).unwrap_or(Segment::Text(s)))
+        .collect()
+}
+
+/// 比较加载器或模组版本号
+///
+/// 数字段按数值比较，兼容 Forge 的四段版本（`14.23.5.2860`、`1.20.1-47.2.0`）；
+/// NeoForge 的 `20.4.80-beta` 等预发布版本排在 `20.4.80` 之前，缺少的数字段按 0 处理
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a, b) = (segments(a), segments(b));
+    for i in 0..a.len().max(b.len()) {
+        let ord = match (a.get(i), b.get(i)) {
+            (Some(x), Some(y)) => x.cmp(y),
+            // 正式版 > 预发布版，1.20 == 1.20.0
+            (None, Some(Segment::Text(_))) => Ordering::Greater,
+            (Some(Segment::Text(_)), None) => Ordering::Less,
+            (None, Some(Segment::Number(n))) => 0.cmp(n),
+            (Some(Segment::Number(n)), None) => n.cmp(&0),
+            (None, None) => Ordering::Equal,
+        };
+        if ord.is_ne() {
+            return ord;
+        }
+    }
+    Ordering::Equal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_with_classifier_and_extension() {
+        let c = MavenCoordinate::parse("de.oceanlabs.mcp:mcp_config:1.20.1-20230612.114412:mappings@txt").unwrap();
+        assert_eq!(c.group, "de.oceanlabs.mcp");
+        assert_eq!(c.artifact, "mcp_config");
+        assert_eq!(c.version, "1.20.1-20230612.114412");
+        assert_eq!(c.classifier.as_deref(), Some("mappings"));
+        assert_eq!(c.extension, "txt");
+        assert_eq!(
+            c.path(),
+            "de/oceanlabs/mcp/mcp_config/1.20.1-20230612.114412/mcp_config-1.20.1-20230612.114412-mappings.txt"
+        );
+        assert_eq!(c.to_string(), "de.oceanlabs.mcp:mcp_config:1.20.1-20230612.114412:mappings@txt");
+
+        let plain = MavenCoordinate::parse("net.fabricmc:fabric-loader:0.15.3").unwrap();
+        assert_eq!(plain.classifier, None);
+        assert_eq!(plain.extension, "jar");
+        assert!(MavenCoordinate::parse("net.fabricmc:fabric-loader").is_none());
+    }
+
+    #[test]
+    fn from_path_round_trip() {
+        for descriptor in [
+            "net.minecraftforge:forge:1.20.1-47.2.0:universal",
+            "org.ow2.asm:asm:9.6",
+            "de.oceanlabs.mcp:mcp_config:1.20.1-20230612.114412:mappings@txt",
+        ] {
+            let coordinate = MavenCoordinate::parse(descriptor).unwrap();
+            assert_eq!(MavenCoordinate::from_path(&coordinate.path()), Some(coordinate));
+        }
+        assert!(MavenCoordinate::from_path("asm-9.6.jar").is_none());
+    }
+
+    #[test]
+    fn lwjgl_platform_natives_path() {
+        let natives = MavenCoordinate::parse("org.lwjgl.lwjgl:lwjgl-platform:2.9.4-nightly-20150209")
+            .unwrap()
+            .with_classifier("natives-windows");
+        assert_eq!(
+            natives.path(),
+            "org/lwjgl/lwjgl/lwjgl-platform/2.9.4-nightly-20150209/lwjgl-platform-2.9.4-nightly-20150209-natives-windows.jar"
+        );
+    }
+
+    #[test]
+    fn compare_loader_versions() {
+        assert_eq!(compare_versions("14.23.5.2860", "14.23.5.2859"), Ordering::Greater);
+        assert_eq!(compare_versions("14.23.5.2860", "14.23.10.2000"), Ordering::Less);
+        assert_eq!(compare_versions("1.20.1-47.2.0", "1.20.1-47.10.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.20.1-47.2.0", "1.19.4-45.2.0"), Ordering::Greater);
+        assert_eq!(compare_versions("20.4.80-beta", "20.4.80"), Ordering::Less);
+        assert_eq!(compare_versions("20.4.80", "20.4.80-beta"), Ordering::Greater);
+        assert_eq!(compare_versions("1.20", "1.20.0"), Ordering::Equal);
+    }
+}
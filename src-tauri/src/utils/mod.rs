@@ -1,2 +1,9 @@
+pub mod cli;
+pub mod client_jar;
+pub mod encoding;
 pub mod file_utils;
+pub mod nbt;
 pub mod logger;
+pub mod long_path;
+pub mod maven;
+pub mod mod_metadata;
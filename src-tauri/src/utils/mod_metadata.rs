@@ -0,0 +1,339 @@
+//! 模组元数据解析
+//!
+//! 从模组 JAR 中读取 fabric.mod.json、quilt.mod.json、(neoforge.)mods.toml
+//! 或 mcmod.info，得到模组 ID、版本、适用的加载器、Minecraft 版本要求和必需的前置模组。
+
+use crate::errors::LauncherError;
+use crate::utils::maven::compare_versions;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+
+/// 由游戏或加载器本身提供、不需要作为模组安装的依赖
+const BUILTIN_DEPENDENCIES: [&str; 9] = [
+    "minecraft",
+    "java",
+    "fabricloader",
+    "fabric-loader",
+    "quilt_loader",
+    "forge",
+    "neoforge",
+    "fml",
+    "javafml",
+];
+
+/// 内嵌 JAR 的最大嵌套层数
+const MAX_NESTED_DEPTH: usize = 2;
+
+/// 模组元数据
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModMetadata {
+    pub mod_id: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// 适用的加载器（小写：fabric / quilt / forge / neoforge）
+    pub loaders: Vec<String>,
+    /// Minecraft 版本要求（任一满足即可），未声明时为空
+    pub minecraft: Vec<String>,
+    /// 必需的前置模组 ID（不含游戏和加载器本身）
+    pub dependencies: Vec<String>,
+    /// 额外提供的模组 ID（provides 声明和内嵌 JAR 中的模组）
+    pub provides: Vec<String>,
+}
+
+fn is_builtin(id: &str) -> bool {
+    BUILTIN_DEPENDENCIES.contains(&id.to_lowercase().as_str())
+}
+
+fn read_entry<R: Read + Seek>(archive: &mut zip::ZipArchive<R>, name: &str) -> Option<String> {
+    let mut file = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    file.read_to_string(&mut content).ok()?;
+    Some(content)
+}
+
+/// 字符串或字符串数组
+fn string_list(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(items) => items.iter().filter_map(|v| v.as_str().map(String::from)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn parse_fabric(content: &str) -> Option<ModMetadata> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    Some(ModMetadata {
+        mod_id: json["id"].as_str()?.to_string(),
+        name: json["name"].as_str().map(String::from),
+        version: json["version"].as_str().map(String::from),
+        loaders: vec!["fabric".to_string()],
+        minecraft: string_list(&json["depends"]["minecraft"]),
+        dependencies: json["depends"]
+            .as_object()
+            .into_iter()
+            .flat_map(|deps| deps.keys())
+            .filter(|id| !is_builtin(id))
+            .cloned()
+            .collect(),
+        provides: string_list(&json["provides"]),
+    })
+}
+
+fn parse_quilt(content: &str) -> Option<ModMetadata> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    let loader = &json["quilt_loader"];
+    let minecraft = loader["depends"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|d| d["id"].as_str() == Some("minecraft"))
+        .flat_map(|d| string_list(&d["versions"]))
+        .collect();
+    // 依赖可以是字符串或对象，optional 的对象不是必需依赖
+    let dependencies = loader["depends"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|d| !d["optional"].as_bool().unwrap_or(false))
+        .filter_map(|d| d.as_str().or_else(|| d["id"].as_str()))
+        .filter(|id| !is_builtin(id))
+        .map(String::from)
+        .collect();
+    let provides = loader["provides"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|p| p.as_str().or_else(|| p["id"].as_str()))
+        .map(String::from)
+        .collect();
+    Some(ModMetadata {
+        mod_id: loader["id"].as_str()?.to_string(),
+        name: loader["metadata"]["name"].as_str().map(String::from),
+        version: loader["version"].as_str().map(String::from),
+        loaders: vec!["quilt".to_string()],
+        minecraft,
+        dependencies,
+        provides,
+    })
+}
+
+fn parse_mods_toml(content: &str, loader: &str) -> Option<ModMetadata> {
+    let table: toml::Table = toml::from_str(content).ok()?;
+    let first = table.get("mods")?.as_array()?.first()?;
+    let mod_id = first.get("modId")?.as_str()?.to_string();
+    let deps: Vec<&toml::Value> = table
+        .get("dependencies")
+        .and_then(|d| d.get(&mod_id))
+        .and_then(|d| d.as_array())
+        .into_iter()
+        .flatten()
+        .collect();
+    let minecraft = deps
+        .iter()
+        .filter(|d| d.get("modId").and_then(|v| v.as_str()) == Some("minecraft"))
+        .filter_map(|d| d.get("versionRange").and_then(|v| v.as_str()).map(String::from))
+        .collect();
+    // Forge 使用 mandatory，NeoForge 使用 type = "required"
+    let dependencies = deps
+        .iter()
+        .filter(|d| {
+            d.get("mandatory").and_then(|v| v.as_bool()).unwrap_or(false)
+                || d.get("type").and_then(|v| v.as_str()) == Some("required")
+        })
+        .filter_map(|d| d.get("modId").and_then(|v| v.as_str()))
+        .filter(|id| !is_builtin(id))
+        .map(String::from)
+        .collect();
+    Some(ModMetadata {
+        name: first.get("displayName").and_then(|v| v.as_str()).map(String::from),
+        // `${file.jarVersion}` 等占位符无实际意义
+        version: first
+            .get("version")
+            .and_then(|v| v.as_str())
+            .filter(|v| !v.starts_with("${"))
+            .map(String::from),
+        loaders: vec![loader.to_string()],
+        minecraft,
+        dependencies,
+        provides: Vec::new(),
+        mod_id,
+    })
+}
+
+fn parse_mcmod_info(content: &str) -> Option<ModMetadata> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    let first = match &json {
+        Value::Array(list) => list.first()?,
+        _ => json["modList"].as_array()?.first()?,
+    };
+    Some(ModMetadata {
+        mod_id: first["modid"].as_str()?.to_string(),
+        name: first["name"].as_str().map(String::from),
+        version: first["version"].as_str().map(String::from),
+        loaders: vec!["forge".to_string()],
+        minecraft: first["mcversion"].as_str().map(String::from).into_iter().collect(),
+        dependencies: string_list(&first["requiredMods"])
+            .into_iter()
+            .filter(|id| !is_builtin(id))
+            .collect(),
+        provides: Vec::new(),
+    })
+}
+
+/// 内嵌 JAR 在压缩包中的路径（Fabric/Quilt 的 jars 声明和 Forge 的 jarjar 清单）
+fn nested_jar_paths<R: Read + Seek>(archive: &mut zip::ZipArchive<R>) -> Vec<String> {
+    let mut paths = Vec::new();
+    if let Some(json) = read_entry(archive, "fabric.mod.json").and_then(|c| serde_json::from_str::<Value>(&c).ok()) {
+        paths.extend(json["jars"].as_array().into_iter().flatten().filter_map(|j| j["file"].as_str().map(String::from)));
+    }
+    if let Some(json) = read_entry(archive, "quilt.mod.json").and_then(|c| serde_json::from_str::<Value>(&c).ok()) {
+        paths.extend(string_list(&json["quilt_loader"]["jars"]));
+    }
+    if let Some(json) = read_entry(archive, "META-INF/jarjar/metadata.json").and_then(|c| serde_json::from_str::<Value>(&c).ok()) {
+        paths.extend(json["jars"].as_array().into_iter().flatten().filter_map(|j| j["path"].as_str().map(String::from)));
+    }
+    paths
+}
+
+/// 读取内嵌 JAR 的模组 ID（含其 provides 和更深层的内嵌 JAR）
+fn nested_mod_ids<R: Read + Seek>(archive: &mut zip::ZipArchive<R>, depth: usize) -> Vec<String> {
+    let mut ids = Vec::new();
+    if depth >= MAX_NESTED_DEPTH {
+        return ids;
+    }
+    for path in nested_jar_paths(archive) {
+        let mut bytes = Vec::new();
+        let Ok(mut entry) = archive.by_name(&path) else {
+            continue;
+        };
+        if entry.read_to_end(&mut bytes).is_err() {
+            continue;
+        }
+        drop(entry);
+        let Ok(mut nested) = zip::ZipArchive::new(Cursor::new(bytes)) else {
+            continue;
+        };
+        if let Some(meta) = read_archive_metadata(&mut nested, depth + 1) {
+            ids.push(meta.mod_id);
+            ids.extend(meta.provides);
+        }
+    }
+    ids
+}
+
+fn read_archive_metadata<R: Read + Seek>(archive: &mut zip::ZipArchive<R>, depth: usize) -> Option<ModMetadata> {
+    let mut found: Vec<ModMetadata> = Vec::new();
+    if let Some(m) = read_entry(archive, "quilt.mod.json").and_then(|c| parse_quilt(&c)) {
+        found.push(m);
+    }
+    if let Some(m) = read_entry(archive, "fabric.mod.json").and_then(|c| parse_fabric(&c)) {
+        found.push(m);
+    }
+    if let Some(m) = read_entry(archive, "META-INF/neoforge.mods.toml").and_then(|c| parse_mods_toml(&c, "neoforge")) {
+        found.push(m);
+    }
+    if let Some(m) = read_entry(archive, "META-INF/mods.toml").and_then(|c| parse_mods_toml(&c, "forge")) {
+        found.push(m);
+    }
+    if found.is_empty() {
+        if let Some(m) = read_entry(archive, "mcmod.info").and_then(|c| parse_mcmod_info(&c)) {
+            found.push(m);
+        }
+    }
+
+    // 多加载器模组（同时包含多种元数据）合并为一条
+    let mut iter = found.into_iter();
+    let mut merged = iter.next()?;
+    for other in iter {
+        for (target, items) in [
+            (&mut merged.loaders, other.loaders),
+            (&mut merged.minecraft, other.minecraft),
+            (&mut merged.dependencies, other.dependencies),
+            (&mut merged.provides, other.provides),
+        ] {
+            for item in items {
+                if !target.contains(&item) {
+                    target.push(item);
+                }
+            }
+        }
+    }
+    merged.provides.extend(nested_mod_ids(archive, depth));
+    Some(merged)
+}
+
+/// 读取模组 JAR 的元数据；不是可识别的模组时返回 None
+pub fn read_mod_metadata(path: &Path) -> Result<Option<ModMetadata>, LauncherError> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(path)?)?;
+    Ok(read_archive_metadata(&mut archive, 0))
+}
+
+/// 单个 Fabric 风格版本谓词（如 `>=1.20`、`~1.20.1`、`1.20.x`、`*`）
+fn matches_predicate(version: &str, predicate: &str) -> bool {
+    use std::cmp::Ordering::*;
+    let predicate = predicate.trim();
+    if predicate.is_empty() || predicate == "*" {
+        return true;
+    }
+    for (op, check) in [
+        (">=", &[Greater, Equal][..]),
+        ("<=", &[Less, Equal][..]),
+        (">", &[Greater][..]),
+        ("<", &[Less][..]),
+        ("=", &[Equal][..]),
+    ] {
+        if let Some(target) = predicate.strip_prefix(op) {
+            return check.contains(&compare_versions(version, target.trim()));
+        }
+    }
+    // ~1.20.1：同一次版本；^1.20：同一主版本（MC 中视为同一次版本）
+    if let Some(target) = predicate.strip_prefix('~').or_else(|| predicate.strip_prefix('^')) {
+        let minor: Vec<&str> = target.split('.').take(2).collect();
+        return compare_versions(version, target).is_ge()
+            && version.split('.').take(2).eq(minor.iter().copied());
+    }
+    if let Some(prefix) = predicate.strip_suffix(".x").or_else(|| predicate.strip_suffix(".*")) {
+        return version == prefix || version.starts_with(&format!("{}.", prefix));
+    }
+    version == predicate
+}
+
+/// Maven 版本区间（如 `[1.20,1.21)`、`[1.19.2]`）
+fn matches_maven_range(version: &str, range: &str) -> Option<bool> {
+    let range = range.trim();
+    let (open, close) = (range.chars().next()?, range.chars().last()?);
+    if !matches!(open, '[' | '(') || !matches!(close, ']' | ')') {
+        return None;
+    }
+    let inner = &range[1..range.len() - 1];
+    let Some((low, high)) = inner.split_once(',') else {
+        return Some(compare_versions(version, inner.trim()).is_eq());
+    };
+    let (low, high) = (low.trim(), high.trim());
+    let low_ok = low.is_empty() || {
+        let ord = compare_versions(version, low);
+        ord.is_gt() || (open == '[' && ord.is_eq())
+    };
+    let high_ok = high.is_empty() || {
+        let ord = compare_versions(version, high);
+        ord.is_lt() || (close == ']' && ord.is_eq())
+    };
+    Some(low_ok && high_ok)
+}
+
+/// 判断 Minecraft 版本是否满足模组声明的要求；无法判断（如快照版本）时视为满足
+pub fn minecraft_matches(mc_version: &str, requirements: &[String]) -> bool {
+    if requirements.is_empty() || !mc_version.chars().next().is_some_and(|c| c.is_ascii_digit()) || mc_version.contains('w') {
+        return true;
+    }
+    requirements.iter().any(|req| {
+        matches_maven_range(mc_version, req).unwrap_or_else(|| {
+            // Fabric 谓词：空格分隔的条件需同时满足
+            req.split_whitespace().all(|p| matches_predicate(mc_version, p))
+        })
+    })
+}
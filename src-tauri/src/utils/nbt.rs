@@ -0,0 +1,161 @@
+//! 最小化的 NBT 读取（用于 level.dat 等存档文件）
+
+use flate2::read::GzDecoder;
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// NBT 标签
+#[derive(Debug, Clone, PartialEq)]
+pub enum NbtTag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(Vec<NbtTag>),
+    Compound(HashMap<String, NbtTag>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl NbtTag {
+    /// 按路径读取嵌套复合标签中的值，如 `["Data", "Version", "Name"]`
+    pub fn get_path(&self, path: &[&str]) -> Option<&NbtTag> {
+        path.iter().try_fold(self, |tag, key| match tag {
+            NbtTag::Compound(map) => map.get(*key),
+            _ => None,
+        })
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            NbtTag::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            NbtTag::Byte(v) => Some(*v as i64),
+            NbtTag::Short(v) => Some(*v as i64),
+            NbtTag::Int(v) => Some(*v as i64),
+            NbtTag::Long(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+/// 嵌套深度上限，防止恶意文件导致栈溢出
+const MAX_DEPTH: usize = 512;
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+fn read_u8(r: &mut impl Read) -> io::Result<u8> {
+    let mut buf = [0u8; 1];
+    r.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_array<const N: usize>(r: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut buf = [0u8; N];
+    r.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_len(r: &mut impl Read) -> io::Result<usize> {
+    let len = i32::from_be_bytes(read_array(r)?);
+    usize::try_from(len).map_err(|_| invalid("NBT 长度为负数"))
+}
+
+fn read_string(r: &mut impl Read) -> io::Result<String> {
+    let len = u16::from_be_bytes(read_array(r)?) as usize;
+    let mut buf = vec![0u8; len];
+    r.read_exact(&mut buf)?;
+    // NBT 使用 Modified UTF-8，常规文本与 UTF-8 一致
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+fn read_payload(r: &mut impl Read, tag_type: u8, depth: usize) -> io::Result<NbtTag> {
+    if depth > MAX_DEPTH {
+        return Err(invalid("NBT 嵌套过深"));
+    }
+    Ok(match tag_type {
+        1 => NbtTag::Byte(read_u8(r)? as i8),
+        2 => NbtTag::Short(i16::from_be_bytes(read_array(r)?)),
+        3 => NbtTag::Int(i32::from_be_bytes(read_array(r)?)),
+        4 => NbtTag::Long(i64::from_be_bytes(read_array(r)?)),
+        5 => NbtTag::Float(f32::from_be_bytes(read_array(r)?)),
+        6 => NbtTag::Double(f64::from_be_bytes(read_array(r)?)),
+        7 => {
+            let len = read_len(r)?;
+            // 不按声明长度预分配，避免损坏文件导致巨量内存申请
+            let mut buf = Vec::new();
+            r.by_ref().take(len as u64).read_to_end(&mut buf)?;
+            if buf.len() != len {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            NbtTag::ByteArray(buf.into_iter().map(|b| b as i8).collect())
+        }
+        8 => NbtTag::String(read_string(r)?),
+        9 => {
+            let item_type = read_u8(r)?;
+            let len = read_len(r)?;
+            let mut items = Vec::new();
+            for _ in 0..len {
+                items.push(read_payload(r, item_type, depth + 1)?);
+            }
+            NbtTag::List(items)
+        }
+        10 => {
+            let mut map = HashMap::new();
+            loop {
+                let child_type = read_u8(r)?;
+                if child_type == 0 {
+                    break;
+                }
+                let name = read_string(r)?;
+                map.insert(name, read_payload(r, child_type, depth + 1)?);
+            }
+            NbtTag::Compound(map)
+        }
+        11 => {
+            let len = read_len(r)?;
+            let mut items = Vec::new();
+            for _ in 0..len {
+                items.push(i32::from_be_bytes(read_array(r)?));
+            }
+            NbtTag::IntArray(items)
+        }
+        12 => {
+            let len = read_len(r)?;
+            let mut items = Vec::new();
+            for _ in 0..len {
+                items.push(i64::from_be_bytes(read_array(r)?));
+            }
+            NbtTag::LongArray(items)
+        }
+        _ => return Err(invalid("未知的 NBT 标签类型")),
+    })
+}
+
+/// 读取未压缩的 NBT 根标签（根标签名被忽略）
+pub fn read_nbt(r: &mut impl Read) -> io::Result<NbtTag> {
+    let root_type = read_u8(r)?;
+    if root_type != 10 {
+        return Err(invalid("NBT 根标签不是复合标签"));
+    }
+    read_string(r)?;
+    read_payload(r, root_type, 0)
+}
+
+/// 读取 gzip 压缩的 NBT 文件（如 level.dat）
+pub fn read_gzip_file(path: &Path) -> io::Result<NbtTag> {
+    let file = std::fs::File::open(path)?;
+    read_nbt(&mut io::BufReader::new(GzDecoder::new(file)))
+}
@@ -4,6 +4,7 @@ import { useSettingsStore } from '../stores/settings';
 import { useNotificationStore } from '../stores/notificationStore';
 import { listen } from '@tauri-apps/api/event';
 import type { UnlistenFn } from '@tauri-apps/api/event';
+import { instanceApi } from '../services/api';
 import type { DownloadProgress } from '../types/events';
 
 export function useGameLaunch() {
@@ -15,12 +16,8 @@ export function useGameLaunch() {
     
     let unlistenRepairProgress: UnlistenFn | null = null;
 
-    async function launchGame(
-        version: string,
-        username: string,
-        offline: boolean,
-        gameDir: string
-    ) {
+    /** 通过实例流程启动（使用实例设置和已保存的账户） */
+    async function launchGame(version: string) {
         if (!version) {
             notificationStore.warning('请先选择一个版本');
             return;
@@ -49,15 +46,7 @@ export function useGameLaunch() {
                 return;
             }
 
-            await invoke('launch_minecraft', {
-                options: {
-                    version: version,
-                    memory: settingsStore.maxMemory,
-                    username: username,
-                    offline: offline,
-                    game_dir: gameDir
-                }
-            });
+            await instanceApi.launchInstance(version);
         } catch (err) {
             console.error('Failed to launch game:', err);
             const errorMessage = err instanceof Error ? err.message : String(err);
@@ -6,10 +6,39 @@ import { invoke } from '@tauri-apps/api/core';
 import type {
   VersionManifest,
   GameInstance,
+  LoaderPayload,
   AvailableLoaders,
   ForgeVersion,
   LoaderVersionInfo,
   InstanceNameValidation,
+  InstanceSettings,
+  LaunchOverrides,
+  ShareInfo,
+  VerificationLevel,
+  VerificationEstimate,
+  NativesDirInfo,
+  NativesPruneResult,
+  TroubleshootingToggleInfo,
+  DefaultJvmFlagInfo,
+  SessionInfo,
+  ModpackUpdatePreview,
+  KioskStatus,
+  LifecycleHooks,
+  HostLimit,
+  RegeneratedVersionJson,
+  ModInstallResult,
+  ModListFormat,
+  ExportedModList,
+  ArchivedInstance,
+  ServerSyncReport,
+  AssetGcReport,
+  InstanceHealth,
+  AccountInfo,
+  AccountType,
+  SkinInfo,
+  SkinModel,
+  ResourcePackScan,
+  ConsolidateResult,
 } from '../types/events';
 
 // ============ 请求去重机制 ============
@@ -82,9 +111,19 @@ export const versionApi = {
     return invoke('cancel_download');
   },
 
-  /** 验证版本文件完整性 */
-  async validateVersionFiles(versionId: string): Promise<string[]> {
-    return dedupedInvoke<string[]>('validate_version_files', { versionId });
+  /** 回收所有版本都不再引用的资源文件（dryRun 为 true 时只统计可释放的空间） */
+  async collectAssetGarbage(dryRun = false): Promise<AssetGcReport> {
+    return invoke('collect_asset_garbage', { dryRun });
+  },
+
+  /** 验证版本文件完整性，默认只检查文件存在和大小 */
+  async validateVersionFiles(versionId: string, level?: VerificationLevel): Promise<string[]> {
+    return dedupedInvoke<string[]>('validate_version_files', { versionId, level });
+  },
+
+  /** 估算各校验级别的耗时 */
+  async estimateVersionVerification(versionId: string): Promise<VerificationEstimate[]> {
+    return invoke('estimate_version_verification', { versionId });
   },
 };
 
@@ -96,23 +135,82 @@ export const instanceApi = {
     return dedupedInvoke<GameInstance[]>('get_instances');
   },
 
-  /** 创建实例 */
+  /** 创建实例，可同时安装 Forge / Fabric / Quilt / NeoForge 加载器 */
   async createInstance(
     newInstanceName: string,
     baseVersionId: string,
-    loader?: { type: string; mc_version: string; loader_version: string }
+    loader?: LoaderPayload
   ): Promise<void> {
     return invoke('create_instance', { newInstanceName, baseVersionId, loader });
   },
 
-  /** 删除实例 */
-  async deleteInstance(instanceName: string): Promise<void> {
-    return invoke('delete_instance', { instanceName });
+  /** 从版本 JSON 地址安装自定义版本 */
+  async installVersionFromUrl(jsonUrl: string, name: string): Promise<void> {
+    return invoke('install_version_from_url', { jsonUrl, name });
+  },
+
+  /** 根据记录的游戏版本和加载器重新生成实例的版本 JSON */
+  async regenerateVersionJson(instanceName: string): Promise<RegeneratedVersionJson> {
+    return invoke('regenerate_version_json', { instanceName });
+  },
+
+  /** 从 Modrinth 安装模组，返回结果和推荐安装的可选依赖（一键安装时传入建议中的版本 ID） */
+  async installModrinthMod(
+    instanceName: string,
+    projectId: string,
+    versionId?: string,
+  ): Promise<ModInstallResult> {
+    return invoke('install_modrinth_mod', { instanceName, projectId, versionId });
+  },
+
+  /** 导出实例的模组列表（写入实例目录的 mods.md / mods.csv） */
+  async exportModList(instanceName: string, format: ModListFormat): Promise<ExportedModList> {
+    return invoke('export_mod_list', { instanceName, format });
+  },
+
+  /** 按服务器清单同步实例（省略地址时使用上次的地址；dryRun 为 true 时只预览差异） */
+  async syncInstanceFromServer(
+    instanceName: string,
+    manifestUrl?: string,
+    protectedPaths?: string[],
+    dryRun = false,
+  ): Promise<ServerSyncReport> {
+    return invoke('sync_instance_from_server', { instanceName, manifestUrl, protectedPaths, dryRun });
+  },
+
+  /** 实例上次同步的清单地址 */
+  async getServerSyncUrl(instanceName: string): Promise<string | null> {
+    return invoke('get_server_sync_url', { instanceName });
+  },
+
+  /** 实例健康检查（结果有缓存，refresh 为 true 时重新检查） */
+  async getInstanceHealth(instance: string, refresh = false): Promise<InstanceHealth> {
+    return invoke('get_instance_health', { instance, refresh });
+  },
+
+  /** 删除实例（force 为 true 时先结束正在运行的游戏） */
+  async deleteInstance(instanceName: string, force = false): Promise<void> {
+    return invoke('delete_instance', { instanceName, force });
+  },
+
+  /** 重命名实例（force 为 true 时先结束正在运行的游戏） */
+  async renameInstance(oldName: string, newName: string, force = false): Promise<void> {
+    return invoke('rename_instance', { oldName, newName, force });
+  },
+
+  /** 归档实例：压缩后移出版本目录（force 为 true 时先结束正在运行的游戏） */
+  async archiveInstance(instanceName: string, force = false): Promise<ArchivedInstance> {
+    return invoke('archive_instance', { instanceName, force });
+  },
+
+  /** 从归档恢复实例 */
+  async unarchiveInstance(instanceName: string): Promise<void> {
+    return invoke('unarchive_instance', { instanceName });
   },
 
-  /** 重命名实例 */
-  async renameInstance(oldName: string, newName: string): Promise<void> {
-    return invoke('rename_instance', { oldName, newName });
+  /** 已归档的实例 */
+  async listArchivedInstances(): Promise<ArchivedInstance[]> {
+    return invoke('list_archived_instances');
   },
 
   /** 打开实例文件夹 */
@@ -120,9 +218,44 @@ export const instanceApi = {
     return invoke('open_instance_folder', { instanceName });
   },
 
-  /** 启动实例 */
-  async launchInstance(instanceName: string): Promise<void> {
-    return invoke('launch_instance', { instanceName });
+  /** 启动实例，overrides 只对本次启动生效 */
+  async launchInstance(instanceName: string, overrides?: LaunchOverrides): Promise<void> {
+    return invoke('launch_instance', { instanceName, overrides: overrides ?? null });
+  },
+
+  /** 读取实例独立设置 */
+  async getInstanceSettings(instanceName: string): Promise<InstanceSettings> {
+    return invoke('get_instance_settings', { instanceName });
+  },
+
+  /** 保存实例独立设置（包括游戏进程代理） */
+  async setInstanceSettings(instanceName: string, settings: InstanceSettings): Promise<void> {
+    return invoke('set_instance_settings', { instanceName, settings });
+  },
+
+  /** 在局域网中分享实例 */
+  async startInstanceShare(instanceName: string): Promise<ShareInfo> {
+    return invoke('start_instance_share', { instanceName });
+  },
+
+  /** 停止局域网分享 */
+  async stopInstanceShare(): Promise<void> {
+    return invoke('stop_instance_share');
+  },
+
+  /** 通过分享码接收实例，返回导入后的实例名 */
+  async receiveInstance(code: string, instanceName?: string): Promise<string> {
+    return invoke('receive_instance', { code, instanceName: instanceName ?? null });
+  },
+
+  /** 扫描各实例和公共目录中重复的资源包 */
+  async scanDuplicateResourcepacks(): Promise<ResourcePackScan> {
+    return invoke('scan_duplicate_resourcepacks');
+  },
+
+  /** 合并重复的资源包到公共目录，sha1s 为空时处理全部 */
+  async consolidateResourcepacks(sha1s?: string[]): Promise<ConsolidateResult> {
+    return invoke('consolidate_resourcepacks', { sha1s: sha1s ?? null });
   },
 
   /** 验证实例名称 */
@@ -217,6 +350,11 @@ export const configApi = {
     return invoke('set_download_threads', { threads });
   },
 
+  /** 设置 Modrinth 文件镜像（为空时使用官方 CDN） */
+  async setModrinthMirror(mirror: string | null): Promise<void> {
+    return invoke('set_modrinth_mirror', { mirror });
+  },
+
   /** 加载配置项 */
   async loadConfigKey(key: string): Promise<string | null> {
     return dedupedInvoke<string | null>('load_config_key', { key });
@@ -227,6 +365,56 @@ export const configApi = {
     return invoke('save_config_key', { key, value });
   },
 
+  /** 获取管理员锁定状态 */
+  async getKioskStatus(): Promise<KioskStatus> {
+    return invoke('get_kiosk_status');
+  },
+
+  /** 用管理员 PIN 解锁设置、实例删除和账户修改 */
+  async unlockAdmin(pin: string): Promise<KioskStatus> {
+    return invoke('unlock_admin', { pin });
+  },
+
+  /** 立即恢复锁定 */
+  async lockAdmin(): Promise<KioskStatus> {
+    return invoke('lock_admin');
+  },
+
+  /** 设置管理员 PIN，传 null 关闭锁定模式 */
+  async setAdminPin(pin: string | null): Promise<KioskStatus> {
+    return invoke('set_admin_pin', { pin });
+  },
+
+  /** 覆盖默认 JVM 参数规则，传 null 恢复自动判断 */
+  async setJvmFlagOverride(id: string, enabled: boolean | null): Promise<void> {
+    return invoke('set_jvm_flag_override', { id, enabled });
+  },
+
+  /** 获取全局自定义 JVM 参数 */
+  async getExtraJvmArgs(): Promise<string[]> {
+    return invoke('get_extra_jvm_args');
+  },
+
+  /** 设置全局自定义 JVM 参数（如 -XX:+UseZGC） */
+  async setExtraJvmArgs(args: string[]): Promise<void> {
+    return invoke('set_extra_jvm_args', { args });
+  },
+
+  /** 各下载主机生效的并发限制 */
+  async getHostLimits(): Promise<Record<string, HostLimit>> {
+    return invoke('get_host_limits');
+  },
+
+  /** 覆盖下载主机的并发限制，传 null 恢复默认值 */
+  async setHostLimit(host: string, limit: HostLimit | null): Promise<void> {
+    return invoke('set_host_limit', { host, limit });
+  },
+
+  /** 设置全局生命周期钩子 */
+  async setGlobalHooks(hooks: LifecycleHooks): Promise<void> {
+    return invoke('set_global_hooks', { hooks });
+  },
+
   /** 获取上次选择的版本 */
   async getLastSelectedVersion(): Promise<string | null> {
     return dedupedInvoke<string | null>('get_last_selected_version');
@@ -241,6 +429,16 @@ export const configApi = {
   async getTotalMemory(): Promise<number> {
     return dedupedInvoke<number>('get_total_memory');
   },
+
+  /** 开始实时内存采样（通过 memory-stats 事件推送），返回实际间隔（毫秒） */
+  async startMemorySampling(intervalMs?: number): Promise<number> {
+    return invoke('start_memory_sampling', { intervalMs: intervalMs ?? null });
+  },
+
+  /** 停止实时内存采样 */
+  async stopMemorySampling(): Promise<void> {
+    return invoke('stop_memory_sampling');
+  },
 };
 
 // ============ 用户相关 API ============
@@ -265,20 +463,84 @@ export const userApi = {
   async setSavedUuid(uuid: string): Promise<void> {
     return invoke('set_saved_uuid', { uuid });
   },
+
+  /** 列出保存的账户 */
+  async getAccounts(): Promise<AccountInfo[]> {
+    return invoke('get_accounts');
+  },
+
+  /** 添加账户（默认离线账户；正版账户需提供 UUID 和访问令牌） */
+  async addAccount(
+    username: string,
+    accountType: AccountType = 'legacy',
+    online?: { uuid: string; accessToken: string; xuid?: string },
+  ): Promise<AccountInfo> {
+    return invoke('add_account', {
+      username,
+      accountType,
+      uuid: online?.uuid,
+      accessToken: online?.accessToken,
+      xuid: online?.xuid,
+    });
+  },
+
+  /** 删除账户 */
+  async removeAccount(accountId: string): Promise<void> {
+    return invoke('remove_account', { accountId });
+  },
+
+  /** 设置默认账户 */
+  async setActiveAccount(accountId: string): Promise<AccountInfo> {
+    return invoke('set_active_account', { accountId });
+  },
+
+  /** 获取账户的皮肤（不指定账户时为默认账户） */
+  async getSkin(accountId?: string): Promise<SkinInfo> {
+    return invoke('get_skin', { accountId });
+  },
+
+  /** 上传皮肤（PNG，64×64 或 64×32） */
+  async uploadSkin(filePath: string, model: SkinModel = 'classic', accountId?: string): Promise<SkinInfo> {
+    return invoke('upload_skin', { accountId, filePath, model });
+  },
+
+  /** 切换皮肤模型 */
+  async setSkinVariant(model: SkinModel, accountId?: string): Promise<SkinInfo> {
+    return invoke('set_skin_variant', { accountId, model });
+  },
+
+  /** 选择显示的披风，不指定 capeId 时隐藏披风 */
+  async setActiveCape(capeId: string | null, accountId?: string): Promise<SkinInfo> {
+    return invoke('set_active_cape', { accountId, capeId });
+  },
 };
 
 // ============ 启动器相关 API ============
 
 export const launcherApi = {
-  /** 启动 Minecraft */
-  async launchMinecraft(options: {
-    version: string;
-    memory: number;
-    username: string;
-    offline: boolean;
-    game_dir: string;
-  }): Promise<void> {
-    return invoke('launch_minecraft', { options });
+  /** 实例可用的故障排查启动选项 */
+  async getTroubleshootingOptions(instanceName: string): Promise<TroubleshootingToggleInfo[]> {
+    return invoke('get_troubleshooting_options', { instanceName });
+  },
+
+  /** 获取实例的启动会话（最新的在前） */
+  async getSessions(instanceName: string): Promise<SessionInfo[]> {
+    return invoke('get_sessions', { instanceName });
+  },
+
+  /** 获取实例的默认 JVM 参数规则 */
+  async getDefaultJvmFlags(instanceName: string): Promise<DefaultJvmFlagInfo[]> {
+    return invoke('get_default_jvm_flags', { instanceName });
+  },
+
+  /** 列出可清理的 natives 目录和过期临时目录 */
+  async listOrphanedNatives(unusedDays?: number): Promise<NativesDirInfo[]> {
+    return invoke('list_orphaned_natives', { unusedDays });
+  },
+
+  /** 清理 natives 和临时目录，不指定 paths 时清理全部 */
+  async pruneNatives(unusedDays?: number, paths?: string[]): Promise<NativesPruneResult> {
+    return invoke('prune_natives', { unusedDays, paths });
   },
 };
 
@@ -339,10 +601,20 @@ export const modpackApi = {
     return invoke('install_modrinth_modpack', { options });
   },
 
+  /** 从本地 .mrpack 或 CurseForge 整合包 zip 安装 */
+  async importModpackFromFile(filePath: string, instanceName: string): Promise<void> {
+    return invoke('import_modpack_from_file', { filePath, instanceName });
+  },
+
   /** 取消整合包安装 */
   async cancelModpackInstall(): Promise<void> {
     return invoke('cancel_modpack_install');
   },
+
+  /** 预览整合包更新将新增、移除和变化的文件（newVersion 为版本 ID 或版本号） */
+  async previewModpackUpdate(instanceName: string, newVersion: string): Promise<ModpackUpdatePreview> {
+    return invoke('preview_modpack_update', { instanceName, newVersion });
+  },
 };
 
 // ============ 统一导出 ============
@@ -3,7 +3,7 @@ import { ref, computed, onScopeDispose } from 'vue'
 import { listen, emit } from '@tauri-apps/api/event'
 import type { UnlistenFn } from '@tauri-apps/api/event'
 import { invoke } from '@tauri-apps/api/core'
-import type { DownloadProgress, DownloadStatus } from '../types/events'
+import type { DownloadProgress, DownloadStatus, MirrorFallbackPayload } from '../types/events'
 import { useNotificationStore } from './notificationStore'
 
 // Add 'idle' to the possible statuses for the store
@@ -34,6 +34,7 @@ export const useDownloadStore = defineStore('download', () => {
 
   // Listeners
   let unlistenDownloadProgress: UnlistenFn | null = null;
+  let unlistenMirrorFallback: UnlistenFn | null = null;
 
   // 当 store 的作用域销毁时自动清理监听器
   onScopeDispose(() => {
@@ -66,6 +67,10 @@ export const useDownloadStore = defineStore('download', () => {
         }
       }
     })
+    unlistenMirrorFallback = await listen<MirrorFallbackPayload>('download-mirror-fallback', (event) => {
+      const notificationStore = useNotificationStore()
+      notificationStore.warning('镜像文件缺失，已切换到官方源', event.payload.message)
+    })
   }
 
   function unsubscribe() {
@@ -73,6 +78,10 @@ export const useDownloadStore = defineStore('download', () => {
       unlistenDownloadProgress();
       unlistenDownloadProgress = null;
     }
+    if (unlistenMirrorFallback) {
+      unlistenMirrorFallback();
+      unlistenMirrorFallback = null;
+    }
   }
 
   // Actions
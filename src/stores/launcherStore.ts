@@ -3,6 +3,7 @@ import { onScopeDispose } from 'vue'
 import { listen } from '@tauri-apps/api/event'
 import type { UnlistenFn } from '@tauri-apps/api/event'
 import { useNotificationStore } from './notificationStore'
+import type { GameExitPayload } from '../types/events'
 
 export const useLauncherStore = defineStore('launcher', () => {
   // Listeners
@@ -25,8 +26,17 @@ export const useLauncherStore = defineStore('launcher', () => {
       notificationStore.success('Minecraft 已启动', msg)
     })
 
-    unlistenExited = await listen('minecraft-exited', (event) => {
-      const msg = String(event.payload ?? '游戏已退出')
+    unlistenExited = await listen<GameExitPayload>('minecraft-exited', (event) => {
+      const exit = event.payload
+      let msg = exit?.explanation
+        ? `${exit.message}\n${exit.explanation.description}`
+        : exit?.message ?? '游戏已退出'
+      if (exit?.jvmCrash) {
+        msg += `\n${exit.jvmCrash.suggestion}\n崩溃日志: ${exit.jvmCrash.file}`
+      }
+      if (exit?.troubleshooting) {
+        msg += `\n已连续崩溃 ${exit.troubleshooting.consecutiveCrashes} 次，已停止自动重启，可尝试以故障排查模式启动`
+      }
       notificationStore.info('Minecraft 已退出', msg)
     })
 
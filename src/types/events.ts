@@ -1,6 +1,26 @@
 export type DownloadStatus = 'downloading' | 'completed' | 'cancelled' | 'error';
 
+// 进度事件的阶段（与后端 ProgressStage 对应）
+export type ProgressStage =
+  | 'preparing'
+  | 'fetching_manifest'
+  | 'downloading_modpack'
+  | 'downloading_files'
+  | 'downloading_assets'
+  | 'downloading_mods'
+  | 'extracting'
+  | 'copying_files'
+  | 'installing_loader'
+  | 'configuring'
+  | 'verifying_files'
+  | 'extracting_natives'
+  | 'building_classpath'
+  | 'preparing_arguments'
+  | 'launching'
+  | 'completed';
+
 export interface DownloadProgress {
+  stage: ProgressStage;
   progress: number;
   total: number;
   speed: number;
@@ -11,9 +31,69 @@ export interface DownloadProgress {
   error?: string;
 }
 
+// 镜像大量 404 时切换到官方源（download-mirror-fallback 事件）
+export interface MirrorFallbackPayload {
+  versionId: string;
+  host: string;
+  attempts: number;
+  notFound: number;
+  message: string;
+}
+
+export interface ExitExplanation {
+    title: string;
+    description: string;
+    restartable: boolean;
+}
+
+export interface JvmCrashReport {
+    file: string;
+    error: string | null;
+    problematicFrame: string | null;
+    module: string | null;
+    gpuVendor: string | null;
+    jreVersion: string | null;
+    suggestion: string;
+}
+
+// 故障排查（安全模式）启动选项
+export type TroubleshootingToggle =
+    | 'disableMods'
+    | 'disableModShuffle'
+    | 'mixinDebug'
+    | 'disableEarlyWindow';
+
+export interface TroubleshootingToggleInfo {
+    toggle: TroubleshootingToggle;
+    title: string;
+    description: string;
+}
+
+// 按版本范围生效的默认 JVM 参数规则
+export interface DefaultJvmFlagInfo {
+    id: string;
+    description: string;
+    args: string[];
+    applies: boolean;
+    enabled: boolean;
+    overrideValue: boolean | null;
+}
+
+// 崩溃循环保护给出的排查建议
+export interface TroubleshootingSuggestion {
+    consecutiveCrashes: number;
+    toggles: TroubleshootingToggle[];
+}
+
 export interface GameExitPayload {
-    code: number | null;
-    signal: string | null;
+    instance: string;
+    exitCode: number | null;
+    signal: number | null;
+    message: string;
+    explanation: ExitExplanation | null;
+    jvmCrash: JvmCrashReport | null;
+    /** 连续崩溃时推荐的故障排查选项 */
+    troubleshooting: TroubleshootingSuggestion | null;
 }
 
 export type LaunchCommandPayload = string;
@@ -45,11 +125,78 @@ export interface GameInstance {
   loaderType?: string;
   gameVersion?: string;
   lastPlayed?: number;
+  mcVersion?: string;
+  loaderVersion?: string;
+  /** 实例所在的游戏目录 */
+  gameDir?: string;
+  /** 整合包安装校验结果 */
+  health?: 'green' | 'yellow' | 'red' | null;
+  /** 基础版本类型：release / snapshot / old_beta / old_alpha / custom */
+  versionType?: string;
+  /** 继承链中缺失的父版本 */
+  missingBase?: string | null;
+  /** 各校验级别上次通过的时间（Unix 秒） */
+  verifiedLevels?: Partial<Record<VerificationLevel, number>>;
+  /** 是否已归档（需要解档后才能启动） */
+  archived?: boolean;
   modLoader?: string;
   modLoaderVersion?: string;
   icon?: string;
 }
 
+// 已归档实例
+export interface ArchivedInstance {
+  name: string;
+  version: string;
+  mcVersion?: string | null;
+  loaderType?: string | null;
+  loaderVersion?: string | null;
+  /** 归档前所在的游戏目录 */
+  gameDir: string;
+  archivePath: string;
+  archivedAt: string;
+  /** 归档前的目录大小（字节） */
+  originalSize: number;
+  archiveSize: number;
+}
+
+// 版本文件校验级别：quick 只检查存在和大小，standard 校验库文件和抽样资源，full 校验全部文件
+export type VerificationLevel = 'quick' | 'standard' | 'full';
+
+// 校验级别的预计耗时
+export interface VerificationEstimate {
+  level: VerificationLevel;
+  files: number;
+  hashBytes: number;
+  estimatedMs: number;
+}
+
+// 单次启动的选项覆盖（不会保存）
+export interface LaunchOverrides {
+  memory?: number;
+  username?: string;
+  windowWidth?: number;
+  windowHeight?: number;
+  fullscreen?: boolean;
+  extraJvmArgs?: string[];
+  extraGameArgs?: string[];
+  /** 启动后直接加入的服务器 host[:port] */
+  server?: string;
+  /** 本次启动启用的故障排查选项 */
+  troubleshooting?: TroubleshootingToggle[];
+}
+
+// 局域网实例分享
+export interface ShareInfo {
+  instanceName: string;
+  /** 分享码：<地址>:<端口>/<令牌> */
+  code: string;
+  /** 完整下载地址，用于生成二维码 */
+  url: string;
+  packSize: number;
+  expiresAt: string;
+}
+
 // 创建实例参数
 export interface CreateInstancePayload {
   newInstanceName: string;
@@ -66,6 +213,7 @@ export type LoaderPayload =
 
 // 安装进度事件
 export interface InstallProgressPayload {
+  stage: ProgressStage;
   progress: number;
   message: string;
   indeterminate: boolean;
@@ -100,3 +248,290 @@ export interface InstanceNameValidation {
   is_valid: boolean;
   error_message: string | null;
 }
+
+// 游戏进程代理
+export type GameProxyKind = 'http' | 'socks5';
+
+export interface GameProxy {
+  kind: GameProxyKind;
+  host: string;
+  port: number;
+  username?: string | null;
+  password?: string | null;
+  nonProxyHosts?: string[];
+}
+
+// 生命周期钩子脚本
+export interface HookCommand {
+  command: string;
+  args?: string[];
+  timeoutSecs?: number | null;
+}
+
+export interface LifecycleHooks {
+  preLaunch?: HookCommand | null;
+  postExit?: HookCommand | null;
+  postModpackInstall?: HookCommand | null;
+}
+
+// 实例独立设置（未设置的项使用全局配置）
+export interface InstanceSettings {
+  windowWidth?: number | null;
+  windowHeight?: number | null;
+  fullscreen?: boolean | null;
+  windowTitle?: string | null;
+  javaPath?: string | null;
+  maxMemory?: number | null;
+  jvmArgs?: string[];
+  gameArgs?: string[];
+  healedLibraries?: string[];
+  autoRestartOnCrash?: boolean;
+  stripJarSignatures?: boolean;
+  proxy?: GameProxy | null;
+  hooks?: LifecycleHooks;
+}
+
+// 实时内存采样（memory-stats 事件）
+export interface GameMemoryUsage {
+  instance: string;
+  pid: number;
+  memory_mb: number;
+}
+
+export interface MemorySample {
+  timestamp: number;
+  total_memory_mb: number;
+  used_memory_mb: number;
+  available_memory_mb: number;
+  memory_usage_percent: number;
+  launcher_memory_mb: number;
+  games: GameMemoryUsage[];
+}
+
+// 可清理的 natives / 临时目录
+export interface NativesDirInfo {
+  path: string;
+  /** 所属版本目录名，临时目录为空 */
+  instanceName?: string | null;
+  size: number;
+  /** 上次使用时间（Unix 毫秒） */
+  lastUsed?: number | null;
+  /** orphaned：版本已删除；unused：长期未启动；temp：过期临时目录 */
+  reason: 'orphaned' | 'unused' | 'temp';
+}
+
+export interface NativesPruneResult {
+  removed: number;
+  freedBytes: number;
+  failed: string[];
+}
+
+// 整合包更新中一个文件的变化
+export interface ModpackFileDiff {
+  path: string;
+  oldSize: number | null;
+  newSize: number | null;
+  /** 与上次安装时内容不同，更新会覆盖或删除这些修改 */
+  userModified: boolean;
+}
+
+// 整合包更新预览
+export interface ModpackUpdatePreview {
+  instanceName: string;
+  currentVersion: string | null;
+  newVersion: string;
+  added: ModpackFileDiff[];
+  removed: ModpackFileDiff[];
+  changed: ModpackFileDiff[];
+  unchanged: number;
+  downloadBytes: number;
+  hasInstallRecord: boolean;
+}
+
+// 管理员锁定状态
+export interface KioskStatus {
+  /** 是否设置了管理员 PIN */
+  enabled: boolean;
+  /** 本次运行是否已解锁 */
+  unlocked: boolean;
+  unlockRemainingSecs: number | null;
+}
+
+// 资源包所在位置
+export interface PackLocation {
+  path: string;
+  /** 所属实例，公共目录为 null */
+  instanceName: string | null;
+}
+
+// 一组内容相同的资源包
+export interface DuplicatePackGroup {
+  sha1: string;
+  size: number;
+  gameDir: string;
+  locations: PackLocation[];
+  wastedBytes: number;
+}
+
+// 资源包去重扫描结果
+export interface ResourcePackScan {
+  scannedFiles: number;
+  totalBytes: number;
+  groups: DuplicatePackGroup[];
+  wastedBytes: number;
+}
+
+// 资源包合并结果
+export interface ConsolidateResult {
+  linked: number;
+  freedBytes: number;
+  failed: string[];
+}
+
+// 推荐安装的可选依赖
+export interface ModSuggestion {
+  projectId: string;
+  title: string;
+  description: string;
+  iconUrl: string | null;
+  versionId: string;
+  versionNumber: string;
+}
+
+// 从 Modrinth 安装模组的结果
+export interface ModInstallResult {
+  projectId: string;
+  versionId: string;
+  versionNumber: string;
+  fileName: string;
+  alreadyInstalled: boolean;
+  suggestions: ModSuggestion[];
+}
+
+// 模组列表导出格式
+export type ModListFormat = 'markdown' | 'csv';
+
+// 模组列表导出结果
+export interface ExportedModList {
+  content: string;
+  path: string;
+  modCount: number;
+  linkedCount: number;
+}
+
+// 资源文件回收结果（dryRun 为 true 时表示可回收的内容）
+export interface AssetGcReport {
+  dryRun: boolean;
+  referencedIndexes: string[];
+  referencedObjects: number;
+  unreferencedObjects: number;
+  unreferencedIndexes: string[];
+  freedBytes: number;
+  warnings: string[];
+}
+
+// 服务器整合包同步结果（dryRun 为 true 时表示将要执行的操作）
+export interface ServerSyncReport {
+  manifestUrl: string;
+  packName: string | null;
+  packVersion: string | null;
+  dryRun: boolean;
+  downloaded: string[];
+  removed: string[];
+  unchanged: number;
+  /** 位于受保护路径而跳过的文件 */
+  protected: string[];
+  failed: string[];
+  warnings: string[];
+}
+
+// 下载主机的并发限制
+export interface HostLimit {
+  maxConnections: number;
+  delayMs: number;
+}
+
+// 实例健康检查建议的修复操作
+export type HealthFix =
+  | { kind: 'repairFiles' }
+  | { kind: 'reinstallLoader' }
+  | { kind: 'installDependencies' }
+  | { kind: 'changeJava'; major: number };
+
+// 实例健康检查发现的问题
+export interface HealthFinding {
+  category: 'version' | 'files' | 'loader' | 'dependency' | 'java';
+  message: string;
+  fatal: boolean;
+  suggestion: string | null;
+  fix: HealthFix | null;
+}
+
+// 实例健康报告
+export interface InstanceHealth {
+  instanceName: string;
+  status: 'green' | 'yellow' | 'red';
+  score: number;
+  checkedAt: string;
+  findings: HealthFinding[];
+}
+
+// 重新生成版本 JSON 的结果
+export interface RegeneratedVersionJson {
+  mcVersion: string;
+  loaderType: string | null;
+  loaderVersion: string | null;
+  preservedJvmArgs: string[];
+  preservedGameArgs: string[];
+  backupPath: string | null;
+}
+
+// 启动会话（每次启动的命令、日志和崩溃报告）
+export interface SessionInfo {
+  id: string;
+  instance: string;
+  path: string;
+  pid: number;
+  startedAt: string;
+  endedAt: string | null;
+  exitCode: number | null;
+  crashed: boolean;
+  files: string[];
+}
+
+// 账户类型：正版（微软 / 旧版 Mojang）或离线
+export type AccountType = 'msa' | 'mojang' | 'legacy';
+
+// 保存的账户（不含访问令牌）
+export interface AccountInfo {
+  id: string;
+  username: string;
+  uuid: string;
+  accountType: AccountType;
+  active: boolean;
+  addedAt: string;
+}
+
+// 皮肤模型：经典（Steve）或纤细（Alex）
+export type SkinModel = 'classic' | 'slim';
+
+// 披风
+export interface CapeInfo {
+  id: string;
+  alias: string | null;
+  url: string;
+  path: string | null;
+  active: boolean;
+}
+
+// 账户当前的皮肤（材质已缓存到本地时 skinPath 为本地路径）
+export interface SkinInfo {
+  accountId: string;
+  username: string;
+  uuid: string;
+  source: 'mojang' | 'offline' | 'default';
+  model: SkinModel;
+  skinUrl: string | null;
+  skinPath: string | null;
+  capes: CapeInfo[];
+}
@@ -1,3 +1,5 @@
+import type { ProgressStage, VerificationLevel } from '../types/events'
+
 /**
  * 格式化时间戳为相对时间（如"3 小时前"）
  */
@@ -23,6 +25,22 @@ export function formatLastPlayed(timestamp?: number): string {
   return formatTimeAgo(timestamp)
 }
 
+const VERIFICATION_LEVEL_NAMES: Record<VerificationLevel, string> = {
+  quick: '快速',
+  standard: '标准',
+  full: '完整',
+}
+
+/**
+ * 格式化各校验级别上次通过的时间（用于实例健康标记的提示）
+ */
+export function formatVerifiedLevels(levels?: Partial<Record<VerificationLevel, number>>): string[] {
+  return (Object.keys(VERIFICATION_LEVEL_NAMES) as VerificationLevel[]).map(level => {
+    const time = levels?.[level]
+    return `${VERIFICATION_LEVEL_NAMES[level]}校验：${time ? formatTimeAgo(time * 1000) : '未进行'}`
+  })
+}
+
 /** 图标配置 */
 const LOADER_ICONS: Record<string, { list: string; select: string }> = {
   forge: { list: 'mdi-anvil', select: 'mdi-anvil' },
@@ -61,3 +79,47 @@ export function getLoaderSelectIcon(loader: string): string {
 export function getErrorMessage(error: unknown): string {
   return error instanceof Error ? error.message : String(error)
 }
+
+/** 进度阶段的默认名称和图标 */
+const PROGRESS_STAGES: Record<ProgressStage, { label: string; icon: string }> = {
+  preparing: { label: '准备中', icon: 'mdi-progress-clock' },
+  fetching_manifest: { label: '获取版本信息', icon: 'mdi-file-find-outline' },
+  downloading_modpack: { label: '下载整合包', icon: 'mdi-package-down' },
+  downloading_files: { label: '下载游戏文件', icon: 'mdi-download' },
+  downloading_assets: { label: '下载资源文件', icon: 'mdi-image-multiple-outline' },
+  downloading_mods: { label: '下载模组', icon: 'mdi-puzzle-outline' },
+  extracting: { label: '解压文件', icon: 'mdi-folder-zip-outline' },
+  copying_files: { label: '复制文件', icon: 'mdi-content-copy' },
+  installing_loader: { label: '安装加载器', icon: 'mdi-anvil' },
+  configuring: { label: '配置实例', icon: 'mdi-cog-outline' },
+  verifying_files: { label: '校验文件', icon: 'mdi-shield-check-outline' },
+  extracting_natives: { label: '解压本地库', icon: 'mdi-chip' },
+  building_classpath: { label: '构建 Classpath', icon: 'mdi-format-list-bulleted' },
+  preparing_arguments: { label: '构建启动参数', icon: 'mdi-console' },
+  launching: { label: '启动游戏', icon: 'mdi-rocket-launch-outline' },
+  completed: { label: '已完成', icon: 'mdi-check-circle-outline' },
+}
+
+/** 界面语言提供的阶段名称，覆盖默认中文名称 */
+let stageTranslations: Partial<Record<ProgressStage, string>> = {}
+
+/**
+ * 注册进度阶段名称的翻译
+ */
+export function registerProgressStageLabels(labels: Partial<Record<ProgressStage, string>>): void {
+  stageTranslations = { ...stageTranslations, ...labels }
+}
+
+/**
+ * 获取进度阶段的显示名称（也用于读屏播报）
+ */
+export function progressStageLabel(stage: ProgressStage): string {
+  return stageTranslations[stage] ?? PROGRESS_STAGES[stage]?.label ?? stage
+}
+
+/**
+ * 获取进度阶段的图标
+ */
+export function progressStageIcon(stage: ProgressStage): string {
+  return PROGRESS_STAGES[stage]?.icon ?? 'mdi-progress-helper'
+}